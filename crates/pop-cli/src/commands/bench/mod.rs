@@ -0,0 +1,19 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use clap::{Args, Subcommand};
+
+pub(crate) mod pallet;
+
+#[derive(Args)]
+#[command(args_conflicts_with_subcommands = true)]
+pub(crate) struct BenchArgs {
+	#[command(subcommand)]
+	pub command: BenchCommands,
+}
+
+#[derive(Subcommand)]
+pub(crate) enum BenchCommands {
+	/// Benchmark the extrinsics of a pallet using frame-omni-bencher, generating its weights
+	#[clap(alias = "p")]
+	Pallet(pallet::BenchPalletCommand),
+}
@@ -0,0 +1,131 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::{
+	commands::{
+		binaries::lock::{lock, project_version},
+		up::{confirm_build, report_verifications, ProgressReporter},
+	},
+	style::{style, Theme},
+};
+use clap::Args;
+use cliclack::{clear_screen, intro, log, outro, outro_cancel, set_theme};
+use pop_parachains::{
+	benchmark_runtime, weights_path, PalletBenchmark, PolkadotRelease, OMNI_BENCHER_DEFAULT_VERSION,
+};
+use std::path::PathBuf;
+
+#[derive(Args)]
+pub struct BenchPalletCommand {
+	/// Directory path for your project, [default: the current project]
+	#[arg(short = 'p', long = "path")]
+	path: Option<PathBuf>,
+	/// The runtime wasm to benchmark, compiled with the `runtime-benchmarks` feature. [default:
+	/// the runtime of the latest release build of the project]
+	#[arg(long)]
+	runtime: Option<PathBuf>,
+	/// The name of the pallet to benchmark, as per its crate (e.g. `pallet_template`), or `*` for
+	/// all pallets.
+	#[arg(long)]
+	pallet: String,
+	/// The extrinsics to benchmark, or `*` for all extrinsics.
+	#[arg(long, default_value = "*")]
+	extrinsic: String,
+	/// The number of samples taken across the range of each component.
+	#[arg(long, default_value_t = 50)]
+	steps: u32,
+	/// The number of times each sample is repeated.
+	#[arg(long, default_value_t = 20)]
+	repeat: u32,
+	/// The path of the generated weight file, or a directory when benchmarking all pallets.
+	/// [default: `runtime/src/weights/<pallet>.rs` when the runtime has a weights module,
+	/// otherwise `pallets/<name>/src/weights.rs`]
+	#[arg(long)]
+	output: Option<PathBuf>,
+	/// The polkadot release providing `frame-omni-bencher`, as per the release tag. [default: the
+	/// version pinned by the project using `pop binaries use frame-omni-bencher`, otherwise the
+	/// version locked within its pop.lock, otherwise stable2409]
+	#[arg(long)]
+	bencher_version: Option<String>,
+}
+
+impl BenchPalletCommand {
+	pub(crate) async fn execute(&self) -> anyhow::Result<()> {
+		clear_screen()?;
+		intro(format!("{}: Benchmarking a pallet", style(" Pop CLI ").black().on_magenta()))?;
+		set_theme(Theme);
+		let path = crate::project_path(self.path.as_deref());
+		let runtime = match self.runtime.clone().map_or_else(|| benchmark_runtime(&path), Ok) {
+			Ok(runtime) => runtime,
+			Err(e) => {
+				outro_cancel(format!("{e}"))?;
+				return Ok(());
+			},
+		};
+		let output = self.output.clone().unwrap_or_else(|| match self.pallet.as_str() {
+			"*" => path.join("runtime").join("src").join("weights"),
+			pallet => weights_path(&path, pallet),
+		});
+
+		// Locate frame-omni-bencher within the cache, sourcing it when missing.
+		let cache = crate::cache()?;
+		let version = match &self.bencher_version {
+			Some(version) => version.clone(),
+			None => project_version(&path, "frame-omni-bencher")?
+				.unwrap_or(OMNI_BENCHER_DEFAULT_VERSION.into()),
+		};
+		let release = match PolkadotRelease::new(&version) {
+			Ok(release) => release,
+			Err(e) => {
+				outro_cancel(format!("{e}"))?;
+				return Ok(());
+			},
+		};
+		let bencher = release.omni_bencher(&cache);
+		if !bencher.path.exists() {
+			if !confirm_build([&bencher])? {
+				outro_cancel("Cannot benchmark the pallet until frame-omni-bencher is available.")?;
+				return Ok(());
+			}
+			log::info(format!("ℹ️ {} will be cached at {}", bencher.name, cache.display()))?;
+			let spinner = crate::progress::spinner();
+			spinner.start(format!("📦 Sourcing {}...", bencher.name));
+			let verifications = match bencher.source(&cache, ProgressReporter(&spinner)).await {
+				Ok(verifications) => verifications,
+				Err(e) => {
+					spinner.error(format!("🚫 Sourcing failed: {e}"));
+					outro_cancel(
+						"Cannot benchmark the pallet until frame-omni-bencher is available.",
+					)?;
+					return Ok(());
+				},
+			};
+			spinner.stop(format!("✅ Sourcing {} complete.", bencher.name));
+			report_verifications(&verifications)?;
+		}
+		if let Err(e) = lock(&path, [&bencher]) {
+			outro_cancel(format!("{e}"))?;
+			return Ok(());
+		}
+
+		let benchmark = PalletBenchmark {
+			runtime,
+			pallet: self.pallet.clone(),
+			extrinsic: self.extrinsic.clone(),
+			steps: self.steps,
+			repeat: self.repeat,
+			output,
+		};
+		log::info(format!(
+			"Benchmarking `{}` against {}...",
+			self.pallet,
+			benchmark.runtime.display()
+		))?;
+		if let Err(e) = benchmark.run(&bencher.path) {
+			outro_cancel(format!("Benchmarking failed: {e}"))?;
+			return Ok(());
+		}
+		log::success(format!("Weights written to {}", benchmark.output.display()))?;
+		outro("Benchmarking complete!")?;
+		Ok(())
+	}
+}
@@ -0,0 +1,123 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::{
+	output::OutputArgs,
+	style::{self, style, Align, Theme},
+};
+use clap::Args;
+use cliclack::{intro, log, outro, set_theme};
+use pop_common::{cache::cached_binaries, project::pinned_binaries};
+use serde::Serialize;
+use std::{collections::BTreeMap, path::PathBuf};
+
+#[derive(Args)]
+pub(crate) struct ListBinariesCommand {
+	/// Directory path for your project, [default: the current project]
+	#[arg(short = 'p', long)]
+	path: Option<PathBuf>,
+	#[command(flatten)]
+	output: OutputArgs,
+}
+
+/// The versions of a binary, as output by `--output json`.
+#[derive(Debug, Default, PartialEq, Serialize)]
+struct Versions {
+	/// The versions cached, from the newest.
+	cached: Vec<String>,
+	/// The version pinned by the project, if any.
+	pinned: Option<String>,
+}
+
+impl ListBinariesCommand {
+	pub(crate) fn execute(&self) -> anyhow::Result<()> {
+		self.output.clear_screen()?;
+		intro(format!("{}: List binaries", style(" Pop CLI ").black().on_magenta()))?;
+		set_theme(Theme);
+
+		let cache = crate::cache()?;
+		let path = crate::project_path(self.path.as_deref());
+		let binaries = versions(
+			cached_binaries(&cache)?.into_iter().map(|b| (b.name, b.version)),
+			pinned_binaries(&path)?,
+		);
+		self.output.report(&binaries)?;
+		if binaries.is_empty() {
+			outro(format!("No binaries cached within {}.", cache.display()))?;
+			return Ok(());
+		}
+		log::info(table(&binaries))?;
+		outro("Pin the version used by the project with `pop binaries use <binary> <version>`.")?;
+		Ok(())
+	}
+}
+
+/// The versions of each binary, as per the `cached` binaries and those `pinned`.
+///
+/// # Arguments
+///
+/// * `cached` - the name and version of each binary cached, from the newest version
+/// * `pinned` - the version of each binary pinned by the project
+fn versions(
+	cached: impl IntoIterator<Item = (String, String)>,
+	pinned: BTreeMap<String, String>,
+) -> BTreeMap<String, Versions> {
+	let mut binaries: BTreeMap<String, Versions> = BTreeMap::new();
+	for (name, version) in cached {
+		binaries.entry(name).or_default().cached.push(version);
+	}
+	for (name, version) in pinned {
+		binaries.entry(name).or_default().pinned = Some(version);
+	}
+	binaries
+}
+
+/// A table listing the versions of each binary, marking the version pinned, which is sourced when
+/// first required if not yet cached.
+fn table(binaries: &BTreeMap<String, Versions>) -> String {
+	let mut rows = Vec::new();
+	for (name, versions) in binaries {
+		let mut listed: Vec<_> = versions
+			.cached
+			.iter()
+			.map(|v| match versions.pinned.as_ref() == Some(v) {
+				true => format!("{v} (pinned)"),
+				false => v.clone(),
+			})
+			.collect();
+		if let Some(pinned) = versions.pinned.as_ref().filter(|p| !versions.cached.contains(p)) {
+			listed.insert(0, format!("{pinned} (pinned, not cached)"));
+		}
+		rows.push([name.clone(), listed.join(", ")]);
+	}
+	style::table([("Binary", Align::Left), ("Versions", Align::Left)], &rows)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn versions_and_table_work() {
+		let cached = [("polkadot", "v1.13.0"), ("polkadot", "v1.11.0"), ("pop-node", "latest")]
+			.map(|(n, v)| (n.to_string(), v.to_string()));
+		let pinned = BTreeMap::from([
+			("polkadot".to_string(), "v1.11.0".to_string()),
+			("frame-omni-bencher".to_string(), "stable2409".to_string()),
+		]);
+		let binaries = versions(cached, pinned);
+		assert_eq!(
+			binaries["polkadot"],
+			Versions {
+				cached: vec!["v1.13.0".into(), "v1.11.0".into()],
+				pinned: Some("v1.11.0".into())
+			}
+		);
+		assert_eq!(
+			table(&binaries),
+			"Binary              Versions\n\
+			 frame-omni-bencher  stable2409 (pinned, not cached)\n\
+			 polkadot            v1.13.0, v1.11.0 (pinned)\n\
+			 pop-node            latest"
+		);
+	}
+}
@@ -0,0 +1,135 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use super::PINNABLE;
+use cliclack::log;
+use pop_common::{
+	lock::LOCK_FILE,
+	project::{pinned_binary, PROJECT_FILE},
+	Binary, Lock, Platform, Project, Source,
+};
+use std::path::Path;
+
+/// The version of `binary` used by the project at `path` when none is specified: the version
+/// pinned within its `pop.toml`, otherwise the version locked within its `pop.lock`.
+///
+/// # Arguments
+///
+/// * `path` - path to the project
+/// * `binary` - the name of the binary
+pub(crate) fn project_version(path: &Path, binary: &str) -> anyhow::Result<Option<String>> {
+	if let Some(version) = pinned_binary(path, binary)? {
+		log::info(format!("Using {binary} {version}, as pinned by the project."))?;
+		return Ok(Some(version));
+	}
+	let locked = Lock::load(path)?.version(binary).map(String::from);
+	if let Some(version) = &locked {
+		log::info(format!("Using {binary} {version}, as locked within {LOCK_FILE}."))?;
+	}
+	Ok(locked)
+}
+
+/// Locks the version of each of the `binaries` used by the project at `path` within its
+/// `pop.lock`, along with the hash of those released for the platform, so that everyone working
+/// on the project uses identical binaries. Fails when a binary differs from the one locked.
+/// Directories which are not projects generated by pop are left as is.
+///
+/// # Arguments
+///
+/// * `path` - path to the project
+/// * `binaries` - the binaries used
+pub(crate) fn lock<'a>(
+	path: &Path,
+	binaries: impl IntoIterator<Item = &'a Binary>,
+) -> anyhow::Result<()> {
+	if !path.join(PROJECT_FILE).exists() {
+		return Ok(());
+	}
+	let mut lock = Lock::load(path)?;
+	let unchanged = lock.clone();
+	let platform = Platform::detect().ok();
+	for binary in binaries {
+		// The name of a binary is versioned, unlike the binary cached.
+		let Some(name) = binary.path.file_name().and_then(|n| n.to_str()) else { continue };
+		if !PINNABLE.iter().any(|(n, _)| *n == name)
+			|| binary.version.is_empty()
+			|| !binary.path.exists()
+		{
+			continue;
+		}
+		match lock.version(name) {
+			None => lock.lock(name, &binary.version),
+			Some(locked) if locked != binary.version => {
+				log::warning(format!(
+					"⚠️ {name} {} is used, whereas {locked} is locked within {LOCK_FILE}: update \
+					 it with `pop update lock`.",
+					binary.version
+				))?;
+				continue;
+			},
+			Some(_) => {},
+		}
+		// Only the hashes of binaries downloaded are recorded, as those built from source differ
+		// between builds.
+		let downloaded = binary
+			.sources
+			.iter()
+			.any(|s| matches!(s, Source::Url { .. } | Source::Archive { .. }));
+		if let Some(platform) = platform {
+			lock.check(name, &binary.path, platform.target(), downloaded)?;
+		}
+	}
+	if lock.template.is_none() {
+		if let Some(project) = Project::load(path)? {
+			lock.lock_template(&project);
+		}
+	}
+	if lock != unchanged {
+		lock.save(path)?;
+	}
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use pop_common::project::pin_binary;
+	use std::fs;
+
+	#[test]
+	fn lock_works() -> anyhow::Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let path = temp_dir.path();
+		let cache = path.join("cache");
+		let binary = |name: &str, version: &str| {
+			let binary_path = Source::cached_path(&cache, name, Some(version));
+			fs::create_dir_all(binary_path.parent().unwrap()).unwrap();
+			fs::write(&binary_path, "binary").unwrap();
+			Binary {
+				name: format!("{name}-{version}"),
+				version: version.into(),
+				path: binary_path,
+				sources: Vec::new(),
+			}
+		};
+		let polkadot = binary("polkadot", "v1.13.0");
+		// Only projects are locked.
+		lock(path, [&polkadot])?;
+		assert!(!path.join(LOCK_FILE).exists());
+
+		Project { kind: "parachain".into(), template: "standard".into(), ..Default::default() }
+			.save(path)?;
+		lock(path, [&polkadot, &binary("pop-node", "v0.1.0")])?;
+		let locked = Lock::load(path)?;
+		assert_eq!(locked.version("polkadot"), Some("v1.13.0"));
+		assert_eq!(locked.version("pop-node"), None);
+		assert_eq!(locked.template.unwrap().name, "standard");
+		assert_eq!(project_version(path, "polkadot")?.as_deref(), Some("v1.13.0"));
+
+		// Another version used leaves the lockfile as is, whereas pins take precedence.
+		lock(path, [&binary("polkadot", "v1.14.0")])?;
+		assert_eq!(Lock::load(path)?.version("polkadot"), Some("v1.13.0"));
+		pin_binary(path, "polkadot", "v1.14.0")?;
+		assert_eq!(project_version(path, "polkadot")?.as_deref(), Some("v1.14.0"));
+		Ok(())
+	}
+}
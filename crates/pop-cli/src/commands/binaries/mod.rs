@@ -0,0 +1,31 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use clap::{Args, Subcommand};
+
+pub(crate) mod list;
+pub(crate) mod lock;
+pub(crate) mod pin;
+
+/// The binaries whose version a project can pin, along with the command honoring it.
+pub(crate) const PINNABLE: [(&str, &str); 4] = [
+	("polkadot", "pop up parachain"),
+	("polkadot-parachain", "pop up parachain"),
+	("frame-omni-bencher", "pop bench pallet"),
+	("substrate-contracts-node", "pop up contracts-node"),
+];
+
+#[derive(Args)]
+#[command(args_conflicts_with_subcommands = true)]
+pub(crate) struct BinariesArgs {
+	#[command(subcommand)]
+	pub command: BinariesCommands,
+}
+
+#[derive(Subcommand)]
+pub(crate) enum BinariesCommands {
+	/// List the versions of the binaries cached, along with those pinned by the project
+	#[clap(alias = "ls")]
+	List(list::ListBinariesCommand),
+	/// Pin the version of a binary used by the project, e.g. `pop binaries use polkadot v1.13.0`
+	Use(pin::UseBinaryCommand),
+}
@@ -0,0 +1,87 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use super::PINNABLE;
+use crate::style::{style, Theme};
+use anyhow::anyhow;
+use clap::{builder::PossibleValuesParser, Args};
+use cliclack::{clear_screen, intro, outro, outro_cancel, set_theme};
+use pop_common::project::{pin_binary, PROJECT_FILE};
+use std::path::PathBuf;
+
+#[derive(Args)]
+pub(crate) struct UseBinaryCommand {
+	/// The binary pinned.
+	#[arg(value_parser = PossibleValuesParser::new(PINNABLE.map(|(name, _)| name)))]
+	binary: String,
+	/// The version used, as per its release tag (e.g. "v1.13.0" or "stable2409").
+	version: String,
+	/// Directory path for your project, [default: the current project]
+	#[arg(short = 'p', long)]
+	path: Option<PathBuf>,
+}
+
+impl UseBinaryCommand {
+	pub(crate) fn execute(&self) -> anyhow::Result<()> {
+		clear_screen()?;
+		intro(format!("{}: Pin the version of a binary", style(" Pop CLI ").black().on_magenta()))?;
+		set_theme(Theme);
+
+		let version = match release(&self.binary, &self.version) {
+			Ok(version) => version,
+			Err(e) => {
+				outro_cancel(format!("{e}"))?;
+				return Ok(());
+			},
+		};
+		let path = crate::project_path(self.path.as_deref());
+		pin_binary(&path, &self.binary, &version)?;
+		let command = PINNABLE
+			.iter()
+			.find_map(|(name, command)| (*name == self.binary).then_some(*command))
+			.unwrap_or_default();
+		outro(format!(
+			"Pinned {} {version} within {}, used by `{command}` unless another version is \
+			 specified.",
+			self.binary,
+			path.join(PROJECT_FILE).display()
+		))?;
+		Ok(())
+	}
+}
+
+/// The release of `binary` recorded for `version`, which is validated, e.g. `v1.13.0` for the
+/// `polkadot-v1.13.0` release of the Polkadot SDK.
+///
+/// # Arguments
+///
+/// * `binary` - the binary pinned
+/// * `version` - the version specified
+fn release(binary: &str, version: &str) -> anyhow::Result<String> {
+	#[cfg(feature = "parachain")]
+	if matches!(binary, "polkadot" | "polkadot-parachain" | "frame-omni-bencher") {
+		return Ok(pop_parachains::PolkadotRelease::new(version)?.version().to_string());
+	}
+	let version = version.trim();
+	if version.is_empty() || version.contains(char::is_whitespace) {
+		return Err(anyhow!("invalid version `{version}` of {binary}, expected a release tag"));
+	}
+	Ok(version.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn release_works() -> anyhow::Result<()> {
+		assert_eq!(release("substrate-contracts-node", "v0.41.0")?, "v0.41.0");
+		assert!(release("substrate-contracts-node", "").is_err());
+		#[cfg(feature = "parachain")]
+		{
+			assert_eq!(release("polkadot", "polkadot-v1.13.0")?, "v1.13.0");
+			assert_eq!(release("frame-omni-bencher", "stable2409")?, "stable2409");
+			assert!(release("polkadot", "latest").is_err());
+		}
+		Ok(())
+	}
+}
@@ -1,29 +1,273 @@
 // SPDX-License-Identifier: GPL-3.0
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use clap::Args;
-use cliclack::{clear_screen, intro, log, outro, set_theme};
+use cliclack::{intro, log, outro, outro_cancel, set_theme};
 use console::style;
+use serde::Serialize;
 
-use crate::style::Theme;
-use pop_contracts::build_smart_contract;
+use crate::{output::OutputArgs, style::Theme};
+use pop_common::BuildCache;
+use pop_contracts::{
+	build_contract_artifacts, build_report, build_smart_contract, build_verifiable_contract,
+	built_artifacts, generate_typescript_bindings, workspace_contracts, ContractArtifacts,
+};
 
 #[derive(Args)]
 pub struct BuildContractCommand {
-	#[arg(short = 'p', long, help = "Path for the contract project, [default: current directory]")]
+	#[arg(
+		short = 'p',
+		long,
+		help = "Path for the contract project, [default: the current project]"
+	)]
 	pub(crate) path: Option<PathBuf>,
+	#[arg(
+		long,
+		help = "Build all contracts of the workspace at the path, in dependency order, consolidating their artifacts into target/pop/contracts"
+	)]
+	pub(crate) all: bool,
+	#[arg(
+		long,
+		conflicts_with = "all",
+		help = "Build the contract inside the reference docker image used for source verification, producing a reproducible wasm"
+	)]
+	pub(crate) verifiable: bool,
+	#[arg(
+		long,
+		requires = "verifiable",
+		help = "A custom docker image to use for the verifiable build, [default: the reference image]"
+	)]
+	pub(crate) image: Option<String>,
+	#[arg(
+		long,
+		help = "Generate TypeScript bindings (constructors, messages and events) from the contract metadata into this directory"
+	)]
+	pub(crate) types_output: Option<PathBuf>,
+	#[command(flatten)]
+	pub(crate) output: OutputArgs,
+}
+
+/// The artifacts of a contract build, as output by `--output json`.
+#[derive(Serialize)]
+struct ContractBuildReport {
+	/// The size of the optimized code, in KiB.
+	size: Option<f64>,
+	/// The code hash, reported by verifiable builds.
+	code_hash: Option<String>,
+	artifacts: Vec<PathBuf>,
+}
+
+/// The contracts of a workspace build, as output by `--output json`.
+#[derive(Serialize)]
+struct WorkspaceBuildReport<'a> {
+	contracts: Vec<WorkspaceContractReport<'a>>,
+}
+
+/// A contract of a workspace build, as output by `--output json`.
+#[derive(Serialize)]
+struct WorkspaceContractReport<'a> {
+	name: &'a str,
+	path: &'a Path,
+	size: Option<f64>,
+	artifacts: &'a [PathBuf],
 }
 
 impl BuildContractCommand {
+	/// The contract project, detected from the current directory unless provided.
+	fn path(&self) -> Option<PathBuf> {
+		Some(crate::contract_path(self.path.as_deref()))
+	}
+
 	pub(crate) fn execute(&self) -> anyhow::Result<()> {
-		clear_screen()?;
+		self.output.clear_screen()?;
+		if self.all {
+			return self.build_workspace();
+		}
+		if self.verifiable {
+			return self.build_verifiable();
+		}
 		intro(format!("{}: Building a contract", style(" Pop CLI ").black().on_magenta()))?;
 		set_theme(Theme);
+		let cache = BuildCache::detect(&crate::contract_path(self.path.as_deref()));
+		if cache.is_enabled() {
+			log::info(format!("Compilation is cached using {cache}."))?;
+		}
 
-		let result_build = build_smart_contract(&self.path)?;
+		let result_build = build_smart_contract(&self.path())?;
+		self.generate_types(&self.path())?;
 		outro("Build completed successfully!")?;
 		log::success(result_build.to_string())?;
+		let mut size = None;
+		match build_report(&self.path()) {
+			Ok(Some(report)) => {
+				size = report.current.optimized_size;
+				let title = match report.previous {
+					Some(_) => "Build report, compared with the previous build:",
+					None => "Build report:",
+				};
+				log::info(format!("{title}\n{report}"))?
+			},
+			Ok(None) => {},
+			Err(e) => log::warning(format!("Unable to read the build report: {e}"))?,
+		}
+		let artifacts = built_artifacts(&self.path())?;
+		artifacts.iter().for_each(|a| crate::progress::artifact(a));
+		self.output.report(&ContractBuildReport { size, code_hash: None, artifacts })?;
+		Ok(())
+	}
+
+	/// Builds the contract inside a docker container, producing a reproducible wasm.
+	fn build_verifiable(&self) -> anyhow::Result<()> {
+		intro(format!(
+			"{}: Building a verifiable contract",
+			style(" Pop CLI ").black().on_magenta()
+		))?;
+		set_theme(Theme);
+
+		let spinner = crate::progress::spinner();
+		spinner.start("Building the contract inside a docker container, this may take a while...");
+		match build_verifiable_contract(&self.path(), self.image.clone()) {
+			Ok(build) => {
+				spinner.stop("Verifiable build completed.");
+				log::success(build.output)?;
+				log::info(format!("Code hash: {}", build.code_hash))?;
+				self.generate_types(&self.path())?;
+				outro("Deployed contracts built from the same source share this code hash.")?;
+				let artifacts = built_artifacts(&self.path())?;
+				artifacts.iter().for_each(|a| crate::progress::artifact(a));
+				self.output.report(&ContractBuildReport {
+					size: None,
+					code_hash: Some(build.code_hash),
+					artifacts,
+				})?;
+			},
+			Err(e) => {
+				spinner.error(
+					"The verifiable build failed, make sure docker is installed and running.",
+				);
+				outro_cancel(format!("{e}"))?;
+			},
+		}
+		Ok(())
+	}
+
+	/// Builds all contracts of a workspace, consolidating their artifacts into a single directory.
+	fn build_workspace(&self) -> anyhow::Result<()> {
+		intro(format!(
+			"{}: Building all contracts of the workspace",
+			style(" Pop CLI ").black().on_magenta()
+		))?;
+		set_theme(Theme);
+
+		let root = crate::contract_path(self.path.as_deref());
+		let contracts = match workspace_contracts(&root) {
+			Ok(contracts) if contracts.is_empty() => {
+				outro_cancel("No contracts were found within the workspace.")?;
+				return Ok(());
+			},
+			Ok(contracts) => contracts,
+			Err(e) => {
+				outro_cancel(format!("{e}"))?;
+				return Ok(());
+			},
+		};
+		let output = root.join("target").join("pop").join("contracts");
+		let mut built = Vec::new();
+		for (i, contract) in contracts.iter().enumerate() {
+			let spinner = crate::progress::spinner();
+			spinner.start(format!(
+				"Building {} ({}/{})...",
+				contract.display(),
+				i + 1,
+				contracts.len()
+			));
+			match build_contract_artifacts(contract, &output) {
+				Ok(artifacts) => {
+					spinner.stop(format!("{} built", artifacts.name));
+					artifacts.artifacts.iter().for_each(|a| crate::progress::artifact(a));
+					self.generate_types(&Some(contract.clone()))?;
+					built.push(artifacts);
+				},
+				Err(e) => {
+					spinner.error(format!("Failed to build {}", contract.display()));
+					outro_cancel(format!("{e}"))?;
+					return Ok(());
+				},
+			}
+		}
+		log::success(summary(&built))?;
+		outro(format!(
+			"Built {} contracts, artifacts are available at {}",
+			built.len(),
+			output.display()
+		))?;
+		let contracts = built
+			.iter()
+			.map(|contract| WorkspaceContractReport {
+				name: &contract.name,
+				path: &contract.path,
+				size: contract.size,
+				artifacts: &contract.artifacts,
+			})
+			.collect();
+		self.output.report(&WorkspaceBuildReport { contracts })?;
+		Ok(())
+	}
+
+	/// Generates TypeScript bindings for the built contract at `path`, when requested.
+	fn generate_types(&self, path: &Option<PathBuf>) -> anyhow::Result<()> {
+		let Some(output) = &self.types_output else {
+			return Ok(());
+		};
+		match generate_typescript_bindings(path, output) {
+			Ok(file) => log::info(format!("TypeScript bindings generated at {}", file.display()))?,
+			Err(e) => log::warning(format!("Unable to generate TypeScript bindings: {e}"))?,
+		}
 		Ok(())
 	}
 }
+
+/// A table summarizing the name, code size and artifacts of each contract built.
+fn summary(contracts: &[ContractArtifacts]) -> String {
+	let width = contracts.iter().map(|c| c.name.len()).max().unwrap_or_default().max(8);
+	let mut table = format!("{:<width$}  {:>10}  {}", "Contract", "Size", "Artifacts");
+	for contract in contracts {
+		let size = contract.size.map(|s| format!("{s:.1}K")).unwrap_or_else(|| "-".into());
+		let artifacts: Vec<_> = contract
+			.artifacts
+			.iter()
+			.filter_map(|a| a.file_name())
+			.map(|a| a.to_string_lossy())
+			.collect();
+		table.push_str(&format!(
+			"\n{:<width$}  {:>10}  {}",
+			contract.name,
+			size,
+			artifacts.join(", ")
+		));
+	}
+	table
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_summary() {
+		let contracts = vec![ContractArtifacts {
+			name: "flipper".into(),
+			path: PathBuf::from("contracts/flipper"),
+			size: Some(1.3),
+			artifacts: vec![
+				PathBuf::from("target/pop/contracts/flipper.wasm"),
+				PathBuf::from("target/pop/contracts/flipper.json"),
+			],
+		}];
+		assert_eq!(
+			summary(&contracts),
+			"Contract        Size  Artifacts\nflipper         1.3K  flipper.wasm, flipper.json"
+		);
+	}
+}
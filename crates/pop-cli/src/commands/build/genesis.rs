@@ -0,0 +1,77 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use super::spec::recorded_para_id;
+use crate::style::{style, Theme};
+use clap::Args;
+use cliclack::{clear_screen, intro, log, outro, outro_cancel, set_theme};
+use pop_parachains::{
+	build_parachain, export_genesis, is_parachain, node_binary, ChainSpecOverrides,
+};
+use std::path::PathBuf;
+
+#[derive(Args)]
+pub struct BuildGenesisArtifactsCommand {
+	/// Directory path for your project, [default: the current project]
+	#[arg(short = 'p', long = "path")]
+	pub(crate) path: Option<PathBuf>,
+	/// The chain to compute the genesis of, as supported by the node (e.g. `dev`, `local`).
+	#[arg(long, default_value = "dev")]
+	chain: String,
+	/// The identifier of the parachain, overriding that of the chain spec, [default: that recorded
+	/// within the project's pop.toml]
+	#[arg(long)]
+	para_id: Option<u32>,
+}
+
+impl BuildGenesisArtifactsCommand {
+	pub(crate) fn execute(&self) -> anyhow::Result<()> {
+		clear_screen()?;
+		intro(format!(
+			"{}: Exporting the genesis artifacts",
+			style(" Pop CLI ").black().on_magenta()
+		))?;
+		set_theme(Theme);
+		let path = crate::project_path(self.path.as_deref());
+		if !is_parachain(&path)? {
+			outro_cancel(format!(
+				"{} is not a parachain project, as it does not depend on Cumulus.",
+				path.display()
+			))?;
+			return Ok(());
+		}
+		// The artifacts are exported by the node, so build it first if required.
+		if node_binary(&path).is_err() {
+			log::info("The node has not been built yet, building the parachain...")?;
+			build_parachain(&Some(path.clone()))?;
+		}
+
+		let spinner = crate::progress::spinner();
+		spinner.start(format!("Exporting the genesis of the `{}` chain...", self.chain));
+		let overrides = ChainSpecOverrides {
+			para_id: self.para_id.or_else(|| recorded_para_id(&path)),
+			..Default::default()
+		};
+		let artifacts = match export_genesis(&path, &self.chain, &overrides) {
+			Ok(artifacts) => artifacts,
+			Err(e) => {
+				spinner.error(format!("{e}"));
+				outro_cancel("Unable to export the genesis artifacts.")?;
+				return Ok(());
+			},
+		};
+		spinner.stop("Genesis artifacts exported.");
+		let para_id = match artifacts.para_id {
+			Some(para_id) => format!("Parachain id: {para_id}\n"),
+			None => String::new(),
+		};
+		log::info(format!(
+			"{para_id}Chain spec: {}\nGenesis state (head): {}\nGenesis wasm (validation code): {}",
+			artifacts.chain_spec.display(),
+			artifacts.genesis_state.display(),
+			artifacts.genesis_wasm.display()
+		))?;
+
+		outro("Use the genesis state and wasm to register the parachain on the relay chain.")?;
+		Ok(())
+	}
+}
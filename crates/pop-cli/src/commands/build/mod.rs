@@ -1,17 +1,56 @@
 // SPDX-License-Identifier: GPL-3.0
 
+use crate::{
+	output::OutputArgs,
+	workspace::{self, Kind},
+};
 use clap::{Args, Subcommand};
+use cliclack::{intro, set_theme};
+use std::path::PathBuf;
 
 #[cfg(feature = "contract")]
 pub(crate) mod contract;
 #[cfg(feature = "parachain")]
+pub(crate) mod genesis;
+#[cfg(feature = "parachain")]
 pub(crate) mod parachain;
+#[cfg(feature = "parachain")]
+pub(crate) mod spec;
 
 #[derive(Args)]
-#[command(args_conflicts_with_subcommands = true)]
+#[command(args_conflicts_with_subcommands = true, arg_required_else_help = true)]
 pub(crate) struct BuildArgs {
 	#[command(subcommand)]
-	pub command: BuildCommands,
+	pub command: Option<BuildCommands>,
+	/// Build every parachain and contract found within the workspace.
+	#[arg(long)]
+	pub all: bool,
+	/// Directory path of the workspace, [default: the current project]
+	#[arg(short = 'p', long, requires = "all")]
+	pub path: Option<PathBuf>,
+	#[command(flatten)]
+	pub(crate) output: OutputArgs,
+}
+
+impl BuildArgs {
+	/// Builds every parachain and contract found within the workspace, in release and debug mode
+	/// respectively, reporting a summary once all were built.
+	pub(crate) fn execute_all(&self) -> anyhow::Result<()> {
+		self.output.clear_screen()?;
+		intro(format!(
+			"{}: Building the workspace",
+			crate::style::style(" Pop CLI ").black().on_magenta()
+		))?;
+		set_theme(crate::style::Theme);
+		let root = crate::project_path(self.path.as_deref());
+		let members = workspace::members(&root)?;
+		workspace::run_all(&root, &members, "Building", &self.output, |member| match member.kind {
+			#[cfg(feature = "parachain")]
+			Kind::Parachain => pop_parachains::build_parachain(&Some(member.path.clone())),
+			#[cfg(feature = "contract")]
+			Kind::Contract => pop_contracts::build_smart_contract(&Some(member.path.clone())).map(|_| ()),
+		})
+	}
 }
 
 #[derive(Subcommand)]
@@ -20,6 +59,14 @@ pub(crate) enum BuildCommands {
 	#[cfg(feature = "parachain")]
 	#[clap(alias = "p")]
 	Parachain(parachain::BuildParachainCommand),
+	/// Generate the plain and raw chain specs of a parachain, applying overrides
+	#[cfg(feature = "parachain")]
+	#[clap(alias = "s")]
+	Spec(spec::BuildSpecCommand),
+	/// Export the genesis state and wasm of a parachain, as required to register it
+	#[cfg(feature = "parachain")]
+	#[clap(alias = "g")]
+	GenesisArtifacts(genesis::BuildGenesisArtifactsCommand),
 	/// Build a contract, generate metadata, bundle together in a `<name>.contract` file
 	#[cfg(feature = "contract")]
 	#[clap(alias = "c")]
@@ -1,29 +1,217 @@
 // SPDX-License-Identifier: GPL-3.0
 
-use crate::style::{style, Theme};
+use crate::{
+	output::OutputArgs,
+	style::{style, Theme},
+};
 use clap::Args;
-use cliclack::{clear_screen, intro, outro, set_theme};
-use pop_parachains::build_parachain;
-use std::path::PathBuf;
+use cliclack::{intro, log, outro, outro_cancel, set_theme};
+use pop_common::{BuildCache, Project};
+use pop_parachains::{
+	build_deterministic_runtime, build_parachain_profile, export_artifacts, is_parachain,
+	PolkadotRelease, Profile,
+};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
 
 #[derive(Args)]
 pub struct BuildParachainCommand {
 	#[arg(
 		short = 'p',
 		long = "path",
-		help = "Directory path for your project, [default: current directory]"
+		help = "Directory path for your project, [default: the current project]"
 	)]
 	pub(crate) path: Option<PathBuf>,
+	/// The profile to build with: `dev` for quick local iteration, `release`, or `production` for
+	/// a fully optimized build. The cargo features configured for the profile within
+	/// `[workspace.metadata.pop.profiles.<profile>]` are enabled.
+	#[arg(long, default_value = "release")]
+	profile: Profile,
+	/// Additional cargo features to enable (e.g. `try-runtime`, `runtime-benchmarks`).
+	#[arg(long, value_delimiter = ',')]
+	features: Vec<String>,
+	/// Build the runtime deterministically using srtool, run with Docker, so that the resulting
+	/// wasm can be reproduced and verified, as expected of runtimes proposed to governance.
+	#[arg(long)]
+	deterministic: bool,
+	#[command(flatten)]
+	output: OutputArgs,
+}
+
+/// The artifacts of a build, as output by `--output json`.
+#[derive(Serialize)]
+struct BuildReport<'a> {
+	profile: String,
+	node: &'a Path,
+	runtime: &'a Path,
+	genesis_state: &'a Path,
+	genesis_wasm: &'a Path,
+}
+
+/// The artifacts of a deterministic build, as output by `--output json`.
+#[derive(Serialize)]
+struct DeterministicBuildReport<'a> {
+	profile: String,
+	compact: &'a Path,
+	compressed: &'a Path,
+	proposal_hash: &'a str,
+	digest: &'a Path,
 }
 
 impl BuildParachainCommand {
 	pub(crate) fn execute(&self) -> anyhow::Result<()> {
-		clear_screen()?;
+		self.output.clear_screen()?;
 		intro(format!("{}: Building a parachain", style(" Pop CLI ").black().on_magenta()))?;
 		set_theme(Theme);
-		build_parachain(&self.path)?;
+		let path = crate::project_path(self.path.as_deref());
+		if !is_parachain(&path)? {
+			outro_cancel(format!(
+				"{} is not a parachain project, as it does not depend on Cumulus.",
+				path.display()
+			))?;
+			return Ok(());
+		}
+		if let Some(drift) = drift(&path) {
+			log::warning(drift)?;
+		}
+		let mut features = self.profile.features(&path)?;
+		features.extend(self.features.iter().cloned());
+		match features.is_empty() {
+			true => log::info(format!("Building with the `{}` profile...", self.profile))?,
+			false => log::info(format!(
+				"Building with the `{}` profile and features: {}...",
+				self.profile,
+				features.join(", ")
+			))?,
+		}
+		if self.deterministic {
+			return self.build_deterministic(&path, &features);
+		}
+		let cache = BuildCache::detect(&path);
+		if cache.is_enabled() {
+			log::info(format!("Compilation is cached using {cache}."))?;
+		}
+		if let Err(e) = build_parachain_profile(&path, self.profile, &self.features) {
+			outro_cancel(format!("{e}"))?;
+			return Ok(());
+		}
+
+		// Export the artifacts required to launch or register the parachain.
+		let spinner = crate::progress::spinner();
+		spinner.start("Exporting the runtime and genesis artifacts...");
+		let artifacts = match export_artifacts(&path, self.profile) {
+			Ok(artifacts) => artifacts,
+			Err(e) => {
+				spinner.error(format!("{e}"));
+				outro_cancel("Unable to export the build artifacts.")?;
+				return Ok(());
+			},
+		};
+		spinner.stop("Artifacts exported.");
+		for artifact in
+			[&artifacts.node, &artifacts.runtime, &artifacts.genesis_state, &artifacts.genesis_wasm]
+		{
+			crate::progress::artifact(artifact);
+		}
+		log::info(format!(
+			"Node: {}\nRuntime: {}\nGenesis state: {}\nGenesis wasm: {}",
+			artifacts.node.display(),
+			artifacts.runtime.display(),
+			artifacts.genesis_state.display(),
+			artifacts.genesis_wasm.display()
+		))?;
 
 		outro("Build Completed Successfully!")?;
+		self.output.report(&BuildReport {
+			profile: self.profile.to_string(),
+			node: &artifacts.node,
+			runtime: &artifacts.runtime,
+			genesis_state: &artifacts.genesis_state,
+			genesis_wasm: &artifacts.genesis_wasm,
+		})?;
+		Ok(())
+	}
+
+	/// Builds the runtime of the parachain at `path` deterministically using srtool.
+	fn build_deterministic(&self, path: &Path, features: &[String]) -> anyhow::Result<()> {
+		let spinner = crate::progress::spinner();
+		spinner.start("Building the runtime with srtool, which may take a while...");
+		match build_deterministic_runtime(path, self.profile, features) {
+			Ok(artifacts) => {
+				spinner.stop("Runtime built deterministically.");
+				log::info(format!(
+					"Compact wasm: {}\nCompressed wasm: {}\nProposal hash: {}\nBuild digest: {}",
+					artifacts.compact.display(),
+					artifacts.compressed.display(),
+					style(&artifacts.proposal_hash).bold(),
+					artifacts.digest.display()
+				))?;
+				outro("Build Completed Successfully!")?;
+				self.output.report(&DeterministicBuildReport {
+					profile: self.profile.to_string(),
+					compact: &artifacts.compact,
+					compressed: &artifacts.compressed,
+					proposal_hash: &artifacts.proposal_hash,
+					digest: &artifacts.digest,
+				})?;
+			},
+			Err(e) => {
+				spinner.error(format!("{e}"));
+				outro_cancel("The runtime could not be built deterministically.")?;
+			},
+		}
+		Ok(())
+	}
+}
+
+/// Describes how the project at `path` drifted from the release of the Polkadot SDK recorded
+/// within its pop.toml when it was generated, if it did.
+///
+/// # Arguments
+///
+/// * `path` - path to the project
+fn drift(path: &Path) -> Option<String> {
+	let project = Project::load(path).ok().flatten()?;
+	let recorded = PolkadotRelease::new(project.sdk_version.as_deref()?).ok()?;
+	let current = PolkadotRelease::from_project(path).ok().flatten()?;
+	(!current.is_compatible(&recorded)).then(|| {
+		format!(
+			"⚠️ The project depends on Polkadot SDK {}, whereas the `{}` template was generated \
+			 with {}: review the changes of the template before updating the project.",
+			current.version(),
+			project.template,
+			recorded.version()
+		)
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::fs;
+
+	#[test]
+	fn drift_works() -> anyhow::Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let path = temp_dir.path();
+		fs::write(
+			path.join("Cargo.toml"),
+			"[package]\nname = \"node\"\n\n[dependencies]\npolkadot-sdk = \"2503.0.0\"\n",
+		)?;
+		assert_eq!(drift(path), None);
+
+		let mut project = Project {
+			kind: "parachain".into(),
+			template: "standard".into(),
+			sdk_version: Some("stable2503".into()),
+			..Default::default()
+		};
+		project.save(path)?;
+		assert_eq!(drift(path), None);
+
+		project.sdk_version = Some("stable2412".into());
+		project.save(path)?;
+		assert!(drift(path).is_some_and(|drift| drift.contains("stable2503")));
 		Ok(())
 	}
 }
@@ -0,0 +1,101 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::style::{style, Theme};
+use clap::Args;
+use cliclack::{clear_screen, intro, log, outro, outro_cancel, set_theme};
+use pop_common::Project;
+use pop_parachains::{
+	build_parachain, generate_chain_spec, is_parachain, node_binary, ChainSpecOverrides,
+};
+use std::path::{Path, PathBuf};
+
+#[derive(Args)]
+pub struct BuildSpecCommand {
+	/// Directory path for your project, [default: the current project]
+	#[arg(short = 'p', long = "path")]
+	pub(crate) path: Option<PathBuf>,
+	/// The chain to generate the spec of, as supported by the node (e.g. `dev`, `local`).
+	#[arg(long, default_value = "dev")]
+	chain: String,
+	/// Also generate the raw chain spec, as used to launch the parachain.
+	#[arg(long)]
+	raw: bool,
+	/// The identifier of the parachain, [default: that recorded within the project's pop.toml]
+	#[arg(long)]
+	para_id: Option<u32>,
+	/// The relay chain the parachain connects to (e.g. `paseo`, `rococo-local`).
+	#[arg(long)]
+	relay: Option<String>,
+	/// A bootnode of the chain, replacing those defined by the node. Can be specified multiple
+	/// times.
+	#[arg(long = "bootnode")]
+	boot_nodes: Vec<String>,
+	/// The network protocol id of the chain.
+	#[arg(long)]
+	protocol_id: Option<String>,
+	/// The symbol of the native token.
+	#[arg(long)]
+	token_symbol: Option<String>,
+	/// The number of decimals of the native token.
+	#[arg(long)]
+	token_decimals: Option<u8>,
+}
+
+impl BuildSpecCommand {
+	pub(crate) fn execute(&self) -> anyhow::Result<()> {
+		clear_screen()?;
+		intro(format!("{}: Generating a chain spec", style(" Pop CLI ").black().on_magenta()))?;
+		set_theme(Theme);
+		let path = crate::project_path(self.path.as_deref());
+		if !is_parachain(&path)? {
+			outro_cancel(format!(
+				"{} is not a parachain project, as it does not depend on Cumulus.",
+				path.display()
+			))?;
+			return Ok(());
+		}
+		// The spec is generated by the node, so build it first if required.
+		if node_binary(&path).is_err() {
+			log::info("The node has not been built yet, building the parachain...")?;
+			build_parachain(&Some(path.clone()))?;
+		}
+
+		let overrides = ChainSpecOverrides {
+			para_id: self.para_id.or_else(|| recorded_para_id(&path)),
+			relay_chain: self.relay.clone(),
+			boot_nodes: self.boot_nodes.clone(),
+			protocol_id: self.protocol_id.clone(),
+			token_symbol: self.token_symbol.clone(),
+			token_decimals: self.token_decimals,
+			genesis: Vec::new(),
+		};
+		let spinner = crate::progress::spinner();
+		spinner.start(format!("Generating the `{}` chain spec...", self.chain));
+		let spec = match generate_chain_spec(&path, &self.chain, &overrides, self.raw) {
+			Ok(spec) => spec,
+			Err(e) => {
+				spinner.error(format!("{e}"));
+				outro_cancel("Unable to generate the chain spec.")?;
+				return Ok(());
+			},
+		};
+		spinner.stop("Chain spec generated.");
+		let mut specs = format!("Plain: {}", spec.plain.display());
+		if let Some(raw) = &spec.raw {
+			specs += &format!("\nRaw: {}", raw.display());
+		}
+		log::info(specs)?;
+
+		outro("Chain spec generated successfully!")?;
+		Ok(())
+	}
+}
+
+/// The identifier of the parachain recorded within the pop.toml of the project at `path`, if any.
+///
+/// # Arguments
+///
+/// * `path` - path to the project
+pub(crate) fn recorded_para_id(path: &Path) -> Option<u32> {
+	Project::load(path).ok().flatten()?.para_id
+}
@@ -0,0 +1,80 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use super::list::table;
+use crate::{
+	commands::clean::format_size,
+	style::{style, Theme},
+};
+use clap::Args;
+use cliclack::{clear_screen, intro, log, outro, set_theme};
+use pop_common::cache::{cached_binaries, export};
+use std::{fs, path::PathBuf};
+
+#[derive(Args)]
+pub(crate) struct ExportCacheCommand {
+	/// Path of the archive created, a gzipped tarball.
+	archive: PathBuf,
+	/// Skip mirroring the repositories of the parachain templates within the archive.
+	#[arg(long)]
+	skip_templates: bool,
+}
+
+impl ExportCacheCommand {
+	pub(crate) fn execute(&self) -> anyhow::Result<()> {
+		clear_screen()?;
+		intro(format!("{}: Export the cache", style(" Pop CLI ").black().on_magenta()))?;
+		set_theme(Theme);
+
+		let cache = crate::cache()?;
+		if !self.skip_templates {
+			#[cfg(feature = "parachain")]
+			mirror_templates(&cache)?;
+		}
+		let binaries = cached_binaries(&cache)?;
+		if !binaries.is_empty() {
+			log::info(table(&binaries))?;
+		}
+		let spinner = crate::progress::spinner();
+		spinner.start(format!("Exporting {}...", cache.display()));
+		export(&cache, &self.archive)?;
+		spinner.stop(format!(
+			"Exported {} binaries to {} ({}).",
+			binaries.len(),
+			self.archive.display(),
+			format_size(fs::metadata(&self.archive)?.len())
+		));
+		outro(format!(
+			"Import the archive with `pop cache import {}` on the machine without network access.",
+			self.archive.display()
+		))?;
+		Ok(())
+	}
+}
+
+/// Mirrors the repository of each parachain template within `cache`, from which parachains are
+/// generated when the repository cannot be reached. Repositories which cannot be mirrored are
+/// reported and skipped.
+#[cfg(feature = "parachain")]
+fn mirror_templates(cache: &std::path::Path) -> anyhow::Result<()> {
+	use crate::commands::up::ProgressReporter;
+	use pop_common::{cache::template_mirror, Git};
+	use pop_parachains::Template;
+	use strum::VariantArray;
+
+	let mut urls: Vec<_> =
+		Template::VARIANTS.iter().filter_map(|t| t.repository_url().ok()).collect();
+	urls.sort();
+	urls.dedup();
+	let spinner = crate::progress::spinner();
+	for url in urls {
+		spinner.start(format!("Mirroring {url}..."));
+		let mirrored = url::Url::parse(url).map_err(anyhow::Error::from).and_then(|url| {
+			Git::mirror(&url, &template_mirror(cache, &url)?, ProgressReporter(&spinner))
+		});
+		if let Err(e) = mirrored {
+			log::warning(format!("⚠️ {url} could not be mirrored: {e}"))?;
+		}
+	}
+	spinner.stop("Templates mirrored.");
+	Ok(())
+}
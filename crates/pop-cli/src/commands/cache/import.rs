@@ -0,0 +1,41 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use super::list::table;
+use crate::style::{style, Theme};
+use clap::Args;
+use cliclack::{clear_screen, intro, log, outro, outro_cancel, set_theme};
+use pop_common::cache::{cached_binaries, import};
+use std::path::PathBuf;
+
+#[derive(Args)]
+pub(crate) struct ImportCacheCommand {
+	/// Path of the archive imported, as created by `pop cache export`.
+	archive: PathBuf,
+}
+
+impl ImportCacheCommand {
+	pub(crate) fn execute(&self) -> anyhow::Result<()> {
+		clear_screen()?;
+		intro(format!("{}: Import a cache", style(" Pop CLI ").black().on_magenta()))?;
+		set_theme(Theme);
+
+		if !self.archive.exists() {
+			outro_cancel(format!("{} could not be found.", self.archive.display()))?;
+			return Ok(());
+		}
+		let cache = crate::cache()?;
+		let spinner = crate::progress::spinner();
+		spinner.start(format!("Importing {}...", self.archive.display()));
+		let imported = import(&cache, &self.archive)?;
+		spinner.stop(format!("Imported {imported} entries into {}.", cache.display()));
+		let binaries = cached_binaries(&cache)?;
+		if !binaries.is_empty() {
+			log::info(table(&binaries))?;
+		}
+		outro(
+			"Binaries are used from the cache without network access, provided their version is \
+			 specified or pinned with `pop binaries use`.",
+		)?;
+		Ok(())
+	}
+}
@@ -0,0 +1,81 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::{
+	commands::clean::format_size,
+	output::OutputArgs,
+	style::{self, style, Align, Theme},
+};
+use clap::Args;
+use cliclack::{intro, log, outro, set_theme};
+use pop_common::cache::{cached_binaries, CachedBinary};
+use serde::Serialize;
+
+#[derive(Args)]
+pub(crate) struct ListCacheCommand {
+	#[command(flatten)]
+	output: OutputArgs,
+}
+
+/// The binaries cached, as output by `--output json`.
+#[derive(Serialize)]
+struct CacheReport<'a> {
+	binaries: &'a [CachedBinary],
+	/// The total size of the binaries, in bytes.
+	size: u64,
+}
+
+impl ListCacheCommand {
+	pub(crate) fn execute(&self) -> anyhow::Result<()> {
+		self.output.clear_screen()?;
+		intro(format!("{}: List cached binaries", style(" Pop CLI ").black().on_magenta()))?;
+		set_theme(Theme);
+
+		let cache = crate::cache()?;
+		let binaries = cached_binaries(&cache)?;
+		let size = binaries.iter().map(|b| b.size).sum();
+		self.output.report(&CacheReport { binaries: &binaries, size })?;
+		if binaries.is_empty() {
+			outro(format!("No binaries cached within {}.", cache.display()))?;
+			return Ok(());
+		}
+		log::info(table(&binaries))?;
+		outro(format!(
+			"{} binaries cached within {} ({}).",
+			binaries.len(),
+			cache.display(),
+			format_size(size)
+		))?;
+		Ok(())
+	}
+}
+
+/// A table listing the name, version and size of each binary.
+pub(crate) fn table(binaries: &[CachedBinary]) -> String {
+	let rows: Vec<_> = binaries
+		.iter()
+		.map(|b| [b.name.clone(), b.version.clone(), format_size(b.size)])
+		.collect();
+	style::table([("Binary", Align::Left), ("Version", Align::Left), ("Size", Align::Right)], &rows)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::path::PathBuf;
+
+	#[test]
+	fn table_works() {
+		let binary = |name: &str, version: &str, size| CachedBinary {
+			name: name.into(),
+			version: version.into(),
+			path: PathBuf::new(),
+			size,
+		};
+		assert_eq!(
+			table(&[binary("polkadot", "stable2409", 2048), binary("pop-node", "latest", 512)]),
+			"Binary    Version       Size\n\
+			 polkadot  stable2409  2.0 KB\n\
+			 pop-node  latest       512 B"
+		);
+	}
+}
@@ -0,0 +1,33 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use clap::{Args, Subcommand};
+
+pub(crate) mod export;
+pub(crate) mod import;
+pub(crate) mod list;
+pub(crate) mod prune;
+pub(crate) mod verify;
+
+#[derive(Args)]
+#[command(args_conflicts_with_subcommands = true)]
+pub(crate) struct CacheArgs {
+	#[command(subcommand)]
+	pub command: CacheCommands,
+}
+
+#[derive(Subcommand)]
+pub(crate) enum CacheCommands {
+	/// List the binaries cached, by name and version
+	#[clap(alias = "ls")]
+	List(list::ListCacheCommand),
+	/// Remove the older versions of the binaries cached
+	Prune(prune::PruneCacheCommand),
+	/// Export the binaries cached and template mirrors to an archive, for use without network
+	/// access
+	Export(export::ExportCacheCommand),
+	/// Import an archive created by `pop cache export` into the cache
+	Import(import::ImportCacheCommand),
+	/// Verify the binaries cached and template mirrors against their recorded digests, repairing
+	/// those damaged on request
+	Verify(verify::VerifyCacheCommand),
+}
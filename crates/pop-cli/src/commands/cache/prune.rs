@@ -0,0 +1,74 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use super::list::table;
+use crate::{
+	commands::clean::{format_size, size},
+	dry_run::{self, dry_run, Effect},
+	prompt,
+	style::{style, Theme},
+};
+use clap::Args;
+use cliclack::{clear_screen, intro, log, outro, outro_cancel, set_theme};
+use pop_common::cache::{legacy_binaries, prunable, prune};
+use std::fs;
+
+#[derive(Args)]
+pub(crate) struct PruneCacheCommand {
+	/// The number of versions of each binary kept, from the newest.
+	#[arg(long, default_value_t = 1)]
+	keep_latest: usize,
+}
+
+impl PruneCacheCommand {
+	pub(crate) fn execute(&self) -> anyhow::Result<()> {
+		clear_screen()?;
+		intro(format!("{}: Prune cached binaries", style(" Pop CLI ").black().on_magenta()))?;
+		set_theme(Theme);
+
+		let cache = crate::cache()?;
+		let binaries = prunable(&cache, self.keep_latest)?;
+		// Binaries cached by previous versions of pop are no longer used.
+		let legacy = legacy_binaries(&cache)?;
+		if binaries.is_empty() && legacy.is_empty() {
+			outro("Nothing to prune.")?;
+			return Ok(());
+		}
+		let total = binaries.iter().map(|b| b.size).sum::<u64>()
+			+ legacy.iter().map(|path| size(path)).sum::<u64>();
+		if !binaries.is_empty() {
+			log::info(table(&binaries))?;
+		}
+		if !legacy.is_empty() {
+			log::info(format!(
+				"Cached by a previous version of pop:\n{}",
+				legacy
+					.iter()
+					.map(|path| path.display().to_string())
+					.collect::<Vec<_>>()
+					.join("\n")
+			))?;
+		}
+		if dry_run() {
+			let effects: Vec<_> = binaries
+				.iter()
+				.map(|b| b.path.clone())
+				.chain(legacy)
+				.map(Effect::Remove)
+				.collect();
+			return dry_run::report(&effects);
+		}
+		if !prompt::confirm(
+			format!("Would you like to remove the above, freeing {}?", format_size(total)),
+			true,
+		)? {
+			outro_cancel("Nothing removed.")?;
+			return Ok(());
+		}
+		prune(&cache, self.keep_latest)?;
+		for path in &legacy {
+			fs::remove_file(path)?;
+		}
+		outro(format!("Removed {}.", format_size(total)))?;
+		Ok(())
+	}
+}
@@ -0,0 +1,129 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::{
+	commands::up::ProgressReporter,
+	dry_run::{self, dry_run, Effect},
+	output::OutputArgs,
+	style::{self, style, Align, Theme},
+};
+use clap::Args;
+use cliclack::{intro, log, outro, outro_cancel, set_theme};
+use pop_common::cache::{repair, verify, Integrity, VerifiedEntry};
+
+#[derive(Args)]
+pub(crate) struct VerifyCacheCommand {
+	/// Repair the damaged entries: interrupted downloads are removed, binaries are downloaded
+	/// again and template mirrors are mirrored again.
+	#[arg(long)]
+	repair: bool,
+	#[command(flatten)]
+	output: OutputArgs,
+}
+
+impl VerifyCacheCommand {
+	pub(crate) async fn execute(&self) -> anyhow::Result<()> {
+		self.output.clear_screen()?;
+		intro(format!("{}: Verify the cache", style(" Pop CLI ").black().on_magenta()))?;
+		set_theme(Theme);
+
+		let cache = crate::cache()?;
+		let spinner = crate::progress::spinner();
+		spinner.start(format!("Verifying {}...", cache.display()));
+		let entries = verify(&cache)?;
+		spinner.stop(format!("Verified {} entries.", entries.len()));
+		self.output.report(&entries)?;
+		let damaged: Vec<_> = entries.iter().filter(|e| e.is_damaged()).collect();
+		let unrecorded = entries.iter().filter(|e| e.integrity == Integrity::Unrecorded).count();
+		if unrecorded > 0 {
+			log::info(format!(
+				"{unrecorded} binaries cached before their digests were recorded could not be \
+				 verified."
+			))?;
+		}
+		if damaged.is_empty() {
+			outro("The cache is intact.")?;
+			return Ok(());
+		}
+		log::warning(table(&damaged))?;
+		if !self.repair {
+			outro_cancel(format!(
+				"{} damaged entries found: repair them with `pop cache verify --repair`.",
+				damaged.len()
+			))?;
+			return Ok(());
+		}
+		if dry_run() {
+			let effects: Vec<_> = damaged.iter().map(|e| Effect::Remove(e.path.clone())).collect();
+			return dry_run::report(&effects);
+		}
+		let (mut repaired, mut removed) = (0, Vec::new());
+		for entry in damaged {
+			spinner.start(format!("Repairing {}...", entry.path.display()));
+			match repair(&cache, entry, ProgressReporter(&spinner)).await {
+				Ok(true) => repaired += 1,
+				Ok(false) => removed.push(entry.name.as_str()),
+				Err(e) => log::warning(format!("⚠️ {} could not be repaired: {e}", entry.name))?,
+			}
+		}
+		spinner.stop(format!("Repaired {repaired} entries."));
+		if !removed.is_empty() {
+			log::info(format!(
+				"Removed {}, built from source again when next used.",
+				removed.join(", ")
+			))?;
+		}
+		outro("The cache was repaired.")?;
+		Ok(())
+	}
+}
+
+/// A table listing each damaged entry along with its damage.
+fn table(entries: &[&VerifiedEntry]) -> String {
+	let name = |e: &VerifiedEntry| match &e.version {
+		Some(version) => format!("{} {version}", e.name),
+		None => e.name.clone(),
+	};
+	let rows: Vec<_> = entries
+		.iter()
+		.filter_map(|entry| {
+			let damage = match &entry.integrity {
+				Integrity::Corrupted { reason } => format!("corrupted: {reason}"),
+				Integrity::Partial => format!("interrupted download: {}", entry.path.display()),
+				Integrity::Intact | Integrity::Unrecorded => return None,
+			};
+			Some([name(entry), damage])
+		})
+		.collect();
+	style::table([("Entry", Align::Left), ("Damage", Align::Left)], &rows)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::path::PathBuf;
+
+	#[test]
+	fn table_works() {
+		let entry = |name: &str, version: Option<&str>, integrity| VerifiedEntry {
+			name: name.into(),
+			version: version.map(String::from),
+			path: PathBuf::from("/cache/bins/polkadot/v1.13.0/polkadot.part"),
+			integrity,
+		};
+		let entries = [
+			entry("polkadot", Some("v1.13.0"), Integrity::Partial),
+			entry(
+				"r0gue-io/base-parachain",
+				None,
+				Integrity::Corrupted { reason: "object not found".into() },
+			),
+		];
+		assert_eq!(
+			table(&entries.iter().collect::<Vec<_>>()),
+			"Entry                    Damage\n\
+			 polkadot v1.13.0         interrupted download: \
+			 /cache/bins/polkadot/v1.13.0/polkadot.part\n\
+			 r0gue-io/base-parachain  corrupted: object not found"
+		);
+	}
+}
@@ -2,31 +2,40 @@
 
 use anyhow::anyhow;
 use clap::Args;
-use cliclack::{clear_screen, intro, log, outro, outro_cancel, set_theme};
+use cliclack::{clear_screen, input, intro, log, outro, outro_cancel, set_theme};
 use console::style;
 use pop_contracts::{
-	call_smart_contract, dry_run_call, dry_run_gas_estimate_call, set_up_call, CallOpts,
+	call_smart_contract, dry_run_call, dry_run_gas_estimate_call, environment, load_metadata,
+	parse_metadata, prepare_offline_call, set_up_call, validate_arg, Address, CallOpts,
+	FunctionType, DEFAULT_GAS_MARGIN,
 };
 use sp_weights::Weight;
 use std::path::PathBuf;
 
-use crate::style::Theme;
+use crate::{
+	commands::key::{resolve_suri, sign_externally},
+	prompt,
+	style::Theme,
+};
 
-#[derive(Args)]
+#[derive(Args, Clone)]
 pub struct CallContractCommand {
 	/// Path to the contract build folder.
 	#[arg(short = 'p', long)]
 	path: Option<PathBuf>,
-	/// The address of the contract to call.
+	/// The address of the contract to call. Prompted for when not provided.
 	#[clap(name = "contract", long, env = "CONTRACT")]
-	contract: String,
-	/// The name of the contract message to call.
+	contract: Option<String>,
+	/// The name of the contract message to call. Prompted for, along with its arguments, when not
+	/// provided.
 	#[clap(long, short)]
-	message: String,
-	/// The constructor arguments, encoded as strings.
+	message: Option<String>,
+	/// The message arguments, encoded as strings.
 	#[clap(long, num_args = 0..)]
 	args: Vec<String>,
-	/// Transfers an initial balance to the instantiated contract.
+	/// Transfers an initial balance to the contract, which requires a payable message: either an
+	/// amount in the smallest unit of the chain's token, or an amount of the token using its
+	/// symbol, e.g. `10UNIT`.
 	#[clap(name = "value", long, default_value = "0")]
 	value: String,
 	/// Maximum amount of gas to be used for this command.
@@ -38,82 +47,441 @@ pub struct CallContractCommand {
 	/// If not specified it will perform a dry-run to estimate the proof size required.
 	#[clap(long)]
 	proof_size: Option<u64>,
-	/// Websocket endpoint of a node.
-	#[clap(name = "url", long, value_parser, default_value = "ws://localhost:9944")]
-	url: url::Url,
-	/// Secret key URI for the account deploying the contract.
+	/// The margin added to the gas estimated by the dry-run, as a percentage.
+	#[clap(long, default_value_t = DEFAULT_GAS_MARGIN)]
+	gas_margin: u8,
+	/// The environment to call the contract on, providing the endpoint and default signer: one of
+	/// `local`, `pop-testnet`, `shibuya` or an environment defined within the project's pop.toml.
+	/// [default: local]
+	#[clap(long)]
+	env: Option<String>,
+	/// Websocket endpoint of a node, [default: the endpoint of the environment]
+	#[clap(name = "url", long, value_parser)]
+	url: Option<url::Url>,
+	/// Secret key URI for the account calling the contract, [default: the signer of the
+	/// environment, although executing the call prompts for it unless the environment is selected
+	/// with `--env` or its signer is configured]
 	///
 	/// e.g.
 	/// - for a dev account "//Alice"
 	/// - with a password "//Alice///SECRET_PASSWORD"
 	#[clap(name = "suri", long, short)]
-	suri: String,
+	suri: Option<String>,
+	/// The name of a key within the local keystore to sign with, instead of providing a secret key
+	/// URI (see `pop key import`).
+	#[clap(long, conflicts_with = "suri")]
+	account: Option<String>,
+	/// Select a key from the local keystore to sign with.
+	#[clap(long, conflicts_with_all = ["suri", "account"])]
+	use_wallet: bool,
 	/// Submit an extrinsic for on-chain execution.
 	#[clap(short('x'), long)]
 	execute: bool,
+	/// Sign the call with an external signer, e.g. an air-gapped device: the payload to be signed
+	/// is displayed, along with a QR code, and the call is submitted once the signature is
+	/// provided. No secret key is required.
+	#[clap(long, requires_all = ["execute", "signer"], conflicts_with_all = ["suri", "account", "use_wallet"])]
+	offline: bool,
+	/// The address of the account signing the call with an external signer.
+	#[clap(long, requires = "offline")]
+	signer: Option<String>,
 }
 
 impl CallContractCommand {
+	/// The contract project, detected from the current directory unless provided.
+	fn path(&self) -> Option<PathBuf> {
+		Some(crate::contract_path(self.path.as_deref()))
+	}
+
 	pub(crate) async fn execute(&self) -> anyhow::Result<()> {
 		clear_screen()?;
 		intro(format!("{}: Calling a contract", style(" Pop CLI ").black().on_magenta()))?;
 		set_theme(Theme);
 
-		let call_exec = set_up_call(CallOpts {
-			path: self.path.clone(),
-			contract: self.contract.clone(),
-			message: self.message.clone(),
-			args: self.args.clone(),
-			value: self.value.clone(),
-			gas_limit: self.gas_limit,
-			proof_size: self.proof_size,
-			url: self.url.clone(),
-			suri: self.suri.clone(),
-			execute: self.execute,
-		})
-		.await?;
+		// Guide the user through the call when the contract or message is not provided, offering to
+		// execute mutating messages once the result of the dry run is shown.
+		if self.contract.is_none() || self.message.is_none() {
+			return match guide_user_to_call_contract(self) {
+				Ok((command, mutates)) => command.call(mutates).await,
+				Err(e) => {
+					outro_cancel(format!("{e}"))?;
+					Ok(())
+				},
+			};
+		}
+		self.call(false).await
+	}
+
+	/// Calls the contract, prompting to execute the call after the dry run when `prompt_to_execute`
+	/// is set.
+	async fn call(&self, prompt_to_execute: bool) -> anyhow::Result<()> {
+		let environment = match environment(&self.path(), self.env.as_deref(), crate::config()) {
+			Ok(environment) => environment,
+			Err(e) => {
+				outro_cancel(format!("{e}"))?;
+				return Ok(());
+			},
+		};
+		let url = self.url.clone().unwrap_or_else(|| environment.url.clone());
+		if self.offline {
+			return self.call_offline(url).await;
+		}
+		// Executing the call requires its signer to be provided, rather than implicitly signing with
+		// the built-in dev account of the environment. Selecting the environment with `--env`, or
+		// configuring its signer, counts as providing it.
+		let signer_provided = self.suri.is_some()
+			|| self.account.is_some()
+			|| self.use_wallet
+			|| environment.account.is_some()
+			|| (environment.suri.is_some() && (self.env.is_some() || !environment.built_in_signer));
+		let suri = match self.execute && !signer_provided {
+			true => prompt_suri(),
+			false => resolve_suri(
+				self.suri.as_deref(),
+				self.account.as_deref(),
+				self.use_wallet,
+				&environment,
+			),
+		};
+		let suri = match suri {
+			Ok(suri) => suri,
+			Err(e) => {
+				outro_cancel(format!("{e}"))?;
+				return Ok(());
+			},
+		};
+		let mut call_exec = match set_up_call(self.call_opts(url.clone(), suri)).await {
+			Ok(exec) => exec,
+			Err(e) => {
+				outro_cancel(format!("{e}"))?;
+				return Ok(());
+			},
+		};
 
 		if !self.execute {
-			let spinner = cliclack::spinner();
+			let spinner = crate::progress::spinner();
 			spinner.start("Calling the contract...");
-			let call_dry_run_result = dry_run_call(&call_exec).await?;
-			log::info(format!("Result: {}", call_dry_run_result))?;
-			log::warning("Your call has not been executed.")?;
-			log::warning(format!(
-                    "To submit the transaction and execute the call on chain, add {} flag to the command.",
-                    "-x/--execute"
-            ))?;
-		} else {
-			let weight_limit;
-			if self.gas_limit.is_some() && self.proof_size.is_some() {
-				weight_limit =
-					Weight::from_parts(self.gas_limit.unwrap(), self.proof_size.unwrap());
-			} else {
-				let spinner = cliclack::spinner();
-				spinner.start("Doing a dry run to estimate the gas...");
-				weight_limit = match dry_run_gas_estimate_call(&call_exec).await {
-					Ok(w) => {
-						log::info(format!("Gas limit {:?}", w))?;
-						w
-					},
+			let call_dry_run_result = match dry_run_call(&call_exec).await {
+				Ok(result) => result,
+				Err(e) => {
+					spinner.error(format!("{e}"));
+					outro_cancel("Call failed.")?;
+					return Ok(());
+				},
+			};
+			spinner.stop(format!("Result: {}", call_dry_run_result));
+			// Submitting requires `--execute` when running non-interactively.
+			let submit = prompt_to_execute
+				&& !prompt::skip_confirm()
+				&& prompt::confirm(
+					"Do you want to submit the call and execute it on chain?",
+					false,
+				)?;
+			if !submit {
+				log::warning("Your call has not been executed.")?;
+				log::warning(format!(
+					"To submit the transaction and execute the call on chain, add {} flag to the command.",
+					"-x/--execute"
+				))?;
+				outro("Call completed successfully!")?;
+				return Ok(());
+			}
+			if !signer_provided {
+				call_exec = match prompt_suri() {
+					Ok(suri) => set_up_call(self.call_opts(url.clone(), suri)).await?,
 					Err(e) => {
-						spinner.error(format!("{e}"));
-						outro_cancel("Deployment failed.")?;
+						outro_cancel(format!("{e}"))?;
 						return Ok(());
 					},
 				};
 			}
-			let spinner = cliclack::spinner();
-			spinner.start("Calling the contract...");
+		}
+		let weight_limit;
+		if let (Some(gas_limit), Some(proof_size)) = (self.gas_limit, self.proof_size) {
+			weight_limit = Weight::from_parts(gas_limit, proof_size);
+		} else {
+			let spinner = crate::progress::spinner();
+			spinner.start("Doing a dry run to estimate the gas...");
+			let estimate = match dry_run_gas_estimate_call(&call_exec, self.gas_margin).await {
+				Ok(estimate) => estimate,
+				Err(e) => {
+					spinner.error(format!("{e}"));
+					outro_cancel("Call failed.")?;
+					return Ok(());
+				},
+			};
+			spinner.stop(format!(
+				"Estimated gas limit: ref_time {}, proof_size {} ({}% margin)",
+				estimate.weight.ref_time(),
+				estimate.weight.proof_size(),
+				self.gas_margin
+			));
+			log::info(format!("Estimated storage deposit: {}", estimate.storage_deposit))?;
+			weight_limit = estimate.weight;
+		}
+		let spinner = crate::progress::spinner();
+		spinner.start("Calling the contract...");
 
-			let call_result = call_smart_contract(call_exec, weight_limit, &self.url)
-				.await
-				.map_err(|err| anyhow!("{} {}", "ERROR:", format!("{err:?}")))?;
+		let call_result = call_smart_contract(call_exec, weight_limit, &url)
+			.await
+			.map_err(|err| anyhow!("{} {}", "ERROR:", format!("{err:?}")))?;
+
+		spinner.stop("Call submitted and executed on chain.");
+		log::info(format!("Events:\n{call_result}"))?;
+
+		outro("Call completed successfully!")?;
+		Ok(())
+	}
 
-			log::info(call_result)?;
+	/// The options of the call, signed with the account of `suri`.
+	///
+	/// # Arguments
+	///
+	/// * `url` - the endpoint of the node
+	/// * `suri` - the secret key URI of the account calling the contract
+	fn call_opts(&self, url: url::Url, suri: String) -> CallOpts {
+		CallOpts {
+			path: self.path(),
+			contract: self.contract.clone().unwrap_or_default(),
+			message: self.message.clone().unwrap_or_default(),
+			args: self.args.clone(),
+			value: self.value.clone(),
+			gas_limit: self.gas_limit,
+			proof_size: self.proof_size,
+			url,
+			suri,
+			execute: self.execute,
 		}
+	}
 
+	/// Executes the call using an external signer.
+	async fn call_offline(&self, url: url::Url) -> anyhow::Result<()> {
+		let signer = self.signer.clone().unwrap_or_default();
+		let spinner = crate::progress::spinner();
+		spinner.start("Doing a dry run on behalf of the signer to estimate the gas...");
+		let call_opts = CallOpts {
+			path: self.path(),
+			contract: self.contract.clone().unwrap_or_default(),
+			message: self.message.clone().unwrap_or_default(),
+			args: self.args.clone(),
+			value: self.value.clone(),
+			gas_limit: self.gas_limit,
+			proof_size: self.proof_size,
+			url,
+			suri: String::new(),
+			execute: true,
+		};
+		let unsigned = match prepare_offline_call(&call_opts, &signer, self.gas_margin).await {
+			Ok(unsigned) => unsigned,
+			Err(e) => {
+				spinner.error(format!("{e}"));
+				outro_cancel("Call failed.")?;
+				return Ok(());
+			},
+		};
+		spinner.stop(format!(
+			"Gas limit: ref_time {}, proof_size {}, storage deposit: {}",
+			unsigned.estimate.weight.ref_time(),
+			unsigned.estimate.weight.proof_size(),
+			unsigned.estimate.storage_deposit
+		));
+		let submitted = match sign_externally(&unsigned, &signer).await {
+			Ok(submitted) => submitted,
+			Err(e) => {
+				outro_cancel(format!("Call failed: {e}"))?;
+				return Ok(());
+			},
+		};
+		log::info(format!("Events:\n{}", submitted.events.join("\n")))?;
 		outro("Call completed successfully!")?;
 		Ok(())
 	}
 }
+
+/// Prompts for the secret key URI of the account executing the call, so that it does not appear
+/// within the shell history.
+fn prompt_suri() -> anyhow::Result<String> {
+	prompt::password("Enter the secret key URI of the account executing the call:", "--suri")
+}
+
+/// Prompts for the contract address, message and arguments of the call, using the metadata of the
+/// contract to list its messages and validate each argument as it is entered. Returns the resulting
+/// command, along with whether the selected message mutates the contract state.
+fn guide_user_to_call_contract(
+	command: &CallContractCommand,
+) -> anyhow::Result<(CallContractCommand, bool)> {
+	let json = load_metadata(&command.path())?.ok_or(anyhow!(
+		"the contract has not been built, build it first with `pop build contract`"
+	))?;
+	let metadata = parse_metadata(&json)?;
+	let mut command = command.clone();
+
+	if command.contract.is_none() {
+		prompt::ensure_interactive("--contract")?;
+		let contract: String = input("Where is your contract deployed?")
+			.placeholder("5DYs7UGBm2LuX4ryvyqfksozNAW5V47tPbGiVgnjYWCZ29bt")
+			.validate(|address: &String| match Address::parse(address) {
+				Ok(Address::Account(_)) => Ok(()),
+				_ => Err("Please provide the SS58 or hex encoded address of the contract."),
+			})
+			.interact()?;
+		command.contract = Some(contract);
+	}
+
+	let message = match &command.message {
+		Some(label) => metadata
+			.messages
+			.iter()
+			.find(|m| &m.label == label)
+			.ok_or(anyhow!("the contract has no message named `{label}`"))?,
+		None => {
+			if metadata.messages.is_empty() {
+				return Err(anyhow!("the contract has no messages to call"));
+			}
+			prompt::ensure_interactive("--message")?;
+			let mut select = cliclack::select("Select the message to call:");
+			for (i, message) in metadata.messages.iter().enumerate() {
+				let kind = if message.mutates { "[mutates]" } else { "[reads]" };
+				select =
+					select.item(i, &message.label, format!("{kind} {}", message.docs.join(" ")));
+			}
+			&metadata.messages[select.interact()?]
+		},
+	};
+	command.message = Some(message.label.clone());
+
+	if command.args.is_empty() && !message.args.is_empty() {
+		prompt::ensure_interactive("--args")?;
+		for (index, param) in message.args.iter().enumerate() {
+			let (json, label) = (json.clone(), message.label.clone());
+			let arg: String =
+				input(format!("Enter the value for `{}` ({}):", param.label, param.type_name))
+					.validate(move |value: &String| {
+						validate_arg(&json, FunctionType::Message, &label, index, value)
+					})
+					.interact()?;
+			command.args.push(arg);
+		}
+	}
+	if message.payable && command.value == "0" {
+		command.value =
+			prompt::input("How much value should be transferred to the contract?", "0")?;
+	}
+	Ok((command, message.mutates))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		commands::call::{CallArgs, CallCommands::Contract},
+		Cli,
+		Commands::Call,
+	};
+	use clap::Parser;
+
+	#[test]
+	fn test_call_contract_command_defaults_to_dry_run() {
+		let cli = Cli::parse_from([
+			"pop",
+			"call",
+			"contract",
+			"--contract",
+			"5CLPm1CeUvJhZ8GCDZCR7nWZ2m3XXe4X5MtAQK69zEjut36A",
+			"--message",
+			"get",
+		]);
+		let Call(CallArgs { command: Contract(command) }) = cli.command else {
+			panic!("unable to parse command")
+		};
+		assert_eq!(command.message.as_deref(), Some("get"));
+		assert_eq!(command.suri, None);
+		assert_eq!(command.url, None);
+		assert_eq!(command.env, None);
+		assert!(!command.execute);
+	}
+
+	#[test]
+	fn test_call_contract_command_with_environment() {
+		let args = [
+			"pop",
+			"call",
+			"contract",
+			"--contract",
+			"5CLPm1CeUvJhZ8GCDZCR7nWZ2m3XXe4X5MtAQK69zEjut36A",
+			"--message",
+			"get",
+			"--env",
+			"shibuya",
+		];
+		let cli = Cli::parse_from(args);
+		let Call(CallArgs { command: Contract(command) }) = cli.command else {
+			panic!("unable to parse command")
+		};
+		assert_eq!(command.env.as_deref(), Some("shibuya"));
+	}
+
+	#[test]
+	fn test_call_contract_command_without_message_is_guided() {
+		let cli = Cli::parse_from(["pop", "call", "contract"]);
+		let Call(CallArgs { command: Contract(command) }) = cli.command else {
+			panic!("unable to parse command")
+		};
+		assert!(command.message.is_none());
+		assert!(command.args.is_empty());
+	}
+
+	#[test]
+	fn test_call_contract_command_signs_with_account() {
+		let args = [
+			"pop",
+			"call",
+			"contract",
+			"--contract",
+			"5CLPm1CeUvJhZ8GCDZCR7nWZ2m3XXe4X5MtAQK69zEjut36A",
+			"--message",
+			"get",
+			"--account",
+			"alice",
+		];
+		let cli = Cli::parse_from(args);
+		let Call(CallArgs { command: Contract(command) }) = cli.command else {
+			panic!("unable to parse command")
+		};
+		assert_eq!(command.account.as_deref(), Some("alice"));
+		assert!(!command.use_wallet);
+
+		// An explicit secret key URI cannot be combined with a key from the keystore.
+		assert!(Cli::try_parse_from(args.iter().chain(&["--suri", "//Bob"])).is_err());
+	}
+
+	#[test]
+	fn test_call_contract_command_signs_offline() {
+		let args = [
+			"pop",
+			"call",
+			"contract",
+			"--contract",
+			"5CLPm1CeUvJhZ8GCDZCR7nWZ2m3XXe4X5MtAQK69zEjut36A",
+			"--message",
+			"flip",
+			"-x",
+			"--offline",
+			"--signer",
+			"5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY",
+		];
+		let cli = Cli::parse_from(args);
+		let Call(CallArgs { command: Contract(command) }) = cli.command else {
+			panic!("unable to parse command")
+		};
+		assert!(command.offline);
+		assert_eq!(
+			command.signer.as_deref(),
+			Some("5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY")
+		);
+
+		// Signing offline requires the signer and cannot be combined with a secret key URI.
+		assert!(Cli::try_parse_from(&args[..args.len() - 2]).is_err());
+		assert!(Cli::try_parse_from(args.iter().chain(&["--suri", "//Bob"])).is_err());
+	}
+}
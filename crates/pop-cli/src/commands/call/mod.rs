@@ -4,6 +4,16 @@ use clap::{Args, Subcommand};
 
 #[cfg(feature = "contract")]
 pub(crate) mod contract;
+#[cfg(feature = "parachain")]
+pub(crate) mod open_hrmp;
+#[cfg(feature = "parachain")]
+pub(crate) mod parachain;
+#[cfg(feature = "parachain")]
+pub(crate) mod reserve_para_id;
+#[cfg(feature = "parachain")]
+pub(crate) mod sudo;
+#[cfg(feature = "parachain")]
+pub(crate) mod xcm;
 
 #[derive(Args)]
 #[command(args_conflicts_with_subcommands = true)]
@@ -18,4 +28,38 @@ pub(crate) enum CallCommands {
 	#[cfg(feature = "contract")]
 	#[clap(alias = "c")]
 	Contract(contract::CallContractCommand),
+	/// Submit an extrinsic to a parachain, selecting its pallet, call and arguments interactively
+	/// when not provided
+	#[cfg(feature = "parachain")]
+	#[clap(alias = "p")]
+	Parachain(parachain::CallParachainCommand),
+	/// Submit an extrinsic to a parachain, dispatching its call using the sudo account of the
+	/// chain, such as the dev sudo key of networks launched by `pop up parachain`
+	#[cfg(feature = "parachain")]
+	Sudo(sudo::SudoCommand),
+	/// Reserve a para id on a relay chain
+	#[cfg(feature = "parachain")]
+	ReserveParaId(reserve_para_id::ReserveParaIdCommand),
+	/// Open HRMP channels between two parachains, in both directions, using sudo on the relay chain
+	#[cfg(feature = "parachain")]
+	OpenHrmp(open_hrmp::OpenHrmpCommand),
+	/// Transfer assets between the chains of a network via XCM, tracking the delivery of the
+	/// message on the destination
+	#[cfg(feature = "parachain")]
+	Xcm(xcm::XcmArgs),
+}
+
+/// Resolves the secret key URI signing an extrinsic: the key named `account` within the local
+/// keystore, if provided, otherwise `suri`.
+///
+/// # Arguments
+///
+/// * `suri` - the secret key URI provided on the command line
+/// * `account` - the name of a key within the local keystore
+#[cfg(feature = "parachain")]
+pub(crate) fn signer(suri: &str, account: Option<&str>) -> anyhow::Result<String> {
+	match account {
+		Some(account) => crate::key_suri(account),
+		None => Ok(suri.to_string()),
+	}
 }
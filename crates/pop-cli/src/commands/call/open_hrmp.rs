@@ -0,0 +1,84 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::{
+	dry_run::{dry_run, report, Effect},
+	style::{style, Theme},
+};
+use clap::Args;
+use cliclack::{clear_screen, intro, outro, outro_cancel, set_theme};
+use pop_parachains::{
+	open_hrmp_channels, HrmpChannel, DEFAULT_HRMP_MAX_CAPACITY, DEFAULT_HRMP_MAX_MESSAGE_SIZE,
+};
+use url::Url;
+
+#[derive(Args)]
+pub struct OpenHrmpCommand {
+	/// The identifier of a parachain.
+	para_a: u32,
+	/// The identifier of the other parachain.
+	para_b: u32,
+	/// Websocket endpoint of the relay chain.
+	#[arg(long, value_parser, default_value = "ws://127.0.0.1:9944")]
+	relay_url: Url,
+	/// Secret key URI for the sudo account of the relay chain.
+	#[arg(long, short, default_value = "//Alice")]
+	suri: String,
+	/// The maximum number of messages within each channel.
+	#[arg(long, default_value_t = DEFAULT_HRMP_MAX_CAPACITY)]
+	max_capacity: u32,
+	/// The maximum size of a message, in bytes.
+	#[arg(long, default_value_t = DEFAULT_HRMP_MAX_MESSAGE_SIZE)]
+	max_message_size: u32,
+}
+
+impl OpenHrmpCommand {
+	pub(crate) async fn execute(&self) -> anyhow::Result<()> {
+		clear_screen()?;
+		intro(format!("{}: Open HRMP channels", style(" Pop CLI ").black().on_magenta()))?;
+		set_theme(Theme);
+		if self.para_a == self.para_b {
+			outro_cancel("A channel can only be opened between two different parachains.")?;
+			return Ok(());
+		}
+
+		let channels = HrmpChannel::bidirectional(
+			self.para_a,
+			self.para_b,
+			self.max_capacity,
+			self.max_message_size,
+		);
+		if dry_run() {
+			let calls: Vec<_> = channels
+				.iter()
+				.map(|c| {
+					format!(
+						"Hrmp.force_open_hrmp_channel({}, {}, {}, {})",
+						c.sender, c.recipient, c.max_capacity, c.max_message_size
+					)
+				})
+				.collect();
+			let call = format!("Sudo.sudo(Utility.batch_all([{}]))", calls.join(", "));
+			return report(&[Effect::Submit { chain: self.relay_url.to_string(), call }]);
+		}
+
+		let spinner = crate::progress::spinner();
+		spinner.start(format!(
+			"Opening channels between parachains {} and {}...",
+			self.para_a, self.para_b
+		));
+		match open_hrmp_channels(&self.relay_url, &self.suri, &channels).await {
+			Ok(()) => {
+				spinner.stop("Channels opened.");
+				outro(format!(
+					"Parachains {} and {} can now exchange XCM messages.",
+					self.para_a, self.para_b
+				))?;
+			},
+			Err(e) => {
+				spinner.error(format!("{e}"));
+				outro_cancel("Unable to open the HRMP channels.")?;
+			},
+		}
+		Ok(())
+	}
+}
@@ -0,0 +1,274 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use super::signer;
+use crate::{
+	dry_run::{dry_run, report, Effect},
+	prompt,
+	style::{style, Theme},
+};
+use anyhow::anyhow;
+use clap::Args;
+use cliclack::{clear_screen, intro, log, outro, outro_cancel, set_theme};
+use pop_parachains::{
+	construct_call, construct_sudo_call, metadata, pallets, submit_call, submit_sudo_call, Call,
+	Pallet,
+};
+use url::Url;
+
+#[derive(Args)]
+pub struct CallParachainCommand {
+	#[command(flatten)]
+	call: CallParachainArgs,
+	/// Dispatch the call using the sudo account of the chain, as `Sudo::sudo`.
+	#[arg(long)]
+	sudo: bool,
+	/// Dispatch the call using the sudo account of the chain without checking its weight, as
+	/// `Sudo::sudo_unchecked_weight`, e.g. for `System::set_code`. Implies `--sudo`.
+	#[arg(long)]
+	unchecked_weight: bool,
+}
+
+impl CallParachainCommand {
+	pub(crate) async fn execute(&self) -> anyhow::Result<()> {
+		clear_screen()?;
+		intro(format!("{}: Call a parachain", style(" Pop CLI ").black().on_magenta()))?;
+		set_theme(Theme);
+		self.call
+			.execute(self.sudo || self.unchecked_weight, self.unchecked_weight)
+			.await
+	}
+}
+
+/// The call submitted to a parachain, shared by `pop call parachain` and `pop call sudo`.
+#[derive(Args)]
+pub(crate) struct CallParachainArgs {
+	/// Websocket endpoint of the chain.
+	#[arg(long, value_parser, default_value = "ws://127.0.0.1:9944")]
+	url: Url,
+	/// The pallet of the call, e.g. `Balances`. Selected among those of the chain if not provided.
+	#[arg(long)]
+	pallet: Option<String>,
+	/// The call dispatched, e.g. `transfer_keep_alive`. Selected among those of the pallet if not
+	/// provided.
+	#[arg(long)]
+	call: Option<String>,
+	/// The arguments of the call, one per parameter, e.g. `--args 5GrwvaEF... 1000000000000`.
+	/// Values use the syntax of scale-value, such as `"text"`, `(1, 2)` or `Id(5GrwvaEF...)`.
+	/// Prompted for if not provided.
+	#[arg(long, num_args = 1..)]
+	args: Vec<String>,
+	/// Secret key URI for the account signing the extrinsic, which must be the sudo account of
+	/// the chain when dispatched using sudo, as `//Alice` on networks launched by `pop up parachain`.
+	///
+	/// e.g.
+	/// - for a dev account "//Alice"
+	/// - with a password "//Alice///SECRET_PASSWORD"
+	#[arg(long, short, default_value = "//Alice")]
+	suri: String,
+	/// The name of a key within the local keystore signing the extrinsic, instead of `--suri`.
+	#[arg(long)]
+	account: Option<String>,
+}
+
+impl CallParachainArgs {
+	/// Submits the call, dispatched using the sudo account of the chain if `sudo`.
+	///
+	/// # Arguments
+	///
+	/// * `sudo` - whether the call is dispatched using the sudo account of the chain
+	/// * `unchecked_weight` - whether the weight of the call dispatched using sudo is not checked
+	pub(super) async fn execute(&self, sudo: bool, unchecked_weight: bool) -> anyhow::Result<()> {
+		let spinner = crate::progress::spinner();
+		spinner.start(format!("Connecting to {}...", self.url));
+		let metadata = match metadata(&self.url).await {
+			Ok(metadata) => metadata,
+			Err(e) => {
+				spinner.error(format!("{e}"));
+				outro_cancel("Unable to read the metadata of the chain.")?;
+				return Ok(());
+			},
+		};
+		spinner.stop(format!("Connected to {}.", self.url));
+		let pallets = pallets(&metadata);
+		let call = match select_pallet(&pallets, self.pallet.as_deref())
+			.and_then(|pallet| select_call(pallet, self.call.as_deref()))
+		{
+			Ok(call) => call,
+			Err(e) => {
+				outro_cancel(format!("{e}"))?;
+				return Ok(());
+			},
+		};
+		let args = match self.args.is_empty() && !call.params.is_empty() {
+			true => prompt_args(call)?,
+			false => self.args.clone(),
+		};
+		let payload = match construct_call(&metadata, &call.pallet, &call.name, &args) {
+			Ok(payload) => payload,
+			Err(e) => {
+				outro_cancel(format!("{e}"))?;
+				return Ok(());
+			},
+		};
+		let payload = match sudo {
+			true => construct_sudo_call(payload, unchecked_weight),
+			false => payload,
+		};
+		let display = describe(call, &args, sudo, unchecked_weight);
+		if dry_run() {
+			return report(&[Effect::Submit { chain: self.url.to_string(), call: display }]);
+		}
+		let suri = signer(&self.suri, self.account.as_deref())?;
+		if !prompt::confirm(format!("Would you like to submit {display}?"), true)? {
+			outro_cancel("Nothing submitted.")?;
+			return Ok(());
+		}
+
+		spinner.start(format!("Submitting {display}..."));
+		let submitted = match sudo {
+			true => submit_sudo_call(&self.url, &suri, &payload).await,
+			false => submit_call(&self.url, &suri, &payload).await,
+		};
+		match submitted {
+			Ok(submitted) => {
+				spinner.stop(format!("Extrinsic {} finalized.", submitted.hash));
+				log::info(format!("Events:\n{}", submitted.events.join("\n")))?;
+				outro(format!("{display} dispatched."))?;
+			},
+			Err(e) => {
+				spinner.error(format!("{e}"));
+				outro_cancel("Unable to submit the extrinsic.")?;
+			},
+		}
+		Ok(())
+	}
+}
+
+/// The pallet named `name`, otherwise selected among `pallets`.
+///
+/// # Arguments
+///
+/// * `pallets` - the pallets of the chain
+/// * `name` - the name of the pallet, if provided
+fn select_pallet<'a>(pallets: &'a [Pallet], name: Option<&str>) -> anyhow::Result<&'a Pallet> {
+	if let Some(name) = name {
+		return pallets
+			.iter()
+			.find(|p| matches(&p.name, name))
+			.ok_or(anyhow!("the chain has no pallet named `{name}` exposing calls"));
+	}
+	prompt::ensure_interactive("--pallet")?;
+	let mut select = cliclack::select("Select the pallet of the call:");
+	for (i, pallet) in pallets.iter().enumerate() {
+		select = select.item(i, &pallet.name, &pallet.docs);
+	}
+	Ok(&pallets[select.interact()?])
+}
+
+/// The call of `pallet` named `name`, otherwise selected among those of the pallet.
+///
+/// # Arguments
+///
+/// * `pallet` - the pallet of the call
+/// * `name` - the name of the call, if provided
+fn select_call<'a>(pallet: &'a Pallet, name: Option<&str>) -> anyhow::Result<&'a Call> {
+	if let Some(name) = name {
+		return pallet
+			.calls
+			.iter()
+			.find(|c| matches(&c.name, name))
+			.ok_or(anyhow!("the {} pallet has no call named `{name}`", pallet.name));
+	}
+	prompt::ensure_interactive("--call")?;
+	let mut select = cliclack::select(format!("Select the call of the {} pallet:", pallet.name));
+	for (i, call) in pallet.calls.iter().enumerate() {
+		select = select.item(i, &call.name, &call.docs);
+	}
+	Ok(&pallet.calls[select.interact()?])
+}
+
+/// Prompts for the value of each parameter of `call`.
+fn prompt_args(call: &Call) -> anyhow::Result<Vec<String>> {
+	prompt::ensure_interactive("--args")?;
+	call.params
+		.iter()
+		.map(|param| {
+			Ok(cliclack::input(format!(
+				"Enter the value of `{}` ({}):",
+				param.name, param.type_name
+			))
+			.interact()?)
+		})
+		.collect()
+}
+
+/// Describes `call` with its `args`, as dispatched using sudo if `sudo`.
+///
+/// # Arguments
+///
+/// * `call` - the call
+/// * `args` - the arguments of the call
+/// * `sudo` - whether the call is dispatched using the sudo account of the chain
+/// * `unchecked_weight` - whether the weight of the call dispatched using sudo is not checked
+fn describe(call: &Call, args: &[String], sudo: bool, unchecked_weight: bool) -> String {
+	let display = format!("{}.{}({})", call.pallet, call.name, args.join(", "));
+	match (sudo, unchecked_weight) {
+		(false, _) => display,
+		(true, false) => format!("Sudo.sudo({display})"),
+		(true, true) => format!("Sudo.sudo_unchecked_weight({display})"),
+	}
+}
+
+/// Whether `name` matches `query`, regardless of case or of the naming convention, so that
+/// `transferKeepAlive`, as shown by polkadot-js, matches `transfer_keep_alive`.
+fn matches(name: &str, query: &str) -> bool {
+	let normalize = |s: &str| s.replace('_', "").to_lowercase();
+	normalize(name) == normalize(query)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn matches_works() {
+		assert!(matches("transfer_keep_alive", "transfer_keep_alive"));
+		assert!(matches("transfer_keep_alive", "transferKeepAlive"));
+		assert!(matches("Balances", "balances"));
+		assert!(!matches("transfer_keep_alive", "transfer_all"));
+	}
+
+	#[test]
+	fn select_works() -> anyhow::Result<()> {
+		let call = Call {
+			pallet: "Balances".into(),
+			name: "transfer_keep_alive".into(),
+			docs: String::new(),
+			params: Vec::new(),
+		};
+		let pallets =
+			[Pallet { name: "Balances".into(), docs: String::new(), calls: vec![call.clone()] }];
+		let pallet = select_pallet(&pallets, Some("balances"))?;
+		assert_eq!(select_call(pallet, Some("transferKeepAlive"))?, &call);
+		assert!(select_pallet(&pallets, Some("Assets")).is_err());
+		assert!(select_call(pallet, Some("burn")).is_err());
+		Ok(())
+	}
+
+	#[test]
+	fn describe_works() {
+		let call = Call {
+			pallet: "System".into(),
+			name: "remark".into(),
+			docs: String::new(),
+			params: Vec::new(),
+		};
+		let args = ["0x00".to_string()];
+		assert_eq!(describe(&call, &args, false, false), "System.remark(0x00)");
+		assert_eq!(describe(&call, &args, true, false), "Sudo.sudo(System.remark(0x00))");
+		assert_eq!(
+			describe(&call, &args, true, true),
+			"Sudo.sudo_unchecked_weight(System.remark(0x00))"
+		);
+	}
+}
@@ -0,0 +1,54 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::{
+	dry_run::{dry_run, report, Effect},
+	style::{style, Theme},
+};
+use clap::Args;
+use cliclack::{clear_screen, intro, log, outro, outro_cancel, set_theme};
+use pop_parachains::reserve_para_id;
+use url::Url;
+
+#[derive(Args)]
+pub struct ReserveParaIdCommand {
+	/// Websocket endpoint of the relay chain, e.g. wss://paseo.rpc.amforc.com
+	#[arg(long, value_parser)]
+	relay_url: Url,
+	/// Secret key URI for the account reserving the para id, which pays the registrar deposit.
+	///
+	/// e.g.
+	/// - for a dev account "//Alice"
+	/// - with a password "//Alice///SECRET_PASSWORD"
+	#[arg(long, short)]
+	suri: String,
+}
+
+impl ReserveParaIdCommand {
+	pub(crate) async fn execute(&self) -> anyhow::Result<()> {
+		clear_screen()?;
+		intro(format!("{}: Reserve a para id", style(" Pop CLI ").black().on_magenta()))?;
+		set_theme(Theme);
+		if dry_run() {
+			let call = "Registrar.reserve()".to_string();
+			return report(&[Effect::Submit { chain: self.relay_url.to_string(), call }]);
+		}
+
+		let spinner = crate::progress::spinner();
+		spinner.start(format!("Reserving a para id on {}...", self.relay_url));
+		match reserve_para_id(&self.relay_url, &self.suri).await {
+			Ok(para_id) => {
+				spinner.stop(format!("Para id {para_id} reserved."));
+				log::info(format!(
+					"Use `--para-id {para_id}` when generating the chain spec of your parachain \
+					 (see `pop build spec`), before registering it."
+				))?;
+				outro(format!("Para id: {para_id}"))?;
+			},
+			Err(e) => {
+				spinner.error(format!("{e}"));
+				outro_cancel("Unable to reserve a para id.")?;
+			},
+		}
+		Ok(())
+	}
+}
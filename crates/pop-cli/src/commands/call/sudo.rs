@@ -0,0 +1,25 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use super::parachain::CallParachainArgs;
+use crate::style::{style, Theme};
+use clap::Args;
+use cliclack::{clear_screen, intro, set_theme};
+
+#[derive(Args)]
+pub struct SudoCommand {
+	#[command(flatten)]
+	call: CallParachainArgs,
+	/// Dispatch the call without checking its weight, as `Sudo::sudo_unchecked_weight`, e.g. for
+	/// `System::set_code`.
+	#[arg(long)]
+	unchecked_weight: bool,
+}
+
+impl SudoCommand {
+	pub(crate) async fn execute(&self) -> anyhow::Result<()> {
+		clear_screen()?;
+		intro(format!("{}: Call a parachain using sudo", style(" Pop CLI ").black().on_magenta()))?;
+		set_theme(Theme);
+		self.call.execute(true, self.unchecked_weight).await
+	}
+}
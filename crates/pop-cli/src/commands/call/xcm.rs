@@ -0,0 +1,263 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use super::signer;
+use crate::{
+	dry_run::{dry_run, report, Effect},
+	prompt,
+	style::{style, Theme},
+};
+use anyhow::anyhow;
+use clap::{Args, Subcommand};
+use cliclack::{clear_screen, intro, log, outro, outro_cancel, set_theme};
+use pop_parachains::{
+	account_address, construct_xcm_transfer, metadata, submit_xcm_transfer, wait_for_delivery,
+	Chain, NetworkEndpoints, Token, TransferKind, XcmTransfer,
+};
+use std::time::Duration;
+use url::Url;
+
+#[derive(Args)]
+#[command(args_conflicts_with_subcommands = true)]
+pub(crate) struct XcmArgs {
+	#[command(subcommand)]
+	pub command: XcmCommands,
+}
+
+#[derive(Subcommand)]
+pub(crate) enum XcmCommands {
+	/// Transfer an asset held in reserve by its reserve chain, minting a derivative on the
+	/// destination
+	ReserveTransfer(XcmTransferCommand),
+	/// Teleport an asset between chains which trust each other, burning it on the origin and
+	/// minting it on the destination
+	Teleport(XcmTransferCommand),
+}
+
+impl XcmCommands {
+	pub(crate) async fn execute(&self) -> anyhow::Result<()> {
+		match self {
+			XcmCommands::ReserveTransfer(cmd) => cmd.execute(TransferKind::ReserveTransfer).await,
+			XcmCommands::Teleport(cmd) => cmd.execute(TransferKind::Teleport).await,
+		}
+	}
+}
+
+#[derive(Args)]
+pub(crate) struct XcmTransferCommand {
+	/// The chain the asset is transferred from: `relay` or a para id.
+	#[arg(long, value_parser = parse_chain)]
+	from: Chain,
+	/// The chain the asset is transferred to: `relay` or a para id.
+	#[arg(long, value_parser = parse_chain)]
+	to: Chain,
+	/// The asset transferred, as the chain whose native token it is: `native` for the token of
+	/// the origin, `relay` or a para id.
+	#[arg(long, default_value = "native")]
+	asset: String,
+	/// The amount transferred, in whole tokens of the asset, e.g. `1.5`.
+	#[arg(long)]
+	amount: String,
+	/// The SS58 address of the account receiving the asset. [default: the account sending it]
+	#[arg(long)]
+	beneficiary: Option<String>,
+	/// Websocket endpoint of the origin chain. [default: its configured endpoint, otherwise that
+	/// of the local network launched]
+	#[arg(long, value_parser)]
+	from_url: Option<Url>,
+	/// Websocket endpoint of the destination chain. [default: its configured endpoint, otherwise
+	/// that of the local network launched]
+	#[arg(long, value_parser)]
+	to_url: Option<Url>,
+	/// Secret key URI for the account sending the asset.
+	///
+	/// e.g.
+	/// - for a dev account "//Alice"
+	/// - with a password "//Alice///SECRET_PASSWORD"
+	#[arg(long, short, default_value = "//Alice")]
+	suri: String,
+	/// The name of a key within the local keystore sending the asset, instead of `--suri`.
+	#[arg(long)]
+	account: Option<String>,
+	/// How long to wait for the message to be processed by the destination, in seconds.
+	#[arg(long, default_value_t = 120)]
+	timeout: u64,
+}
+
+impl XcmTransferCommand {
+	pub(crate) async fn execute(&self, kind: TransferKind) -> anyhow::Result<()> {
+		clear_screen()?;
+		intro(format!("{}: Transfer an asset via XCM", style(" Pop CLI ").black().on_magenta()))?;
+		set_theme(Theme);
+
+		let (asset, from_url, to_url, asset_url) = match self.resolve() {
+			Ok(resolved) => resolved,
+			Err(e) => {
+				outro_cancel(format!("{e}"))?;
+				return Ok(());
+			},
+		};
+
+		let spinner = crate::progress::spinner();
+		spinner.start(format!("Connecting to {from_url}..."));
+		let (metadata, token) =
+			match futures::try_join!(metadata(&from_url), Token::fetch(&asset_url)) {
+				Ok(result) => result,
+				Err(e) => {
+					spinner.error(format!("{e}"));
+					outro_cancel("Unable to connect to the chains.")?;
+					return Ok(());
+				},
+			};
+		spinner.stop(format!("Connected to {from_url}."));
+		let suri = signer(&self.suri, self.account.as_deref())?;
+		let transfer = match token.parse(&self.amount).and_then(|amount| {
+			let beneficiary = match &self.beneficiary {
+				Some(beneficiary) => beneficiary.clone(),
+				None => account_address(&suri)?,
+			};
+			Ok(XcmTransfer { kind, from: self.from, to: self.to, asset, amount, beneficiary })
+		}) {
+			Ok(transfer) => transfer,
+			Err(e) => {
+				outro_cancel(format!("{e}"))?;
+				return Ok(());
+			},
+		};
+		let call = match construct_xcm_transfer(&metadata, &transfer) {
+			Ok(call) => call,
+			Err(e) => {
+				outro_cancel(format!("{e}"))?;
+				return Ok(());
+			},
+		};
+		let display = format!(
+			"{} of {} from {} to {}",
+			kind,
+			token.format(transfer.amount),
+			self.from,
+			self.to
+		);
+		if dry_run() {
+			return report(&[Effect::Submit {
+				chain: from_url.to_string(),
+				call: format!(
+					"{}.{}({})",
+					call.pallet_name(),
+					call.call_name(),
+					transfer.describe()
+				),
+			}]);
+		}
+		if !prompt::confirm(format!("Would you like to submit the {display}?"), true)? {
+			outro_cancel("Nothing submitted.")?;
+			return Ok(());
+		}
+
+		// The destination is watched from before the submission, as the message may be processed
+		// before the extrinsic is finalized on the origin.
+		spinner.start(format!("Submitting the {display}..."));
+		let delivery = wait_for_delivery(&to_url, self.from, Duration::from_secs(self.timeout));
+		let submission = async {
+			let submitted = submit_xcm_transfer(&from_url, &suri, &call).await?;
+			spinner.set_message(format!(
+				"Extrinsic {} finalized, waiting for the message to be processed by {}...",
+				submitted.hash, self.to
+			));
+			Ok::<_, pop_parachains::Error>(submitted)
+		};
+		match futures::try_join!(submission, delivery) {
+			Ok((submitted, delivery)) => {
+				spinner.stop(format!(
+					"Message processed by {} in block #{}.",
+					self.to, delivery.block
+				));
+				log::info(format!(
+					"Events on {}:\n{}\nEvents on {}:\n{}",
+					self.from,
+					submitted.events.join("\n"),
+					self.to,
+					delivery.events.join("\n")
+				))?;
+				outro(format!("The {display} was delivered."))?;
+			},
+			Err(e) => {
+				spinner.error(format!("{e}"));
+				outro_cancel("Unable to transfer the asset.")?;
+			},
+		}
+		Ok(())
+	}
+
+	/// Resolves the chain of the asset, along with the endpoints of the origin, the destination
+	/// and the chain of the asset, whose token determines how amounts are parsed.
+	fn resolve(&self) -> anyhow::Result<(Chain, Url, Url, Url)> {
+		if self.from == self.to {
+			return Err(anyhow!("the asset must be transferred to another chain"));
+		}
+		let asset = match self.asset.trim() {
+			"native" => self.from,
+			asset => parse_chain(asset).map_err(|e| anyhow!(e))?,
+		};
+		let network = NetworkEndpoints::load(&crate::network_endpoints_path()?)?;
+		let from = endpoint(self.from, self.from_url.as_ref(), network.as_ref())?;
+		let to = endpoint(self.to, self.to_url.as_ref(), network.as_ref())?;
+		let asset_url = match asset {
+			asset if asset == self.from => from.clone(),
+			asset if asset == self.to => to.clone(),
+			asset => endpoint(asset, None, network.as_ref())?,
+		};
+		Ok((asset, from, to, asset_url))
+	}
+}
+
+/// The endpoint of `chain`: the one provided, otherwise the one configured as
+/// `endpoints.<chain>`, otherwise that of the local network launched by `pop up parachain`.
+///
+/// # Arguments
+///
+/// * `chain` - the chain
+/// * `url` - the endpoint provided, if any
+/// * `network` - the endpoints of the local network launched, if any
+fn endpoint(
+	chain: Chain,
+	url: Option<&Url>,
+	network: Option<&NetworkEndpoints>,
+) -> anyhow::Result<Url> {
+	if let Some(url) = url {
+		return Ok(url.clone());
+	}
+	if let Some(url) = crate::config().endpoint(&chain.to_string()) {
+		return Ok(url);
+	}
+	network.and_then(|n| n.endpoint(chain)).cloned().ok_or(anyhow!(
+		"no endpoint is known for chain `{chain}`: launch a local network with `pop up parachain`, \
+		 configure one with `pop config set endpoints.{chain} <url>`, or provide one"
+	))
+}
+
+fn parse_chain(chain: &str) -> Result<Chain, String> {
+	chain.parse().map_err(|e: pop_parachains::Error| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::collections::BTreeMap;
+
+	#[test]
+	fn endpoint_works() -> anyhow::Result<()> {
+		let provided = Url::parse("ws://127.0.0.1:8000")?;
+		let network = NetworkEndpoints {
+			relay: Some(Url::parse("ws://127.0.0.1:9944")?),
+			parachains: BTreeMap::from([(1000, Url::parse("ws://127.0.0.1:9946")?)]),
+		};
+		assert_eq!(endpoint(Chain::Para(1000), Some(&provided), Some(&network))?, provided);
+		assert_eq!(
+			endpoint(Chain::Para(1000), None, Some(&network))?.as_str(),
+			"ws://127.0.0.1:9946"
+		);
+		assert!(endpoint(Chain::Para(2000), None, Some(&network)).is_err());
+		assert!(endpoint(Chain::Relay, None, None).is_err());
+		Ok(())
+	}
+}
@@ -0,0 +1,202 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::{
+	dry_run::dry_run,
+	prompt,
+	style::{style, Theme},
+};
+use clap::Args;
+use cliclack::{clear_screen, intro, log, outro, outro_cancel, set_theme};
+use std::{
+	fs,
+	path::{Path, PathBuf},
+};
+
+/// The prefix of the directories holding the chain data of local networks, within the temporary
+/// directory.
+const NETWORK_DIR_PREFIX: &str = "zombie-";
+
+#[derive(Args)]
+/// Remove the files created by pop
+pub(crate) struct CleanArgs {
+	/// Remove the cached binaries, such as those of the relay chain and contracts node, along
+	/// with the chain specs they generated and the mirrors of templates, which are sourced again
+	/// when next required. Anything else within the cache is kept.
+	#[arg(long)]
+	cache: bool,
+	/// Remove the audit log of the verification of the binaries sourced, which is otherwise kept.
+	#[arg(long)]
+	audit_log: bool,
+	/// Remove the state persisted by local contracts nodes, which is otherwise kept.
+	#[cfg(feature = "contract")]
+	#[arg(long)]
+	node_state: bool,
+	/// Remove the chain data of the local networks launched.
+	#[arg(long)]
+	network: bool,
+	/// Remove the build artifacts of the project, such as its exported chain specs.
+	#[arg(long)]
+	artifacts: bool,
+	/// Remove the cached binaries, the chain data of local networks and the build artifacts of
+	/// the project. The default when none of them is specified.
+	#[arg(long, conflicts_with_all = ["cache", "network", "artifacts"])]
+	all: bool,
+	/// Directory path of the project, [default: the current project]
+	#[arg(short = 'p', long)]
+	path: Option<PathBuf>,
+}
+
+impl CleanArgs {
+	pub(crate) fn execute(&self) -> anyhow::Result<()> {
+		clear_screen()?;
+		intro(format!("{}: Clean up", style(" Pop CLI ").black().on_magenta()))?;
+		set_theme(Theme);
+
+		let all = self.all || !(self.cache || self.network || self.artifacts || self.kept());
+		let mut targets = Vec::new();
+		if all || self.cache {
+			targets.extend(pop_common::cache::sourced(&crate::cache()?)?);
+		}
+		// The audit log and the state of nodes are only removed when requested explicitly.
+		if self.audit_log {
+			targets.push(crate::cache()?.join(pop_common::verification::AUDIT_FILE));
+		}
+		#[cfg(feature = "contract")]
+		if self.node_state {
+			targets.extend(pop_contracts::contracts_node_states(&crate::cache()?)?);
+		}
+		if all || self.network {
+			targets.extend(network_dirs(&std::env::temp_dir())?);
+		}
+		if all || self.artifacts {
+			let path = crate::project_path(self.path.as_deref());
+			targets.push(path.join("target").join("pop"));
+		}
+		let targets: Vec<_> = targets
+			.into_iter()
+			.filter(|t| t.exists())
+			.map(|t| {
+				let size = size(&t);
+				(t, size)
+			})
+			.collect();
+		if targets.is_empty() {
+			outro("Nothing to clean.")?;
+			return Ok(());
+		}
+
+		let total: u64 = targets.iter().map(|(_, size)| size).sum();
+		log::info(format!(
+			"{}\ntotal: {}",
+			targets
+				.iter()
+				.map(|(t, size)| format!("{} ({})", t.display(), format_size(*size)))
+				.collect::<Vec<_>>()
+				.join("\n"),
+			format_size(total)
+		))?;
+		if dry_run() {
+			outro("Dry run, nothing removed.")?;
+			return Ok(());
+		}
+		if !prompt::confirm(
+			format!("Would you like to remove the above, freeing {}?", format_size(total)),
+			true,
+		)? {
+			outro_cancel("Nothing removed.")?;
+			return Ok(());
+		}
+		for (target, _) in &targets {
+			match fs::symlink_metadata(target)?.is_dir() {
+				true => fs::remove_dir_all(target)?,
+				false => fs::remove_file(target)?,
+			}
+		}
+		outro(format!("Removed {}.", format_size(total)))?;
+		Ok(())
+	}
+
+	/// Whether anything kept unless requested explicitly, such as the audit log, is to be removed.
+	fn kept(&self) -> bool {
+		#[cfg(feature = "contract")]
+		if self.node_state {
+			return true;
+		}
+		self.audit_log
+	}
+}
+
+/// The directories holding the chain data of local networks within `temp_dir`.
+fn network_dirs(temp_dir: &Path) -> anyhow::Result<Vec<PathBuf>> {
+	let mut dirs: Vec<_> = fs::read_dir(temp_dir)?
+		.flatten()
+		.filter(|e| e.file_name().to_string_lossy().starts_with(NETWORK_DIR_PREFIX))
+		.map(|e| e.path())
+		.filter(|p| p.is_dir())
+		.collect();
+	dirs.sort();
+	Ok(dirs)
+}
+
+/// The total size of the files within `path`, in bytes.
+pub(crate) fn size(path: &Path) -> u64 {
+	let Ok(metadata) = fs::symlink_metadata(path) else { return 0 };
+	if !metadata.is_dir() {
+		return metadata.len();
+	}
+	fs::read_dir(path)
+		.map(|entries| entries.flatten().map(|e| size(&e.path())).sum())
+		.unwrap_or_default()
+}
+
+/// Formats a size in bytes for display.
+pub(crate) fn format_size(bytes: u64) -> String {
+	const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+	if bytes < 1024 {
+		return format!("{bytes} B");
+	}
+	let mut size = bytes as f64 / 1024.0;
+	let mut unit = 0;
+	while size >= 1024.0 && unit < UNITS.len() - 1 {
+		size /= 1024.0;
+		unit += 1;
+	}
+	format!("{size:.1} {}", UNITS[unit])
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+
+	#[test]
+	fn test_network_dirs() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let path = temp_dir.path();
+		for dir in ["zombie-b", "zombie-a", "other"] {
+			fs::create_dir(path.join(dir))?;
+		}
+		fs::write(path.join("zombie-file"), "")?;
+		assert_eq!(network_dirs(path)?, [path.join("zombie-a"), path.join("zombie-b")]);
+		Ok(())
+	}
+
+	#[test]
+	fn test_size() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let path = temp_dir.path();
+		fs::create_dir(path.join("nested"))?;
+		fs::write(path.join("file"), [0u8; 100])?;
+		fs::write(path.join("nested").join("file"), [0u8; 24])?;
+		assert_eq!(size(path), 124);
+		assert_eq!(size(&path.join("missing")), 0);
+		Ok(())
+	}
+
+	#[test]
+	fn test_format_size() {
+		assert_eq!(format_size(512), "512 B");
+		assert_eq!(format_size(1536), "1.5 KB");
+		assert_eq!(format_size(3 * 1024 * 1024 * 1024), "3.0 GB");
+	}
+}
@@ -0,0 +1,66 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::Cli;
+use clap::{Args, CommandFactory};
+use clap_complete::{generate, Shell};
+use std::io::{self, Write};
+
+/// The name of the binary, which completions are registered for.
+const BIN_NAME: &str = "pop";
+
+#[derive(Args)]
+pub(crate) struct CompletionsArgs {
+	/// The shell to generate the completions for.
+	shell: Shell,
+}
+
+impl CompletionsArgs {
+	/// Writes the completion script to stdout, so it can be sourced or saved by the shell, e.g.
+	/// `pop completions zsh > ~/.zfunc/_pop`.
+	pub(crate) fn execute(&self) -> anyhow::Result<()> {
+		completions(self.shell, &mut io::stdout())
+	}
+}
+
+/// Writes the completion script of `shell` to `buffer`. The script includes the subcommands and
+/// flags of every command, along with the values accepted by those with a fixed set, such as the
+/// template and provider names.
+///
+/// # Arguments
+///
+/// * `shell` - the shell to generate the completions for
+/// * `buffer` - where the completion script is written
+fn completions(shell: Shell, buffer: &mut impl Write) -> anyhow::Result<()> {
+	generate(shell, &mut Cli::command(), BIN_NAME, buffer);
+	buffer.flush()?;
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_completions() -> anyhow::Result<()> {
+		for shell in [Shell::Bash, Shell::Zsh, Shell::Fish, Shell::PowerShell] {
+			let mut buffer = Vec::new();
+			completions(shell, &mut buffer)?;
+			let script = String::from_utf8(buffer)?;
+			assert!(script.contains(BIN_NAME), "{shell}");
+			assert!(script.contains("completions"), "{shell}");
+		}
+		Ok(())
+	}
+
+	#[test]
+	#[cfg(feature = "parachain")]
+	fn test_completions_include_templates_and_providers() -> anyhow::Result<()> {
+		let mut buffer = Vec::new();
+		completions(Shell::Fish, &mut buffer)?;
+		let script = String::from_utf8(buffer)?;
+		for value in ["standard", "cpt", "parity"] {
+			assert!(script.contains(value), "{value}");
+		}
+		Ok(())
+	}
+}
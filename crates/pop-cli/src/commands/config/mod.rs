@@ -0,0 +1,160 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::style::{style, Theme};
+use clap::{Args, Subcommand};
+use cliclack::{clear_screen, intro, log, outro, outro_cancel, set_theme};
+use pop_common::Config;
+
+#[derive(Args)]
+#[command(args_conflicts_with_subcommands = true)]
+pub(crate) struct ConfigArgs {
+	#[command(subcommand)]
+	pub command: ConfigCommands,
+}
+
+#[derive(Subcommand)]
+pub(crate) enum ConfigCommands {
+	/// Show the value of a configuration key, or of every key set when none is provided.
+	Get(GetConfigCommand),
+	/// Set the value of a configuration key, e.g. `pop config set endpoints.paseo <url>`.
+	Set(SetConfigCommand),
+	/// Remove a configuration key, so that the default applies again.
+	Unset(UnsetConfigCommand),
+}
+
+#[derive(Args)]
+pub(crate) struct GetConfigCommand {
+	/// The configuration key, e.g. `provider` or `endpoints.paseo`.
+	key: Option<String>,
+}
+
+impl GetConfigCommand {
+	pub(crate) fn execute(&self) -> anyhow::Result<()> {
+		clear_screen()?;
+		intro(format!("{}: Show the configuration", style(" Pop CLI ").black().on_magenta()))?;
+		set_theme(Theme);
+
+		let config = match Config::load() {
+			Ok(config) => config,
+			Err(e) => {
+				outro_cancel(format!("{e}"))?;
+				return Ok(());
+			},
+		};
+		let Some(key) = &self.key else {
+			let entries = config.entries();
+			if entries.is_empty() {
+				let keys: Vec<_> = Config::keys()
+					.map(|(key, description)| format!("{key}: {description}"))
+					.collect();
+				log::info(format!("The configuration keys are:\n{}", keys.join("\n")))?;
+				outro("No configuration set, add some with `pop config set <key> <value>`.")?;
+				return Ok(());
+			}
+			for (key, value) in &entries {
+				log::info(format!("{key} = {value}"))?;
+			}
+			outro(format!("Configuration read from {}.", Config::path()?.display()))?;
+			return Ok(());
+		};
+		match config.get(key) {
+			Ok(Some(value)) => outro(format!("{key} = {value}"))?,
+			Ok(None) => outro(format!("`{key}` is not set, so the default applies."))?,
+			Err(e) => outro_cancel(format!("{e}"))?,
+		}
+		Ok(())
+	}
+}
+
+#[derive(Args)]
+pub(crate) struct SetConfigCommand {
+	/// The configuration key, e.g. `provider` or `endpoints.paseo`.
+	key: String,
+	/// The value of the key.
+	value: String,
+}
+
+impl SetConfigCommand {
+	pub(crate) fn execute(&self) -> anyhow::Result<()> {
+		clear_screen()?;
+		intro(format!("{}: Set a configuration key", style(" Pop CLI ").black().on_magenta()))?;
+		set_theme(Theme);
+
+		if let Err(e) = validate(&self.key, &self.value) {
+			outro_cancel(format!("{e}"))?;
+			return Ok(());
+		}
+		let path = Config::path()?;
+		let result = Config::load_from(&path).and_then(|mut config| {
+			config.set(&self.key, &self.value)?;
+			config.save_to(&path)
+		});
+		match result {
+			Ok(()) => outro(format!("{} = {}", self.key, self.value))?,
+			Err(e) => outro_cancel(format!("{e}"))?,
+		}
+		Ok(())
+	}
+}
+
+#[derive(Args)]
+pub(crate) struct UnsetConfigCommand {
+	/// The configuration key, e.g. `provider` or `endpoints.paseo`.
+	key: String,
+}
+
+impl UnsetConfigCommand {
+	pub(crate) fn execute(&self) -> anyhow::Result<()> {
+		clear_screen()?;
+		intro(format!("{}: Remove a configuration key", style(" Pop CLI ").black().on_magenta()))?;
+		set_theme(Theme);
+
+		let path = Config::path()?;
+		let result = Config::load_from(&path).and_then(|mut config| {
+			let removed = config.unset(&self.key)?;
+			config.save_to(&path)?;
+			Ok(removed)
+		});
+		match result {
+			Ok(true) => outro(format!("`{}` removed, so the default applies.", self.key))?,
+			Ok(false) => outro(format!("`{}` is not set.", self.key))?,
+			Err(e) => outro_cancel(format!("{e}"))?,
+		}
+		Ok(())
+	}
+}
+
+/// Validates the values of keys only known to the commands using them, such as the template
+/// provider.
+///
+/// # Arguments
+///
+/// * `key` - the configuration key
+/// * `value` - the value of the key
+fn validate(key: &str, value: &str) -> anyhow::Result<()> {
+	#[cfg(feature = "parachain")]
+	if key == "provider" {
+		use std::str::FromStr;
+		if pop_parachains::Provider::from_str(value).is_err() {
+			let providers: Vec<_> =
+				pop_parachains::Provider::providers().iter().map(|p| p.as_ref()).collect();
+			anyhow::bail!("unknown provider `{value}`, expected one of: {}", providers.join(", "));
+		}
+	}
+	#[cfg(not(feature = "parachain"))]
+	let _ = (key, value);
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	#[cfg(feature = "parachain")]
+	fn test_validate() {
+		assert!(validate("provider", "parity").is_ok());
+		assert!(validate("provider", "unknown").is_err());
+		assert!(validate("cache", "/tmp/pop").is_ok());
+	}
+}
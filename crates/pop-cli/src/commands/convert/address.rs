@@ -0,0 +1,103 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use std::str::FromStr;
+
+use clap::{
+	builder::{PossibleValue, PossibleValuesParser, TypedValueParser},
+	Args,
+};
+use cliclack::{clear_screen, intro, log, outro, outro_cancel, set_theme};
+use console::style;
+use strum::VariantArray;
+
+use crate::style::Theme;
+use pop_contracts::{Address, AddressFormat, AddressMapping, DEFAULT_SS58_PREFIX};
+
+#[derive(Args)]
+pub struct ConvertAddressCommand {
+	#[arg(
+		help = "The address to convert: an SS58 encoded account, or a 32 or 20 byte hex encoded address"
+	)]
+	pub(crate) address: String,
+	#[arg(
+		long,
+		help = "Only output the address in this representation, [default: all representations]",
+		value_parser = crate::enum_variants!(AddressFormat)
+	)]
+	pub(crate) to: Option<AddressFormat>,
+	#[arg(long, help = "The SS58 prefix of the chain", default_value_t = DEFAULT_SS58_PREFIX)]
+	pub(crate) prefix: u16,
+	#[arg(
+		long,
+		help = "How accounts are mapped to and from H160 addresses",
+		default_value = "revive",
+		value_parser = crate::enum_variants!(AddressMapping)
+	)]
+	pub(crate) mapping: AddressMapping,
+}
+
+impl ConvertAddressCommand {
+	pub(crate) fn execute(&self) -> anyhow::Result<()> {
+		clear_screen()?;
+		intro(format!("{}: Convert an address", style(" Pop CLI ").black().on_magenta()))?;
+		set_theme(Theme);
+
+		let address = match Address::parse(&self.address) {
+			Ok(address) => address,
+			Err(e) => {
+				outro_cancel(format!("{e}"))?;
+				return Ok(());
+			},
+		};
+		let formats = match self.to {
+			Some(format) => vec![format],
+			None => AddressFormat::VARIANTS.to_vec(),
+		};
+		for line in convert(&address, &formats, self.prefix, self.mapping) {
+			log::info(line)?;
+		}
+		outro("Address converted.")?;
+		Ok(())
+	}
+}
+
+/// The address in each of the requested `formats`, labelled with the format.
+fn convert(
+	address: &Address,
+	formats: &[AddressFormat],
+	prefix: u16,
+	mapping: AddressMapping,
+) -> Vec<String> {
+	formats
+		.iter()
+		.map(|format| {
+			let label = match format {
+				AddressFormat::Ss58 => format!("SS58 (prefix {prefix})"),
+				AddressFormat::Hex => "Hex".into(),
+				AddressFormat::H160 => format!("H160 ({mapping} mapping)"),
+			};
+			format!("{label}: {}", address.convert(*format, prefix, mapping))
+		})
+		.collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+
+	#[test]
+	fn test_convert() -> Result<()> {
+		let alice = Address::parse("5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY")?;
+		let lines = convert(&alice, AddressFormat::VARIANTS, 0, AddressMapping::Frontier);
+		assert_eq!(
+			lines,
+			vec![
+				"SS58 (prefix 0): 15oF4uVJwmo4TdGW7VfQxNLavjCXviqxT9S1MgbjMNHr6Sp5",
+				"Hex: 0xd43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d",
+				"H160 (frontier mapping): 0xd43593c715fdd31c61141abd04a99fd6822c8558",
+			]
+		);
+		Ok(())
+	}
+}
@@ -0,0 +1,19 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use clap::{Args, Subcommand};
+
+pub(crate) mod address;
+
+#[derive(Args)]
+#[command(args_conflicts_with_subcommands = true)]
+pub(crate) struct ConvertArgs {
+	#[command(subcommand)]
+	pub command: ConvertCommands,
+}
+
+#[derive(Subcommand)]
+pub(crate) enum ConvertCommands {
+	/// Convert an address between its SS58, hex and H160 representations
+	#[clap(alias = "a")]
+	Address(address::ConvertAddressCommand),
+}
@@ -0,0 +1,302 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::{
+	commands::{
+		clean::{format_size, size},
+		install::{Os, Requirement},
+	},
+	progress::spinner,
+	style::{style, Theme},
+};
+use clap::Args;
+use cliclack::{clear_screen, intro, log, outro, outro_cancel, set_theme};
+use duct::cmd;
+use pop_common::{
+	cache::{cached_binaries, legacy_binaries},
+	platform::{Arch, Os as TargetOs},
+	Platform,
+};
+use std::{collections::BTreeMap, fs, time::Duration};
+
+/// The endpoint checked to determine whether templates and binaries can be sourced.
+const GITHUB: &str = "https://api.github.com";
+/// The size of the cache from which removing unused binaries is suggested.
+const LARGE_CACHE: u64 = 10 * 1024 * 1024 * 1024;
+
+#[derive(Args)]
+/// Diagnose the environment used for development
+pub(crate) struct DoctorArgs {}
+
+impl DoctorArgs {
+	pub(crate) async fn execute(&self) -> anyhow::Result<()> {
+		clear_screen()?;
+		set_theme(Theme);
+		intro(format!("{}: Diagnose the environment", style(" Pop CLI ").black().on_magenta()))?;
+
+		let spinner = spinner();
+		spinner.start("Checking the environment...");
+		let os = Os::detect();
+		let checks = vec![
+			toolchain(),
+			wasm(),
+			system_packages(os),
+			platform(),
+			docker(),
+			github().await,
+			cache(),
+			binaries(),
+		];
+		spinner.stop("Environment checked.");
+
+		for check in &checks {
+			check.render()?;
+		}
+		let count = |status| checks.iter().filter(|c| c.status == status).count();
+		let summary = format!(
+			"{} passed, {} warnings, {} failures",
+			count(Status::Pass),
+			count(Status::Warn),
+			count(Status::Fail)
+		);
+		match count(Status::Fail) {
+			0 => outro(format!("✅ {summary}."))?,
+			_ => outro_cancel(format!("🚫 {summary}. Follow the hints above to fix them."))?,
+		}
+		Ok(())
+	}
+}
+
+/// The outcome of a check.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Status {
+	Pass,
+	/// Something is missing or degraded, but only required by some commands.
+	Warn,
+	/// Something required by most commands is missing.
+	Fail,
+}
+
+/// A check of the environment, along with how to remedy it.
+#[derive(Debug, PartialEq)]
+struct Check {
+	/// What is checked.
+	name: &'static str,
+	/// The outcome of the check.
+	status: Status,
+	/// What was found.
+	detail: String,
+	/// How to remedy a warning or failure.
+	hint: Option<String>,
+}
+
+impl Check {
+	fn pass(name: &'static str, detail: impl Into<String>) -> Self {
+		Self { name, status: Status::Pass, detail: detail.into(), hint: None }
+	}
+
+	fn warn(name: &'static str, detail: impl Into<String>, hint: impl Into<String>) -> Self {
+		Self { name, status: Status::Warn, detail: detail.into(), hint: Some(hint.into()) }
+	}
+
+	fn fail(name: &'static str, detail: impl Into<String>, hint: impl Into<String>) -> Self {
+		Self { name, status: Status::Fail, detail: detail.into(), hint: Some(hint.into()) }
+	}
+
+	/// Reports the outcome of the check, along with its hint.
+	fn render(&self) -> anyhow::Result<()> {
+		let mut message = format!("{}: {}", self.name, self.detail);
+		if let Some(hint) = &self.hint {
+			message += &format!("\n{} {hint}", style("help:").cyan().bold());
+		}
+		match self.status {
+			Status::Pass => log::success(message)?,
+			Status::Warn => log::warning(message)?,
+			Status::Fail => log::error(message)?,
+		}
+		Ok(())
+	}
+}
+
+/// Checks the Rust toolchain is installed.
+fn toolchain() -> Check {
+	const NAME: &str = "Rust toolchain";
+	match (cmd!("rustc", "--version").read(), cmd!("cargo", "--version").read()) {
+		(Ok(rustc), Ok(_)) => Check::pass(NAME, rustc.trim()),
+		_ => Check::fail(NAME, "rustc or cargo not found", "Install it using `pop install`."),
+	}
+}
+
+/// Checks the target and component required to compile runtimes and contracts are installed.
+fn wasm() -> Check {
+	const NAME: &str = "Wasm target";
+	let missing: Vec<_> = [Requirement::WasmTarget, Requirement::RustSrc]
+		.into_iter()
+		.filter(|r| !r.installed())
+		.map(|r| r.to_string())
+		.collect();
+	match missing.is_empty() {
+		true => Check::pass(NAME, "wasm32-unknown-unknown and rust-src installed"),
+		false => Check::fail(
+			NAME,
+			format!("{} missing", missing.join(", ")),
+			"Install them using `pop install`.",
+		),
+	}
+}
+
+/// Checks the system packages required to compile nodes are installed.
+///
+/// # Arguments
+///
+/// * `os` - the platform, if supported
+fn system_packages(os: Option<Os>) -> Check {
+	const NAME: &str = "System packages";
+	let missing: Vec<_> = Requirement::all(os)
+		.filter(|r| !is_toolchain(*r) && !r.installed())
+		.map(|r| r.to_string())
+		.collect();
+	match (missing.is_empty(), os) {
+		(true, Some(os)) => Check::pass(NAME, format!("all installed on {os}")),
+		(true, None) => Check::warn(
+			NAME,
+			"unsupported platform, only common packages were checked",
+			"Refer to https://docs.substrate.io/install/ for the packages required.",
+		),
+		(false, _) => Check::fail(
+			NAME,
+			format!("{} missing", missing.join(", ")),
+			"Install them using `pop install`.",
+		),
+	}
+}
+
+/// Checks the platform is one for which the binaries sourced by pop are released, rather than
+/// built from source.
+fn platform() -> Check {
+	const NAME: &str = "Platform";
+	match Platform::detect() {
+		Ok(p) if p.os == TargetOs::LinuxGnu && p.arch == Arch::X86_64 => {
+			Check::pass(NAME, format!("{p}, for which binaries are released"))
+		},
+		Ok(p) => Check::warn(
+			NAME,
+			format!("{p}, for which some binaries are not released"),
+			"Those binaries are built from source when first required, which may take a while.",
+		),
+		Err(e) => Check::warn(
+			NAME,
+			e.to_string(),
+			"Binaries are built from source when first required, if they can be at all.",
+		),
+	}
+}
+
+/// Whether `requirement` is part of the Rust toolchain, checked separately.
+fn is_toolchain(requirement: Requirement) -> bool {
+	matches!(requirement, Requirement::Rustup | Requirement::WasmTarget | Requirement::RustSrc)
+}
+
+/// Checks docker is available, as required by verifiable builds and srtool.
+fn docker() -> Check {
+	const NAME: &str = "Docker";
+	if cmd!("docker", "--version").stdout_null().stderr_null().run().is_err() {
+		return Check::warn(
+			NAME,
+			"not installed, required only by verifiable builds and `--srtool`",
+			"Install it from https://docs.docker.com/get-docker.",
+		);
+	}
+	match cmd!("docker", "info").stdout_null().stderr_null().run() {
+		Ok(_) => Check::pass(NAME, "installed and running"),
+		Err(_) => Check::warn(NAME, "installed but not running", "Start the docker daemon."),
+	}
+}
+
+/// Checks GitHub, from which templates and binaries are sourced, can be reached.
+async fn github() -> Check {
+	const NAME: &str = "Network";
+	let reachable = match reqwest::Client::builder().timeout(Duration::from_secs(10)).build() {
+		Ok(client) => client.head(GITHUB).send().await.map(|_| ()),
+		Err(e) => Err(e),
+	};
+	match reachable {
+		Ok(_) => Check::pass(NAME, "GitHub is reachable"),
+		Err(e) => Check::fail(
+			NAME,
+			format!("GitHub is unreachable: {e}"),
+			"Check your connection, or route requests through a proxy using `pop config set proxy <url>`.",
+		),
+	}
+}
+
+/// Checks the cache is writable, and not too large.
+fn cache() -> Check {
+	const NAME: &str = "Cache";
+	let path = match crate::cache() {
+		Ok(path) => path,
+		Err(e) => {
+			return Check::fail(
+				NAME,
+				format!("unavailable: {e}"),
+				"Set another location using `pop config set cache <path>`.",
+			)
+		},
+	};
+	let probe = path.join(".pop-doctor");
+	if fs::write(&probe, "").and_then(|_| fs::remove_file(&probe)).is_err() {
+		return Check::fail(
+			NAME,
+			format!("{} is not writable", path.display()),
+			"Check the permissions of the cache, or set another location using `pop config set cache <path>`.",
+		);
+	}
+	let size = size(&path);
+	let detail = format!("{} ({})", path.display(), format_size(size));
+	match size > LARGE_CACHE {
+		true => Check::warn(NAME, detail, "Remove unused binaries using `pop cache prune`."),
+		false => Check::pass(NAME, detail),
+	}
+}
+
+/// Reports the versions of the binaries within the cache.
+fn binaries() -> Check {
+	const NAME: &str = "Binaries";
+	let Ok(cache) = crate::cache() else {
+		return Check::pass(NAME, "none cached, sourced when first required");
+	};
+	let mut versions: BTreeMap<String, Vec<String>> = BTreeMap::new();
+	for binary in cached_binaries(&cache).unwrap_or_default() {
+		versions.entry(binary.name).or_default().push(binary.version);
+	}
+	let legacy = legacy_binaries(&cache).unwrap_or_default();
+	if versions.is_empty() && legacy.is_empty() {
+		return Check::pass(NAME, "none cached, sourced when first required");
+	}
+	let mut detail = versions
+		.iter()
+		.map(|(name, versions)| format!("{name} {}", versions.join(", ")))
+		.collect::<Vec<_>>()
+		.join("; ");
+	if !legacy.is_empty() {
+		detail += &format!("; {} cached by a previous version of pop", legacy.len());
+	}
+	match !legacy.is_empty() || versions.values().any(|versions| versions.len() > 1) {
+		true => Check::warn(
+			NAME,
+			detail,
+			"Unused binaries are cached, remove them using `pop cache prune --keep-latest 1`.",
+		),
+		false => Check::pass(NAME, detail),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn is_toolchain_works() {
+		assert!(is_toolchain(Requirement::WasmTarget));
+		assert!(!is_toolchain(Requirement::Clang));
+	}
+}
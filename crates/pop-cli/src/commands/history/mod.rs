@@ -0,0 +1,153 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::{
+	output::OutputArgs,
+	style::{style, Theme},
+};
+use clap::Args;
+use cliclack::{intro, log, outro, set_theme};
+use pop_common::{history::HISTORY_FILE, Operation};
+use std::{env, path::PathBuf};
+
+/// Replaces the value of a secret flag within the arguments recorded.
+const REDACTED: &str = "<redacted>";
+
+#[derive(Args)]
+/// Display the operations applied to a project
+pub(crate) struct HistoryArgs {
+	/// Directory path of the project, [default: the current project]
+	#[arg(short = 'p', long)]
+	path: Option<PathBuf>,
+	/// Only display the most recent operations.
+	#[arg(short = 'n', long)]
+	last: Option<usize>,
+	#[command(flatten)]
+	output: OutputArgs,
+}
+
+impl HistoryArgs {
+	pub(crate) fn execute(&self) -> anyhow::Result<()> {
+		let path = crate::project_path(self.path.as_deref());
+		let mut operations = Operation::history(&path)?;
+		if let Some(last) = self.last {
+			operations = operations.split_off(operations.len().saturating_sub(last));
+		}
+		if self.output.is_json() {
+			return self.output.report(&operations);
+		}
+
+		self.output.clear_screen()?;
+		set_theme(Theme);
+		intro(format!("{}: History", style(" Pop CLI ").black().on_magenta()))?;
+		if operations.is_empty() {
+			outro(format!("No operation recorded within {}.", path.join(HISTORY_FILE).display()))?;
+			return Ok(());
+		}
+		for operation in &operations {
+			let mut message = format!(
+				"{} {}\n{}",
+				style(&operation.timestamp).dim(),
+				style(format!("pop {}", operation.command)).bold(),
+				operation.args.join(" ")
+			);
+			for artifact in &operation.artifacts {
+				message += &format!("\n→ {artifact}");
+			}
+			log::step(message)?;
+		}
+		outro(format!("{} operations recorded.", operations.len()))?;
+		Ok(())
+	}
+}
+
+/// Records an operation applied to the project at `path`, along with the arguments the command
+/// was launched with. Recording is best effort: a failure is only reported as a warning, as the
+/// operation was already applied.
+///
+/// # Arguments
+///
+/// * `path` - path to the project
+/// * `command` - the command applying the operation, e.g. `up contract`
+/// * `secrets` - the flags whose values are redacted, e.g. `--suri`
+/// * `artifacts` - the artifacts resulting from the operation
+pub(crate) fn record(
+	path: &std::path::Path,
+	command: &str,
+	secrets: &[&str],
+	artifacts: Vec<String>,
+) -> anyhow::Result<()> {
+	let args = redact(env::args().skip(1), command, secrets);
+	if let Err(e) = Operation::new(command, args, artifacts).record(path) {
+		log::warning(format!("The operation could not be recorded within the history: {e}"))?;
+	}
+	Ok(())
+}
+
+/// The arguments following `command`, with the values of the `secrets` flags redacted.
+///
+/// # Arguments
+///
+/// * `args` - the arguments the CLI was launched with, excluding the binary
+/// * `command` - the command, whose words are skipped
+/// * `secrets` - the flags whose values are redacted
+fn redact(args: impl Iterator<Item = String>, command: &str, secrets: &[&str]) -> Vec<String> {
+	let words: Vec<_> = command.split_whitespace().collect();
+	let mut args: Vec<String> = args.collect();
+	// Skips the command, which may be preceded by global flags.
+	if let Some(start) = args.iter().position(|a| a == words[0]) {
+		let end = start + words.len();
+		if args.get(start..end).is_some_and(|a| a.iter().zip(&words).all(|(a, w)| a == w)) {
+			args.drain(..end);
+		}
+	}
+	let mut redacted = Vec::with_capacity(args.len());
+	let mut secret = false;
+	for arg in args {
+		if secret {
+			redacted.push(REDACTED.to_string());
+			secret = false;
+			continue;
+		}
+		match arg.split_once('=') {
+			Some((flag, _)) if secrets.contains(&flag) => {
+				redacted.push(format!("{flag}={REDACTED}"))
+			},
+			_ => {
+				secret = secrets.contains(&arg.as_str());
+				redacted.push(arg);
+			},
+		}
+	}
+	redacted
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn args(args: &str) -> impl Iterator<Item = String> + '_ {
+		args.split_whitespace().map(String::from)
+	}
+
+	#[test]
+	fn redact_works() {
+		assert_eq!(
+			redact(
+				args("-y up contract --path ./flipper --suri //Alice --url=ws://localhost:9944"),
+				"up contract",
+				&["--suri", "-s"]
+			),
+			["--path", "./flipper", "--suri", REDACTED, "--url=ws://localhost:9944"]
+		);
+		assert_eq!(
+			redact(args("up contract --suri=//Alice -s //Bob"), "up contract", &["--suri", "-s"]),
+			["--suri=<redacted>", "-s", REDACTED]
+		);
+		assert_eq!(
+			redact(args("new parachain my-app -s DOT"), "new parachain", &[]),
+			["my-app", "-s", "DOT"]
+		);
+		// Generated using the wizard of `pop new`.
+		assert_eq!(redact(args("new"), "new parachain", &[]), ["new"]);
+	}
+}
@@ -0,0 +1,151 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use clap::{Args, ValueEnum};
+use cliclack::{clear_screen, intro, log, outro, outro_cancel, set_theme};
+use console::style;
+use pop_contracts::{inspect_contract, Param};
+use std::path::PathBuf;
+
+use crate::style::Theme;
+
+/// The format in which the contract metadata is output.
+#[derive(Clone, Copy, Debug, Default, PartialEq, ValueEnum)]
+pub(crate) enum OutputFormat {
+	/// Human readable output.
+	#[default]
+	Text,
+	/// JSON output, for use by other tools.
+	Json,
+}
+
+#[derive(Args)]
+pub struct InspectContractCommand {
+	/// Path to the contract project, [default: the current project]
+	#[arg(short = 'p', long)]
+	path: Option<PathBuf>,
+	/// Path to a contract metadata (.json) or bundle (.contract) file, used instead of the build
+	/// artifacts of the contract project.
+	#[arg(short = 'm', long, conflicts_with = "path")]
+	metadata: Option<PathBuf>,
+	/// The output format.
+	#[arg(short = 'o', long, value_enum, default_value_t)]
+	output: OutputFormat,
+}
+
+impl InspectContractCommand {
+	/// The contract project, detected from the current directory unless provided.
+	fn path(&self) -> Option<PathBuf> {
+		Some(crate::contract_path(self.path.as_deref()))
+	}
+
+	pub(crate) fn execute(&self) -> anyhow::Result<()> {
+		if self.output == OutputFormat::Json {
+			let metadata = inspect_contract(&self.path(), self.metadata.as_deref())?;
+			println!("{}", serde_json::to_string_pretty(&metadata)?);
+			return Ok(());
+		}
+
+		clear_screen()?;
+		intro(format!("{}: Inspecting a contract", style(" Pop CLI ").black().on_magenta()))?;
+		set_theme(Theme);
+
+		let metadata = match inspect_contract(&self.path(), self.metadata.as_deref()) {
+			Ok(metadata) => metadata,
+			Err(e) => {
+				outro_cancel(format!("{e}"))?;
+				return Ok(());
+			},
+		};
+
+		let language = metadata.language.map(|l| format!(" ({l})")).unwrap_or_default();
+		log::info(format!("{} v{}{language}", metadata.name, metadata.version))?;
+		log::step(format!(
+			"Constructors:\n{}",
+			list(metadata.constructors.iter().map(|c| {
+				let payable = if c.payable { " [payable]" } else { "" };
+				format!("{}({}){payable}  {}", c.label, args(&c.args), style(&c.selector).dim())
+			}))
+		))?;
+		log::step(format!(
+			"Messages:\n{}",
+			list(metadata.messages.iter().map(|m| {
+				let returns =
+					m.return_type.as_ref().map(|r| format!(" -> {r}")).unwrap_or_default();
+				let mut flags = Vec::new();
+				if m.mutates {
+					flags.push("mutates");
+				}
+				if m.payable {
+					flags.push("payable");
+				}
+				let flags = if flags.is_empty() {
+					String::new()
+				} else {
+					format!(" [{}]", flags.join(", "))
+				};
+				format!(
+					"{}({}){returns}{flags}  {}",
+					m.label,
+					args(&m.args),
+					style(&m.selector).dim()
+				)
+			}))
+		))?;
+		log::step(format!(
+			"Events:\n{}",
+			list(metadata.events.iter().map(|e| {
+				let fields: Vec<_> = e
+					.fields
+					.iter()
+					.map(|f| {
+						let indexed = if f.indexed { " (indexed)" } else { "" };
+						format!("{}: {}{indexed}", f.label, f.type_name)
+					})
+					.collect();
+				format!("{} {{ {} }}", e.label, fields.join(", "))
+			}))
+		))?;
+		log::step(format!(
+			"Storage:\n{}",
+			list(metadata.storage.iter().map(|s| format!("{}: {}", s.name, s.type_name)))
+		))?;
+		outro("Inspection complete.")?;
+		Ok(())
+	}
+}
+
+fn args(args: &[Param]) -> String {
+	args.iter()
+		.map(|a| format!("{}: {}", a.label, a.type_name))
+		.collect::<Vec<_>>()
+		.join(", ")
+}
+
+fn list(items: impl Iterator<Item = String>) -> String {
+	let items: Vec<_> = items.map(|i| format!("  {i}")).collect();
+	if items.is_empty() {
+		"  none".to_string()
+	} else {
+		items.join("\n")
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_list() {
+		assert_eq!(list(std::iter::empty()), "  none");
+		assert_eq!(list(vec!["a".to_string(), "b".to_string()].into_iter()), "  a\n  b");
+	}
+
+	#[test]
+	fn test_args() {
+		let params = vec![
+			Param { label: "to".into(), type_name: "AccountId".into() },
+			Param { label: "value".into(), type_name: "u128".into() },
+		];
+		assert_eq!(args(&params), "to: AccountId, value: u128");
+	}
+}
@@ -0,0 +1,28 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use clap::{Args, Subcommand};
+
+#[cfg(feature = "contract")]
+pub(crate) mod contract;
+#[cfg(feature = "parachain")]
+pub(crate) mod runtime_diff;
+
+#[derive(Args)]
+#[command(args_conflicts_with_subcommands = true)]
+pub(crate) struct InspectArgs {
+	#[command(subcommand)]
+	pub command: InspectCommands,
+}
+
+#[derive(Subcommand)]
+pub(crate) enum InspectCommands {
+	/// Inspect the metadata of a contract
+	#[cfg(feature = "contract")]
+	#[clap(alias = "c")]
+	Contract(contract::InspectContractCommand),
+	/// Compare the metadata of two runtimes, reporting the pallets, calls, storage items and
+	/// constants added, removed or changed
+	#[cfg(feature = "parachain")]
+	#[clap(alias = "rd")]
+	RuntimeDiff(runtime_diff::InspectRuntimeDiffCommand),
+}
@@ -0,0 +1,78 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::style::{style, Theme};
+use clap::Args;
+use cliclack::{clear_screen, intro, log, outro, outro_cancel, set_theme};
+use pop_parachains::{diff_runtimes, Change, RuntimeDifference, RuntimeSource, RuntimeSummary};
+
+#[derive(Args)]
+pub(crate) struct InspectRuntimeDiffCommand {
+	/// The old runtime: either a wasm runtime, or the websocket endpoint of a live chain (e.g.
+	/// `wss://rpc.polkadot.io`).
+	old: String,
+	/// The new runtime: either a wasm runtime, or the websocket endpoint of a live chain.
+	new: String,
+}
+
+impl InspectRuntimeDiffCommand {
+	pub(crate) async fn execute(&self) -> anyhow::Result<()> {
+		clear_screen()?;
+		intro(format!("{}: Comparing runtimes", style(" Pop CLI ").black().on_magenta()))?;
+		set_theme(Theme);
+
+		let spinner = crate::progress::spinner();
+		let mut summaries = Vec::new();
+		for source in [&self.old, &self.new] {
+			spinner.start(format!("Reading the metadata of {source}..."));
+			match source.parse::<RuntimeSource>() {
+				Ok(source) => match source.metadata().await {
+					Ok(metadata) => summaries.push(RuntimeSummary::from(&metadata)),
+					Err(e) => {
+						spinner.error(format!("{e}"));
+						outro_cancel(format!("Unable to read the metadata of {source}."))?;
+						return Ok(());
+					},
+				},
+				Err(e) => {
+					spinner.error(format!("{e}"));
+					outro_cancel(format!("Invalid runtime `{source}`."))?;
+					return Ok(());
+				},
+			}
+		}
+		spinner.stop("Metadata read.");
+
+		let differences = diff_runtimes(&summaries[0], &summaries[1]);
+		if differences.is_empty() {
+			outro(
+				"The pallets, calls, storage items and constants of both runtimes are identical.",
+			)?;
+			return Ok(());
+		}
+		let symbol = |change: Change| match change {
+			Change::Added => style("+").green(),
+			Change::Removed => style("-").red(),
+			Change::Changed => style("~").yellow(),
+		};
+		// Differences are ordered by pallet, so items are listed beneath their pallet.
+		let mut pallet = None;
+		let mut lines = Vec::new();
+		for difference in &differences {
+			match difference {
+				RuntimeDifference::Pallet { pallet, change } => {
+					lines.push(format!("{} {pallet} (pallet {change})", symbol(*change)));
+				},
+				RuntimeDifference::Item { pallet: name, kind, name: item, change } => {
+					if pallet != Some(name) {
+						lines.push(format!("{} {name}", style("~").yellow()));
+						pallet = Some(name);
+					}
+					lines.push(format!("    {} {kind} {item} ({change})", symbol(*change)));
+				},
+			}
+		}
+		log::info(lines.join("\n"))?;
+		outro(format!("{} differences found.", differences.len()))?;
+		Ok(())
+	}
+}
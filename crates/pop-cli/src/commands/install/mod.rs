@@ -1,305 +1,353 @@
 // SPDX-License-Identifier: GPL-3.0
-use crate::style::{style, Theme};
-use anyhow::Context;
+use crate::{
+	prompt,
+	style::{style, Theme},
+};
 use clap::Args;
-use cliclack::{clear_screen, confirm, intro, log, outro, set_theme};
+use cliclack::{clear_screen, intro, log, outro, outro_cancel, set_theme};
 use duct::cmd;
 use os_info::Type;
-use strum::Display;
-use tokio::{fs, process::Command};
-use Dependencies::*;
+use strum::VariantArray as _;
+use strum_macros::{Display, VariantArray};
 
-#[derive(Display)]
-pub enum Dependencies {
-	#[strum(serialize = "build-essential")]
-	BuildEssential,
+/// The script installing Homebrew on macOS.
+const HOMEBREW_INSTALL: &str = "/bin/bash -c \"$(curl -fsSL \
+	https://raw.githubusercontent.com/Homebrew/install/HEAD/install.sh)\"";
+/// The script installing rustup, along with the wasm target and the sources of the standard
+/// library.
+const RUSTUP_INSTALL: &str =
+	"curl --proto '=https' --tlsv1.2 -sSf https://sh.rustup.rs | sh -s -- \
+	-y --target wasm32-unknown-unknown --component rust-src";
+
+/// A dependency of Substrate development, which is detected on the machine.
+#[derive(Clone, Copy, Debug, Display, PartialEq, VariantArray)]
+pub(crate) enum Requirement {
+	/// The package manager of macOS.
+	#[strum(serialize = "homebrew")]
+	Homebrew,
+	/// A C toolchain, to compile the native dependencies.
+	#[strum(serialize = "C compiler")]
+	Compiler,
+	#[strum(serialize = "git")]
+	Git,
 	#[strum(serialize = "clang")]
 	Clang,
-	#[strum(serialize = "clang-devel")]
-	ClangDevel,
 	#[strum(serialize = "cmake")]
 	Cmake,
-	#[strum(serialize = "curl")]
-	Curl,
-	#[strum(serialize = "gcc")]
-	Gcc,
-	#[strum(serialize = "git")]
-	Git,
-	#[strum(serialize = "homebrew")]
-	Homebrew,
-	#[strum(serialize = "libclang-dev")]
-	LibClang,
-	#[strum(serialize = "libssl-dev")]
-	Libssl,
-	#[strum(serialize = "make")]
-	Make,
-	#[strum(serialize = "openssl")]
-	Openssl,
-	#[strum(serialize = "openssl-devel")]
-	OpenSslDevel,
-	#[strum(serialize = "pkg-config")]
-	PkgConfig,
+	/// The protocol buffers compiler, `protoc`.
 	#[strum(serialize = "protobuf")]
 	Protobuf,
-	#[strum(serialize = "protobuf-compiler")]
-	ProtobufCompiler,
+	/// The development headers of OpenSSL, located by `pkg-config`.
+	#[strum(serialize = "openssl")]
+	Openssl,
 	#[strum(serialize = "rustup")]
 	Rustup,
+	/// The target the runtime is compiled to.
+	#[strum(serialize = "wasm32-unknown-unknown target")]
+	WasmTarget,
+	/// The sources of the standard library, required to compile the runtime.
+	#[strum(serialize = "rust-src component")]
+	RustSrc,
+}
+
+impl Requirement {
+	/// The requirements of `os`, or those which are common to every platform when unsupported.
+	///
+	/// # Arguments
+	///
+	/// * `os` - the platform, if supported
+	pub(crate) fn all(os: Option<Os>) -> impl Iterator<Item = Requirement> {
+		Requirement::VARIANTS.iter().copied().filter(move |r| match r {
+			Requirement::Homebrew => os == Some(Os::Mac),
+			Requirement::Openssl => os.is_some_and(|os| os != Os::Mac),
+			_ => true,
+		})
+	}
+
+	/// Whether the requirement is installed.
+	pub(crate) fn installed(&self) -> bool {
+		let succeeds = |program: &str, args: &[&str]| {
+			cmd(program, args).stdout_null().stderr_null().run().is_ok()
+		};
+		let lists = |args: &[&str], item: &str| {
+			cmd("rustup", args)
+				.stderr_null()
+				.read()
+				.is_ok_and(|installed| installed.lines().any(|l| l.starts_with(item)))
+		};
+		match self {
+			Requirement::Homebrew => succeeds("brew", &["--version"]),
+			Requirement::Compiler => succeeds("cc", &["--version"]),
+			Requirement::Git => succeeds("git", &["--version"]),
+			Requirement::Clang => succeeds("clang", &["--version"]),
+			Requirement::Cmake => succeeds("cmake", &["--version"]),
+			Requirement::Protobuf => succeeds("protoc", &["--version"]),
+			Requirement::Openssl => succeeds("pkg-config", &["--exists", "openssl"]),
+			Requirement::Rustup => succeeds("rustup", &["--version"]),
+			Requirement::WasmTarget => {
+				lists(&["target", "list", "--installed"], "wasm32-unknown-unknown")
+			},
+			Requirement::RustSrc => lists(&["component", "list", "--installed"], "rust-src"),
+		}
+	}
+}
+
+/// A platform whose system packages can be installed.
+#[derive(Clone, Copy, Debug, Display, PartialEq)]
+pub(crate) enum Os {
+	#[strum(serialize = "macOS")]
+	Mac,
+	#[strum(serialize = "Arch Linux")]
+	Arch,
+	/// Debian and the distributions based on it, such as Ubuntu.
+	#[strum(serialize = "Debian")]
+	Debian,
+	/// Red Hat and the distributions based on it, such as Fedora.
+	#[strum(serialize = "Red Hat")]
+	Redhat,
+}
+
+impl Os {
+	/// Detects the platform, unless unsupported.
+	pub(crate) fn detect() -> Option<Os> {
+		if cfg!(target_os = "macos") {
+			return Some(Os::Mac);
+		}
+		if !cfg!(target_os = "linux") {
+			return None;
+		}
+		match os_info::get().os_type() {
+			Type::Arch | Type::Manjaro | Type::EndeavourOS => Some(Os::Arch),
+			Type::Debian | Type::Ubuntu | Type::Mint | Type::Pop => Some(Os::Debian),
+			Type::Redhat | Type::RedHatEnterprise | Type::Fedora | Type::CentOS => Some(Os::Redhat),
+			_ => None,
+		}
+	}
+
+	/// The system packages providing `requirement`.
+	///
+	/// # Arguments
+	///
+	/// * `requirement` - the missing requirement
+	fn packages(&self, requirement: Requirement) -> &'static [&'static str] {
+		use Requirement::*;
+		match (self, requirement) {
+			// Provided by the command line tools of Xcode instead.
+			(Os::Mac, Compiler | Clang) => &[],
+			(Os::Arch, Compiler) => &["base-devel"],
+			(Os::Debian, Compiler) => &["build-essential"],
+			(Os::Redhat, Compiler) => &["gcc", "gcc-c++", "make"],
+			(Os::Debian, Clang) => &["clang", "libclang-dev"],
+			(Os::Redhat, Clang) => &["clang", "clang-devel"],
+			(_, Clang) => &["clang"],
+			(_, Git) => &["git"],
+			(_, Cmake) => &["cmake"],
+			(Os::Mac | Os::Arch, Protobuf) => &["protobuf"],
+			(_, Protobuf) => &["protobuf-compiler"],
+			(Os::Arch, Openssl) => &["openssl", "pkgconf"],
+			(Os::Debian, Openssl) => &["libssl-dev", "pkg-config"],
+			(Os::Redhat, Openssl) => &["openssl-devel", "pkgconf-pkg-config"],
+			_ => &[],
+		}
+	}
+
+	/// The commands installing the system `packages`.
+	///
+	/// # Arguments
+	///
+	/// * `packages` - the packages to install
+	/// * `sudo` - the prefix running a command as root, if required
+	fn install(&self, packages: &[&str], sudo: &str) -> Vec<String> {
+		let packages = packages.join(" ");
+		match self {
+			Os::Mac => vec![format!("brew install {packages}")],
+			Os::Arch => vec![format!("{sudo}pacman -S --needed --noconfirm {packages}")],
+			Os::Debian => vec![
+				format!("{sudo}apt-get update"),
+				format!("{sudo}apt-get install -y {packages}"),
+			],
+			Os::Redhat => vec![format!("{sudo}yum install -y {packages}")],
+		}
+	}
+
+	/// The guide describing the installation of the dependencies on the platform.
+	fn guide(&self) -> &'static str {
+		match self {
+			Os::Mac => "https://docs.substrate.io/install/macos/",
+			_ => "https://docs.substrate.io/install/linux/",
+		}
+	}
 }
+
 #[derive(Args)]
 #[command(args_conflicts_with_subcommands = true)]
 /// Setup user environment for development
 pub(crate) struct InstallArgs {
-	/// Automatically install all dependencies required without prompting for confirmation.
-	#[clap(short('y'), long)]
-	skip_confirm: bool,
+	/// Only print the commands installing the missing dependencies, rather than running them.
+	#[arg(long)]
+	print: bool,
 }
 
 impl InstallArgs {
-	pub(crate) async fn execute(self) -> anyhow::Result<()> {
+	pub(crate) fn execute(self) -> anyhow::Result<()> {
 		clear_screen()?;
 		set_theme(Theme);
 		intro(format!(
 			"{}: Install dependencies for development",
 			style(" Pop CLI ").black().on_magenta()
 		))?;
-		if cfg!(target_os = "macos") {
-			log::info("ℹ️ Mac OS (Darwin) detected.")?;
-			install_mac(self.skip_confirm).await?;
-		} else if cfg!(target_os = "linux") {
-			match os_info::get().os_type() {
-				Type::Arch => {
-					log::info("ℹ️ Arch Linux detected.")?;
-					install_arch(self.skip_confirm).await?;
-				},
-				Type::Debian => {
-					log::info("ℹ️ Debian Linux detected.")?;
-					install_debian(self.skip_confirm).await?;
-				},
-				Type::Redhat => {
-					log::info("ℹ️ Redhat Linux detected.")?;
-					install_redhat(self.skip_confirm).await?;
-				},
-				Type::Ubuntu => {
-					log::info("ℹ️ Ubuntu detected.")?;
-					install_ubuntu(self.skip_confirm).await?;
-				},
-				_ => return not_supported_message(),
+		let os = Os::detect();
+		match os {
+			Some(os) => log::info(format!("ℹ️ {os} detected."))?,
+			None => log::warning(
+				"⚠️ The system packages of this OS are not supported at present, please refer to \
+				 https://docs.substrate.io/install/ for setup information.",
+			)?,
+		}
+
+		let missing = missing(os);
+		if missing.is_empty() {
+			outro("✅ All dependencies are installed.")?;
+			return Ok(());
+		}
+		let commands = install_commands(os, &missing, sudo());
+		let names: Vec<_> = missing.iter().map(|r| r.to_string()).collect();
+		log::warning(format!("The following dependencies are missing: {}", names.join(", ")))?;
+		if let Some(os) = os {
+			log::info(format!(
+				"More information about the packages to be installed here: {}",
+				os.guide()
+			))?;
+		}
+		log::info(format!("They can be installed with:\n{}", commands.join("\n")))?;
+		if self.print {
+			outro("Run the commands above to install the missing dependencies.")?;
+			return Ok(());
+		}
+		if !prompt::confirm("📦 Do you want to proceed with the installation?", true)? {
+			outro_cancel("🚫 You have cancelled the installation process.")?;
+			return Ok(());
+		}
+
+		for command in &commands {
+			log::step(command)?;
+			if let Err(e) = cmd("sh", ["-c", command]).run() {
+				outro_cancel(format!("🚫 `{command}` failed: {e}"))?;
+				return Ok(());
 			}
-		} else {
-			return not_supported_message();
 		}
-		install_rustup().await?;
+		let remaining = self::missing(os);
+		if !remaining.is_empty() {
+			let names: Vec<_> = remaining.iter().map(|r| r.to_string()).collect();
+			outro_cancel(format!(
+				"🚫 The following dependencies are still missing: {}. A new shell may be required \
+				 for them to be found.",
+				names.join(", ")
+			))?;
+			return Ok(());
+		}
 		outro("✅ Installation complete.")?;
 		Ok(())
 	}
 }
 
-async fn install_mac(skip_confirm: bool) -> anyhow::Result<()> {
-	log::info("More information about the packages to be installed here: https://docs.substrate.io/install/macos/")?;
-	if !skip_confirm {
-		prompt_for_confirmation(&format!(
-			"{}, {}, {}, {} and {}",
-			Dependencies::Homebrew,
-			Dependencies::Protobuf,
-			Dependencies::Openssl,
-			Dependencies::Rustup,
-			Dependencies::Cmake,
-		))?
-	}
-	install_homebrew().await?;
-	cmd("brew", vec!["update"]).run()?;
-	cmd("brew", vec!["install", &Protobuf.to_string(), &Openssl.to_string(), &Cmake.to_string()])
-		.run()?;
-
-	Ok(())
+/// The requirements of `os` which are not installed.
+///
+/// # Arguments
+///
+/// * `os` - the platform, if supported
+fn missing(os: Option<Os>) -> Vec<Requirement> {
+	Requirement::all(os).filter(|r| !r.installed()).collect()
 }
 
-async fn install_arch(skip_confirm: bool) -> anyhow::Result<()> {
-	log::info("More information about the packages to be installed here: https://docs.substrate.io/install/linux/")?;
-	if !skip_confirm {
-		prompt_for_confirmation(&format!(
-			"{}, {}, {}, {}, {} and {}",
-			Curl, Git, Clang, Make, Openssl, Rustup,
-		))?
+/// The prefix running commands as root, unless already root.
+fn sudo() -> &'static str {
+	match cmd("id", ["-u"]).read() {
+		Ok(id) if id.trim() == "0" => "",
+		_ => "sudo ",
 	}
-	cmd(
-		"pacman",
-		vec![
-			"-Syu",
-			"--needed",
-			"--noconfirm",
-			&Curl.to_string(),
-			&Git.to_string(),
-			&Clang.to_string(),
-			&Make.to_string(),
-			&Openssl.to_string(),
-		],
-	)
-	.run()?;
-
-	Ok(())
-}
-async fn install_ubuntu(skip_confirm: bool) -> anyhow::Result<()> {
-	log::info("More information about the packages to be installed here: https://docs.substrate.io/install/linux/")?;
-	if !skip_confirm {
-		prompt_for_confirmation(&format!(
-			"{}, {}, {}, {}, {} and {}",
-			Git, Clang, Curl, Libssl, ProtobufCompiler, Rustup,
-		))?
-	}
-	cmd(
-		"apt",
-		vec![
-			"install",
-			"--assume-yes",
-			&Git.to_string(),
-			&Clang.to_string(),
-			&Curl.to_string(),
-			&Libssl.to_string(),
-			&ProtobufCompiler.to_string(),
-		],
-	)
-	.run()?;
-
-	Ok(())
 }
 
-async fn install_debian(skip_confirm: bool) -> anyhow::Result<()> {
-	log::info("More information about the packages to be installed here: https://docs.substrate.io/install/linux/")?;
-	if !skip_confirm {
-		prompt_for_confirmation(&format!(
-			"{}, {}, {}, {}, {}, {}, {}, {}, {} and {}",
-			Cmake,
-			PkgConfig,
-			Libssl,
-			Git,
-			Gcc,
-			BuildEssential,
-			ProtobufCompiler,
-			Clang,
-			LibClang,
-			Rustup,
-		))?
+/// The commands installing the `missing` requirements on `os`.
+///
+/// # Arguments
+///
+/// * `os` - the platform, if supported, otherwise only the Rust toolchain is installed
+/// * `missing` - the missing requirements
+/// * `sudo` - the prefix running a command as root, if required
+fn install_commands(os: Option<Os>, missing: &[Requirement], sudo: &str) -> Vec<String> {
+	let mut commands = Vec::new();
+	if let Some(os) = os {
+		if missing.contains(&Requirement::Homebrew) {
+			commands.push(HOMEBREW_INSTALL.to_string());
+		}
+		if os == Os::Mac
+			&& (missing.contains(&Requirement::Compiler) || missing.contains(&Requirement::Clang))
+		{
+			commands.push("xcode-select --install".to_string());
+		}
+		let mut packages: Vec<&str> = Vec::new();
+		for package in missing.iter().flat_map(|r| os.packages(*r)) {
+			if !packages.contains(package) {
+				packages.push(package);
+			}
+		}
+		if !packages.is_empty() {
+			commands.extend(os.install(&packages, sudo));
+		}
 	}
-	cmd(
-		"apt",
-		vec![
-			"install",
-			"-y",
-			&Cmake.to_string(),
-			&PkgConfig.to_string(),
-			&Libssl.to_string(),
-			&Git.to_string(),
-			&Gcc.to_string(),
-			&BuildEssential.to_string(),
-			&ProtobufCompiler.to_string(),
-			&Clang.to_string(),
-			&LibClang.to_string(),
-		],
-	)
-	.run()?;
-
-	Ok(())
-}
-
-async fn install_redhat(skip_confirm: bool) -> anyhow::Result<()> {
-	log::info("More information about the packages to be installed here: https://docs.substrate.io/install/linux/")?;
-	if !skip_confirm {
-		prompt_for_confirmation(&format!(
-			"{}, {}, {}, {}, {}, {}, {} and {}",
-			Cmake, OpenSslDevel, Git, Protobuf, ProtobufCompiler, Clang, ClangDevel, Rustup,
-		))?
+	if missing.contains(&Requirement::Rustup) {
+		// The target and the component are installed along with rustup.
+		commands.push(RUSTUP_INSTALL.to_string());
+		return commands;
 	}
-	cmd("yum", vec!["update", "-y"]).run()?;
-	cmd("yum", vec!["groupinstall", "-y", "'Development Tool"]).run()?;
-	cmd(
-		"yum",
-		vec![
-			"install",
-			"-y",
-			&Cmake.to_string(),
-			&OpenSslDevel.to_string(),
-			&Git.to_string(),
-			&Protobuf.to_string(),
-			&ProtobufCompiler.to_string(),
-			&Clang.to_string(),
-			&ClangDevel.to_string(),
-		],
-	)
-	.run()?;
-
-	Ok(())
-}
-
-fn prompt_for_confirmation(message: &str) -> anyhow::Result<()> {
-	if !confirm(format!(
-		"📦 Do you want to proceed with the installation of the following packages: {} ?",
-		message
-	))
-	.initial_value(true)
-	.interact()?
-	{
-		return Err(anyhow::anyhow!("🚫 You have cancelled the installation process."));
+	if missing.contains(&Requirement::WasmTarget) {
+		commands.push("rustup target add wasm32-unknown-unknown".to_string());
 	}
-	Ok(())
+	if missing.contains(&Requirement::RustSrc) {
+		commands.push("rustup component add rust-src".to_string());
+	}
+	commands
 }
 
-fn not_supported_message() -> anyhow::Result<()> {
-	log::error("This OS is not supported at present")?;
-	log::warning("⚠️ Please refer to https://docs.substrate.io/install/ for setup information.")?;
-	Ok(())
-}
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use Requirement::*;
 
-async fn install_rustup() -> anyhow::Result<()> {
-	match cmd("which", vec!["rustup"]).read() {
-		Ok(output) => {
-			log::info(format!("ℹ️ rustup installed already at {}.", output))?;
-			cmd("rustup", vec!["update"]).run()?;
-			cmd("rustup", vec!["default", "stable"]).run()?;
-		},
-		Err(_) => {
-			let spinner = cliclack::spinner();
-			spinner.start("Installing rustup ...");
-			run_external_script("https://sh.rustup.rs").await?;
-			outro("rustup installed!")?;
-			cmd("source", vec!["~/.cargo/env"]).run()?;
-			cmd("rustup", vec!["default", "stable"]).run()?;
-		},
+	#[test]
+	fn test_requirements() {
+		assert!(Requirement::all(Some(Os::Mac)).any(|r| r == Homebrew));
+		assert!(!Requirement::all(Some(Os::Mac)).any(|r| r == Openssl));
+		assert!(Requirement::all(Some(Os::Debian)).any(|r| r == Openssl));
+		assert!(!Requirement::all(None).any(|r| r == Homebrew || r == Openssl));
 	}
-	cmd("rustup", vec!["update", "nightly"]).run()?;
-	cmd("rustup", vec!["target", "add", "wasm32-unknown-unknown", "--toolchain", "nightly"])
-		.run()?;
 
-	Ok(())
-}
-
-async fn install_homebrew() -> anyhow::Result<()> {
-	match cmd("which", vec!["brew"]).read() {
-		Ok(output) => log::info(format!("ℹ️ Homebrew installed already at {}.", output))?,
-		Err(_) => {
-			run_external_script(
-				"https://raw.githubusercontent.com/Homebrew/install/master/install.sh",
-			)
-			.await?
-		},
+	#[test]
+	fn test_install_commands() {
+		assert_eq!(
+			install_commands(Some(Os::Debian), &[Clang, Protobuf, Cmake, WasmTarget], "sudo "),
+			[
+				"sudo apt-get update",
+				"sudo apt-get install -y clang libclang-dev protobuf-compiler cmake",
+				"rustup target add wasm32-unknown-unknown",
+			]
+		);
+		assert_eq!(
+			install_commands(Some(Os::Arch), &[Compiler, Openssl, RustSrc], ""),
+			[
+				"pacman -S --needed --noconfirm base-devel openssl pkgconf",
+				"rustup component add rust-src",
+			]
+		);
+		assert_eq!(
+			install_commands(Some(Os::Mac), &[Homebrew, Clang, Protobuf, Rustup, WasmTarget], ""),
+			[HOMEBREW_INSTALL, "xcode-select --install", "brew install protobuf", RUSTUP_INSTALL]
+		);
+		assert_eq!(
+			install_commands(None, &[Clang, RustSrc], "sudo "),
+			["rustup component add rust-src"]
+		);
+		assert!(install_commands(Some(Os::Redhat), &[], "sudo ").is_empty());
 	}
-	Ok(())
-}
-
-async fn run_external_script(script_url: &str) -> anyhow::Result<()> {
-	let temp = tempfile::tempdir()?;
-	let scripts_path = temp.path().join("install.sh");
-	let client = reqwest::Client::new();
-	let script = client
-		.get(script_url)
-		.send()
-		.await
-		.context("Network Error: Failed to fetch script from Github")?
-		.text()
-		.await?;
-	fs::write(scripts_path.as_path(), script).await?;
-	Command::new("bash").arg(scripts_path).status().await?;
-	temp.close()?;
-	Ok(())
 }
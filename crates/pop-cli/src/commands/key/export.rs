@@ -0,0 +1,61 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::{
+	prompt,
+	style::{style, Theme},
+};
+use clap::Args;
+use cliclack::{clear_screen, intro, log, outro, outro_cancel, set_theme};
+
+#[derive(Args)]
+pub struct ExportKeyCommand {
+	/// The name of the key within the local keystore.
+	name: String,
+	/// Show the secret key URI without asking, as it is otherwise never shown when running
+	/// non-interactively.
+	#[arg(long)]
+	force: bool,
+}
+
+impl ExportKeyCommand {
+	pub(crate) fn execute(&self) -> anyhow::Result<()> {
+		clear_screen()?;
+		intro(format!("{}: Export a key", style(" Pop CLI ").black().on_magenta()))?;
+		set_theme(Theme);
+
+		let keystore = crate::keystore()?;
+		let info = match keystore.info(&self.name) {
+			Ok(info) => info,
+			Err(e) => {
+				outro_cancel(format!("{e}"))?;
+				return Ok(());
+			},
+		};
+		// The secret is only revealed on request, as it would appear within the terminal.
+		if !self.force
+			&& !prompt::confirm(
+				format!(
+					"The secret key URI of `{}` will be shown in plain text. Continue?",
+					info.name
+				),
+				false,
+			)? {
+			outro_cancel("No key exported.")?;
+			return Ok(());
+		}
+		let keystore = if info.encrypted {
+			keystore.with_password(crate::keystore_password(false)?)
+		} else {
+			keystore
+		};
+		match keystore.get(&self.name) {
+			Ok(key) => {
+				log::info(format!("Scheme: {}\nAddress: {}", key.scheme, key.address))?;
+				log::info(format!("Secret key URI: {}", key.suri))?;
+				outro("Key exported, keep its secret safe.")?;
+			},
+			Err(e) => outro_cancel(format!("{e}"))?,
+		}
+		Ok(())
+	}
+}
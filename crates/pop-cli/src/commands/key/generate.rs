@@ -0,0 +1,79 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::style::{style, Theme};
+use clap::{
+	builder::{PossibleValue, PossibleValuesParser, TypedValueParser},
+	Args,
+};
+use cliclack::{clear_screen, intro, log, outro, outro_cancel, set_theme};
+use pop_common::keystore::Scheme;
+use std::str::FromStr;
+use strum::VariantArray;
+
+#[derive(Args)]
+pub struct GenerateKeyCommand {
+	/// The name of the key, used to refer to it when signing (e.g. `--account <name>`).
+	name: String,
+	/// The signature scheme of the key.
+	#[arg(long, default_value = "sr25519", value_parser = crate::enum_variants!(Scheme))]
+	scheme: Scheme,
+	/// A derivation path applied to the mnemonic phrase generated, e.g. "//polkadot//0".
+	#[arg(long)]
+	derivation: Option<String>,
+}
+
+impl GenerateKeyCommand {
+	pub(crate) fn execute(&self) -> anyhow::Result<()> {
+		clear_screen()?;
+		intro(format!("{}: Generate a key", style(" Pop CLI ").black().on_magenta()))?;
+		set_theme(Theme);
+
+		let phrase = self.scheme.generate();
+		let suri = suri(&phrase, self.derivation.as_deref());
+		let password = crate::keystore_password(true)?;
+		match crate::keystore()?
+			.with_password(password)
+			.insert(&self.name, &suri, self.scheme)
+		{
+			Ok(key) => {
+				log::info(format!("Address: {}", key.address))?;
+				log::warning(format!(
+					"Back up the mnemonic phrase of the key, as it is the only way to recover it \
+					 without the keystore:\n{phrase}"
+				))?;
+				outro(format!(
+					"Key `{}` generated. Sign with it using `--account {}`.",
+					key.name, key.name
+				))?;
+			},
+			Err(e) => outro_cancel(format!("{e}"))?,
+		}
+		Ok(())
+	}
+}
+
+/// The secret key URI of a mnemonic `phrase`, derived using `derivation` if any.
+///
+/// # Arguments
+///
+/// * `phrase` - the mnemonic phrase
+/// * `derivation` - a derivation path, e.g. "//polkadot//0"
+fn suri(phrase: &str, derivation: Option<&str>) -> String {
+	match derivation.map(str::trim).filter(|d| !d.is_empty()) {
+		Some(derivation) => format!("{phrase}{derivation}"),
+		None => phrase.to_string(),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn suri_works() {
+		let phrase = "bottom drive obey lake curtain smoke basket hold race lonely fit walk";
+		assert_eq!(suri(phrase, None), phrase);
+		assert_eq!(suri(phrase, Some(" ")), phrase);
+		assert_eq!(suri(phrase, Some("//polkadot//0")), format!("{phrase}//polkadot//0"));
+	}
+}
@@ -0,0 +1,57 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::{
+	prompt,
+	style::{style, Theme},
+};
+use clap::{
+	builder::{PossibleValue, PossibleValuesParser, TypedValueParser},
+	Args,
+};
+use cliclack::{clear_screen, intro, outro, outro_cancel, set_theme};
+use pop_common::keystore::Scheme;
+use std::str::FromStr;
+use strum::VariantArray;
+
+#[derive(Args)]
+pub struct ImportKeyCommand {
+	/// The name of the key, used to refer to it when signing (e.g. `--account <name>`).
+	name: String,
+	/// Secret key URI of the key. If not provided, it is prompted for so that it does not appear
+	/// within the shell history.
+	///
+	/// e.g.
+	/// - for a dev account "//Alice"
+	/// - a mnemonic phrase, optionally followed by a derivation path "<phrase>//polkadot//0"
+	/// - with a password "//Alice///SECRET_PASSWORD"
+	#[clap(long, short)]
+	suri: Option<String>,
+	/// The signature scheme of the key.
+	#[arg(long, default_value = "sr25519", value_parser = crate::enum_variants!(Scheme))]
+	scheme: Scheme,
+}
+
+impl ImportKeyCommand {
+	pub(crate) fn execute(&self) -> anyhow::Result<()> {
+		clear_screen()?;
+		intro(format!("{}: Import a key", style(" Pop CLI ").black().on_magenta()))?;
+		set_theme(Theme);
+
+		let suri = match &self.suri {
+			Some(suri) => suri.clone(),
+			None => prompt::password("Enter the secret key URI of the key:", "--suri")?,
+		};
+		let password = crate::keystore_password(true)?;
+		match crate::keystore()?
+			.with_password(password)
+			.insert(&self.name, &suri, self.scheme)
+		{
+			Ok(key) => outro(format!(
+				"Key `{}` ({}) imported. Sign with it using `--account {}`.",
+				key.name, key.address, key.name
+			))?,
+			Err(e) => outro_cancel(format!("{e}"))?,
+		}
+		Ok(())
+	}
+}
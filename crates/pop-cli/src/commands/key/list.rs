@@ -0,0 +1,79 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::{
+	output::OutputArgs,
+	style::{style, Theme},
+};
+use clap::Args;
+use cliclack::{intro, log, outro, set_theme};
+use pop_common::KeyInfo;
+use serde::Serialize;
+
+#[derive(Args)]
+pub struct ListKeysCommand {
+	#[command(flatten)]
+	output: OutputArgs,
+}
+
+/// The keys within the local keystore, as output by `--output json`.
+#[derive(Serialize)]
+struct KeysReport<'a> {
+	keys: &'a [KeyInfo],
+}
+
+impl ListKeysCommand {
+	pub(crate) fn execute(&self) -> anyhow::Result<()> {
+		self.output.clear_screen()?;
+		intro(format!("{}: List keys", style(" Pop CLI ").black().on_magenta()))?;
+		set_theme(Theme);
+
+		let keystore = crate::keystore()?;
+		let keys = keystore
+			.list()?
+			.iter()
+			.map(|name| keystore.info(name))
+			.collect::<Result<Vec<_>, _>>()?;
+		self.output.report(&KeysReport { keys: &keys })?;
+		if keys.is_empty() {
+			outro("No keys found, add one with `pop key generate <name>`.")?;
+			return Ok(());
+		}
+		for key in &keys {
+			log::info(describe(key))?;
+		}
+		outro(format!("{} key(s) found.", keys.len()))?;
+		Ok(())
+	}
+}
+
+/// Describes a key, flagging those stored unencrypted by previous versions.
+///
+/// # Arguments
+///
+/// * `key` - the public details of the key
+fn describe(key: &KeyInfo) -> String {
+	let unencrypted = if key.encrypted { "" } else { " [unencrypted]" };
+	format!("{} ({}): {}{unencrypted}", key.name, key.scheme, key.address)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use pop_common::keystore::Scheme;
+
+	#[test]
+	fn describe_works() {
+		let mut key = KeyInfo {
+			name: "alice".into(),
+			scheme: Scheme::Sr25519,
+			address: "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY".into(),
+			encrypted: true,
+		};
+		assert_eq!(
+			describe(&key),
+			"alice (sr25519): 5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY"
+		);
+		key.encrypted = false;
+		assert!(describe(&key).ends_with(" [unencrypted]"));
+	}
+}
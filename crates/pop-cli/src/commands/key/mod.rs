@@ -0,0 +1,177 @@
+// SPDX-License-Identifier: GPL-3.0
+
+#[cfg(any(feature = "contract", feature = "parachain"))]
+use anyhow::anyhow;
+use clap::{Args, Subcommand};
+#[cfg(feature = "contract")]
+use pop_contracts::{Environment, SubmittedExtrinsic, UnsignedExtrinsic};
+#[cfg(feature = "parachain")]
+use pop_parachains::{configure_node, node_binary};
+#[cfg(feature = "contract")]
+use qrcode::{render::unicode::Dense1x2, QrCode};
+#[cfg(feature = "parachain")]
+use std::{
+	fs,
+	path::{Path, PathBuf},
+};
+
+pub(crate) mod export;
+pub(crate) mod generate;
+pub(crate) mod import;
+pub(crate) mod list;
+#[cfg(feature = "parachain")]
+pub(crate) mod node_key;
+#[cfg(feature = "parachain")]
+pub(crate) mod session_keys;
+
+#[derive(Args)]
+#[command(args_conflicts_with_subcommands = true)]
+pub(crate) struct KeyArgs {
+	#[command(subcommand)]
+	pub command: KeyCommands,
+}
+
+#[derive(Subcommand)]
+pub(crate) enum KeyCommands {
+	/// Generate a key, stored encrypted within the local keystore
+	#[clap(alias = "g")]
+	Generate(generate::GenerateKeyCommand),
+	/// Import a key into the local keystore
+	#[clap(alias = "i")]
+	Import(import::ImportKeyCommand),
+	/// List the keys within the local keystore
+	#[clap(alias = "l")]
+	List(list::ListKeysCommand),
+	/// Export the secret of a key within the local keystore
+	#[clap(alias = "e")]
+	Export(export::ExportKeyCommand),
+	/// Generate the network key of a node, so that its peer id is stable
+	#[cfg(feature = "parachain")]
+	GenerateNodeKey(node_key::GenerateNodeKeyCommand),
+	/// Insert the session keys of a collator or validator into the keystore of a node
+	#[cfg(feature = "parachain")]
+	InsertSessionKeys(session_keys::InsertSessionKeysCommand),
+}
+
+/// Resolves the node binary managing keys: either the provided `binary`, or the node of the latest
+/// release build of the parachain project at `path`.
+///
+/// # Arguments
+///
+/// * `path` - path to the parachain project
+/// * `binary` - a node binary to use instead
+#[cfg(feature = "parachain")]
+fn resolve_node(path: &Option<PathBuf>, binary: &Option<PathBuf>) -> anyhow::Result<PathBuf> {
+	match binary {
+		Some(binary) if binary.exists() => Ok(binary.clone()),
+		Some(binary) => Err(anyhow!("{} does not exist", binary.display())),
+		None => Ok(node_binary(&crate::project_path(path.as_deref()))?),
+	}
+}
+
+/// Updates the network configuration file `network`, so that the node named `node` is launched
+/// with `arg`.
+///
+/// # Arguments
+///
+/// * `network` - the network configuration file
+/// * `node` - the name of the node within the network
+/// * `arg` - the argument of the node
+#[cfg(feature = "parachain")]
+fn configure_network(network: &Path, node: &str, arg: String) -> anyhow::Result<()> {
+	let content = fs::read_to_string(network)?;
+	fs::write(network, configure_node(&content, node, &[arg])?)?;
+	Ok(())
+}
+
+/// Resolves the secret key URI used for signing, either from the provided `suri` or from a key
+/// within the local keystore, falling back to the default signer of the selected `environment`.
+///
+/// # Arguments
+///
+/// * `suri` - a secret key URI provided on the command line
+/// * `account` - the name of a key within the local keystore
+/// * `use_wallet` - whether the user should select a key from the local keystore
+/// * `environment` - the selected environment
+#[cfg(feature = "contract")]
+pub(crate) fn resolve_suri(
+	suri: Option<&str>,
+	account: Option<&str>,
+	use_wallet: bool,
+	environment: &Environment,
+) -> anyhow::Result<String> {
+	if let Some(account) = account {
+		return crate::key_suri(account);
+	}
+	if use_wallet {
+		let keystore = crate::keystore()?;
+		let names = keystore.list()?;
+		if names.is_empty() {
+			return Err(anyhow!(
+				"the local keystore is empty, add a key with `pop key generate <name>` first"
+			));
+		}
+		// Any key could sign, so one must be selected explicitly.
+		crate::prompt::ensure_interactive("--account")?;
+		let mut prompt = cliclack::select("Select the key to sign with:");
+		for name in &names {
+			prompt = prompt.item(name, name, "");
+		}
+		let name = prompt.interact()?;
+		return crate::key_suri(name);
+	}
+	if let Some(suri) = suri {
+		return Ok(suri.to_string());
+	}
+	if let Some(account) = &environment.account {
+		return crate::key_suri(account);
+	}
+	environment.suri.clone().ok_or(anyhow!(
+		"provide a secret key URI with `--suri`, or a key with `--account`, as the `{}` \
+		 environment has no default signer",
+		environment.name
+	))
+}
+
+/// Hands the payload of `unsigned` to an external signer, displaying it both as hex and as a QR
+/// code for air-gapped signers, then submits the extrinsic using the signature provided.
+///
+/// # Arguments
+///
+/// * `unsigned` - the extrinsic prepared on behalf of the signer
+/// * `signer` - the address of the account signing the extrinsic
+#[cfg(feature = "contract")]
+pub(crate) async fn sign_externally(
+	unsigned: &UnsignedExtrinsic,
+	signer: &str,
+) -> anyhow::Result<SubmittedExtrinsic> {
+	let payload = unsigned.payload_hex();
+	let qr = QrCode::new(payload.as_bytes())?.render::<Dense1x2>().quiet_zone(true).build();
+	cliclack::log::info(format!(
+		"Sign the following payload with the key of {signer}:\n{payload}"
+	))?;
+	cliclack::log::info(format!("Or scan it with your signing device:\n{qr}"))?;
+	crate::prompt::ensure_interactive("--suri` or `--account")?;
+	let signature = loop {
+		let signature: String = cliclack::input("Paste the hex encoded sr25519 signature:")
+			.placeholder("0x...")
+			.interact()?;
+		// An invalid signature can be corrected, as nothing has been submitted yet.
+		match unsigned.verify(&signature) {
+			Ok(_) => break signature,
+			Err(e) => cliclack::log::error(format!("{e}"))?,
+		}
+	};
+	let spinner = crate::progress::spinner();
+	spinner.start("Submitting the signed extrinsic...");
+	match unsigned.submit(&signature).await {
+		Ok(submitted) => {
+			spinner.stop(format!("Extrinsic {} finalized.", submitted.hash));
+			Ok(submitted)
+		},
+		Err(e) => {
+			spinner.error(format!("{e}"));
+			Err(e.into())
+		},
+	}
+}
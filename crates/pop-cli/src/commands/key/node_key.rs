@@ -0,0 +1,67 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use super::{configure_network, resolve_node};
+use crate::style::{style, Theme};
+use clap::Args;
+use cliclack::{clear_screen, intro, log, outro, outro_cancel, set_theme};
+use pop_parachains::generate_node_key;
+use std::path::PathBuf;
+
+#[derive(Args)]
+pub struct GenerateNodeKeyCommand {
+	/// The file to which the network key is written.
+	#[arg(short, long, default_value = "node-key")]
+	output: PathBuf,
+	/// Directory path of the parachain project whose node generates the key, [default: the
+	/// current project]
+	#[arg(short = 'p', long)]
+	path: Option<PathBuf>,
+	/// A node binary generating the key, rather than the node of the parachain project.
+	#[arg(long, conflicts_with = "path")]
+	binary: Option<PathBuf>,
+	/// A network configuration file to update, so that the node named by `--node` is launched
+	/// with the key.
+	#[arg(long, requires = "node")]
+	network: Option<PathBuf>,
+	/// The name of the node within the network configuration (e.g. `collator-01`).
+	#[arg(long, requires = "network")]
+	node: Option<String>,
+}
+
+impl GenerateNodeKeyCommand {
+	pub(crate) fn execute(&self) -> anyhow::Result<()> {
+		clear_screen()?;
+		intro(format!("{}: Generate a node key", style(" Pop CLI ").black().on_magenta()))?;
+		set_theme(Theme);
+
+		let binary = match resolve_node(&self.path, &self.binary) {
+			Ok(binary) => binary,
+			Err(e) => {
+				outro_cancel(format!("{e}"))?;
+				return Ok(());
+			},
+		};
+		let peer_id = match generate_node_key(&binary, &self.output) {
+			Ok(peer_id) => peer_id,
+			Err(e) => {
+				outro_cancel(format!("{e}"))?;
+				return Ok(());
+			},
+		};
+		log::success(format!(
+			"Node key written to {}\n   peer id: {}",
+			self.output.display(),
+			style(&peer_id).bold()
+		))?;
+		if let (Some(network), Some(node)) = (&self.network, &self.node) {
+			let arg = format!("--node-key-file={}", self.output.canonicalize()?.display());
+			if let Err(e) = configure_network(network, node, arg) {
+				outro_cancel(format!("{e}"))?;
+				return Ok(());
+			}
+			log::info(format!("`{node}` is launched with the key by {}", network.display()))?;
+		}
+		outro(format!("Use the peer id {peer_id} within the bootnodes of the chain."))?;
+		Ok(())
+	}
+}
@@ -0,0 +1,98 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use super::{configure_network, resolve_node};
+use crate::{
+	prompt,
+	style::{style, Theme},
+};
+use clap::Args;
+use cliclack::{clear_screen, intro, log, outro, outro_cancel, set_theme};
+use pop_parachains::{insert_session_keys, SessionKey};
+use std::{fs, path::PathBuf};
+
+#[derive(Args)]
+pub struct InsertSessionKeysCommand {
+	/// The keystore directory into which the keys are inserted, as provided to a node with
+	/// `--keystore-path`.
+	#[arg(short, long)]
+	keystore: PathBuf,
+	/// Secret key URI from which the keys are derived. If not provided, it is prompted for so that
+	/// it does not appear within the shell history.
+	#[arg(short, long)]
+	suri: Option<String>,
+	/// A session key to insert: `aura`, `babe`, `grandpa`, `para-validator`, `para-assignment`,
+	/// `authority-discovery` or `beefy`. Can be specified multiple times. [default: the keys of a
+	/// collator, or those of a validator when `--validator` is specified]
+	#[arg(long = "key-type")]
+	key_types: Vec<SessionKey>,
+	/// Insert the session keys of a relay chain validator, rather than those of a collator.
+	#[arg(long, conflicts_with = "key_types")]
+	validator: bool,
+	/// The chain spec of the node, if not its default.
+	#[arg(long)]
+	chain: Option<String>,
+	/// Directory path of the parachain project whose node inserts the keys, [default: the
+	/// current project]
+	#[arg(short = 'p', long)]
+	path: Option<PathBuf>,
+	/// A node binary inserting the keys, rather than the node of the parachain project.
+	#[arg(long, conflicts_with = "path")]
+	binary: Option<PathBuf>,
+	/// A network configuration file to update, so that the node named by `--node` is launched
+	/// with the keystore.
+	#[arg(long, requires = "node")]
+	network: Option<PathBuf>,
+	/// The name of the node within the network configuration (e.g. `collator-02`).
+	#[arg(long, requires = "network")]
+	node: Option<String>,
+}
+
+impl InsertSessionKeysCommand {
+	pub(crate) fn execute(&self) -> anyhow::Result<()> {
+		clear_screen()?;
+		intro(format!("{}: Insert session keys", style(" Pop CLI ").black().on_magenta()))?;
+		set_theme(Theme);
+
+		let binary = match resolve_node(&self.path, &self.binary) {
+			Ok(binary) => binary,
+			Err(e) => {
+				outro_cancel(format!("{e}"))?;
+				return Ok(());
+			},
+		};
+		let suri = match &self.suri {
+			Some(suri) => suri.clone(),
+			None => prompt::password("Enter the secret key URI of the keys:", "--suri")?,
+		};
+		let keys = match (self.key_types.is_empty(), self.validator) {
+			(false, _) => self.key_types.clone(),
+			(true, true) => SessionKey::VALIDATOR.to_vec(),
+			(true, false) => SessionKey::COLLATOR.to_vec(),
+		};
+		fs::create_dir_all(&self.keystore)?;
+		if let Err(e) =
+			insert_session_keys(&binary, &self.keystore, self.chain.as_deref(), &suri, &keys)
+		{
+			outro_cancel(format!("{e}"))?;
+			return Ok(());
+		}
+		log::success(format!(
+			"Inserted the {} key(s) into {}",
+			keys.iter().map(|k| k.to_string()).collect::<Vec<_>>().join(", "),
+			self.keystore.display()
+		))?;
+		if let (Some(network), Some(node)) = (&self.network, &self.node) {
+			let arg = format!("--keystore-path={}", self.keystore.canonicalize()?.display());
+			if let Err(e) = configure_network(network, node, arg) {
+				outro_cancel(format!("{e}"))?;
+				return Ok(());
+			}
+			log::info(format!("`{node}` is launched with the keystore by {}", network.display()))?;
+		}
+		outro(
+			"The keys must also be registered on-chain (e.g. as session keys or within the \
+			 genesis of the chain) for the node to author blocks.",
+		)?;
+		Ok(())
+	}
+}
@@ -1,8 +1,34 @@
 // SPDX-License-Identifier: GPL-3.0
 
+#[cfg(feature = "parachain")]
+pub(crate) mod bench;
+#[cfg(any(feature = "parachain", feature = "contract"))]
+pub(crate) mod binaries;
 pub(crate) mod build;
+#[cfg(any(feature = "parachain", feature = "contract"))]
+pub(crate) mod cache;
 pub(crate) mod call;
+pub(crate) mod clean;
+pub(crate) mod completions;
+pub(crate) mod config;
+#[cfg(feature = "contract")]
+pub(crate) mod convert;
+#[cfg(any(feature = "parachain", feature = "contract"))]
+pub(crate) mod doctor;
+pub(crate) mod history;
+pub(crate) mod inspect;
 pub(crate) mod install;
+pub(crate) mod key;
 pub(crate) mod new;
+pub(crate) mod plugin;
+#[cfg(any(feature = "parachain", feature = "contract"))]
+pub(crate) mod query;
+#[cfg(feature = "telemetry")]
+pub(crate) mod telemetry;
 pub(crate) mod test;
 pub(crate) mod up;
+#[cfg(any(feature = "parachain", feature = "contract"))]
+pub(crate) mod update;
+pub(crate) mod verify;
+#[cfg(feature = "parachain")]
+pub(crate) mod wallet;
@@ -1,13 +1,33 @@
 // SPDX-License-Identifier: GPL-3.0
 
-use std::{env::current_dir, fs, path::PathBuf};
+use std::{
+	env::current_dir,
+	fs,
+	path::{Path, PathBuf},
+	str::FromStr,
+};
 
-use clap::Args;
-use cliclack::{clear_screen, confirm, intro, outro, outro_cancel, set_theme};
+use anyhow::Result;
+use clap::{
+	builder::{PossibleValue, PossibleValuesParser, TypedValueParser},
+	Args,
+};
+use cliclack::{intro, log, outro, outro_cancel, set_theme};
 use console::style;
+use serde::Serialize;
+use strum::VariantArray;
 
-use crate::style::Theme;
-use pop_contracts::create_smart_contract;
+use crate::{
+	commands::history,
+	dry_run::{created, dry_run, report, Effect},
+	output::OutputArgs,
+	prompt,
+	style::Theme,
+};
+use pop_common::{Project, Token};
+use pop_contracts::{
+	create_smart_contract, create_token_contract, resolve_ink_version, Template, TokenConfig,
+};
 
 #[derive(Args)]
 pub struct NewContractCommand {
@@ -15,46 +35,197 @@ pub struct NewContractCommand {
 	pub(crate) name: String,
 	#[arg(short = 'p', long, help = "Path for the contract project, [default: current directory]")]
 	pub(crate) path: Option<PathBuf>,
+	#[arg(
+		long,
+		help = "The ink! version to target (e.g. 5.0), [default: version of the default template]"
+	)]
+	pub(crate) ink_version: Option<String>,
+	#[arg(long, help = "Include end-to-end tests, run with `pop test contract --e2e`")]
+	pub(crate) e2e: bool,
+	#[arg(
+		short = 't',
+		long,
+		help = "Template to use.",
+		value_parser = crate::enum_variants!(Template, Template::description)
+	)]
+	pub(crate) template: Option<Template>,
+	#[arg(long, help = "Name of the token, for token templates")]
+	pub(crate) token_name: Option<String>,
+	#[arg(long, help = "Symbol of the token, for token templates")]
+	pub(crate) symbol: Option<String>,
+	#[arg(long, help = "Decimals of the token, for fungible token templates")]
+	pub(crate) decimals: Option<u8>,
+	#[arg(
+		long,
+		help = "Initial supply of the token in whole units, minted to the deployer, for fungible token templates"
+	)]
+	pub(crate) supply: Option<u128>,
+	#[arg(long, help = "Remove an existing directory of the same name, rather than asking")]
+	pub(crate) force: bool,
+	#[command(flatten)]
+	pub(crate) output: OutputArgs,
+}
+
+/// The summary of a generated contract, as output by `--output json`.
+#[derive(Serialize)]
+struct NewContractReport<'a> {
+	name: &'a str,
+	path: PathBuf,
+	template: &'a str,
+	ink_version: Option<String>,
 }
 
 impl NewContractCommand {
-	pub(crate) async fn execute(&self) -> anyhow::Result<()> {
-		clear_screen()?;
+	pub(crate) async fn execute(&self) -> Result<Template> {
+		self.output.clear_screen()?;
 		intro(format!(
 			"{}: Generating new contract \"{}\"!",
 			style(" Pop CLI ").black().on_magenta(),
 			&self.name,
 		))?;
 		set_theme(Theme);
+		let template = self.template.clone().unwrap_or_default();
+		let ink_version = match self.ink_version.as_deref().map(resolve_ink_version).transpose() {
+			Ok(version) => version,
+			Err(e) => {
+				outro_cancel(format!("{e}"))?;
+				return Ok(template);
+			},
+		};
+		let config = self.token_config(&template)?;
 		let contract_path = if let Some(ref path) = self.path {
 			path.join(&self.name)
 		} else {
 			current_dir()?.join(&self.name)
 		};
+		if dry_run() {
+			// Generated within a temporary directory, reporting the files it would create.
+			let temp_dir = tempfile::tempdir()?;
+			self.generate(temp_dir.path(), &template, config.as_ref(), ink_version.as_deref())?;
+			let mut effects = Vec::new();
+			if contract_path.exists() {
+				effects.push(Effect::Remove(contract_path.clone()));
+			}
+			effects.extend(created(temp_dir.path(), &contract_path)?);
+			report(&effects)?;
+			return Ok(template);
+		}
 		if contract_path.exists() {
-			if !confirm(format!(
-				"\"{}\" directory already exists. Would you like to remove it?",
-				contract_path.display()
-			))
-			.interact()?
-			{
+			if !self.force
+				&& !prompt::confirm(
+					format!(
+						"\"{}\" directory already exists. Would you like to remove it?",
+						contract_path.display()
+					),
+					false,
+				)? {
 				outro_cancel(format!(
-					"Cannot generate contract until \"{}\" directory is removed.",
+					"Cannot generate contract until \"{}\" directory is removed, or `--force` is used.",
 					contract_path.display()
 				))?;
-				return Ok(());
+				return Ok(template);
 			}
 			fs::remove_dir_all(contract_path.as_path())?;
 		}
-		fs::create_dir_all(contract_path.as_path())?;
-		let spinner = cliclack::spinner();
+		let spinner = crate::progress::spinner();
 		spinner.start("Generating contract...");
-		create_smart_contract(&self.name, contract_path.as_path())?;
+		self.generate(&contract_path, &template, config.as_ref(), ink_version.as_deref())?;
 
-		spinner.stop("Smart contract created!");
+		match &ink_version {
+			Some(version) => {
+				spinner.stop(format!("Smart contract created targeting ink! {version}!"))
+			},
+			None => spinner.stop("Smart contract created!"),
+		}
+		history::record(
+			&contract_path,
+			"new contract",
+			&[],
+			vec![contract_path.display().to_string()],
+		)?;
 		outro(format!("cd into \"{}\" and enjoy hacking! 🚀", contract_path.display()))?;
+		self.output.report(&NewContractReport {
+			name: &self.name,
+			path: fs::canonicalize(&contract_path)?,
+			template: template.as_ref(),
+			ink_version,
+		})?;
+		Ok(template)
+	}
+
+	/// Generates the contract at `path`, recording how it was generated within its pop.toml.
+	///
+	/// # Arguments
+	///
+	/// * `path` - the directory of the contract
+	/// * `template` - the template generated
+	/// * `config` - the customization of a token template
+	/// * `ink_version` - the ink! version targeted, if not that of the template
+	fn generate(
+		&self,
+		path: &Path,
+		template: &Template,
+		config: Option<&TokenConfig>,
+		ink_version: Option<&str>,
+	) -> Result<()> {
+		fs::create_dir_all(path)?;
+		match config {
+			Some(config) => create_token_contract(&self.name, path, template, config, ink_version)?,
+			None => create_smart_contract(&self.name, path, ink_version, self.e2e)?,
+		}
+		let project = Project {
+			kind: "contract".into(),
+			template: template.as_ref().to_string(),
+			sdk_version: ink_version.map(|v| v.to_string()),
+			token: config.map(|config| Token {
+				symbol: config.symbol.clone(),
+				decimals: config.decimals,
+				endowment: None,
+			}),
+			..Default::default()
+		};
+		project.save(path)?;
 		Ok(())
 	}
+
+	/// Resolves the customization of a token template, prompting for any values not provided.
+	fn token_config(&self, template: &Template) -> Result<Option<TokenConfig>> {
+		if !template.is_token() {
+			if self.token_name.is_some()
+				|| self.symbol.is_some()
+				|| self.decimals.is_some()
+				|| self.supply.is_some()
+			{
+				log::warning("Customization options are not available for this template")?;
+			}
+			return Ok(None);
+		}
+		if self.e2e {
+			log::warning("End-to-end tests are not available for this template")?;
+		}
+		let name = match &self.token_name {
+			Some(name) => name.clone(),
+			None => prompt::input("What is the name of your token?", "My Token")?,
+		};
+		let symbol = match &self.symbol {
+			Some(symbol) => symbol.clone(),
+			None => prompt::input("What is the symbol of your token?", "UNIT")?,
+		};
+		let fungible = matches!(template, Template::PSP22);
+		let decimals = match self.decimals {
+			Some(decimals) => decimals,
+			None if fungible => prompt::input("How many token decimals?", "12")?.parse()?,
+			None => 0,
+		};
+		let supply = match self.supply {
+			Some(supply) => supply,
+			None if fungible => {
+				prompt::input("And the initial supply, in whole tokens?", "1000000")?.parse()?
+			},
+			None => 0,
+		};
+		Ok(Some(TokenConfig { name, symbol, decimals, supply }))
+	}
 }
 
 #[cfg(test)]
@@ -68,8 +239,42 @@ mod tests {
 		let command = NewContractCommand {
 			name: "test_contract".to_string(),
 			path: Some(PathBuf::from(temp_contract_dir.path())),
+			ink_version: None,
+			e2e: false,
+			template: None,
+			token_name: None,
+			symbol: None,
+			decimals: None,
+			supply: None,
+			force: false,
+			output: OutputArgs::default(),
+		};
+		assert_eq!(command.execute().await?, Template::Standard);
+		let project = Project::load(&temp_contract_dir.path().join("test_contract"))?.unwrap();
+		assert_eq!((project.kind.as_str(), project.template.as_str()), ("contract", "standard"));
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_new_token_contract_command_execute_success() -> Result<()> {
+		let temp_contract_dir = tempfile::tempdir().expect("Could not create temp dir");
+		let command = NewContractCommand {
+			name: "test_token".to_string(),
+			path: Some(PathBuf::from(temp_contract_dir.path())),
+			ink_version: None,
+			e2e: false,
+			template: Some(Template::PSP22),
+			token_name: Some("Test Token".to_string()),
+			symbol: Some("TST".to_string()),
+			decimals: Some(10),
+			supply: Some(1_000),
+			force: false,
+			output: OutputArgs::default(),
 		};
-		command.execute().await?;
+		assert_eq!(command.execute().await?, Template::PSP22);
+		let lib = fs::read_to_string(temp_contract_dir.path().join("test_token/lib.rs"))?;
+		assert!(lib.contains("const NAME: &str = \"Test Token\";"));
+		assert!(lib.contains("const INITIAL_SUPPLY: Balance = 10000000000000;"));
 		Ok(())
 	}
 }
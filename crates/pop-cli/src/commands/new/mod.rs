@@ -1,6 +1,17 @@
 // SPDX-License-Identifier: GPL-3.0
 
+use crate::{
+	prompt,
+	style::{style, Theme},
+};
 use clap::{Args, Subcommand};
+use cliclack::{clear_screen, intro, outro_cancel, set_theme};
+#[cfg(feature = "contract")]
+use pop_contracts::Template as ContractTemplate;
+#[cfg(feature = "parachain")]
+use pop_parachains::resolve_pallet_path;
+#[cfg(feature = "contract")]
+use strum::VariantArray;
 
 #[cfg(feature = "contract")]
 pub mod contract;
@@ -8,12 +19,187 @@ pub mod contract;
 pub mod pallet;
 #[cfg(feature = "parachain")]
 pub mod parachain;
+#[cfg(feature = "parachain")]
+pub mod test;
+
+#[macro_export]
+macro_rules! enum_variants {
+	($e: ty) => {{
+		PossibleValuesParser::new(
+			<$e>::VARIANTS
+				.iter()
+				.map(|p| PossibleValue::new(p.as_ref()))
+				.collect::<Vec<_>>(),
+		)
+		.try_map(|s| {
+			<$e>::from_str(&s).map_err(|e| format!("could not convert from {s} to provider"))
+		})
+	}};
+	// Describes each variant using `$help`, such that it is shown by shell completions.
+	($e: ty, $help: expr) => {{
+		PossibleValuesParser::new(
+			<$e>::VARIANTS
+				.iter()
+				.map(|p| PossibleValue::new(p.as_ref()).help($help(p)))
+				.collect::<Vec<_>>(),
+		)
+		.try_map(|s| {
+			<$e>::from_str(&s).map_err(|e| format!("could not convert from {s} to provider"))
+		})
+	}};
+}
 
 #[derive(Args)]
 #[command(args_conflicts_with_subcommands = true)]
 pub struct NewArgs {
+	/// The kind of project to generate, [default: selected by a guided wizard]
 	#[command(subcommand)]
-	pub command: NewCommands,
+	pub command: Option<NewCommands>,
+}
+
+/// The kinds of project the wizard generates.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Kind {
+	#[cfg(feature = "parachain")]
+	Parachain,
+	#[cfg(feature = "contract")]
+	Contract,
+	#[cfg(feature = "parachain")]
+	Pallet,
+}
+
+impl NewArgs {
+	/// The command generating the project selected, guiding the user through a wizard when no
+	/// kind of project is provided. Returns `None` when the user cancels the generation.
+	pub(crate) fn command(self) -> anyhow::Result<Option<NewCommands>> {
+		match self.command {
+			Some(command) => Ok(Some(command)),
+			None => guide(),
+		}
+	}
+}
+
+/// Guides the user through generating a project: selecting its kind, then its template and
+/// configuration, before confirming a summary of the project generated.
+fn guide() -> anyhow::Result<Option<NewCommands>> {
+	clear_screen()?;
+	intro(format!("{}: Generate a new project", style(" Pop CLI ").black().on_magenta()))?;
+	set_theme(Theme);
+	let kinds = vec![
+		#[cfg(feature = "parachain")]
+		(Kind::Parachain, "Parachain".into(), "A blockchain connected to a relay chain".into()),
+		#[cfg(feature = "contract")]
+		(Kind::Contract, "Smart contract".into(), "An ink! smart contract".into()),
+		#[cfg(feature = "parachain")]
+		(Kind::Pallet, "Pallet".into(), "A FRAME pallet, to be added to a runtime".into()),
+	];
+	match prompt::select("What would you like to generate?", kinds)? {
+		// The parachain wizard also selects the release of the template, fetched when run.
+		#[cfg(feature = "parachain")]
+		Kind::Parachain => Ok(Some(NewCommands::Parachain(parachain::NewParachainCommand {
+			name: None,
+			provider: None,
+			template: None,
+			release_tag: None,
+			symbol: None,
+			decimals: None,
+			initial_endowment: None,
+			para_id: None,
+			keep_on_failure: false,
+			force: false,
+			output: Default::default(),
+		}))),
+		#[cfg(feature = "contract")]
+		Kind::Contract => guide_contract(),
+		#[cfg(feature = "parachain")]
+		Kind::Pallet => guide_pallet(),
+	}
+}
+
+/// Guides the user through generating a smart contract. Token templates ask for the
+/// configuration of their token when generated.
+#[cfg(feature = "contract")]
+fn guide_contract() -> anyhow::Result<Option<NewCommands>> {
+	let templates = ContractTemplate::VARIANTS
+		.iter()
+		.map(|t| (t.clone(), t.name().to_string(), t.description().to_string()))
+		.collect();
+	let template = prompt::select("Select a contract template:", templates)?;
+	let name = prompt::input("What is the name of your contract?", "my_contract")?;
+	let path = prompt::input("Where should your project be created?", "./")?;
+	let summary = [
+		("Kind", "smart contract".to_string()),
+		("Template", template.name().to_string()),
+		("Name", name.clone()),
+		("Directory", path.clone()),
+	];
+	if !confirm_summary(&summary)? {
+		return Ok(None);
+	}
+	Ok(Some(NewCommands::Contract(contract::NewContractCommand {
+		name,
+		path: Some(path.into()),
+		ink_version: None,
+		e2e: false,
+		template: Some(template),
+		token_name: None,
+		symbol: None,
+		decimals: None,
+		supply: None,
+		force: false,
+		output: Default::default(),
+	})))
+}
+
+/// Guides the user through generating a pallet.
+#[cfg(feature = "parachain")]
+fn guide_pallet() -> anyhow::Result<Option<NewCommands>> {
+	let name = prompt::input("What is the name of your pallet?", "pallet-template")?;
+	let authors = prompt::input("Who are its authors?", "Anonymous")?;
+	let description = prompt::input("How would you describe it?", "Frame Pallet")?;
+	let path = resolve_pallet_path(None)?.display().to_string();
+	let path = prompt::input("Where should your pallet be created?", &path)?;
+	let summary = [
+		("Kind", "pallet".to_string()),
+		("Name", name.clone()),
+		("Authors", authors.clone()),
+		("Description", description.clone()),
+		("Directory", path.clone()),
+	];
+	if !confirm_summary(&summary)? {
+		return Ok(None);
+	}
+	Ok(Some(NewCommands::Pallet(pallet::NewPalletCommand {
+		name,
+		authors: Some(authors),
+		description: Some(description),
+		path: Some(path),
+		force: false,
+	})))
+}
+
+/// Shows a summary of the project about to be generated and asks for confirmation, cancelling the
+/// generation otherwise.
+///
+/// # Arguments
+///
+/// * `summary` - the configuration of the project, as labels along with their values
+pub(crate) fn confirm_summary(summary: &[(&str, String)]) -> anyhow::Result<bool> {
+	cliclack::note("Summary", format_summary(summary))?;
+	if !prompt::confirm("Generate the project?", true)? {
+		outro_cancel("🚫 Generation cancelled.")?;
+		return Ok(false);
+	}
+	Ok(true)
+}
+
+/// Formats a summary, each label on its own line, along with its value.
+fn format_summary(summary: &[(&str, String)]) -> String {
+	summary
+		.iter()
+		.map(|(label, value)| format!("{label}: {value}"))
+		.collect::<Vec<_>>()
+		.join("\n")
 }
 
 #[derive(Subcommand)]
@@ -26,8 +212,32 @@ pub enum NewCommands {
 	#[cfg(feature = "parachain")]
 	#[clap(alias = "m")] // (m)odule, as p used above
 	Pallet(pallet::NewPalletCommand),
+	/// Generate a new test
+	#[cfg(feature = "parachain")]
+	#[clap(alias = "t")]
+	Test(test::NewTestArgs),
 	/// Generate a new smart contract
 	#[cfg(feature = "contract")]
 	#[clap(alias = "c")]
 	Contract(contract::NewContractCommand),
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{Cli, Commands::New};
+	use clap::Parser;
+
+	#[test]
+	fn new_without_kind_parses() {
+		let cli = Cli::parse_from(["pop", "new"]);
+		let New(args) = cli.command else { panic!("unable to parse command") };
+		assert!(args.command.is_none());
+	}
+
+	#[test]
+	fn format_summary_works() {
+		let summary = [("Kind", "pallet".to_string()), ("Name", "pallet-template".to_string())];
+		assert_eq!(format_summary(&summary), "Kind: pallet\nName: pallet-template");
+	}
+}
@@ -1,7 +1,12 @@
 // SPDX-License-Identifier: GPL-3.0
-use crate::style::Theme;
+use crate::{
+	commands::history,
+	dry_run::{created, dry_run, report, Effect},
+	prompt,
+	style::Theme,
+};
 use clap::Args;
-use cliclack::{clear_screen, confirm, intro, outro, outro_cancel, set_theme};
+use cliclack::{clear_screen, intro, outro, outro_cancel, set_theme};
 use console::style;
 use pop_parachains::{create_pallet_template, resolve_pallet_path, TemplatePalletConfig};
 use std::fs;
@@ -14,8 +19,14 @@ pub struct NewPalletCommand {
 	pub(crate) authors: Option<String>,
 	#[arg(short, long, help = "Pallet description", default_value = "Frame Pallet")]
 	pub(crate) description: Option<String>,
-	#[arg(short = 'p', long, help = "Path to the pallet, [default: current directory]")]
+	#[arg(
+		short = 'p',
+		long,
+		help = "Path to the pallet, or to a parachain project to place it within its pallets directory, [default: the pallets directory of the current project]"
+	)]
 	pub(crate) path: Option<String>,
+	#[arg(long, help = "Remove an existing directory of the same name, rather than asking")]
+	pub(crate) force: bool,
 }
 
 impl NewPalletCommand {
@@ -30,34 +41,53 @@ impl NewPalletCommand {
 		let target = resolve_pallet_path(self.path.clone())?;
 		let pallet_name = self.name.clone();
 		let pallet_path = target.join(pallet_name.clone());
+		if dry_run() {
+			// Generated within a temporary directory, reporting the files it would create.
+			let temp_dir = tempfile::tempdir()?;
+			create_pallet_template(Some(temp_dir.path().display().to_string()), self.config())?;
+			let mut effects = Vec::new();
+			if pallet_path.exists() {
+				effects.push(Effect::Remove(pallet_path.clone()));
+			}
+			effects.extend(created(&temp_dir.path().join(&pallet_name), &pallet_path)?);
+			return report(&effects);
+		}
 		if pallet_path.exists() {
-			if !confirm(format!(
-				"\"{}\" directory already exists. Would you like to remove it?",
-				pallet_path.display()
-			))
-			.interact()?
-			{
+			if !self.force
+				&& !prompt::confirm(
+					format!(
+						"\"{}\" directory already exists. Would you like to remove it?",
+						pallet_path.display()
+					),
+					false,
+				)? {
 				outro_cancel(format!(
-					"Cannot generate pallet until \"{}\" directory is removed.",
+					"Cannot generate pallet until \"{}\" directory is removed, or `--force` is used.",
 					pallet_path.display()
 				))?;
 				return Ok(());
 			}
-			fs::remove_dir_all(pallet_path)?;
+			fs::remove_dir_all(&pallet_path)?;
 		}
-		let spinner = cliclack::spinner();
+		let spinner = crate::progress::spinner();
 		spinner.start("Generating pallet...");
-		create_pallet_template(
-			self.path.clone(),
-			TemplatePalletConfig {
-				name: self.name.clone(),
-				authors: self.authors.clone().expect("default values"),
-				description: self.description.clone().expect("default values"),
-			},
-		)?;
+		create_pallet_template(self.path.clone(), self.config())?;
 
 		spinner.stop("Generation complete");
+		// Recorded within the project the pallet was added to, if any.
+		if let Some(project) = pop_common::project::find_root(&target) {
+			history::record(&project, "new pallet", &[], vec![pallet_path.display().to_string()])?;
+		}
 		outro(format!("cd into \"{}\" and enjoy hacking! 🚀", &self.name))?;
 		Ok(())
 	}
+
+	/// The configuration of the pallet generated.
+	fn config(&self) -> TemplatePalletConfig {
+		TemplatePalletConfig {
+			name: self.name.clone(),
+			authors: self.authors.clone().expect("default values"),
+			description: self.description.clone().expect("default values"),
+		}
+	}
 }
@@ -1,19 +1,34 @@
 // SPDX-License-Identifier: GPL-3.0
-use crate::style::{style, Theme};
+use crate::{
+	commands::{history, new::confirm_summary, up::ProgressReporter},
+	dry_run::{created, dry_run, report, Effect},
+	output::OutputArgs,
+	prompt,
+	rollback::Rollback,
+	style::{style, Theme},
+};
 use anyhow::Result;
 use clap::{
 	builder::{PossibleValue, PossibleValuesParser, TypedValueParser},
 	Args,
 };
-use std::{fs, path::Path, str::FromStr};
+use serde::Serialize;
+use std::{
+	fs,
+	path::{Path, PathBuf},
+	str::FromStr,
+};
 
-use cliclack::{clear_screen, confirm, input, intro, log, outro, outro_cancel, set_theme};
+use cliclack::{intro, log, outro, outro_cancel, set_theme};
+use pop_common::{Project, Token};
 use pop_parachains::{
-	instantiate_template_dir, is_initial_endowment_valid, Config, Git, GitHub, Provider, Release,
-	Template,
+	instantiate_template_dir, is_initial_endowment_valid, Config, Git, GitHub, PolkadotRelease,
+	Provider, Release, Template,
 };
 use strum::VariantArray;
 
+const DEFAULT_SYMBOL: &str = "UNIT";
+const DEFAULT_DECIMALS: u8 = 12;
 const DEFAULT_INITIAL_ENDOWMENT: &str = "1u64 << 60";
 
 #[derive(Args, Clone)]
@@ -21,16 +36,15 @@ pub struct NewParachainCommand {
 	#[arg(help = "Name of the project. If empty assistance in the process will be provided.")]
 	pub(crate) name: Option<String>,
 	#[arg(
-		help = "Template provider.",
-		default_value = Provider::Pop.as_ref(),
-		value_parser = crate::enum_variants!(Provider)
+		help = "Template provider, [default: the configured provider, otherwise pop]",
+		value_parser = crate::enum_variants!(Provider, Provider::description)
 	)]
 	pub(crate) provider: Option<Provider>,
 	#[arg(
 		short = 't',
 		long,
 		help = "Template to use.",
-		value_parser = crate::enum_variants!(Template)
+		value_parser = crate::enum_variants!(Template, Template::description)
 	)]
 	pub(crate) template: Option<Template>,
 	#[arg(
@@ -39,42 +53,57 @@ pub struct NewParachainCommand {
 		help = "Release tag to use for template. If empty, latest release will be used."
 	)]
 	pub(crate) release_tag: Option<String>,
-	#[arg(long, short, help = "Token Symbol", default_value = "UNIT")]
+	#[arg(long, short, help = "Token symbol [default: UNIT]", value_parser = parse_symbol)]
 	pub(crate) symbol: Option<String>,
-	#[arg(long, short, help = "Token Decimals", default_value = "12")]
+	#[arg(
+		long,
+		short,
+		help = "Token decimals, from 0 to 255 [default: 12]",
+		value_parser = parse_decimals
+	)]
 	pub(crate) decimals: Option<u8>,
 	#[arg(
-		long = "endowment",
+		long,
+		visible_alias = "endowment",
 		short,
-		help = "Token Endowment for dev accounts",
-		default_value = DEFAULT_INITIAL_ENDOWMENT
+		help = "Initial endowment of dev accounts, e.g. 1_000_000 or 1u64 << 60 [default: 1u64 << 60]",
+		value_parser = parse_initial_endowment
 	)]
 	pub(crate) initial_endowment: Option<String>,
+	#[arg(
+		long,
+		help = "Identifier of the parachain, recorded within the project's pop.toml and used when generating its chain spec"
+	)]
+	pub(crate) para_id: Option<u32>,
+	/// Keep the partially generated parachain when generation fails, for debugging, rather than
+	/// removing it.
+	#[arg(long)]
+	pub(crate) keep_on_failure: bool,
+	/// Remove an existing directory of the same name, rather than asking.
+	#[arg(long)]
+	pub(crate) force: bool,
+	#[command(flatten)]
+	pub(crate) output: OutputArgs,
 }
 
-#[macro_export]
-macro_rules! enum_variants {
-	($e: ty) => {{
-		PossibleValuesParser::new(
-			<$e>::VARIANTS
-				.iter()
-				.map(|p| PossibleValue::new(p.as_ref()))
-				.collect::<Vec<_>>(),
-		)
-		.try_map(|s| {
-			<$e>::from_str(&s).map_err(|e| format!("could not convert from {s} to provider"))
-		})
-	}};
+/// The summary of a generated parachain, as output by `--output json`.
+#[derive(Serialize)]
+struct NewParachainReport<'a> {
+	name: &'a str,
+	path: PathBuf,
+	provider: &'a str,
+	template: &'a str,
+	release: Option<String>,
 }
 
 impl NewParachainCommand {
 	pub(crate) async fn execute(&self) -> Result<Template> {
-		clear_screen()?;
+		self.output.clear_screen()?;
 		set_theme(Theme);
 
 		let parachain_config = if self.name.is_none() {
 			// If user doesn't select the name guide them to generate a parachain.
-			guide_user_to_generate_parachain().await?
+			guide_user_to_generate_parachain(&self.output).await?
 		} else {
 			self.clone()
 		};
@@ -83,7 +112,10 @@ impl NewParachainCommand {
 			.name
 			.clone()
 			.expect("name can not be none as fallback above is interactive input; qed");
-		let provider = &parachain_config.provider.clone().unwrap_or_default();
+		let provider = &match parachain_config.provider.clone() {
+			Some(provider) => provider,
+			None => configured_provider()?,
+		};
 		let template = match &parachain_config.template {
 			Some(template) => template.clone(),
 			None => provider.default_template(), // Each provider has a template by default
@@ -97,67 +129,100 @@ impl NewParachainCommand {
 			parachain_config.initial_endowment.clone(),
 		)?;
 
-		let tag_version = parachain_config.release_tag.clone();
+		let tag_version = parachain_config
+			.release_tag
+			.clone()
+			.or_else(|| crate::config().release_tag(template.as_ref()));
 
-		generate_parachain_from_template(name, provider, &template, tag_version, config)?;
+		let release = generate_parachain_from_template(
+			name,
+			provider,
+			&template,
+			tag_version,
+			config,
+			parachain_config.para_id,
+			self.keep_on_failure,
+			self.force,
+		)?;
+		if dry_run() {
+			return Ok(template);
+		}
+		history::record(Path::new(name), "new parachain", &[], vec![name.clone()])?;
+		self.output.report(&NewParachainReport {
+			name,
+			path: fs::canonicalize(name)?,
+			provider: provider.as_ref(),
+			template: template.as_ref(),
+			release,
+		})?;
 		Ok(template)
 	}
 }
 
-async fn guide_user_to_generate_parachain() -> Result<NewParachainCommand> {
+async fn guide_user_to_generate_parachain(output: &OutputArgs) -> Result<NewParachainCommand> {
 	intro(format!("{}: Generate a parachain", style(" Pop CLI ").black().on_magenta()))?;
 
-	let mut prompt = cliclack::select("Select a template provider: ".to_string());
-	for (i, provider) in Provider::providers().iter().enumerate() {
-		if i == 0 {
-			prompt = prompt.initial_value(provider);
-		}
-		prompt = prompt.item(
-			provider,
-			provider.name(),
-			format!(
+	let providers = Provider::providers()
+		.iter()
+		.map(|provider| {
+			let hint = format!(
 				"{} {} available option(s) {}",
 				provider.description(),
 				provider.templates().len(),
 				if provider.name() == "Parity" { "[deprecated]" } else { "" }
-			),
-		);
-	}
-	let provider = prompt.interact()?;
+			);
+			(provider, provider.name().to_string(), hint)
+		})
+		.collect();
+	let provider = prompt::select("Select a template provider: ", providers)?;
 	let template = display_select_options(provider)?;
 
 	let url = url::Url::parse(&template.repository_url()?).expect("valid repository url");
-	let latest_3_releases = GitHub::get_latest_n_releases(3, &url).await?;
+	// Releases are not listed when offline, with the latest being generated.
+	let latest_3_releases = GitHub::get_latest_n_releases(3, &url).await.unwrap_or_default();
 
 	let mut release_name = None;
 	if latest_3_releases.len() > 0 {
 		release_name = Some(display_release_versions_to_user(latest_3_releases)?);
 	}
 
-	let name: String = input("Where should your project be created?")
-		.placeholder("./my-parachain")
-		.default_input("./my-parachain")
-		.interact()?;
-
-	let mut customizable_options = Config {
-		symbol: "UNIT".to_string(),
-		decimals: 12,
-		initial_endowment: "1u64 << 60".to_string(),
-	};
-	if template.matches(&Provider::Pop) {
-		customizable_options = prompt_customizable_options()?;
+	let name = prompt::input("Where should your project be created?", "./my-parachain")?;
+
+	let customizable_options =
+		template.matches(&Provider::Pop).then(prompt_customizable_options).transpose()?;
+
+	let mut summary = vec![
+		("Kind", "parachain".to_string()),
+		("Provider", provider.name().to_string()),
+		("Template", template.name().to_string()),
+		("Release", release_name.clone().unwrap_or_else(|| "latest".into())),
+		("Directory", name.clone()),
+	];
+	if let Some(options) = &customizable_options {
+		summary.extend([
+			("Token symbol", options.symbol.clone()),
+			("Token decimals", options.decimals.to_string()),
+			("Initial endowment", options.initial_endowment.clone()),
+		]);
+	}
+	if !confirm_summary(&summary)? {
+		return Err(anyhow::anyhow!("generation cancelled"));
 	}
 
-	clear_screen()?;
+	output.clear_screen()?;
 
 	Ok(NewParachainCommand {
 		name: Some(name),
 		provider: Some(provider.clone()),
 		template: Some(template.clone()),
 		release_tag: release_name,
-		symbol: Some(customizable_options.symbol),
-		decimals: Some(customizable_options.decimals),
-		initial_endowment: Some(customizable_options.initial_endowment),
+		symbol: customizable_options.as_ref().map(|options| options.symbol.clone()),
+		decimals: customizable_options.as_ref().map(|options| options.decimals),
+		initial_endowment: customizable_options.map(|options| options.initial_endowment),
+		para_id: None,
+		keep_on_failure: false,
+		force: false,
+		output: output.clone(),
 	})
 }
 fn generate_parachain_from_template(
@@ -166,7 +231,10 @@ fn generate_parachain_from_template(
 	template: &Template,
 	tag_version: Option<String>,
 	config: Config,
-) -> Result<()> {
+	para_id: Option<u32>,
+	keep_on_failure: bool,
+	force: bool,
+) -> Result<Option<String>> {
 	intro(format!(
 		"{}: Generating \"{}\" using {:?} from {:?}!",
 		style(" Pop CLI ").black().on_magenta(),
@@ -175,18 +243,34 @@ fn generate_parachain_from_template(
 		provider
 	))?;
 
-	let destination_path = check_destination_path(name_template)?;
+	if dry_run() {
+		return dry_run_parachain(name_template, provider, template, tag_version, config, para_id);
+	}
+	let destination_path = check_destination_path(name_template, force)?;
+	// Removes the partially generated parachain should any step below fail.
+	let mut rollback = Rollback::new(keep_on_failure);
+	rollback.track(destination_path);
 
-	let spinner = cliclack::spinner();
+	let spinner = crate::progress::spinner();
 	spinner.start("Generating parachain...");
-	let tag = instantiate_template_dir(template, destination_path, tag_version, config)?;
+	let tag = instantiate_template_dir(
+		template,
+		destination_path,
+		tag_version,
+		config.clone(),
+		crate::cache().ok().as_deref(),
+		ProgressReporter(&spinner),
+	)?;
+	// Recorded before the initial commit, so that it is part of the project.
+	project(provider, template, &tag, &config, para_id, destination_path).save(destination_path)?;
 	if let Err(err) = Git::git_init(destination_path, "initialized parachain") {
 		if err.class() == git2::ErrorClass::Config && err.code() == git2::ErrorCode::NotFound {
 			outro_cancel("git signature could not be found. Please configure your git config with your name and email")?;
 		}
 	}
 	spinner.stop("Generation complete");
-	if let Some(tag) = tag {
+	rollback.complete();
+	if let Some(tag) = &tag {
 		log::info(format!("Version: {}", tag))?;
 	}
 
@@ -196,7 +280,88 @@ fn generate_parachain_from_template(
 
 	outro(format!("cd into \"{}\" and enjoy hacking! 🚀", name_template))?;
 
-	Ok(())
+	Ok(tag)
+}
+
+/// Reports the files generating the parachain would create, as generated within a temporary
+/// directory, without creating them.
+fn dry_run_parachain(
+	name_template: &str,
+	provider: &Provider,
+	template: &Template,
+	tag_version: Option<String>,
+	config: Config,
+	para_id: Option<u32>,
+) -> Result<Option<String>> {
+	let destination_path = Path::new(name_template);
+	let temp_dir = tempfile::tempdir()?;
+	let generated = temp_dir.path().join("parachain");
+	let spinner = crate::progress::spinner();
+	spinner.start("Generating parachain in a temporary directory...");
+	let tag = instantiate_template_dir(
+		template,
+		&generated,
+		tag_version,
+		config.clone(),
+		crate::cache().ok().as_deref(),
+		ProgressReporter(&spinner),
+	)?;
+	project(provider, template, &tag, &config, para_id, &generated).save(&generated)?;
+	spinner.stop("Generation complete");
+	let mut effects = Vec::new();
+	if destination_path.exists() {
+		effects.push(Effect::Remove(destination_path.to_path_buf()));
+	}
+	effects.extend(created(&generated, destination_path)?);
+	report(&effects)?;
+	Ok(tag)
+}
+
+/// How the parachain at `path` was generated, as recorded within its pop.toml.
+///
+/// # Arguments
+///
+/// * `provider` - the provider of the template
+/// * `template` - the template generated
+/// * `release` - the release of the template generated
+/// * `config` - the customization of the template
+/// * `para_id` - the identifier of the parachain, if provided
+/// * `path` - path to the project generated
+fn project(
+	provider: &Provider,
+	template: &Template,
+	release: &Option<String>,
+	config: &Config,
+	para_id: Option<u32>,
+	path: &Path,
+) -> Project {
+	// Only the templates of Pop can be customized.
+	let token = template.matches(&Provider::Pop).then(|| Token {
+		symbol: config.symbol.clone(),
+		decimals: config.decimals,
+		endowment: Some(config.initial_endowment.clone()),
+	});
+	Project {
+		kind: "parachain".into(),
+		provider: Some(provider.as_ref().to_string()),
+		template: template.as_ref().to_string(),
+		release: release.clone(),
+		sdk_version: PolkadotRelease::from_project(path)
+			.ok()
+			.flatten()
+			.map(|release| release.version().to_string()),
+		para_id,
+		token,
+	}
+}
+
+/// The template provider configured by the user, otherwise the default provider.
+fn configured_provider() -> Result<Provider> {
+	match crate::config().provider() {
+		Some(provider) => Provider::from_str(&provider)
+			.map_err(|_| anyhow::anyhow!("the configured provider `{provider}` is unknown")),
+		None => Ok(Provider::default()),
+	}
 }
 
 fn is_template_supported(provider: &Provider, template: &Template) -> Result<()> {
@@ -210,14 +375,12 @@ fn is_template_supported(provider: &Provider, template: &Template) -> Result<()>
 }
 
 fn display_select_options(provider: &Provider) -> Result<&Template> {
-	let mut prompt = cliclack::select("Select the type of parachain:".to_string());
-	for (i, template) in provider.templates().into_iter().enumerate() {
-		if i == 0 {
-			prompt = prompt.initial_value(template);
-		}
-		prompt = prompt.item(template, template.name(), template.description());
-	}
-	Ok(prompt.interact()?)
+	let templates = provider
+		.templates()
+		.into_iter()
+		.map(|template| (template, template.name().to_string(), template.description().to_string()))
+		.collect();
+	prompt::select("Select the type of parachain:", templates)
 }
 
 fn get_customization_value(
@@ -226,29 +389,59 @@ fn get_customization_value(
 	decimals: Option<u8>,
 	initial_endowment: Option<String>,
 ) -> Result<Config> {
-	if !matches!(template, Template::Standard)
+	if !template.matches(&Provider::Pop)
 		&& (symbol.is_some() || decimals.is_some() || initial_endowment.is_some())
 	{
 		log::warning("Customization options are not available for this template")?;
 	}
-	return Ok(Config {
-		symbol: symbol.clone().expect("default values"),
-		decimals: decimals.clone().expect("default values"),
-		initial_endowment: initial_endowment.clone().expect("default values"),
-	});
+	// Defaults match those of the interactive prompts, so both generate the same parachain.
+	Ok(Config {
+		symbol: symbol.unwrap_or_else(|| DEFAULT_SYMBOL.to_string()),
+		decimals: decimals.unwrap_or(DEFAULT_DECIMALS),
+		initial_endowment: initial_endowment
+			.unwrap_or_else(|| DEFAULT_INITIAL_ENDOWMENT.to_string()),
+	})
+}
+
+/// Parses the symbol of the token, which must be alphanumeric.
+fn parse_symbol(symbol: &str) -> Result<String, String> {
+	if symbol.is_empty() || !symbol.chars().all(|c| c.is_ascii_alphanumeric()) {
+		return Err(format!("`{symbol}` is not a valid token symbol, use letters and digits only"));
+	}
+	Ok(symbol.to_string())
+}
+
+/// Parses the number of decimals of the token.
+fn parse_decimals(decimals: &str) -> Result<u8, String> {
+	decimals
+		.trim()
+		.parse()
+		.map_err(|_| format!("`{decimals}` is not a valid number of decimals, use 0 to 255"))
 }
 
-fn check_destination_path(name_template: &String) -> Result<&Path> {
+/// Parses the initial endowment of dev accounts, either a balance or a left shift.
+fn parse_initial_endowment(initial_endowment: &str) -> Result<String, String> {
+	if !is_initial_endowment_valid(initial_endowment) {
+		return Err(format!(
+			"`{initial_endowment}` is not a valid balance, use a number (e.g. 1_000_000) or a left shift (e.g. 1u64 << 60)"
+		));
+	}
+	Ok(initial_endowment.to_string())
+}
+
+fn check_destination_path(name_template: &String, force: bool) -> Result<&Path> {
 	let destination_path = Path::new(name_template);
 	if destination_path.exists() {
-		if !confirm(format!(
-			"\"{}\" directory already exists. Would you like to remove it?",
-			destination_path.display()
-		))
-		.interact()?
-		{
+		if !force
+			&& !prompt::confirm(
+				format!(
+					"\"{}\" directory already exists. Would you like to remove it?",
+					destination_path.display()
+				),
+				false,
+			)? {
 			outro_cancel(format!(
-				"Cannot generate parachain until \"{}\" directory is removed.",
+				"Cannot generate parachain until \"{}\" directory is removed, or `--force` is used.",
 				destination_path.display()
 			))?;
 			return Err(anyhow::anyhow!(format!(
@@ -262,46 +455,35 @@ fn check_destination_path(name_template: &String) -> Result<&Path> {
 }
 
 fn display_release_versions_to_user(releases: Vec<Release>) -> Result<String> {
-	let mut prompt = cliclack::select("Select a specific release:".to_string());
-	for (i, release) in releases.iter().enumerate() {
-		if i == 0 {
-			prompt = prompt.initial_value(&release.tag_name);
-		}
-		prompt = prompt.item(
-			&release.tag_name,
-			&release.name,
-			match &release.commit {
+	let releases = releases
+		.iter()
+		.map(|release| {
+			let hint = match &release.commit {
 				Some(commit) => format!("{} / {}", &release.tag_name, &commit[..=6]),
 				None => release.tag_name.to_string(),
-			},
-		)
-	}
-	Ok(prompt.interact()?.to_string())
+			};
+			(&release.tag_name, release.name.to_string(), hint)
+		})
+		.collect();
+	Ok(prompt::select("Select a specific release:", releases)?.to_string())
 }
 
 fn prompt_customizable_options() -> Result<Config> {
-	let symbol: String = input("What is the symbol of your parachain token?")
-		.placeholder("UNIT")
-		.default_input("UNIT")
-		.interact()?;
-
-	let decimals_input: String = input("How many token decimals?")
-		.placeholder("12")
-		.default_input("12")
-		.interact()?;
-	let decimals: u8 = decimals_input.parse::<u8>().expect("input has to be a number");
-
-	let mut initial_endowment: String = input("And the initial endowment for dev accounts?")
-		.placeholder("1u64 << 60")
-		.default_input("1u64 << 60")
-		.interact()?;
+	let symbol = prompt::input("What is the symbol of your parachain token?", DEFAULT_SYMBOL)
+		.and_then(|symbol| parse_symbol(&symbol).map_err(anyhow::Error::msg))?;
+
+	let decimals = prompt::input("How many token decimals?", &DEFAULT_DECIMALS.to_string())
+		.and_then(|decimals| parse_decimals(&decimals).map_err(anyhow::Error::msg))?;
+
+	let mut initial_endowment =
+		prompt::input("And the initial endowment for dev accounts?", DEFAULT_INITIAL_ENDOWMENT)?;
 	if !is_initial_endowment_valid(&initial_endowment) {
 		outro_cancel("⚠️ The specified initial endowment is not valid")?;
 		//Prompt the user if want to use the one by default
-		if !confirm(format!("📦 Would you like to use the default {}?", DEFAULT_INITIAL_ENDOWMENT))
-			.initial_value(true)
-			.interact()?
-		{
+		if !prompt::confirm(
+			format!("📦 Would you like to use the default {}?", DEFAULT_INITIAL_ENDOWMENT),
+			true,
+		)? {
 			outro_cancel(
 				"🚫 Cannot create a parachain with an incorrect initial endowment value.",
 			)?;
@@ -335,7 +517,7 @@ mod tests {
 			dir.path().join("test_parachain").to_str().unwrap(),
 		]);
 
-		let New(NewArgs { command: Parachain(command) }) = cli.command else {
+		let New(NewArgs { command: Some(Parachain(command)) }) = cli.command else {
 			panic!("unable to parse command")
 		};
 		// Execute
@@ -359,9 +541,19 @@ mod tests {
 			symbol: Some("UNIT".to_string()),
 			decimals: Some(12),
 			initial_endowment: Some("1u64 << 60".to_string()),
+			para_id: Some(2000),
+			keep_on_failure: false,
+			force: false,
+			output: OutputArgs::default(),
 		};
 		command.execute().await?;
 
+		// check the project is recorded
+		let project = Project::load(Path::new(command.name.as_ref().unwrap()))?.unwrap();
+		assert_eq!(project.template, "standard");
+		assert_eq!(project.para_id, Some(2000));
+		assert_eq!(project.token.unwrap().symbol, "UNIT");
+
 		// check for git_init
 		let repo = Repository::open(Path::new(&command.name.unwrap()))?;
 		let reflog = repo.reflog("HEAD")?;
@@ -397,6 +589,29 @@ mod tests {
 				initial_endowment: "10000".to_string()
 			}
 		);
+		// Flags which are omitted take the defaults of the interactive prompts.
+		let config = get_customization_value(&Template::Standard, None, Some(6), None)?;
+		assert_eq!(
+			config,
+			Config {
+				symbol: DEFAULT_SYMBOL.to_string(),
+				decimals: 6,
+				initial_endowment: DEFAULT_INITIAL_ENDOWMENT.to_string()
+			}
+		);
 		Ok(())
 	}
+
+	#[test]
+	fn parse_customization_flags_works() {
+		assert_eq!(parse_symbol("DOT"), Ok("DOT".to_string()));
+		assert!(parse_symbol("").is_err());
+		assert!(parse_symbol("D O T").is_err());
+		assert_eq!(parse_decimals("18"), Ok(18));
+		assert!(parse_decimals("twelve").is_err());
+		assert!(parse_decimals("256").is_err());
+		assert_eq!(parse_initial_endowment("1_000_000"), Ok("1_000_000".to_string()));
+		assert_eq!(parse_initial_endowment("1u64 << 60"), Ok("1u64 << 60".to_string()));
+		assert!(parse_initial_endowment("a lot").is_err());
+	}
 }
@@ -0,0 +1,58 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::style::{style, Theme};
+use clap::{Args, Subcommand};
+use cliclack::{clear_screen, intro, log, outro, outro_cancel, set_theme};
+use pop_parachains::{create_network_test, is_parachain};
+use std::path::PathBuf;
+
+#[derive(Args)]
+#[command(args_conflicts_with_subcommands = true)]
+pub struct NewTestArgs {
+	#[command(subcommand)]
+	pub command: NewTestCommands,
+}
+
+#[derive(Subcommand)]
+pub enum NewTestCommands {
+	/// Generate an end-to-end test of a parachain, run against a local network
+	#[clap(alias = "n")]
+	Network(NewTestNetworkCommand),
+}
+
+#[derive(Args)]
+pub struct NewTestNetworkCommand {
+	/// Directory path of the parachain project, [default: the current project]
+	#[arg(short = 'p', long)]
+	path: Option<PathBuf>,
+	/// The identifier of the parachain within the test network.
+	#[arg(long, default_value_t = 2000)]
+	para_id: u32,
+}
+
+impl NewTestNetworkCommand {
+	pub(crate) fn execute(&self) -> anyhow::Result<()> {
+		clear_screen()?;
+		intro(format!("{}: Generating a network test", style(" Pop CLI ").black().on_magenta()))?;
+		set_theme(Theme);
+		let path = crate::project_path(self.path.as_deref());
+		if !is_parachain(&path)? {
+			outro_cancel(format!("{} is not a parachain project.", path.display()))?;
+			return Ok(());
+		}
+		match create_network_test(&path, self.para_id) {
+			Ok(files) => {
+				log::success(format!(
+					"Generated:\n{}",
+					files.iter().map(|f| f.display().to_string()).collect::<Vec<_>>().join("\n")
+				))?;
+				outro(format!(
+					"Run the test with `{}`",
+					path.join("scripts").join("network-tests.sh").display()
+				))?;
+			},
+			Err(e) => outro_cancel(format!("{e}"))?,
+		}
+		Ok(())
+	}
+}
@@ -0,0 +1,119 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use anyhow::{anyhow, Result};
+use pop_common::Config;
+use serde::Serialize;
+use std::{
+	collections::BTreeMap,
+	env,
+	ffi::{OsStr, OsString},
+	fs,
+	os::unix::{fs::PermissionsExt, process::CommandExt},
+	path::{Path, PathBuf},
+	process::Command,
+};
+
+/// The prefix of the executables extending pop, e.g. `pop-foo` providing `pop foo`.
+const PREFIX: &str = "pop-";
+/// The environment variable providing the context of pop to a plugin, as JSON.
+pub(crate) const CONTEXT_ENV: &str = "POP_CONTEXT";
+
+/// The context of pop provided to a plugin.
+#[derive(Serialize)]
+struct Context {
+	/// The version of pop.
+	version: &'static str,
+	/// The directory pop was launched from, which is the project path unless provided otherwise.
+	path: PathBuf,
+	/// The configuration file of the user.
+	config_path: Option<PathBuf>,
+	/// The configuration keys set by the user, along with their values.
+	config: BTreeMap<String, String>,
+}
+
+/// Runs the plugin providing a command which is not built in, replacing the current process with
+/// the `pop-<command>` executable found on `PATH`. The remaining arguments are provided as is,
+/// along with the context of pop as JSON within `POP_CONTEXT`.
+///
+/// # Arguments
+///
+/// * `args` - the command, followed by its arguments
+pub(crate) fn execute(args: &[OsString]) -> Result<()> {
+	let (command, args) = args.split_first().ok_or(anyhow!("no command was provided"))?;
+	let plugin = find(command, env::var_os("PATH")).ok_or(anyhow!(
+		"unrecognized command `{0}`: it is not built in, nor provided by a `{PREFIX}{0}` executable \
+		 on PATH",
+		command.to_string_lossy()
+	))?;
+	let context = context(crate::config())?;
+	// Only returns should the plugin fail to launch.
+	let error = Command::new(&plugin).args(args).env(CONTEXT_ENV, context).exec();
+	Err(anyhow!("unable to launch {}: {error}", plugin.display()))
+}
+
+/// Finds the executable of the plugin providing `command` within the directories of `path`.
+///
+/// # Arguments
+///
+/// * `command` - the command provided by the plugin
+/// * `path` - the directories searched, as per `PATH`
+fn find(command: &OsStr, path: Option<OsString>) -> Option<PathBuf> {
+	let mut name = OsString::from(PREFIX);
+	name.push(command);
+	env::split_paths(&path?)
+		.map(|dir| dir.join(&name))
+		.find(|file| is_executable(file))
+}
+
+/// Whether `file` is an executable file.
+fn is_executable(file: &Path) -> bool {
+	fs::metadata(file).is_ok_and(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+}
+
+/// The context of pop provided to plugins, as JSON.
+///
+/// # Arguments
+///
+/// * `config` - the configuration of the user
+fn context(config: &Config) -> Result<String> {
+	let context = Context {
+		version: env!("CARGO_PKG_VERSION"),
+		path: env::current_dir()?,
+		config_path: Config::path().ok(),
+		config: config.entries().into_iter().collect(),
+	};
+	Ok(serde_json::to_string(&context)?)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use serde_json::Value;
+
+	#[test]
+	fn test_find() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let plugin = temp_dir.path().join("pop-foo");
+		fs::write(&plugin, "#!/bin/sh\n")?;
+		let path = env::join_paths([temp_dir.path()])?;
+
+		// Only executables are plugins.
+		assert_eq!(find(OsStr::new("foo"), Some(path.clone())), None);
+		fs::set_permissions(&plugin, fs::Permissions::from_mode(0o755))?;
+		assert_eq!(find(OsStr::new("foo"), Some(path.clone())), Some(plugin));
+		assert_eq!(find(OsStr::new("bar"), Some(path)), None);
+		assert_eq!(find(OsStr::new("foo"), None), None);
+		Ok(())
+	}
+
+	#[test]
+	fn test_context() -> Result<()> {
+		let mut config = Config::default();
+		config.set("provider", "parity")?;
+		let context: Value = serde_json::from_str(&context(&config)?)?;
+		assert_eq!(context["version"], env!("CARGO_PKG_VERSION"));
+		assert_eq!(context["path"], env::current_dir()?.to_string_lossy().as_ref());
+		assert_eq!(context["config"]["provider"], "parity");
+		Ok(())
+	}
+}
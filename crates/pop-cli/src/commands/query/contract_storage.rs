@@ -0,0 +1,133 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use clap::Args;
+use cliclack::{clear_screen, intro, log, outro, outro_cancel, set_theme};
+use console::style;
+use pop_contracts::{environment, query_contract_storage, StorageEntry};
+use std::path::PathBuf;
+
+use crate::{commands::inspect::contract::OutputFormat, style::Theme};
+
+#[derive(Args)]
+pub struct QueryContractStorageCommand {
+	/// Path to the contract project, [default: the current project]
+	#[arg(short = 'p', long)]
+	path: Option<PathBuf>,
+	/// Path to a contract metadata (.json) or bundle (.contract) file, used instead of the build
+	/// artifacts of the contract project.
+	#[arg(short = 'm', long, conflicts_with = "path")]
+	metadata: Option<PathBuf>,
+	/// The address of the deployed contract.
+	#[clap(name = "contract", long, env = "CONTRACT")]
+	contract: String,
+	/// The environment providing the endpoint of the node: one of `local`, `pop-testnet`,
+	/// `shibuya` or an environment defined within the project's pop.toml. [default: local]
+	#[clap(long)]
+	env: Option<String>,
+	/// Websocket endpoint of a node, [default: the endpoint of the environment]
+	#[clap(name = "url", long, value_parser)]
+	url: Option<url::Url>,
+	/// A hex encoded storage key to look up, as displayed when querying the entire storage.
+	#[arg(short = 'k', long)]
+	key: Option<String>,
+	/// Only output the entries of this storage field, e.g. `balances`.
+	#[arg(short = 'f', long)]
+	field: Option<String>,
+	/// The output format.
+	#[arg(short = 'o', long, value_enum, default_value_t)]
+	output: OutputFormat,
+}
+
+impl QueryContractStorageCommand {
+	/// The contract project, detected from the current directory unless provided.
+	fn path(&self) -> Option<PathBuf> {
+		Some(crate::contract_path(self.path.as_deref()))
+	}
+
+	pub(crate) async fn execute(&self) -> anyhow::Result<()> {
+		if self.output == OutputFormat::Json {
+			let entries = self.query().await?;
+			println!("{}", serde_json::to_string_pretty(&entries)?);
+			return Ok(());
+		}
+
+		clear_screen()?;
+		intro(format!(
+			"{}: Querying the storage of a contract",
+			style(" Pop CLI ").black().on_magenta()
+		))?;
+		set_theme(Theme);
+
+		let spinner = crate::progress::spinner();
+		spinner.start("Fetching the contract storage...");
+		let entries = match self.query().await {
+			Ok(entries) => entries,
+			Err(e) => {
+				spinner.error("Failed to query the contract storage.");
+				outro_cancel(format!("{e}"))?;
+				return Ok(());
+			},
+		};
+		spinner.stop(format!("Storage of {} fetched.", self.contract));
+		if entries.is_empty() {
+			outro("No storage entries found.")?;
+			return Ok(());
+		}
+		for entry in &entries {
+			log::info(describe(entry))?;
+		}
+		outro(format!("{} storage entries found.", entries.len()))?;
+		Ok(())
+	}
+
+	/// Queries the storage of the contract, keeping only the entries of the requested field.
+	async fn query(&self) -> anyhow::Result<Vec<StorageEntry>> {
+		let url = match &self.url {
+			Some(url) => url.clone(),
+			None => environment(&self.path(), self.env.as_deref(), crate::config())?.url,
+		};
+		let entries = query_contract_storage(
+			&self.path(),
+			self.metadata.as_deref(),
+			&url,
+			&self.contract,
+			self.key.as_deref(),
+		)
+		.await?;
+		Ok(entries
+			.into_iter()
+			.filter(|entry| match &self.field {
+				Some(field) => {
+					entry.field == *field || entry.field.starts_with(&format!("{field}."))
+				},
+				None => true,
+			})
+			.collect())
+	}
+}
+
+/// A readable description of a storage entry.
+fn describe(entry: &StorageEntry) -> String {
+	let field = match &entry.mapping_key {
+		Some(key) => format!("{}[{key}]", entry.field),
+		None => entry.field.clone(),
+	};
+	format!("{field}: {}\n{}", entry.value, style(format!("key: {}", entry.key)).dim())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_describe() {
+		let entry = StorageEntry {
+			field: "balances".into(),
+			key: "0x2623dce701".into(),
+			mapping_key: Some("Alice".into()),
+			value: "100".into(),
+		};
+		assert!(describe(&entry).starts_with("balances[Alice]: 100\n"));
+		assert!(describe(&entry).contains("key: 0x2623dce701"));
+	}
+}
@@ -0,0 +1,41 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use anyhow::anyhow;
+use clap::{Args, Subcommand};
+
+#[cfg(feature = "contract")]
+pub(crate) mod contract_storage;
+#[cfg(feature = "parachain")]
+pub(crate) mod storage;
+
+#[derive(Args)]
+#[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
+pub(crate) struct QueryArgs {
+	#[command(subcommand)]
+	pub command: Option<QueryCommands>,
+	/// Query the storage of a chain, decoded using its metadata.
+	#[cfg(feature = "parachain")]
+	#[command(flatten)]
+	pub storage: storage::QueryStorageCommand,
+}
+
+#[derive(Subcommand)]
+pub(crate) enum QueryCommands {
+	/// Query the storage of a deployed contract, decoded using its metadata
+	#[cfg(feature = "contract")]
+	#[clap(alias = "cs")]
+	ContractStorage(contract_storage::QueryContractStorageCommand),
+}
+
+impl QueryArgs {
+	pub(crate) async fn execute(&self) -> anyhow::Result<()> {
+		match &self.command {
+			#[cfg(feature = "contract")]
+			Some(QueryCommands::ContractStorage(cmd)) => cmd.execute().await,
+			#[cfg(feature = "parachain")]
+			None => self.storage.execute().await,
+			#[allow(unreachable_patterns)]
+			_ => Err(anyhow!("specify what to query, e.g. `pop query contract-storage`")),
+		}
+	}
+}
@@ -0,0 +1,105 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::{
+	output::OutputArgs,
+	style::{style, Theme},
+};
+use clap::Args;
+use cliclack::{intro, log, outro, outro_cancel, set_theme};
+use pop_parachains::{query_storage, StorageItem};
+use url::Url;
+
+#[derive(Args)]
+pub(crate) struct QueryStorageCommand {
+	/// The pallet of the storage, e.g. `System`.
+	pallet: String,
+	/// The storage entry, e.g. `Account`.
+	storage: String,
+	/// The keys of the value within a map, e.g. an account. The values within the map are
+	/// iterated when fewer keys are provided than the map has.
+	keys: Vec<String>,
+	/// Websocket endpoint of the chain.
+	#[arg(long, value_parser, default_value = "ws://127.0.0.1:9944")]
+	url: Url,
+	/// The hash or number of the block queried. [default: the best block]
+	#[arg(long)]
+	at: Option<String>,
+	/// The number of values fetched when iterating a map.
+	#[arg(long, default_value_t = 100)]
+	page_size: u32,
+	/// The storage key after which iterating a map starts, as reported to fetch the next page.
+	#[arg(long)]
+	start: Option<String>,
+	#[command(flatten)]
+	output: OutputArgs,
+}
+
+impl QueryStorageCommand {
+	pub(crate) async fn execute(&self) -> anyhow::Result<()> {
+		self.output.clear_screen()?;
+		intro(format!(
+			"{}: Query the storage of a chain",
+			style(" Pop CLI ").black().on_magenta()
+		))?;
+		set_theme(Theme);
+
+		let spinner = crate::progress::spinner();
+		spinner.start(format!("Querying {}::{}...", self.pallet, self.storage));
+		let page = match query_storage(
+			&self.url,
+			&self.pallet,
+			&self.storage,
+			&self.keys,
+			self.at.as_deref(),
+			self.page_size,
+			self.start.as_deref(),
+		)
+		.await
+		{
+			Ok(page) => page,
+			Err(e) => {
+				spinner.error(format!("{e}"));
+				outro_cancel("Unable to query the storage of the chain.")?;
+				return Ok(());
+			},
+		};
+		spinner.stop(format!("{}::{} queried.", self.pallet, self.storage));
+		self.output.report(&page)?;
+		if page.items.is_empty() {
+			outro("No value is stored.")?;
+			return Ok(());
+		}
+		for item in &page.items {
+			log::info(describe(item))?;
+		}
+		match &page.next {
+			Some(next) => outro(format!(
+				"{} values fetched, fetch the next page with `--start {next}`.",
+				page.items.len()
+			))?,
+			None => outro(format!("{} values fetched.", page.items.len()))?,
+		}
+		Ok(())
+	}
+}
+
+/// Describes a stored value along with its keys, if any.
+fn describe(item: &StorageItem) -> String {
+	match item.keys.is_empty() {
+		true => item.value.clone(),
+		false => format!("[{}] {}", item.keys.join(", "), item.value),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn describe_works() {
+		let mut item = StorageItem { key: "0x26aa".into(), keys: Vec::new(), value: "1000".into() };
+		assert_eq!(describe(&item), "1000");
+		item.keys = vec!["2000".into(), "(1, 2)".into()];
+		assert_eq!(describe(&item), "[2000, (1, 2)] 1000");
+	}
+}
@@ -0,0 +1,84 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::style::{style, Theme};
+use clap::{Args, Subcommand};
+use cliclack::{clear_screen, intro, log, outro, outro_cancel, set_theme};
+use pop_common::Config;
+use pop_telemetry::{queue_file_path, Queue};
+use std::path::Path;
+
+/// The documentation of the metrics reported, along with their payload.
+const TELEMETRY_DOCS: &str =
+	"https://github.com/r0gue-io/pop-cli/blob/main/crates/pop-telemetry/README.md";
+
+#[derive(Args)]
+#[command(args_conflicts_with_subcommands = true)]
+pub(crate) struct TelemetryArgs {
+	#[command(subcommand)]
+	pub command: TelemetryCommands,
+}
+
+#[derive(Subcommand)]
+pub(crate) enum TelemetryCommands {
+	/// Opt in to reporting anonymous usage metrics: the commands used and the categories of their
+	/// errors.
+	On,
+	/// Opt out of reporting anonymous usage metrics, discarding those not yet reported.
+	Off,
+}
+
+impl TelemetryCommands {
+	pub(crate) fn execute(&self) -> anyhow::Result<()> {
+		clear_screen()?;
+		intro(format!("{}: Anonymous usage metrics", style(" Pop CLI ").black().on_magenta()))?;
+		set_theme(Theme);
+
+		let enabled = matches!(self, TelemetryCommands::On);
+		if let Err(e) = Config::path().and_then(|path| set_telemetry(&path, enabled)) {
+			outro_cancel(format!("{e}"))?;
+			return Ok(());
+		}
+		if enabled {
+			log::info(format!(
+				"Only the name of each command used and the category of any error are reported, \
+				 never any value provided. The payload is documented at {TELEMETRY_DOCS}."
+			))?;
+			outro("📊 Thank you, anonymous usage metrics will be reported.")?;
+		} else {
+			// Events queued by previous runs are discarded rather than reported later.
+			if let Ok(path) = queue_file_path() {
+				let _ = Queue::new(path).take();
+			}
+			outro("Anonymous usage metrics will no longer be reported.")?;
+		}
+		Ok(())
+	}
+}
+
+/// Enables or disables telemetry within the configuration at `path`.
+///
+/// # Arguments
+///
+/// * `path` - the configuration file
+/// * `enabled` - whether anonymous usage metrics are reported
+fn set_telemetry(path: &Path, enabled: bool) -> Result<(), pop_common::Error> {
+	let mut config = Config::load_from(path)?;
+	config.set("telemetry", &enabled.to_string())?;
+	config.save_to(path)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_set_telemetry() -> anyhow::Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let path = temp_dir.path().join("config.toml");
+		set_telemetry(&path, true)?;
+		assert_eq!(Config::load_from(&path)?.telemetry(), Some(true));
+		set_telemetry(&path, false)?;
+		assert_eq!(Config::load_from(&path)?.telemetry(), Some(false));
+		Ok(())
+	}
+}
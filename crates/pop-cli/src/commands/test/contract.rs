@@ -10,29 +10,40 @@ use crate::style::style;
 
 #[derive(Args)]
 pub(crate) struct TestContractCommand {
-	#[arg(short = 'p', long, help = "Path for the contract project [default: current directory]")]
+	#[arg(
+		short = 'p',
+		long,
+		help = "Path for the contract project [default: the current project]"
+	)]
 	path: Option<PathBuf>,
 	#[arg(short = 'f', long = "features", help = "Features for the contract project")]
 	features: Option<String>,
+	#[arg(long, help = "Run end-to-end tests, equivalent to `--features e2e-tests`")]
+	e2e: bool,
 }
 
 impl TestContractCommand {
+	/// The contract project, detected from the current directory unless provided.
+	fn path(&self) -> Option<PathBuf> {
+		Some(crate::contract_path(self.path.as_deref()))
+	}
+
 	pub(crate) fn execute(&self) -> anyhow::Result<&str> {
 		clear_screen()?;
 
-		if self.features.is_some() && self.features.clone().unwrap().contains("e2e-tests") {
+		if self.e2e || self.features.as_ref().is_some_and(|f| f.contains("e2e-tests")) {
 			intro(format!(
 				"{}: Starting end-to-end tests",
 				style(" Pop CLI ").black().on_magenta()
 			))?;
 
-			test_e2e_smart_contract(&self.path)?;
+			test_e2e_smart_contract(&self.path())?;
 			outro("End-to-end testing complete")?;
 			Ok("e2e")
 		} else {
 			intro(format!("{}: Starting unit tests", style(" Pop CLI ").black().on_magenta()))?;
 
-			test_smart_contract(&self.path)?;
+			test_smart_contract(&self.path())?;
 			outro("Unit testing complete")?;
 			Ok("unit")
 		}
@@ -1,19 +1,75 @@
 // SPDX-License-Identifier: GPL-3.0
 
+use crate::{
+	output::OutputArgs,
+	workspace::{self, Kind},
+};
 use clap::{Args, Subcommand};
+use cliclack::{intro, set_theme};
+use std::path::PathBuf;
 
 #[cfg(feature = "contract")]
 pub mod contract;
+#[cfg(feature = "parachain")]
+pub mod on_runtime_upgrade;
+#[cfg(feature = "parachain")]
+pub mod parachain;
 
 #[derive(Args)]
-#[command(args_conflicts_with_subcommands = true)]
+#[command(args_conflicts_with_subcommands = true, arg_required_else_help = true)]
 pub(crate) struct TestArgs {
 	#[command(subcommand)]
-	pub command: TestCommands,
+	pub command: Option<TestCommands>,
+	/// Test every parachain and contract found within the workspace.
+	#[arg(long)]
+	pub all: bool,
+	/// Directory path of the workspace, [default: the current project]
+	#[arg(short = 'p', long, requires = "all")]
+	pub path: Option<PathBuf>,
+	#[command(flatten)]
+	pub(crate) output: OutputArgs,
+}
+
+impl TestArgs {
+	/// Runs the unit tests of every parachain and contract found within the workspace, reporting a
+	/// summary once all were tested.
+	pub(crate) fn execute_all(&self) -> anyhow::Result<()> {
+		self.output.clear_screen()?;
+		intro(format!(
+			"{}: Testing the workspace",
+			crate::style::style(" Pop CLI ").black().on_magenta()
+		))?;
+		set_theme(crate::style::Theme);
+		let root = crate::project_path(self.path.as_deref());
+		let members = workspace::members(&root)?;
+		workspace::run_all(&root, &members, "Testing", &self.output, |member| match member.kind {
+			#[cfg(feature = "parachain")]
+			Kind::Parachain => {
+				let options = pop_parachains::TestOptions::default();
+				let report = pop_parachains::test_parachain(&member.path, &options)?;
+				match report.success() {
+					true => Ok(()),
+					false => Err(anyhow::anyhow!(
+						"{} tests failed",
+						report.crates.iter().map(|c| c.failed).sum::<u32>()
+					)),
+				}
+			},
+			#[cfg(feature = "contract")]
+			Kind::Contract => Ok(pop_contracts::test_smart_contract(&Some(member.path.clone()))?),
+		})
+	}
 }
 
 #[derive(Subcommand)]
 pub(crate) enum TestCommands {
+	/// Test a parachain
+	#[cfg(feature = "parachain")]
+	#[clap(alias = "p")]
+	Parachain(parachain::TestParachainCommand),
+	/// Test the migrations of a runtime upgrade against the state of a live chain
+	#[cfg(feature = "parachain")]
+	OnRuntimeUpgrade(on_runtime_upgrade::TestOnRuntimeUpgradeCommand),
 	/// Test a smart contract
 	#[cfg(feature = "contract")]
 	#[clap(alias = "c")]
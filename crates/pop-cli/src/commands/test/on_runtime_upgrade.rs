@@ -0,0 +1,118 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::{
+	commands::up::{confirm_build, report_verifications, ProgressReporter},
+	style::{style, Theme},
+};
+use anyhow::anyhow;
+use clap::Args;
+use cliclack::{clear_screen, intro, log, outro, outro_cancel, set_theme};
+use pop_parachains::{
+	try_runtime, try_runtime_runtime, OnRuntimeUpgrade, UpgradeChecks, TRY_RUNTIME_DEFAULT_VERSION,
+};
+use std::path::PathBuf;
+use url::Url;
+
+#[derive(Args)]
+pub(crate) struct TestOnRuntimeUpgradeCommand {
+	/// Directory path for your project, [default: the current project]
+	#[arg(short = 'p', long = "path")]
+	path: Option<PathBuf>,
+	/// Websocket endpoint of a node of the live chain, from which its state is scraped.
+	#[arg(long, value_parser)]
+	uri: Url,
+	/// The runtime wasm to upgrade to, compiled with the `try-runtime` feature. [default: the
+	/// runtime of the latest release build of the project]
+	#[arg(long)]
+	runtime: Option<PathBuf>,
+	/// The checks executed alongside the migrations: `none`, `pre-and-post`, `try-state` or
+	/// `all`.
+	#[arg(long, default_value = "all")]
+	checks: UpgradeChecks,
+	/// The expected block time of the chain, in milliseconds.
+	#[arg(long, default_value_t = 6000)]
+	blocktime: u64,
+	/// Skip checking that the spec version of the runtime is greater than that of the live chain.
+	#[arg(long)]
+	disable_spec_version_check: bool,
+	/// The release of `try-runtime-cli` to use, as per the release tag.
+	#[arg(long, default_value = TRY_RUNTIME_DEFAULT_VERSION)]
+	try_runtime_version: String,
+}
+
+impl TestOnRuntimeUpgradeCommand {
+	pub(crate) async fn execute(&self) -> anyhow::Result<&str> {
+		clear_screen()?;
+		intro(format!(
+			"{}: Testing the runtime upgrade against live state",
+			style(" Pop CLI ").black().on_magenta()
+		))?;
+		set_theme(Theme);
+		let path = crate::project_path(self.path.as_deref());
+		let runtime = match self.runtime.clone().map_or_else(|| try_runtime_runtime(&path), Ok) {
+			Ok(runtime) => runtime,
+			Err(e) => return Err(e.into()),
+		};
+
+		// Locate try-runtime within the cache, sourcing it when missing.
+		let cache = crate::cache()?;
+		let binary = try_runtime(&cache, &self.try_runtime_version);
+		if !binary.path.exists() {
+			if !confirm_build([&binary])? {
+				outro_cancel("Cannot test the runtime upgrade until try-runtime is available.")?;
+				return Ok("on-runtime-upgrade");
+			}
+			log::info(format!("ℹ️ {} will be cached at {}", binary.name, cache.display()))?;
+			let spinner = crate::progress::spinner();
+			spinner.start(format!("📦 Sourcing {}...", binary.name));
+			let verifications = match binary.source(&cache, ProgressReporter(&spinner)).await {
+				Ok(verifications) => verifications,
+				Err(e) => {
+					spinner.error("🚫 Sourcing failed.");
+					return Err(e.into());
+				},
+			};
+			spinner.stop(format!("✅ Sourcing {} complete.", binary.name));
+			report_verifications(&verifications)?;
+		}
+
+		let upgrade = OnRuntimeUpgrade {
+			runtime,
+			uri: self.uri.clone(),
+			checks: self.checks,
+			blocktime: self.blocktime,
+			disable_spec_version_check: self.disable_spec_version_check,
+		};
+		let spinner = crate::progress::spinner();
+		spinner.start(format!(
+			"Scraping the state of {} and executing the migrations of {}...",
+			self.uri,
+			upgrade.runtime.display()
+		));
+		let report = match upgrade.run(&binary.path) {
+			Ok(report) => report,
+			Err(e) => {
+				spinner.error("🚫 The runtime upgrade could not be executed.");
+				return Err(e.into());
+			},
+		};
+		spinner.stop("Runtime upgrade executed.");
+		if crate::logging::verbose() {
+			log::info(report.output.trim())?;
+		}
+		if let Some(weight) = &report.weight {
+			log::info(format!("Consumed weight: {weight}"))?;
+		}
+		for failure in &report.failures {
+			log::error(failure)?;
+		}
+		if report.success {
+			outro("The migrations and their checks succeeded!")?;
+		} else {
+			outro_cancel("The runtime upgrade failed. Use `--verbose` to see the full output.")?;
+			// Exit with a failure, as relied upon by continuous integration.
+			return Err(anyhow!("the migrations or their checks failed"));
+		}
+		Ok("on-runtime-upgrade")
+	}
+}
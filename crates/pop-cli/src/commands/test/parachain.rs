@@ -0,0 +1,74 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::style::{style, Theme};
+use anyhow::anyhow;
+use clap::Args;
+use cliclack::{clear_screen, intro, log, outro, outro_cancel, set_theme};
+use pop_parachains::{test_parachain, TestOptions};
+use std::path::PathBuf;
+
+#[derive(Args)]
+pub(crate) struct TestParachainCommand {
+	/// Directory path for your project, [default: the current project]
+	#[arg(short = 'p', long = "path")]
+	path: Option<PathBuf>,
+	/// Only run the tests whose names contain the filter.
+	filter: Option<String>,
+	/// Also run the benchmarks of the runtime and its pallets in test mode, equivalent to
+	/// `--features runtime-benchmarks`.
+	#[arg(long)]
+	benchmarks: bool,
+}
+
+impl TestParachainCommand {
+	pub(crate) fn execute(&self) -> anyhow::Result<&str> {
+		clear_screen()?;
+		intro(format!("{}: Testing your parachain", style(" Pop CLI ").black().on_magenta()))?;
+		set_theme(Theme);
+		let path = crate::project_path(self.path.as_deref());
+		let options = TestOptions { benchmarks: self.benchmarks, filter: self.filter.clone() };
+
+		let spinner = crate::progress::spinner();
+		spinner.start("Running the tests of the workspace, including its runtime...");
+		let report = match test_parachain(&path, &options) {
+			Ok(report) => report,
+			Err(e) => {
+				spinner.error("🚫 Testing failed.");
+				return Err(e.into());
+			},
+		};
+		spinner.stop("Tests finished.");
+		if crate::logging::verbose() {
+			log::info(report.output.trim())?;
+		}
+
+		for report in &report.crates {
+			let summary = format!(
+				"{}: {} passed, {} failed, {} ignored",
+				report.name, report.passed, report.failed, report.ignored
+			);
+			if report.failures.is_empty() && report.failed == 0 {
+				log::success(summary)?;
+			} else {
+				let failures =
+					report.failures.iter().map(|f| format!("  ✖ {f}")).collect::<Vec<_>>();
+				log::error(format!("{summary}\n{}", failures.join("\n")))?;
+			}
+		}
+
+		if report.crates.is_empty() {
+			outro("No tests were found.")?;
+		} else if report.success() {
+			outro("All tests passed!")?;
+		} else {
+			outro_cancel("Some tests failed. Use `--verbose` to see the full output.")?;
+			// Exit with a failure, as relied upon by continuous integration.
+			let failed = report.crates.iter().filter(|c| c.failed > 0);
+			return Err(anyhow!(
+				"tests failed in {}",
+				failed.map(|c| c.name.as_str()).collect::<Vec<_>>().join(", ")
+			));
+		}
+		Ok("parachain")
+	}
+}
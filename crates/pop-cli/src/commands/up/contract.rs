@@ -2,16 +2,25 @@
 
 use anyhow::anyhow;
 use clap::Args;
-use cliclack::{clear_screen, intro, log, outro, outro_cancel};
+use cliclack::{intro, log, outro, outro_cancel};
 use pop_contracts::{
-	dry_run_gas_estimate_instantiate, instantiate_smart_contract, parse_hex_bytes,
-	set_up_deployment, UpOpts,
+	dry_run_gas_estimate_instantiate, dry_run_upload, environment, instantiate_smart_contract,
+	parse_hex_bytes, prepare_offline_instantiate, set_up_deployment, set_up_upload,
+	upload_smart_contract, uploaded_code_hash, UpOpts, DEFAULT_GAS_MARGIN,
 };
+use serde::Serialize;
 use sp_core::Bytes;
 use sp_weights::Weight;
 use std::path::PathBuf;
 
-use crate::style::style;
+use crate::{
+	commands::{
+		history,
+		key::{resolve_suri, sign_externally},
+	},
+	output::OutputArgs,
+	style::style,
+};
 
 #[derive(Args)]
 pub struct UpContractCommand {
@@ -24,7 +33,9 @@ pub struct UpContractCommand {
 	/// The constructor arguments, encoded as strings.
 	#[clap(long, num_args = 0..)]
 	args: Vec<String>,
-	/// Transfers an initial balance to the instantiated contract.
+	/// Transfers an initial balance to the instantiated contract, which requires a payable
+	/// constructor: either an amount in the smallest unit of the chain's token, or an amount of the
+	/// token using its symbol, e.g. `10UNIT`.
 	#[clap(name = "value", long, default_value = "0")]
 	value: String,
 	/// Maximum amount of gas to be used for this command.
@@ -36,67 +47,313 @@ pub struct UpContractCommand {
 	/// If not specified it will perform a dry-run to estimate the proof size required.
 	#[clap(long)]
 	proof_size: Option<u64>,
+	/// The margin added to the gas estimated by the dry-run, as a percentage.
+	#[clap(long, default_value_t = DEFAULT_GAS_MARGIN)]
+	gas_margin: u8,
 	/// A salt used in the address derivation of the new contract. Use to create multiple
 	/// instances of the same contract code from the same account.
 	#[clap(long, value_parser = parse_hex_bytes)]
 	salt: Option<Bytes>,
-	/// Websocket endpoint of a node.
-	#[clap(name = "url", long, value_parser, default_value = "ws://localhost:9944")]
-	url: url::Url,
-	/// Secret key URI for the account deploying the contract.
+	/// Instantiate from the code already uploaded on chain, if the code of the contract has
+	/// already been uploaded, rather than uploading it again.
+	#[clap(long)]
+	skip_upload_if_exists: bool,
+	/// Upload the contract code without instantiating it, displaying its code hash. Useful when
+	/// the contract is instantiated on-chain later, e.g. by a factory contract.
+	#[clap(long, conflicts_with_all = ["args", "salt", "skip_upload_if_exists"])]
+	upload_only: bool,
+	/// The environment to deploy the contract to, providing the endpoint and default signer: one
+	/// of `local`, `pop-testnet`, `shibuya` or an environment defined within the project's
+	/// pop.toml. [default: local]
+	#[clap(long)]
+	env: Option<String>,
+	/// Websocket endpoint of a node, [default: the endpoint of the environment]
+	#[clap(name = "url", long, value_parser)]
+	url: Option<url::Url>,
+	/// Secret key URI for the account deploying the contract, required unless an environment
+	/// with a default signer is selected.
 	///
 	/// e.g.
 	/// - for a dev account "//Alice"
 	/// - with a password "//Alice///SECRET_PASSWORD"
-	#[clap(name = "suri", long, short)]
-	suri: String,
+	#[clap(name = "suri", long, short, required_unless_present_any = ["account", "use_wallet", "env", "offline"])]
+	suri: Option<String>,
+	/// The name of a key within the local keystore to sign with, instead of providing a secret key
+	/// URI (see `pop key import`).
+	#[clap(long, conflicts_with = "suri")]
+	account: Option<String>,
+	/// Select a key from the local keystore to sign with.
+	#[clap(long, conflicts_with_all = ["suri", "account"])]
+	use_wallet: bool,
+	/// Sign the deployment with an external signer, e.g. an air-gapped device: the payload to be
+	/// signed is displayed, along with a QR code, and the contract is uploaded and instantiated
+	/// once the signature is provided. No secret key is required.
+	#[clap(
+		long,
+		requires = "signer",
+		conflicts_with_all = ["suri", "account", "use_wallet", "upload_only", "skip_upload_if_exists"]
+	)]
+	offline: bool,
+	/// The address of the account signing the deployment with an external signer.
+	#[clap(long, requires = "offline")]
+	signer: Option<String>,
+	#[command(flatten)]
+	output: OutputArgs,
 }
+
+/// The deployed contract, as output by `--output json`.
+#[derive(Default, Serialize)]
+struct DeploymentReport {
+	/// The address of the contract, unless only its code was uploaded.
+	address: Option<String>,
+	/// The hash of the contract code, when uploaded or already on chain.
+	code_hash: Option<String>,
+}
+
 impl UpContractCommand {
+	/// The contract project, detected from the current directory unless provided.
+	fn path(&self) -> Option<PathBuf> {
+		Some(crate::contract_path(self.path.as_deref()))
+	}
+
 	pub(crate) async fn execute(&self) -> anyhow::Result<()> {
-		clear_screen()?;
+		self.output.clear_screen()?;
 		intro(format!("{}: Deploy a smart contract", style(" Pop CLI ").black().on_magenta()))?;
 
-		let instantiate_exec = set_up_deployment(UpOpts {
-			path: self.path.clone(),
+		let environment = match environment(&self.path(), self.env.as_deref(), crate::config()) {
+			Ok(environment) => environment,
+			Err(e) => {
+				outro_cancel(format!("{e}"))?;
+				return Ok(());
+			},
+		};
+		let url = self.url.clone().unwrap_or_else(|| environment.url.clone());
+		if self.offline {
+			return self.deploy_offline(url).await;
+		}
+		let suri = match resolve_suri(
+			self.suri.as_deref(),
+			self.account.as_deref(),
+			self.use_wallet,
+			&environment,
+		) {
+			Ok(suri) => suri,
+			Err(e) => {
+				outro_cancel(format!("{e}"))?;
+				return Ok(());
+			},
+		};
+		if self.upload_only {
+			return self.upload(url, suri).await;
+		}
+		let mut skip_upload = false;
+		if self.skip_upload_if_exists {
+			match uploaded_code_hash(&self.path(), &url).await {
+				Ok(Some(code_hash)) => {
+					log::info(format!(
+						"The contract code {code_hash} has already been uploaded, skipping upload."
+					))?;
+					skip_upload = true;
+				},
+				Ok(None) => log::info("The contract code has not been uploaded yet.")?,
+				Err(e) => {
+					outro_cancel(format!("Unable to check for existing code: {e}"))?;
+					return Ok(());
+				},
+			}
+		}
+		let instantiate_exec = match set_up_deployment(UpOpts {
+			path: self.path(),
 			constructor: self.constructor.clone(),
 			args: self.args.clone(),
 			value: self.value.clone(),
 			gas_limit: self.gas_limit,
 			proof_size: self.proof_size,
 			salt: self.salt.clone(),
-			url: self.url.clone(),
-			suri: self.suri.clone(),
+			url,
+			suri,
+			skip_upload,
 		})
-		.await?;
+		.await
+		{
+			Ok(exec) => exec,
+			Err(e) => {
+				outro_cancel(format!("{e}"))?;
+				return Ok(());
+			},
+		};
 
 		let weight_limit;
 		if self.gas_limit.is_some() && self.proof_size.is_some() {
 			weight_limit = Weight::from_parts(self.gas_limit.unwrap(), self.proof_size.unwrap());
 		} else {
-			let spinner = cliclack::spinner();
+			let spinner = crate::progress::spinner();
 			spinner.start("Doing a dry run to estimate the gas...");
-			weight_limit = match dry_run_gas_estimate_instantiate(&instantiate_exec).await {
-				Ok(w) => {
-					log::info(format!("Gas limit {:?}", w))?;
-					w
-				},
-				Err(e) => {
-					spinner.error(format!("{e}"));
-					outro_cancel("Deployment failed.")?;
-					return Ok(());
-				},
-			};
+			let estimate =
+				match dry_run_gas_estimate_instantiate(&instantiate_exec, self.gas_margin).await {
+					Ok(estimate) => estimate,
+					Err(e) => {
+						spinner.error(format!("{e}"));
+						outro_cancel("Deployment failed.")?;
+						return Ok(());
+					},
+				};
+			spinner.stop(format!(
+				"Estimated gas limit: ref_time {}, proof_size {} ({}% margin)",
+				estimate.weight.ref_time(),
+				estimate.weight.proof_size(),
+				self.gas_margin
+			));
+			log::info(format!("Estimated storage deposit: {}", estimate.storage_deposit))?;
+			weight_limit = estimate.weight;
 		}
-		let spinner = cliclack::spinner();
-		spinner.start("Uploading and instantiating the contract...");
-		let contract_address = instantiate_smart_contract(instantiate_exec, weight_limit)
+		let spinner = crate::progress::spinner();
+		spinner.start(if skip_upload {
+			"Instantiating the contract..."
+		} else {
+			"Uploading and instantiating the contract..."
+		});
+		let contract_info = instantiate_smart_contract(instantiate_exec, weight_limit)
 			.await
 			.map_err(|err| anyhow!("{} {}", "ERROR:", format!("{err:?}")))?;
 		spinner.stop(format!(
 			"Contract deployed and instantiated: The Contract Address is {:?}",
-			contract_address
+			contract_info.address
 		));
+		if let Some(code_hash) = &contract_info.code_hash {
+			log::info(format!("The contract code hash is {:?}", code_hash))?;
+		}
+		self.record(vec![contract_info.address.clone()])?;
 		outro("Deployment complete")?;
+		self.output.report(&DeploymentReport {
+			address: Some(contract_info.address),
+			code_hash: contract_info.code_hash,
+		})?;
+		Ok(())
+	}
+
+	/// Uploads and instantiates the contract using an external signer.
+	async fn deploy_offline(&self, url: url::Url) -> anyhow::Result<()> {
+		let signer = self.signer.clone().unwrap_or_default();
+		let spinner = crate::progress::spinner();
+		spinner.start("Doing a dry run on behalf of the signer to estimate the gas...");
+		let up_opts = UpOpts {
+			path: self.path(),
+			constructor: self.constructor.clone(),
+			args: self.args.clone(),
+			value: self.value.clone(),
+			gas_limit: self.gas_limit,
+			proof_size: self.proof_size,
+			salt: self.salt.clone(),
+			url,
+			suri: String::new(),
+			skip_upload: false,
+		};
+		let unsigned = match prepare_offline_instantiate(&up_opts, &signer, self.gas_margin).await {
+			Ok(unsigned) => unsigned,
+			Err(e) => {
+				spinner.error(format!("{e}"));
+				outro_cancel("Deployment failed.")?;
+				return Ok(());
+			},
+		};
+		spinner.stop(format!(
+			"Gas limit: ref_time {}, proof_size {}, storage deposit: {}",
+			unsigned.estimate.weight.ref_time(),
+			unsigned.estimate.weight.proof_size(),
+			unsigned.estimate.storage_deposit
+		));
+		let address = match sign_externally(&unsigned, &signer).await {
+			Ok(submitted) => submitted.contract,
+			Err(e) => {
+				outro_cancel(format!("Deployment failed: {e}"))?;
+				return Ok(());
+			},
+		};
+		match &address {
+			Some(contract) => log::success(format!(
+				"Contract deployed and instantiated: The Contract Address is {contract:?}"
+			))?,
+			None => log::warning("No contract instantiation event was found.")?,
+		}
+		self.record(address.iter().cloned().collect())?;
+		outro("Deployment complete")?;
+		self.output.report(&DeploymentReport { address, code_hash: None })?;
+		Ok(())
+	}
+
+	/// Records the deployment within the history of the contract project.
+	///
+	/// # Arguments
+	///
+	/// * `artifacts` - the contract address or code hash resulting from the deployment
+	fn record(&self, artifacts: Vec<String>) -> anyhow::Result<()> {
+		let path = crate::contract_path(self.path.as_deref());
+		history::record(&path, "up contract", &["--suri", "-s"], artifacts)
+	}
+
+	/// Uploads the contract code without instantiating it.
+	async fn upload(&self, url: url::Url, suri: String) -> anyhow::Result<()> {
+		match uploaded_code_hash(&self.path(), &url).await {
+			Ok(Some(code_hash)) => {
+				outro(format!(
+					"The contract code has already been uploaded: The code hash is {code_hash}"
+				))?;
+				let report = DeploymentReport { code_hash: Some(code_hash), ..Default::default() };
+				return self.output.report(&report);
+			},
+			Ok(None) => {},
+			Err(e) => {
+				outro_cancel(format!("Unable to check for existing code: {e}"))?;
+				return Ok(());
+			},
+		}
+		let upload_exec = match set_up_upload(UpOpts {
+			path: self.path(),
+			constructor: self.constructor.clone(),
+			args: self.args.clone(),
+			value: self.value.clone(),
+			gas_limit: self.gas_limit,
+			proof_size: self.proof_size,
+			salt: self.salt.clone(),
+			url,
+			suri,
+			skip_upload: false,
+		})
+		.await
+		{
+			Ok(exec) => exec,
+			Err(e) => {
+				outro_cancel(format!("{e}"))?;
+				return Ok(());
+			},
+		};
+
+		let spinner = crate::progress::spinner();
+		spinner.start("Doing a dry run to estimate the storage deposit...");
+		match dry_run_upload(&upload_exec).await {
+			Ok(deposit) => spinner.stop(format!("Estimated storage deposit: {deposit}")),
+			Err(e) => {
+				spinner.error(format!("{e}"));
+				outro_cancel("Upload failed.")?;
+				return Ok(());
+			},
+		}
+		let spinner = crate::progress::spinner();
+		spinner.start("Uploading the contract code...");
+		let code_hash = match upload_smart_contract(&upload_exec).await {
+			Ok(code_hash) => code_hash,
+			Err(e) => {
+				spinner.error(format!("{e}"));
+				outro_cancel("Upload failed.")?;
+				return Ok(());
+			},
+		};
+		spinner.stop(format!("Contract code uploaded: The code hash is {code_hash}"));
+		self.record(vec![code_hash.to_string()])?;
+		outro("Upload complete")?;
+		self.output
+			.report(&DeploymentReport { code_hash: Some(code_hash), ..Default::default() })?;
 		Ok(())
 	}
 }
@@ -0,0 +1,163 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use anyhow::anyhow;
+use clap::Args;
+use cliclack::{clear_screen, intro, log, outro, outro_cancel};
+use pop_contracts::{
+	dry_run_gas_estimate_instantiate, environment, instantiate_smart_contract, parse_hex_bytes,
+	set_up_deployment, uploaded_code_hash, ContractDeployment, DeploymentPlan, DeploymentState,
+	UpOpts, DEFAULT_GAS_MARGIN,
+};
+use std::path::PathBuf;
+use url::Url;
+
+use crate::{commands::key::resolve_suri, style::style};
+
+#[derive(Args)]
+pub struct UpContractsCommand {
+	/// Path to the manifest describing the contracts to deploy, their constructors, arguments and
+	/// salts. The address of a contract can be passed to the constructor of another using
+	/// `${name}`, in which case it is deployed first.
+	#[clap(long, default_value = "deploy.toml")]
+	manifest: PathBuf,
+	/// Deploy every contract again, ignoring the progress of a previous deployment. By default, a
+	/// deployment which failed part way resumes from the first contract not yet deployed.
+	#[clap(long)]
+	restart: bool,
+	/// The margin added to the gas estimated by the dry-run, as a percentage.
+	#[clap(long, default_value_t = DEFAULT_GAS_MARGIN)]
+	gas_margin: u8,
+	/// The environment to deploy the contracts to, providing the endpoint and default signer: one
+	/// of `local`, `pop-testnet`, `shibuya` or an environment defined within the pop.toml next to
+	/// the manifest. [default: local]
+	#[clap(long)]
+	env: Option<String>,
+	/// Websocket endpoint of a node, [default: the endpoint of the environment]
+	#[clap(name = "url", long, value_parser)]
+	url: Option<url::Url>,
+	/// Secret key URI for the account deploying the contracts, [default: the signer of the
+	/// environment]
+	#[clap(name = "suri", long, short)]
+	suri: Option<String>,
+	/// The name of a key within the local keystore to sign with, instead of providing a secret key
+	/// URI (see `pop key import`).
+	#[clap(long, conflicts_with = "suri")]
+	account: Option<String>,
+	/// Select a key from the local keystore to sign with.
+	#[clap(long, conflicts_with_all = ["suri", "account"])]
+	use_wallet: bool,
+}
+
+impl UpContractsCommand {
+	pub(crate) async fn execute(&self) -> anyhow::Result<()> {
+		clear_screen()?;
+		intro(format!("{}: Deploy smart contracts", style(" Pop CLI ").black().on_magenta()))?;
+
+		let plan = match DeploymentPlan::load(&self.manifest) {
+			Ok(plan) => plan,
+			Err(e) => {
+				outro_cancel(format!("{e}"))?;
+				return Ok(());
+			},
+		};
+		let project = self.manifest.parent().map(|p| p.to_path_buf());
+		let environment = match environment(&project, self.env.as_deref(), crate::config()) {
+			Ok(environment) => environment,
+			Err(e) => {
+				outro_cancel(format!("{e}"))?;
+				return Ok(());
+			},
+		};
+		let url = self.url.clone().unwrap_or_else(|| environment.url.clone());
+		let suri = match resolve_suri(
+			self.suri.as_deref(),
+			self.account.as_deref(),
+			self.use_wallet,
+			&environment,
+		) {
+			Ok(suri) => suri,
+			Err(e) => {
+				outro_cancel(format!("{e}"))?;
+				return Ok(());
+			},
+		};
+
+		if self.restart {
+			DeploymentState::clear(&self.manifest)?;
+		}
+		let mut state = DeploymentState::load(&self.manifest)?;
+		for contract in plan.order()? {
+			if let Some(address) = state.deployed.get(&contract.name) {
+				log::info(format!("`{}` is already deployed at {address}.", contract.name))?;
+				continue;
+			}
+			match self.deploy(contract, &state, &url, &suri).await {
+				Ok(address) => {
+					state.deployed.insert(contract.name.clone(), address);
+					state.save(&self.manifest)?;
+				},
+				Err(e) => {
+					outro_cancel(format!(
+						"Failed to deploy `{}`: {e}\nRun the command again to resume the \
+						 deployment from `{}`.",
+						contract.name, contract.name
+					))?;
+					return Ok(());
+				},
+			}
+		}
+
+		let summary: Vec<_> = plan
+			.order()?
+			.iter()
+			.filter_map(|c| {
+				state.deployed.get(&c.name).map(|address| format!("{:<20} {address}", c.name))
+			})
+			.collect();
+		log::success(format!("Contracts deployed:\n{}", summary.join("\n")))?;
+		outro("Deployment complete")?;
+		Ok(())
+	}
+
+	/// Deploys a single contract of the plan, returning its address.
+	async fn deploy(
+		&self,
+		contract: &ContractDeployment,
+		state: &DeploymentState,
+		url: &Url,
+		suri: &str,
+	) -> anyhow::Result<String> {
+		let spinner = crate::progress::spinner();
+		spinner.start(format!("Deploying `{}`...", contract.name));
+		let result = async {
+			let path = Some(contract.path.clone());
+			// Contracts sharing the same code only upload it once.
+			let skip_upload = uploaded_code_hash(&path, url).await?.is_some();
+			let instantiate_exec = set_up_deployment(UpOpts {
+				path,
+				constructor: contract.constructor.clone(),
+				args: contract.resolve_args(&state.deployed)?,
+				value: contract.value.clone(),
+				gas_limit: None,
+				proof_size: None,
+				salt: contract.salt.as_deref().map(parse_hex_bytes).transpose()?,
+				url: url.clone(),
+				suri: suri.to_string(),
+				skip_upload,
+			})
+			.await?;
+			let estimate =
+				dry_run_gas_estimate_instantiate(&instantiate_exec, self.gas_margin).await?;
+			let contract_info = instantiate_smart_contract(instantiate_exec, estimate.weight)
+				.await
+				.map_err(|err| anyhow!("{err:?}"))?;
+			anyhow::Ok(contract_info.address)
+		}
+		.await;
+		match &result {
+			Ok(address) => spinner.stop(format!("`{}` deployed at {address}", contract.name)),
+			Err(e) => spinner.error(format!("{e}")),
+		}
+		result
+	}
+}
@@ -0,0 +1,157 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use super::{confirm_build, report_verifications, ProgressReporter};
+use crate::{
+	commands::binaries::lock::{lock, project_version},
+	interrupt,
+	style::{style, Theme},
+};
+use clap::Args;
+use cliclack::{clear_screen, intro, log, outro, outro_cancel, set_theme};
+use pop_contracts::{
+	clean_contracts_node_state, compatible_contracts_node_version, contracts_node_args,
+	contracts_node_generator, contracts_node_state, is_contracts_node_ready, run_contracts_node,
+	DEFAULT_CONTRACTS_NODE_PORT,
+};
+use std::{fs, net::TcpListener, path::PathBuf, time::Duration};
+use tempfile::NamedTempFile;
+
+/// The time allowed for the node to start serving RPC requests.
+const READY_TIMEOUT: Duration = Duration::from_secs(60);
+
+#[derive(Args)]
+pub(crate) struct ContractsNodeCommand {
+	/// The version of substrate-contracts-node to be used, as per the release tag (e.g.
+	/// "v0.41.0"). If not specified, the version pinned by the project using `pop binaries use
+	/// substrate-contracts-node` is used, otherwise the version locked within its pop.lock,
+	/// otherwise the release compatible with the ink! version of the contract at `path`, otherwise
+	/// the latest release.
+	#[arg(long)]
+	version: Option<String>,
+	/// Path to the contract project, [default: the current project]
+	#[arg(short = 'p', long)]
+	path: Option<PathBuf>,
+	/// The port on which the node serves RPC requests.
+	#[arg(long, default_value_t = DEFAULT_CONTRACTS_NODE_PORT)]
+	port: u16,
+	/// Start from a fresh chain, removing the state persisted by previous launches.
+	#[arg(long)]
+	clean: bool,
+	/// Discard the state of the chain once the node terminates, rather than persisting it for the
+	/// next launch.
+	#[arg(long, conflicts_with = "clean")]
+	tmp: bool,
+}
+
+impl ContractsNodeCommand {
+	pub(crate) async fn execute(&self) -> anyhow::Result<()> {
+		clear_screen()?;
+		intro(format!(
+			"{}: Launch a local contracts node",
+			style(" Pop CLI ").black().on_magenta()
+		))?;
+		set_theme(Theme);
+
+		let cache = crate::cache()?;
+		let path = crate::contract_path(self.path.as_deref());
+		let version = match &self.version {
+			Some(version) => Some(version.clone()),
+			None => match project_version(&path, "substrate-contracts-node")? {
+				Some(version) => Some(version),
+				None => compatible_contracts_node_version(&Some(path))?,
+			},
+		};
+		let binary = contracts_node_generator(cache.clone(), version.as_deref()).await?;
+		if !binary.path.exists() {
+			if !confirm_build([&binary])? {
+				outro_cancel("Cannot launch the contracts node until it is available.")?;
+				return Ok(());
+			}
+			log::info(format!("ℹ️ {} will be cached at {}", binary.name, cache.display()))?;
+			let spinner = crate::progress::spinner();
+			spinner.start(format!("📦 Sourcing {}...", binary.name));
+			let verifications = match binary.source(&cache, ProgressReporter(&spinner)).await {
+				Ok(verifications) => verifications,
+				Err(e) => {
+					spinner.error(format!("🚫 Sourcing failed: {e}"));
+					outro_cancel("Cannot launch the contracts node until it is available.")?;
+					return Ok(());
+				},
+			};
+			spinner.stop(format!("✅ Sourcing {} complete.", binary.name));
+			report_verifications(&verifications)?;
+		}
+		if let Err(e) = lock(&path, [&binary]) {
+			outro_cancel(format!("{e}"))?;
+			return Ok(());
+		}
+
+		if TcpListener::bind(("127.0.0.1", self.port)).is_err() {
+			outro_cancel(format!(
+				"Port {} is already in use, select another using `--port`.",
+				self.port
+			))?;
+			return Ok(());
+		}
+		// The state of the chain is persisted across launches, unless requested otherwise.
+		let state = (!self.tmp).then(|| contracts_node_state(&cache, &binary.name));
+		if let Some(state) = &state {
+			if self.clean && clean_contracts_node_state(state)? {
+				log::info(format!(
+					"Removed the state of previous launches at {}",
+					state.display()
+				))?;
+			}
+		}
+
+		let log = NamedTempFile::new()?;
+		let args = contracts_node_args(self.port, state.as_deref());
+		let mut node = run_contracts_node(&binary.path, &args, Some(log.as_file()))?;
+		// Terminated should ctrl-c be pressed before the node is ready.
+		let _tracked = interrupt::track(node.id());
+
+		// Wait for the node to serve requests, surfacing its output should it terminate early.
+		let spinner = crate::progress::spinner();
+		spinner.start(format!("Launching {}...", binary.name));
+		let started = std::time::Instant::now();
+		loop {
+			let output = fs::read_to_string(log.path()).unwrap_or_default();
+			if is_contracts_node_ready(&output) {
+				spinner.stop("Node ready.");
+				break;
+			}
+			if let Some(status) = node.try_wait()? {
+				spinner.error(format!("🚫 {} exited ({status}).", binary.name));
+				log::error(output.trim())?;
+				outro_cancel("Unable to launch the contracts node.")?;
+				return Ok(());
+			}
+			if started.elapsed() > READY_TIMEOUT {
+				node.kill()?;
+				spinner.error("🚫 Timed out waiting for the node.");
+				outro_cancel(format!("See the output at {}", log.path().display()))?;
+				return Ok(());
+			}
+			tokio::time::sleep(Duration::from_millis(500)).await;
+		}
+
+		let mut details = format!(
+			"🚀 {} launched successfully - ctrl-c to terminate\n   endpoint: ws://127.0.0.1:{}\n   logs: tail -f {}",
+			binary.name,
+			self.port,
+			log.path().display()
+		);
+		match &state {
+			Some(state) => details.push_str(&format!(
+				"\n   state: {} (use `--clean` to start afresh)",
+				state.display()
+			)),
+			None => details.push_str("\n   state: discarded on exit"),
+		}
+		log::success(details)?;
+		interrupt::wait().await?;
+		node.kill()?;
+		outro("Done")?;
+		Ok(())
+	}
+}
@@ -0,0 +1,110 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::{
+	interrupt,
+	style::{style, Theme},
+};
+use clap::Args;
+use cliclack::{clear_screen, intro, log, outro, outro_cancel, set_theme};
+use pop_parachains::{Fork, CHOPSTICKS_DEFAULT_VERSION, DEFAULT_FORK_PORT};
+use std::{fs, path::PathBuf, time::Duration};
+use tempfile::NamedTempFile;
+use url::Url;
+
+/// The time allowed for the fork to be served, including fetching Chopsticks on first use.
+const READY_TIMEOUT: Duration = Duration::from_secs(300);
+
+#[derive(Args)]
+pub(crate) struct ForkCommand {
+	/// Websocket endpoint of the live chain. Can be specified multiple times, with each endpoint
+	/// tried in order.
+	#[arg(long = "uri", value_parser, required = true)]
+	uris: Vec<Url>,
+	/// The port on which the fork serves RPC requests.
+	#[arg(long, default_value_t = DEFAULT_FORK_PORT)]
+	port: u16,
+	/// The block from which the chain is forked, by number or hash. [default: the latest block]
+	#[arg(long)]
+	block: Option<String>,
+	/// A runtime wasm replacing that of the live chain, to test an upgrade against production
+	/// state.
+	#[arg(long)]
+	runtime: Option<PathBuf>,
+	/// A database persisting the state of the fork across launches.
+	#[arg(long)]
+	db: Option<PathBuf>,
+	/// Accept any signature, so that extrinsics can be submitted on behalf of any account.
+	#[arg(long)]
+	mock_signatures: bool,
+	/// The release of Chopsticks to use, as per its npm version.
+	#[arg(long, default_value = CHOPSTICKS_DEFAULT_VERSION)]
+	chopsticks_version: String,
+}
+
+impl ForkCommand {
+	pub(crate) async fn execute(&self) -> anyhow::Result<()> {
+		clear_screen()?;
+		intro(format!("{}: Fork a live chain", style(" Pop CLI ").black().on_magenta()))?;
+		set_theme(Theme);
+
+		let fork = Fork {
+			endpoints: self.uris.clone(),
+			port: self.port,
+			block: self.block.clone(),
+			wasm_override: self.runtime.clone(),
+			db: self.db.clone(),
+			mock_signatures: self.mock_signatures,
+			version: self.chopsticks_version.clone(),
+		};
+		let log = NamedTempFile::new()?;
+		let mut process = match fork.run(Some(log.as_file())) {
+			Ok(process) => process,
+			Err(e) => {
+				outro_cancel(format!("{e}"))?;
+				return Ok(());
+			},
+		};
+		// Terminated should ctrl-c be pressed before the fork is ready.
+		let _tracked = interrupt::track(process.id());
+
+		// Wait for the fork to be served, surfacing its output should it terminate early.
+		let spinner = crate::progress::spinner();
+		spinner.start(format!("Forking {}...", self.uris[0]));
+		let started = std::time::Instant::now();
+		loop {
+			let output = fs::read_to_string(log.path()).unwrap_or_default();
+			if Fork::is_ready(&output) {
+				spinner.stop("Fork ready.");
+				break;
+			}
+			if let Some(status) = process.try_wait()? {
+				spinner.error(format!("🚫 Chopsticks exited ({status})."));
+				log::error(output.trim())?;
+				outro_cancel("Unable to fork the chain.")?;
+				return Ok(());
+			}
+			if started.elapsed() > READY_TIMEOUT {
+				process.kill()?;
+				spinner.error("🚫 Timed out waiting for the fork.");
+				outro_cancel(format!("See the output at {}", log.path().display()))?;
+				return Ok(());
+			}
+			tokio::time::sleep(Duration::from_millis(500)).await;
+		}
+
+		let mut details = format!(
+			"🚀 {} forked successfully - ctrl-c to terminate\n   endpoint: {}\n   logs: tail -f {}",
+			self.uris[0],
+			fork.endpoint(),
+			log.path().display()
+		);
+		if let Some(runtime) = &self.runtime {
+			details.push_str(&format!("\n   runtime: {}", runtime.display()));
+		}
+		log::success(details)?;
+		interrupt::wait().await?;
+		process.kill()?;
+		outro("Done")?;
+		Ok(())
+	}
+}
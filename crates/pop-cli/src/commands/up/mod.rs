@@ -2,10 +2,27 @@
 
 #[cfg(feature = "contract")]
 mod contract;
+#[cfg(feature = "contract")]
+mod contracts;
+#[cfg(feature = "contract")]
+mod contracts_node;
+#[cfg(feature = "parachain")]
+mod fork;
 #[cfg(feature = "parachain")]
 mod parachain;
+#[cfg(feature = "parachain")]
+mod runtime_upgrade;
+#[cfg(feature = "parachain")]
+mod testnet;
 
+use crate::prompt;
+use anyhow::anyhow;
 use clap::{Args, Subcommand};
+use cliclack::{log, multi_progress, ProgressBar};
+use futures::future::join_all;
+use pop_common::{Binary, Platform, Progress, Status, Verification};
+use std::{path::PathBuf, time::Duration};
+use tokio::time::sleep;
 
 #[derive(Args)]
 #[command(args_conflicts_with_subcommands = true)]
@@ -20,8 +37,186 @@ pub(crate) enum UpCommands {
 	/// Deploy a parachain to a local network.
 	#[clap(alias = "p")]
 	Parachain(parachain::ZombienetCommand),
+	#[cfg(feature = "parachain")]
+	/// Fork a live chain locally, to test extrinsics and upgrades against production state.
+	#[clap(alias = "f")]
+	Fork(fork::ForkCommand),
+	#[cfg(feature = "parachain")]
+	/// Upgrade the runtime of a chain on a local network, waiting for the upgrade to be enacted.
+	RuntimeUpgrade(runtime_upgrade::RuntimeUpgradeCommand),
+	#[cfg(feature = "parachain")]
+	/// Onboard a parachain to a public testnet, such as Paseo.
+	#[clap(alias = "paseo")]
+	Testnet(testnet::TestnetCommand),
 	#[cfg(feature = "contract")]
 	/// Deploy a smart contract to a node.
 	#[clap(alias = "c")]
 	Contract(contract::UpContractCommand),
+	#[cfg(feature = "contract")]
+	/// Deploy multiple smart contracts, as described by a deployment manifest.
+	Contracts(contracts::UpContractsCommand),
+	#[cfg(feature = "contract")]
+	/// Launch a local contracts node.
+	#[clap(alias = "n")]
+	ContractsNode(contracts_node::ContractsNodeCommand),
+}
+
+/// Reports any observed status updates to a progress bar, including the progress of cloning,
+/// downloading and building, rendered as the step running along with how far along it is.
+#[derive(Copy, Clone)]
+pub(crate) struct ProgressReporter<'a>(pub(crate) &'a ProgressBar);
+
+impl Status for ProgressReporter<'_> {
+	fn update(&self, status: &str) {
+		self.0.start(status.replace("   Compiling", "Compiling"))
+	}
+
+	fn progress(&self, progress: Progress) {
+		crate::progress::progress(&progress);
+		self.update(&progress.to_string())
+	}
+}
+
+/// Reports the released binaries among `binaries` which are built from source instead, as no
+/// release of them exists for the platform, and asks whether to proceed. Binaries without a
+/// version, such as parachains sourced from a repository, are always built and not reported.
+///
+/// # Arguments
+///
+/// * `binaries` - the binaries about to be sourced
+pub(crate) fn confirm_build<'a>(
+	binaries: impl IntoIterator<Item = &'a Binary>,
+) -> anyhow::Result<bool> {
+	let built: Vec<_> = binaries
+		.into_iter()
+		.filter(|b| b.is_built() && !b.version.is_empty())
+		.map(|b| b.name.as_str())
+		.collect();
+	if built.is_empty() {
+		return Ok(true);
+	}
+	let platform = Platform::detect().map_or_else(
+		|_| format!("{} {}", std::env::consts::ARCH, std::env::consts::OS),
+		|p| p.to_string(),
+	);
+	log::warning(format!(
+		"⚠️ No release of {} is available for {platform}: it can be built from source instead, \
+		 which may take a while.",
+		built.join(", ")
+	))?;
+	prompt::confirm("🔨 Would you like to build from source?", true)
+}
+
+/// Sources the `binaries` concurrently, rendering the progress of each beneath a shared heading and
+/// retrying each once upon failure, rather than one after the other. Binaries sharing a path are
+/// sourced once. Returns the verification of each artifact sourced, or a summary of every failure
+/// once all have completed.
+///
+/// # Arguments
+///
+/// * `binaries` - the binaries sourced
+/// * `cache` - path to the local cache
+pub(crate) async fn source_binaries(
+	binaries: &[&Binary],
+	cache: &PathBuf,
+) -> anyhow::Result<Vec<Verification>> {
+	let mut unique: Vec<&Binary> = Vec::new();
+	for binary in binaries {
+		if !unique.iter().any(|b| b.path == binary.path) {
+			unique.push(binary);
+		}
+	}
+	let names: Vec<_> = unique.iter().map(|b| b.name.as_str()).collect();
+	let multi = multi_progress(format!("📦 Sourcing {}...", names.join(", ")));
+	let bars: Vec<_> = unique
+		.iter()
+		.map(|binary| {
+			let bar = multi.add(cliclack::spinner());
+			bar.start(format!("Sourcing {}...", binary.name));
+			bar
+		})
+		.collect();
+	let results =
+		join_all(unique.iter().zip(&bars).map(|(binary, bar)| source(binary, cache, bar))).await;
+	multi.stop();
+	let mut verifications = Vec::new();
+	let mut failures = Vec::new();
+	for (name, result) in names.into_iter().zip(results) {
+		match result {
+			Ok(verified) => verifications.extend(verified),
+			Err(e) => failures.push((name, e.to_string())),
+		}
+	}
+	match failures.is_empty() {
+		true => Ok(verifications),
+		false => Err(anyhow!(failure_summary(&failures))),
+	}
+}
+
+/// Sources `binary`, rendering its progress to `bar` and retrying once upon failure, after showing
+/// why the first attempt failed.
+async fn source(
+	binary: &Binary,
+	cache: &PathBuf,
+	bar: &ProgressBar,
+) -> Result<Vec<Verification>, pop_common::Error> {
+	let mut retried = false;
+	loop {
+		match binary.source(cache, ProgressReporter(bar)).await {
+			Ok(verifications) => {
+				bar.stop(format!("✅ Sourcing {} complete.", binary.name));
+				return Ok(verifications);
+			},
+			Err(e) if retried => {
+				bar.error(format!("🚫 Sourcing {} failed.", binary.name));
+				return Err(e);
+			},
+			Err(e) => {
+				retried = true;
+				bar.error(format!("🚫 Sourcing {} failed: {e}", binary.name));
+				sleep(Duration::from_secs(1)).await;
+				bar.start(format!("Sourcing {} failed, retrying...", binary.name));
+			},
+		}
+	}
+}
+
+/// A summary of the binaries which could not be sourced, along with why.
+fn failure_summary(failures: &[(&str, String)]) -> String {
+	let failures: Vec<_> =
+		failures.iter().map(|(name, error)| format!("{name}: {error}")).collect();
+	format!("🚫 The following binaries could not be sourced:\n{}", failures.join("\n"))
+}
+
+/// Reports the verification of each artifact sourced, as recorded within the audit log of the
+/// cache, warning about those which could not be verified by a signature or attestation.
+///
+/// # Arguments
+///
+/// * `verifications` - the verification of each artifact sourced
+pub(crate) fn report_verifications(verifications: &[Verification]) -> anyhow::Result<()> {
+	for verification in verifications {
+		match verification.is_verified() {
+			true => log::success(format!("🔏 {verification}"))?,
+			false => log::warning(format!("⚠️ {verification}"))?,
+		}
+	}
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn failure_summary_works() {
+		assert_eq!(
+			failure_summary(&[
+				("polkadot", "connection reset".into()),
+				("polkadot-parachain", "not found".into())
+			]),
+			"🚫 The following binaries could not be sourced:\npolkadot: connection reset\n\
+			 polkadot-parachain: not found"
+		);
+	}
 }
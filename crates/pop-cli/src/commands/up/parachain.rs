@@ -1,34 +1,171 @@
 // SPDX-License-Identifier: GPL-3.0
 
-use crate::style::{style, Theme};
-use clap::Args;
-use cliclack::{
-	clear_screen, confirm, intro, log, multi_progress, outro, outro_cancel, set_theme, ProgressBar,
+use super::{confirm_build, report_verifications, source_binaries};
+use crate::{
+	commands::binaries::lock::{lock, project_version},
+	prompt,
+	style::{style, Theme},
 };
+use clap::Args;
+use cliclack::{clear_screen, intro, log, outro, outro_cancel, set_theme};
 use console::{Emoji, Style};
-use pop_parachains::{NetworkNode, Status, Zombienet};
-use std::time::Duration;
+use pop_common::Project;
+use pop_parachains::{
+	build_parachain, build_runtime, export_artifacts, export_genesis, is_parachain,
+	open_hrmp_channels, parse_genesis_override, parse_parachain_image, parse_rpc_port,
+	read_runtime, register_parachain, runtime_crate, tail, upgrade_runtime, wait_for_code,
+	wait_until_ready, ChainSpecOverrides, HrmpChannel, Images, LocalNetwork, LogFilter, LogLevel,
+	Monitoring, NetworkEndpoints, NetworkNode, NetworkProvider, ParachainArtifacts,
+	PolkadotRelease, Profile, Readiness, SourceWatcher, SystemChain, UpgradeMethod, Zombienet,
+	DEFAULT_HRMP_MAX_CAPACITY, DEFAULT_HRMP_MAX_MESSAGE_SIZE,
+};
+use std::{
+	collections::HashMap,
+	fs::File,
+	io::{BufRead, BufReader},
+	path::PathBuf,
+	time::{Duration, Instant},
+};
 use tokio::time::sleep;
+use url::Url;
+
+/// The identifier of the parachain launched from a project.
+const DEFAULT_PARA_ID: u32 = 2000;
+/// The sudo account of the local relay chain, used to register the parachain launched from a
+/// project and to open HRMP channels.
+const RELAY_SUDO: &str = "//Alice";
+/// The sudo account of the parachain launched from a project, used to upgrade its runtime.
+const PARACHAIN_SUDO: &str = "//Alice";
+/// The number of lines of the log of each node shown when the network does not become ready.
+const LOG_LINES: usize = 20;
+/// The time allowed for an upgraded runtime to be enacted, which for a parachain includes the
+/// validation upgrade delay of the relay chain.
+const UPGRADE_TIMEOUT: Duration = Duration::from_secs(300);
 
 #[derive(Args)]
 pub(crate) struct ZombienetCommand {
-	/// The Zombienet configuration file to be used.
+	/// The network configuration file to be used, either in the format expected by Zombienet or
+	/// in pop's native format (see the README). When not provided, the parachain project at
+	/// `--path` is built and launched alongside a local relay chain.
 	#[arg(short, long)]
-	file: String,
+	file: Option<String>,
+	/// Directory path of the parachain project to launch when no configuration file is provided,
+	/// [default: the current project]
+	#[arg(long, conflicts_with = "file")]
+	path: Option<PathBuf>,
+	/// The identifier of the parachain launched when no configuration file is provided.
+	#[arg(long, default_value_t = DEFAULT_PARA_ID, conflicts_with = "file")]
+	para_id: u32,
+	/// The number of collators of the parachain launched when no configuration file is provided.
+	#[arg(
+		long,
+		default_value_t = 1,
+		value_parser = clap::value_parser!(u32).range(1..),
+		conflicts_with = "file"
+	)]
+	collators: u32,
+	/// An argument provided to each collator of the parachain launched when no configuration file
+	/// is provided (e.g. `--collator-arg=--rpc-max-connections=1000`). Can be specified multiple
+	/// times.
+	#[arg(long = "collator-arg", allow_hyphen_values = true, conflicts_with = "file")]
+	collator_args: Vec<String>,
+	/// An argument provided to each validator of the relay chain launched when no configuration
+	/// file is provided (e.g. `--validator-arg=--pruning=archive`). Can be specified multiple
+	/// times.
+	#[arg(long = "validator-arg", allow_hyphen_values = true, conflicts_with = "file")]
+	validator_args: Vec<String>,
+	/// An override of the genesis configuration of the parachain launched when no configuration
+	/// file is provided, specified as `<path>=<json>` with a dot-separated path (e.g.
+	/// `--genesis-override balances='[["5GrwvaEF...", 1e15]]'` or
+	/// `--genesis-override sudo.key='"5GrwvaEF..."'`). Can be specified multiple times.
+	#[arg(long = "genesis-override", value_parser = parse_genesis_override, conflicts_with = "file")]
+	genesis_overrides: Vec<serde_json::Value>,
+	/// A JSON file patching the genesis configuration of the parachain launched when no
+	/// configuration file is provided, applied before any `--genesis-override`.
+	#[arg(long, conflicts_with = "file")]
+	genesis_patch: Option<PathBuf>,
+	/// The sudo account of the parachain launched when no configuration file is provided.
+	#[arg(long, conflicts_with = "file")]
+	sudo: Option<String>,
+	/// A system parachain to launch alongside the parachain when no configuration file is
+	/// provided: one of `asset-hub`, `bridge-hub`, `coretime` or `people`. Can be specified
+	/// multiple times.
+	#[arg(long = "system-chain", conflicts_with = "file")]
+	system_chains: Vec<SystemChain>,
 	/// The version of Polkadot to be used for the relay chain, as per the release tag (e.g.
-	/// "v1.7.0").
+	/// "v1.7.0" or "stable2407"). The binaries of each release are cached separately, so a network
+	/// can be pinned to a release. [default: the version pinned by the project using `pop
+	/// binaries use polkadot`, otherwise the version locked within its pop.lock, otherwise the
+	/// release of the Polkadot SDK the project depends on, otherwise the latest release]
 	#[arg(short, long)]
 	relay_chain: Option<String>,
 	/// The version of Polkadot to be used for a system parachain, as per the release tag (e.g.
-	/// "v1.7.0").
+	/// "v1.7.0"). [default: the version pinned by the project using `pop binaries use
+	/// polkadot-parachain`, otherwise the version locked within its pop.lock]
 	#[arg(short, long)]
 	system_parachain: Option<String>,
 	/// The url of the git repository of a parachain to be used, with branch/release tag specified as #fragment (e.g. 'https://github.com/org/repository#tag'). A specific binary name can also be optionally specified via query string parameter (e.g. 'https://github.com/org/repository?binaryname#tag'), defaulting to the name of the repository when not specified.
 	#[arg(short, long)]
 	parachain: Option<Vec<String>>,
-	/// Whether the output should be verbose.
-	#[arg(short, long, action)]
-	verbose: bool,
+	/// The RPC port of a node, specified as `<node>=<port>` (e.g. `--rpc-port collator-01=9944`),
+	/// overriding the configuration. Can be specified multiple times. Any node without an RPC
+	/// port is assigned the next free port from 9944.
+	#[arg(long = "rpc-port", value_parser = parse_rpc_port)]
+	rpc_ports: Vec<(String, u16)>,
+	/// Open HRMP channels between each pair of parachains once the network is launched, in both
+	/// directions, so that the parachains can exchange XCM messages.
+	#[arg(long)]
+	hrmp: bool,
+	/// Watch the runtime of the parachain launched when no configuration file is provided,
+	/// rebuilding it whenever its sources change and upgrading the running parachain to it.
+	#[arg(long, conflicts_with = "file")]
+	watch_runtime: bool,
+	/// Only stream the output of the named node (e.g. `alice` or `collator-01`) once the network
+	/// is launched. Can be specified multiple times. [default: all nodes]
+	#[arg(long = "follow")]
+	follow: Vec<String>,
+	/// The least severe level of the output streamed once the network is launched: `error`,
+	/// `warn`, `info`, `debug` or `trace`. The full output of each node remains within its log
+	/// file.
+	#[arg(long, default_value = "info")]
+	log_level: LogLevel,
+	/// The provider spawning the nodes: `native`, as local processes, or `k8s`, as pods within
+	/// the Kubernetes cluster of the current kubeconfig context.
+	#[arg(long, default_value = "native")]
+	provider: NetworkProvider,
+	/// The container image of the relay chain nodes, when not spawned natively. [default:
+	/// `parity/polkadot` of the relay chain release]
+	#[arg(long)]
+	relay_image: Option<String>,
+	/// The container image of the collators of a parachain, when not spawned natively, specified
+	/// as `<para-id>=<image>`. Can be specified multiple times. System parachains default to
+	/// `parity/polkadot-parachain` of the relay chain release.
+	#[arg(long = "parachain-image", value_parser = parse_parachain_image)]
+	parachain_images: Vec<(u32, String)>,
+	/// Expose the Prometheus endpoint of each node and launch Prometheus and Grafana, using
+	/// Docker, with a dashboard of block production, finality and collation.
+	#[arg(long, conflicts_with = "export")]
+	with_monitoring: bool,
+	/// The directory within which the state of the network is persisted, rather than a temporary
+	/// directory, so that it survives restarts.
+	#[arg(long, conflicts_with = "export")]
+	base_path: Option<PathBuf>,
+	/// Resume the network from the state persisted within `--base-path` by a previous launch,
+	/// rather than launching it from genesis.
+	#[arg(long, requires = "base_path")]
+	resume: bool,
+	/// Wipe the state persisted within `--base-path` by a previous launch, launching the network
+	/// from genesis.
+	#[arg(long, requires = "base_path", conflicts_with = "resume")]
+	purge: bool,
+	/// Rather than launching the network, write a Docker Compose file replicating it to the
+	/// specified path (e.g. `docker-compose.yml`).
+	#[arg(long)]
+	export: Option<PathBuf>,
+	/// The time allowed, in seconds, for the relay chain to finalize blocks and for each parachain
+	/// to produce blocks once the network is launched.
+	#[arg(long, default_value_t = 300)]
+	ready_timeout: u64,
 }
 impl ZombienetCommand {
 	pub(crate) async fn execute(&self) -> anyhow::Result<()> {
@@ -37,15 +174,100 @@ impl ZombienetCommand {
 		set_theme(Theme);
 
 		// Parse arguments
+		let (file, artifacts) = match &self.file {
+			Some(file) => (file.clone(), None),
+			None => match self.configure_project() {
+				Ok((file, artifacts)) => (file, Some(artifacts)),
+				Err(e) => {
+					outro_cancel(format!("{e}"))?;
+					return Ok(());
+				},
+			},
+		};
+		let relay_chain = match self.relay_chain_version() {
+			Ok(version) => version,
+			Err(e) => {
+				outro_cancel(format!("{e}"))?;
+				return Ok(());
+			},
+		};
+		let project = crate::project_path(self.path.as_deref());
+		let system_parachain = self
+			.system_parachain
+			.clone()
+			.or_else(|| project_version(&project, "polkadot-parachain").ok().flatten());
 		let cache = crate::cache()?;
-		let mut zombienet = Zombienet::new(
+		let mut zombienet = match Zombienet::new(
 			cache.clone(),
-			&self.file,
-			self.relay_chain.as_ref(),
-			self.system_parachain.as_ref(),
+			&file,
+			relay_chain.as_ref(),
+			system_parachain.as_ref(),
 			self.parachain.as_ref(),
 		)
-		.await?;
+		.await
+		{
+			Ok(zombienet) => zombienet,
+			Err(e) => {
+				outro_cancel(format!("{e}"))?;
+				return Ok(());
+			},
+		};
+		log::info(format!(
+			"Using Polkadot {} for the relay chain.",
+			zombienet.relay_chain_version()
+		))?;
+		// The monitoring stack reaches the nodes on the local host.
+		if self.with_monitoring && self.provider != NetworkProvider::Native {
+			outro_cancel("Monitoring is only supported for networks launched natively.")?;
+			return Ok(());
+		}
+		zombienet
+			.rpc_ports(self.rpc_ports.clone())
+			.monitoring(self.with_monitoring)
+			.provider(
+				self.provider,
+				Images {
+					relay_chain: self.relay_image.clone(),
+					parachains: self.parachain_images.clone(),
+				},
+			);
+		if let Some(export) = &self.export {
+			if let Err(e) = zombienet.export_docker_compose(export) {
+				outro_cancel(format!("{e}"))?;
+				return Ok(());
+			}
+			log::success(format!("Docker Compose file written to {}", export.display()))?;
+			if let Some(artifacts) = &artifacts {
+				log::info(format!(
+					"Once launched, register parachain {} on the relay chain using {} and {}.",
+					self.para_id,
+					artifacts.genesis_state.display(),
+					artifacts.genesis_wasm.display()
+				))?;
+			}
+			outro(format!("Launch the network with `docker compose -f {} up`", export.display()))?;
+			return Ok(());
+		}
+		// Any state of a previous launch is only discarded when requested.
+		let mut resumed = false;
+		if let Some(base_path) = &self.base_path {
+			zombienet.base_path(base_path.clone());
+			if self.purge {
+				zombienet.purge()?;
+				log::info(format!("Wiped the network state within {}.", base_path.display()))?;
+			} else if zombienet.has_state() {
+				if !self.resume {
+					outro_cancel(format!(
+						"{} holds the state of a previous network: resume it with `--resume` or \
+						 wipe it with `--purge`.",
+						base_path.display()
+					))?;
+					return Ok(());
+				}
+				resumed = true;
+				log::info(format!("Resuming the network from {}.", base_path.display()))?;
+			}
+		}
 		// Check if any binaries need to be sourced
 		let missing = zombienet.missing_binaries();
 		if missing.len() > 0 {
@@ -53,42 +275,30 @@ impl ZombienetCommand {
 				"⚠️ The following missing binaries are required: {}",
 				missing.iter().map(|b| b.name.as_str()).collect::<Vec<_>>().join(", ")
 			))?;
-			if !confirm("📦 Would you like to source them automatically now?")
-				.initial_value(true)
-				.interact()?
+			if !prompt::confirm("📦 Would you like to source them automatically now?", true)?
+				|| !confirm_build(missing.iter().copied())?
 			{
 				outro_cancel("🚫 Cannot deploy parachain to local network until all required binaries are available.")?;
 				return Ok(());
 			}
 			log::info(format!("ℹ️ They will be cached at {}", &cache.to_str().unwrap()))?;
-			// Source binaries
-			for binary in missing {
-				let multi = multi_progress(format!("📦 Sourcing {}...", binary.name));
-				let progress = multi.add(cliclack::spinner());
-				let progress_reporter = ProgressReporter(&progress);
-				for attempt in (0..=1).rev() {
-					if let Err(e) = binary.source(&cache, progress_reporter).await {
-						match attempt {
-							0 => {
-								progress.error(format!("🚫 Sourcing failed: {e}"));
-								multi.stop();
-								return Ok(());
-							},
-							_ => {
-								progress.error("🚫 Sourcing attempt failed, retrying...");
-								sleep(Duration::from_secs(1)).await;
-							},
-						}
-					}
-				}
-				progress.stop(format!("✅ Sourcing {} complete.", binary.name));
-				multi.stop();
+			// Source binaries concurrently
+			match source_binaries(&missing, &cache).await {
+				Ok(verifications) => report_verifications(&verifications)?,
+				Err(e) => {
+					outro_cancel(format!("{e}"))?;
+					return Ok(());
+				},
 			}
 		}
+		// The binaries used are locked by the project, so that everyone launches the same network.
+		if let Err(e) = lock(&project, zombienet.binaries()) {
+			outro_cancel(format!("{e}"))?;
+			return Ok(());
+		}
 		// Finally spawn network and wait for signal to terminate
-		let spinner = cliclack::spinner();
+		let spinner = crate::progress::spinner();
 		spinner.start("🚀 Launching local network...");
-		//tracing_subscriber::fmt().init();
 		match zombienet.spawn().await {
 			Ok(network) => {
 				let mut result =
@@ -100,11 +310,13 @@ impl ZombienetCommand {
 					let name = node.name();
 					let mut output = format!(
 						"\n{bar}       {name}:
+{bar}         endpoint: {}
 {bar}         portal: https://polkadot.js.org/apps/?rpc={}#/explorer
 {bar}         logs: tail -f {base_dir}/{name}/{name}.log",
+						style(node.ws_uri()).bold(),
 						node.ws_uri(),
 					);
-					if self.verbose {
+					if crate::logging::verbose() {
 						output += &format!(
 							"\n{bar}         command: {} {}",
 							node.spec().command(),
@@ -139,7 +351,220 @@ impl ZombienetCommand {
 				}
 
 				spinner.stop(result);
-				tokio::signal::ctrl_c().await?;
+				let mut endpoint = None;
+				if let Some(artifacts) = &artifacts {
+					// A resumed parachain is already registered.
+					if !resumed {
+						// The relay chain is only aware of the parachain once registered.
+						let mut validators = network.relaychain().nodes();
+						validators.sort_by_key(|n| n.name());
+						let url = url::Url::parse(validators[0].ws_uri())?;
+						let spinner = crate::progress::spinner();
+						spinner.start(format!("Registering parachain {}...", self.para_id));
+						match register_parachain(
+							&url,
+							RELAY_SUDO,
+							self.para_id,
+							&artifacts.genesis_state,
+							&artifacts.genesis_wasm,
+						)
+						.await
+						{
+							Ok(()) => spinner.stop(format!(
+								"Parachain {} registered, it will start producing blocks once \
+								 onboarded by the relay chain.",
+								self.para_id
+							)),
+							Err(e) => {
+								spinner.error(format!("Unable to register the parachain: {e}"))
+							},
+						}
+					}
+					if let Some(collator) = parachains
+						.iter()
+						.find(|p| p.para_id() == self.para_id)
+						.and_then(|p| p.collators().into_iter().min_by_key(|n| n.name()))
+					{
+						log::success(format!(
+							"Parachain {} endpoint: {}",
+							self.para_id,
+							style(collator.ws_uri()).bold()
+						))?;
+						endpoint = Some(Url::parse(collator.ws_uri())?);
+					}
+				}
+				// The network is only usable once the relay chain finalizes blocks and each
+				// parachain produces blocks.
+				let mut validators = network.relaychain().nodes();
+				validators.sort_by_key(|n| n.name());
+				let mut chains = vec![(
+					format!("⛓️ {}", network.relaychain().chain()),
+					validators[0],
+					Readiness::Finalizing,
+				)];
+				for parachain in &parachains {
+					if let Some(collator) =
+						parachain.collators().into_iter().min_by_key(|n| n.name())
+					{
+						let name = format!("⛓️ para_id: {}", parachain.para_id());
+						chains.push((name, collator, Readiness::Producing));
+					}
+				}
+				let spinner = crate::progress::spinner();
+				spinner.start(
+					"Waiting for the relay chain to finalize and parachains to produce blocks...",
+				);
+				let deadline = Instant::now() + Duration::from_secs(self.ready_timeout);
+				let mut summary = Vec::new();
+				for (name, node, readiness) in chains {
+					let url = Url::parse(node.ws_uri())?;
+					let timeout = deadline.saturating_duration_since(Instant::now());
+					match wait_until_ready(&url, readiness, timeout).await {
+						Ok(status) => summary.push(format!(
+							"{name}: {} (best #{}, finalized #{})",
+							node.ws_uri(),
+							status.best,
+							status.finalized
+						)),
+						Err(e) => {
+							spinner.error(format!("{e}"));
+							let mut nodes = network.relaychain().nodes();
+							nodes.extend(parachains.iter().flat_map(|p| p.collators()));
+							for node in nodes {
+								let name = node.name();
+								let log =
+									PathBuf::from(base_dir).join(name).join(format!("{name}.log"));
+								let lines = tail(&log, LOG_LINES).unwrap_or_default();
+								log::warning(format!("{name}:\n{}", lines.join("\n")))?;
+							}
+							outro_cancel(
+								"The network did not become ready, see the output of each node above.",
+							)?;
+							return Ok(());
+						},
+					}
+				}
+				spinner.stop(style(format!("✅ Network ready\n{}", summary.join("\n"))).green());
+				// The endpoints are recorded while the network runs, so that other commands (e.g.
+				// `pop call xcm`) can reach its chains.
+				let endpoints_path = crate::network_endpoints_path()?;
+				let mut endpoints = NetworkEndpoints {
+					relay: Some(Url::parse(validators[0].ws_uri())?),
+					..Default::default()
+				};
+				for parachain in &parachains {
+					if let Some(collator) =
+						parachain.collators().into_iter().min_by_key(|n| n.name())
+					{
+						endpoints
+							.parachains
+							.insert(parachain.para_id(), Url::parse(collator.ws_uri())?);
+					}
+				}
+				if let Err(e) = endpoints.save(&endpoints_path) {
+					log::warning(format!("⚠️ Unable to record the endpoints of the network: {e}"))?;
+				}
+				let para_ids: Vec<_> = parachains.iter().map(|p| p.para_id()).collect();
+				// Channels opened previously are retained by a resumed network.
+				if self.hrmp && para_ids.len() > 1 && !resumed {
+					let mut validators = network.relaychain().nodes();
+					validators.sort_by_key(|n| n.name());
+					let url = url::Url::parse(validators[0].ws_uri())?;
+					let channels: Vec<_> = para_ids
+						.iter()
+						.enumerate()
+						.flat_map(|(i, a)| para_ids[i + 1..].iter().map(move |b| (*a, *b)))
+						.flat_map(|(a, b)| {
+							HrmpChannel::bidirectional(
+								a,
+								b,
+								DEFAULT_HRMP_MAX_CAPACITY,
+								DEFAULT_HRMP_MAX_MESSAGE_SIZE,
+							)
+						})
+						.collect();
+					let spinner = crate::progress::spinner();
+					spinner.start("Opening HRMP channels between the parachains...");
+					match open_hrmp_channels(&url, RELAY_SUDO, &channels).await {
+						Ok(()) => spinner.stop(format!(
+							"HRMP channels opened between parachains {}.",
+							para_ids.iter().map(|id| id.to_string()).collect::<Vec<_>>().join(", ")
+						)),
+						Err(e) => spinner.error(format!(
+							"{e}\nOnce the parachains are onboarded, use `pop call open-hrmp` to \
+							 open the channels."
+						)),
+					}
+				}
+				let mut nodes: Vec<_> = network.relaychain().nodes();
+				nodes.extend(network.parachains().into_iter().flat_map(|p| p.collators()));
+				for name in &self.follow {
+					if !nodes.iter().any(|n| n.name() == name) {
+						log::warning(format!("⚠️ The network has no node named `{name}`."))?;
+					}
+				}
+				let logs = nodes
+					.iter()
+					.filter(|n| self.follow.is_empty() || self.follow.iter().any(|f| f == n.name()))
+					.map(|node| {
+						let name = node.name().to_string();
+						(
+							name.clone(),
+							PathBuf::from(base_dir).join(&name).join(format!("{name}.log")),
+						)
+					})
+					.collect();
+				let monitoring = match self.with_monitoring {
+					true => {
+						let monitoring = Monitoring::new(
+							&PathBuf::from(base_dir).join("monitoring"),
+							zombienet.metrics_targets(),
+						);
+						let spinner = crate::progress::spinner();
+						spinner.start("Launching Prometheus and Grafana...");
+						match monitoring.launch() {
+							Ok(()) => {
+								spinner.stop(format!(
+									"Monitoring launched, dashboard: {} (Prometheus: {})",
+									style(monitoring.dashboard_url()).bold(),
+									monitoring.prometheus_url()
+								));
+								Some(monitoring)
+							},
+							Err(e) => {
+								spinner.error(format!("Unable to launch monitoring: {e}"));
+								None
+							},
+						}
+					},
+					false => None,
+				};
+				let watcher = match (self.watch_runtime, endpoint) {
+					(true, Some(url)) => {
+						let path = crate::project_path(self.path.as_deref());
+						log::info("Watching the runtime, changes are upgraded automatically.")?;
+						Some(tokio::spawn(async move {
+							if let Err(e) = watch_runtime(path, url).await {
+								let _ = log::error(format!("Stopped watching the runtime: {e}"));
+							}
+						}))
+					},
+					_ => None,
+				};
+				log::info(format!(
+					"The full output of each node is written to {base_dir}/<node>/<node>.log, \
+					 streaming {} output...",
+					self.log_level
+				))?;
+				let streamed = stream_logs(logs, self.log_level).await;
+				let _ = std::fs::remove_file(&endpoints_path);
+				streamed?;
+				if let Some(watcher) = watcher {
+					watcher.abort();
+				}
+				if let Some(monitoring) = monitoring {
+					monitoring.stop()?;
+				}
 				outro("Done")?;
 			},
 			Err(e) => {
@@ -149,14 +574,211 @@ impl ZombienetCommand {
 
 		Ok(())
 	}
+
+	/// The version of Polkadot used for the relay chain: the version specified, pinned or locked by
+	/// the project, otherwise the release of the Polkadot SDK the project depends on, so that the
+	/// relay chain is compatible with the parachain. A warning is shown when the version specified
+	/// is of another release.
+	fn relay_chain_version(&self) -> anyhow::Result<Option<String>> {
+		let path = crate::project_path(self.path.as_deref());
+		let specified = match &self.relay_chain {
+			Some(version) => Some(version.clone()),
+			None => project_version(&path, "polkadot")?,
+		};
+		// The project is only inspected on a best effort basis, as a network may be launched
+		// from any directory.
+		let project = match is_parachain(&path).unwrap_or_default() {
+			// Otherwise the release recorded when the project was generated.
+			true => PolkadotRelease::from_project(&path).ok().flatten().or_else(|| {
+				let recorded = Project::load(&path).ok().flatten()?.sdk_version?;
+				PolkadotRelease::new(&recorded).ok()
+			}),
+			false => None,
+		};
+		let Some(project) = project else { return Ok(specified) };
+		match &specified {
+			Some(version) => {
+				if !PolkadotRelease::new(version)?.is_compatible(&project) {
+					log::warning(format!(
+						"⚠️ The project depends on Polkadot SDK {}, but Polkadot {version} was \
+						 specified for the relay chain, which may be incompatible.",
+						project.version()
+					))?;
+				}
+				Ok(Some(version.clone()))
+			},
+			None => {
+				log::info(format!(
+					"The project depends on Polkadot SDK {}, using the matching relay chain.",
+					project.version()
+				))?;
+				Ok(Some(project.version().to_string()))
+			},
+		}
+	}
+
+	/// The patches of the genesis configuration of the parachain, in the order applied.
+	fn genesis(&self) -> anyhow::Result<Vec<serde_json::Value>> {
+		let mut genesis = Vec::new();
+		if let Some(file) = &self.genesis_patch {
+			let patch = std::fs::read_to_string(file)
+				.map_err(|e| anyhow::anyhow!("unable to read {}: {e}", file.display()))?;
+			genesis.push(serde_json::from_str(&patch)?);
+		}
+		genesis.extend(self.genesis_overrides.iter().cloned());
+		if let Some(sudo) = &self.sudo {
+			genesis.push(serde_json::json!({ "sudo": { "key": sudo } }));
+		}
+		Ok(genesis)
+	}
+
+	/// Builds the parachain project at `path` and generates the configuration of a local network
+	/// launching it, returning the path of the configuration along with the artifacts required to
+	/// register the parachain.
+	fn configure_project(&self) -> anyhow::Result<(String, ParachainArtifacts)> {
+		let path = crate::project_path(self.path.as_deref());
+		if !is_parachain(&path)? {
+			return Err(anyhow::anyhow!(
+				"{} is not a parachain project, provide a network configuration file with `--file`",
+				path.display()
+			));
+		}
+		log::info("Building the parachain...")?;
+		build_parachain(&Some(path.clone()))?;
+		let mut artifacts = export_artifacts(&path, Profile::Release)?;
+		// The genesis of the parachain is only computed from a chain spec of its own when
+		// overridden, being registered using the genesis computed from that chain spec.
+		let genesis = self.genesis()?;
+		let chain_spec = match genesis.is_empty() {
+			true => None,
+			false => {
+				let overrides = ChainSpecOverrides {
+					para_id: Some(self.para_id),
+					genesis,
+					..Default::default()
+				};
+				let exported = export_genesis(&path, "local", &overrides)?;
+				log::info(format!(
+					"Genesis overrides applied to the chain spec at {}",
+					exported.chain_spec.display()
+				))?;
+				artifacts.genesis_state = exported.genesis_state;
+				artifacts.genesis_wasm = exported.genesis_wasm;
+				Some(exported.chain_spec.canonicalize()?)
+			},
+		};
+		let network = LocalNetwork {
+			node: artifacts.node.canonicalize()?,
+			para_id: self.para_id,
+			collators: self.collators,
+			collator_args: self.collator_args.clone(),
+			validator_args: self.validator_args.clone(),
+			system_chains: self.system_chains.clone(),
+			chain_spec,
+		};
+		let file = path.join("target").join("pop").join("network.toml");
+		network.write(&file)?;
+		log::info(format!("Network configuration generated at {}", file.display()))?;
+		Ok((file.to_string_lossy().to_string(), artifacts))
+	}
 }
 
-/// Reports any observed status updates to a progress bar.
-#[derive(Copy, Clone)]
-struct ProgressReporter<'a>(&'a ProgressBar);
+/// Watches the runtime of the parachain project at `path`, rebuilding it whenever its sources
+/// change and upgrading the runtime of the parachain at `url`, until the task is aborted.
+///
+/// # Arguments
+///
+/// * `path` - path to the project
+/// * `url` - the endpoint of a collator of the parachain
+async fn watch_runtime(path: PathBuf, url: Url) -> anyhow::Result<()> {
+	let (dir, package) = runtime_crate(&path)?;
+	let mut watcher = SourceWatcher::new(&dir)?;
+	loop {
+		sleep(Duration::from_secs(1)).await;
+		if !watcher.changed()? {
+			continue;
+		}
+		log::info(format!("🔄 {package} changed, rebuilding..."))?;
+		// Changes made while building are picked up by the next rebuild.
+		let (project, name) = (path.clone(), package.clone());
+		let runtime =
+			match tokio::task::spawn_blocking(move || build_runtime(&project, &name)).await? {
+				Ok(runtime) => runtime,
+				Err(e) => {
+					log::error(format!("{e}"))?;
+					continue;
+				},
+			};
+		// The spec version is not required to increase between iterations.
+		let code = read_runtime(&runtime)?;
+		if let Err(e) =
+			upgrade_runtime(&url, PARACHAIN_SUDO, code.clone(), UpgradeMethod::SetCodeWithoutChecks)
+				.await
+		{
+			log::error(format!("{e}"))?;
+			continue;
+		}
+		log::info("Runtime upgrade submitted, waiting for it to be enacted...")?;
+		match wait_for_code(&url, &code, UPGRADE_TIMEOUT).await {
+			Ok(()) => {
+				log::success(format!("✅ Runtime of the parachain upgraded from {package}."))?
+			},
+			Err(e) => log::error(format!("{e}"))?,
+		}
+	}
+}
 
-impl Status for ProgressReporter<'_> {
-	fn update(&self, status: &str) {
-		self.0.start(status.replace("   Compiling", "Compiling"))
+/// Streams the output written to the log files of the nodes, prefixed by the name of each node in
+/// its own color, until ctrl-c is pressed.
+///
+/// # Arguments
+///
+/// * `logs` - the names of the nodes, along with the paths of their log files
+/// * `level` - the least severe level of the output streamed
+async fn stream_logs(logs: Vec<(String, PathBuf)>, level: LogLevel) -> anyhow::Result<()> {
+	let colors = [
+		Style::new().cyan(),
+		Style::new().green(),
+		Style::new().yellow(),
+		Style::new().blue(),
+		Style::new().magenta(),
+		Style::new().red(),
+	];
+	let prefixes: HashMap<&String, String> = logs
+		.iter()
+		.enumerate()
+		.map(|(i, (name, _))| {
+			(name, colors[i % colors.len()].apply_to(format!("[{name}]")).to_string())
+		})
+		.collect();
+	let mut readers: HashMap<String, (BufReader<File>, String, LogFilter)> = HashMap::new();
+	let ctrl_c = crate::interrupt::wait();
+	tokio::pin!(ctrl_c);
+	loop {
+		for (name, path) in &logs {
+			if !readers.contains_key(name) {
+				// The log file is only created once the node starts writing to it.
+				let Ok(file) = File::open(path) else { continue };
+				readers.insert(
+					name.clone(),
+					(BufReader::new(file), String::new(), LogFilter::new(level)),
+				);
+			}
+			let (reader, line, filter) = readers.get_mut(name).expect("reader inserted above");
+			while reader.read_line(line)? > 0 {
+				// A partially written line is completed by subsequent reads.
+				if !line.ends_with('\n') {
+					break;
+				}
+				if filter.keep(line) {
+					print!("{} {line}", prefixes[name]);
+				}
+				line.clear();
+			}
+		}
+		tokio::select! {
+			result = &mut ctrl_c => return Ok(result?),
+			_ = sleep(Duration::from_millis(500)) => {},
+		}
 	}
 }
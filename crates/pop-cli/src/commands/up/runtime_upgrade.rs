@@ -0,0 +1,116 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::{
+	commands::history,
+	style::{style, Theme},
+};
+use clap::Args;
+use cliclack::{clear_screen, intro, log, outro, outro_cancel, set_theme};
+use pop_parachains::{
+	read_runtime, spec_version, upgrade_runtime, wait_for_code, wait_for_spec_version,
+	UpgradeMethod,
+};
+use std::{path::PathBuf, time::Duration};
+use url::Url;
+
+#[derive(Args)]
+pub(crate) struct RuntimeUpgradeCommand {
+	/// The new runtime, either as a wasm binary or as hex encoded bytes.
+	#[arg(long)]
+	runtime: PathBuf,
+	/// Websocket endpoint of a node of the chain to upgrade, as shown once a network is launched
+	/// (e.g. the endpoint of `collator-01`).
+	#[arg(long, value_parser)]
+	url: Url,
+	/// Secret key URI for the sudo account of the chain.
+	#[arg(long, short, default_value = "//Alice")]
+	suri: String,
+	/// How the runtime is upgraded: `set-code`, using `System::set_code`,
+	/// `set-code-without-checks`, which does not require the spec version to be increased, or
+	/// `authorize`, using `System::authorize_upgrade` followed by
+	/// `System::apply_authorized_upgrade`.
+	#[arg(long, default_value = "set-code")]
+	method: UpgradeMethod,
+	/// The time to wait for the upgrade to be enacted, in seconds.
+	#[arg(long, default_value_t = 300)]
+	timeout: u64,
+	/// Directory path of the parachain project, within whose history the upgrade is recorded,
+	/// [default: the current project]
+	#[arg(short = 'p', long)]
+	path: Option<PathBuf>,
+}
+
+impl RuntimeUpgradeCommand {
+	pub(crate) async fn execute(&self) -> anyhow::Result<()> {
+		clear_screen()?;
+		intro(format!("{}: Upgrade a runtime", style(" Pop CLI ").black().on_magenta()))?;
+		set_theme(Theme);
+
+		let code = match read_runtime(&self.runtime) {
+			Ok(code) => code,
+			Err(e) => {
+				outro_cancel(format!("Unable to read {}: {e}", self.runtime.display()))?;
+				return Ok(());
+			},
+		};
+		let previous = match spec_version(&self.url).await {
+			Ok(version) => version,
+			Err(e) => {
+				outro_cancel(format!("{e}"))?;
+				return Ok(());
+			},
+		};
+		log::info(format!("The current spec version of the chain is {previous}."))?;
+
+		let spinner = crate::progress::spinner();
+		spinner.start(format!("Submitting the upgrade using `{}`...", self.method));
+		if let Err(e) = upgrade_runtime(&self.url, &self.suri, code.clone(), self.method).await {
+			spinner.error(format!("{e}"));
+			outro_cancel("The runtime was not upgraded.")?;
+			return Ok(());
+		}
+		spinner.start("Upgrade submitted, waiting for it to be enacted...");
+		let timeout = Duration::from_secs(self.timeout);
+		// The spec version is not necessarily increased when upgrading without checks.
+		if self.method == UpgradeMethod::SetCodeWithoutChecks {
+			match wait_for_code(&self.url, &code, timeout).await {
+				Ok(()) => {
+					spinner.stop("New runtime enacted.");
+					self.record(Vec::new())?;
+					outro("Runtime upgrade complete!")?;
+				},
+				Err(e) => {
+					spinner.error(format!("{e}"));
+					outro_cancel("The upgrade was submitted, but not yet enacted.")?;
+				},
+			}
+			return Ok(());
+		}
+		match wait_for_spec_version(&self.url, previous, timeout).await {
+			Ok(current) => {
+				spinner.stop(format!("Spec version upgraded from {previous} to {current}."));
+				self.record(vec![format!("spec version {current}")])?;
+				outro("Runtime upgrade complete!")?;
+			},
+			Err(e) => {
+				spinner.error(format!("{e}"));
+				outro_cancel(
+					"The upgrade was submitted, but not yet enacted. Ensure the spec version of \
+					 the new runtime was increased.",
+				)?;
+			},
+		}
+		Ok(())
+	}
+
+	/// Records the upgrade within the history of the project.
+	///
+	/// # Arguments
+	///
+	/// * `artifacts` - the outcome of the upgrade, in addition to the runtime enacted
+	fn record(&self, mut artifacts: Vec<String>) -> anyhow::Result<()> {
+		artifacts.insert(0, self.runtime.display().to_string());
+		let path = crate::project_path(self.path.as_deref());
+		history::record(&path, "up runtime-upgrade", &["--suri", "-s"], artifacts)
+	}
+}
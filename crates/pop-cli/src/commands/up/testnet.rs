@@ -0,0 +1,248 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::{
+	prompt,
+	style::{style, Theme},
+};
+use clap::Args;
+use cliclack::{clear_screen, intro, log, outro, outro_cancel, set_theme};
+use pop_parachains::{
+	account_address, build_parachain, export_genesis, free_balance, is_parachain, node_binary,
+	para_lifecycle, place_on_demand_order, register_genesis, reserve_para_id, wait_for_onboarding,
+	wait_for_para_block, ChainSpecOverrides, GenesisArtifacts, Testnet,
+};
+use std::{
+	path::{Path, PathBuf},
+	time::Duration,
+};
+use url::Url;
+
+#[derive(Args)]
+pub(crate) struct TestnetCommand {
+	/// Directory path for your project, [default: the current project]
+	#[arg(short = 'p', long = "path")]
+	path: Option<PathBuf>,
+	/// The public testnet onto which the parachain is onboarded.
+	#[arg(long, default_value = "paseo")]
+	network: Testnet,
+	/// Websocket endpoint of the relay chain, [default: the endpoint of the network configured
+	/// using `pop config`, otherwise a public endpoint]
+	#[arg(long, value_parser)]
+	url: Option<Url>,
+	/// Secret key URI for the account managing the parachain, which pays the deposits and fees,
+	/// [default: the key within the local keystore configured for the network using `pop config`]
+	///
+	/// e.g.
+	/// - for a dev account "//Alice"
+	/// - with a password "//Alice///SECRET_PASSWORD"
+	#[arg(long, short)]
+	suri: Option<String>,
+	/// A para id previously reserved by the account, rather than reserving a new one.
+	#[arg(long)]
+	para_id: Option<u32>,
+	/// The chain of the node whose genesis is registered (e.g. `local`).
+	#[arg(long, default_value = "local")]
+	chain: String,
+	/// The maximum amount paid for an on-demand core, in whole tokens.
+	#[arg(long, default_value_t = 1)]
+	max_amount: u128,
+	/// The time to wait for the parachain to be onboarded, in seconds. Onboarding completes at
+	/// the start of the next session but one.
+	#[arg(long, default_value_t = 7200)]
+	onboarding_timeout: u64,
+	/// The time to wait for the parachain to produce a block, once a core is ordered, in seconds.
+	#[arg(long, default_value_t = 300)]
+	timeout: u64,
+}
+
+impl TestnetCommand {
+	pub(crate) async fn execute(&self) -> anyhow::Result<()> {
+		clear_screen()?;
+		intro(format!(
+			"{}: Onboard a parachain to {}",
+			style(" Pop CLI ").black().on_magenta(),
+			self.network
+		))?;
+		set_theme(Theme);
+		let path = crate::project_path(self.path.as_deref());
+		if !is_parachain(&path)? {
+			outro_cancel(format!(
+				"{} is not a parachain project, as it does not depend on Cumulus.",
+				path.display()
+			))?;
+			return Ok(());
+		}
+		// The genesis artifacts are exported by the node, so build it first if required.
+		if node_binary(&path).is_err() {
+			log::info("The node has not been built yet, building the parachain...")?;
+			build_parachain(&Some(path.clone()))?;
+		}
+		let network = self.network.to_string();
+		let url = match self.url.clone().or_else(|| crate::config().endpoint(&network)) {
+			Some(url) => url,
+			None => Url::parse(self.network.rpc_url())?,
+		};
+		let suri = match (&self.suri, crate::config().account(&network)) {
+			(Some(suri), _) => suri.clone(),
+			(None, Some(account)) => crate::key_suri(&account)?,
+			(None, None) => prompt::password(
+				"Enter the secret key URI of the account managing the parachain:",
+				"--suri",
+			)?,
+		};
+		if let Err(e) = self.fund(&url, &suri).await {
+			outro_cancel(format!("{e}"))?;
+			return Ok(());
+		}
+
+		// Reserve a para id, unless already reserved.
+		let spinner = crate::progress::spinner();
+		let para_id = match self.para_id {
+			Some(para_id) => para_id,
+			None => {
+				spinner.start(format!("Reserving a para id on {}...", self.network));
+				match reserve_para_id(&url, &suri).await {
+					Ok(para_id) => {
+						spinner.stop(format!("Para id {para_id} reserved."));
+						para_id
+					},
+					Err(e) => {
+						spinner.error(format!("{e}"));
+						outro_cancel("Unable to reserve a para id.")?;
+						return Ok(());
+					},
+				}
+			},
+		};
+
+		// Export the genesis of the parachain, configured for the para id and relay chain.
+		spinner.start(format!("Exporting the genesis of the `{}` chain...", self.chain));
+		let overrides = ChainSpecOverrides {
+			para_id: Some(para_id),
+			relay_chain: Some(self.network.relay_chain().into()),
+			..Default::default()
+		};
+		let artifacts = match export_genesis(&path, &self.chain, &overrides) {
+			Ok(artifacts) => artifacts,
+			Err(e) => {
+				spinner.error(format!("{e}"));
+				outro_cancel("Unable to export the genesis artifacts.")?;
+				return Ok(());
+			},
+		};
+		spinner.stop(format!("Genesis exported to {}.", artifacts.chain_spec.display()));
+
+		// Register the genesis, unless already registered when resuming a previous attempt.
+		match para_lifecycle(&url, para_id).await {
+			Ok(Some(lifecycle)) => {
+				log::info(format!("Parachain {para_id} is already registered ({lifecycle})."))?
+			},
+			Ok(None) => {
+				spinner.start(format!("Registering parachain {para_id}..."));
+				let (state, wasm) = (&artifacts.genesis_state, &artifacts.genesis_wasm);
+				if let Err(e) = register_genesis(&url, &suri, para_id, state, wasm).await {
+					spinner.error(format!("{e}"));
+					outro_cancel(format!(
+						"Unable to register the parachain. Resume using `--para-id {para_id}`."
+					))?;
+					return Ok(());
+				}
+				spinner.stop(format!("Parachain {para_id} registered."));
+			},
+			Err(e) => {
+				outro_cancel(format!("{e}"))?;
+				return Ok(());
+			},
+		}
+		log::info(self.collator_command(&path, &artifacts)?)?;
+
+		spinner.start("Waiting for the parachain to be onboarded, which may take a few hours...");
+		let timeout = Duration::from_secs(self.onboarding_timeout);
+		let lifecycle = match wait_for_onboarding(&url, para_id, timeout).await {
+			Ok(lifecycle) => lifecycle,
+			Err(e) => {
+				spinner.error(format!("{e}"));
+				outro_cancel(format!(
+					"The parachain is not yet onboarded. Resume later using `--para-id {para_id}`."
+				))?;
+				return Ok(());
+			},
+		};
+		spinner.stop(format!("Parachain {para_id} onboarded as a {lifecycle}."));
+
+		// An on-demand parachain only produces a block when a core is ordered for it.
+		if lifecycle == "Parathread" {
+			let max_amount = self.network.units(self.max_amount);
+			if !prompt::confirm(
+				format!(
+					"Once your collator is running and synced, order an on-demand core for up to {}?",
+					self.network.format_balance(max_amount)
+				),
+				true,
+			)? {
+				outro(format!(
+					"Parachain {para_id} is onboarded. Order coretime to produce blocks."
+				))?;
+				return Ok(());
+			}
+			spinner.start("Placing an on-demand order...");
+			if let Err(e) = place_on_demand_order(&url, &suri, para_id, max_amount).await {
+				spinner.error(format!("{e}"));
+				outro_cancel("Unable to order a core.")?;
+				return Ok(());
+			}
+			spinner.stop("On-demand core ordered.");
+		}
+
+		spinner.start("Waiting for the parachain to produce a block...");
+		let timeout = Duration::from_secs(self.timeout);
+		match wait_for_para_block(&url, para_id, &artifacts.genesis_state, timeout).await {
+			Ok(()) => {
+				spinner.stop(format!("Parachain {para_id} produced a block."));
+				outro(format!("Parachain {para_id} is live on {}!", self.network))?;
+			},
+			Err(e) => {
+				spinner.error(format!("{e}"));
+				outro_cancel("Ensure your collator is running, synced and has its session keys.")?;
+			},
+		}
+		Ok(())
+	}
+
+	/// Ensures the account of `suri` is funded, prompting to fund it from the faucet otherwise.
+	async fn fund(&self, url: &Url, suri: &str) -> anyhow::Result<()> {
+		let address = account_address(suri)?;
+		loop {
+			let balance = free_balance(url, suri).await?;
+			log::info(format!("Account {address} has {}.", self.network.format_balance(balance)))?;
+			if balance > 0 {
+				return Ok(());
+			}
+			log::warning(format!(
+				"The account is required to pay the deposits and fees. Fund it using the faucet at \
+				 {}.",
+				self.network.faucet()
+			))?;
+			// Funding can't be awaited when running non-interactively.
+			if prompt::skip_confirm() || !prompt::confirm("Have you funded the account?", true)? {
+				anyhow::bail!("The account of the parachain is not funded.");
+			}
+		}
+	}
+
+	/// The command running a collator of the registered parachain.
+	fn collator_command(
+		&self,
+		path: &Path,
+		artifacts: &GenesisArtifacts,
+	) -> anyhow::Result<String> {
+		Ok(format!(
+			"Run a collator of the parachain, inserting its session keys using `pop key \
+			 insert-session-keys`:\n{} --collator --chain {} --base-path ./collator -- --chain {} \
+			 --sync warp",
+			node_binary(path)?.display(),
+			artifacts.chain_spec.display(),
+			self.network.relay_chain()
+		))
+	}
+}
@@ -0,0 +1,149 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::{
+	commands::binaries::PINNABLE,
+	style::{self, style, Align, Theme},
+};
+use anyhow::anyhow;
+use clap::{builder::PossibleValuesParser, Args};
+use cliclack::{clear_screen, intro, log, outro, outro_cancel, set_theme};
+use pop_common::{
+	lock::LOCK_FILE,
+	project::{pinned_binary, PROJECT_FILE},
+	Lock, Project,
+};
+use std::path::PathBuf;
+
+#[derive(Args)]
+pub(crate) struct UpdateLockCommand {
+	/// The binaries bumped. [default: every binary locked]
+	#[arg(value_parser = PossibleValuesParser::new(PINNABLE.map(|(name, _)| name)))]
+	binaries: Vec<String>,
+	/// Directory path for your project, [default: the current project]
+	#[arg(short = 'p', long)]
+	path: Option<PathBuf>,
+}
+
+/// A binary bumped: its name, the version previously locked, if any, and the version locked.
+type Bump = (String, Option<String>, String);
+
+impl UpdateLockCommand {
+	pub(crate) async fn execute(&self) -> anyhow::Result<()> {
+		clear_screen()?;
+		intro(format!("{}: Update the lockfile", style(" Pop CLI ").black().on_magenta()))?;
+		set_theme(Theme);
+
+		let path = crate::project_path(self.path.as_deref());
+		if !path.join(PROJECT_FILE).exists() {
+			outro_cancel(format!(
+				"No project generated by pop was found at {}, whose tooling is locked.",
+				path.display()
+			))?;
+			return Ok(());
+		}
+		let mut lock = Lock::load(&path)?;
+		let binaries: Vec<String> = match self.binaries.is_empty() {
+			true => lock.binaries.keys().cloned().collect(),
+			false => self.binaries.clone(),
+		};
+		if binaries.is_empty() {
+			outro(
+				"No binary is locked yet: binaries are locked when first used by `pop up` or `pop \
+				 bench`.",
+			)?;
+			return Ok(());
+		}
+		let spinner = crate::progress::spinner();
+		spinner.start("Resolving the versions of the binaries...");
+		let mut bumps = Vec::new();
+		for binary in binaries {
+			let version = match pinned_binary(&path, &binary)? {
+				Some(version) => version,
+				None => match latest(&binary).await {
+					Ok(version) => version,
+					Err(e) => {
+						spinner.error(format!("🚫 {e}"));
+						outro_cancel("The lockfile was left as is.")?;
+						return Ok(());
+					},
+				},
+			};
+			let locked = lock.version(&binary).map(String::from);
+			if locked.as_ref() != Some(&version) {
+				lock.lock(&binary, &version);
+				bumps.push((binary, locked, version));
+			}
+		}
+		spinner.stop("Versions resolved.");
+		if let Some(project) = Project::load(&path)? {
+			lock.lock_template(&project);
+		}
+		lock.save(&path)?;
+		if bumps.is_empty() {
+			outro("The binaries locked are up to date.")?;
+			return Ok(());
+		}
+		log::info(table(&bumps))?;
+		outro(format!(
+			"Updated {}: commit it so that everyone working on the project uses the same binaries, \
+			 which are sourced when first used.",
+			path.join(LOCK_FILE).display()
+		))?;
+		Ok(())
+	}
+}
+
+/// The latest release of `binary`, as per its release tag.
+///
+/// # Arguments
+///
+/// * `binary` - the name of the binary
+async fn latest(binary: &str) -> anyhow::Result<String> {
+	#[cfg(feature = "parachain")]
+	match binary {
+		"polkadot" | "polkadot-parachain" => {
+			return Ok(pop_parachains::PolkadotRelease::latest().await.version().to_string())
+		},
+		"frame-omni-bencher" => return Ok(pop_parachains::OMNI_BENCHER_DEFAULT_VERSION.into()),
+		_ => {},
+	}
+	#[cfg(feature = "contract")]
+	if binary == "substrate-contracts-node" {
+		return Ok(pop_contracts::contracts_node_generator(crate::cache()?, None).await?.version);
+	}
+	Err(anyhow!("the latest release of {binary} could not be resolved"))
+}
+
+/// A table listing each binary bumped, from the version previously locked to the version locked.
+fn table(bumps: &[Bump]) -> String {
+	let rows: Vec<_> = bumps
+		.iter()
+		.map(|(name, locked, version)| {
+			let change = match locked {
+				Some(locked) => format!("{locked} → {version}"),
+				None => format!("{version} (new)"),
+			};
+			[name.clone(), change]
+		})
+		.collect();
+	style::table([("Binary", Align::Left), ("Version", Align::Left)], &rows)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn table_works() {
+		let bumps = [
+			("polkadot".to_string(), Some("v1.11.0".to_string()), "v1.13.0".to_string()),
+			("frame-omni-bencher".to_string(), None, "stable2409".to_string()),
+		];
+		assert_eq!(
+			table(&bumps),
+			"Binary              Version\n\
+			 polkadot            v1.11.0 → v1.13.0\n\
+			 frame-omni-bencher  stable2409 (new)"
+		);
+	}
+}
@@ -0,0 +1,19 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use clap::{Args, Subcommand};
+
+pub(crate) mod lock;
+
+#[derive(Args)]
+#[command(args_conflicts_with_subcommands = true)]
+pub(crate) struct UpdateArgs {
+	#[command(subcommand)]
+	pub command: UpdateCommands,
+}
+
+#[derive(Subcommand)]
+pub(crate) enum UpdateCommands {
+	/// Bump the binaries locked within the project's pop.lock to the versions pinned, otherwise
+	/// to their latest releases
+	Lock(lock::UpdateLockCommand),
+}
@@ -0,0 +1,89 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use clap::Args;
+use cliclack::{clear_screen, intro, log, outro, outro_cancel, set_theme};
+use console::style;
+use pop_contracts::{environment, verify_contract, Verification};
+use std::path::PathBuf;
+
+use crate::style::Theme;
+
+#[derive(Args)]
+pub struct VerifyContractCommand {
+	/// Path to the contract project, [default: the current project]
+	#[arg(short = 'p', long)]
+	path: Option<PathBuf>,
+	/// The address of the deployed contract.
+	#[clap(name = "contract", long, env = "CONTRACT")]
+	contract: String,
+	/// The environment providing the endpoint of the node: one of `local`, `pop-testnet`,
+	/// `shibuya` or an environment defined within the project's pop.toml. [default: local]
+	#[clap(long)]
+	env: Option<String>,
+	/// Websocket endpoint of a node, [default: the endpoint of the environment]
+	#[clap(name = "url", long, value_parser)]
+	url: Option<url::Url>,
+}
+
+impl VerifyContractCommand {
+	/// The contract project, detected from the current directory unless provided.
+	fn path(&self) -> Option<PathBuf> {
+		Some(crate::contract_path(self.path.as_deref()))
+	}
+
+	pub(crate) async fn execute(&self) -> anyhow::Result<()> {
+		clear_screen()?;
+		intro(format!("{}: Verifying a contract", style(" Pop CLI ").black().on_magenta()))?;
+		set_theme(Theme);
+
+		let url = match &self.url {
+			Some(url) => url.clone(),
+			None => match environment(&self.path(), self.env.as_deref(), crate::config()) {
+				Ok(environment) => environment.url,
+				Err(e) => {
+					outro_cancel(format!("{e}"))?;
+					return Ok(());
+				},
+			},
+		};
+		let spinner = crate::progress::spinner();
+		spinner.start("Building the contract in release mode and fetching the deployed code...");
+		let verification = match verify_contract(&self.path(), &url, &self.contract).await {
+			Ok(verification) => verification,
+			Err(e) => {
+				spinner.error(format!("{e}"));
+				outro_cancel("Verification failed.")?;
+				return Ok(());
+			},
+		};
+		spinner.stop("Local build and on-chain code retrieved.");
+
+		match verification {
+			Verification::Verified { code_hash } => {
+				log::success(format!(
+					"The contract at {} matches the local build.",
+					self.contract
+				))?;
+				log::info(format!("Code hash: {code_hash}"))?;
+				outro("Contract verified.")?;
+			},
+			Verification::Mismatch { local, local_size, on_chain, on_chain_size } => {
+				log::error(format!(
+					"The contract at {} does not match the local build.",
+					self.contract
+				))?;
+				log::info(format!("Local code hash:    {local} ({local_size} bytes)"))?;
+				let on_chain_size = on_chain_size
+					.map(|size| format!("{size} bytes"))
+					.unwrap_or_else(|| "code unavailable".into());
+				log::info(format!("On-chain code hash: {on_chain} ({on_chain_size})"))?;
+				log::warning(
+					"Ensure the same source, toolchain and cargo-contract version were used to build \
+					 the deployed contract.",
+				)?;
+				outro_cancel("Contract verification failed.")?;
+			},
+		}
+		Ok(())
+	}
+}
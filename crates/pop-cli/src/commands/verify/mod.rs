@@ -0,0 +1,21 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use clap::{Args, Subcommand};
+
+#[cfg(feature = "contract")]
+pub(crate) mod contract;
+
+#[derive(Args)]
+#[command(args_conflicts_with_subcommands = true)]
+pub(crate) struct VerifyArgs {
+	#[command(subcommand)]
+	pub command: VerifyCommands,
+}
+
+#[derive(Subcommand)]
+pub(crate) enum VerifyCommands {
+	/// Verify a deployed contract against its local source code
+	#[cfg(feature = "contract")]
+	#[clap(alias = "c")]
+	Contract(contract::VerifyContractCommand),
+}
@@ -0,0 +1,92 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::{
+	output::OutputArgs,
+	style::{style, Theme},
+};
+use clap::Args;
+use cliclack::{intro, log, outro, outro_cancel, set_theme};
+use pop_parachains::{balance, Balance, Token};
+use serde::Serialize;
+use url::Url;
+
+#[derive(Args)]
+pub(crate) struct BalanceCommand {
+	/// The SS58 address of the account.
+	address: String,
+	/// Websocket endpoint of the chain.
+	#[arg(long, value_parser, default_value = "ws://127.0.0.1:9944")]
+	url: Url,
+	#[command(flatten)]
+	output: OutputArgs,
+}
+
+/// The balance of an account, as output by `--output json`.
+#[derive(Serialize)]
+struct BalanceReport<'a> {
+	address: &'a str,
+	symbol: &'a str,
+	decimals: u8,
+	#[serde(flatten)]
+	balance: &'a Balance,
+}
+
+impl BalanceCommand {
+	pub(crate) async fn execute(&self) -> anyhow::Result<()> {
+		self.output.clear_screen()?;
+		intro(format!(
+			"{}: Show the balance of an account",
+			style(" Pop CLI ").black().on_magenta()
+		))?;
+		set_theme(Theme);
+
+		let spinner = crate::progress::spinner();
+		spinner.start(format!("Fetching the balance of {}...", self.address));
+		let (token, balance) =
+			match futures::try_join!(Token::fetch(&self.url), balance(&self.url, &self.address)) {
+				Ok(result) => result,
+				Err(e) => {
+					spinner.error(format!("{e}"));
+					outro_cancel("Unable to fetch the balance of the account.")?;
+					return Ok(());
+				},
+			};
+		spinner.stop(format!("Balance of {} fetched.", self.address));
+		self.output.report(&BalanceReport {
+			address: &self.address,
+			symbol: &token.symbol,
+			decimals: token.decimals,
+			balance: &balance,
+		})?;
+		log::info(describe(&token, &balance))?;
+		outro(format!("{} transferable.", token.format(balance.transferable())))?;
+		Ok(())
+	}
+}
+
+/// Describes the parts of `balance`, formatted as whole tokens.
+///
+/// # Arguments
+///
+/// * `token` - the native token of the chain
+/// * `balance` - the balance of the account
+fn describe(token: &Token, balance: &Balance) -> String {
+	format!(
+		"Free: {}\nReserved: {}\nFrozen: {}",
+		token.format(balance.free),
+		token.format(balance.reserved),
+		token.format(balance.frozen)
+	)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn describe_works() {
+		let token = Token { symbol: "DOT".into(), decimals: 10 };
+		let balance = Balance { free: 15_000_000_000, reserved: 10_000_000_000, frozen: 0 };
+		assert_eq!(describe(&token, &balance), "Free: 1.5 DOT\nReserved: 1 DOT\nFrozen: 0 DOT");
+	}
+}
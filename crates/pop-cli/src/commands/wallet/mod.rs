@@ -0,0 +1,23 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use clap::{Args, Subcommand};
+
+pub(crate) mod balance;
+pub(crate) mod transfer;
+
+#[derive(Args)]
+#[command(args_conflicts_with_subcommands = true)]
+pub(crate) struct WalletArgs {
+	#[command(subcommand)]
+	pub command: WalletCommands,
+}
+
+#[derive(Subcommand)]
+pub(crate) enum WalletCommands {
+	/// Show the balance of an account, in the native token of the chain
+	#[clap(alias = "b")]
+	Balance(balance::BalanceCommand),
+	/// Transfer the native token of the chain to an account, e.g. to fund it on a local network
+	#[clap(alias = "t")]
+	Transfer(transfer::TransferCommand),
+}
@@ -0,0 +1,91 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::{
+	commands::call::signer,
+	dry_run::{dry_run, report, Effect},
+	prompt,
+	style::{style, Theme},
+};
+use clap::Args;
+use cliclack::{clear_screen, intro, log, outro, outro_cancel, set_theme};
+use pop_parachains::{balance, transfer, Token};
+use url::Url;
+
+#[derive(Args)]
+pub(crate) struct TransferCommand {
+	/// The SS58 address of the account receiving the transfer.
+	dest: String,
+	/// The amount transferred, in whole tokens of the chain, e.g. `1.5` or `1.5 UNIT`.
+	amount: String,
+	/// Websocket endpoint of the chain.
+	#[arg(long, value_parser, default_value = "ws://127.0.0.1:9944")]
+	url: Url,
+	/// Secret key URI for the account sending the transfer.
+	///
+	/// e.g.
+	/// - for a dev account "//Alice"
+	/// - with a password "//Alice///SECRET_PASSWORD"
+	#[arg(long, short, default_value = "//Alice")]
+	suri: String,
+	/// The name of a key within the local keystore sending the transfer, instead of `--suri`.
+	#[arg(long)]
+	account: Option<String>,
+}
+
+impl TransferCommand {
+	pub(crate) async fn execute(&self) -> anyhow::Result<()> {
+		clear_screen()?;
+		intro(format!("{}: Transfer tokens", style(" Pop CLI ").black().on_magenta()))?;
+		set_theme(Theme);
+
+		let spinner = crate::progress::spinner();
+		spinner.start(format!("Connecting to {}...", self.url));
+		let token = match Token::fetch(&self.url).await {
+			Ok(token) => token,
+			Err(e) => {
+				spinner.error(format!("{e}"));
+				outro_cancel("Unable to connect to the chain.")?;
+				return Ok(());
+			},
+		};
+		spinner.stop(format!("Connected to {}.", self.url));
+		let amount = match token.parse(&self.amount) {
+			Ok(amount) => amount,
+			Err(e) => {
+				outro_cancel(format!("{e}"))?;
+				return Ok(());
+			},
+		};
+		let display = format!("{} to {}", token.format(amount), self.dest);
+		if dry_run() {
+			return report(&[Effect::Submit {
+				chain: self.url.to_string(),
+				call: format!("Balances.transfer_keep_alive(Id({}), {amount})", self.dest),
+			}]);
+		}
+		let suri = signer(&self.suri, self.account.as_deref())?;
+		if !prompt::confirm(format!("Would you like to transfer {display}?"), true)? {
+			outro_cancel("Nothing transferred.")?;
+			return Ok(());
+		}
+
+		spinner.start(format!("Transferring {display}..."));
+		match transfer(&self.url, &suri, &self.dest, amount).await {
+			Ok(submitted) => spinner.stop(format!("Extrinsic {} finalized.", submitted.hash)),
+			Err(e) => {
+				spinner.error(format!("{e}"));
+				outro_cancel("Unable to transfer the tokens.")?;
+				return Ok(());
+			},
+		}
+		if let Ok(balance) = balance(&self.url, &self.dest).await {
+			log::info(format!(
+				"The balance of {} is now {}.",
+				self.dest,
+				token.format(balance.free)
+			))?;
+		}
+		outro(format!("Transferred {display}."))?;
+		Ok(())
+	}
+}
@@ -0,0 +1,159 @@
+// SPDX-License-Identifier: GPL-3.0
+use anyhow::Result;
+use cliclack::{log, outro};
+use std::{
+	fmt::{Display, Formatter},
+	path::{Path, PathBuf},
+	sync::atomic::{AtomicBool, Ordering},
+};
+use walkdir::WalkDir;
+
+/// The commands supporting `--dry-run`, as their command and subcommand names.
+const SUPPORTED: [(&str, Option<&str>); 13] = [
+	// The wizard generating any kind of project.
+	("new", None),
+	("new", Some("parachain")),
+	("new", Some("contract")),
+	("new", Some("pallet")),
+	("clean", None),
+	("cache", Some("prune")),
+	("call", Some("reserve-para-id")),
+	("call", Some("open-hrmp")),
+	("call", Some("parachain")),
+	("wallet", Some("transfer")),
+	("call", Some("xcm")),
+	("call", Some("sudo")),
+	("cache", Some("verify")),
+];
+
+/// Whether `--dry-run` was provided.
+static DRY_RUN: AtomicBool = AtomicBool::new(false);
+
+/// Makes commands report what they would do, rather than doing it.
+///
+/// # Arguments
+///
+/// * `enabled` - whether commands only report what they would do
+pub(crate) fn set_dry_run(enabled: bool) {
+	DRY_RUN.store(enabled, Ordering::Relaxed);
+}
+
+/// Whether commands only report what they would do, as `--dry-run` was provided.
+pub(crate) fn dry_run() -> bool {
+	DRY_RUN.load(Ordering::Relaxed)
+}
+
+/// Whether the command selected supports `--dry-run`, so that it is never silently ignored by a
+/// command applying its effects regardless.
+///
+/// # Arguments
+///
+/// * `command` - the name of the command
+/// * `subcommand` - the name of its subcommand, if any
+pub(crate) fn is_supported(command: &str, subcommand: Option<&str>) -> bool {
+	SUPPORTED.iter().any(|(c, s)| *c == command && *s == subcommand)
+}
+
+/// An effect of a command, as reported by a dry run.
+#[derive(Debug, PartialEq)]
+pub(crate) enum Effect {
+	/// A file created.
+	Create(PathBuf),
+	/// A file or directory removed.
+	Remove(PathBuf),
+	/// A transaction submitted to a chain.
+	Submit {
+		/// The endpoint of the chain.
+		chain: String,
+		/// The call dispatched, e.g. `Registrar.reserve()`.
+		call: String,
+	},
+}
+
+impl Display for Effect {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Effect::Create(path) => write!(f, "create {}", path.display()),
+			Effect::Remove(path) => write!(f, "remove {}", path.display()),
+			Effect::Submit { chain, call } => write!(f, "submit {call} to {chain}"),
+		}
+	}
+}
+
+/// Reports the effects a command would have, instead of applying them.
+///
+/// # Arguments
+///
+/// * `effects` - the effects of the command
+pub(crate) fn report(effects: &[Effect]) -> Result<()> {
+	let effects: Vec<_> = effects.iter().map(|e| e.to_string()).collect();
+	log::info(format!("The following would be applied:\n{}", effects.join("\n")))?;
+	outro("Dry run, nothing applied.")?;
+	Ok(())
+}
+
+/// The files generated within `dir`, as created at `target` instead.
+///
+/// # Arguments
+///
+/// * `dir` - the directory the files were generated within
+/// * `target` - the directory the files would be created within
+pub(crate) fn created(dir: &Path, target: &Path) -> Result<Vec<Effect>> {
+	let mut files = Vec::new();
+	for entry in WalkDir::new(dir).sort_by_file_name() {
+		let entry = entry?;
+		if entry.file_type().is_file() {
+			files.push(Effect::Create(target.join(entry.path().strip_prefix(dir)?)));
+		}
+	}
+	Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::fs;
+
+	#[test]
+	fn is_supported_works() {
+		assert!(is_supported("new", Some("parachain")));
+		assert!(is_supported("clean", None));
+		assert!(is_supported("cache", Some("prune")));
+		assert!(!is_supported("new", Some("test")));
+		assert!(is_supported("call", Some("open-hrmp")));
+		assert!(is_supported("call", Some("parachain")));
+		assert!(is_supported("wallet", Some("transfer")));
+		assert!(is_supported("call", Some("xcm")));
+		assert!(is_supported("call", Some("sudo")));
+		assert!(is_supported("cache", Some("verify")));
+		assert!(!is_supported("call", Some("contract")));
+		assert!(!is_supported("up", Some("contract")));
+	}
+
+	#[test]
+	fn created_works() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		fs::create_dir(temp_dir.path().join("src"))?;
+		fs::write(temp_dir.path().join("src/lib.rs"), "")?;
+		fs::write(temp_dir.path().join("Cargo.toml"), "")?;
+		let target = Path::new("my-app");
+		assert_eq!(
+			created(temp_dir.path(), target)?,
+			vec![
+				Effect::Create(target.join("Cargo.toml")),
+				Effect::Create(target.join("src/lib.rs"))
+			]
+		);
+		Ok(())
+	}
+
+	#[test]
+	fn display_works() {
+		assert_eq!(Effect::Remove("my-app".into()).to_string(), "remove my-app");
+		let submit = Effect::Submit {
+			chain: "ws://localhost:9944/".into(),
+			call: "Registrar.reserve()".into(),
+		};
+		assert_eq!(submit.to_string(), "submit Registrar.reserve() to ws://localhost:9944/");
+	}
+}
@@ -0,0 +1,149 @@
+// SPDX-License-Identifier: GPL-3.0
+use clap::{Arg, Command};
+
+/// The environment variables supported, as the command accepting the option (its subcommands
+/// separated by spaces, the root command being empty), the id of the option and the name of the
+/// variable providing its value.
+const VARIABLES: &[(&str, &str, &str)] = &[
+	("", "require_signed", "POP_REQUIRE_SIGNED"),
+	("", "progress_format", "POP_PROGRESS_FORMAT"),
+	("new parachain", "provider", "POP_PROVIDER"),
+	("new parachain", "template", "POP_TEMPLATE"),
+	("new parachain", "release_tag", "POP_RELEASE_TAG"),
+	("new contract", "template", "POP_CONTRACT_TEMPLATE"),
+	("up parachain", "relay_chain", "POP_RELAY_VERSION"),
+	("up parachain", "system_parachain", "POP_SYSTEM_PARACHAIN_VERSION"),
+	("up contract", "url", "POP_RPC_URL"),
+	("up contracts", "url", "POP_RPC_URL"),
+	("call contract", "url", "POP_RPC_URL"),
+	("call parachain", "url", "POP_RPC_URL"),
+	("call sudo", "url", "POP_RPC_URL"),
+	("query", "url", "POP_RPC_URL"),
+	("query contract-storage", "url", "POP_RPC_URL"),
+	("verify contract", "url", "POP_RPC_URL"),
+	("wallet balance", "url", "POP_RPC_URL"),
+	("wallet transfer", "url", "POP_RPC_URL"),
+];
+
+/// Lets the supported environment variables provide the value of their option, which is then
+/// shown by `--help`. A flag takes precedence over its environment variable, which takes
+/// precedence over the configuration.
+///
+/// # Arguments
+///
+/// * `command` - the root command
+/// * `env` - looks up the value of an environment variable
+pub(crate) fn with_env_vars(command: Command, env: &dyn Fn(&str) -> Option<String>) -> Command {
+	with_env_vars_of(command, "", env)
+}
+
+/// Lets the environment variables supported by `command` and its subcommands provide the value of
+/// their option.
+///
+/// # Arguments
+///
+/// * `command` - the command
+/// * `path` - the path of the command, as its subcommands separated by spaces
+/// * `env` - looks up the value of an environment variable
+fn with_env_vars_of(
+	mut command: Command,
+	path: &str,
+	env: &dyn Fn(&str) -> Option<String>,
+) -> Command {
+	// An option may be unavailable, depending on the features enabled.
+	let supported: Vec<_> = VARIABLES
+		.iter()
+		.filter(|(c, id, _)| *c == path && command.get_arguments().any(|a| a.get_id() == *id))
+		.collect();
+	for (_, id, name) in supported {
+		command = command.mut_arg(*id, |arg| with_env_var(arg, name, env(name)));
+	}
+	let subcommands: Vec<String> =
+		command.get_subcommands().map(|c| c.get_name().to_string()).collect();
+	for subcommand in subcommands {
+		let path = match path {
+			"" => subcommand.clone(),
+			path => format!("{path} {subcommand}"),
+		};
+		command = command.mut_subcommand(&subcommand, |c| with_env_vars_of(c, &path, env));
+	}
+	command
+}
+
+/// Documents the environment variable of `arg`, using its value as the default of the option so
+/// that a flag still takes precedence.
+///
+/// # Arguments
+///
+/// * `arg` - the option
+/// * `name` - the name of the environment variable
+/// * `value` - the value of the environment variable, if set
+fn with_env_var(mut arg: Arg, name: &str, value: Option<String>) -> Arg {
+	if let Some(help) = arg.get_help().map(|help| format!("{help} [env: {name}]")) {
+		arg = arg.help(help);
+	}
+	if let Some(help) = arg.get_long_help().map(|help| format!("{help}\n\n[env: {name}]")) {
+		arg = arg.long_help(help);
+	}
+	match value {
+		Some(value) => arg.default_value(value),
+		None => arg,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use clap::ArgAction;
+
+	fn command() -> Command {
+		Command::new("pop").subcommand(
+			Command::new("up").subcommand(
+				Command::new("parachain")
+					.arg(Arg::new("relay_chain").long("relay-chain").help("The relay chain"))
+					.arg(Arg::new("path"))
+					.arg(Arg::new("verbose").short('v').action(ArgAction::Count)),
+			),
+		)
+	}
+
+	fn relay_chain(env: &dyn Fn(&str) -> Option<String>, args: &[&str]) -> Option<String> {
+		let matches = with_env_vars(command(), env).get_matches_from(args);
+		let (_, up) = matches.subcommand().unwrap();
+		let (_, parachain) = up.subcommand().unwrap();
+		parachain.get_one::<String>("relay_chain").cloned()
+	}
+
+	#[test]
+	fn only_supported_env_vars_are_documented() {
+		let command = with_env_vars(command(), &|_| None);
+		let parachain =
+			command.find_subcommand("up").unwrap().find_subcommand("parachain").unwrap();
+		let help = |id: &str| {
+			let arg = parachain.get_arguments().find(|a| a.get_id() == id).unwrap();
+			arg.get_help().map(|h| h.to_string())
+		};
+		assert_eq!(
+			help("relay_chain").as_deref(),
+			Some("The relay chain [env: POP_RELAY_VERSION]")
+		);
+		assert_eq!(help("path"), None);
+		assert_eq!(help("verbose"), None);
+	}
+
+	#[test]
+	fn env_var_provides_value() {
+		let env = |name: &str| (name == "POP_RELAY_VERSION").then(|| "v1.2.0".to_string());
+		assert_eq!(relay_chain(&env, &["pop", "up", "parachain"]).as_deref(), Some("v1.2.0"));
+		assert_eq!(relay_chain(&|_| None, &["pop", "up", "parachain"]), None);
+	}
+
+	#[test]
+	fn flag_takes_precedence_over_env_var() {
+		let env = |name: &str| (name == "POP_RELAY_VERSION").then(|| "v1.2.0".to_string());
+		assert_eq!(
+			relay_chain(&env, &["pop", "up", "parachain", "--relay-chain", "v1.3.0"]).as_deref(),
+			Some("v1.3.0")
+		);
+	}
+}
@@ -0,0 +1,203 @@
+// SPDX-License-Identifier: GPL-3.0
+use console::style;
+use std::{error::Error as StdError, io::ErrorKind};
+
+/// The code of a failure which is not diagnosed any further.
+const UNKNOWN: &str = "E0001";
+
+/// The diagnosis of a failed command, as reported to the user: a stable code identifying the
+/// failure, its short cause and a suggested fix. Codes are grouped by the kind of failure and are
+/// never reused:
+///
+/// * `E1xx` - network
+/// * `E2xx` - git
+/// * `E3xx` - filesystem
+/// * `E4xx` - configuration and keys
+/// * `E5xx` - tools and binaries
+/// * `E6xx` - parachains
+/// * `E7xx` - contracts
+#[derive(Debug, PartialEq)]
+pub(crate) struct Diagnostic {
+	/// The stable code of the failure.
+	pub(crate) code: &'static str,
+	/// The short cause of the failure.
+	pub(crate) cause: String,
+	/// The suggested fix, if any.
+	pub(crate) fix: Option<&'static str>,
+	/// The underlying causes, from the outermost.
+	chain: Vec<String>,
+}
+
+impl Diagnostic {
+	/// Diagnoses `error` as per the first cause within its chain whose type is known.
+	///
+	/// # Arguments
+	///
+	/// * `error` - the error of a command
+	pub(crate) fn new(error: &anyhow::Error) -> Self {
+		let (code, fix) = error.chain().find_map(diagnose).unwrap_or((UNKNOWN, None));
+		Self {
+			code,
+			cause: error.to_string(),
+			fix,
+			chain: error.chain().skip(1).map(|cause| cause.to_string()).collect(),
+		}
+	}
+
+	/// Renders the diagnosis, along with the underlying causes when `verbose`.
+	///
+	/// # Arguments
+	///
+	/// * `verbose` - whether the underlying causes are shown
+	pub(crate) fn render(&self, verbose: bool) -> String {
+		let mut rendered =
+			format!("{} {}", style(format!("error[{}]:", self.code)).red().bold(), self.cause);
+		if let Some(fix) = self.fix {
+			rendered += &format!("\n  {} {fix}", style("help:").cyan().bold());
+		}
+		match (verbose, self.chain.is_empty()) {
+			(true, false) => {
+				rendered += "\n  caused by:";
+				for cause in &self.chain {
+					rendered += &format!("\n    - {cause}");
+				}
+			},
+			(false, false) => rendered += "\n  Run with `-v` to show the underlying causes.",
+			_ => {},
+		}
+		rendered
+	}
+}
+
+/// The code and suggested fix of `cause`, when its type is known.
+fn diagnose(cause: &(dyn StdError + 'static)) -> Option<(&'static str, Option<&'static str>)> {
+	if cause.is::<reqwest::Error>() {
+		return Some((
+			"E101",
+			Some("Check your connection, or route requests through a proxy using `pop config set proxy <url>`."),
+		));
+	}
+	if cause.is::<git2::Error>() {
+		return Some((
+			"E200",
+			Some("Check the repository is reachable, and that git credentials are configured for private repositories."),
+		));
+	}
+	if let Some(error) = cause.downcast_ref::<std::io::Error>() {
+		return Some(match error.kind() {
+			ErrorKind::NotFound => {
+				("E301", Some("Check the path exists, or provide another using `--path`."))
+			},
+			ErrorKind::PermissionDenied => {
+				("E302", Some("Check the permissions of the path, or use a directory you own."))
+			},
+			_ => ("E300", None),
+		});
+	}
+	if let Some(error) = cause.downcast_ref::<pop_common::Error>() {
+		use pop_common::Error::*;
+		return Some(match error {
+			Git(_) => ("E201", Some("Check the repository is reachable and its URL is correct.")),
+			ReleaseNotFound(_) => (
+				"E202",
+				Some("Omit the release to use the latest, or select one of the releases of the template."),
+			),
+			HttpError(_) => ("E101", None),
+			Config(_) => (
+				"E401",
+				Some("Review the configuration using `pop config get`, or remove a key using `pop config unset <key>`."),
+			),
+			Keystore(_) => (
+				"E402",
+				Some("List the keys available using `pop key list`, or import one using `pop key import`."),
+			),
+			MissingBinary(_) => (
+				"E501",
+				Some("Install the missing dependencies using `pop install`, or remove the cached binaries using `pop clean --cache` to source them again."),
+			),
+			_ => return None,
+		});
+	}
+	#[cfg(feature = "parachain")]
+	if let Some(error) = cause.downcast_ref::<pop_parachains::Error>() {
+		use pop_parachains::Error::*;
+		return Some(match error {
+			Git(_) => ("E201", Some("Check the repository is reachable and its URL is correct.")),
+			Config(_) => ("E403", Some("Review the flags provided, along with the network configuration file.")),
+			MissingBinary(_) => (
+				"E501",
+				Some("Install the missing dependencies using `pop install`, or remove the cached binaries using `pop clean --cache` to source them again."),
+			),
+			TargetExists(_) => ("E601", Some("Remove the existing directory, or choose another name.")),
+			Build(_) => ("E602", Some("Run with `-vv` to show the full output of cargo.")),
+			Extrinsic(_) | Signer(_) => (
+				"E603",
+				Some("Check the endpoint is reachable, and that the signer exists and is funded."),
+			),
+			Metadata(_) => (
+				"E604",
+				Some("Omit `--pallet` or `--call` to select among those of the chain, and check the arguments provided."),
+			),
+			_ => return None,
+		});
+	}
+	#[cfg(feature = "contract")]
+	if let Some(error) = cause.downcast_ref::<pop_contracts::Error>() {
+		use pop_contracts::Error::*;
+		return Some(match error {
+			UnsupportedInkVersion(_) => (
+				"E701",
+				Some("Omit `--ink-version` to target the version of the default template."),
+			),
+			ManifestPath(_) | ManifestParsing(_) => (
+				"E702",
+				Some("Run from the directory of a contract project, or provide it using `--path`."),
+			),
+			Config(_) => {
+				("E703", Some("Review the environments defined within the project's pop.toml."))
+			},
+			Deployment(_) | DryRun(_) => (
+				"E704",
+				Some("Check the constructor arguments, and that the signer exists and is funded."),
+			),
+			_ => return None,
+		});
+	}
+	None
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::anyhow;
+
+	#[test]
+	fn diagnostic_works() {
+		let error = anyhow::Error::from(pop_common::Error::ReleaseNotFound("v9.9.9".into()))
+			.context("unable to generate the parachain");
+		let diagnostic = Diagnostic::new(&error);
+		assert_eq!(diagnostic.code, "E202");
+		assert_eq!(diagnostic.cause, "unable to generate the parachain");
+		assert!(diagnostic.fix.is_some());
+		assert_eq!(diagnostic.chain, vec!["the release `v9.9.9` could not be found"]);
+
+		let io = std::io::Error::new(ErrorKind::NotFound, "missing");
+		assert_eq!(Diagnostic::new(&io.into()).code, "E301");
+		let diagnostic = Diagnostic::new(&anyhow!("failed"));
+		assert_eq!((diagnostic.code, diagnostic.fix), (UNKNOWN, None));
+	}
+
+	#[test]
+	fn render_works() {
+		console::set_colors_enabled(false);
+		let error = anyhow::Error::from(pop_common::Error::Keystore("no key `alice`".into()))
+			.context("unable to sign");
+		let diagnostic = Diagnostic::new(&error);
+		let rendered = diagnostic.render(false);
+		assert!(rendered.starts_with("error[E402]: unable to sign\n  help: List the keys"));
+		assert!(rendered.ends_with("Run with `-v` to show the underlying causes."));
+		assert!(diagnostic
+			.render(true)
+			.ends_with("caused by:\n    - Keystore error: no key `alice`"));
+	}
+}
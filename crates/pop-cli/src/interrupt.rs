@@ -0,0 +1,141 @@
+// SPDX-License-Identifier: GPL-3.0
+use std::{
+	fs,
+	path::Path,
+	process::Command,
+	sync::{
+		atomic::{AtomicUsize, Ordering},
+		Mutex,
+	},
+};
+use walkdir::WalkDir;
+
+/// The exit code of an interrupted command, as per the convention of shells (128 + SIGINT).
+const INTERRUPTED: i32 = 130;
+
+/// The number of commands awaiting ctrl-c themselves, in order to terminate gracefully.
+static AWAITING: AtomicUsize = AtomicUsize::new(0);
+/// The identifiers of the processes spawned, terminated when interrupted.
+static PROCESSES: Mutex<Vec<u32>> = Mutex::new(Vec::new());
+
+/// Handles ctrl-c on behalf of commands not awaiting it themselves: the processes they spawned
+/// are terminated, partial downloads removed and the terminal restored before exiting, rather
+/// than leaving orphan nodes and partial files behind.
+pub(crate) fn install() {
+	tokio::spawn(async {
+		loop {
+			if tokio::signal::ctrl_c().await.is_err() {
+				return;
+			}
+			if AWAITING.load(Ordering::SeqCst) == 0 {
+				break;
+			}
+		}
+		cleanup();
+		std::process::exit(INTERRUPTED);
+	});
+}
+
+/// Waits for ctrl-c, handled by the command itself so that it terminates gracefully.
+pub(crate) async fn wait() -> std::io::Result<()> {
+	AWAITING.fetch_add(1, Ordering::SeqCst);
+	// Decremented once ctrl-c is received, or when no longer awaited.
+	let _awaiting = Awaiting;
+	tokio::signal::ctrl_c().await
+}
+
+/// Marks a command as awaiting ctrl-c, until dropped.
+struct Awaiting;
+
+impl Drop for Awaiting {
+	fn drop(&mut self) {
+		AWAITING.fetch_sub(1, Ordering::SeqCst);
+	}
+}
+
+/// Terminates the process `id` when interrupted, until the returned guard is dropped.
+///
+/// # Arguments
+///
+/// * `id` - the identifier of the process spawned
+pub(crate) fn track(id: u32) -> Tracked {
+	if let Ok(mut processes) = PROCESSES.lock() {
+		processes.push(id);
+	}
+	Tracked(id)
+}
+
+/// A process terminated when interrupted, until dropped.
+pub(crate) struct Tracked(u32);
+
+impl Drop for Tracked {
+	fn drop(&mut self) {
+		if let Ok(mut processes) = PROCESSES.lock() {
+			processes.retain(|id| *id != self.0);
+		}
+	}
+}
+
+/// Terminates the processes spawned, removes partial downloads and restores the terminal.
+fn cleanup() {
+	let processes = PROCESSES.lock().map(|p| p.clone()).unwrap_or_default();
+	for id in processes {
+		let _ = Command::new("kill").arg(id.to_string()).status();
+	}
+	#[cfg(any(feature = "parachain", feature = "contract"))]
+	if let Ok(cache) = crate::cache() {
+		remove_partial_downloads(&cache);
+	}
+	// Spinners hide the cursor while rendering.
+	let _ = console::Term::stderr().show_cursor();
+	let _ = cliclack::outro_cancel("Interrupted.");
+}
+
+/// Removes the downloads interrupted within `cache`, along with the sources of binaries being
+/// built, which may be partially cloned.
+///
+/// # Arguments
+///
+/// * `cache` - the location of the cache
+fn remove_partial_downloads(cache: &Path) {
+	let _ = fs::remove_dir_all(cache.join(".src"));
+	for entry in WalkDir::new(cache).into_iter().flatten() {
+		let path = entry.path();
+		if entry.file_type().is_file()
+			&& path.extension().is_some_and(|e| e == pop_common::sourcing::PARTIAL_EXTENSION)
+		{
+			let _ = fs::remove_file(path);
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+
+	#[test]
+	fn remove_partial_downloads_works() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let cache = temp_dir.path();
+		let binaries = cache.join("bins").join("polkadot");
+		fs::create_dir_all(binaries.join("v1.7.0"))?;
+		fs::create_dir_all(binaries.join("v1.11.0"))?;
+		fs::write(binaries.join("v1.7.0").join("polkadot"), "")?;
+		fs::write(binaries.join("v1.11.0").join("polkadot.part"), "")?;
+		fs::create_dir_all(cache.join(".src").join("polkadot-sdk"))?;
+		remove_partial_downloads(cache);
+		assert!(binaries.join("v1.7.0").join("polkadot").exists());
+		assert!(!binaries.join("v1.11.0").join("polkadot.part").exists());
+		assert!(!cache.join(".src").exists());
+		Ok(())
+	}
+
+	#[test]
+	fn track_works() {
+		let tracked = track(u32::MAX);
+		assert!(PROCESSES.lock().unwrap().contains(&u32::MAX));
+		drop(tracked);
+		assert!(!PROCESSES.lock().unwrap().contains(&u32::MAX));
+	}
+}
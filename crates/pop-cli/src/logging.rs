@@ -0,0 +1,107 @@
+// SPDX-License-Identifier: GPL-3.0
+use anyhow::Result;
+use clap::{ArgAction, Args};
+use std::{
+	fs::OpenOptions,
+	path::PathBuf,
+	sync::{
+		atomic::{AtomicU8, Ordering},
+		Mutex,
+	},
+};
+use tracing_subscriber::EnvFilter;
+
+/// The crates of Pop CLI, whose logs are selected by the verbosity. The logs of dependencies are
+/// limited to warnings until `-vvv`.
+const CRATES: [&str; 5] = ["pop", "pop_common", "pop_contracts", "pop_parachains", "pop_telemetry"];
+
+/// The verbosity selected, once the logs are initialized.
+static VERBOSITY: AtomicU8 = AtomicU8::new(0);
+
+/// Selects which logs of the internal operations of commands are shown, such as git fetches, HTTP
+/// requests and the processes spawned.
+#[derive(Args, Clone, Debug, Default)]
+pub(crate) struct Verbosity {
+	/// Log the internal operations of commands, such as git fetches, HTTP requests and the
+	/// processes spawned, along with the full output of the tools launched: `-v` for info, `-vv`
+	/// for debug and `-vvv` for trace, including the logs of dependencies.
+	#[arg(short, long, action = ArgAction::Count, global = true)]
+	verbose: u8,
+	/// Log nothing, not even errors.
+	#[arg(short, long, global = true, conflicts_with = "verbose")]
+	quiet: bool,
+	/// Write the logs to a file instead of stderr, at least at the debug level.
+	#[arg(long, global = true, value_name = "PATH")]
+	log_file: Option<PathBuf>,
+}
+
+impl Verbosity {
+	/// Installs the subscriber writing the logs selected, which can otherwise be selected by
+	/// `RUST_LOG` when no verbosity is provided. Logs written to stderr are colored as per
+	/// `--color`, which must be applied beforehand.
+	pub(crate) fn init(&self) -> Result<()> {
+		VERBOSITY.store(self.verbose, Ordering::Relaxed);
+		let filter = match self.verbose == 0 && !self.quiet && self.log_file.is_none() {
+			true => EnvFilter::try_from_default_env()
+				.unwrap_or_else(|_| EnvFilter::new(self.directives())),
+			false => EnvFilter::new(self.directives()),
+		};
+		let logs = tracing_subscriber::fmt().with_env_filter(filter);
+		match &self.log_file {
+			Some(path) => {
+				let file = OpenOptions::new().create(true).append(true).open(path)?;
+				logs.with_ansi(false).with_writer(Mutex::new(file)).try_init()
+			},
+			None => logs
+				.without_time()
+				.with_ansi(console::colors_enabled_stderr())
+				.with_writer(std::io::stderr)
+				.try_init(),
+		}
+		.map_err(|e| anyhow::anyhow!(e))
+	}
+
+	/// The filter directives of the verbosity selected.
+	fn directives(&self) -> String {
+		if self.quiet {
+			return "off".into();
+		}
+		let minimum = if self.log_file.is_some() { 2 } else { 0 };
+		let level = match self.verbose.max(minimum) {
+			0 => return "error".into(),
+			1 => "info",
+			2 => "debug",
+			_ => return "trace".into(),
+		};
+		let crates: Vec<_> = CRATES.iter().map(|c| format!("{c}={level}")).collect();
+		format!("warn,{}", crates.join(","))
+	}
+}
+
+/// Whether `-v` was provided, so that the full output of the tools launched is also shown.
+pub(crate) fn verbose() -> bool {
+	VERBOSITY.load(Ordering::Relaxed) > 0
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_directives() {
+		let verbosity = |verbose, quiet, log_file: Option<&str>| Verbosity {
+			verbose,
+			quiet,
+			log_file: log_file.map(PathBuf::from),
+		};
+		assert_eq!(verbosity(0, false, None).directives(), "error");
+		assert_eq!(verbosity(0, true, None).directives(), "off");
+		assert_eq!(
+			verbosity(1, false, None).directives(),
+			"warn,pop=info,pop_common=info,pop_contracts=info,pop_parachains=info,pop_telemetry=info"
+		);
+		assert!(verbosity(2, false, None).directives().contains("pop_common=debug"));
+		assert!(verbosity(0, false, Some("pop.log")).directives().contains("pop_common=debug"));
+		assert_eq!(verbosity(3, false, None).directives(), "trace");
+	}
+}
@@ -5,24 +5,79 @@ compile_error!("feature \"contract\" or feature \"parachain\" must be enabled");
 
 #[cfg(any(feature = "parachain", feature = "contract"))]
 mod commands;
+mod dry_run;
+mod env_vars;
+mod errors;
+mod interrupt;
+mod logging;
+mod output;
+mod progress;
+mod prompt;
+#[cfg(feature = "parachain")]
+mod rollback;
 mod style;
+mod update;
+#[cfg(any(feature = "parachain", feature = "contract"))]
+mod workspace;
 
-#[cfg(feature = "parachain")]
+#[cfg(any(feature = "parachain", feature = "contract"))]
 use anyhow::anyhow;
 use anyhow::Result;
-use clap::{Parser, Subcommand};
+use clap::{CommandFactory, FromArgMatches, Parser, Subcommand};
 use commands::*;
+use pop_common::Config;
 #[cfg(feature = "telemetry")]
-use pop_telemetry::{config_file_path, record_cli_command, record_cli_used, Telemetry};
+use pop_telemetry::{
+	config_file_path, queue_file_path, record_cli_command, record_cli_used, Queue, Telemetry,
+};
 use serde_json::{json, Value};
-#[cfg(feature = "parachain")]
-use std::{fs::create_dir_all, path::PathBuf};
+use std::{env, ffi::OsString, sync::OnceLock};
+#[cfg(any(feature = "parachain", feature = "contract"))]
+use std::{
+	fs::create_dir_all,
+	path::{Path, PathBuf},
+};
+
+/// The environment variable providing the password of the local keystore.
+#[cfg(any(feature = "parachain", feature = "contract"))]
+const KEYSTORE_PASSWORD: &str = "POP_KEYSTORE_PASSWORD";
+
+/// The configuration of the user, loaded once when launched.
+static CONFIG: OnceLock<Config> = OnceLock::new();
 
 #[derive(Parser)]
 #[command(author, version, about, styles=style::get_styles())]
 pub struct Cli {
 	#[command(subcommand)]
 	command: Commands,
+	/// Run non-interactively: every question is answered with its default, failing when a
+	/// required value is missing. Actions which are only taken when explicitly confirmed, such as
+	/// removing an existing directory, require `--force` instead. Also enabled by setting
+	/// `POP_SKIP_CONFIRM=1`.
+	#[arg(short = 'y', long, global = true)]
+	skip_confirm: bool,
+	/// When to color the output: `auto` colors it when writing to a terminal, unless `NO_COLOR`
+	/// is set.
+	#[arg(long, value_enum, global = true, default_value_t)]
+	color: style::ColorChoice,
+	/// Report the files that would be generated or removed and the transactions that would be
+	/// submitted, without doing so. Supported by `pop new`, `pop clean`, `pop cache
+	/// prune|verify`, `pop call parachain|sudo|reserve-para-id|open-hrmp|xcm` and `pop wallet
+	/// transfer`.
+	#[arg(long, global = true)]
+	dry_run: bool,
+	/// Refuse the binaries downloaded which cannot be verified by their GPG signature or a GitHub
+	/// artifact attestation, as well as those built from source, rather than only warning about
+	/// them.
+	#[arg(long, global = true)]
+	require_signed: bool,
+	/// The format of the progress reported: `human`, or `ndjson` streaming events (steps started
+	/// and finished, progress, artifacts and results) to stdout for editors and other tools,
+	/// whereas the human output is written to stderr.
+	#[arg(long, value_enum, global = true, default_value_t)]
+	progress_format: progress::ProgressFormat,
+	#[command(flatten)]
+	verbosity: logging::Verbosity,
 }
 
 #[derive(Subcommand)]
@@ -36,116 +91,462 @@ enum Commands {
 	#[clap(alias = "b")]
 	#[cfg(any(feature = "parachain", feature = "contract"))]
 	Build(build::BuildArgs),
-	/// Call a smart contract.
+	/// Benchmark a parachain runtime.
+	#[cfg(feature = "parachain")]
+	Bench(bench::BenchArgs),
+	/// Call a smart contract or a relay chain.
 	#[clap(alias = "c")]
-	#[cfg(feature = "contract")]
+	#[cfg(any(feature = "parachain", feature = "contract"))]
 	Call(call::CallArgs),
 	/// Deploy a parachain or smart contract.
 	#[clap(alias = "u")]
 	#[cfg(any(feature = "parachain", feature = "contract"))]
 	Up(up::UpArgs),
-	/// Test a smart contract.
+	/// Test a parachain or smart contract.
 	#[clap(alias = "t")]
-	#[cfg(feature = "contract")]
+	#[cfg(any(feature = "parachain", feature = "contract"))]
 	Test(test::TestArgs),
+	/// Inspect a smart contract or compare runtimes.
+	#[cfg(any(feature = "parachain", feature = "contract"))]
+	Inspect(inspect::InspectArgs),
+	/// Verify a deployed smart contract against its source code.
+	#[clap(alias = "v")]
+	#[cfg(feature = "contract")]
+	Verify(verify::VerifyArgs),
+	/// Query the on-chain state of a chain or smart contract.
+	#[clap(alias = "q")]
+	#[cfg(any(feature = "parachain", feature = "contract"))]
+	Query(query::QueryArgs),
+	/// Convert values between their representations, such as addresses.
+	#[cfg(feature = "contract")]
+	Convert(convert::ConvertArgs),
+	/// Manage the keys within the local keystore.
+	#[clap(alias = "k")]
+	#[cfg(any(feature = "parachain", feature = "contract"))]
+	Key(key::KeyArgs),
+	/// Show the balances of accounts or transfer tokens, e.g. to fund accounts on test networks.
+	#[clap(alias = "w")]
+	#[cfg(feature = "parachain")]
+	Wallet(wallet::WalletArgs),
+	/// List the versions of the binaries cached or pin the version used by a project.
+	#[cfg(any(feature = "parachain", feature = "contract"))]
+	Binaries(binaries::BinariesArgs),
+	/// Bump the tooling locked by a project within its pop.lock.
+	#[cfg(any(feature = "parachain", feature = "contract"))]
+	Update(commands::update::UpdateArgs),
+	/// List or prune the versions of the binaries cached.
+	#[cfg(any(feature = "parachain", feature = "contract"))]
+	Cache(cache::CacheArgs),
+	/// Remove cached binaries, the chain data of local networks and build artifacts.
+	#[cfg(any(feature = "parachain", feature = "contract"))]
+	Clean(clean::CleanArgs),
+	/// Manage the defaults of all commands, held within `~/.pop/config.toml`.
+	Config(config::ConfigArgs),
+	/// Generate the completion script of a shell, written to stdout.
+	Completions(completions::CompletionsArgs),
+	/// Opt in or out of reporting anonymous usage metrics.
+	#[cfg(feature = "telemetry")]
+	Telemetry(telemetry::TelemetryArgs),
 	/// Set up the environment for development by installing required packages
 	#[clap(alias = "i")]
 	Install(install::InstallArgs),
+	/// Display the operations applied to a project, such as its generation and deployments.
+	#[cfg(any(feature = "parachain", feature = "contract"))]
+	History(history::HistoryArgs),
+	/// Diagnose the environment for development, reporting how to fix any issue found.
+	#[cfg(any(feature = "parachain", feature = "contract"))]
+	Doctor(doctor::DoctorArgs),
+	/// A command provided by a plugin: a `pop-<command>` executable on PATH.
+	#[command(external_subcommand)]
+	Plugin(Vec<OsString>),
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
+	let user_config = Config::load();
+	CONFIG.get_or_init(|| user_config.as_ref().cloned().unwrap_or_default());
+	use_proxy(config());
+	pop_common::sourcing::set_mirrors(config().mirrors());
 	#[cfg(feature = "telemetry")]
 	let maybe_tel = init().unwrap_or(None);
 
-	let cli = Cli::parse();
+	let matches =
+		env_vars::with_env_vars(Cli::command(), &|name| env::var(name).ok()).get_matches();
+	let cli = Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+	cli.color.apply();
+	cli.verbosity.init()?;
+	progress::set_format(cli.progress_format);
+	interrupt::install();
+	// Checked while the command runs, so that the notice is shown once it completes.
+	let update = tokio::spawn(update::check(config()));
+	prompt::set_skip_confirm(cli.skip_confirm);
+	pop_common::verification::set_require_signed(cli.require_signed);
+	if cli.dry_run {
+		// Rather than being ignored by a command applying its effects regardless.
+		if let Some((command, args)) = matches.subcommand() {
+			if !dry_run::is_supported(command, args.subcommand_name()) {
+				let command = [Some(command), args.subcommand_name()].into_iter().flatten();
+				clap::Error::raw(
+					clap::error::ErrorKind::ArgumentConflict,
+					format!(
+						"`--dry-run` is not supported by `pop {}`\n",
+						command.collect::<Vec<_>>().join(" ")
+					),
+				)
+				.exit();
+			}
+		}
+		dry_run::set_dry_run(true);
+	}
+	// An invalid configuration is only ignored by `pop config`, so that it can be repaired.
+	if let Err(e) = user_config {
+		if !matches!(cli.command, Commands::Config(_)) {
+			return Err(e.into());
+		}
+	}
+	#[cfg(feature = "telemetry")]
+	let plugin = matches!(cli.command, Commands::Plugin(_));
 	let res = match cli.command {
 		#[cfg(any(feature = "parachain", feature = "contract"))]
-		Commands::New(args) => match args.command {
-			#[cfg(feature = "parachain")]
-			new::NewCommands::Parachain(cmd) => match cmd.execute().await {
-				Ok(template) => {
-					// telemetry should never cause a panic or early exit
-					Ok(json!({template.provider().unwrap_or("provider-missing"): template.name()}))
+		Commands::New(args) => match args.command() {
+			Ok(Some(command)) => match command {
+				#[cfg(feature = "parachain")]
+				new::NewCommands::Parachain(cmd) => match cmd.execute().await {
+					Ok(template) => {
+						// telemetry should never cause a panic or early exit
+						Ok(
+							json!({template.provider().unwrap_or("provider-missing"): template.name()}),
+						)
+					},
+					Err(e) => Err(e),
 				},
-				Err(e) => Err(e),
-			},
-			#[cfg(feature = "parachain")]
-			new::NewCommands::Pallet(cmd) => {
-				// When more contract selections are added the tel data will likely need to go deeper in the stack
-				cmd.execute().await.map(|_| json!("template"))
-			},
-			#[cfg(feature = "contract")]
-			new::NewCommands::Contract(cmd) => {
-				// When more contract selections are added, the tel data will likely need to go deeper in the stack
-				cmd.execute().await.map(|_| json!("default"))
+				#[cfg(feature = "parachain")]
+				new::NewCommands::Pallet(cmd) => {
+					// When more contract selections are added the tel data will likely need to go deeper in the stack
+					cmd.execute().await.map(|_| json!("template"))
+				},
+				#[cfg(feature = "parachain")]
+				new::NewCommands::Test(args) => match &args.command {
+					new::test::NewTestCommands::Network(cmd) => {
+						cmd.execute().map(|_| json!("network"))
+					},
+				},
+				#[cfg(feature = "contract")]
+				new::NewCommands::Contract(cmd) => cmd.execute().await.map(|template| json!(template.as_ref())),
 			},
+			// The generation was cancelled.
+			Ok(None) => Ok(Value::Null),
+			Err(e) => Err(e),
 		},
 		#[cfg(any(feature = "parachain", feature = "contract"))]
 		Commands::Build(args) => match &args.command {
 			#[cfg(feature = "parachain")]
-			build::BuildCommands::Parachain(cmd) => cmd.execute().map(|_| Value::Null),
+			Some(build::BuildCommands::Parachain(cmd)) => cmd.execute().map(|_| Value::Null),
+			#[cfg(feature = "parachain")]
+			Some(build::BuildCommands::Spec(cmd)) => cmd.execute().map(|_| Value::Null),
+			#[cfg(feature = "parachain")]
+			Some(build::BuildCommands::GenesisArtifacts(cmd)) => cmd.execute().map(|_| Value::Null),
 			#[cfg(feature = "contract")]
-			build::BuildCommands::Contract(cmd) => cmd.execute().map(|_| Value::Null),
+			Some(build::BuildCommands::Contract(cmd)) => cmd.execute().map(|_| Value::Null),
+			None => args.execute_all().map(|_| json!("all")),
 		},
-		#[cfg(feature = "contract")]
+		#[cfg(feature = "parachain")]
+		Commands::Bench(args) => match &args.command {
+			bench::BenchCommands::Pallet(cmd) => cmd.execute().await.map(|_| Value::Null),
+		},
+		#[cfg(any(feature = "parachain", feature = "contract"))]
 		Commands::Call(args) => match &args.command {
+			#[cfg(feature = "contract")]
 			call::CallCommands::Contract(cmd) => cmd.execute().await.map(|_| Value::Null),
+			#[cfg(feature = "parachain")]
+			call::CallCommands::Parachain(cmd) => cmd.execute().await.map(|_| Value::Null),
+			#[cfg(feature = "parachain")]
+			call::CallCommands::Sudo(cmd) => cmd.execute().await.map(|_| Value::Null),
+			#[cfg(feature = "parachain")]
+			call::CallCommands::ReserveParaId(cmd) => cmd.execute().await.map(|_| Value::Null),
+			#[cfg(feature = "parachain")]
+			call::CallCommands::OpenHrmp(cmd) => cmd.execute().await.map(|_| Value::Null),
+			#[cfg(feature = "parachain")]
+			call::CallCommands::Xcm(args) => args.command.execute().await.map(|_| Value::Null),
 		},
 		#[cfg(any(feature = "parachain", feature = "contract"))]
 		Commands::Up(args) => match &args.command {
 			#[cfg(feature = "parachain")]
 			up::UpCommands::Parachain(cmd) => cmd.execute().await.map(|_| Value::Null),
+			#[cfg(feature = "parachain")]
+			up::UpCommands::Fork(cmd) => cmd.execute().await.map(|_| Value::Null),
+			#[cfg(feature = "parachain")]
+			up::UpCommands::RuntimeUpgrade(cmd) => cmd.execute().await.map(|_| Value::Null),
+			#[cfg(feature = "parachain")]
+			up::UpCommands::Testnet(cmd) => cmd.execute().await.map(|_| Value::Null),
 			#[cfg(feature = "contract")]
 			up::UpCommands::Contract(cmd) => cmd.execute().await.map(|_| Value::Null),
+			#[cfg(feature = "contract")]
+			up::UpCommands::Contracts(cmd) => cmd.execute().await.map(|_| Value::Null),
+			#[cfg(feature = "contract")]
+			up::UpCommands::ContractsNode(cmd) => cmd.execute().await.map(|_| Value::Null),
 		},
-		#[cfg(feature = "contract")]
+		#[cfg(any(feature = "parachain", feature = "contract"))]
 		Commands::Test(args) => match &args.command {
-			test::TestCommands::Contract(cmd) => match cmd.execute() {
+			#[cfg(feature = "parachain")]
+			Some(test::TestCommands::Parachain(cmd)) => cmd.execute().map(|suite| json!(suite)),
+			#[cfg(feature = "parachain")]
+			Some(test::TestCommands::OnRuntimeUpgrade(cmd)) => cmd.execute().await.map(|test| json!(test)),
+			#[cfg(feature = "contract")]
+			Some(test::TestCommands::Contract(cmd)) => match cmd.execute() {
 				Ok(feature) => Ok(json!(feature)),
 				Err(e) => Err(e),
 			},
+			None => args.execute_all().map(|_| json!("all")),
+		},
+		#[cfg(any(feature = "parachain", feature = "contract"))]
+		Commands::Inspect(args) => match &args.command {
+			#[cfg(feature = "contract")]
+			inspect::InspectCommands::Contract(cmd) => cmd.execute().map(|_| Value::Null),
+			#[cfg(feature = "parachain")]
+			inspect::InspectCommands::RuntimeDiff(cmd) => cmd.execute().await.map(|_| Value::Null),
+		},
+		#[cfg(feature = "contract")]
+		Commands::Verify(args) => match &args.command {
+			verify::VerifyCommands::Contract(cmd) => cmd.execute().await.map(|_| Value::Null),
+		},
+		#[cfg(any(feature = "parachain", feature = "contract"))]
+		Commands::Query(args) => args.execute().await.map(|_| Value::Null),
+		#[cfg(feature = "contract")]
+		Commands::Convert(args) => match &args.command {
+			convert::ConvertCommands::Address(cmd) => cmd.execute().map(|_| Value::Null),
+		},
+		#[cfg(any(feature = "parachain", feature = "contract"))]
+		Commands::Key(args) => match &args.command {
+			key::KeyCommands::Import(cmd) => cmd.execute().map(|_| Value::Null),
+			key::KeyCommands::List(cmd) => cmd.execute().map(|_| Value::Null),
+			key::KeyCommands::Generate(cmd) => cmd.execute().map(|_| Value::Null),
+			key::KeyCommands::Export(cmd) => cmd.execute().map(|_| Value::Null),
+			#[cfg(feature = "parachain")]
+			key::KeyCommands::GenerateNodeKey(cmd) => cmd.execute().map(|_| Value::Null),
+			#[cfg(feature = "parachain")]
+			key::KeyCommands::InsertSessionKeys(cmd) => cmd.execute().map(|_| Value::Null),
 		},
 		#[cfg(any(feature = "parachain", feature = "contract"))]
-		Commands::Install(args) => args.execute().await.map(|_| Value::Null),
+		Commands::Binaries(args) => match &args.command {
+			binaries::BinariesCommands::List(cmd) => cmd.execute().map(|_| Value::Null),
+			binaries::BinariesCommands::Use(cmd) => cmd.execute().map(|_| Value::Null),
+		},
+		#[cfg(feature = "parachain")]
+		Commands::Wallet(args) => match &args.command {
+			wallet::WalletCommands::Balance(cmd) => cmd.execute().await.map(|_| Value::Null),
+			wallet::WalletCommands::Transfer(cmd) => cmd.execute().await.map(|_| Value::Null),
+		},
+		#[cfg(any(feature = "parachain", feature = "contract"))]
+		Commands::Update(args) => match &args.command {
+			commands::update::UpdateCommands::Lock(cmd) => cmd.execute().await.map(|_| Value::Null),
+		},
+		#[cfg(any(feature = "parachain", feature = "contract"))]
+		Commands::Cache(args) => match &args.command {
+			cache::CacheCommands::List(cmd) => cmd.execute().map(|_| Value::Null),
+			cache::CacheCommands::Prune(cmd) => cmd.execute().map(|_| Value::Null),
+			cache::CacheCommands::Export(cmd) => cmd.execute().map(|_| Value::Null),
+			cache::CacheCommands::Import(cmd) => cmd.execute().map(|_| Value::Null),
+			cache::CacheCommands::Verify(cmd) => cmd.execute().await.map(|_| Value::Null),
+		},
+		#[cfg(any(feature = "parachain", feature = "contract"))]
+		Commands::Clean(args) => args.execute().map(|_| Value::Null),
+		Commands::Config(args) => match &args.command {
+			config::ConfigCommands::Get(cmd) => cmd.execute().map(|_| Value::Null),
+			config::ConfigCommands::Set(cmd) => cmd.execute().map(|_| Value::Null),
+			config::ConfigCommands::Unset(cmd) => cmd.execute().map(|_| Value::Null),
+		},
+		Commands::Completions(args) => args.execute().map(|_| Value::Null),
+		#[cfg(feature = "telemetry")]
+		Commands::Telemetry(args) => args.command.execute().map(|_| Value::Null),
+		#[cfg(any(feature = "parachain", feature = "contract"))]
+		Commands::Install(args) => args.execute().map(|_| Value::Null),
+		#[cfg(any(feature = "parachain", feature = "contract"))]
+		Commands::History(args) => args.execute().map(|_| Value::Null),
+		#[cfg(any(feature = "parachain", feature = "contract"))]
+		Commands::Doctor(args) => args.execute().await.map(|_| Value::Null),
+		Commands::Plugin(args) => plugin::execute(&args).map(|_| Value::Null),
 	};
 
 	#[cfg(feature = "telemetry")]
 	if let Some(tel) = maybe_tel.clone() {
-		// `args` is guaranteed to have at least 3 elements as clap will display help message if not set.
-		let args: Vec<_> = std::env::args().collect();
-		let command = args.get(1).expect("expected command missing");
-		let subcommand = args.get(2).expect("expected sub-command missing");
-
-		if let Ok(sub_data) = &res {
-			// Best effort to send on first try, no action if failure.
-			let _ =
-				record_cli_command(tel.clone(), command, json!({subcommand: sub_data.to_string()}))
+		// Only the names of the command and subcommand are reported, never any value provided,
+		// including the names of plugins.
+		let (command, subcommand) = match plugin {
+			true => ("plugin", ""),
+			false => matches
+				.subcommand()
+				.map(|(name, args)| (name, args.subcommand_name().unwrap_or_default()))
+				.unwrap_or_default(),
+		};
+
+		// Best effort to send on first try, queued for the next run if failure.
+		match &res {
+			Ok(sub_data) => {
+				let _ = record_cli_command(tel, command, json!({subcommand: sub_data.to_string()}))
 					.await;
-		} else {
-			let _ = record_cli_command(tel, "error", json!({command: subcommand})).await;
+			},
+			Err(e) => {
+				let data = json!({command: subcommand, "category": error_category(e)});
+				let _ = record_cli_command(tel, "error", data).await;
+			},
 		}
 	}
 
-	// map result from Result<Value> to Result<()>
-	res.map(|_| ())
+	if let Ok(Some(latest)) = update.await {
+		update::notify(&latest);
+	}
+
+	// Failures are rendered with their code and suggested fix, rather than as a debug chain.
+	if let Err(e) = res {
+		eprintln!("{}", errors::Diagnostic::new(&e).render(logging::verbose()));
+		std::process::exit(1);
+	}
+	Ok(())
 }
-#[cfg(feature = "parachain")]
+
+/// The configuration of the user, providing the defaults of all commands.
+fn config() -> &'static Config {
+	CONFIG.get_or_init(Config::default)
+}
+
+/// Routes network requests through the proxy configured by the user, including those of the tools
+/// launched such as cargo and git, unless a proxy is already set by the environment.
+///
+/// # Arguments
+///
+/// * `config` - the configuration of the user
+fn use_proxy(config: &Config) {
+	const VARS: [&str; 4] = ["HTTPS_PROXY", "https_proxy", "HTTP_PROXY", "http_proxy"];
+	let Some(proxy) = config.proxy() else { return };
+	if VARS.iter().any(|var| env::var_os(var).is_some()) {
+		return;
+	}
+	env::set_var("HTTPS_PROXY", proxy.as_str());
+	env::set_var("HTTP_PROXY", proxy.as_str());
+}
+
+#[cfg(any(feature = "parachain", feature = "contract"))]
 fn cache() -> Result<PathBuf> {
-	let cache_path = dirs::cache_dir()
-		.ok_or(anyhow!("the cache directory could not be determined"))?
-		.join("pop");
+	let cache_path = match config().cache() {
+		Some(path) => path,
+		None => dirs::cache_dir()
+			.ok_or(anyhow!("the cache directory could not be determined"))?
+			.join("pop"),
+	};
 	// Creates pop dir if needed
 	create_dir_all(cache_path.as_path())?;
 	Ok(cache_path)
 }
 
+/// The directory of the project a command applies to: `path` when provided, otherwise the root of
+/// the project containing the current directory, so that pop can be run from any of its
+/// subdirectories, otherwise the current directory.
+///
+/// # Arguments
+///
+/// * `path` - the path of the project provided using `--path`, if any
+#[cfg(any(feature = "parachain", feature = "contract"))]
+fn project_path(path: Option<&Path>) -> PathBuf {
+	match path {
+		Some(path) => path.to_path_buf(),
+		None => env::current_dir()
+			.ok()
+			.and_then(|dir| pop_common::project::find_root(&dir))
+			.unwrap_or_else(|| PathBuf::from("./")),
+	}
+}
+
+/// The directory of the contract a command applies to: `path` when provided, otherwise the
+/// nearest crate containing the current directory, so that a contract within a workspace of
+/// contracts is targeted rather than the workspace, otherwise the current directory.
+///
+/// # Arguments
+///
+/// * `path` - the path of the contract provided using `--path`, if any
+#[cfg(feature = "contract")]
+fn contract_path(path: Option<&Path>) -> PathBuf {
+	match path {
+		Some(path) => path.to_path_buf(),
+		None => env::current_dir()
+			.ok()
+			.and_then(|dir| pop_common::project::find_crate(&dir))
+			.unwrap_or_else(|| PathBuf::from("./")),
+	}
+}
+
+/// The local keystore, holding the keys which can be used for signing.
+#[cfg(any(feature = "parachain", feature = "contract"))]
+fn keystore() -> Result<pop_common::Keystore> {
+	let path = dirs::data_dir()
+		.ok_or(anyhow!("the data directory could not be determined"))?
+		.join("pop")
+		.join("keystore");
+	Ok(pop_common::Keystore::new(path))
+}
+
+/// The file recording the endpoints of the local network launched, while it is running.
+#[cfg(feature = "parachain")]
+fn network_endpoints_path() -> Result<PathBuf> {
+	Ok(dirs::data_dir()
+		.ok_or(anyhow!("the data directory could not be determined"))?
+		.join("pop")
+		.join("network.json"))
+}
+
+/// The password of the local keystore, from `POP_KEYSTORE_PASSWORD` when set so that it can be
+/// used non-interactively, otherwise prompted for.
+///
+/// # Arguments
+///
+/// * `confirm` - whether a password prompted for is confirmed, as when encrypting a new key
+#[cfg(any(feature = "parachain", feature = "contract"))]
+fn keystore_password(confirm: bool) -> Result<String> {
+	if let Ok(password) = env::var(KEYSTORE_PASSWORD) {
+		return Ok(password);
+	}
+	let password = prompt::password("Enter the password of the keystore:", KEYSTORE_PASSWORD)?;
+	if confirm && prompt::password("Confirm the password:", KEYSTORE_PASSWORD)? != password {
+		return Err(anyhow!("the passwords do not match"));
+	}
+	Ok(password)
+}
+
+/// The secret key URI of the key named `name` within the local keystore, used to sign
+/// extrinsics. Prompts for the password of the keystore when the key is encrypted.
+///
+/// # Arguments
+///
+/// * `name` - the name of the key
+#[cfg(any(feature = "parachain", feature = "contract"))]
+fn key_suri(name: &str) -> Result<String> {
+	let mut keystore = keystore()?;
+	let info = keystore.info(name)?;
+	// Extrinsics are signed with sr25519 keys, which would derive another account from the secret.
+	if info.scheme != pop_common::keystore::Scheme::Sr25519 {
+		return Err(anyhow!(
+			"the key `{name}` is an {} key, whereas only sr25519 keys can sign extrinsics",
+			info.scheme
+		));
+	}
+	if info.encrypted {
+		keystore = keystore.with_password(keystore_password(false)?);
+	}
+	Ok(keystore.get(name)?.suri)
+}
+
 #[cfg(feature = "telemetry")]
 fn init() -> Result<Option<Telemetry>> {
-	env_logger::init();
+	// Telemetry is opt-in: nothing is initialized unless enabled with `pop telemetry on`.
+	if config().telemetry() != Some(true) {
+		return Ok(None);
+	}
 	let maybe_config_path = config_file_path();
 
-	let maybe_tel = maybe_config_path.ok().map(|path| Telemetry::new(&path));
+	let maybe_tel = maybe_config_path.ok().map(|path| match queue_file_path() {
+		Ok(queue) => Telemetry::new(&path).with_queue(Queue::new(queue)),
+		Err(_) => Telemetry::new(&path),
+	});
 
 	// Handle for await not used here as telemetry should complete before any of the commands do.
 	// Sends a generic ping saying the CLI was used.
@@ -157,6 +558,36 @@ fn init() -> Result<Option<Telemetry>> {
 	Ok(maybe_tel)
 }
 
+/// The category of `error`, reported instead of its message as the latter may contain values
+/// provided by the user.
+///
+/// # Arguments
+///
+/// * `error` - the error of a command
+#[cfg(feature = "telemetry")]
+fn error_category(error: &anyhow::Error) -> &'static str {
+	for cause in error.chain() {
+		if cause.is::<reqwest::Error>() {
+			return "network";
+		} else if cause.is::<git2::Error>() {
+			return "git";
+		} else if cause.is::<std::io::Error>() {
+			return "io";
+		} else if cause.is::<pop_common::Error>() {
+			return "common";
+		}
+		#[cfg(feature = "parachain")]
+		if cause.is::<pop_parachains::Error>() {
+			return "parachain";
+		}
+		#[cfg(feature = "contract")]
+		if cause.is::<pop_contracts::Error>() {
+			return "contract";
+		}
+	}
+	"other"
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -165,7 +596,15 @@ mod tests {
 	fn verify_cli() {
 		// https://docs.rs/clap/latest/clap/_derive/_tutorial/chapter_4/index.html
 		use clap::CommandFactory;
-		Cli::command().debug_assert()
+		env_vars::with_env_vars(Cli::command(), &|_| None).debug_assert()
+	}
+
+	#[test]
+	#[cfg(feature = "telemetry")]
+	fn test_error_category() {
+		let io = std::io::Error::new(std::io::ErrorKind::NotFound, "missing");
+		assert_eq!(error_category(&anyhow::Error::from(io).context("unable to read")), "io");
+		assert_eq!(error_category(&anyhow!("failed")), "other");
 	}
 
 	#[test]
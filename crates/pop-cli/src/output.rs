@@ -0,0 +1,87 @@
+// SPDX-License-Identifier: GPL-3.0
+use crate::progress::Event;
+use clap::{Args, ValueEnum};
+use serde::Serialize;
+
+/// The format in which a command reports its results.
+#[derive(Clone, Copy, Debug, Default, PartialEq, ValueEnum)]
+pub(crate) enum OutputFormat {
+	/// Styled text, for the terminal.
+	#[default]
+	Text,
+	/// A stable JSON structure written to stdout, for scripts.
+	Json,
+}
+
+/// Selects how a command reporting data outputs its results.
+#[derive(Args, Clone, Debug, Default)]
+pub(crate) struct OutputArgs {
+	/// The format of the results: `text`, or `json` written to stdout for scripts, whereas
+	/// progress is still reported to stderr.
+	#[arg(long = "output", value_enum, default_value_t)]
+	pub(crate) format: OutputFormat,
+}
+
+impl OutputArgs {
+	/// Whether the results are output as JSON.
+	pub(crate) fn is_json(&self) -> bool {
+		self.format == OutputFormat::Json
+	}
+
+	/// Clears the screen, unless the results are output as JSON, which must remain the only
+	/// content of stdout.
+	pub(crate) fn clear_screen(&self) -> anyhow::Result<()> {
+		if !self.is_json() {
+			cliclack::clear_screen()?;
+		}
+		Ok(())
+	}
+
+	/// Writes `report` to stdout as JSON, when selected. When progress is streamed as events, the
+	/// report is streamed as the result of the command instead, so that stdout only holds events.
+	///
+	/// # Arguments
+	///
+	/// * `report` - the results of the command
+	pub(crate) fn report(&self, report: &impl Serialize) -> anyhow::Result<()> {
+		if crate::progress::is_ndjson() {
+			crate::progress::emit(&Event::Result { report: serde_json::to_value(report)? });
+		} else if self.is_json() {
+			println!("{}", to_json(report)?);
+		}
+		Ok(())
+	}
+}
+
+/// Serializes `report` as pretty-printed JSON.
+fn to_json(report: &impl Serialize) -> anyhow::Result<String> {
+	Ok(serde_json::to_string_pretty(report)?)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::path::PathBuf;
+
+	#[derive(Serialize)]
+	struct Report {
+		name: String,
+		path: PathBuf,
+		release: Option<String>,
+	}
+
+	#[test]
+	fn test_to_json() -> anyhow::Result<()> {
+		let report = Report {
+			name: "my-parachain".into(),
+			path: PathBuf::from("/tmp/my-parachain"),
+			release: None,
+		};
+		assert_eq!(
+			to_json(&report)?,
+			"{\n  \"name\": \"my-parachain\",\n  \"path\": \"/tmp/my-parachain\",\n  \"release\": null\n}"
+		);
+		assert!(!OutputArgs::default().is_json());
+		Ok(())
+	}
+}
@@ -0,0 +1,192 @@
+// SPDX-License-Identifier: GPL-3.0
+use clap::ValueEnum;
+use cliclack::ProgressBar;
+use serde::Serialize;
+use std::{
+	fmt::Display,
+	ops::Deref,
+	path::Path,
+	sync::{
+		atomic::{AtomicBool, Ordering},
+		Mutex,
+	},
+};
+
+/// Whether progress is streamed to stdout as events.
+static NDJSON: AtomicBool = AtomicBool::new(false);
+
+/// The format in which the progress of a command is reported.
+#[derive(Clone, Copy, Debug, Default, PartialEq, ValueEnum)]
+pub(crate) enum ProgressFormat {
+	/// Styled output, for the terminal.
+	#[default]
+	Human,
+	/// Structured events streamed to stdout, one JSON object per line, for editors and other
+	/// tools, whereas the human output is still written to stderr.
+	Ndjson,
+}
+
+/// Selects the format in which progress is reported.
+///
+/// # Arguments
+///
+/// * `format` - the format selected
+pub(crate) fn set_format(format: ProgressFormat) {
+	NDJSON.store(format == ProgressFormat::Ndjson, Ordering::Relaxed);
+}
+
+/// Whether progress is streamed to stdout as events.
+pub(crate) fn is_ndjson() -> bool {
+	NDJSON.load(Ordering::Relaxed)
+}
+
+/// An event streamed when progress is reported as `ndjson`.
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub(crate) enum Event<'a> {
+	/// A step started.
+	StepStarted { step: &'a str },
+	/// A step finished, successfully or not.
+	StepFinished { step: &'a str, success: bool, message: &'a str },
+	/// The progress of a long-running step, such as a download.
+	Progress { step: &'a str, done: u64, total: Option<u64>, percentage: Option<u64> },
+	/// An artifact was produced, such as a runtime or a contract bundle.
+	Artifact { path: &'a Path },
+	/// The results of the command, as otherwise output using `--output json`.
+	Result { report: serde_json::Value },
+}
+
+/// Streams `event` to stdout, when progress is reported as `ndjson`.
+///
+/// # Arguments
+///
+/// * `event` - the event
+pub(crate) fn emit(event: &Event) {
+	if !is_ndjson() {
+		return;
+	}
+	if let Ok(line) = serde_json::to_string(event) {
+		println!("{line}");
+	}
+}
+
+/// Reports the artifact at `path`, once produced.
+///
+/// # Arguments
+///
+/// * `path` - path to the artifact
+pub(crate) fn artifact(path: &Path) {
+	emit(&Event::Artifact { path });
+}
+
+/// Reports the `progress` of a long-running step.
+///
+/// # Arguments
+///
+/// * `progress` - the progress of the step
+pub(crate) fn progress(progress: &pop_common::Progress) {
+	emit(&Event::Progress {
+		step: progress.step,
+		done: progress.done,
+		total: progress.total,
+		percentage: progress.percentage(),
+	});
+}
+
+/// A spinner rendering a step, whose start and end are also streamed as events.
+pub(crate) struct Spinner {
+	bar: ProgressBar,
+	/// The step running.
+	step: Mutex<String>,
+}
+
+/// A spinner rendering a step, whose start and end are also streamed as events.
+pub(crate) fn spinner() -> Spinner {
+	Spinner { bar: cliclack::spinner(), step: Mutex::new(String::new()) }
+}
+
+impl Spinner {
+	/// Starts rendering the step described by `message`.
+	pub(crate) fn start(&self, message: impl Display) {
+		let step = message.to_string();
+		emit(&Event::StepStarted { step: &step });
+		self.bar.start(&step);
+		if let Ok(mut current) = self.step.lock() {
+			*current = step;
+		}
+	}
+
+	/// Stops rendering the step, which succeeded.
+	pub(crate) fn stop(&self, message: impl Display) {
+		self.finish(message.to_string(), true);
+	}
+
+	/// Stops rendering the step, which failed.
+	pub(crate) fn error(&self, message: impl Display) {
+		self.finish(message.to_string(), false);
+	}
+
+	fn finish(&self, message: String, success: bool) {
+		let step = self.step.lock().map(|s| s.clone()).unwrap_or_default();
+		emit(&Event::StepFinished { step: &step, success, message: &message });
+		match success {
+			true => self.bar.stop(message),
+			false => self.bar.error(message),
+		}
+	}
+}
+
+impl Deref for Spinner {
+	type Target = ProgressBar;
+
+	fn deref(&self) -> &Self::Target {
+		&self.bar
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use serde_json::json;
+
+	#[test]
+	fn events_serialize_as_tagged_objects() -> anyhow::Result<()> {
+		assert_eq!(
+			serde_json::to_value(Event::StepStarted { step: "Building..." })?,
+			json!({"event": "step_started", "step": "Building..."})
+		);
+		assert_eq!(
+			serde_json::to_value(Event::StepFinished {
+				step: "Building...",
+				success: false,
+				message: "Build failed."
+			})?,
+			json!({"event": "step_finished", "step": "Building...", "success": false, "message": "Build failed."})
+		);
+		assert_eq!(
+			serde_json::to_value(Event::Progress {
+				step: "Downloading",
+				done: 1,
+				total: Some(4),
+				percentage: Some(25)
+			})?,
+			json!({"event": "progress", "step": "Downloading", "done": 1, "total": 4, "percentage": 25})
+		);
+		assert_eq!(
+			serde_json::to_value(Event::Artifact {
+				path: Path::new("target/ink/flipper.contract")
+			})?,
+			json!({"event": "artifact", "path": "target/ink/flipper.contract"})
+		);
+		Ok(())
+	}
+
+	#[test]
+	fn set_format_works() {
+		assert!(!is_ndjson());
+		set_format(ProgressFormat::Ndjson);
+		assert!(is_ndjson());
+		set_format(ProgressFormat::Human);
+		assert!(!is_ndjson());
+	}
+}
@@ -0,0 +1,143 @@
+// SPDX-License-Identifier: GPL-3.0
+use anyhow::{anyhow, Result};
+use std::{
+	fmt::Display,
+	io::IsTerminal,
+	sync::atomic::{AtomicBool, Ordering},
+};
+
+/// The environment variable which, when set to `1` or `true`, runs every command
+/// non-interactively, as with `--skip-confirm`.
+pub(crate) const SKIP_CONFIRM_ENV: &str = "POP_SKIP_CONFIRM";
+
+/// Whether `--skip-confirm` was provided.
+static SKIP_CONFIRM: AtomicBool = AtomicBool::new(false);
+
+/// Runs every command non-interactively: every question is answered with its default.
+///
+/// # Arguments
+///
+/// * `skip` - whether prompts are skipped
+pub(crate) fn set_skip_confirm(skip: bool) {
+	SKIP_CONFIRM.store(skip, Ordering::Relaxed);
+}
+
+/// Whether commands run non-interactively, either as `--skip-confirm` was provided or as
+/// `POP_SKIP_CONFIRM` is set.
+pub(crate) fn skip_confirm() -> bool {
+	SKIP_CONFIRM.load(Ordering::Relaxed)
+		|| std::env::var(SKIP_CONFIRM_ENV).is_ok_and(|value| is_enabled(&value))
+}
+
+/// Whether the value of an environment variable enables an option.
+fn is_enabled(value: &str) -> bool {
+	matches!(value.trim().to_lowercase().as_str(), "1" | "true" | "yes")
+}
+
+/// Ensures that a question without a default can be asked, failing with how to provide its answer
+/// otherwise.
+///
+/// # Arguments
+///
+/// * `flag` - the flag providing the answer instead (e.g. `--suri`)
+pub(crate) fn ensure_interactive(flag: &str) -> Result<()> {
+	if skip_confirm() {
+		return Err(anyhow!("`{flag}` is required when running non-interactively"));
+	}
+	ensure_terminal()
+}
+
+/// Ensures prompts can be shown, rather than aborting, as they do without a terminal, or being
+/// interleaved with the output when piped, as in CI logs.
+fn ensure_terminal() -> Result<()> {
+	match std::io::stdin().is_terminal() && std::io::stdout().is_terminal() {
+		true => Ok(()),
+		false => Err(anyhow!(
+			"unable to prompt without a terminal: run with `--skip-confirm` (or set \
+			 {SKIP_CONFIRM_ENV}=1) to proceed with the defaults"
+		)),
+	}
+}
+
+/// Asks for confirmation, which is answered with `initial` when running non-interactively, so that
+/// an action which is only taken when explicitly confirmed, such as removing a directory, is never
+/// taken implicitly.
+///
+/// # Arguments
+///
+/// * `prompt` - the question
+/// * `initial` - the initially selected answer
+pub(crate) fn confirm(prompt: impl Display, initial: bool) -> Result<bool> {
+	if skip_confirm() {
+		return Ok(initial);
+	}
+	ensure_terminal()?;
+	Ok(cliclack::confirm(prompt).initial_value(initial).interact()?)
+}
+
+/// Asks for a value, which is `default` when running non-interactively.
+///
+/// # Arguments
+///
+/// * `prompt` - the question
+/// * `default` - the default answer
+pub(crate) fn input(prompt: impl Display, default: &str) -> Result<String> {
+	if skip_confirm() {
+		return Ok(default.into());
+	}
+	ensure_terminal()?;
+	Ok(cliclack::input(prompt).placeholder(default).default_input(default).interact()?)
+}
+
+/// Asks to select one of `items`, each with a label and hint, which is the first item when running
+/// non-interactively.
+///
+/// # Arguments
+///
+/// * `prompt` - the question
+/// * `items` - the items to select from, along with their label and hint
+pub(crate) fn select<T: Clone + Eq>(
+	prompt: impl Display,
+	items: Vec<(T, String, String)>,
+) -> Result<T> {
+	let first = items
+		.first()
+		.map(|(item, ..)| item.clone())
+		.ok_or(anyhow!("nothing to select"))?;
+	if skip_confirm() {
+		return Ok(first);
+	}
+	ensure_terminal()?;
+	let mut select = cliclack::select(prompt).initial_value(first);
+	for (item, label, hint) in items {
+		select = select.item(item, label, hint);
+	}
+	Ok(select.interact()?)
+}
+
+/// Asks for a secret, such as a secret key URI, which must be provided by `flag` when running
+/// non-interactively.
+///
+/// # Arguments
+///
+/// * `prompt` - the question
+/// * `flag` - the flag providing the secret instead
+pub(crate) fn password(prompt: impl Display, flag: &str) -> Result<String> {
+	ensure_interactive(flag)?;
+	Ok(cliclack::password(prompt).mask('▪').interact()?)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_is_enabled() {
+		for value in ["1", "true", "TRUE", " yes "] {
+			assert!(is_enabled(value));
+		}
+		for value in ["", "0", "false", "no"] {
+			assert!(!is_enabled(value));
+		}
+	}
+}
@@ -0,0 +1,109 @@
+// SPDX-License-Identifier: GPL-3.0
+use cliclack::log;
+use std::{
+	fs,
+	path::{Path, PathBuf},
+};
+
+/// The paths created by a command, removed when it fails so that no partially generated project
+/// remains, unless kept for debugging. The paths are kept once the command completes.
+pub(crate) struct Rollback {
+	/// The paths created, in order.
+	paths: Vec<PathBuf>,
+	/// Whether the paths are kept on failure, for debugging.
+	keep: bool,
+	/// Whether the command completed.
+	completed: bool,
+}
+
+impl Rollback {
+	/// Starts tracking the paths created by a command.
+	///
+	/// # Arguments
+	///
+	/// * `keep` - whether the paths are kept on failure, for debugging
+	pub(crate) fn new(keep: bool) -> Self {
+		Self { paths: Vec::new(), keep, completed: false }
+	}
+
+	/// Tracks `path`, about to be created, unless it already exists.
+	///
+	/// # Arguments
+	///
+	/// * `path` - the file or directory about to be created
+	pub(crate) fn track(&mut self, path: &Path) {
+		if !path.exists() {
+			self.paths.push(path.to_path_buf());
+		}
+	}
+
+	/// Marks the command as completed, keeping the paths created.
+	pub(crate) fn complete(mut self) {
+		self.completed = true;
+	}
+}
+
+impl Drop for Rollback {
+	fn drop(&mut self) {
+		if self.completed {
+			return;
+		}
+		for path in self.paths.iter().rev().filter(|p| p.exists()) {
+			if self.keep {
+				let _ = log::warning(format!("Kept {} for debugging.", path.display()));
+				continue;
+			}
+			let removed = match path.is_dir() {
+				true => fs::remove_dir_all(path),
+				false => fs::remove_file(path),
+			};
+			let _ = match removed {
+				Ok(_) => log::info(format!("Removed the partially generated {}.", path.display())),
+				Err(e) => log::warning(format!("Unable to remove {}: {e}", path.display())),
+			};
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+
+	#[test]
+	fn rollback_removes_paths_on_failure() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let existing = temp_dir.path().join("existing");
+		fs::create_dir(&existing)?;
+		let created = temp_dir.path().join("my-parachain");
+		{
+			let mut rollback = Rollback::new(false);
+			rollback.track(&existing);
+			rollback.track(&created);
+			fs::create_dir(&created)?;
+			fs::write(created.join("Cargo.toml"), "")?;
+		}
+		assert!(!created.exists());
+		assert!(existing.exists());
+		Ok(())
+	}
+
+	#[test]
+	fn rollback_keeps_paths() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let created = temp_dir.path().join("my-parachain");
+		let mut rollback = Rollback::new(false);
+		rollback.track(&created);
+		fs::create_dir(&created)?;
+		rollback.complete();
+		assert!(created.exists());
+
+		let kept = temp_dir.path().join("kept");
+		let mut rollback = Rollback::new(true);
+		rollback.track(&kept);
+		fs::create_dir(&kept)?;
+		drop(rollback);
+		assert!(kept.exists());
+		Ok(())
+	}
+}
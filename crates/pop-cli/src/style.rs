@@ -1,8 +1,91 @@
 // SPDX-License-Identifier: GPL-3.0
+use clap::ValueEnum;
 use cliclack::ThemeState;
 #[cfg(any(feature = "parachain", feature = "contract"))]
 pub(crate) use console::style;
-use console::Style;
+use console::{measure_text_width, Style};
+use std::{
+	env,
+	ffi::OsString,
+	io::{self, IsTerminal},
+};
+
+/// The environment variable which, when set to a non-empty value, disables colors by default.
+const NO_COLOR_ENV: &str = "NO_COLOR";
+
+/// When the output is colored.
+#[derive(Clone, Copy, Debug, Default, PartialEq, ValueEnum)]
+pub(crate) enum ColorChoice {
+	/// Colored when writing to a terminal, unless `NO_COLOR` is set.
+	#[default]
+	Auto,
+	/// Always colored, even when piped.
+	Always,
+	/// Never colored.
+	Never,
+}
+
+impl ColorChoice {
+	/// Colors every styled output, including prompts, according to the choice.
+	pub(crate) fn apply(self) {
+		let terminal = io::stdout().is_terminal() && io::stderr().is_terminal();
+		let enabled = self.enabled(env::var_os(NO_COLOR_ENV), terminal);
+		console::set_colors_enabled(enabled);
+		console::set_colors_enabled_stderr(enabled);
+	}
+
+	/// Whether the output is colored.
+	///
+	/// # Arguments
+	///
+	/// * `no_color` - the value of `NO_COLOR`, if set
+	/// * `terminal` - whether the output is written to a terminal
+	fn enabled(self, no_color: Option<OsString>, terminal: bool) -> bool {
+		match self {
+			ColorChoice::Always => true,
+			ColorChoice::Never => false,
+			ColorChoice::Auto => terminal && no_color.map_or(true, |value| value.is_empty()),
+		}
+	}
+}
+
+/// The alignment of the cells of a column of a table.
+#[derive(Clone, Copy)]
+pub(crate) enum Align {
+	Left,
+	Right,
+}
+
+/// A table of `rows` beneath a header, each column padded to its widest cell and separated by two
+/// spaces. A last column aligned left is not padded.
+///
+/// # Arguments
+///
+/// * `columns` - the header and alignment of each column
+/// * `rows` - the cells of each row
+pub(crate) fn table<const N: usize>(columns: [(&str, Align); N], rows: &[[String; N]]) -> String {
+	let header = columns.map(|(header, _)| header.to_string());
+	let lines: Vec<_> = std::iter::once(&header).chain(rows).collect();
+	let widths: [usize; N] = std::array::from_fn(|i| {
+		lines.iter().map(|line| measure_text_width(&line[i])).max().unwrap_or_default()
+	});
+	let format = |line: &[String; N]| {
+		let cells: Vec<_> = line
+			.iter()
+			.enumerate()
+			.map(|(i, cell)| {
+				let padding = " ".repeat(widths[i] - measure_text_width(cell));
+				match columns[i].1 {
+					Align::Left if i == N - 1 => cell.clone(),
+					Align::Left => format!("{cell}{padding}"),
+					Align::Right => format!("{padding}{cell}"),
+				}
+			})
+			.collect();
+		cells.join("  ")
+	};
+	lines.into_iter().map(format).collect::<Vec<_>>().join("\n")
+}
 
 pub(crate) fn get_styles() -> clap::builder::Styles {
 	use clap::builder::styling::{AnsiColor, Color, Style};
@@ -40,3 +123,40 @@ impl cliclack::Theme for Theme {
 		"⚙".into()
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn color_choice_works() {
+		let no_color = || Some(OsString::from("1"));
+		assert!(ColorChoice::Auto.enabled(None, true));
+		assert!(ColorChoice::Auto.enabled(Some(OsString::new()), true));
+		assert!(!ColorChoice::Auto.enabled(no_color(), true));
+		assert!(!ColorChoice::Auto.enabled(None, false));
+		assert!(ColorChoice::Always.enabled(no_color(), false));
+		assert!(!ColorChoice::Never.enabled(None, true));
+	}
+
+	#[test]
+	fn table_works() {
+		let rows = [
+			["polkadot".to_string(), "512 B".to_string(), "v1.11.0 → v1.13.0".to_string()],
+			["frame-omni-bencher".to_string(), "2.0 KB".to_string(), "new".to_string()],
+		];
+		assert_eq!(
+			table(
+				[("Binary", Align::Left), ("Size", Align::Right), ("Version", Align::Left)],
+				&rows
+			),
+			"Binary                Size  Version\n\
+			 polkadot             512 B  v1.11.0 → v1.13.0\n\
+			 frame-omni-bencher  2.0 KB  new"
+		);
+		assert_eq!(
+			table([("Binary", Align::Left), ("Version", Align::Left)], &[]),
+			"Binary  Version"
+		);
+	}
+}
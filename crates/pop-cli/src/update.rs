@@ -0,0 +1,144 @@
+// SPDX-License-Identifier: GPL-3.0
+use crate::style::style;
+use pop_common::{Config, GitHub};
+use serde::{Deserialize, Serialize};
+use std::{
+	env, fs,
+	io::{self, IsTerminal},
+	path::Path,
+	time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use url::Url;
+
+/// The repository whose releases are checked.
+const REPOSITORY: &str = "https://github.com/r0gue-io/pop-cli";
+/// Disables the check when set, e.g. `POP_NO_UPDATE_CHECK=1`.
+const DISABLE: &str = "POP_NO_UPDATE_CHECK";
+/// The file within the configuration directory caching the latest release found.
+const FILE: &str = "update-check.json";
+/// How long the latest release found is cached, so that releases are checked once per day.
+const INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+/// How long the releases are awaited, so that commands are never held up by the check.
+const TIMEOUT: Duration = Duration::from_secs(2);
+
+/// The outcome of the latest check, cached between runs.
+#[derive(Debug, Default, Deserialize, PartialEq, Serialize)]
+struct State {
+	/// When the releases were last checked, in seconds since the Unix epoch.
+	checked_at: u64,
+	/// The latest release found, if any.
+	latest: Option<String>,
+}
+
+/// The latest release of pop, when newer than the version running. The releases are checked at
+/// most once per day, unless disabled with the `update_check` configuration key or by setting
+/// `POP_NO_UPDATE_CHECK`, and never when the output is not a terminal, such as within CI.
+///
+/// # Arguments
+///
+/// * `config` - the configuration of the user
+pub(crate) async fn check(config: &Config) -> Option<String> {
+	if config.update_check() == Some(false)
+		|| env::var_os(DISABLE).is_some_and(|v| !v.is_empty())
+		|| !io::stderr().is_terminal()
+	{
+		return None;
+	}
+	let file = Config::path().ok()?.with_file_name(FILE);
+	let now = SystemTime::now().duration_since(UNIX_EPOCH).ok()?.as_secs();
+	let latest = match load(&file) {
+		Some(state) if now.saturating_sub(state.checked_at) < INTERVAL.as_secs() => state.latest,
+		_ => {
+			let latest = tokio::time::timeout(TIMEOUT, latest_release()).await.ok().flatten();
+			// Failures are cached too, so that an offline machine is not slowed down each run.
+			let _ = save(&file, &State { checked_at: now, latest: latest.clone() });
+			latest
+		},
+	};
+	latest.filter(|latest| is_newer(latest, env!("CARGO_PKG_VERSION")))
+}
+
+/// Prints a one-line notice that the `latest` release is available.
+///
+/// # Arguments
+///
+/// * `latest` - the latest release
+pub(crate) fn notify(latest: &str) {
+	eprintln!(
+		"{} pop {latest} is available (running v{}), update with `cargo install --locked --git {REPOSITORY}`",
+		style("note:").cyan().bold(),
+		env!("CARGO_PKG_VERSION")
+	);
+}
+
+/// The tag of the latest release of pop, excluding pre-releases.
+async fn latest_release() -> Option<String> {
+	let repository = Url::parse(REPOSITORY).ok()?;
+	GitHub::get_latest_releases(&repository)
+		.await
+		.ok()?
+		.into_iter()
+		.find(|r| !r.prerelease)
+		.map(|r| r.tag_name)
+}
+
+/// The state cached within `file`, if any.
+fn load(file: &Path) -> Option<State> {
+	serde_json::from_str(&fs::read_to_string(file).ok()?).ok()
+}
+
+/// Caches `state` within `file`.
+fn save(file: &Path, state: &State) -> anyhow::Result<()> {
+	if let Some(dir) = file.parent() {
+		fs::create_dir_all(dir)?;
+	}
+	fs::write(file, serde_json::to_string(state)?)?;
+	Ok(())
+}
+
+/// Whether the version `latest` is newer than `current`, comparing their numeric components,
+/// e.g. `v0.3.0` is newer than `0.2.1`.
+///
+/// # Arguments
+///
+/// * `latest` - the version of the latest release
+/// * `current` - the version running
+fn is_newer(latest: &str, current: &str) -> bool {
+	let components = |version: &str| -> Option<Vec<u64>> {
+		let version = version.trim_start_matches('v');
+		// Pre-release and build metadata are ignored.
+		let version = version.split(['-', '+']).next()?;
+		version.split('.').map(|c| c.parse().ok()).collect()
+	};
+	match (components(latest), components(current)) {
+		(Some(latest), Some(current)) => latest > current,
+		_ => false,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+
+	#[test]
+	fn is_newer_works() {
+		assert!(is_newer("v0.3.0", "0.2.1"));
+		assert!(is_newer("v0.10.0", "0.9.0"));
+		assert!(is_newer("v1.0.0", "0.9.9-rc1"));
+		assert!(!is_newer("v0.2.1", "0.2.1"));
+		assert!(!is_newer("v0.2.0", "0.2.1"));
+		assert!(!is_newer("nightly", "0.2.1"));
+	}
+
+	#[test]
+	fn save_and_load_works() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let file = temp_dir.path().join(".pop").join(FILE);
+		assert_eq!(load(&file), None);
+		let state = State { checked_at: 1_700_000_000, latest: Some("v0.3.0".into()) };
+		save(&file, &state)?;
+		assert_eq!(load(&file), Some(state));
+		Ok(())
+	}
+}
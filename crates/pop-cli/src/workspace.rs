@@ -0,0 +1,299 @@
+// SPDX-License-Identifier: GPL-3.0
+use crate::output::OutputArgs;
+use anyhow::anyhow;
+use cliclack::{log, outro, outro_cancel};
+use serde::Serialize;
+use std::{
+	fmt,
+	path::{Path, PathBuf},
+};
+use walkdir::{DirEntry, WalkDir};
+
+/// The directories never searched for projects, as they hold build artifacts or dependencies.
+const IGNORED: [&str; 2] = ["target", "node_modules"];
+
+/// The kind of a project found within a workspace.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Kind {
+	#[cfg(feature = "parachain")]
+	Parachain,
+	#[cfg(feature = "contract")]
+	Contract,
+}
+
+impl fmt::Display for Kind {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			#[cfg(feature = "parachain")]
+			Kind::Parachain => write!(f, "parachain"),
+			#[cfg(feature = "contract")]
+			Kind::Contract => write!(f, "contract"),
+		}
+	}
+}
+
+/// A parachain or contract found within a workspace.
+#[derive(Debug, PartialEq)]
+pub(crate) struct Member {
+	/// The kind of the project.
+	pub(crate) kind: Kind,
+	/// The path of the project.
+	pub(crate) path: PathBuf,
+}
+
+/// The outcome of an operation applied to each member of a workspace, as output by `--output json`.
+#[derive(Serialize)]
+struct WorkspaceReport<'a> {
+	members: Vec<MemberReport<'a>>,
+}
+
+/// The outcome of an operation applied to a member, along with the error if it failed.
+#[derive(Serialize)]
+struct MemberReport<'a> {
+	kind: Kind,
+	path: &'a Path,
+	error: Option<String>,
+}
+
+/// The parachains and contracts found under `root`, in the order of their paths. A parachain is
+/// identified by the root of its workspace, whereas each contract is identified by its own crate.
+/// The contracts of a workspace are ordered so that each comes after the contracts it depends on,
+/// as with `pop build contract --all`.
+///
+/// # Arguments
+///
+/// * `root` - the directory searched, such as the root of a monorepo
+pub(crate) fn members(root: &Path) -> anyhow::Result<Vec<Member>> {
+	let mut members: Vec<Member> = Vec::new();
+	#[cfg(feature = "contract")]
+	let canonical_root = root.canonicalize()?;
+	let mut entries = WalkDir::new(root)
+		.sort_by_file_name()
+		.into_iter()
+		.filter_entry(|e| e.depth() == 0 || !is_ignored(e));
+	while let Some(entry) = entries.next() {
+		let entry = entry?;
+		let path = entry.path();
+		if !entry.file_type().is_dir() || !path.join("Cargo.toml").is_file() {
+			continue;
+		}
+		#[cfg(feature = "contract")]
+		if pop_contracts::is_contract(path)? {
+			if !members.iter().any(|m| m.path == path) {
+				members.push(Member { kind: Kind::Contract, path: path.to_path_buf() });
+			}
+			entries.skip_current_dir();
+			continue;
+		}
+		#[cfg(feature = "contract")]
+		if pop_common::project::is_workspace(&path.join("Cargo.toml")) {
+			for contract in pop_contracts::workspace_contracts(path)? {
+				// Displayed relative to the root, as found when searching it.
+				let contract = match contract.strip_prefix(&canonical_root) {
+					Ok(relative) => root.join(relative),
+					Err(_) => contract,
+				};
+				if !members.iter().any(|m| m.path == contract) {
+					members.push(Member { kind: Kind::Contract, path: contract });
+				}
+			}
+		}
+		// The crates of a parachain, such as its runtime, are only built or tested as part of its
+		// workspace, whereas contracts nested within it are searched for.
+		#[cfg(feature = "parachain")]
+		if pop_common::project::is_workspace(&path.join("Cargo.toml"))
+			&& pop_parachains::is_parachain(path)?
+		{
+			members.push(Member { kind: Kind::Parachain, path: path.to_path_buf() });
+		}
+	}
+	Ok(members)
+}
+
+/// Whether `entry` is a hidden directory or one holding build artifacts or dependencies.
+fn is_ignored(entry: &DirEntry) -> bool {
+	let name = entry.file_name().to_string_lossy();
+	name.starts_with('.') || IGNORED.contains(&name.as_ref())
+}
+
+/// Runs `action` on each of `members` in turn, regardless of the failure of previous ones, and
+/// reports a consolidated summary of their outcomes. Fails if any member failed, so that the
+/// command exits with a failure.
+///
+/// # Arguments
+///
+/// * `root` - the directory searched, relative to which the members are displayed
+/// * `members` - the members of the workspace
+/// * `verb` - what `action` does, e.g. `Building`
+/// * `output` - how the outcomes are output
+/// * `action` - the operation applied to each member
+pub(crate) fn run_all(
+	root: &Path,
+	members: &[Member],
+	verb: &str,
+	output: &OutputArgs,
+	mut action: impl FnMut(&Member) -> anyhow::Result<()>,
+) -> anyhow::Result<()> {
+	if members.is_empty() {
+		outro_cancel(format!("No parachain or contract was found within {}.", root.display()))?;
+		return Ok(());
+	}
+	let name = |member: &Member| {
+		let path = member.path.strip_prefix(root).unwrap_or(&member.path);
+		match path.as_os_str().is_empty() {
+			true => format!("{} (.)", member.kind),
+			false => format!("{} {}", member.kind, path.display()),
+		}
+	};
+	log::info(format!(
+		"Found {}:\n{}",
+		members.len(),
+		members.iter().map(|m| format!("  {}", name(m))).collect::<Vec<_>>().join("\n")
+	))?;
+
+	let mut outcomes = Vec::with_capacity(members.len());
+	for member in members {
+		log::step(format!("{verb} the {}...", name(member)))?;
+		outcomes.push(action(member));
+	}
+
+	for (member, outcome) in members.iter().zip(&outcomes) {
+		match outcome {
+			Ok(_) => log::success(name(member))?,
+			Err(e) => log::error(format!("{}: {e}", name(member)))?,
+		}
+	}
+	let failed: Vec<_> = members
+		.iter()
+		.zip(&outcomes)
+		.filter(|(_, outcome)| outcome.is_err())
+		.map(|(member, _)| name(member))
+		.collect();
+	output.report(&WorkspaceReport {
+		members: members
+			.iter()
+			.zip(&outcomes)
+			.map(|(member, outcome)| MemberReport {
+				kind: member.kind,
+				path: &member.path,
+				error: outcome.as_ref().err().map(|e| e.to_string()),
+			})
+			.collect(),
+	})?;
+	let summary = format!("{} succeeded, {} failed", members.len() - failed.len(), failed.len());
+	if failed.is_empty() {
+		outro(format!("✅ {summary}."))?;
+		return Ok(());
+	}
+	outro_cancel(format!("🚫 {summary}."))?;
+	Err(anyhow!("{} of {} failed: {}", failed.len(), members.len(), failed.join(", ")))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+
+	#[test]
+	#[cfg(all(feature = "parachain", feature = "contract"))]
+	fn members_works() -> Result<()> {
+		use std::fs;
+		let temp_dir = tempfile::tempdir()?;
+		let root = temp_dir.path();
+		let manifest = |path: &Path, contents: &str| -> Result<()> {
+			fs::create_dir_all(path)?;
+			fs::write(path.join("Cargo.toml"), contents)?;
+			Ok(())
+		};
+		let contract = "[package]\nname = \"flipper\"\n\n[dependencies]\nink = \"5.0.0\"\n";
+		// A parachain, whose runtime is not a member on its own, with a contract nested within it.
+		let parachain = root.join("my-parachain");
+		manifest(&parachain, "[workspace]\nmembers = [\"node\", \"runtime\"]\n")?;
+		manifest(
+			&parachain.join("node"),
+			"[package]\nname = \"node\"\n\n[dependencies]\ncumulus-client-cli = \"0.7.0\"\n",
+		)?;
+		manifest(
+			&parachain.join("runtime"),
+			"[package]\nname = \"runtime\"\n\n[dependencies]\ncumulus-pallet-parachain-system = \"0.7.0\"\n",
+		)?;
+		manifest(&parachain.join("contracts").join("flipper"), contract)?;
+		manifest(&root.join("contracts").join("erc20"), contract)?;
+		// Neither a parachain nor a contract.
+		manifest(&root.join("tools"), "[package]\nname = \"tools\"\n")?;
+		// Ignored.
+		manifest(&root.join("target").join("flipper"), contract)?;
+		manifest(&root.join(".cache").join("flipper"), contract)?;
+
+		assert_eq!(
+			members(root)?,
+			vec![
+				Member { kind: Kind::Contract, path: root.join("contracts").join("erc20") },
+				Member { kind: Kind::Parachain, path: parachain.clone() },
+				Member { kind: Kind::Contract, path: parachain.join("contracts").join("flipper") },
+			]
+		);
+		Ok(())
+	}
+
+	#[test]
+	#[cfg(feature = "contract")]
+	fn members_of_contract_workspace_are_in_dependency_order() -> Result<()> {
+		use std::fs;
+		let temp_dir = tempfile::tempdir()?;
+		let root = temp_dir.path();
+		fs::write(root.join("Cargo.toml"), "[workspace]\nmembers = [\"contracts/*\"]\n")?;
+		let contract = |name: &str, dependencies: &str| -> Result<PathBuf> {
+			let path = root.join("contracts").join(name);
+			fs::create_dir_all(&path)?;
+			fs::write(
+				path.join("Cargo.toml"),
+				format!("[package]\nname = \"{name}\"\n\n[dependencies]\nink = \"5.0.0\"\n{dependencies}"),
+			)?;
+			Ok(path)
+		};
+		// Found first when searched by file name, but depends on the token.
+		let router = contract("a_router", "token = { path = \"../token\" }\n")?;
+		let token = contract("token", "")?;
+
+		assert_eq!(
+			members(root)?,
+			vec![
+				Member { kind: Kind::Contract, path: token },
+				Member { kind: Kind::Contract, path: router },
+			]
+		);
+		Ok(())
+	}
+
+	#[test]
+	#[cfg(feature = "contract")]
+	fn run_all_fails_with_failed_members() -> Result<()> {
+		let root = Path::new("workspace");
+		let members = [
+			Member { kind: Kind::Contract, path: root.join("flipper") },
+			Member { kind: Kind::Contract, path: root.join("erc20") },
+		];
+		let mut runs = 0;
+		let output = OutputArgs::default();
+		let result = run_all(root, &members, "Building", &output, |member| {
+			runs += 1;
+			match member.path.ends_with("flipper") {
+				true => Err(anyhow!("build failed")),
+				false => Ok(()),
+			}
+		});
+		assert_eq!(runs, 2);
+		assert_eq!(result.unwrap_err().to_string(), "1 of 2 failed: contract flipper");
+		assert!(run_all(root, &members, "Building", &output, |_| Ok(())).is_ok());
+		Ok(())
+	}
+
+	#[test]
+	fn members_of_empty_directory_works() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		assert!(members(temp_dir.path())?.is_empty());
+		Ok(())
+	}
+}
@@ -0,0 +1,177 @@
+// SPDX-License-Identifier: GPL-3.0
+use duct::Expression;
+use regex::Regex;
+use std::{
+	env,
+	ffi::OsString,
+	fmt::{Display, Formatter},
+	fs,
+	path::{Path, PathBuf},
+};
+
+/// The environment variable instructing cargo to wrap each invocation of the compiler.
+const RUSTC_WRAPPER: &str = "RUSTC_WRAPPER";
+/// The binary of sccache, the compilation cache used when installed.
+const SCCACHE: &str = "sccache";
+
+/// How the compilation of the builds launched by `pop` is cached across builds and projects,
+/// beyond the incremental compilation of cargo within the target directory of each project.
+#[derive(Clone, Debug, PartialEq)]
+pub enum BuildCache {
+	/// A compiler wrapper already configured for cargo, either by the environment or by a cargo
+	/// configuration file, which cargo uses as is.
+	Configured(String),
+	/// sccache, as found on the `PATH`, wrapping each invocation of the compiler.
+	Sccache(PathBuf),
+	/// No compiler wrapper is configured or installed, or caching was disabled by setting an
+	/// empty `RUSTC_WRAPPER`.
+	Disabled,
+}
+
+impl BuildCache {
+	/// Detects how the builds of the project at `path` are cached. A compiler wrapper configured
+	/// for cargo takes precedence, otherwise sccache is used when installed.
+	///
+	/// # Arguments
+	///
+	/// * `path` - path to the project
+	pub fn detect(path: &Path) -> Self {
+		for var in [RUSTC_WRAPPER, "CARGO_BUILD_RUSTC_WRAPPER"] {
+			if let Some(wrapper) = env::var_os(var) {
+				return match wrapper.is_empty() {
+					true => BuildCache::Disabled,
+					false => BuildCache::Configured(wrapper.to_string_lossy().into()),
+				};
+			}
+		}
+		if let Some(wrapper) = configured_wrapper(path, cargo_home().as_deref()) {
+			return match wrapper.is_empty() {
+				true => BuildCache::Disabled,
+				false => BuildCache::Configured(wrapper),
+			};
+		}
+		let paths = env::var_os("PATH").unwrap_or_default();
+		match find_binary(SCCACHE, env::split_paths(&paths)) {
+			Some(sccache) => BuildCache::Sccache(sccache),
+			None => BuildCache::Disabled,
+		}
+	}
+
+	/// Whether compilation results are cached across builds.
+	pub fn is_enabled(&self) -> bool {
+		*self != BuildCache::Disabled
+	}
+
+	/// The environment variable enabling the cache for cargo, unless cargo is already configured
+	/// to use it.
+	pub fn env(&self) -> Option<(&'static str, OsString)> {
+		match self {
+			BuildCache::Sccache(sccache) => Some((RUSTC_WRAPPER, sccache.into())),
+			_ => None,
+		}
+	}
+
+	/// Enables the cache for the cargo `command`.
+	///
+	/// # Arguments
+	///
+	/// * `command` - a cargo command
+	pub fn apply(&self, command: Expression) -> Expression {
+		tracing::debug!(?command, cache = %self, "spawning build");
+		match self.env() {
+			Some((var, value)) => command.env(var, value),
+			None => command,
+		}
+	}
+}
+
+impl Display for BuildCache {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self {
+			BuildCache::Configured(wrapper) => write!(f, "{wrapper} (configured)"),
+			BuildCache::Sccache(sccache) => write!(f, "sccache ({})", sccache.display()),
+			BuildCache::Disabled => write!(f, "disabled"),
+		}
+	}
+}
+
+/// The home directory of cargo.
+fn cargo_home() -> Option<PathBuf> {
+	env::var_os("CARGO_HOME").map(PathBuf::from).or_else(|| {
+		env::var_os("HOME")
+			.or_else(|| env::var_os("USERPROFILE"))
+			.map(|home| PathBuf::from(home).join(".cargo"))
+	})
+}
+
+/// The compiler wrapper configured by the cargo configuration files applying to the project at
+/// `path`, which are searched from the project upwards and then within `cargo_home`, as cargo
+/// does.
+fn configured_wrapper(path: &Path, cargo_home: Option<&Path>) -> Option<String> {
+	let wrapper = Regex::new(r#"(?m)^\s*(?:build\.)?rustc-wrapper\s*=\s*["']([^"']*)["']"#)
+		.expect("valid regex");
+	let path = path.canonicalize().unwrap_or(path.to_path_buf());
+	path.ancestors()
+		.map(|dir| dir.join(".cargo"))
+		.chain(cargo_home.map(|home| home.to_path_buf()))
+		.flat_map(|dir| [dir.join("config.toml"), dir.join("config")])
+		.filter_map(|config| fs::read_to_string(config).ok())
+		.find_map(|config| wrapper.captures(&config).map(|c| c[1].to_string()))
+}
+
+/// The first binary named `name` within `paths`.
+fn find_binary(name: &str, paths: impl Iterator<Item = PathBuf>) -> Option<PathBuf> {
+	let name = format!("{name}{}", env::consts::EXE_SUFFIX);
+	paths.map(|dir| dir.join(&name)).find(|binary| binary.is_file())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+
+	#[test]
+	fn test_configured_wrapper() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let project = temp_dir.path().join("project");
+		fs::create_dir_all(project.join("runtime"))?;
+		fs::create_dir_all(temp_dir.path().join(".cargo"))?;
+		assert_eq!(configured_wrapper(&project, None), None);
+
+		let home = temp_dir.path().join("cargo-home");
+		fs::create_dir_all(&home)?;
+		fs::write(home.join("config.toml"), "[build]\nrustc-wrapper = \"/usr/bin/cachepot\"\n")?;
+		assert_eq!(configured_wrapper(&project, Some(&home)), Some("/usr/bin/cachepot".into()));
+
+		// The configuration closest to the project takes precedence.
+		fs::write(temp_dir.path().join(".cargo/config.toml"), "build.rustc-wrapper = ''\n")?;
+		assert_eq!(configured_wrapper(&project.join("runtime"), Some(&home)), Some("".into()));
+		Ok(())
+	}
+
+	#[test]
+	fn test_find_binary() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let (empty, bin) = (temp_dir.path().join("empty"), temp_dir.path().join("bin"));
+		fs::create_dir_all(&empty)?;
+		fs::create_dir_all(&bin)?;
+		let sccache = bin.join(format!("sccache{}", env::consts::EXE_SUFFIX));
+		assert_eq!(find_binary(SCCACHE, [empty.clone(), bin.clone()].into_iter()), None);
+		fs::write(&sccache, "")?;
+		assert_eq!(find_binary(SCCACHE, [empty, bin].into_iter()), Some(sccache));
+		Ok(())
+	}
+
+	#[test]
+	fn test_env() {
+		let sccache = PathBuf::from("/usr/bin/sccache");
+		assert_eq!(
+			BuildCache::Sccache(sccache.clone()).env(),
+			Some((RUSTC_WRAPPER, sccache.into()))
+		);
+		assert_eq!(BuildCache::Configured("sccache".into()).env(), None);
+		assert_eq!(BuildCache::Disabled.env(), None);
+		assert!(!BuildCache::Disabled.is_enabled());
+		assert_eq!(BuildCache::Configured("sccache".into()).to_string(), "sccache (configured)");
+	}
+}
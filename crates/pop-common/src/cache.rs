@@ -0,0 +1,713 @@
+// SPDX-License-Identifier: GPL-3.0
+use crate::{
+	lock::sha256,
+	sourcing::{BINARIES_DIR, LATEST, PARTIAL_EXTENSION},
+	Error, Git, GitHub, Source, Status,
+};
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use git2::Repository;
+use serde::{Deserialize, Serialize};
+use std::{
+	cmp::Reverse,
+	fs::{self, File},
+	os::unix::fs::PermissionsExt,
+	path::{Component, Path, PathBuf},
+};
+use tar::{Archive, Builder};
+use url::Url;
+use walkdir::WalkDir;
+
+/// The directory within the cache holding mirrors of the repositories of templates.
+pub const TEMPLATES_DIR: &str = "templates";
+/// The directory within the cache holding the chain specs generated by versioned binaries.
+pub const CHAIN_SPECS_DIR: &str = "chain-specs";
+/// The directory within the cache in which repositories are cloned to build binaries from source.
+const SOURCES_DIR: &str = ".src";
+/// The extension of the file recording the digest of a binary, alongside it within the cache.
+pub const DIGEST_EXTENSION: &str = "digest";
+
+/// The digest of a binary within the cache, recorded when sourced so that the binary can be
+/// verified, and sourced again when damaged.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct Digest {
+	/// The SHA-256 hash of the binary, hex encoded.
+	pub sha256: String,
+	/// The url the binary was downloaded from, if any, rather than built from source.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub url: Option<String>,
+	/// Whether the url is that of an archive containing the binary.
+	#[serde(default, skip_serializing_if = "std::ops::Not::not")]
+	pub archive: bool,
+}
+
+impl Digest {
+	/// Records the digest of the binary at `path`, sourced from `url` if downloaded.
+	///
+	/// # Arguments
+	///
+	/// * `path` - path to the binary within the cache
+	/// * `url` - the url the binary was downloaded from, if any
+	/// * `archive` - whether the url is that of an archive containing the binary
+	pub fn record(path: &Path, url: Option<&str>, archive: bool) -> Result<(), Error> {
+		let digest = Digest { sha256: sha256(path)?, url: url.map(String::from), archive };
+		let contents = toml_edit::ser::to_string(&digest)
+			.map_err(|e| Error::Config(format!("unable to record the digest: {e}")))?;
+		fs::write(Self::path(path), contents)?;
+		Ok(())
+	}
+
+	/// The digest recorded for the binary at `path`, if any.
+	///
+	/// # Arguments
+	///
+	/// * `path` - path to the binary within the cache
+	pub fn load(path: &Path) -> Result<Option<Self>, Error> {
+		let file = Self::path(path);
+		if !file.exists() {
+			return Ok(None);
+		}
+		toml_edit::de::from_str(&fs::read_to_string(&file)?)
+			.map(Some)
+			.map_err(|e| Error::Config(format!("invalid {}: {e}", file.display())))
+	}
+
+	/// The file recording the digest of the binary at `path`, e.g. `polkadot.digest`.
+	fn path(path: &Path) -> PathBuf {
+		let mut name = path.file_name().unwrap_or_default().to_os_string();
+		name.push(format!(".{DIGEST_EXTENSION}"));
+		path.with_file_name(name)
+	}
+}
+
+/// An entry within the cache, as verified by [`verify`].
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct VerifiedEntry {
+	/// The name of the binary, or the repository of the template, e.g. `r0gue-io/base-parachain`.
+	pub name: String,
+	/// The version of the binary, if a binary.
+	pub version: Option<String>,
+	/// The path of the entry within the cache.
+	pub path: PathBuf,
+	/// The integrity of the entry.
+	pub integrity: Integrity,
+}
+
+impl VerifiedEntry {
+	/// Whether the entry is damaged, and should be repaired.
+	pub fn is_damaged(&self) -> bool {
+		matches!(self.integrity, Integrity::Corrupted { .. } | Integrity::Partial)
+	}
+}
+
+/// The integrity of an entry within the cache.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case", tag = "status")]
+pub enum Integrity {
+	/// The entry matches its recorded digest, or the objects of the template are intact.
+	Intact,
+	/// No digest was recorded for the binary, such as those cached by previous versions of pop.
+	Unrecorded,
+	/// The entry differs from its recorded digest or cannot be read.
+	Corrupted {
+		/// The reason the entry is deemed corrupted.
+		reason: String,
+	},
+	/// The entry is an interrupted download.
+	Partial,
+}
+
+/// A version of a binary within the cache.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct CachedBinary {
+	/// The name of the binary.
+	pub name: String,
+	/// The version of the binary, or `latest` when built from the latest sources.
+	pub version: String,
+	/// The directory holding the version, along with anything sourced with it such as workers.
+	pub path: PathBuf,
+	/// The size of the directory, in bytes.
+	pub size: u64,
+}
+
+/// The versions of the binaries within `cache`, ordered by name and then from the newest version.
+///
+/// # Arguments
+///
+/// * `cache` - path to the local cache
+pub fn cached_binaries(cache: &Path) -> Result<Vec<CachedBinary>, Error> {
+	let dir = cache.join(BINARIES_DIR);
+	if !dir.exists() {
+		return Ok(Vec::new());
+	}
+	let mut binaries = Vec::new();
+	for name in fs::read_dir(dir)?.flatten().filter(|e| e.path().is_dir()) {
+		for version in fs::read_dir(name.path())?.flatten().filter(|e| e.path().is_dir()) {
+			binaries.push(CachedBinary {
+				name: name.file_name().to_string_lossy().to_string(),
+				version: version.file_name().to_string_lossy().to_string(),
+				path: version.path(),
+				size: size(&version.path()),
+			});
+		}
+	}
+	binaries.sort_by(|a, b| {
+		a.name.cmp(&b.name).then_with(|| {
+			(Reverse(version_key(&a.version)), &a.version)
+				.cmp(&(Reverse(version_key(&b.version)), &b.version))
+		})
+	});
+	Ok(binaries)
+}
+
+/// Removes the versions of each binary within `cache` but the `keep_latest` newest, returning
+/// those removed. Binaries built from the latest sources are kept, as they have no release to be
+/// sourced from again.
+///
+/// # Arguments
+///
+/// * `cache` - path to the local cache
+/// * `keep_latest` - the number of versions of each binary kept
+pub fn prune(cache: &Path, keep_latest: usize) -> Result<Vec<CachedBinary>, Error> {
+	let removed = prunable(cache, keep_latest)?;
+	for binary in &removed {
+		fs::remove_dir_all(&binary.path)?;
+		// Removes the directory of the binary once no version remains.
+		if let Some(dir) = binary.path.parent() {
+			if fs::read_dir(dir)?.next().is_none() {
+				fs::remove_dir(dir)?;
+			}
+		}
+	}
+	Ok(removed)
+}
+
+/// The versions of each binary within `cache` which would be removed when keeping only the
+/// `keep_latest` newest.
+///
+/// # Arguments
+///
+/// * `cache` - path to the local cache
+/// * `keep_latest` - the number of versions of each binary kept
+pub fn prunable(cache: &Path, keep_latest: usize) -> Result<Vec<CachedBinary>, Error> {
+	let mut prunable = Vec::new();
+	let (mut name, mut kept) = (String::new(), 0);
+	for binary in cached_binaries(cache)?.into_iter().filter(|b| b.version != LATEST) {
+		if binary.name != name {
+			(name, kept) = (binary.name.clone(), 0);
+		}
+		match kept < keep_latest {
+			true => kept += 1,
+			false => prunable.push(binary),
+		}
+	}
+	Ok(prunable)
+}
+
+/// The binaries cached directly within `cache` by previous versions of pop, which no longer use
+/// them, e.g. `polkadot-v1.7.0`.
+///
+/// # Arguments
+///
+/// * `cache` - path to the local cache
+pub fn legacy_binaries(cache: &Path) -> Result<Vec<PathBuf>, Error> {
+	if !cache.exists() {
+		return Ok(Vec::new());
+	}
+	let mut binaries: Vec<_> = fs::read_dir(cache)?
+		.flatten()
+		.filter(|e| {
+			e.file_type().is_ok_and(|t| t.is_symlink())
+				|| e.metadata().is_ok_and(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+		})
+		.map(|e| e.path())
+		.collect();
+	binaries.sort();
+	Ok(binaries)
+}
+
+/// The entries within `cache` sourced by pop, which are sourced again when next required: the
+/// binaries along with the chain specs they generated, the mirrors of templates, the repositories
+/// cloned to build binaries and the binaries cached by previous versions of pop. Anything else,
+/// such as the audit log or the state of nodes, is excluded.
+///
+/// # Arguments
+///
+/// * `cache` - path to the local cache
+pub fn sourced(cache: &Path) -> Result<Vec<PathBuf>, Error> {
+	let mut entries: Vec<_> = [BINARIES_DIR, CHAIN_SPECS_DIR, TEMPLATES_DIR, SOURCES_DIR]
+		.into_iter()
+		.map(|dir| cache.join(dir))
+		.filter(|dir| dir.exists())
+		.collect();
+	entries.extend(legacy_binaries(cache)?);
+	Ok(entries)
+}
+
+/// The path of the mirror of the template repository at `url` within `cache`, from which projects
+/// are generated when the repository cannot be reached, e.g. `templates/r0gue-io/base-parachain`.
+///
+/// # Arguments
+///
+/// * `cache` - path to the local cache
+/// * `url` - the url of the template repository
+pub fn template_mirror(cache: &Path, url: &Url) -> Result<PathBuf, Error> {
+	Ok(cache.join(TEMPLATES_DIR).join(GitHub::org(url)?).join(GitHub::name(url)?))
+}
+
+/// Exports the binaries and template mirrors within `cache` to `archive`, a gzipped tarball which
+/// can be imported into the cache of a machine without network access.
+///
+/// # Arguments
+///
+/// * `cache` - path to the local cache
+/// * `archive` - path of the archive created
+pub fn export(cache: &Path, archive: &Path) -> Result<(), Error> {
+	let mut builder = Builder::new(GzEncoder::new(File::create(archive)?, Compression::default()));
+	// Binaries symlinked within their version, such as those sourced with workers, are preserved.
+	builder.follow_symlinks(false);
+	for dir in [BINARIES_DIR, TEMPLATES_DIR] {
+		let dir = cache.join(dir);
+		if !dir.exists() {
+			continue;
+		}
+		for entry in WalkDir::new(&dir).follow_links(false) {
+			let entry = entry.map_err(|e| Error::AnyhowError(e.into()))?;
+			// Partial downloads are skipped.
+			if entry.path().extension().is_some_and(|e| e == PARTIAL_EXTENSION) {
+				continue;
+			}
+			let name = entry.path().strip_prefix(cache).expect("walked within cache; qed");
+			builder.append_path_with_name(entry.path(), name)?;
+		}
+	}
+	builder.into_inner()?.finish()?;
+	Ok(())
+}
+
+/// Imports the binaries and template mirrors within `archive`, as exported by [`export`], into
+/// `cache`, returning the number of entries imported. Entries outside of those directories are
+/// ignored.
+///
+/// # Arguments
+///
+/// * `cache` - path to the local cache
+/// * `archive` - path of the archive imported
+pub fn import(cache: &Path, archive: &Path) -> Result<usize, Error> {
+	fs::create_dir_all(cache)?;
+	let mut archive = Archive::new(GzDecoder::new(File::open(archive)?));
+	let mut imported = 0;
+	for entry in archive.entries()? {
+		let mut entry = entry?;
+		let path = entry.path()?.into_owned();
+		let within = path.components().next().is_some_and(|c| {
+			c == Component::Normal(BINARIES_DIR.as_ref())
+				|| c == Component::Normal(TEMPLATES_DIR.as_ref())
+		});
+		if !within {
+			continue;
+		}
+		// Entries escaping the cache are refused, rather than unpacked.
+		if entry.unpack_in(cache)? {
+			imported += 1;
+		}
+	}
+	Ok(imported)
+}
+
+/// Verifies the binaries and template mirrors within `cache`: binaries are hashed against their
+/// recorded digests, the objects of template mirrors are checked against their hashes, and
+/// interrupted downloads are flagged.
+///
+/// # Arguments
+///
+/// * `cache` - path to the local cache
+pub fn verify(cache: &Path) -> Result<Vec<VerifiedEntry>, Error> {
+	let mut entries = Vec::new();
+	for binary in cached_binaries(cache)? {
+		let entry = |path: PathBuf, integrity| VerifiedEntry {
+			name: binary.name.clone(),
+			version: Some(binary.version.clone()),
+			path,
+			integrity,
+		};
+		let mut partials: Vec<_> = fs::read_dir(&binary.path)?
+			.flatten()
+			.map(|e| e.path())
+			.filter(|p| p.extension().is_some_and(|e| e == PARTIAL_EXTENSION))
+			.collect();
+		partials.sort();
+		entries.extend(partials.into_iter().map(|p| entry(p, Integrity::Partial)));
+		let path = binary.path.join(&binary.name);
+		if !path.exists() {
+			// Only the interrupted download of the binary remains.
+			continue;
+		}
+		let integrity = match Digest::load(&path) {
+			Ok(None) => Integrity::Unrecorded,
+			Ok(Some(digest)) => match sha256(&path) {
+				Ok(hash) if hash == digest.sha256 => Integrity::Intact,
+				Ok(_) => Integrity::Corrupted { reason: "differs from its digest".into() },
+				Err(e) => Integrity::Corrupted { reason: e.to_string() },
+			},
+			Err(e) => Integrity::Corrupted { reason: e.to_string() },
+		};
+		entries.push(entry(path, integrity));
+	}
+	let templates = cache.join(TEMPLATES_DIR);
+	for org in read_dirs(&templates)? {
+		for mirror in read_dirs(&org)? {
+			let integrity = match verify_mirror(&mirror) {
+				Ok(()) => Integrity::Intact,
+				Err(e) => Integrity::Corrupted { reason: e.message().to_string() },
+			};
+			let name = mirror.strip_prefix(&templates).expect("read within templates; qed");
+			entries.push(VerifiedEntry {
+				name: name.to_string_lossy().to_string(),
+				version: None,
+				path: mirror,
+				integrity,
+			});
+		}
+	}
+	Ok(entries)
+}
+
+/// Repairs the damaged `entry` within `cache`: interrupted downloads are removed, binaries are
+/// downloaded again from the url recorded with their digest and template mirrors are mirrored
+/// again. Returns whether the entry was sourced again, as binaries built from source are only
+/// removed, being built again when next used.
+///
+/// # Arguments
+///
+/// * `cache` - path to the local cache
+/// * `entry` - the damaged entry
+/// * `status` - used to observe status updates
+pub async fn repair(
+	cache: &Path,
+	entry: &VerifiedEntry,
+	status: impl Status,
+) -> Result<bool, Error> {
+	if entry.integrity == Integrity::Partial {
+		fs::remove_file(&entry.path)?;
+		return Ok(true);
+	}
+	let Some(version) = &entry.version else {
+		// A template mirror, which is mirrored again from scratch.
+		let url = Url::parse(&format!("https://github.com/{}", entry.name))
+			.map_err(|e| Error::AnyhowError(e.into()))?;
+		fs::remove_dir_all(&entry.path)?;
+		Git::mirror(&url, &entry.path, status)?;
+		return Ok(true);
+	};
+	let digest = Digest::load(&entry.path).ok().flatten();
+	fs::remove_file(&entry.path)?;
+	let _ = fs::remove_file(Digest::path(&entry.path));
+	let Some(Digest { url: Some(url), archive, .. }) = digest else {
+		// Removes the directory of the version once nothing remains.
+		if let Some(dir) = entry.path.parent() {
+			if fs::read_dir(dir)?.next().is_none() {
+				fs::remove_dir(dir)?;
+			}
+		}
+		return Ok(false);
+	};
+	let source = match archive {
+		true => {
+			Source::Archive { version: version.clone(), url, contents: vec![entry.name.clone()] }
+		},
+		false => Source::Url { name: entry.name.clone(), version: version.clone(), url },
+	};
+	source.process(cache, status).await?;
+	Ok(true)
+}
+
+/// Checks each object of the bare repository at `path` against its hash, as objects are verified
+/// when read.
+fn verify_mirror(path: &Path) -> Result<(), git2::Error> {
+	let repo = Repository::open_bare(path)?;
+	repo.head()?.peel_to_commit()?;
+	let odb = repo.odb()?;
+	let mut corrupted = None;
+	let walked = odb.foreach(|oid| match odb.read(*oid) {
+		Ok(_) => true,
+		Err(e) => {
+			corrupted = Some(e);
+			false
+		},
+	});
+	match corrupted {
+		Some(e) => Err(e),
+		None => walked,
+	}
+}
+
+/// The directories within `path`, ordered by name.
+fn read_dirs(path: &Path) -> Result<Vec<PathBuf>, Error> {
+	if !path.exists() {
+		return Ok(Vec::new());
+	}
+	let mut dirs: Vec<_> =
+		fs::read_dir(path)?.flatten().map(|e| e.path()).filter(|p| p.is_dir()).collect();
+	dirs.sort();
+	Ok(dirs)
+}
+
+/// The numeric components of `version`, by which versions are ordered, e.g. `[1, 11, 0]` for
+/// `v1.11.0` or `[2409, 2]` for `stable2409-2`.
+fn version_key(version: &str) -> Vec<u64> {
+	version
+		.split(|c: char| !c.is_ascii_digit())
+		.filter(|c| !c.is_empty())
+		.filter_map(|c| c.parse().ok())
+		.collect()
+}
+
+/// The total size of the files within `path`, in bytes, without following symlinks.
+fn size(path: &Path) -> u64 {
+	let Ok(metadata) = fs::symlink_metadata(path) else { return 0 };
+	if !metadata.is_dir() {
+		return metadata.len();
+	}
+	fs::read_dir(path)
+		.map(|entries| entries.flatten().map(|e| size(&e.path())).sum())
+		.unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::Source;
+	use anyhow::Result;
+
+	fn cache(binaries: &[(&str, Option<&str>)]) -> Result<tempfile::TempDir> {
+		let temp_dir = tempfile::tempdir()?;
+		for (name, version) in binaries {
+			let path = Source::cached_path(temp_dir.path(), name, *version);
+			fs::create_dir_all(path.parent().unwrap())?;
+			fs::write(path, "binary")?;
+		}
+		Ok(temp_dir)
+	}
+
+	#[test]
+	fn cached_binaries_works() -> Result<()> {
+		let temp_dir = cache(&[
+			("polkadot", Some("v1.7.0")),
+			("polkadot", Some("stable2409")),
+			("polkadot", Some("v1.11.0")),
+			("parachain-node", None),
+		])?;
+		let binaries: Vec<_> = cached_binaries(temp_dir.path())?
+			.into_iter()
+			.map(|b| (b.name, b.version, b.size))
+			.collect();
+		assert_eq!(
+			binaries,
+			vec![
+				("parachain-node".into(), "latest".into(), 6),
+				("polkadot".into(), "stable2409".into(), 6),
+				("polkadot".into(), "v1.11.0".into(), 6),
+				("polkadot".into(), "v1.7.0".into(), 6),
+			]
+		);
+		assert!(cached_binaries(&temp_dir.path().join("missing"))?.is_empty());
+		Ok(())
+	}
+
+	#[test]
+	fn prune_works() -> Result<()> {
+		let temp_dir = cache(&[
+			("polkadot", Some("v1.7.0")),
+			("polkadot", Some("v1.11.0")),
+			("polkadot", Some("stable2409")),
+			("substrate-contracts-node", Some("v0.41.0")),
+			("parachain-node", None),
+		])?;
+		let cache = temp_dir.path();
+		let versions = |binaries: Vec<CachedBinary>| -> Vec<String> {
+			binaries.into_iter().map(|b| format!("{}-{}", b.name, b.version)).collect()
+		};
+		assert_eq!(versions(prunable(cache, 1)?), ["polkadot-v1.11.0", "polkadot-v1.7.0"]);
+		assert_eq!(versions(prune(cache, 2)?), ["polkadot-v1.7.0"]);
+		assert_eq!(
+			versions(prune(cache, 0)?),
+			["polkadot-stable2409", "polkadot-v1.11.0", "substrate-contracts-node-v0.41.0"]
+		);
+		// Binaries built from the latest sources are kept.
+		assert_eq!(versions(cached_binaries(cache)?), ["parachain-node-latest"]);
+		assert!(!cache.join(BINARIES_DIR).join("polkadot").exists());
+		Ok(())
+	}
+
+	#[test]
+	fn legacy_binaries_works() -> Result<()> {
+		let temp_dir = cache(&[("polkadot", Some("v1.7.0"))])?;
+		let cache = temp_dir.path();
+		fs::write(cache.join("polkadot-v1.7.0"), "")?;
+		fs::set_permissions(cache.join("polkadot-v1.7.0"), fs::Permissions::from_mode(0o755))?;
+		fs::write(cache.join("chain-spec.json"), "")?;
+		assert_eq!(legacy_binaries(cache)?, vec![cache.join("polkadot-v1.7.0")]);
+		Ok(())
+	}
+
+	#[test]
+	fn sourced_works() -> Result<()> {
+		let temp_dir = cache(&[("polkadot", Some("v1.7.0"))])?;
+		let cache = temp_dir.path();
+		fs::create_dir_all(cache.join(TEMPLATES_DIR).join("r0gue-io"))?;
+		fs::write(cache.join("polkadot-v1.6.0"), "")?;
+		fs::set_permissions(cache.join("polkadot-v1.6.0"), fs::Permissions::from_mode(0o755))?;
+		// Neither sourced nor sourced again.
+		fs::write(cache.join(crate::verification::AUDIT_FILE), "")?;
+		fs::create_dir_all(cache.join("substrate-contracts-node-v0.41.0-state"))?;
+		assert_eq!(
+			sourced(cache)?,
+			vec![
+				cache.join(BINARIES_DIR),
+				cache.join(TEMPLATES_DIR),
+				cache.join("polkadot-v1.6.0")
+			]
+		);
+		assert!(sourced(&cache.join("missing"))?.is_empty());
+		Ok(())
+	}
+
+	#[test]
+	fn template_mirror_works() -> Result<()> {
+		let url = Url::parse("https://github.com/r0gue-io/base-parachain")?;
+		assert_eq!(
+			template_mirror(Path::new("/cache"), &url)?,
+			Path::new("/cache/templates/r0gue-io/base-parachain")
+		);
+		Ok(())
+	}
+
+	#[test]
+	fn export_and_import_work() -> Result<()> {
+		let temp_dir = cache(&[("polkadot", Some("v1.13.0")), ("parachain-node", None)])?;
+		let source = temp_dir.path();
+		let binary = Source::cached_path(source, "polkadot", Some("v1.13.0"));
+		std::os::unix::fs::symlink(&binary, binary.with_file_name("polkadot-worker"))?;
+		fs::write(binary.with_file_name("polkadot-prepare-worker.part"), "")?;
+		let mirror = source.join(TEMPLATES_DIR).join("r0gue-io").join("base-parachain");
+		fs::create_dir_all(&mirror)?;
+		fs::write(mirror.join("HEAD"), "ref: refs/heads/main")?;
+		fs::write(source.join("chain-spec.json"), "")?;
+		let archive = tempfile::tempdir()?;
+		let archive = archive.path().join("cache.tar.gz");
+		export(source, &archive)?;
+
+		let target = tempfile::tempdir()?;
+		let target = target.path();
+		assert!(import(target, &archive)? > 0);
+		let imported = Source::cached_path(target, "polkadot", Some("v1.13.0"));
+		assert_eq!(fs::read_to_string(&imported)?, "binary");
+		assert!(fs::symlink_metadata(imported.with_file_name("polkadot-worker"))?
+			.file_type()
+			.is_symlink());
+		assert!(!imported.with_file_name("polkadot-prepare-worker.part").exists());
+		assert!(Source::cached_path(target, "parachain-node", None).exists());
+		assert!(target.join(TEMPLATES_DIR).join("r0gue-io/base-parachain/HEAD").exists());
+		assert!(!target.join("chain-spec.json").exists());
+		Ok(())
+	}
+
+	#[test]
+	fn digest_works() -> Result<()> {
+		let temp_dir = cache(&[("polkadot", Some("v1.13.0"))])?;
+		let binary = Source::cached_path(temp_dir.path(), "polkadot", Some("v1.13.0"));
+		assert_eq!(Digest::load(&binary)?, None);
+		Digest::record(&binary, Some("https://example.com/polkadot"), false)?;
+		assert!(binary.with_file_name("polkadot.digest").exists());
+		assert_eq!(
+			Digest::load(&binary)?,
+			Some(Digest {
+				sha256: sha256(&binary)?,
+				url: Some("https://example.com/polkadot".into()),
+				archive: false,
+			})
+		);
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn verify_and_repair_work() -> Result<()> {
+		let temp_dir = cache(&[
+			("polkadot", Some("v1.13.0")),
+			("polkadot-parachain", Some("v1.13.0")),
+			("parachain-node", None),
+			("substrate-contracts-node", Some("v0.41.0")),
+		])?;
+		let cache = temp_dir.path();
+		let path = |name: &str, version| Source::cached_path(cache, name, version);
+		Digest::record(&path("polkadot", Some("v1.13.0")), None, false)?;
+		Digest::record(&path("parachain-node", None), None, false)?;
+		fs::write(path("parachain-node", None), "tampered")?;
+		let partial = path("substrate-contracts-node", Some("v0.41.0")).with_extension("part");
+		fs::rename(path("substrate-contracts-node", Some("v0.41.0")), &partial)?;
+		let repo =
+			Repository::init_bare(cache.join(TEMPLATES_DIR).join("r0gue-io/base-parachain"))?;
+		let tree = repo.treebuilder(None)?.write()?;
+		let signature = git2::Signature::now("pop", "pop@r0gue.io")?;
+		repo.commit(Some("HEAD"), &signature, &signature, "init", &repo.find_tree(tree)?, &[])?;
+
+		let entries = verify(cache)?;
+		let integrity: Vec<_> = entries
+			.iter()
+			.map(|e| (e.name.as_str(), e.version.as_deref(), &e.integrity))
+			.collect();
+		assert_eq!(
+			integrity,
+			[
+				(
+					"parachain-node",
+					Some("latest"),
+					&Integrity::Corrupted { reason: "differs from its digest".into() }
+				),
+				("polkadot", Some("v1.13.0"), &Integrity::Intact),
+				("polkadot-parachain", Some("v1.13.0"), &Integrity::Unrecorded),
+				("substrate-contracts-node", Some("v0.41.0"), &Integrity::Partial),
+				("r0gue-io/base-parachain", None, &Integrity::Intact),
+			]
+		);
+
+		// Interrupted downloads are removed, whereas binaries built from source are removed to
+		// be built again when next used.
+		for entry in entries.iter().filter(|e| e.is_damaged()) {
+			let sourced = repair(cache, entry, ()).await?;
+			assert_eq!(sourced, entry.integrity == Integrity::Partial);
+		}
+		assert!(!partial.exists());
+		assert!(!path("parachain-node", None).exists());
+		assert!(!verify(cache)?.iter().any(|e| e.is_damaged()));
+		Ok(())
+	}
+
+	#[test]
+	fn verify_detects_corrupted_mirrors() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let mirror = temp_dir.path().join(TEMPLATES_DIR).join("r0gue-io/base-parachain");
+		let repo = Repository::init_bare(&mirror)?;
+		let blob = repo.blob(b"template")?;
+		let mut tree = repo.treebuilder(None)?;
+		tree.insert("README.md", blob, 0o100644)?;
+		let tree = repo.find_tree(tree.write()?)?;
+		let signature = git2::Signature::now("pop", "pop@r0gue.io")?;
+		repo.commit(Some("HEAD"), &signature, &signature, "init", &tree, &[])?;
+		let hex = blob.to_string();
+		let object = mirror.join("objects").join(&hex[..2]).join(&hex[2..]);
+		fs::set_permissions(&object, fs::Permissions::from_mode(0o644))?;
+		fs::write(&object, "corrupted")?;
+		let entries = verify(temp_dir.path())?;
+		assert_eq!(entries.len(), 1);
+		assert!(entries[0].is_damaged());
+		Ok(())
+	}
+
+	#[test]
+	fn version_key_works() {
+		assert_eq!(version_key("v1.11.0"), [1, 11, 0]);
+		assert_eq!(version_key("stable2409-2"), [2409, 2]);
+		assert_eq!(version_key("latest"), Vec::<u64>::new());
+	}
+}
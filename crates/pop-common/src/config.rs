@@ -0,0 +1,403 @@
+// SPDX-License-Identifier: GPL-3.0
+use crate::errors::Error;
+use std::{
+	env, fs,
+	path::{Path, PathBuf},
+};
+use toml_edit::{table, DocumentMut, Item};
+use url::Url;
+
+/// The directory within the home directory holding the configuration of pop.
+const DIR: &str = ".pop";
+/// The configuration file, within the configuration directory.
+const FILE: &str = "config.toml";
+
+/// The kind of value of a configuration key, which is validated when set.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Kind {
+	Text,
+	Flag,
+	Url,
+	Path,
+}
+
+/// The configuration keys: their names, with `*` matching a single name within a table, the
+/// kind of their values and what they configure.
+const KEYS: [(&str, Kind, &str); 10] = [
+	("provider", Kind::Text, "the template provider used by `pop new parachain`"),
+	("tags.*", Kind::Text, "the release tag of a template, e.g. `tags.standard`"),
+	("telemetry", Kind::Flag, "whether anonymous usage metrics are reported, once opted in"),
+	("proxy", Kind::Url, "the proxy used for network requests, unless set by the environment"),
+	("cache", Kind::Path, "the directory in which binaries are cached"),
+	(
+		"mirrors.*",
+		Kind::Url,
+		"a mirror of the binaries released, tried in order before GitHub, e.g. `mirrors.internal`",
+	),
+	("account", Kind::Text, "the key within the local keystore signing by default"),
+	("accounts.*", Kind::Text, "the key signing on an environment, e.g. `accounts.shibuya`"),
+	("endpoints.*", Kind::Url, "the endpoint of an environment, e.g. `endpoints.paseo`"),
+	(
+		"update_check",
+		Kind::Flag,
+		"whether pop checks daily for a newer release, enabled by default",
+	),
+];
+
+/// The configuration of the user, providing the defaults of all commands, which their flags
+/// override, e.g.:
+///
+/// ```toml
+/// provider = "pop"
+/// telemetry = false
+///
+/// [endpoints]
+/// paseo = "wss://paseo.rpc.amforc.com"
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Config {
+	document: DocumentMut,
+}
+
+impl Config {
+	/// The configuration file of the user: `~/.pop/config.toml`.
+	pub fn path() -> Result<PathBuf, Error> {
+		let home = env::var_os("HOME")
+			.or_else(|| env::var_os("USERPROFILE"))
+			.ok_or(Error::Config("the home directory could not be determined".into()))?;
+		Ok(PathBuf::from(home).join(DIR).join(FILE))
+	}
+
+	/// Loads the configuration of the user, which is empty until a value is set.
+	pub fn load() -> Result<Self, Error> {
+		Self::load_from(&Self::path()?)
+	}
+
+	/// Loads the configuration file at `path`, which is empty when the file does not exist.
+	///
+	/// # Arguments
+	///
+	/// * `path` - the configuration file
+	pub fn load_from(path: &Path) -> Result<Self, Error> {
+		if !path.exists() {
+			return Ok(Self::default());
+		}
+		let document = fs::read_to_string(path)?
+			.parse::<DocumentMut>()
+			.map_err(|e| Error::Config(format!("{} is invalid: {e}", path.display())))?;
+		Ok(Self { document })
+	}
+
+	/// Saves the configuration to the file at `path`, preserving any comments and formatting of
+	/// the values loaded.
+	///
+	/// # Arguments
+	///
+	/// * `path` - the configuration file
+	pub fn save_to(&self, path: &Path) -> Result<(), Error> {
+		if let Some(dir) = path.parent() {
+			fs::create_dir_all(dir)?;
+		}
+		fs::write(path, self.document.to_string())?;
+		Ok(())
+	}
+
+	/// Returns the value of the configuration `key`, if set.
+	///
+	/// # Arguments
+	///
+	/// * `key` - the configuration key, e.g. `endpoints.paseo`
+	pub fn get(&self, key: &str) -> Result<Option<String>, Error> {
+		let kind = Self::kind(key)?;
+		Ok(self.item(key).and_then(|item| Self::format(item, kind)))
+	}
+
+	/// Sets the configuration `key` to `value`, once validated.
+	///
+	/// # Arguments
+	///
+	/// * `key` - the configuration key, e.g. `endpoints.paseo`
+	/// * `value` - the value of the key
+	pub fn set(&mut self, key: &str, value: &str) -> Result<(), Error> {
+		let item = match Self::kind(key)? {
+			Kind::Flag => match value {
+				"true" => toml_edit::value(true),
+				"false" => toml_edit::value(false),
+				_ => return Err(Error::Config(format!("`{key}` must be `true` or `false`"))),
+			},
+			Kind::Url => {
+				Url::parse(value)
+					.map_err(|e| Error::Config(format!("`{key}` must be a url: {e}")))?;
+				toml_edit::value(value)
+			},
+			Kind::Text | Kind::Path => toml_edit::value(value),
+		};
+		match key.split_once('.') {
+			Some((name, entry)) => {
+				let entries = self.document.entry(name).or_insert(table());
+				let entries = entries.as_table_like_mut().ok_or_else(|| {
+					Error::Config(format!("`{name}` must be a table within the configuration file"))
+				})?;
+				entries.insert(entry, item);
+			},
+			None => {
+				self.document.insert(key, item);
+			},
+		}
+		Ok(())
+	}
+
+	/// Removes the configuration `key`, returning whether it was set.
+	///
+	/// # Arguments
+	///
+	/// * `key` - the configuration key, e.g. `endpoints.paseo`
+	pub fn unset(&mut self, key: &str) -> Result<bool, Error> {
+		Self::kind(key)?;
+		let removed = match key.split_once('.') {
+			Some((name, entry)) => self
+				.document
+				.get_mut(name)
+				.and_then(Item::as_table_like_mut)
+				.and_then(|table| table.remove(entry)),
+			None => self.document.remove(key),
+		};
+		Ok(removed.is_some())
+	}
+
+	/// Returns every configuration key which is set, along with its value.
+	pub fn entries(&self) -> Vec<(String, String)> {
+		let mut entries = Vec::new();
+		for (name, item) in self.document.iter() {
+			match item.as_table_like() {
+				Some(table) => {
+					for (entry, item) in table.iter() {
+						let key = format!("{name}.{entry}");
+						if let Some(value) =
+							Self::kind(&key).ok().and_then(|k| Self::format(item, k))
+						{
+							entries.push((key, value));
+						}
+					}
+				},
+				None => {
+					if let Some(value) =
+						Self::kind(name).ok().and_then(|kind| Self::format(item, kind))
+					{
+						entries.push((name.to_string(), value));
+					}
+				},
+			}
+		}
+		entries
+	}
+
+	/// The configuration keys, along with what they configure.
+	pub fn keys() -> impl Iterator<Item = (&'static str, &'static str)> {
+		KEYS.iter().map(|(key, _, description)| (*key, *description))
+	}
+
+	/// The template provider used by default.
+	pub fn provider(&self) -> Option<String> {
+		self.text("provider")
+	}
+
+	/// The release tag used for the template named `template`, rather than the latest release.
+	///
+	/// # Arguments
+	///
+	/// * `template` - the name of the template
+	pub fn release_tag(&self, template: &str) -> Option<String> {
+		self.text(&format!("tags.{template}"))
+	}
+
+	/// Whether anonymous usage metrics are reported, if configured.
+	pub fn telemetry(&self) -> Option<bool> {
+		self.item("telemetry").and_then(Item::as_bool)
+	}
+
+	/// Whether pop checks for a newer release, if configured.
+	pub fn update_check(&self) -> Option<bool> {
+		self.item("update_check").and_then(Item::as_bool)
+	}
+
+	/// The proxy used for network requests.
+	pub fn proxy(&self) -> Option<Url> {
+		self.text("proxy").and_then(|proxy| Url::parse(&proxy).ok())
+	}
+
+	/// The directory in which binaries are cached.
+	pub fn cache(&self) -> Option<PathBuf> {
+		self.text("cache").map(PathBuf::from)
+	}
+
+	/// The mirrors of the binaries released, in the order they are tried before the canonical
+	/// url of a release.
+	pub fn mirrors(&self) -> Vec<Url> {
+		self.item("mirrors")
+			.and_then(Item::as_table_like)
+			.map(|table| table.iter().filter_map(|(_, url)| url.as_str()?.parse().ok()).collect())
+			.unwrap_or_default()
+	}
+
+	/// The name of the key within the local keystore signing on the environment named
+	/// `environment`, falling back to the key signing by default.
+	///
+	/// # Arguments
+	///
+	/// * `environment` - the name of the environment, e.g. `paseo`
+	pub fn account(&self, environment: &str) -> Option<String> {
+		self.text(&format!("accounts.{environment}")).or_else(|| self.text("account"))
+	}
+
+	/// The endpoint of the environment named `environment`.
+	///
+	/// # Arguments
+	///
+	/// * `environment` - the name of the environment, e.g. `paseo`
+	pub fn endpoint(&self, environment: &str) -> Option<Url> {
+		self.text(&format!("endpoints.{environment}"))
+			.and_then(|url| Url::parse(&url).ok())
+	}
+
+	/// The names of the environments with an endpoint configured.
+	pub fn endpoints(&self) -> Vec<String> {
+		self.item("endpoints")
+			.and_then(Item::as_table_like)
+			.map(|table| table.iter().map(|(name, _)| name.to_string()).collect())
+			.unwrap_or_default()
+	}
+
+	/// The kind of value of the configuration `key`, failing when the key is unknown.
+	fn kind(key: &str) -> Result<Kind, Error> {
+		let pattern = match key.split_once('.') {
+			Some((name, entry)) if !entry.is_empty() && !entry.contains('.') => {
+				format!("{name}.*")
+			},
+			Some(_) => String::new(),
+			None => key.to_string(),
+		};
+		KEYS.iter()
+			.find(|(k, ..)| *k == pattern)
+			.map(|(_, kind, _)| *kind)
+			.ok_or_else(|| {
+				Error::Config(format!(
+					"unknown configuration key `{key}`, expected one of: {}",
+					KEYS.iter().map(|(k, ..)| *k).collect::<Vec<_>>().join(", ")
+				))
+			})
+	}
+
+	/// The item of the configuration `key`, if set.
+	fn item(&self, key: &str) -> Option<&Item> {
+		match key.split_once('.') {
+			Some((name, entry)) => self.document.get(name)?.as_table_like()?.get(entry),
+			None => self.document.get(key),
+		}
+	}
+
+	/// The text value of the configuration `key`, if set.
+	fn text(&self, key: &str) -> Option<String> {
+		self.item(key).and_then(Item::as_str).map(|value| value.to_string())
+	}
+
+	/// Formats the value of `item`, if of the expected `kind`.
+	fn format(item: &Item, kind: Kind) -> Option<String> {
+		match kind {
+			Kind::Flag => item.as_bool().map(|flag| flag.to_string()),
+			_ => item.as_str().map(|value| value.to_string()),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+
+	#[test]
+	fn test_set_and_get() -> Result<()> {
+		let mut config = Config::default();
+		assert_eq!(config.get("provider")?, None);
+		config.set("provider", "parity")?;
+		config.set("telemetry", "false")?;
+		config.set("endpoints.paseo", "wss://paseo.example.com")?;
+		config.set("tags.standard", "v1.0.0")?;
+		assert_eq!(config.get("provider")?.as_deref(), Some("parity"));
+		assert_eq!(config.get("telemetry")?.as_deref(), Some("false"));
+		assert_eq!(config.provider().as_deref(), Some("parity"));
+		assert_eq!(config.release_tag("standard").as_deref(), Some("v1.0.0"));
+		assert_eq!(config.telemetry(), Some(false));
+		assert_eq!(config.endpoint("paseo"), Some(Url::parse("wss://paseo.example.com")?));
+		assert_eq!(config.endpoints(), vec!["paseo".to_string()]);
+		assert_eq!(
+			config.entries(),
+			vec![
+				("provider".to_string(), "parity".to_string()),
+				("telemetry".to_string(), "false".to_string()),
+				("endpoints.paseo".to_string(), "wss://paseo.example.com".to_string()),
+				("tags.standard".to_string(), "v1.0.0".to_string()),
+			]
+		);
+		config.set("mirrors.internal", "https://artifacts.example.com/github")?;
+		config.set("mirrors.fallback", "https://mirror.example.com")?;
+		assert_eq!(
+			config.mirrors(),
+			vec![
+				Url::parse("https://artifacts.example.com/github")?,
+				Url::parse("https://mirror.example.com")?
+			]
+		);
+		assert!(config.unset("mirrors.internal")?);
+		assert!(config.unset("mirrors.fallback")?);
+		assert!(config.unset("endpoints.paseo")?);
+		assert!(!config.unset("endpoints.paseo")?);
+		assert_eq!(config.endpoint("paseo"), None);
+		assert_eq!(config.update_check(), None);
+		config.set("update_check", "false")?;
+		assert_eq!(config.update_check(), Some(false));
+		Ok(())
+	}
+
+	#[test]
+	fn test_invalid_keys_and_values() {
+		let mut config = Config::default();
+		for key in ["unknown", "endpoints", "endpoints.", "endpoints.paseo.url", "provider.pop"] {
+			assert!(matches!(config.get(key), Err(Error::Config(_))), "{key}");
+		}
+		assert!(matches!(config.set("telemetry", "maybe"), Err(Error::Config(_))));
+		assert!(matches!(config.set("proxy", "not a url"), Err(Error::Config(_))));
+		assert!(config.entries().is_empty());
+	}
+
+	#[test]
+	fn test_account() -> Result<()> {
+		let mut config = Config::default();
+		assert_eq!(config.account("shibuya"), None);
+		config.set("account", "alice")?;
+		config.set("accounts.shibuya", "deployer")?;
+		assert_eq!(config.account("shibuya").as_deref(), Some("deployer"));
+		assert_eq!(config.account("paseo").as_deref(), Some("alice"));
+		Ok(())
+	}
+
+	#[test]
+	fn test_save_and_load() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let path = temp_dir.path().join(DIR).join(FILE);
+		assert!(Config::load_from(&path)?.entries().is_empty());
+		fs::create_dir_all(path.parent().unwrap())?;
+		fs::write(&path, "# Set by hand.\ncache = \"/tmp/pop\"\n")?;
+		let mut config = Config::load_from(&path)?;
+		assert_eq!(config.cache(), Some(PathBuf::from("/tmp/pop")));
+		config.set("accounts.local", "bob")?;
+		config.save_to(&path)?;
+		assert!(fs::read_to_string(&path)?.starts_with("# Set by hand.\n"));
+		let config = Config::load_from(&path)?;
+		assert_eq!(config.cache(), Some(PathBuf::from("/tmp/pop")));
+		assert_eq!(config.account("local").as_deref(), Some("bob"));
+		fs::write(&path, "cache = ")?;
+		assert!(matches!(Config::load_from(&path), Err(Error::Config(_))));
+		Ok(())
+	}
+}
@@ -0,0 +1,44 @@
+// SPDX-License-Identifier: GPL-3.0
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum Error {
+	#[error("a git error occurred: {0}")]
+	Git(String),
+
+	#[error("the release `{0}` could not be found")]
+	ReleaseNotFound(String),
+
+	#[error("IO error: {0}")]
+	IO(#[from] std::io::Error),
+
+	#[error("HTTP error: {0}")]
+	HttpError(#[from] reqwest::Error),
+
+	#[error("Configuration error: {0}")]
+	Config(String),
+
+	#[error("ParseError error: {0}")]
+	ParseError(#[from] url::ParseError),
+
+	#[error("Anyhow error: {0}")]
+	AnyhowError(#[from] anyhow::Error),
+
+	#[error("Keystore error: {0}")]
+	Keystore(String),
+
+	#[error("Missing binary: {0}")]
+	MissingBinary(String),
+
+	#[error("Unsupported platform: {arch} {os}")]
+	UnsupportedPlatform { os: String, arch: String },
+
+	#[error(
+		"`{name}` {version} differs from the binary locked for {platform}, update the lockfile \
+		 with `pop update lock` if intended"
+	)]
+	LockMismatch { name: String, version: String, platform: String },
+
+	#[error("`{artifact}` could not be verified: {reason}")]
+	Unverified { artifact: String, reason: String },
+}
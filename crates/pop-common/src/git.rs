@@ -1,5 +1,9 @@
 // SPDX-License-Identifier: GPL-3.0
-use crate::errors::Error;
+use crate::{
+	errors::Error,
+	progress::{Progress, Throttle, Unit},
+	sourcing::Status,
+};
 use anyhow::Result;
 use git2::{
 	build::RepoBuilder, FetchOptions, IndexAddOption, RemoteCallbacks, Repository, ResetType,
@@ -8,31 +12,132 @@ use git2_credentials::CredentialHandler;
 use regex::Regex;
 use std::path::Path;
 use std::{env, fs};
+use tracing::{debug, warn};
 use url::Url;
 
 pub struct Git;
 impl Git {
-	pub(crate) fn clone(url: &Url, working_dir: &Path, branch: Option<&str>) -> Result<()> {
+	/// Clone `url` into `working_dir`, optionally checking out `branch`, reporting the progress
+	/// to `status`.
+	pub fn clone(
+		url: &Url,
+		working_dir: &Path,
+		branch: Option<&str>,
+		status: impl Status,
+	) -> Result<()> {
 		if !working_dir.exists() {
 			let mut fo = FetchOptions::new();
 			fo.depth(1);
+			fo.remote_callbacks(Self::progress_callbacks(url.as_str(), status));
 			let mut repo = RepoBuilder::new();
 			repo.fetch_options(fo);
 			if let Some(branch) = branch {
 				repo.branch(branch);
 			}
-			if let Err(_e) = repo.clone(url.as_str(), working_dir) {
-				Self::ssh_clone(url, working_dir, branch)?;
+			debug!(%url, ?branch, path = %working_dir.display(), "cloning repository");
+			if let Err(e) = repo.clone(url.as_str(), working_dir) {
+				warn!(%url, error = %e, "clone failed, retrying over ssh");
+				Self::ssh_clone(url, working_dir, branch, status)?;
 			}
 		}
 		Ok(())
 	}
-	pub(crate) fn ssh_clone(url: &Url, working_dir: &Path, branch: Option<&str>) -> Result<()> {
+
+	/// Clone the commit tagged `tag` of `url` into `working_dir`, fetching only that commit, as
+	/// [`Git::clone`] only checks out branches. Falls back to checking out a branch named `tag`
+	/// when no such tag exists, reporting the progress to `status`.
+	///
+	/// # Arguments
+	///
+	/// * `url` - the url of the repository
+	/// * `working_dir` - the directory cloned into
+	/// * `tag` - the tag, or branch, checked out
+	/// * `status` - used to observe status updates
+	pub fn clone_tag(url: &Url, working_dir: &Path, tag: &str, status: impl Status) -> Result<()> {
+		if working_dir.exists() {
+			return Ok(());
+		}
+		debug!(%url, tag, path = %working_dir.display(), "cloning tag of repository");
+		let fetched = (|| -> Result<()> {
+			let repo = Repository::init(working_dir)?;
+			let mut fo = FetchOptions::new();
+			fo.depth(1);
+			fo.remote_callbacks(Self::progress_callbacks(url.as_str(), status));
+			let reference = format!("refs/tags/{tag}");
+			repo.remote_anonymous(url.as_str())?.fetch(
+				&[format!("+{reference}:{reference}")],
+				Some(&mut fo),
+				None,
+			)?;
+			let commit = repo.revparse_single(&reference)?.peel_to_commit()?;
+			repo.checkout_tree(
+				commit.as_object(),
+				Some(git2::build::CheckoutBuilder::new().force()),
+			)?;
+			repo.set_head_detached(commit.id())?;
+			Ok(())
+		})();
+		if let Err(e) = fetched {
+			debug!(%url, tag, error = %e, "tag not fetched, cloning as a branch");
+			if working_dir.exists() {
+				fs::remove_dir_all(working_dir)?;
+			}
+			Self::clone(url, working_dir, Some(tag), status)?;
+		}
+		Ok(())
+	}
+
+	/// Mirrors the repository at `url` within `path`, as a bare repository holding all of its
+	/// branches and tags, from which projects can be generated when the repository cannot be
+	/// reached. An existing mirror is updated.
+	///
+	/// # Arguments
+	///
+	/// * `url` - the url of the repository
+	/// * `path` - the directory of the mirror
+	/// * `status` - used to observe status updates
+	pub fn mirror(url: &Url, path: &Path, status: impl Status) -> Result<()> {
+		debug!(%url, path = %path.display(), "mirroring repository");
+		let repo = match Repository::open_bare(path) {
+			Ok(repo) => repo,
+			Err(_) => {
+				if path.exists() {
+					fs::remove_dir_all(path)?;
+				}
+				Repository::init_bare(path)?
+			},
+		};
+		let mut fo = FetchOptions::new();
+		fo.remote_callbacks(Self::progress_callbacks(url.as_str(), status));
+		fo.download_tags(git2::AutotagOption::All);
+		let mut remote = repo.remote_anonymous(url.as_str())?;
+		remote.fetch(
+			&["+refs/heads/*:refs/heads/*", "+refs/tags/*:refs/tags/*"],
+			Some(&mut fo),
+			None,
+		)?;
+		// Clones of the mirror check out the default branch of the repository.
+		if let Some(branch) =
+			remote.default_branch().ok().and_then(|b| b.as_str().map(String::from))
+		{
+			repo.set_head(&branch)?;
+		}
+		Ok(())
+	}
+
+	/// Clone `url` into `working_dir` using ssh, for users that have ssh configured.
+	pub fn ssh_clone(
+		url: &Url,
+		working_dir: &Path,
+		branch: Option<&str>,
+		status: impl Status,
+	) -> Result<()> {
 		let ssh_url = GitHub::convert_to_ssh_url(url);
 		if !working_dir.exists() {
+			debug!(url = ssh_url, ?branch, path = %working_dir.display(), "cloning repository over ssh");
 			// Prepare callback and fetch options.
 			let mut fo = FetchOptions::new();
-			Self::set_up_ssh_fetch_options(&mut fo)?;
+			Self::set_up_ssh_fetch_options(&mut fo, &ssh_url, status)?;
 			// Prepare builder and clone.
 			let mut repo = RepoBuilder::new();
 			repo.fetch_options(fo);
@@ -43,30 +148,39 @@ impl Git {
 		}
 		Ok(())
 	}
-	/// Clone `url` into `target` and degit it
+	/// Clone `url` into `target` and degit it, reporting the progress of the clone to `status`.
 	pub fn clone_and_degit(
 		url: &str,
 		target: &Path,
 		tag_version: Option<String>,
+		status: impl Status,
 	) -> Result<Option<String>> {
-		let repo = match Repository::clone(url, target) {
+		debug!(url, ?tag_version, path = %target.display(), "cloning repository");
+		let mut fo = FetchOptions::new();
+		fo.remote_callbacks(Self::progress_callbacks(url, status));
+		let repo = match RepoBuilder::new().fetch_options(fo).clone(url, target) {
 			Ok(repo) => repo,
-			Err(_e) => Self::ssh_clone_and_degit(
-				url::Url::parse(url).map_err(|err| Error::from(err))?,
-				target,
-			)?,
+			Err(e) => {
+				warn!(url, error = %e, "clone failed, retrying over ssh");
+				Self::ssh_clone_and_degit(
+					url::Url::parse(url).map_err(|err| Error::from(err))?,
+					target,
+					status,
+				)?
+			},
 		};
 
 		if let Some(tag_version) = tag_version {
-			let (object, reference) = repo.revparse_ext(&tag_version).expect("Object not found");
-			repo.checkout_tree(&object, None).expect("Failed to checkout");
-			match reference {
+			let (object, reference) = repo
+				.revparse_ext(&tag_version)
+				.map_err(|_| Error::ReleaseNotFound(tag_version.clone()))?;
+			repo.checkout_tree(&object, None)?;
+			match reference.as_ref().and_then(|gref| gref.name()) {
 				// gref is an actual reference like branches or tags
-				Some(gref) => repo.set_head(gref.name().unwrap()),
+				Some(name) => repo.set_head(name),
 				// this is a commit, not a reference
 				None => repo.set_head_detached(object.id()),
-			}
-			.expect("Failed to set HEAD");
+			}?;
 
 			let git_dir = repo.path();
 			fs::remove_dir_all(&git_dir)?;
@@ -75,6 +189,7 @@ impl Git {
 
 		// fetch tags from remote
 		let release = Self::fetch_latest_tag(&repo);
+		debug!(?release, "latest release tag");
 
 		let git_dir = repo.path();
 		fs::remove_dir_all(&git_dir)?;
@@ -83,11 +198,12 @@ impl Git {
 	}
 
 	/// For users that have ssh configuration for cloning repositories
-	fn ssh_clone_and_degit(url: Url, target: &Path) -> Result<Repository> {
+	fn ssh_clone_and_degit(url: Url, target: &Path, status: impl Status) -> Result<Repository> {
 		let ssh_url = GitHub::convert_to_ssh_url(&url);
+		debug!(url = ssh_url, path = %target.display(), "cloning repository over ssh");
 		// Prepare callback and fetch options.
 		let mut fo = FetchOptions::new();
-		Self::set_up_ssh_fetch_options(&mut fo)?;
+		Self::set_up_ssh_fetch_options(&mut fo, &ssh_url, status)?;
 		// Prepare builder and clone.
 		let mut builder = RepoBuilder::new();
 		builder.fetch_options(fo);
@@ -95,8 +211,12 @@ impl Git {
 		Ok(repo)
 	}
 
-	fn set_up_ssh_fetch_options(fo: &mut FetchOptions) -> Result<()> {
-		let mut callbacks = RemoteCallbacks::new();
+	fn set_up_ssh_fetch_options<'a, S: Status + 'a>(
+		fo: &mut FetchOptions<'a>,
+		url: &str,
+		status: S,
+	) -> Result<()> {
+		let mut callbacks = Self::progress_callbacks(url, status);
 		let git_config = git2::Config::open_default()
 			.map_err(|e| Error::Config(format!("Cannot open git configuration: {}", e)))?;
 		let mut ch = CredentialHandler::new(git_config);
@@ -108,6 +228,26 @@ impl Git {
 		Ok(())
 	}
 
+	/// Callbacks reporting the objects received when cloning `url` to `status`.
+	fn progress_callbacks<'a, S: Status + 'a>(url: &str, status: S) -> RemoteCallbacks<'a> {
+		let step = format!("Cloning {url}");
+		let mut throttle = Throttle::default();
+		let mut callbacks = RemoteCallbacks::new();
+		callbacks.transfer_progress(move |stats| {
+			let progress = Progress {
+				step: &step,
+				done: stats.received_objects() as u64,
+				total: Some(stats.total_objects() as u64),
+				unit: Unit::Objects,
+			};
+			if throttle.ready(&progress, 1) {
+				status.progress(progress);
+			}
+			true
+		});
+		callbacks
+	}
+
 	/// Fetch the latest release from a repository
 	fn fetch_latest_tag(repo: &Repository) -> Option<String> {
 		let version_reg = Regex::new(r"v\d+\.\d+\.\d+").expect("Valid regex");
@@ -150,14 +290,14 @@ impl GitHub {
 			concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
 
 		let client = reqwest::ClientBuilder::new().user_agent(APP_USER_AGENT).build()?;
-		let response = client
-			.get(format!(
-				"https://api.github.com/repos/{}/{}/releases",
-				Self::org(repo)?,
-				Self::name(repo)?
-			))
-			.send()
-			.await?;
+		let url = format!(
+			"https://api.github.com/repos/{}/{}/releases",
+			Self::org(repo)?,
+			Self::name(repo)?
+		);
+		debug!(url, "fetching releases");
+		let response = client.get(url).send().await?;
+		debug!(status = %response.status(), "fetched releases");
 		Ok(response.json::<Vec<Release>>().await?)
 	}
 
@@ -175,15 +315,14 @@ impl GitHub {
 			.collect();
 		// Additional lookup for commit sha
 		for release in releases.iter_mut() {
-			let response = client
-				.get(format!(
-					"https://api.github.com/repos/{}/{}/git/ref/tags/{}",
-					Self::org(repo)?,
-					Self::name(repo)?,
-					&release.tag_name
-				))
-				.send()
-				.await?;
+			let url = format!(
+				"https://api.github.com/repos/{}/{}/git/ref/tags/{}",
+				Self::org(repo)?,
+				Self::name(repo)?,
+				&release.tag_name
+			);
+			debug!(url, "fetching release tag");
+			let response = client.get(url).send().await?;
 			let value = response.json::<serde_json::Value>().await?;
 			let commit = value
 				.get("object")
@@ -196,7 +335,7 @@ impl GitHub {
 		Ok(releases)
 	}
 
-	fn org(repo: &Url) -> Result<&str> {
+	pub(crate) fn org(repo: &Url) -> Result<&str> {
 		let path_segments = repo
 			.path_segments()
 			.map(|c| c.collect::<Vec<_>>())
@@ -206,7 +345,8 @@ impl GitHub {
 		))?)
 	}
 
-	pub(crate) fn name(repo: &Url) -> Result<&str> {
+	/// The name of the repository.
+	pub fn name(repo: &Url) -> Result<&str> {
 		let path_segments = repo
 			.path_segments()
 			.map(|c| c.collect::<Vec<_>>())
@@ -216,7 +356,8 @@ impl GitHub {
 			.ok_or(Error::Git("the repository name is missing from the github url".to_string()))?)
 	}
 
-	pub(crate) fn release(repo: &Url, tag: &str, artifact: &str) -> String {
+	/// The download url of a release `artifact` for the provided `tag`.
+	pub fn release(repo: &Url, tag: &str, artifact: &str) -> String {
 		format!("{}/releases/download/{tag}/{artifact}", repo.as_str())
 	}
 	pub(crate) fn convert_to_ssh_url(url: &Url) -> String {
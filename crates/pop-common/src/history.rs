@@ -0,0 +1,131 @@
+// SPDX-License-Identifier: GPL-3.0
+use crate::Error;
+use chrono::{SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+use std::{
+	fs::{self, OpenOptions},
+	io::Write,
+	path::{Path, PathBuf},
+};
+
+/// The file of a project in which the operations applied to it are recorded, one JSON object per
+/// line.
+pub const HISTORY_FILE: &str = ".pop/history.jsonl";
+
+/// An operation applied to a project, such as its generation or the deployment of a contract,
+/// recorded so that teams can audit what was scaffolded or deployed.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Operation {
+	/// When the operation completed, in RFC 3339 format (UTC).
+	pub timestamp: String,
+	/// The command applying the operation, e.g. `up contract`.
+	pub command: String,
+	/// The arguments provided to the command, with any secret redacted.
+	pub args: Vec<String>,
+	/// The artifacts resulting from the operation, such as the paths generated or the address of
+	/// a contract deployed.
+	pub artifacts: Vec<String>,
+}
+
+impl Operation {
+	/// An operation completed now.
+	///
+	/// # Arguments
+	///
+	/// * `command` - the command applying the operation
+	/// * `args` - the arguments provided to the command, with any secret already redacted
+	/// * `artifacts` - the artifacts resulting from the operation
+	pub fn new(command: impl Into<String>, args: Vec<String>, artifacts: Vec<String>) -> Self {
+		Self {
+			timestamp: Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true),
+			command: command.into(),
+			args,
+			artifacts,
+		}
+	}
+
+	/// Appends the operation to the history of the project at `path`.
+	///
+	/// # Arguments
+	///
+	/// * `path` - path to the project
+	pub fn record(&self, path: &Path) -> Result<(), Error> {
+		let file = history_file(path);
+		if let Some(dir) = file.parent() {
+			fs::create_dir_all(dir)?;
+		}
+		let line = serde_json::to_string(self)
+			.map_err(|e| Error::Config(format!("unable to record the operation: {e}")))?;
+		let mut file = OpenOptions::new().create(true).append(true).open(file)?;
+		writeln!(file, "{line}")?;
+		Ok(())
+	}
+
+	/// The operations recorded within the history of the project at `path`, from the oldest.
+	///
+	/// # Arguments
+	///
+	/// * `path` - path to the project
+	pub fn history(path: &Path) -> Result<Vec<Self>, Error> {
+		let file = history_file(path);
+		if !file.exists() {
+			return Ok(Vec::new());
+		}
+		fs::read_to_string(&file)?
+			.lines()
+			.filter(|line| !line.trim().is_empty())
+			.enumerate()
+			.map(|(i, line)| {
+				serde_json::from_str(line).map_err(|e| {
+					Error::Config(format!("invalid {} at line {}: {e}", file.display(), i + 1))
+				})
+			})
+			.collect()
+	}
+}
+
+/// The file holding the history of the project at `path`.
+fn history_file(path: &Path) -> PathBuf {
+	path.join(HISTORY_FILE)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+
+	#[test]
+	fn record_and_history_works() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		assert!(Operation::history(temp_dir.path())?.is_empty());
+		let generation = Operation::new(
+			"new parachain",
+			vec!["my-parachain".into()],
+			vec!["my-parachain".into()],
+		);
+		let deployment = Operation::new(
+			"up contract",
+			vec!["--suri".into(), "<redacted>".into()],
+			vec!["5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY".into()],
+		);
+		generation.record(temp_dir.path())?;
+		deployment.record(temp_dir.path())?;
+		assert_eq!(Operation::history(temp_dir.path())?, vec![generation, deployment]);
+		Ok(())
+	}
+
+	#[test]
+	fn history_fails_on_invalid_line() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		fs::create_dir(temp_dir.path().join(".pop"))?;
+		fs::write(temp_dir.path().join(HISTORY_FILE), "not json\n")?;
+		assert!(matches!(Operation::history(temp_dir.path()), Err(Error::Config(_))));
+		Ok(())
+	}
+
+	#[test]
+	fn timestamp_is_rfc3339() {
+		let operation = Operation::new("new pallet", Vec::new(), Vec::new());
+		assert!(chrono::DateTime::parse_from_rfc3339(&operation.timestamp).is_ok());
+	}
+}
@@ -0,0 +1,460 @@
+// SPDX-License-Identifier: GPL-3.0
+use crate::errors::Error;
+use aes_gcm::{
+	aead::{Aead, KeyInit},
+	Aes256Gcm, Nonce,
+};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use sp_core::{
+	crypto::{AccountId32, SecretStringError, Ss58Codec},
+	ecdsa, ed25519, sr25519, Pair,
+};
+use std::{
+	fs::{self, OpenOptions},
+	io::Write,
+	os::unix::fs::OpenOptionsExt,
+	path::PathBuf,
+};
+use strum_macros::{AsRefStr, Display, EnumString, VariantArray};
+
+/// The number of PBKDF2 rounds deriving the encryption key of a key from the password.
+#[cfg(not(test))]
+const ROUNDS: u32 = 600_000;
+#[cfg(test)]
+const ROUNDS: u32 = 1_000;
+
+/// The signature scheme of a key.
+#[derive(
+	AsRefStr,
+	Clone,
+	Copy,
+	Debug,
+	Default,
+	Deserialize,
+	Display,
+	EnumString,
+	Eq,
+	PartialEq,
+	Serialize,
+	VariantArray,
+)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum Scheme {
+	/// Schnorr signatures over Ristretto, used by most accounts.
+	#[default]
+	Sr25519,
+	/// Ed25519 signatures, as used by the keys of some consensus engines (e.g. GRANDPA).
+	Ed25519,
+	/// ECDSA signatures over secp256k1, as used by bridges to Ethereum.
+	Ecdsa,
+}
+
+impl Scheme {
+	/// Generates a new key, returning its mnemonic phrase.
+	pub fn generate(&self) -> String {
+		match self {
+			Scheme::Sr25519 => sr25519::Pair::generate_with_phrase(None).1,
+			Scheme::Ed25519 => ed25519::Pair::generate_with_phrase(None).1,
+			Scheme::Ecdsa => ecdsa::Pair::generate_with_phrase(None).1,
+		}
+	}
+
+	/// The SS58 address of the account of a key, which also validates its secret key URI.
+	///
+	/// # Arguments
+	///
+	/// * `suri` - the secret key URI of the key, e.g. a mnemonic phrase followed by a derivation
+	///   path
+	pub fn address(&self, suri: &str) -> Result<String, Error> {
+		let invalid =
+			|e: SecretStringError| Error::Keystore(format!("invalid secret key URI: {e:?}"));
+		Ok(match self {
+			Scheme::Sr25519 => {
+				sr25519::Pair::from_string(suri, None).map_err(invalid)?.public().to_ss58check()
+			},
+			Scheme::Ed25519 => {
+				ed25519::Pair::from_string(suri, None).map_err(invalid)?.public().to_ss58check()
+			},
+			// The account of an ECDSA key is the hash of its compressed public key.
+			Scheme::Ecdsa => {
+				let public = ecdsa::Pair::from_string(suri, None).map_err(invalid)?.public();
+				AccountId32::from(sp_core::blake2_256(public.as_ref())).to_ss58check()
+			},
+		})
+	}
+}
+
+/// A key held within the keystore.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Key {
+	/// The name of the key.
+	pub name: String,
+	/// The signature scheme of the key.
+	pub scheme: Scheme,
+	/// The SS58 address of the account of the key.
+	pub address: String,
+	/// The secret key URI of the key.
+	pub suri: String,
+}
+
+/// The public details of a key held within the keystore, available without its password.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct KeyInfo {
+	/// The name of the key.
+	pub name: String,
+	/// The signature scheme of the key.
+	pub scheme: Scheme,
+	/// The SS58 address of the account of the key.
+	pub address: String,
+	/// Whether the secret of the key is encrypted, and so requires the password of the keystore.
+	pub encrypted: bool,
+}
+
+/// A key as stored within its file.
+#[derive(Deserialize, Serialize)]
+struct StoredKey {
+	name: String,
+	#[serde(default)]
+	scheme: Scheme,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	address: Option<String>,
+	/// The secret key URI, as stored unencrypted by previous versions.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	suri: Option<String>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	encrypted: Option<Encrypted>,
+}
+
+/// A secret key URI encrypted with AES-256-GCM, using a key derived from the password with
+/// PBKDF2-HMAC-SHA256. Each field is hex encoded.
+#[derive(Deserialize, Serialize)]
+struct Encrypted {
+	salt: String,
+	nonce: String,
+	rounds: u32,
+	ciphertext: String,
+}
+
+/// A local keystore, where each key is held in its own file which is only accessible by the
+/// current user, with its secret encrypted using the password of the keystore.
+pub struct Keystore {
+	path: PathBuf,
+	password: Option<String>,
+}
+
+impl Keystore {
+	/// Opens the keystore at `path`, which is created when the first key is inserted.
+	///
+	/// # Arguments
+	///
+	/// * `path` - the directory of the keystore
+	pub fn new(path: PathBuf) -> Self {
+		Self { path, password: None }
+	}
+
+	/// Unlocks the keystore with `password`, which encrypts the keys inserted and decrypts those
+	/// retrieved.
+	///
+	/// # Arguments
+	///
+	/// * `password` - the password of the keystore
+	pub fn with_password(mut self, password: impl Into<String>) -> Self {
+		self.password = Some(password.into());
+		self
+	}
+
+	/// Inserts a key into the keystore, encrypting its secret with the password of the keystore.
+	///
+	/// # Arguments
+	///
+	/// * `name` - the name of the key, which may only contain alphanumeric characters, `-` or `_`
+	/// * `suri` - the secret key URI of the key
+	/// * `scheme` - the signature scheme of the key
+	pub fn insert(&self, name: &str, suri: &str, scheme: Scheme) -> Result<Key, Error> {
+		Self::validate(name)?;
+		let path = self.key_path(name);
+		if path.exists() {
+			return Err(Error::Keystore(format!("a key named `{name}` already exists")));
+		}
+		let Some(password) = self.password.as_deref() else {
+			return Err(Error::Keystore("a password is required to encrypt the key".into()));
+		};
+		let address = scheme.address(suri)?;
+		let stored = StoredKey {
+			name: name.to_string(),
+			scheme,
+			address: Some(address.clone()),
+			suri: None,
+			encrypted: Some(encrypt(suri, password)?),
+		};
+		fs::create_dir_all(&self.path)?;
+		let mut file = OpenOptions::new().write(true).create_new(true).mode(0o600).open(path)?;
+		let json =
+			serde_json::to_string_pretty(&stored).map_err(|e| Error::Keystore(format!("{e}")))?;
+		file.write_all(json.as_bytes())?;
+		Ok(Key { name: name.to_string(), scheme, address, suri: suri.to_string() })
+	}
+
+	/// Returns the key with the provided `name`, decrypting its secret with the password of the
+	/// keystore.
+	///
+	/// # Arguments
+	///
+	/// * `name` - the name of the key
+	pub fn get(&self, name: &str) -> Result<Key, Error> {
+		let stored = self.load(name)?;
+		let suri = match (stored.suri, &stored.encrypted) {
+			(Some(suri), _) => suri,
+			(None, Some(encrypted)) => {
+				let Some(password) = self.password.as_deref() else {
+					return Err(Error::Keystore(format!(
+						"the key named `{name}` is encrypted, a password is required"
+					)));
+				};
+				decrypt(name, encrypted, password)?
+			},
+			(None, None) => {
+				return Err(Error::Keystore(format!("the key named `{name}` has no secret")))
+			},
+		};
+		let address = match stored.address {
+			Some(address) => address,
+			None => stored.scheme.address(&suri)?,
+		};
+		Ok(Key { name: name.to_string(), scheme: stored.scheme, address, suri })
+	}
+
+	/// Returns the public details of the key with the provided `name`, which does not require the
+	/// password of the keystore.
+	///
+	/// # Arguments
+	///
+	/// * `name` - the name of the key
+	pub fn info(&self, name: &str) -> Result<KeyInfo, Error> {
+		let stored = self.load(name)?;
+		let address = match (stored.address, &stored.suri) {
+			(Some(address), _) => address,
+			// Keys stored by previous versions are unencrypted, so their address can be derived.
+			(None, Some(suri)) => stored.scheme.address(suri)?,
+			(None, None) => String::new(),
+		};
+		Ok(KeyInfo {
+			name: name.to_string(),
+			scheme: stored.scheme,
+			address,
+			encrypted: stored.encrypted.is_some(),
+		})
+	}
+
+	/// Returns the names of all keys within the keystore, in alphabetical order.
+	pub fn list(&self) -> Result<Vec<String>, Error> {
+		if !self.path.exists() {
+			return Ok(Vec::new());
+		}
+		let mut names = Vec::new();
+		for entry in fs::read_dir(&self.path)? {
+			let path = entry?.path();
+			if path.extension().is_some_and(|e| e == "json") {
+				if let Some(name) = path.file_stem().and_then(|n| n.to_str()) {
+					names.push(name.to_string());
+				}
+			}
+		}
+		names.sort();
+		Ok(names)
+	}
+
+	fn load(&self, name: &str) -> Result<StoredKey, Error> {
+		Self::validate(name)?;
+		let path = self.key_path(name);
+		if !path.exists() {
+			return Err(Error::Keystore(format!("no key named `{name}` could be found")));
+		}
+		serde_json::from_str(&fs::read_to_string(path)?)
+			.map_err(|e| Error::Keystore(format!("the key named `{name}` is invalid: {e}")))
+	}
+
+	fn key_path(&self, name: &str) -> PathBuf {
+		self.path.join(format!("{name}.json"))
+	}
+
+	fn validate(name: &str) -> Result<(), Error> {
+		if name.is_empty()
+			|| !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+		{
+			return Err(Error::Keystore(format!(
+				"invalid key name `{name}`, only alphanumeric characters, `-` and `_` are allowed"
+			)));
+		}
+		Ok(())
+	}
+}
+
+fn cipher(password: &str, salt: &[u8], rounds: u32) -> Aes256Gcm {
+	let mut key = [0u8; 32];
+	pbkdf2::pbkdf2_hmac::<Sha256>(password.as_bytes(), salt, rounds, &mut key);
+	Aes256Gcm::new(&key.into())
+}
+
+fn encrypt(suri: &str, password: &str) -> Result<Encrypted, Error> {
+	let salt: [u8; 16] = rand::random();
+	let nonce: [u8; 12] = rand::random();
+	let ciphertext = cipher(password, &salt, ROUNDS)
+		.encrypt(Nonce::from_slice(&nonce), suri.as_bytes())
+		.map_err(|_| Error::Keystore("the key could not be encrypted".into()))?;
+	Ok(Encrypted {
+		salt: to_hex(&salt),
+		nonce: to_hex(&nonce),
+		rounds: ROUNDS,
+		ciphertext: to_hex(&ciphertext),
+	})
+}
+
+fn decrypt(name: &str, encrypted: &Encrypted, password: &str) -> Result<String, Error> {
+	let invalid = || Error::Keystore(format!("the key named `{name}` is invalid"));
+	let salt = from_hex(&encrypted.salt).ok_or_else(invalid)?;
+	let nonce = from_hex(&encrypted.nonce).filter(|n| n.len() == 12).ok_or_else(invalid)?;
+	let ciphertext = from_hex(&encrypted.ciphertext).ok_or_else(invalid)?;
+	let suri = cipher(password, &salt, encrypted.rounds)
+		.decrypt(Nonce::from_slice(&nonce), ciphertext.as_ref())
+		.map_err(|_| Error::Keystore(format!("incorrect password for the key named `{name}`")))?;
+	String::from_utf8(suri).map_err(|_| invalid())
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+	bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+fn from_hex(hex: &str) -> Option<Vec<u8>> {
+	if hex.len() % 2 != 0 {
+		return None;
+	}
+	(0..hex.len())
+		.step_by(2)
+		.map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+		.collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+	use std::os::unix::fs::PermissionsExt;
+
+	const ALICE: &str = "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY";
+
+	#[test]
+	fn test_insert_and_get() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let keystore = Keystore::new(temp_dir.path().join("keystore")).with_password("secret");
+		assert!(keystore.list()?.is_empty());
+
+		let key = keystore.insert("alice", "//Alice", Scheme::Sr25519)?;
+		assert_eq!(keystore.get("alice")?, key);
+		assert_eq!(keystore.get("alice")?.suri, "//Alice");
+		assert_eq!(key.address, ALICE);
+		let mode = fs::metadata(keystore.key_path("alice"))?.permissions().mode();
+		assert_eq!(mode & 0o777, 0o600);
+		// The secret is not stored in plain text.
+		assert!(!fs::read_to_string(keystore.key_path("alice"))?.contains("//Alice"));
+
+		keystore.insert("bob", "//Bob", Scheme::Ed25519)?;
+		assert_eq!(keystore.list()?, vec!["alice", "bob"]);
+		Ok(())
+	}
+
+	#[test]
+	fn test_password_is_required() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let path = temp_dir.path().to_path_buf();
+		assert!(matches!(
+			Keystore::new(path.clone()).insert("alice", "//Alice", Scheme::Sr25519),
+			Err(Error::Keystore(..))
+		));
+		Keystore::new(path.clone()).with_password("secret").insert(
+			"alice",
+			"//Alice",
+			Scheme::Sr25519,
+		)?;
+		// The public details of the key are available without the password.
+		let locked = Keystore::new(path.clone());
+		assert_eq!(
+			locked.info("alice")?,
+			KeyInfo {
+				name: "alice".into(),
+				scheme: Scheme::Sr25519,
+				address: ALICE.into(),
+				encrypted: true
+			}
+		);
+		assert!(matches!(locked.get("alice"), Err(Error::Keystore(..))));
+		assert!(matches!(
+			Keystore::new(path).with_password("wrong").get("alice"),
+			Err(Error::Keystore(..))
+		));
+		Ok(())
+	}
+
+	#[test]
+	fn test_unencrypted_keys_are_supported() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let keystore = Keystore::new(temp_dir.path().to_path_buf());
+		// As stored by previous versions.
+		fs::write(keystore.key_path("alice"), r#"{ "name": "alice", "suri": "//Alice" }"#)?;
+		let key = keystore.get("alice")?;
+		assert_eq!(
+			(key.scheme, key.address.as_str(), key.suri.as_str()),
+			(Scheme::Sr25519, ALICE, "//Alice")
+		);
+		assert!(!keystore.info("alice")?.encrypted);
+		Ok(())
+	}
+
+	#[test]
+	fn test_insert_fails_for_existing_key() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let keystore = Keystore::new(temp_dir.path().to_path_buf()).with_password("secret");
+		keystore.insert("alice", "//Alice", Scheme::Sr25519)?;
+		assert!(matches!(
+			keystore.insert("alice", "//Bob", Scheme::Sr25519),
+			Err(Error::Keystore(..))
+		));
+		assert_eq!(keystore.get("alice")?.suri, "//Alice");
+		Ok(())
+	}
+
+	#[test]
+	fn test_invalid_keys_are_rejected() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let keystore = Keystore::new(temp_dir.path().to_path_buf()).with_password("secret");
+		assert!(matches!(
+			keystore.insert("../alice", "//Alice", Scheme::Sr25519),
+			Err(Error::Keystore(..))
+		));
+		assert!(matches!(
+			keystore.insert("", "//Alice", Scheme::Sr25519),
+			Err(Error::Keystore(..))
+		));
+		assert!(matches!(
+			keystore.insert("alice", "not a mnemonic", Scheme::Sr25519),
+			Err(Error::Keystore(..))
+		));
+		assert!(matches!(keystore.get("missing"), Err(Error::Keystore(..))));
+		Ok(())
+	}
+
+	#[test]
+	fn generate_works() -> Result<()> {
+		for scheme in [Scheme::Sr25519, Scheme::Ed25519, Scheme::Ecdsa] {
+			let phrase = scheme.generate();
+			assert_eq!(phrase.split_whitespace().count(), 12);
+			// Derivation paths derive other accounts from the same phrase.
+			assert_ne!(scheme.address(&phrase)?, scheme.address(&format!("{phrase}//0"))?);
+		}
+		assert_eq!(Scheme::Sr25519.address("//Alice")?, ALICE);
+		assert_eq!("ecdsa".parse::<Scheme>()?, Scheme::Ecdsa);
+		Ok(())
+	}
+}
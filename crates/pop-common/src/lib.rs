@@ -0,0 +1,27 @@
+// SPDX-License-Identifier: GPL-3.0
+pub mod build_cache;
+pub mod cache;
+pub mod config;
+mod errors;
+pub mod git;
+pub mod history;
+pub mod keystore;
+pub mod lock;
+pub mod platform;
+pub mod progress;
+pub mod project;
+pub mod sourcing;
+pub mod verification;
+
+pub use build_cache::BuildCache;
+pub use config::Config;
+pub use errors::Error;
+pub use git::{Git, GitHub, Release};
+pub use history::Operation;
+pub use keystore::{Key, KeyInfo, Keystore};
+pub use lock::Lock;
+pub use platform::Platform;
+pub use progress::{Progress, Unit};
+pub use project::{Project, Token};
+pub use sourcing::{Binary, Source, Status};
+pub use verification::Verification;
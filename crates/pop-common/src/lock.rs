@@ -0,0 +1,250 @@
+// SPDX-License-Identifier: GPL-3.0
+use crate::{Error, Project};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{
+	collections::BTreeMap,
+	fs::{self, File},
+	io,
+	path::Path,
+};
+
+/// The file of a project recording the exact tooling it uses, alongside its `pop.toml`.
+pub const LOCK_FILE: &str = "pop.lock";
+/// The header of the lockfile, as it is not meant to be edited by hand.
+const HEADER: &str = "# Generated by pop and meant to be committed, so that everyone working on \
+	the project uses the same tooling.\n# Update it with `pop update lock` rather than by hand.\n";
+
+/// The exact versions of the tooling used by a project, recorded within its `pop.lock` so that
+/// teammates and CI resolve identical binaries, e.g.:
+///
+/// ```toml
+/// [template]
+/// name = "standard"
+/// provider = "pop"
+/// release = "v1.0.0"
+///
+/// [binaries.polkadot]
+/// version = "v1.13.0"
+///
+/// [binaries.polkadot.sha256]
+/// x86_64-unknown-linux-gnu = "0f1e…"
+/// ```
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct Lock {
+	/// The template the project was generated from, if any.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub template: Option<LockedTemplate>,
+	/// The binaries used by the project, by name.
+	#[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+	pub binaries: BTreeMap<String, LockedBinary>,
+}
+
+/// The template a project was generated from.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct LockedTemplate {
+	/// The name of the template.
+	pub name: String,
+	/// The provider of the template, if any.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub provider: Option<String>,
+	/// The release tag or commit of the template.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub release: Option<String>,
+}
+
+/// A binary used by a project.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct LockedBinary {
+	/// The version of the binary, as per its release tag.
+	pub version: String,
+	/// The SHA-256 hash of the binary released for each platform, by target triple.
+	#[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+	pub sha256: BTreeMap<String, String>,
+}
+
+impl Lock {
+	/// Loads the lockfile of the project at `path`, which is empty when missing.
+	///
+	/// # Arguments
+	///
+	/// * `path` - path to the project
+	pub fn load(path: &Path) -> Result<Self, Error> {
+		let file = path.join(LOCK_FILE);
+		if !file.exists() {
+			return Ok(Self::default());
+		}
+		toml_edit::de::from_str(&fs::read_to_string(&file)?)
+			.map_err(|e| Error::Config(format!("invalid {}: {e}", file.display())))
+	}
+
+	/// Saves the lockfile of the project at `path`.
+	///
+	/// # Arguments
+	///
+	/// * `path` - path to the project
+	pub fn save(&self, path: &Path) -> Result<(), Error> {
+		let contents = toml_edit::ser::to_string_pretty(self)
+			.map_err(|e| Error::Config(format!("unable to record the lockfile: {e}")))?;
+		fs::write(path.join(LOCK_FILE), format!("{HEADER}\n{contents}"))?;
+		Ok(())
+	}
+
+	/// The version of the binary `name` locked, if any.
+	///
+	/// # Arguments
+	///
+	/// * `name` - the name of the binary
+	pub fn version(&self, name: &str) -> Option<&str> {
+		self.binaries.get(name).map(|b| b.version.as_str())
+	}
+
+	/// Locks the binary `name` at `version`, discarding the hashes of any other version.
+	///
+	/// # Arguments
+	///
+	/// * `name` - the name of the binary
+	/// * `version` - the version of the binary
+	pub fn lock(&mut self, name: &str, version: &str) {
+		let binary = self.binaries.entry(name.to_string()).or_default();
+		if binary.version != version {
+			*binary = LockedBinary { version: version.to_string(), sha256: BTreeMap::new() };
+		}
+	}
+
+	/// Checks the binary at `path`, of the version of `name` locked, against the hash locked for
+	/// `platform`, recording its hash when none is locked yet and `record` is set. Fails when the
+	/// binary differs from the one locked.
+	///
+	/// # Arguments
+	///
+	/// * `name` - the name of the binary
+	/// * `path` - path to the binary
+	/// * `platform` - the target triple of the platform
+	/// * `record` - whether the hash is recorded when none is locked for the platform
+	pub fn check(
+		&mut self,
+		name: &str,
+		path: &Path,
+		platform: &str,
+		record: bool,
+	) -> Result<(), Error> {
+		let Some(binary) = self.binaries.get_mut(name) else { return Ok(()) };
+		let hash = sha256(path)?;
+		match binary.sha256.get(platform) {
+			Some(locked) if *locked != hash => Err(Error::LockMismatch {
+				name: name.to_string(),
+				version: binary.version.clone(),
+				platform: platform.to_string(),
+			}),
+			Some(_) => Ok(()),
+			None => {
+				if record {
+					binary.sha256.insert(platform.to_string(), hash);
+				}
+				Ok(())
+			},
+		}
+	}
+
+	/// Locks the template of `project`.
+	///
+	/// # Arguments
+	///
+	/// * `project` - the project, as recorded within its `pop.toml`
+	pub fn lock_template(&mut self, project: &Project) {
+		self.template = Some(LockedTemplate {
+			name: project.template.clone(),
+			provider: project.provider.clone(),
+			release: project.release.clone(),
+		});
+	}
+}
+
+/// The SHA-256 hash of the file at `path`, hex encoded.
+///
+/// # Arguments
+///
+/// * `path` - path to the file
+pub fn sha256(path: &Path) -> Result<String, Error> {
+	let mut hasher = Sha256::new();
+	io::copy(&mut File::open(path)?, &mut hasher)?;
+	Ok(hasher.finalize().iter().map(|b| format!("{b:02x}")).collect())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+
+	const PLATFORM: &str = "x86_64-unknown-linux-gnu";
+
+	#[test]
+	fn sha256_works() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let file = temp_dir.path().join("binary");
+		fs::write(&file, "binary")?;
+		assert_eq!(
+			sha256(&file)?,
+			"9a3a45d01531a20e89ac6ae10b0b0beb0492acd7216a368aa062d1a5fecaf9cd"
+		);
+		Ok(())
+	}
+
+	#[test]
+	fn lock_and_check_work() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let binary = temp_dir.path().join("polkadot");
+		fs::write(&binary, "binary")?;
+		let mut lock = Lock::default();
+		// Binaries which are not locked are not checked.
+		lock.check("polkadot", &binary, PLATFORM, true)?;
+		lock.lock("polkadot", "v1.13.0");
+		lock.check("polkadot", &binary, PLATFORM, false)?;
+		assert!(lock.binaries["polkadot"].sha256.is_empty());
+		lock.check("polkadot", &binary, PLATFORM, true)?;
+		assert_eq!(lock.binaries["polkadot"].sha256[PLATFORM], sha256(&binary)?);
+		fs::write(&binary, "tampered")?;
+		assert!(matches!(
+			lock.check("polkadot", &binary, PLATFORM, true),
+			Err(Error::LockMismatch { .. })
+		));
+		// Another platform has its own hash.
+		lock.check("polkadot", &binary, "aarch64-apple-darwin", true)?;
+		// Locking the same version keeps its hashes, whereas another version discards them.
+		lock.lock("polkadot", "v1.13.0");
+		assert_eq!(lock.binaries["polkadot"].sha256.len(), 2);
+		lock.lock("polkadot", "v1.14.0");
+		assert_eq!(lock.version("polkadot"), Some("v1.14.0"));
+		assert!(lock.binaries["polkadot"].sha256.is_empty());
+		Ok(())
+	}
+
+	#[test]
+	fn save_and_load_work() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let path = temp_dir.path();
+		assert_eq!(Lock::load(path)?, Lock::default());
+		let mut lock = Lock::default();
+		lock.lock_template(&Project {
+			kind: "parachain".into(),
+			provider: Some("pop".into()),
+			template: "standard".into(),
+			release: Some("v1.0.0".into()),
+			..Default::default()
+		});
+		lock.lock("polkadot", "v1.13.0");
+		lock.binaries
+			.get_mut("polkadot")
+			.unwrap()
+			.sha256
+			.insert(PLATFORM.into(), "0f1e".into());
+		lock.lock("frame-omni-bencher", "stable2409");
+		lock.save(path)?;
+		let contents = fs::read_to_string(path.join(LOCK_FILE))?;
+		assert!(contents.starts_with(HEADER));
+		assert!(contents.contains("[binaries.polkadot]"));
+		assert_eq!(Lock::load(path)?, lock);
+		Ok(())
+	}
+}
@@ -0,0 +1,145 @@
+// SPDX-License-Identifier: GPL-3.0
+use crate::Error;
+use std::{fmt, fs, path::Path};
+
+/// An operating system for which binaries may be released.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Os {
+	/// Linux, linked against glibc.
+	LinuxGnu,
+	/// Linux, linked against musl, such as Alpine, which cannot run binaries linked against glibc.
+	LinuxMusl,
+	/// macOS.
+	MacOs,
+}
+
+/// A CPU architecture for which binaries may be released.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Arch {
+	/// 64-bit x86, also known as amd64.
+	X86_64,
+	/// 64-bit ARM, such as Apple Silicon.
+	Aarch64,
+}
+
+/// The platform on which pop runs, which determines the release asset of a binary sourced.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Platform {
+	/// The operating system.
+	pub os: Os,
+	/// The CPU architecture.
+	pub arch: Arch,
+}
+
+impl Platform {
+	/// Detects the platform on which pop runs, failing when it is not one for which binaries are
+	/// released.
+	pub fn detect() -> Result<Self, Error> {
+		Self::from(std::env::consts::OS, std::env::consts::ARCH, Path::new("/lib"))
+	}
+
+	/// The platform of the operating system `os` and architecture `arch`, as per
+	/// [`std::env::consts`], whose C library is detected from the loaders within `lib`.
+	fn from(os: &str, arch: &str, lib: &Path) -> Result<Self, Error> {
+		let unsupported = || Error::UnsupportedPlatform { os: os.into(), arch: arch.into() };
+		let arch = match arch {
+			"x86_64" => Arch::X86_64,
+			"aarch64" => Arch::Aarch64,
+			_ => return Err(unsupported()),
+		};
+		let os = match os {
+			"linux" if cfg!(target_env = "musl") || is_musl(lib) => Os::LinuxMusl,
+			"linux" => Os::LinuxGnu,
+			"macos" => Os::MacOs,
+			_ => return Err(unsupported()),
+		};
+		Ok(Self { os, arch })
+	}
+
+	/// The target triple of the platform, as used to name release assets, e.g.
+	/// `aarch64-apple-darwin`.
+	pub fn target(&self) -> &'static str {
+		match (self.os, self.arch) {
+			(Os::LinuxGnu, Arch::X86_64) => "x86_64-unknown-linux-gnu",
+			(Os::LinuxGnu, Arch::Aarch64) => "aarch64-unknown-linux-gnu",
+			(Os::LinuxMusl, Arch::X86_64) => "x86_64-unknown-linux-musl",
+			(Os::LinuxMusl, Arch::Aarch64) => "aarch64-unknown-linux-musl",
+			(Os::MacOs, Arch::X86_64) => "x86_64-apple-darwin",
+			(Os::MacOs, Arch::Aarch64) => "aarch64-apple-darwin",
+		}
+	}
+
+	/// Whether the platform is Linux, regardless of its C library.
+	pub fn is_linux(&self) -> bool {
+		matches!(self.os, Os::LinuxGnu | Os::LinuxMusl)
+	}
+}
+
+impl fmt::Display for Platform {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}", self.target())
+	}
+}
+
+/// Whether the dynamic loader of musl is found within `lib`, e.g. `ld-musl-x86_64.so.1`.
+fn is_musl(lib: &Path) -> bool {
+	fs::read_dir(lib)
+		.map(|entries| {
+			entries
+				.flatten()
+				.any(|e| e.file_name().to_string_lossy().starts_with("ld-musl-"))
+		})
+		.unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+
+	#[test]
+	fn from_works() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let lib = temp_dir.path();
+		let gnu = if cfg!(target_env = "musl") { Os::LinuxMusl } else { Os::LinuxGnu };
+		assert_eq!(
+			Platform::from("linux", "x86_64", lib)?,
+			Platform { os: gnu, arch: Arch::X86_64 }
+		);
+		assert_eq!(
+			Platform::from("macos", "aarch64", lib)?,
+			Platform { os: Os::MacOs, arch: Arch::Aarch64 }
+		);
+		fs::write(lib.join("ld-musl-aarch64.so.1"), "")?;
+		assert_eq!(
+			Platform::from("linux", "aarch64", lib)?,
+			Platform { os: Os::LinuxMusl, arch: Arch::Aarch64 }
+		);
+		assert!(matches!(
+			Platform::from("windows", "x86_64", lib),
+			Err(Error::UnsupportedPlatform { .. })
+		));
+		assert!(matches!(
+			Platform::from("linux", "riscv64", lib),
+			Err(Error::UnsupportedPlatform { .. })
+		));
+		Ok(())
+	}
+
+	#[test]
+	fn target_works() {
+		let platform = Platform { os: Os::MacOs, arch: Arch::Aarch64 };
+		assert_eq!(platform.to_string(), "aarch64-apple-darwin");
+		assert!(!platform.is_linux());
+		let platform = Platform { os: Os::LinuxMusl, arch: Arch::X86_64 };
+		assert_eq!(platform.target(), "x86_64-unknown-linux-musl");
+		assert!(platform.is_linux());
+	}
+
+	#[test]
+	fn detect_works() {
+		if cfg!(all(target_os = "linux", target_arch = "x86_64")) {
+			assert!(Platform::detect().unwrap().is_linux());
+		}
+	}
+}
@@ -0,0 +1,141 @@
+// SPDX-License-Identifier: GPL-3.0
+use std::fmt::{Display, Formatter};
+
+/// The width of the bar rendered when the total of a step is known.
+const BAR_WIDTH: u64 = 20;
+/// The number of bytes within a mebibyte.
+const MIB: f64 = 1024.0 * 1024.0;
+
+/// The unit in which the progress of a step is measured.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Unit {
+	/// The objects received when cloning a repository.
+	Objects,
+	/// The bytes received when downloading.
+	Bytes,
+	/// The crates compiled when building.
+	Crates,
+}
+
+/// The progress of a step of a long-running operation, such as cloning, downloading or building,
+/// rendered as the step along with how far along it is, e.g.
+/// `Downloading polkadot [########------------] 40% (12.0/30.0 MiB)`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Progress<'a> {
+	/// The step running.
+	pub step: &'a str,
+	/// The units completed.
+	pub done: u64,
+	/// The total units, when known.
+	pub total: Option<u64>,
+	/// The unit of `done` and `total`.
+	pub unit: Unit,
+}
+
+impl Progress<'_> {
+	/// The percentage of the step completed, when its total is known.
+	pub fn percentage(&self) -> Option<u64> {
+		self.total
+			.filter(|total| *total > 0)
+			.map(|total| self.done.min(total) * 100 / total)
+	}
+
+	/// Formats `units` of the unit of the step.
+	fn units(&self, units: u64) -> String {
+		match self.unit {
+			Unit::Bytes => format!("{:.1}", units as f64 / MIB),
+			_ => units.to_string(),
+		}
+	}
+}
+
+impl Display for Progress<'_> {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		let unit = match self.unit {
+			Unit::Objects => "objects",
+			Unit::Bytes => "MiB",
+			Unit::Crates => "crates",
+		};
+		match (self.total, self.percentage()) {
+			(Some(total), Some(percentage)) => {
+				let filled = (percentage * BAR_WIDTH / 100) as usize;
+				write!(
+					f,
+					"{} [{}{}] {percentage}% ({}/{} {unit})",
+					self.step,
+					"#".repeat(filled),
+					"-".repeat(BAR_WIDTH as usize - filled),
+					self.units(self.done),
+					self.units(total),
+				)
+			},
+			_ => write!(f, "{} ({} {unit})", self.step, self.units(self.done)),
+		}
+	}
+}
+
+/// Throttles the reporting of progress to changes a user would notice: each percent when the
+/// total is known, otherwise every `interval` units.
+#[derive(Debug, Default)]
+pub struct Throttle {
+	last: Option<u64>,
+}
+
+impl Throttle {
+	/// Whether `progress` should be reported, having changed noticeably since last reported.
+	///
+	/// # Arguments
+	///
+	/// * `progress` - the progress of the step
+	/// * `interval` - the units between reports, when the total is unknown
+	pub fn ready(&mut self, progress: &Progress, interval: u64) -> bool {
+		let mark = progress.percentage().unwrap_or(progress.done / interval.max(1));
+		if self.last == Some(mark) {
+			return false;
+		}
+		self.last = Some(mark);
+		true
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn display_works() {
+		let progress = Progress {
+			step: "Downloading",
+			done: 12 * 1024 * 1024,
+			total: Some(30 * 1024 * 1024),
+			unit: Unit::Bytes,
+		};
+		assert_eq!(progress.percentage(), Some(40));
+		assert_eq!(progress.to_string(), "Downloading [########------------] 40% (12.0/30.0 MiB)");
+
+		let progress =
+			Progress { step: "Compiling serde", done: 42, total: None, unit: Unit::Crates };
+		assert_eq!(progress.percentage(), None);
+		assert_eq!(progress.to_string(), "Compiling serde (42 crates)");
+
+		let progress = Progress { step: "Cloning", done: 5, total: Some(0), unit: Unit::Objects };
+		assert_eq!(progress.to_string(), "Cloning (5 objects)");
+	}
+
+	#[test]
+	fn throttle_works() {
+		let mut throttle = Throttle::default();
+		let progress =
+			|done| Progress { step: "Cloning", done, total: Some(1000), unit: Unit::Objects };
+		assert!(throttle.ready(&progress(0), 1));
+		assert!(!throttle.ready(&progress(5), 1));
+		assert!(throttle.ready(&progress(10), 1));
+
+		let mut throttle = Throttle::default();
+		let progress =
+			|done| Progress { step: "Downloading", done, total: None, unit: Unit::Bytes };
+		assert!(throttle.ready(&progress(10), 100));
+		assert!(!throttle.ready(&progress(99), 100));
+		assert!(throttle.ready(&progress(100), 100));
+	}
+}
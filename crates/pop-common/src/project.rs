@@ -0,0 +1,343 @@
+// SPDX-License-Identifier: GPL-3.0
+use crate::Error;
+use serde::{Deserialize, Serialize};
+use std::{
+	collections::BTreeMap,
+	fs,
+	path::{Path, PathBuf},
+};
+use toml_edit::{DocumentMut, Item};
+
+/// The file of a project in which pop records how the project was generated, alongside the
+/// environments of contract projects.
+pub const PROJECT_FILE: &str = "pop.toml";
+/// The table of the file holding the project.
+const TABLE: &str = "project";
+/// The table of the file pinning the versions of the binaries used by the project.
+const BINARIES: &str = "binaries";
+
+/// How a project was generated, recorded within the `[project]` table of its `pop.toml` so that
+/// subsequent commands do not need the same flags again and can detect drift, e.g.:
+///
+/// ```toml
+/// [project]
+/// kind = "parachain"
+/// provider = "pop"
+/// template = "standard"
+/// release = "v1.0.0"
+/// sdk_version = "v1.11.0"
+/// para_id = 2000
+///
+/// [project.token]
+/// symbol = "UNIT"
+/// decimals = 12
+/// endowment = "1u64 << 60"
+/// ```
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct Project {
+	/// The kind of project, e.g. `parachain` or `contract`.
+	pub kind: String,
+	/// The provider of the template, if any.
+	pub provider: Option<String>,
+	/// The template the project was generated from.
+	pub template: String,
+	/// The release tag or commit of the template.
+	pub release: Option<String>,
+	/// The version of the SDK the project depended on, such as the release of the Polkadot SDK.
+	pub sdk_version: Option<String>,
+	/// The identifier of the parachain.
+	pub para_id: Option<u32>,
+	/// The configuration of the native token.
+	pub token: Option<Token>,
+}
+
+/// The configuration of the native token of a project.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct Token {
+	/// The symbol of the token.
+	pub symbol: String,
+	/// The number of decimals of the token.
+	pub decimals: u8,
+	/// The initial endowment of development accounts.
+	pub endowment: Option<String>,
+}
+
+/// The contents of `pop.toml` relevant to the project, ignoring any other table.
+#[derive(Deserialize)]
+struct File {
+	project: Option<Project>,
+}
+
+impl Project {
+	/// Loads the project recorded within the `pop.toml` of the project at `path`, if any.
+	///
+	/// # Arguments
+	///
+	/// * `path` - path to the project
+	pub fn load(path: &Path) -> Result<Option<Self>, Error> {
+		let file = path.join(PROJECT_FILE);
+		if !file.exists() {
+			return Ok(None);
+		}
+		let contents = fs::read_to_string(&file)?;
+		let file: File = toml_edit::de::from_str(&contents)
+			.map_err(|e| Error::Config(format!("invalid {}: {e}", file.display())))?;
+		Ok(file.project)
+	}
+
+	/// Records the project within the `pop.toml` of the project at `path`, preserving any other
+	/// table, such as the environments of a contract project.
+	///
+	/// # Arguments
+	///
+	/// * `path` - path to the project
+	pub fn save(&self, path: &Path) -> Result<(), Error> {
+		let file = path.join(PROJECT_FILE);
+		let mut document = document(&file)?;
+		let project = toml_edit::ser::to_document(self)
+			.map_err(|e| Error::Config(format!("unable to record the project: {e}")))?;
+		document[TABLE] = Item::Table(project.as_table().clone());
+		fs::write(&file, document.to_string())?;
+		Ok(())
+	}
+}
+
+/// The contents of `file`, which is empty when missing.
+fn document(file: &Path) -> Result<DocumentMut, Error> {
+	match file.exists() {
+		true => fs::read_to_string(file)?
+			.parse::<DocumentMut>()
+			.map_err(|e| Error::Config(format!("invalid {}: {e}", file.display()))),
+		false => Ok(DocumentMut::new()),
+	}
+}
+
+/// The versions of the binaries pinned by the project at `path`, by name, as recorded within the
+/// `[binaries]` table of its `pop.toml`, e.g.:
+///
+/// ```toml
+/// [binaries]
+/// polkadot = "v1.13.0"
+/// frame-omni-bencher = "stable2409"
+/// ```
+///
+/// # Arguments
+///
+/// * `path` - path to the project
+pub fn pinned_binaries(path: &Path) -> Result<BTreeMap<String, String>, Error> {
+	let file = path.join(PROJECT_FILE);
+	let document = document(&file)?;
+	let Some(binaries) = document.get(BINARIES) else { return Ok(BTreeMap::new()) };
+	let binaries = binaries.as_table_like().ok_or(Error::Config(format!(
+		"invalid {}: `{BINARIES}` is expected to be a table",
+		file.display()
+	)))?;
+	binaries
+		.iter()
+		.map(|(name, version)| match version.as_str() {
+			Some(version) => Ok((name.to_string(), version.to_string())),
+			None => Err(Error::Config(format!(
+				"invalid {}: the version of `{name}` is expected to be a string",
+				file.display()
+			))),
+		})
+		.collect()
+}
+
+/// The version of the binary `name` pinned by the project at `path`, if any.
+///
+/// # Arguments
+///
+/// * `path` - path to the project
+/// * `name` - the name of the binary
+pub fn pinned_binary(path: &Path, name: &str) -> Result<Option<String>, Error> {
+	Ok(pinned_binaries(path)?.remove(name))
+}
+
+/// Pins the version of the binary `name` used by the project at `path`, within its `pop.toml`,
+/// preserving any other table.
+///
+/// # Arguments
+///
+/// * `path` - path to the project
+/// * `name` - the name of the binary
+/// * `version` - the version used
+pub fn pin_binary(path: &Path, name: &str, version: &str) -> Result<(), Error> {
+	let file = path.join(PROJECT_FILE);
+	let mut document = document(&file)?;
+	if !document.contains_table(BINARIES) {
+		document[BINARIES] = toml_edit::table();
+	}
+	document[BINARIES][name] = toml_edit::value(version);
+	fs::write(&file, document.to_string())?;
+	Ok(())
+}
+
+/// The root of the project containing `dir`, so that commands can be run from any of its
+/// subdirectories: the nearest directory holding a `pop.toml`, otherwise the root of the Cargo
+/// workspace containing `dir`, otherwise its nearest crate. `None` when `dir` is not within a
+/// project.
+///
+/// # Arguments
+///
+/// * `dir` - a directory within the project
+pub fn find_root(dir: &Path) -> Option<PathBuf> {
+	let dir = dir.canonicalize().ok()?;
+	if let Some(root) = dir.ancestors().find(|d| d.join(PROJECT_FILE).is_file()) {
+		return Some(root.to_path_buf());
+	}
+	let krate = dir.ancestors().find(|d| d.join("Cargo.toml").is_file())?;
+	let workspace = krate.ancestors().find(|d| is_workspace(&d.join("Cargo.toml")));
+	Some(workspace.unwrap_or(krate).to_path_buf())
+}
+
+/// The nearest crate containing `dir`, without walking up to the workspace it belongs to, so that
+/// commands applying to a single crate, such as a contract within a workspace of contracts, target
+/// the crate they are run from. `None` when `dir` is not within a crate.
+///
+/// # Arguments
+///
+/// * `dir` - a directory within the crate
+pub fn find_crate(dir: &Path) -> Option<PathBuf> {
+	let dir = dir.canonicalize().ok()?;
+	dir.ancestors().find(|d| d.join("Cargo.toml").is_file()).map(Path::to_path_buf)
+}
+
+/// Whether `manifest` declares a Cargo workspace.
+///
+/// # Arguments
+///
+/// * `manifest` - path to the manifest
+pub fn is_workspace(manifest: &Path) -> bool {
+	fs::read_to_string(manifest)
+		.ok()
+		.and_then(|contents| contents.parse::<DocumentMut>().ok())
+		.is_some_and(|manifest| manifest.contains_key("workspace"))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+
+	fn project() -> Project {
+		Project {
+			kind: "parachain".into(),
+			provider: Some("pop".into()),
+			template: "standard".into(),
+			release: Some("v1.0.0".into()),
+			sdk_version: Some("v1.11.0".into()),
+			para_id: Some(2000),
+			token: Some(Token {
+				symbol: "UNIT".into(),
+				decimals: 12,
+				endowment: Some("1u64 << 60".into()),
+			}),
+		}
+	}
+
+	#[test]
+	fn save_and_load_works() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		assert_eq!(Project::load(temp_dir.path())?, None);
+		project().save(temp_dir.path())?;
+		assert_eq!(Project::load(temp_dir.path())?, Some(project()));
+
+		let project = Project { kind: "contract".into(), template: "erc20".into(), ..project() };
+		project.save(temp_dir.path())?;
+		assert_eq!(Project::load(temp_dir.path())?, Some(project));
+		Ok(())
+	}
+
+	#[test]
+	fn save_preserves_environments() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let environments = "[environments.staging]\nurl = \"wss://staging.example.com\"\n";
+		fs::write(temp_dir.path().join(PROJECT_FILE), environments)?;
+		project().save(temp_dir.path())?;
+		let contents = fs::read_to_string(temp_dir.path().join(PROJECT_FILE))?;
+		assert!(contents.starts_with(environments));
+		assert_eq!(Project::load(temp_dir.path())?, Some(project()));
+		Ok(())
+	}
+
+	#[test]
+	fn pin_binary_works() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let path = temp_dir.path();
+		assert!(pinned_binaries(path)?.is_empty());
+		project().save(path)?;
+		pin_binary(path, "polkadot", "v1.11.0")?;
+		pin_binary(path, "frame-omni-bencher", "stable2409")?;
+		pin_binary(path, "polkadot", "v1.13.0")?;
+		assert_eq!(
+			pinned_binaries(path)?,
+			BTreeMap::from([
+				("frame-omni-bencher".into(), "stable2409".into()),
+				("polkadot".into(), "v1.13.0".into())
+			])
+		);
+		assert_eq!(pinned_binary(path, "polkadot")?, Some("v1.13.0".into()));
+		assert_eq!(pinned_binary(path, "polkadot-parachain")?, None);
+		// The project is preserved.
+		assert_eq!(Project::load(path)?, Some(project()));
+
+		fs::write(
+			path.join(PROJECT_FILE),
+			"[binaries]
+polkadot = 1
+",
+		)?;
+		assert!(matches!(pinned_binaries(path), Err(Error::Config(..))));
+		Ok(())
+	}
+
+	#[test]
+	fn find_root_works() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let root = temp_dir.path().canonicalize()?;
+		assert_eq!(find_root(&root), None);
+
+		// A crate within a workspace.
+		fs::write(root.join("Cargo.toml"), "[workspace]\nmembers = [\"runtime\"]\n")?;
+		let runtime = root.join("runtime");
+		fs::create_dir_all(runtime.join("src"))?;
+		fs::write(runtime.join("Cargo.toml"), "[package]\nname = \"runtime\"\n")?;
+		assert_eq!(find_root(&runtime.join("src")), Some(root.clone()));
+
+		// A project generated by pop, nested within the workspace.
+		let contract = root.join("contracts").join("flipper");
+		fs::create_dir_all(&contract)?;
+		fs::write(contract.join(PROJECT_FILE), "")?;
+		fs::write(contract.join("Cargo.toml"), "[package]\nname = \"flipper\"\n")?;
+		assert_eq!(find_root(&contract), Some(contract.clone()));
+		Ok(())
+	}
+
+	#[test]
+	fn find_crate_works() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let root = temp_dir.path().canonicalize()?;
+		assert_eq!(find_crate(&root), None);
+
+		// A contract within a workspace of contracts.
+		fs::write(root.join("Cargo.toml"), "[workspace]\nmembers = [\"flipper\"]\n")?;
+		let flipper = root.join("flipper");
+		fs::create_dir_all(flipper.join("src"))?;
+		fs::write(flipper.join("Cargo.toml"), "[package]\nname = \"flipper\"\n")?;
+		assert_eq!(find_crate(&flipper.join("src")), Some(flipper.clone()));
+		assert_eq!(find_crate(&flipper), Some(flipper));
+		assert_eq!(find_crate(&root), Some(root));
+		Ok(())
+	}
+
+	#[test]
+	fn load_ignores_files_without_project() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		fs::write(temp_dir.path().join(PROJECT_FILE), "[environments]\n")?;
+		assert_eq!(Project::load(temp_dir.path())?, None);
+		fs::write(temp_dir.path().join(PROJECT_FILE), "[project]\nkind = 1\n")?;
+		assert!(Project::load(temp_dir.path()).is_err());
+		Ok(())
+	}
+}
@@ -0,0 +1,663 @@
+// SPDX-License-Identifier: GPL-3.0
+use crate::{
+	build_cache::BuildCache,
+	cache::Digest,
+	errors::Error,
+	git::{Git, GitHub},
+	progress::{Progress, Throttle, Unit},
+	verification::{self, Outcome, Verification},
+};
+use duct::cmd;
+use flate2::read::GzDecoder;
+use futures::future::join_all;
+use std::{
+	fs::{copy, create_dir_all, metadata, remove_dir_all, remove_file, rename, File},
+	io::{BufRead, Write},
+	os::unix::fs::PermissionsExt,
+	path::{Path, PathBuf},
+	sync::RwLock,
+};
+use tar::Archive;
+use tracing::{debug, trace, warn};
+use url::Url;
+use walkdir::WalkDir;
+
+/// The extension of a file being downloaded, renamed once the download completes so that an
+/// interrupted download is never mistaken for a complete one.
+pub const PARTIAL_EXTENSION: &str = "part";
+/// The directory within the cache holding the binaries, by name and then by version, e.g.
+/// `bins/polkadot/v1.7.0/polkadot`.
+pub const BINARIES_DIR: &str = "bins";
+/// The version under which a binary built from the latest sources, rather than a release, is
+/// cached.
+pub const LATEST: &str = "latest";
+
+/// The mirrors of the binaries released, tried in order before the canonical url of a release.
+static MIRRORS: RwLock<Vec<Url>> = RwLock::new(Vec::new());
+
+/// Downloads the binaries released from `mirrors` when available, such as an internal artifact
+/// server mirroring GitHub releases, trying each in order before the canonical url.
+///
+/// # Arguments
+///
+/// * `mirrors` - the base urls of the mirrors
+pub fn set_mirrors(mirrors: Vec<Url>) {
+	*MIRRORS.write().unwrap_or_else(|e| e.into_inner()) = mirrors;
+}
+
+/// A binary used to launch a node.
+pub struct Binary {
+	/// The name of a binary.
+	pub name: String,
+	/// The version of the binary.
+	pub version: String,
+	/// The path to the binary within the cache.
+	pub path: PathBuf,
+	/// The sources used to acquire the binary, if it is not yet available within the cache.
+	pub sources: Vec<Source>,
+}
+
+impl Binary {
+	/// Sources the binary by either downloading from a url or by cloning a git repository and
+	/// building locally from the resulting source code, returning the verification of each
+	/// artifact sourced. Its sources, such as the workers of a relay chain binary, are processed
+	/// concurrently.
+	///
+	/// # Arguments
+	///
+	/// * `cache` - path to the local cache
+	/// * `status` - used to observe status updates
+	pub async fn source(
+		&self,
+		cache: &PathBuf,
+		status: impl Status,
+	) -> Result<Vec<Verification>, Error> {
+		let processed = join_all(self.sources.iter().map(|s| s.process(cache, status))).await;
+		let mut verifications = Vec::new();
+		for verification in processed {
+			verifications.extend(verification?);
+		}
+		Ok(verifications)
+	}
+
+	/// Whether the binary is built locally from its source code when sourced, such as when no
+	/// release of it exists for the platform, which may take a while.
+	pub fn is_built(&self) -> bool {
+		self.sources.iter().any(|s| matches!(s, Source::Git { .. }))
+	}
+}
+
+/// The source of a binary.
+#[derive(Clone, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Source {
+	/// The source is a URL.
+	Url {
+		/// The name of the binary.
+		name: String,
+		/// The version of the binary.
+		version: String,
+		/// The url to download the binary.
+		url: String,
+	},
+	/// The source is a `.tar.gz` archive, containing one or more binaries.
+	Archive {
+		/// The version of the binaries.
+		version: String,
+		/// The url to download the archive.
+		url: String,
+		/// The names of the binaries to be extracted from the archive.
+		contents: Vec<String>,
+	},
+	/// The source is a git repository.
+	Git {
+		/// The url of the repository.
+		url: Url,
+		branch: Option<String>,
+		package: String,
+		binaries: Vec<String>,
+		version: Option<String>,
+	},
+}
+
+impl Source {
+	async fn build_binaries(
+		path: &Path,
+		package: &str,
+		names: Vec<(&String, PathBuf)>,
+		status: impl Status,
+	) -> Result<(), Error> {
+		if cmd("cargo", vec!["--version"]).stdout_null().stderr_null().run().is_err() {
+			return Err(Error::MissingBinary(format!(
+				"cargo is required to build `{package}` from source"
+			)));
+		}
+		// Build binaries and then copy to cache and target
+		let binaries: Vec<_> = names.iter().map(|(name, _)| name.as_str()).collect();
+		let build = cmd("cargo", Self::build_args(path, package, &binaries));
+		debug!(package, path = %path.display(), "building binaries");
+		let reader = BuildCache::detect(path).apply(build).dir(path).stderr_to_stdout().reader()?;
+		let mut output = std::io::BufReader::new(reader).lines();
+		let mut compiled = 0;
+		while let Some(Ok(line)) = output.next() {
+			trace!(package, "{line}");
+			let line = line.trim();
+			if line.starts_with("Compiling") {
+				compiled += 1;
+				status.progress(Progress {
+					step: line,
+					done: compiled,
+					total: None,
+					unit: Unit::Crates,
+				});
+			} else {
+				status.update(line);
+			}
+		}
+		for (name, dest) in names {
+			Self::create_parent(&dest)?;
+			copy(path.join(format!("target/release/{name}")), &dest)?;
+			Digest::record(&dest, None, false)?;
+		}
+		Ok(())
+	}
+
+	/// The arguments building only the `binaries` of `package` within the source code at `path`,
+	/// with the dependencies locked by the release when it has a lockfile, so that the binaries
+	/// built match those released.
+	///
+	/// # Arguments
+	///
+	/// * `path` - the source code
+	/// * `package` - the package providing the binaries
+	/// * `binaries` - the binaries built
+	fn build_args(path: &Path, package: &str, binaries: &[&str]) -> Vec<String> {
+		let mut args: Vec<String> =
+			["build", "--release", "-p", package].into_iter().map(String::from).collect();
+		if path.join("Cargo.lock").exists() {
+			args.push("--locked".into());
+		}
+		for binary in binaries {
+			args.extend(["--bin".into(), binary.to_string()]);
+		}
+		args
+	}
+
+	/// The directory within `cache` into which the repository at `url` is cloned to build
+	/// `version`, which is kept apart from the clones of other versions.
+	///
+	/// # Arguments
+	///
+	/// * `cache` - path to the local cache
+	/// * `url` - the url of the repository
+	/// * `version` - the version built, if any
+	fn working_dir(cache: &Path, url: &Url, version: Option<&str>) -> Result<PathBuf, Error> {
+		let name = Self::versioned_name(GitHub::name(url)?, version);
+		Ok(cache.join(".src").join(name))
+	}
+
+	/// Downloads `url` to `path`, which is only written once the download is verified, recording
+	/// its verification within the audit log of `cache`. Fails when the download does not match
+	/// its signature or cannot be verified while unverified artifacts are refused.
+	///
+	/// # Arguments
+	///
+	/// * `url` - the url downloaded
+	/// * `path` - the destination of the download
+	/// * `cache` - path to the local cache
+	/// * `status` - used to observe status updates
+	async fn download(
+		url: &str,
+		path: &Path,
+		cache: &Path,
+		status: impl Status,
+	) -> Result<Verification, Error> {
+		let mirrors = MIRRORS.read().unwrap_or_else(|e| e.into_inner()).clone();
+		let (source, mut response) = Self::request(url, &mirrors).await?;
+		debug!(url = source, path = %path.display(), "downloading");
+		let step = format!("Downloading from {source}");
+		let total = response.content_length();
+		let partial = Self::partial(path);
+		Self::create_parent(&partial)?;
+		let mut file = File::create(&partial)?;
+		let (mut done, mut throttle) = (0, Throttle::default());
+		while let Some(chunk) = response.chunk().await? {
+			file.write_all(&chunk)?;
+			done += chunk.len() as u64;
+			let progress = Progress { step: &step, done, total, unit: Unit::Bytes };
+			if throttle.ready(&progress, 1024 * 1024) {
+				status.progress(progress);
+			}
+		}
+		debug!(url = source, size = done, "downloaded");
+		drop(file);
+		status.update(&format!("Verifying {source}..."));
+		let verification = match verification::verify(&source, url, &partial).await {
+			Ok(verification) => verification,
+			Err(e) => {
+				Verification::new(source, Outcome::Unverified { reason: e.to_string() })
+					.record(cache)?;
+				remove_file(&partial)?;
+				return Err(e);
+			},
+		};
+		debug!(%verification, "verified");
+		verification.record(cache)?;
+		if let Err(e) = verification.enforce() {
+			remove_file(&partial)?;
+			return Err(e);
+		}
+		rename(&partial, path)?;
+		// Make executable
+		Self::set_executable(path)?;
+		Ok(verification)
+	}
+
+	/// Requests `url` from each of the `mirrors` in turn, falling back to `url` itself, returning
+	/// the url responding along with its response.
+	///
+	/// # Arguments
+	///
+	/// * `url` - the canonical url of the download
+	/// * `mirrors` - the base urls of the mirrors
+	async fn request(url: &str, mirrors: &[Url]) -> Result<(String, reqwest::Response), Error> {
+		for mirrored in Self::mirrored(url, mirrors) {
+			match reqwest::get(&mirrored).await.and_then(|r| r.error_for_status()) {
+				Ok(response) => return Ok((mirrored, response)),
+				Err(e) => warn!(url = mirrored, error = %e, "mirror unavailable"),
+			}
+		}
+		let response = reqwest::get(url).await?.error_for_status()?;
+		Ok((url.to_string(), response))
+	}
+
+	/// The urls of the download at `url` within each of the `mirrors`, which mirror the path of
+	/// the canonical url, e.g. `https://artifacts.example.com/github/paritytech/polkadot-sdk/
+	/// releases/download/polkadot-v1.13.0/polkadot` for a mirror of GitHub at
+	/// `https://artifacts.example.com/github`.
+	///
+	/// # Arguments
+	///
+	/// * `url` - the canonical url of the download
+	/// * `mirrors` - the base urls of the mirrors
+	fn mirrored(url: &str, mirrors: &[Url]) -> Vec<String> {
+		let Ok(url) = Url::parse(url) else { return Vec::new() };
+		let path = match url.query() {
+			Some(query) => format!("{}?{query}", url.path()),
+			None => url.path().to_string(),
+		};
+		mirrors
+			.iter()
+			.map(|mirror| format!("{}{path}", mirror.as_str().trim_end_matches('/')))
+			.collect()
+	}
+
+	/// The file a download to `path` is written to until complete.
+	///
+	/// # Arguments
+	///
+	/// * `path` - the destination of the download
+	fn partial(path: &Path) -> PathBuf {
+		let mut name = path.file_name().unwrap_or_default().to_os_string();
+		name.push(format!(".{PARTIAL_EXTENSION}"));
+		path.with_file_name(name)
+	}
+
+	fn extract(archive: &Path, destination: &Path) -> Result<(), Error> {
+		let mut archive = Archive::new(GzDecoder::new(File::open(archive)?));
+		archive.unpack(destination)?;
+		Ok(())
+	}
+
+	fn set_executable(path: &Path) -> Result<(), Error> {
+		let mut perms = metadata(path)?.permissions();
+		perms.set_mode(0o755);
+		std::fs::set_permissions(path, perms)?;
+		Ok(())
+	}
+
+	/// Processes the binary source, by either downloading the binary from a url or by cloning a
+	/// git repository and building locally from the resulting source code, returning the
+	/// verification of the artifact sourced, if not already cached.
+	///
+	/// # Arguments
+	///
+	/// * `cache` - path to the local cache
+	/// * `status` - used to observe status updates
+	pub async fn process(
+		&self,
+		cache: &Path,
+		status: impl Status,
+	) -> Result<Option<Verification>, Error> {
+		// Download or clone and build from source
+		match self {
+			Source::Url { name, version, url } => {
+				// Check if source already exist within cache
+				let path = Self::cached_path(cache, name, Some(version));
+				if path.exists() {
+					return Ok(None);
+				}
+
+				// Download required version of binaries
+				status.update(&format!("Downloading from {url}..."));
+				let verification = Self::download(&url, &path, cache, status).await?;
+				Digest::record(&path, Some(url), false)?;
+				Ok(Some(verification))
+			},
+			Source::Archive { version, url, contents } => {
+				// Check if all binaries already exist within cache
+				let paths: Vec<_> = contents
+					.iter()
+					.map(|n| (n, Self::cached_path(cache, n, Some(version))))
+					.collect();
+				if paths.iter().all(|(_, path)| path.exists()) {
+					return Ok(None);
+				}
+
+				// Download and extract the archive into a temporary working directory
+				status.update(&format!("Downloading from {url}..."));
+				let working_dir = tempfile::tempdir()?;
+				let archive = working_dir.path().join("archive.tar.gz");
+				let verification = Self::download(&url, &archive, cache, status).await?;
+				status.update("Extracting archive...");
+				Self::extract(&archive, working_dir.path())?;
+
+				// Copy the required binaries, wherever they are located within the archive
+				for (name, dest) in paths {
+					let binary = WalkDir::new(working_dir.path())
+						.into_iter()
+						.filter_map(|e| e.ok())
+						.find(|e| e.file_type().is_file() && e.file_name() == name.as_str())
+						.ok_or(Error::MissingBinary(format!(
+							"`{name}` was not found within the archive"
+						)))?;
+					Self::create_parent(&dest)?;
+					copy(binary.path(), &dest)?;
+					Self::set_executable(&dest)?;
+					Digest::record(&dest, Some(url), true)?;
+				}
+				Ok(Some(verification))
+			},
+			Source::Git { url, branch, package, binaries, version } => {
+				// Check if all binaries already exist within cache
+				let paths: Vec<_> = binaries
+					.iter()
+					.map(|n| (n, Self::cached_path(cache, n, version.as_deref())))
+					.collect();
+				if paths.iter().all(|(_, path)| path.exists()) {
+					return Ok(None);
+				}
+
+				// Binaries built from source have no signature or attestation.
+				let verification = Verification::new(
+					url.as_str(),
+					Outcome::Unverified { reason: "built from source".into() },
+				);
+				verification.record(cache)?;
+				verification.enforce()?;
+
+				let working_dir = Self::working_dir(cache, url, version.as_deref())?;
+				let working_dir = Path::new(&working_dir);
+
+				// Clone repository into working directory, at the tag or branch of the release
+				if !working_dir.exists() {
+					status.update(&format!("Cloning {url}..."));
+					let cloned = match branch {
+						Some(reference) => Git::clone_tag(url, working_dir, reference, status),
+						None => Git::clone(url, working_dir, None, status),
+					};
+					if let Err(e) = cloned {
+						if working_dir.exists() {
+							// Preserve original error
+							let _ = Self::remove(working_dir);
+						}
+						return Err(e.into());
+					}
+				}
+				// Build binaries and finally remove working directory
+				if let Err(e) = Self::build_binaries(working_dir, package, paths, status).await {
+					if working_dir.exists() {
+						// Preserve original error
+						let _ = Self::remove(working_dir);
+					}
+					return Err(e.into());
+				}
+				Self::remove(working_dir)?;
+				Ok(Some(verification))
+			},
+		}
+	}
+
+	fn remove(path: &Path) -> Result<(), Error> {
+		remove_dir_all(path)?;
+		if let Some(source) = path.parent() {
+			if source.exists() && source.read_dir().map(|mut i| i.next().is_none()).unwrap_or(false)
+			{
+				remove_dir_all(source)?;
+			}
+		}
+		Ok(())
+	}
+
+	/// The path of the binary named `name` within `cache`: `bins/<name>/<version>/<name>`, so
+	/// that each version is kept apart and can be listed or pruned.
+	///
+	/// # Arguments
+	///
+	/// * `cache` - path to the local cache
+	/// * `name` - the name of the binary
+	/// * `version` - the version of the binary, if built from a release
+	pub fn cached_path(cache: &Path, name: &str, version: Option<&str>) -> PathBuf {
+		cache.join(BINARIES_DIR).join(name).join(version.unwrap_or(LATEST)).join(name)
+	}
+
+	/// Creates the parent directory of `path`, if missing.
+	fn create_parent(path: &Path) -> Result<(), Error> {
+		if let Some(parent) = path.parent() {
+			create_dir_all(parent)?;
+		}
+		Ok(())
+	}
+
+	/// A versioned name of a binary.
+	///
+	/// # Arguments
+	///
+	/// * `version` - an optional version to be appended to the binary name
+	pub fn versioned_name(name: &str, version: Option<&str>) -> String {
+		match version {
+			Some(version) => format!("{name}-{version}"),
+			None => name.to_string(),
+		}
+	}
+}
+
+/// Trait for observing status updates.
+pub trait Status: Copy {
+	/// Update the observer with the provided `status`.
+	fn update(&self, status: &str);
+
+	/// Update the observer with the `progress` of a long-running step, reported as a status
+	/// update unless rendered otherwise.
+	fn progress(&self, progress: Progress) {
+		self.update(&progress.to_string())
+	}
+}
+
+impl Status for () {
+	// no-op: status updates are ignored
+	fn update(&self, _: &str) {}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+
+	const TESTING_POLKADOT_VERSION: &str = "v1.7.0";
+	const POLKADOT_BINARY: &str = "polkadot-v1.7.0";
+
+	#[tokio::test]
+	async fn test_process_url() -> Result<()> {
+		let temp_dir = tempfile::tempdir().expect("Could not create temp dir");
+		let cache = PathBuf::from(temp_dir.path());
+
+		let source = Source::Url {
+			name: "polkadot".to_string(),
+			version: TESTING_POLKADOT_VERSION.to_string(),
+			url: "https://github.com/paritytech/polkadot-sdk/releases/download/polkadot-v1.7.0/polkadot".to_string()
+		};
+		let verification = source.process(&cache, ()).await?.expect("downloaded");
+		assert!(Source::cached_path(&cache, "polkadot", Some(TESTING_POLKADOT_VERSION)).exists());
+		// The verification is recorded, whether or not it could be verified.
+		let audit = std::fs::read_to_string(cache.join(verification::AUDIT_FILE))?;
+		assert!(audit.contains(&verification.artifact));
+		// The digest is recorded, along with the url to source the binary from again.
+		let binary = Source::cached_path(&cache, "polkadot", Some(TESTING_POLKADOT_VERSION));
+		let digest = Digest::load(&binary)?.expect("recorded");
+		assert_eq!(digest.url.as_deref(), Some(verification.artifact.as_str()));
+		assert_eq!(source.process(&cache, ()).await?, None);
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_process_archive_skips_cached_binaries() -> Result<()> {
+		let temp_dir = tempfile::tempdir().expect("Could not create temp dir");
+		let cache = PathBuf::from(temp_dir.path());
+		let binary = Source::cached_path(&cache, "substrate-contracts-node", Some("v0.41.0"));
+		create_dir_all(binary.parent().unwrap())?;
+		File::create(binary)?;
+
+		let source = Source::Archive {
+			version: "v0.41.0".to_string(),
+			url: "https://invalid.url/substrate-contracts-node-linux.tar.gz".to_string(),
+			contents: vec!["substrate-contracts-node".to_string()],
+		};
+		// No download is attempted as the binary already exists within the cache
+		source.process(&cache, ()).await?;
+		Ok(())
+	}
+
+	#[test]
+	fn test_extract() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		// Create an archive containing a nested binary
+		let archive_path = temp_dir.path().join("archive.tar.gz");
+		{
+			let encoder = flate2::write::GzEncoder::new(
+				File::create(&archive_path)?,
+				flate2::Compression::default(),
+			);
+			let mut builder = tar::Builder::new(encoder);
+			let mut header = tar::Header::new_gnu();
+			let contents = b"binary";
+			header.set_size(contents.len() as u64);
+			header.set_mode(0o755);
+			header.set_cksum();
+			builder.append_data(&mut header, "artifacts/node", &contents[..])?;
+			builder.into_inner()?.finish()?;
+		}
+		let destination = temp_dir.path().join("extracted");
+		Source::extract(&archive_path, &destination)?;
+		assert!(destination.join("artifacts/node").exists());
+		Ok(())
+	}
+
+	#[test]
+	fn test_versioned_name() -> Result<()> {
+		let versioned_name =
+			Source::versioned_name("polkadot", Some(&TESTING_POLKADOT_VERSION.to_string()));
+		assert_eq!(versioned_name, POLKADOT_BINARY);
+
+		let versioned_name_no_version = Source::versioned_name("polkadot", None);
+		assert_eq!(versioned_name_no_version, "polkadot");
+		Ok(())
+	}
+
+	#[test]
+	fn test_cached_path() {
+		assert_eq!(
+			Source::cached_path(Path::new("/cache"), "polkadot", Some("v1.7.0")),
+			PathBuf::from("/cache/bins/polkadot/v1.7.0/polkadot")
+		);
+		assert_eq!(
+			Source::cached_path(Path::new("/cache"), "parachain-node", None),
+			PathBuf::from("/cache/bins/parachain-node/latest/parachain-node")
+		);
+	}
+
+	#[test]
+	fn test_build_args() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let path = temp_dir.path();
+		assert_eq!(
+			Source::build_args(path, "polkadot", &["polkadot", "polkadot-execute-worker"]),
+			[
+				"build",
+				"--release",
+				"-p",
+				"polkadot",
+				"--bin",
+				"polkadot",
+				"--bin",
+				"polkadot-execute-worker"
+			]
+		);
+		// The dependencies of the release are used when locked.
+		std::fs::write(path.join("Cargo.lock"), "")?;
+		assert_eq!(
+			Source::build_args(path, "contracts-node", &["substrate-contracts-node"]),
+			[
+				"build",
+				"--release",
+				"-p",
+				"contracts-node",
+				"--locked",
+				"--bin",
+				"substrate-contracts-node"
+			]
+		);
+		Ok(())
+	}
+
+	#[test]
+	fn test_working_dir() -> Result<()> {
+		let cache = Path::new("/cache");
+		let url = Url::parse("https://github.com/paritytech/polkadot-sdk")?;
+		assert_eq!(
+			Source::working_dir(cache, &url, Some("polkadot-stable2407"))?,
+			cache.join(".src").join("polkadot-sdk-polkadot-stable2407")
+		);
+		assert_eq!(
+			Source::working_dir(cache, &url, None)?,
+			cache.join(".src").join("polkadot-sdk")
+		);
+		Ok(())
+	}
+
+	#[test]
+	fn test_mirrored() -> Result<()> {
+		let url = "https://github.com/paritytech/polkadot-sdk/releases/download/polkadot-v1.13.0/polkadot";
+		let mirrors = [
+			Url::parse("https://artifacts.example.com/github/")?,
+			Url::parse("http://mirror.internal:8080")?,
+		];
+		assert_eq!(
+			Source::mirrored(url, &mirrors),
+			[
+				"https://artifacts.example.com/github/paritytech/polkadot-sdk/releases/download/polkadot-v1.13.0/polkadot",
+				"http://mirror.internal:8080/paritytech/polkadot-sdk/releases/download/polkadot-v1.13.0/polkadot"
+			]
+		);
+		assert!(Source::mirrored(url, &[]).is_empty());
+		Ok(())
+	}
+
+	#[test]
+	fn test_partial() {
+		assert_eq!(
+			Source::partial(Path::new("/cache/polkadot-v1.7.0")),
+			PathBuf::from("/cache/polkadot-v1.7.0.part")
+		);
+	}
+}
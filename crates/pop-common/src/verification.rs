@@ -0,0 +1,339 @@
+// SPDX-License-Identifier: GPL-3.0
+use crate::Error;
+use chrono::{SecondsFormat, Utc};
+use duct::cmd;
+use serde::{Deserialize, Serialize};
+use std::{
+	ffi::OsStr,
+	fmt,
+	fs::{self, OpenOptions},
+	io::Write,
+	path::Path,
+	sync::atomic::{AtomicBool, Ordering},
+};
+use tracing::debug;
+
+/// The file within the cache in which the verification of each artifact sourced is recorded, one
+/// JSON object per line.
+pub const AUDIT_FILE: &str = "audit.jsonl";
+/// The extension of the detached GPG signature published alongside a release asset.
+const SIGNATURE_EXTENSION: &str = "asc";
+
+/// The fingerprints of the keys signing the releases of each repository, so that a signature made
+/// by any other key within the keyring of the user, e.g. one served by a mirror, is refused.
+const SIGNING_KEYS: [(&str, &[&str]); 1] = [
+	// Parity Security Team.
+	("paritytech/polkadot-sdk", &["90BD75EBBB8E95CB3DA6078F94A4029AB4B35DAE"]),
+];
+
+/// Whether artifacts which cannot be verified are refused.
+static REQUIRE_SIGNED: AtomicBool = AtomicBool::new(false);
+
+/// Refuses the artifacts sourced which cannot be verified by a signature or attestation, rather
+/// than only recording that they are unverified.
+///
+/// # Arguments
+///
+/// * `enabled` - whether unverified artifacts are refused
+pub fn set_require_signed(enabled: bool) {
+	REQUIRE_SIGNED.store(enabled, Ordering::Relaxed);
+}
+
+/// Whether the artifacts sourced which cannot be verified are refused.
+pub fn require_signed() -> bool {
+	REQUIRE_SIGNED.load(Ordering::Relaxed)
+}
+
+/// How an artifact was verified.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case", tag = "kind")]
+pub enum Outcome {
+	/// Verified by its detached GPG signature, made by the key with `fingerprint`.
+	Signature { fingerprint: String },
+	/// Verified by a GitHub artifact attestation of `repository`.
+	Attestation { repository: String },
+	/// Not verified, for `reason`.
+	Unverified { reason: String },
+}
+
+/// The verification of an artifact sourced, as recorded within the audit log of the cache.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Verification {
+	/// When the artifact was verified, in RFC 3339 format (UTC).
+	pub timestamp: String,
+	/// The artifact: the url downloaded or the repository built from.
+	pub artifact: String,
+	/// How the artifact was verified.
+	pub outcome: Outcome,
+}
+
+impl Verification {
+	/// The verification of `artifact`, completed now.
+	///
+	/// # Arguments
+	///
+	/// * `artifact` - the url downloaded or the repository built from
+	/// * `outcome` - how the artifact was verified
+	pub fn new(artifact: impl Into<String>, outcome: Outcome) -> Self {
+		Self {
+			timestamp: Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true),
+			artifact: artifact.into(),
+			outcome,
+		}
+	}
+
+	/// Whether the artifact was verified, by either its signature or an attestation.
+	pub fn is_verified(&self) -> bool {
+		!matches!(self.outcome, Outcome::Unverified { .. })
+	}
+
+	/// Fails when the artifact is unverified and unverified artifacts are refused, as per
+	/// `--require-signed`.
+	pub fn enforce(&self) -> Result<(), Error> {
+		self.check(require_signed())
+	}
+
+	/// Fails when the artifact is unverified and verification is `required`.
+	fn check(&self, required: bool) -> Result<(), Error> {
+		match &self.outcome {
+			Outcome::Unverified { reason } if required => {
+				Err(Error::Unverified { artifact: self.artifact.clone(), reason: reason.clone() })
+			},
+			_ => Ok(()),
+		}
+	}
+
+	/// Appends the verification to the audit log within `cache`.
+	///
+	/// # Arguments
+	///
+	/// * `cache` - path to the local cache
+	pub fn record(&self, cache: &Path) -> Result<(), Error> {
+		fs::create_dir_all(cache)?;
+		let line = serde_json::to_string(self)
+			.map_err(|e| Error::Config(format!("unable to record the verification: {e}")))?;
+		let mut file = OpenOptions::new().create(true).append(true).open(cache.join(AUDIT_FILE))?;
+		writeln!(file, "{line}")?;
+		Ok(())
+	}
+}
+
+impl fmt::Display for Verification {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match &self.outcome {
+			Outcome::Signature { fingerprint } => {
+				write!(f, "{} verified by its signature (key {fingerprint})", self.artifact)
+			},
+			Outcome::Attestation { repository } => {
+				write!(f, "{} verified by an attestation of {repository}", self.artifact)
+			},
+			Outcome::Unverified { reason } => write!(f, "{} not verified: {reason}", self.artifact),
+		}
+	}
+}
+
+/// Verifies the artifact downloaded from `url` to `path`: by the GPG signature published
+/// alongside its canonical url by a release key of its repository, when any, and otherwise by a
+/// GitHub artifact attestation. Fails when the signature does not match or is made by another
+/// key, as the artifact was then tampered with.
+///
+/// # Arguments
+///
+/// * `url` - the url the artifact was downloaded from, such as a mirror
+/// * `canonical` - the canonical url of the artifact, identifying the repository releasing it
+/// * `path` - the artifact downloaded
+pub(crate) async fn verify(url: &str, canonical: &str, path: &Path) -> Result<Verification, Error> {
+	let mut reasons = Vec::new();
+	match signature(canonical, path).await? {
+		Ok(fingerprint) => return Ok(Verification::new(url, Outcome::Signature { fingerprint })),
+		Err(reason) => reasons.push(reason),
+	}
+	match attestation(canonical, path) {
+		Ok(repository) => return Ok(Verification::new(url, Outcome::Attestation { repository })),
+		Err(reason) => reasons.push(reason),
+	}
+	Ok(Verification::new(url, Outcome::Unverified { reason: reasons.join("; ") }))
+}
+
+/// Verifies the artifact at `path` by the detached signature published at its canonical `url`
+/// with an `.asc` extension, made by a release key of the repository releasing it, returning the
+/// fingerprint of the signing key, or why it could not be verified.
+async fn signature(url: &str, path: &Path) -> Result<Result<String, String>, Error> {
+	let Some(keys) = repository(url).and_then(|r| signing_keys(&r)) else {
+		return Ok(Err("no release signing key is known for it".into()));
+	};
+	let response = match reqwest::get(format!("{url}.{SIGNATURE_EXTENSION}")).await {
+		Ok(response) if response.status().is_success() => response,
+		_ => return Ok(Err("no signature is published".into())),
+	};
+	let mut name = path.file_name().unwrap_or_default().to_os_string();
+	name.push(format!(".{SIGNATURE_EXTENSION}"));
+	let signature = path.with_file_name(name);
+	fs::write(&signature, response.bytes().await?)?;
+	let args = ["--batch", "--status-fd", "1", "--verify"].map(OsStr::new);
+	let output = cmd("gpg", args.into_iter().chain([signature.as_os_str(), path.as_os_str()]))
+		.stdout_capture()
+		.stderr_null()
+		.unchecked()
+		.run();
+	fs::remove_file(&signature)?;
+	let Ok(output) = output else {
+		return Ok(Err("gpg is required to verify its signature".into()));
+	};
+	let status = String::from_utf8_lossy(&output.stdout);
+	debug!(url, %status, "verified signature");
+	gpg_status(&status, keys).map_err(|reason| Error::Unverified { artifact: url.into(), reason })
+}
+
+/// The fingerprints of the keys signing the releases of `repository`, if known.
+fn signing_keys(repository: &str) -> Option<&'static [&'static str]> {
+	SIGNING_KEYS.iter().find(|(r, _)| *r == repository).map(|(_, keys)| *keys)
+}
+
+/// The fingerprint of the key which signed an artifact, as per the machine-readable `status` of
+/// `gpg --verify`, or why the artifact could not be verified. Fails when the signature is bad, or
+/// made by a revoked key or by a key other than the release `keys`.
+fn gpg_status(status: &str, keys: &[&str]) -> Result<Result<String, String>, String> {
+	let mut fingerprint = None;
+	for line in status.lines().filter_map(|l| l.strip_prefix("[GNUPG:] ")) {
+		let mut words = line.split_whitespace();
+		match (words.next(), words.next()) {
+			// Followed by the fingerprint of the primary key when made by a subkey.
+			(Some("VALIDSIG"), Some(fpr)) => {
+				let primary = words.nth(8);
+				if !keys.iter().any(|k| *k == fpr || Some(*k) == primary) {
+					return Err(format!("it was signed by {fpr}, which is not a release key"));
+				}
+				fingerprint = Some(fpr.to_string());
+			},
+			(Some("BADSIG"), _) => return Err("its signature does not match".into()),
+			(Some("REVKEYSIG"), Some(key)) => {
+				return Err(format!("it was signed by the revoked key {key}"))
+			},
+			(Some("EXPKEYSIG"), Some(key)) => {
+				return Ok(Err(format!("it was signed by the expired key {key}")))
+			},
+			(Some("NO_PUBKEY"), Some(key)) => {
+				return Ok(Err(format!("the signing key {key} is not within the GPG keyring")))
+			},
+			_ => {},
+		}
+	}
+	Ok(fingerprint.ok_or_else(|| "its signature could not be checked".to_string()))
+}
+
+/// Verifies the artifact at `path` by a GitHub artifact attestation of the repository releasing
+/// it at `url`, using the GitHub CLI, returning the repository, or why it could not be verified.
+fn attestation(url: &str, path: &Path) -> Result<String, String> {
+	let Some(repository) = repository(url) else {
+		return Err("it is not released on GitHub, so has no attestation".into());
+	};
+	let args = [OsStr::new("attestation"), "verify".as_ref(), path.as_os_str()];
+	let verified = cmd("gh", args.into_iter().chain(["--repo".as_ref(), repository.as_ref()]))
+		.stdout_null()
+		.stderr_null()
+		.unchecked()
+		.run();
+	match verified {
+		Ok(output) if output.status.success() => Ok(repository),
+		Ok(_) => Err(format!("no attestation of {repository} matches it")),
+		Err(_) => Err("the GitHub CLI (gh) is required to verify its attestation".into()),
+	}
+}
+
+/// The GitHub repository, as `<org>/<name>`, releasing the asset at `url`, e.g.
+/// `paritytech/polkadot-sdk` for
+/// `https://github.com/paritytech/polkadot-sdk/releases/download/polkadot-v1.13.0/polkadot`.
+fn repository(url: &str) -> Option<String> {
+	let url = url::Url::parse(url).ok()?;
+	if url.host_str() != Some("github.com") {
+		return None;
+	}
+	let segments: Vec<_> = url.path_segments()?.collect();
+	match segments.as_slice() {
+		[org, name, "releases", "download", ..] => Some(format!("{org}/{name}")),
+		_ => None,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+
+	#[test]
+	fn gpg_status_works() {
+		let keys = signing_keys("paritytech/polkadot-sdk").expect("pinned");
+		let valid = "[GNUPG:] NEWSIG\n\
+			[GNUPG:] GOODSIG 9D4B2B6EB8F97156 Parity Security Team\n\
+			[GNUPG:] VALIDSIG 90BD75EBBB8E95CB3DA6078F94A4029AB4B35DAE 2024-06-12 0";
+		assert_eq!(
+			gpg_status(valid, keys),
+			Ok(Ok("90BD75EBBB8E95CB3DA6078F94A4029AB4B35DAE".into()))
+		);
+		// Made by a subkey of a release key.
+		let subkey = "[GNUPG:] VALIDSIG 1111111111111111111111111111111111111111 2024-06-12 \
+			1718000000 0 4 0 1 10 00 90BD75EBBB8E95CB3DA6078F94A4029AB4B35DAE";
+		assert!(matches!(gpg_status(subkey, keys), Ok(Ok(_))));
+		// Made by any other key within the keyring.
+		let other = "[GNUPG:] VALIDSIG 1111111111111111111111111111111111111111 2024-06-12 0";
+		assert!(gpg_status(other, keys).is_err());
+		assert!(gpg_status("[GNUPG:] BADSIG 9D4B2B6EB8F97156 Parity Security Team", keys).is_err());
+		assert!(
+			gpg_status("[GNUPG:] REVKEYSIG 9D4B2B6EB8F97156 Parity Security Team", keys).is_err()
+		);
+		assert_eq!(
+			gpg_status("[GNUPG:] ERRSIG 9D4B2B6EB8F97156 1 10 00 1718000000 9\n[GNUPG:] NO_PUBKEY 9D4B2B6EB8F97156", keys),
+			Ok(Err("the signing key 9D4B2B6EB8F97156 is not within the GPG keyring".into()))
+		);
+		assert!(matches!(gpg_status("", keys), Ok(Err(_))));
+	}
+
+	#[test]
+	fn signing_keys_works() {
+		assert!(signing_keys("paritytech/polkadot-sdk").is_some());
+		assert_eq!(signing_keys("r0gue-io/pop-node"), None);
+	}
+
+	#[test]
+	fn repository_works() {
+		assert_eq!(
+			repository(
+				"https://github.com/paritytech/polkadot-sdk/releases/download/polkadot-v1.13.0/polkadot"
+			),
+			Some("paritytech/polkadot-sdk".into())
+		);
+		assert_eq!(repository("https://github.com/paritytech/polkadot-sdk"), None);
+		assert_eq!(repository("https://example.com/org/repo/releases/download/v1/bin"), None);
+	}
+
+	#[test]
+	fn check_works() {
+		let unverified =
+			Verification::new("https://example.com/bin", Outcome::Unverified { reason: "".into() });
+		let signed = Verification::new(
+			"https://example.com/bin",
+			Outcome::Signature { fingerprint: "90BD75EB".into() },
+		);
+		assert!(unverified.check(false).is_ok());
+		assert!(matches!(unverified.check(true), Err(Error::Unverified { .. })));
+		assert!(signed.check(true).is_ok());
+		assert!(signed.is_verified() && !unverified.is_verified());
+	}
+
+	#[test]
+	fn record_works() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let verification = Verification::new(
+			"https://github.com/paritytech/polkadot-sdk/releases/download/polkadot-v1.13.0/polkadot",
+			Outcome::Attestation { repository: "paritytech/polkadot-sdk".into() },
+		);
+		verification.record(temp_dir.path())?;
+		verification.record(temp_dir.path())?;
+		let log = fs::read_to_string(temp_dir.path().join(AUDIT_FILE))?;
+		let recorded: Vec<Verification> =
+			log.lines().map(serde_json::from_str).collect::<Result<_, _>>()?;
+		assert_eq!(recorded, vec![verification.clone(), verification]);
+		Ok(())
+	}
+}
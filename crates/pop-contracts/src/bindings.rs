@@ -0,0 +1,360 @@
+// SPDX-License-Identifier: GPL-3.0
+use crate::{
+	errors::Error,
+	new::camel_case,
+	utils::metadata::{array, find, load_metadata},
+};
+use serde_json::Value;
+use std::{
+	collections::BTreeMap,
+	fs,
+	path::{Path, PathBuf},
+};
+
+/// Generates TypeScript bindings from the metadata of the built contract at `path`, written to
+/// `<output>/<contract name>.ts`. Returns the path of the generated file.
+///
+/// # Arguments
+///
+/// * `path` - path to the contract project
+/// * `output` - the directory the bindings are written to
+pub fn generate_typescript_bindings(
+	path: &Option<PathBuf>,
+	output: &Path,
+) -> Result<PathBuf, Error> {
+	let metadata = load_metadata(path)?.ok_or(Error::MetadataParsing(
+		"the metadata could not be found, build the contract first".into(),
+	))?;
+	let name = metadata["contract"]["name"]
+		.as_str()
+		.ok_or(Error::MetadataParsing("the metadata does not contain a contract name".into()))?;
+	let bindings = typescript_bindings(&metadata)?;
+	fs::create_dir_all(output)?;
+	let file = output.join(format!("{name}.ts"));
+	fs::write(&file, bindings)?;
+	Ok(file)
+}
+
+/// Generates TypeScript interfaces for the constructors, messages and events of a contract from
+/// its metadata.
+///
+/// # Arguments
+///
+/// * `metadata` - the metadata of the contract
+pub fn typescript_bindings(metadata: &Value) -> Result<String, Error> {
+	let spec = metadata
+		.get("spec")
+		.ok_or(Error::MetadataParsing("the metadata does not contain a `spec`".into()))?;
+	let contract = metadata["contract"]["name"].as_str().unwrap_or("contract");
+	let version = metadata["contract"]["version"].as_str().unwrap_or_default();
+	let interface = camel_case(contract);
+	let mut generator = Generator {
+		types: metadata["types"].as_array().map(|t| t.as_slice()).unwrap_or_default(),
+		..Default::default()
+	};
+
+	let mut constructors = Vec::new();
+	for constructor in array(&spec["constructors"]) {
+		let args = generator.args(&constructor["args"]);
+		constructors.push(member(&constructor["docs"], &constructor["label"], &args));
+	}
+	let mut messages = Vec::new();
+	for message in array(&spec["messages"]) {
+		let args = generator.args(&message["args"]);
+		let returns = match message["returnType"]["type"].as_u64() {
+			Some(id) => generator.message_return(id),
+			None => "null".to_string(),
+		};
+		let signature = format!("{{ args: {args}; returns: {returns} }}");
+		messages.push(member(&message["docs"], &message["label"], &signature));
+	}
+	let mut events = Vec::new();
+	for event in array(&spec["events"]) {
+		let fields = generator.args(&event["args"]);
+		events.push(member(&event["docs"], &event["label"], &fields));
+	}
+
+	let mut bindings = format!(
+		"// Generated by Pop CLI from the metadata of the `{contract}` contract (version {version}).\n\
+		 // Do not edit manually, regenerate with `pop build contract --types-output <dir>`.\n\
+		 // Integers wider than 32 bits are represented as `bigint`, bytes as hex encoded strings.\n"
+	);
+	for definition in &generator.definitions {
+		bindings.push_str(&format!("\n{definition}\n"));
+	}
+	for (kind, description, members) in [
+		("Constructors", "constructors of the contract, with their arguments", constructors),
+		("Messages", "messages of the contract, with their arguments and return types", messages),
+		("Events", "events emitted by the contract, with their fields", events),
+	] {
+		bindings.push_str(&format!(
+			"\n/** The {description}. */\nexport interface {interface}{kind} {{\n{}}}\n",
+			members.concat()
+		));
+	}
+	Ok(bindings)
+}
+
+/// Resolves types of the type registry to TypeScript types, defining a named type for each
+/// composite or enum type declared by the contract.
+#[derive(Default)]
+struct Generator<'a> {
+	types: &'a [Value],
+	/// The names of the types defined so far, by type identifier.
+	names: BTreeMap<u64, String>,
+	/// The definitions of the named types.
+	definitions: Vec<String>,
+}
+
+impl Generator<'_> {
+	/// The arguments (or fields) as an object type, e.g. `{ to: string; value: bigint }`.
+	fn args(&mut self, args: &Value) -> String {
+		let fields: Vec<_> = array(args)
+			.map(|a| {
+				let ty = a["type"]["type"].as_u64().map(|id| self.ts_type(id));
+				format!("{}: {}", key(&a["label"]), ty.unwrap_or_else(|| "unknown".into()))
+			})
+			.collect();
+		match fields.is_empty() {
+			true => "{}".to_string(),
+			false => format!("{{ {} }}", fields.join("; ")),
+		}
+	}
+
+	/// The return type of a message, where the `Result<T, LangError>` wrapping the return value of
+	/// every message (as of ink! v4) is unwrapped.
+	fn message_return(&mut self, id: u64) -> String {
+		if let Some(ty) = find(self.types, id) {
+			let params: Vec<_> = array(&ty["params"]).filter_map(|p| p["type"].as_u64()).collect();
+			if last_segment(ty) == Some("Result") && params.len() == 2 {
+				let error = find(self.types, params[1]).and_then(last_segment);
+				if error == Some("LangError") {
+					return self.ts_type(params[0]);
+				}
+			}
+		}
+		self.ts_type(id)
+	}
+
+	fn ts_type(&mut self, id: u64) -> String {
+		let Some(ty) = find(self.types, id) else {
+			return "unknown".to_string();
+		};
+		let def = &ty["def"];
+		if let Some(primitive) = def["primitive"].as_str() {
+			return match primitive {
+				"bool" => "boolean",
+				"char" | "str" => "string",
+				"u8" | "u16" | "u32" | "i8" | "i16" | "i32" => "number",
+				_ => "bigint",
+			}
+			.to_string();
+		}
+		for collection in ["sequence", "array"] {
+			if let Some(inner) = def[collection]["type"].as_u64() {
+				if find(self.types, inner).and_then(|t| t["def"]["primitive"].as_str())
+					== Some("u8")
+				{
+					return "string".to_string();
+				}
+				return format!("{}[]", self.ts_type(inner));
+			}
+		}
+		if let Some(inner) = def["compact"]["type"].as_u64() {
+			return self.ts_type(inner);
+		}
+		if let Some(tuple) = def["tuple"].as_array() {
+			return self.tuple(tuple.iter().filter_map(|t| t.as_u64()).collect());
+		}
+
+		let params: Vec<_> = array(&ty["params"]).filter_map(|p| p["type"].as_u64()).collect();
+		match last_segment(ty) {
+			Some("AccountId" | "Hash" | "H160" | "H256") => return "string".to_string(),
+			Some("Option") if params.len() == 1 => {
+				return format!("{} | null", self.ts_type(params[0]));
+			},
+			Some("Result") if params.len() == 2 => {
+				let (ok, err) = (self.ts_type(params[0]), self.ts_type(params[1]));
+				return format!("{{ Ok: {ok} }} | {{ Err: {err} }}");
+			},
+			_ => {},
+		}
+		if let Some(name) = self.names.get(&id) {
+			return name.clone();
+		}
+		let Some(name) = last_segment(ty) else {
+			// Anonymous types are inlined.
+			return self.body(def);
+		};
+		// Register the name before resolving the definition, so that recursive types terminate.
+		let name = match self.names.values().any(|n| n == name) {
+			true => format!("{name}{id}"),
+			false => name.to_string(),
+		};
+		self.names.insert(id, name.clone());
+		let body = self.body(def);
+		let docs = docs(&ty["docs"], "");
+		self.definitions.push(format!("{docs}export type {name} = {body};"));
+		name
+	}
+
+	/// The body of a composite or enum type.
+	fn body(&mut self, def: &Value) -> String {
+		if def.get("composite").is_some() {
+			return self.fields(&def["composite"]["fields"]);
+		}
+		let variants: Vec<_> = array(&def["variant"]["variants"])
+			.map(|v| {
+				let name = v["name"].as_str().unwrap_or_default();
+				match v["fields"].as_array().map_or(true, |f| f.is_empty()) {
+					true => format!("\"{name}\""),
+					false => format!("{{ {}: {} }}", key(&v["name"]), self.fields(&v["fields"])),
+				}
+			})
+			.collect();
+		match variants.is_empty() {
+			true => "never".to_string(),
+			false => variants.join(" | "),
+		}
+	}
+
+	/// The fields of a composite type or enum variant, as an object when named, otherwise as the
+	/// inner type or a tuple.
+	fn fields(&mut self, fields: &Value) -> String {
+		let named = array(fields).all(|f| f["name"].is_string());
+		let fields: Vec<_> = array(fields).collect();
+		if fields.is_empty() {
+			return "null".to_string();
+		}
+		if named {
+			let fields: Vec<_> = fields
+				.iter()
+				.map(|f| {
+					let ty = f["type"].as_u64().map(|id| self.ts_type(id));
+					format!("{}: {}", key(&f["name"]), ty.unwrap_or_else(|| "unknown".into()))
+				})
+				.collect();
+			return format!("{{ {} }}", fields.join("; "));
+		}
+		self.tuple(fields.iter().filter_map(|f| f["type"].as_u64()).collect())
+	}
+
+	fn tuple(&mut self, types: Vec<u64>) -> String {
+		match types.as_slice() {
+			[] => "null".to_string(),
+			[single] => self.ts_type(*single),
+			types => {
+				let types: Vec<_> = types.iter().map(|t| self.ts_type(*t)).collect();
+				format!("[{}]", types.join(", "))
+			},
+		}
+	}
+}
+
+fn last_segment(ty: &Value) -> Option<&str> {
+	array(&ty["path"]).filter_map(|s| s.as_str()).last()
+}
+
+/// An object key, quoted unless it is a valid identifier (e.g. `"PSP22::transfer"`).
+fn key(label: &Value) -> String {
+	let label = label.as_str().unwrap_or_default();
+	let identifier = label.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+		&& label.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+	match identifier {
+		true => label.to_string(),
+		false => format!("{label:?}"),
+	}
+}
+
+/// A documentation comment from the documentation lines of the metadata.
+fn docs(docs: &Value, indent: &str) -> String {
+	let lines: Vec<_> = array(docs).filter_map(|d| d.as_str()).map(|d| d.trim()).collect();
+	match lines.iter().all(|l| l.is_empty()) {
+		true => String::new(),
+		false => format!("{indent}/** {} */\n", lines.join(" ").trim()),
+	}
+}
+
+/// A member of an interface, along with its documentation.
+fn member(documentation: &Value, label: &Value, ty: &str) -> String {
+	format!("{}  {}: {ty};\n", docs(documentation, "  "), key(label))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+	use serde_json::json;
+
+	fn metadata() -> Value {
+		json!({
+			"contract": { "name": "my_token", "version": "0.1.0" },
+			"spec": {
+				"constructors": [
+					{ "label": "new", "docs": [" Creates a new token."],
+					  "args": [{ "label": "supply", "type": { "displayName": ["Balance"], "type": 0 } }] }
+				],
+				"messages": [
+					{ "label": "PSP22::transfer", "docs": [],
+					  "args": [
+						{ "label": "to", "type": { "type": 1 } },
+						{ "label": "value", "type": { "type": 0 } },
+						{ "label": "data", "type": { "type": 2 } }
+					  ],
+					  "returnType": { "displayName": ["ink", "MessageResult"], "type": 4 } },
+					{ "label": "owner", "docs": [], "args": [],
+					  "returnType": { "displayName": ["ink", "MessageResult"], "type": 8 } }
+				],
+				"events": [
+					{ "label": "Transfer", "docs": [], "args": [
+						{ "label": "from", "type": { "type": 7 } },
+						{ "label": "value", "type": { "type": 0 } }
+					] }
+				]
+			},
+			"types": [
+				{ "id": 0, "type": { "def": { "primitive": "u128" } } },
+				{ "id": 1, "type": { "path": ["ink_primitives", "types", "AccountId"], "def": { "composite": { "fields": [{ "type": 9 }] } } } },
+				{ "id": 2, "type": { "def": { "sequence": { "type": 3 } } } },
+				{ "id": 3, "type": { "def": { "primitive": "u8" } } },
+				{ "id": 4, "type": { "path": ["Result"], "params": [{ "name": "T", "type": 5 }, { "name": "E", "type": 10 }], "def": { "variant": {} } } },
+				{ "id": 5, "type": { "path": ["Result"], "params": [{ "name": "T", "type": 11 }, { "name": "E", "type": 6 }], "def": { "variant": {} } } },
+				{ "id": 6, "type": { "path": ["my_token", "my_token", "PSP22Error"], "docs": [" The errors of the token."], "def": { "variant": { "variants": [
+					{ "name": "Custom", "fields": [{ "type": 12 }] },
+					{ "name": "InsufficientBalance" }
+				] } } } },
+				{ "id": 7, "type": { "path": ["Option"], "params": [{ "name": "T", "type": 1 }], "def": { "variant": {} } } },
+				{ "id": 8, "type": { "path": ["Result"], "params": [{ "name": "T", "type": 1 }, { "name": "E", "type": 10 }], "def": { "variant": {} } } },
+				{ "id": 9, "type": { "def": { "array": { "len": 32, "type": 3 } } } },
+				{ "id": 10, "type": { "path": ["ink_primitives", "LangError"], "def": { "variant": { "variants": [{ "name": "CouldNotReadInput" }] } } } },
+				{ "id": 11, "type": { "def": { "tuple": [] } } },
+				{ "id": 12, "type": { "def": { "primitive": "str" } } }
+			]
+		})
+	}
+
+	#[test]
+	fn test_typescript_bindings() -> Result<()> {
+		let bindings = typescript_bindings(&metadata())?;
+		assert!(bindings.contains(
+			"/** The errors of the token. */\nexport type PSP22Error = { Custom: string } | \"InsufficientBalance\";"
+		));
+		assert!(bindings.contains(
+			"export interface MyTokenConstructors {\n  /** Creates a new token. */\n  new: { supply: bigint };\n}"
+		));
+		assert!(bindings.contains(
+			"  \"PSP22::transfer\": { args: { to: string; value: bigint; data: string }; returns: { Ok: null } | { Err: PSP22Error } };\n"
+		));
+		assert!(bindings.contains("  owner: { args: {}; returns: string };\n"));
+		assert!(bindings.contains(
+			"export interface MyTokenEvents {\n  Transfer: { from: string | null; value: bigint };\n}"
+		));
+		assert!(!bindings.contains("LangError"));
+		Ok(())
+	}
+
+	#[test]
+	fn test_typescript_bindings_fails_without_spec() {
+		assert!(matches!(typescript_bindings(&json!({})), Err(Error::MetadataParsing(..))));
+	}
+}
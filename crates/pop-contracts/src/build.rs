@@ -1,17 +1,312 @@
 // SPDX-License-Identifier: GPL-3.0
-use contract_build::{execute, ExecuteArgs};
-use std::path::PathBuf;
+use contract_build::{execute, BuildMode, BuildResult, ExecuteArgs, ImageVariant};
+use pop_common::BuildCache;
+use sp_core::hashing::blake2_256;
+use std::{
+	fs,
+	path::{Path, PathBuf},
+};
+use toml_edit::{DocumentMut, Item};
 
-use crate::utils::helpers::get_manifest_path;
+use crate::{
+	errors::Error,
+	report::record_build_report,
+	utils::{
+		helpers::{get_manifest_path, is_contract, to_hex},
+		metadata::metadata_path,
+	},
+};
 
 pub fn build_smart_contract(path: &Option<PathBuf>) -> anyhow::Result<String> {
+	// Execute the build and log the output of the build
+	let result = build(path, BuildMode::Debug)?;
+	record_build_report(&result)?;
+	let formatted_result = result.display();
+
+	Ok(formatted_result)
+}
+
+/// Builds the contract at `path` using the provided `build_mode`, returning the resulting
+/// artifacts.
+pub(crate) fn build(path: &Option<PathBuf>, build_mode: BuildMode) -> anyhow::Result<BuildResult> {
 	let manifest_path = get_manifest_path(path)?;
+	enable_build_cache(manifest_path.directory().unwrap_or(Path::new("./")));
 	// Default values
-	let args = ExecuteArgs { manifest_path, ..Default::default() };
+	let args = ExecuteArgs { manifest_path, build_mode, ..Default::default() };
+	execute(args)
+}
 
-	// Execute the build and log the output of the build
+/// Enables the cache detected for the contract at `path` for the builds of this process, as the
+/// build is run by `cargo-contract` rather than by a command of our own.
+fn enable_build_cache(path: &Path) {
+	if let Some((var, value)) = BuildCache::detect(path).env() {
+		std::env::set_var(var, value);
+	}
+}
+
+/// The result of a verifiable build.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VerifiableBuild {
+	/// The formatted output of the build.
+	pub output: String,
+	/// The code hash of the reproducible wasm, which matches the code hash of the deployed contract
+	/// when it was deployed from the same source.
+	pub code_hash: String,
+}
+
+/// Builds the contract at `path` inside a docker container, using the reference image used for
+/// source verification unless another `image` is provided. The build is deterministic, producing
+/// the same wasm (and code hash) for the same source.
+///
+/// # Arguments
+///
+/// * `path` - path to the contract project
+/// * `image` - a custom image to build the contract with, instead of the reference image
+pub fn build_verifiable_contract(
+	path: &Option<PathBuf>,
+	image: Option<String>,
+) -> anyhow::Result<VerifiableBuild> {
+	let manifest_path = get_manifest_path(path)?;
+	let image = image.map(ImageVariant::Custom).unwrap_or(ImageVariant::Default);
+	let args = ExecuteArgs {
+		manifest_path,
+		build_mode: BuildMode::Verifiable,
+		image,
+		..Default::default()
+	};
 	let result = execute(args)?;
-	let formatted_result = result.display();
+	let wasm = result
+		.dest_wasm
+		.as_ref()
+		.ok_or(Error::Verification("the build did not produce a wasm artifact".into()))?;
+	let code_hash = to_hex(&blake2_256(&fs::read(wasm)?));
+	Ok(VerifiableBuild { output: result.display(), code_hash })
+}
 
-	Ok(formatted_result)
+/// The artifacts of a contract built as part of a workspace.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContractArtifacts {
+	/// The name of the contract.
+	pub name: String,
+	/// The path to the contract project.
+	pub path: PathBuf,
+	/// The size of the optimized contract code, in KiB.
+	pub size: Option<f64>,
+	/// The artifacts of the contract, copied into the consolidated artifacts directory.
+	pub artifacts: Vec<PathBuf>,
+}
+
+/// Builds the contract at `path`, copying its artifacts (code, metadata and bundle) into the
+/// `output` directory.
+///
+/// # Arguments
+///
+/// * `path` - path to the contract project
+/// * `output` - the directory into which the artifacts are consolidated
+pub fn build_contract_artifacts(path: &Path, output: &Path) -> anyhow::Result<ContractArtifacts> {
+	let result = build(&Some(path.to_path_buf()), BuildMode::Debug)?;
+	record_build_report(&result)?;
+	fs::create_dir_all(output)?;
+	let mut artifacts = Vec::new();
+	let metadata = result.metadata_result.iter().flat_map(|m| [&m.dest_metadata, &m.dest_bundle]);
+	for source in result.dest_wasm.iter().chain(metadata) {
+		if let Some(file_name) = source.file_name() {
+			let destination = output.join(file_name);
+			fs::copy(source, &destination)?;
+			artifacts.push(destination);
+		}
+	}
+	Ok(ContractArtifacts {
+		name: package_name(path)?,
+		path: path.to_path_buf(),
+		size: result.optimization_result.map(|r| r.optimized_size),
+		artifacts,
+	})
+}
+
+/// Returns the artifacts of the latest build of the contract at `path`, within its target
+/// directory: its code, metadata and bundle.
+///
+/// # Arguments
+///
+/// * `path` - path to the contract project
+pub fn built_artifacts(path: &Option<PathBuf>) -> Result<Vec<PathBuf>, Error> {
+	let metadata = metadata_path(path)?;
+	Ok(["wasm", "json", "contract"]
+		.into_iter()
+		.map(|extension| metadata.with_extension(extension))
+		.filter(|artifact| artifact.exists())
+		.collect())
+}
+
+/// Returns the contracts within the workspace at `path`, ordered so that each contract comes after
+/// the contracts it depends on.
+///
+/// # Arguments
+///
+/// * `path` - path to the root of the workspace
+pub fn workspace_contracts(path: &Path) -> Result<Vec<PathBuf>, Error> {
+	let mut contracts = Vec::new();
+	for member in workspace_members(path)? {
+		if is_contract(&member)? {
+			contracts.push(member.canonicalize()?);
+		}
+	}
+	// The indices of the contracts each contract depends on, via path dependencies.
+	let mut dependencies = Vec::new();
+	for contract in &contracts {
+		let manifest = read_manifest(contract)?;
+		let indices: Vec<usize> = path_dependencies(contract, &manifest)
+			.iter()
+			.filter_map(|dependency| contracts.iter().position(|c| c == dependency))
+			.collect();
+		dependencies.push(indices);
+	}
+
+	let mut ordered = Vec::new();
+	let mut built = vec![false; contracts.len()];
+	while ordered.len() < contracts.len() {
+		let next = (0..contracts.len())
+			.find(|&i| !built[i] && dependencies[i].iter().all(|&d| built[d]))
+			.ok_or(Error::Workspace(
+				"the contracts of the workspace have cyclic dependencies".into(),
+			))?;
+		built[next] = true;
+		ordered.push(contracts[next].clone());
+	}
+	Ok(ordered)
+}
+
+/// The members of the workspace at `path`, where members ending with `/*` are expanded.
+fn workspace_members(path: &Path) -> Result<Vec<PathBuf>, Error> {
+	let manifest = read_manifest(path)?;
+	let Some(workspace) = manifest.get("workspace") else {
+		return Err(Error::Workspace(format!("{} is not the root of a workspace", path.display())));
+	};
+	let strings = |item: Option<&Item>| -> Vec<String> {
+		item.and_then(|i| i.as_array())
+			.map(|a| a.iter().filter_map(|v| v.as_str()).map(|v| v.to_string()).collect())
+			.unwrap_or_default()
+	};
+	let exclude: Vec<PathBuf> =
+		strings(workspace.get("exclude")).iter().map(|e| path.join(e)).collect();
+	let mut members = Vec::new();
+	for member in strings(workspace.get("members")) {
+		match member.strip_suffix("/*") {
+			Some(directory) => {
+				for entry in fs::read_dir(path.join(directory))? {
+					let entry = entry?.path();
+					if entry.join("Cargo.toml").exists() {
+						members.push(entry);
+					}
+				}
+			},
+			None => members.push(path.join(member)),
+		}
+	}
+	members.retain(|m| !exclude.contains(m));
+	members.sort();
+	Ok(members)
+}
+
+/// The canonical paths of the path dependencies declared in the `manifest` of the package at
+/// `path`.
+fn path_dependencies(path: &Path, manifest: &DocumentMut) -> Vec<PathBuf> {
+	let Some(dependencies) = manifest.get("dependencies").and_then(|d| d.as_table_like()) else {
+		return Vec::new();
+	};
+	dependencies
+		.iter()
+		.filter_map(|(_, dependency)| dependency.get("path").and_then(|p| p.as_str()))
+		.filter_map(|dependency| path.join(dependency).canonicalize().ok())
+		.collect()
+}
+
+fn package_name(path: &Path) -> Result<String, Error> {
+	read_manifest(path)?
+		.get("package")
+		.and_then(|p| p.get("name"))
+		.and_then(|n| n.as_str())
+		.map(|n| n.to_string())
+		.ok_or(Error::ManifestParsing(format!("{} has no package name", path.display())))
+}
+
+fn read_manifest(path: &Path) -> Result<DocumentMut, Error> {
+	fs::read_to_string(path.join("Cargo.toml"))?
+		.parse::<DocumentMut>()
+		.map_err(|e| Error::ManifestParsing(format!("{}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+
+	fn create_package(root: &Path, path: &str, manifest: &str) -> Result<PathBuf> {
+		let path = root.join(path);
+		fs::create_dir_all(&path)?;
+		fs::write(path.join("Cargo.toml"), manifest)?;
+		Ok(path.canonicalize()?)
+	}
+
+	#[test]
+	fn test_workspace_contracts_in_dependency_order() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let root = temp_dir.path();
+		fs::write(
+			root.join("Cargo.toml"),
+			"[workspace]\nmembers = [\"contracts/*\", \"utils\"]\nexclude = [\"contracts/ignored\"]\n",
+		)?;
+		let ink = "ink = { version = \"5.0.0\", default-features = false }";
+		let router = create_package(
+			root,
+			"contracts/a_router",
+			&format!(
+				"[package]\nname = \"router\"\n\n[dependencies]\n{ink}\ntoken = {{ path = \"../token\", \
+				 features = [\"ink-as-dependency\"] }}\n"
+			),
+		)?;
+		let token = create_package(
+			root,
+			"contracts/token",
+			&format!("[package]\nname = \"token\"\n\n[dependencies]\n{ink}\n"),
+		)?;
+		create_package(
+			root,
+			"contracts/ignored",
+			&format!("[package]\nname = \"ignored\"\n\n[dependencies]\n{ink}\n"),
+		)?;
+		create_package(root, "utils", "[package]\nname = \"utils\"\n\n[dependencies]\n")?;
+
+		assert_eq!(workspace_contracts(root)?, vec![token.clone(), router]);
+		assert_eq!(package_name(&token)?, "token");
+		Ok(())
+	}
+
+	#[test]
+	fn test_workspace_contracts_fails_with_cyclic_dependencies() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let root = temp_dir.path();
+		fs::write(root.join("Cargo.toml"), "[workspace]\nmembers = [\"a\", \"b\"]\n")?;
+		create_package(
+			root,
+			"a",
+			"[package]\nname = \"a\"\n\n[dependencies]\nink = \"5.0.0\"\nb = { path = \"../b\" }\n",
+		)?;
+		create_package(
+			root,
+			"b",
+			"[package]\nname = \"b\"\n\n[dependencies]\nink = \"5.0.0\"\na = { path = \"../a\" }\n",
+		)?;
+		assert!(matches!(workspace_contracts(root), Err(Error::Workspace(..))));
+		Ok(())
+	}
+
+	#[test]
+	fn test_workspace_contracts_fails_without_workspace() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		create_package(temp_dir.path(), "", "[package]\nname = \"token\"\n")?;
+		assert!(matches!(workspace_contracts(temp_dir.path()), Err(Error::Workspace(..))));
+		Ok(())
+	}
 }
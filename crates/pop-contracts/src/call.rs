@@ -2,19 +2,22 @@
 use anyhow::Context;
 use contract_build::Verbosity;
 use contract_extrinsics::{
-	BalanceVariant, CallCommandBuilder, CallExec, DisplayEvents, ErrorVariant,
-	ExtrinsicOptsBuilder, TokenMetadata,
+	CallCommandBuilder, CallExec, DisplayEvents, ErrorVariant, ExtrinsicOptsBuilder, TokenMetadata,
 };
-use ink_env::{DefaultEnvironment, Environment};
+use ink_env::DefaultEnvironment;
 use sp_weights::Weight;
 use std::path::PathBuf;
 use subxt::{Config, PolkadotConfig as DefaultConfig};
 use subxt_signer::sr25519::Keypair;
 use url::Url;
 
-use crate::utils::{
-	helpers::{get_manifest_path, parse_account, parse_balance},
-	signer::create_signer,
+use crate::{
+	estimate::{gas_limit, GasEstimate},
+	utils::{
+		helpers::{get_manifest_path, parse_account, parse_value},
+		metadata::{ensure_payable, load_metadata, resolve_args, FunctionType},
+		signer::create_signer,
+	},
 };
 pub struct CallOpts {
 	/// Path to the contract build folder.
@@ -42,6 +45,8 @@ pub struct CallOpts {
 pub async fn set_up_call(
 	call_opts: CallOpts,
 ) -> anyhow::Result<CallExec<DefaultConfig, DefaultEnvironment, Keypair>> {
+	let args =
+		resolve_args(&call_opts.path, FunctionType::Message, &call_opts.message, &call_opts.args)?;
 	let token_metadata = TokenMetadata::query::<DefaultConfig>(&call_opts.url).await?;
 	let manifest_path = get_manifest_path(&call_opts.path)?;
 	let signer = create_signer(&call_opts.suri)?;
@@ -51,15 +56,17 @@ pub async fn set_up_call(
 		.url(call_opts.url.clone())
 		.done();
 
-	let value: BalanceVariant<<DefaultEnvironment as Environment>::Balance> =
-		parse_balance(&call_opts.value)?;
+	let value = parse_value(&call_opts.value, &token_metadata)?;
+	if let Some(metadata) = load_metadata(&call_opts.path)? {
+		ensure_payable(&metadata, FunctionType::Message, &call_opts.message, value)?;
+	}
 
 	let contract: <DefaultConfig as Config>::AccountId = parse_account(&call_opts.contract)?;
 
 	let call_exec: CallExec<DefaultConfig, DefaultEnvironment, Keypair> =
 		CallCommandBuilder::new(contract.clone(), &call_opts.message, extrinsic_opts)
-			.args(call_opts.args.clone())
-			.value(value.denominate_balance(&token_metadata)?)
+			.args(args)
+			.value(value)
 			.gas_limit(call_opts.gas_limit)
 			.proof_size(call_opts.proof_size)
 			.done()
@@ -72,50 +79,57 @@ pub async fn dry_run_call(
 ) -> anyhow::Result<String> {
 	let call_result = call_exec.call_dry_run().await?;
 	match call_result.result {
-        Ok(ref ret_val) => {
-            let value = call_exec
+		Ok(ref ret_val) => {
+			let value = call_exec
 				.transcoder()
-				.decode_message_return(
-					call_exec.message(),
-					&mut &ret_val.data[..],
-				)
-				.context(format!(
-					"Failed to decode return value {:?}",
-					&ret_val
-			))?;
+				.decode_message_return(call_exec.message(), &mut &ret_val.data[..])
+				.context(format!("Failed to decode return value {:?}", &ret_val))?;
 			Ok(value.to_string())
-        }
-        Err(ref _err) => {
-             Err(anyhow::anyhow!(
-                "Pre-submission dry-run failed. Add gas_limit and proof_size manually to skip this step."
-            ))
-        }
-    }
+		},
+		Err(ref err) => Err(anyhow::anyhow!("Dry-run call failed: {:?}", err)),
+	}
 }
 
+/// Performs a dry-run of the call, estimating the gas and storage deposit required.
+///
+/// # Arguments
+///
+/// * `call_exec` - the call to be estimated
+/// * `margin` - the margin added to the estimated gas, as a percentage
 pub async fn dry_run_gas_estimate_call(
 	call_exec: &CallExec<DefaultConfig, DefaultEnvironment, Keypair>,
-) -> anyhow::Result<Weight> {
+	margin: u8,
+) -> anyhow::Result<GasEstimate> {
 	let call_result = call_exec.call_dry_run().await?;
 	match call_result.result {
-        Ok(_) => {
-            // use user specified values where provided, otherwise use the estimates
-            let ref_time = call_exec
-                .gas_limit()
-                .unwrap_or_else(|| call_result.gas_required.ref_time());
-            let proof_size = call_exec
-                .proof_size()
-                .unwrap_or_else(|| call_result.gas_required.proof_size());
-            Ok(Weight::from_parts(ref_time, proof_size))
-        }
-        Err(ref _err) => {
-             Err(anyhow::anyhow!(
-                "Pre-submission dry-run failed. Add gas_limit and proof_size manually to skip this step."
-            ))
-        }
-    }
+		Ok(_) => {
+			// use user specified values where provided, otherwise use the estimates
+			let weight = gas_limit(
+				call_exec.gas_limit(),
+				call_exec.proof_size(),
+				call_result.gas_required,
+				margin,
+			);
+			Ok(GasEstimate {
+				weight,
+				storage_deposit: call_result.storage_deposit.charge_or_zero(),
+			})
+		},
+		Err(ref _err) => Err(anyhow::anyhow!(
+			"Pre-submission dry-run failed. Add gas_limit and proof_size manually to skip this step."
+		)),
+	}
 }
 
+/// Submits the call for on-chain execution, returning the events emitted. Events emitted by the
+/// contract (`ContractEmitted`) are decoded using its metadata, displaying the names and values of
+/// their fields.
+///
+/// # Arguments
+///
+/// * `call_exec` - the call to be submitted
+/// * `gas_limit` - the maximum amount of gas to be used
+/// * `url` - websocket endpoint of a node
 pub async fn call_smart_contract(
 	call_exec: CallExec<DefaultConfig, DefaultEnvironment, Keypair>,
 	gas_limit: Weight,
@@ -124,8 +138,18 @@ pub async fn call_smart_contract(
 	let token_metadata = TokenMetadata::query::<DefaultConfig>(url).await?;
 	let metadata = call_exec.client().metadata();
 	let events = call_exec.call(Some(gas_limit)).await?;
-	let display_events =
-		DisplayEvents::from_events::<DefaultConfig, DefaultEnvironment>(&events, None, &metadata)?;
+	// Fall back to the raw event data when the events cannot be decoded, e.g. when the local
+	// metadata does not match the deployed contract.
+	let display_events = match DisplayEvents::from_events::<DefaultConfig, DefaultEnvironment>(
+		&events,
+		Some(call_exec.transcoder()),
+		&metadata,
+	) {
+		Ok(display_events) => display_events,
+		Err(_) => DisplayEvents::from_events::<DefaultConfig, DefaultEnvironment>(
+			&events, None, &metadata,
+		)?,
+	};
 
 	let output =
 		display_events.display_events::<DefaultEnvironment>(Verbosity::Default, &token_metadata)?;
@@ -147,7 +171,7 @@ mod tests {
 		let temp_dir = tempfile::tempdir().expect("Could not create temp dir");
 		let temp_contract_dir = temp_dir.path().join("test_contract");
 		fs::create_dir(&temp_contract_dir)?;
-		create_smart_contract("test_contract", temp_contract_dir.as_path())?;
+		create_smart_contract("test_contract", temp_contract_dir.as_path(), None, true)?;
 		Ok(temp_dir)
 	}
 	fn build_smart_contract_test_environment(temp_dir: &TempDir) -> Result<(), Error> {
@@ -0,0 +1,325 @@
+// SPDX-License-Identifier: GPL-3.0
+use crate::errors::Error;
+use serde::{Deserialize, Serialize};
+use std::{
+	collections::BTreeMap,
+	fs,
+	path::{Path, PathBuf},
+};
+
+/// The extension of the file recording the progress of a deployment, stored alongside its
+/// manifest.
+const STATE_EXTENSION: &str = "state.json";
+
+/// A plan deploying multiple contracts, described by a manifest, e.g.:
+///
+/// ```toml
+/// [[contracts]]
+/// name = "token"
+/// path = "./erc20"
+/// args = ["1_000_000"]
+///
+/// [[contracts]]
+/// name = "dex"
+/// path = "./dex"
+/// # the address of the `token` contract, which is therefore deployed first
+/// args = ["${token}"]
+/// salt = "0x01"
+/// ```
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct DeploymentPlan {
+	/// The contracts to deploy.
+	pub contracts: Vec<ContractDeployment>,
+}
+
+/// A contract deployed as part of a plan.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct ContractDeployment {
+	/// The name of the deployment, used to reference the address of the contract within the
+	/// arguments of other deployments as `${name}`.
+	pub name: String,
+	/// Path to the contract project, relative to the manifest.
+	pub path: PathBuf,
+	/// The name of the contract constructor to call.
+	#[serde(default = "default_constructor")]
+	pub constructor: String,
+	/// The constructor arguments, encoded as strings.
+	#[serde(default)]
+	pub args: Vec<String>,
+	/// Transfers an initial balance to the instantiated contract.
+	#[serde(default = "default_value")]
+	pub value: String,
+	/// A hex encoded salt used in the address derivation of the new contract.
+	pub salt: Option<String>,
+	/// The names of deployments which must complete first, in addition to those referenced
+	/// within the arguments.
+	#[serde(default)]
+	pub depends_on: Vec<String>,
+}
+
+fn default_constructor() -> String {
+	"new".into()
+}
+
+fn default_value() -> String {
+	"0".into()
+}
+
+impl ContractDeployment {
+	/// The names of the deployments this deployment depends on.
+	pub fn dependencies(&self) -> Vec<&str> {
+		let mut dependencies: Vec<&str> = self.depends_on.iter().map(|d| d.as_str()).collect();
+		for arg in &self.args {
+			let mut rest = arg.as_str();
+			while let Some((name, remainder)) =
+				rest.split_once("${").and_then(|(_, r)| r.split_once('}'))
+			{
+				if !dependencies.contains(&name) {
+					dependencies.push(name);
+				}
+				rest = remainder;
+			}
+		}
+		dependencies
+	}
+
+	/// The constructor arguments, with references to other deployments replaced by the addresses
+	/// of their contracts.
+	///
+	/// # Arguments
+	///
+	/// * `deployed` - the addresses of the contracts deployed so far, by name
+	pub fn resolve_args(&self, deployed: &BTreeMap<String, String>) -> Result<Vec<String>, Error> {
+		self.args
+			.iter()
+			.map(|arg| {
+				let mut arg = arg.clone();
+				for name in self.dependencies() {
+					let placeholder = format!("${{{name}}}");
+					if arg.contains(&placeholder) {
+						let address = deployed.get(name).ok_or(Error::Deployment(format!(
+							"`{}` depends on `{name}`, which has not been deployed",
+							self.name
+						)))?;
+						arg = arg.replace(&placeholder, address);
+					}
+				}
+				Ok(arg)
+			})
+			.collect()
+	}
+}
+
+impl DeploymentPlan {
+	/// Loads the plan described by the manifest at `path`, resolving the paths of the contracts
+	/// relative to it.
+	///
+	/// # Arguments
+	///
+	/// * `path` - path to the manifest
+	pub fn load(path: &Path) -> Result<Self, Error> {
+		let mut plan: DeploymentPlan = toml_edit::de::from_str(&fs::read_to_string(path)?)
+			.map_err(|e| Error::Deployment(format!("invalid manifest: {e}")))?;
+		let root = path.parent().unwrap_or(Path::new("./"));
+		for contract in plan.contracts.iter_mut() {
+			contract.path = root.join(&contract.path);
+		}
+		plan.order()?;
+		Ok(plan)
+	}
+
+	/// The deployments in the order they are to be executed: each deployment follows those it
+	/// depends on, while otherwise keeping the order of the manifest.
+	pub fn order(&self) -> Result<Vec<&ContractDeployment>, Error> {
+		let mut ordered: Vec<&ContractDeployment> = Vec::new();
+		for contract in &self.contracts {
+			if self.contracts.iter().filter(|c| c.name == contract.name).count() > 1 {
+				return Err(Error::Deployment(format!("`{}` is defined twice", contract.name)));
+			}
+			if let Some(name) = contract
+				.dependencies()
+				.into_iter()
+				.find(|d| !self.contracts.iter().any(|c| &c.name == d))
+			{
+				return Err(Error::Deployment(format!(
+					"`{}` depends on `{name}`, which is not defined",
+					contract.name
+				)));
+			}
+		}
+		while ordered.len() < self.contracts.len() {
+			let next = self.contracts.iter().find(|c| {
+				!ordered.iter().any(|o| o.name == c.name)
+					&& c.dependencies().iter().all(|d| ordered.iter().any(|o| &o.name == d))
+			});
+			match next {
+				Some(contract) => ordered.push(contract),
+				None => {
+					let remaining: Vec<_> = self
+						.contracts
+						.iter()
+						.filter(|c| !ordered.iter().any(|o| o.name == c.name))
+						.map(|c| c.name.as_str())
+						.collect();
+					return Err(Error::Deployment(format!(
+						"circular dependency between: {}",
+						remaining.join(", ")
+					)));
+				},
+			}
+		}
+		Ok(ordered)
+	}
+}
+
+/// The progress of a deployment, recorded after each contract is deployed so that a deployment
+/// which failed part way can be resumed.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct DeploymentState {
+	/// The addresses of the contracts deployed, by name.
+	pub deployed: BTreeMap<String, String>,
+}
+
+impl DeploymentState {
+	/// Loads the progress of the deployment described by the manifest at `manifest`, if any.
+	///
+	/// # Arguments
+	///
+	/// * `manifest` - path to the manifest
+	pub fn load(manifest: &Path) -> Result<Self, Error> {
+		let path = state_path(manifest);
+		if !path.exists() {
+			return Ok(Self::default());
+		}
+		serde_json::from_str(&fs::read_to_string(&path)?)
+			.map_err(|e| Error::Deployment(format!("invalid state at {}: {e}", path.display())))
+	}
+
+	/// Records the progress of the deployment described by the manifest at `manifest`.
+	///
+	/// # Arguments
+	///
+	/// * `manifest` - path to the manifest
+	pub fn save(&self, manifest: &Path) -> Result<(), Error> {
+		fs::write(
+			state_path(manifest),
+			serde_json::to_string_pretty(self).map_err(anyhow::Error::from)?,
+		)?;
+		Ok(())
+	}
+
+	/// Removes the recorded progress of the deployment described by the manifest at `manifest`,
+	/// so that it starts afresh.
+	///
+	/// # Arguments
+	///
+	/// * `manifest` - path to the manifest
+	pub fn clear(manifest: &Path) -> Result<(), Error> {
+		let path = state_path(manifest);
+		if path.exists() {
+			fs::remove_file(path)?;
+		}
+		Ok(())
+	}
+}
+
+fn state_path(manifest: &Path) -> PathBuf {
+	manifest.with_extension(STATE_EXTENSION)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+
+	fn deployment(name: &str, args: &[&str], depends_on: &[&str]) -> ContractDeployment {
+		ContractDeployment {
+			name: name.into(),
+			path: PathBuf::from(name),
+			constructor: default_constructor(),
+			args: args.iter().map(|a| a.to_string()).collect(),
+			value: default_value(),
+			salt: None,
+			depends_on: depends_on.iter().map(|d| d.to_string()).collect(),
+		}
+	}
+
+	fn names<'a>(order: &[&'a ContractDeployment]) -> Vec<&'a str> {
+		order.iter().map(|c| c.name.as_str()).collect()
+	}
+
+	#[test]
+	fn test_load_plan() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let manifest = temp_dir.path().join("deploy.toml");
+		fs::write(
+			&manifest,
+			"[[contracts]]\nname = \"dex\"\npath = \"./dex\"\nargs = [\"${token}\"]\nvalue = \"1UNIT\"\n\n\
+			 [[contracts]]\nname = \"token\"\npath = \"./erc20\"\nconstructor = \"with_supply\"\nsalt = \"0x01\"\n",
+		)?;
+		let plan = DeploymentPlan::load(&manifest)?;
+		assert_eq!(plan.contracts[0].path, temp_dir.path().join("./dex"));
+		assert_eq!(plan.contracts[0].constructor, "new");
+		assert_eq!(plan.contracts[0].value, "1UNIT");
+		assert_eq!(plan.contracts[1].constructor, "with_supply");
+		assert_eq!(plan.contracts[1].salt.as_deref(), Some("0x01"));
+		assert_eq!(names(&plan.order()?), ["token", "dex"]);
+
+		fs::write(&manifest, "[[contracts]]\nname = \"dex\"\n")?;
+		assert!(matches!(DeploymentPlan::load(&manifest), Err(Error::Deployment(..))));
+		Ok(())
+	}
+
+	#[test]
+	fn test_order_follows_dependencies() -> Result<()> {
+		let plan = DeploymentPlan {
+			contracts: vec![
+				deployment("router", &["${dex}", "${token}"], &[]),
+				deployment("dex", &["[${token}, ${other}]"], &[]),
+				deployment("token", &["1000"], &[]),
+				deployment("other", &[], &["token"]),
+			],
+		};
+		assert_eq!(plan.contracts[1].dependencies(), ["token", "other"]);
+		assert_eq!(names(&plan.order()?), ["token", "other", "dex", "router"]);
+		Ok(())
+	}
+
+	#[test]
+	fn test_order_fails_with_invalid_dependencies() {
+		let order = |contracts| DeploymentPlan { contracts }.order().map(|_| ()).unwrap_err();
+		assert!(order(vec![deployment("a", &["${b}"], &[])]).to_string().contains("not defined"));
+		assert!(order(vec![deployment("a", &["${b}"], &[]), deployment("b", &[], &["a"])])
+			.to_string()
+			.contains("circular dependency between: a, b"));
+		assert!(order(vec![deployment("a", &[], &[]), deployment("a", &[], &[])])
+			.to_string()
+			.contains("defined twice"));
+	}
+
+	#[test]
+	fn test_resolve_args() -> Result<()> {
+		let dex = deployment("dex", &["${token}", "[${token}, ${other}]", "10"], &[]);
+		let mut deployed = BTreeMap::from([("token".to_string(), "5Token".to_string())]);
+		assert!(matches!(dex.resolve_args(&deployed), Err(Error::Deployment(..))));
+		deployed.insert("other".into(), "5Other".into());
+		assert_eq!(dex.resolve_args(&deployed)?, ["5Token", "[5Token, 5Other]", "10"]);
+		Ok(())
+	}
+
+	#[test]
+	fn test_deployment_state() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let manifest = temp_dir.path().join("deploy.toml");
+		assert_eq!(DeploymentState::load(&manifest)?, DeploymentState::default());
+		let state = DeploymentState {
+			deployed: BTreeMap::from([("token".to_string(), "5Token".to_string())]),
+		};
+		state.save(&manifest)?;
+		assert!(temp_dir.path().join("deploy.state.json").exists());
+		assert_eq!(DeploymentState::load(&manifest)?, state);
+		DeploymentState::clear(&manifest)?;
+		assert_eq!(DeploymentState::load(&manifest)?, DeploymentState::default());
+		Ok(())
+	}
+}
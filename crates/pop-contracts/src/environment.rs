@@ -0,0 +1,225 @@
+// SPDX-License-Identifier: GPL-3.0
+use crate::errors::Error;
+use pop_common::Config;
+use std::{fs, path::PathBuf};
+use toml_edit::{DocumentMut, Item};
+use url::Url;
+
+/// The project configuration file, in which custom environments are defined.
+pub const CONFIG_FILE: &str = "pop.toml";
+/// The environment used when none is selected.
+pub const DEFAULT_ENVIRONMENT: &str = "local";
+
+/// The built-in environments: their names, endpoints and default secret key URIs.
+const BUILT_IN: [(&str, &str, Option<&str>); 3] = [
+	("local", "ws://localhost:9944", Some("//Alice")),
+	("pop-testnet", "wss://rpc1.paseo.popnetwork.xyz", None),
+	("shibuya", "wss://rpc.shibuya.astar.network", None),
+];
+
+/// A named environment, mapping to the endpoint of a node and a default signer.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Environment {
+	/// The name of the environment.
+	pub name: String,
+	/// The websocket endpoint of a node.
+	pub url: Url,
+	/// The secret key URI of the default signer, if any.
+	pub suri: Option<String>,
+	/// The name of a key within the local keystore to sign with by default, if any.
+	pub account: Option<String>,
+	/// Whether the default signer is the built-in development account of the environment, rather
+	/// than one configured by the user or project.
+	pub built_in_signer: bool,
+}
+
+/// Returns the environments available to the project at `path`: the built-in environments, those
+/// with an endpoint within the configuration of the user, along with those defined within its
+/// `pop.toml`. Project environments take precedence over those of the user, which take precedence
+/// over built-in environments of the same name, e.g.:
+///
+/// ```toml
+/// [environments.staging]
+/// url = "wss://staging.example.com"
+/// account = "deployer"
+/// ```
+///
+/// # Arguments
+///
+/// * `path` - path to the contract project
+/// * `user` - the configuration of the user
+pub fn environments(path: &Option<PathBuf>, user: &Config) -> Result<Vec<Environment>, Error> {
+	let mut environments: Vec<Environment> = BUILT_IN
+		.iter()
+		.map(|(name, url, suri)| Environment {
+			name: name.to_string(),
+			url: Url::parse(url).expect("built-in urls are valid"),
+			suri: suri.map(|s| s.to_string()),
+			account: None,
+			built_in_signer: suri.is_some(),
+		})
+		.collect();
+	for name in user.endpoints() {
+		if !environments.iter().any(|e| e.name == name) {
+			let url = user.endpoint(&name).expect("configured endpoints are valid");
+			environments.push(Environment {
+				name,
+				url,
+				suri: None,
+				account: None,
+				built_in_signer: false,
+			});
+		}
+	}
+	for environment in environments.iter_mut() {
+		if let Some(url) = user.endpoint(&environment.name) {
+			environment.url = url;
+		}
+		// A key configured by the user replaces the default signer.
+		if let Some(account) = user.account(&environment.name) {
+			(environment.suri, environment.account) = (None, Some(account));
+			environment.built_in_signer = false;
+		}
+	}
+
+	let config = path.clone().unwrap_or_else(|| PathBuf::from("./")).join(CONFIG_FILE);
+	if !config.exists() {
+		return Ok(environments);
+	}
+	let config = fs::read_to_string(config)?
+		.parse::<DocumentMut>()
+		.map_err(|e| Error::Config(format!("{}", e)))?;
+	let Some(custom) = config.get("environments").and_then(|e| e.as_table_like()) else {
+		return Ok(environments);
+	};
+	for (name, item) in custom.iter() {
+		let value = |key: &str| item.get(key).and_then(Item::as_str).map(|v| v.to_string());
+		let built_in = environments.iter().position(|e| e.name == name);
+		let url = match (value("url"), built_in) {
+			(Some(url), _) => Url::parse(&url)
+				.map_err(|e| Error::Config(format!("invalid url for `{name}`: {e}")))?,
+			(None, Some(index)) => environments[index].url.clone(),
+			(None, None) => return Err(Error::Config(format!("no url provided for `{name}`"))),
+		};
+		// A signer configured for the environment replaces the default signer.
+		let (suri, account, built_in_signer) = match (value("suri"), value("account"), built_in) {
+			(None, None, Some(index)) => (
+				environments[index].suri.clone(),
+				environments[index].account.clone(),
+				environments[index].built_in_signer,
+			),
+			(suri, account, _) => (suri, account, false),
+		};
+		let environment =
+			Environment { name: name.to_string(), url, suri, account, built_in_signer };
+		match built_in {
+			Some(index) => environments[index] = environment,
+			None => environments.push(environment),
+		}
+	}
+	Ok(environments)
+}
+
+/// Returns the environment with the provided `name`, or the default environment when no name is
+/// provided.
+///
+/// # Arguments
+///
+/// * `path` - path to the contract project
+/// * `name` - the name of the environment
+/// * `user` - the configuration of the user
+pub fn environment(
+	path: &Option<PathBuf>,
+	name: Option<&str>,
+	user: &Config,
+) -> Result<Environment, Error> {
+	let name = name.unwrap_or(DEFAULT_ENVIRONMENT);
+	let environments = environments(path, user)?;
+	let available: Vec<_> = environments.iter().map(|e| e.name.clone()).collect();
+	environments.into_iter().find(|e| e.name == name).ok_or(Error::Config(format!(
+		"unknown environment `{name}`, expected one of: {}",
+		available.join(", ")
+	)))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+
+	#[test]
+	fn test_built_in_environments() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let path = Some(temp_dir.path().to_path_buf());
+		let local = environment(&path, None, &Config::default())?;
+		assert_eq!(local.name, "local");
+		assert_eq!(local.url.as_str(), "ws://localhost:9944/");
+		assert_eq!(local.suri.as_deref(), Some("//Alice"));
+		assert!(local.built_in_signer);
+		let shibuya = environment(&path, Some("shibuya"), &Config::default())?;
+		assert_eq!(shibuya.suri, None);
+		assert!(environment(&path, Some("pop-testnet"), &Config::default()).is_ok());
+		Ok(())
+	}
+
+	#[test]
+	fn test_project_environments() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		fs::write(
+			temp_dir.path().join(CONFIG_FILE),
+			"[environments.staging]\nurl = \"wss://staging.example.com\"\naccount = \"deployer\"\n\n\
+			 [environments.local]\nsuri = \"//Bob\"\n",
+		)?;
+		let path = Some(temp_dir.path().to_path_buf());
+		let staging = environment(&path, Some("staging"), &Config::default())?;
+		assert_eq!(staging.url.as_str(), "wss://staging.example.com/");
+		assert_eq!(staging.account.as_deref(), Some("deployer"));
+		assert_eq!(staging.suri, None);
+		// Built-in environments can be partially overridden.
+		let local = environment(&path, Some("local"), &Config::default())?;
+		assert_eq!(local.url.as_str(), "ws://localhost:9944/");
+		assert_eq!(local.suri.as_deref(), Some("//Bob"));
+		assert!(!local.built_in_signer);
+		assert_eq!(environments(&path, &Config::default())?.len(), 4);
+		Ok(())
+	}
+
+	#[test]
+	fn test_user_environments() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let path = Some(temp_dir.path().to_path_buf());
+		let mut user = Config::default();
+		user.set("endpoints.shibuya", "wss://shibuya.example.com")?;
+		user.set("endpoints.mainnet", "wss://mainnet.example.com")?;
+		user.set("accounts.local", "alice")?;
+		let shibuya = environment(&path, Some("shibuya"), &user)?;
+		assert_eq!(shibuya.url.as_str(), "wss://shibuya.example.com/");
+		let mainnet = environment(&path, Some("mainnet"), &user)?;
+		assert_eq!(mainnet.url.as_str(), "wss://mainnet.example.com/");
+		let local = environment(&path, None, &user)?;
+		assert_eq!((local.suri, local.account.as_deref()), (None, Some("alice")));
+		assert!(!local.built_in_signer);
+		// Project environments take precedence over those of the user.
+		fs::write(
+			temp_dir.path().join(CONFIG_FILE),
+			"[environments.mainnet]\naccount = \"bob\"\n",
+		)?;
+		let mainnet = environment(&path, Some("mainnet"), &user)?;
+		assert_eq!(mainnet.url.as_str(), "wss://mainnet.example.com/");
+		assert_eq!(mainnet.account.as_deref(), Some("bob"));
+		Ok(())
+	}
+
+	#[test]
+	fn test_unknown_or_invalid_environments() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let path = Some(temp_dir.path().to_path_buf());
+		assert!(matches!(
+			environment(&path, Some("mainnet"), &Config::default()),
+			Err(Error::Config(e)) if e.contains("expected one of: local, pop-testnet, shibuya")
+		));
+		fs::write(temp_dir.path().join(CONFIG_FILE), "[environments.custom]\nsuri = \"//Bob\"\n")?;
+		assert!(matches!(environments(&path, &Config::default()), Err(Error::Config(..))));
+		Ok(())
+	}
+}
@@ -5,6 +5,9 @@ pub enum Error {
 	#[error("Failed to create new contract project: {0}")]
 	NewContract(String),
 
+	#[error("Unsupported ink! version: {0}")]
+	UnsupportedInkVersion(String),
+
 	#[error("IO error: {0}")]
 	IO(#[from] std::io::Error),
 
@@ -28,4 +31,46 @@ pub enum Error {
 
 	#[error("Failed to parse hex encoded bytes: {0}")]
 	HexParsing(String),
+
+	#[error("Failed to parse the contract manifest: {0}")]
+	ManifestParsing(String),
+
+	#[error("Incorrect arguments: {0}")]
+	IncorrectArguments(String),
+
+	#[error("Failed to parse the contract metadata: {0}")]
+	MetadataParsing(String),
+
+	#[error("Failed to verify the contract: {0}")]
+	Verification(String),
+
+	#[error("Failed to build the workspace: {0}")]
+	Workspace(String),
+
+	#[error("Configuration error: {0}")]
+	Config(String),
+
+	#[error("Failed to decode {0}")]
+	Decode(String),
+
+	#[error("Failed to query the contract storage: {0}")]
+	ContractStorage(String),
+
+	#[error("Deployment failed: {0}")]
+	Deployment(String),
+
+	#[error("Dry run failed: {0}")]
+	DryRun(String),
+
+	#[error("Invalid signature: {0}")]
+	Signature(String),
+
+	#[error("Subxt error: {0}")]
+	SubxtError(#[from] subxt::Error),
+
+	#[error("Anyhow error: {0}")]
+	AnyhowError(#[from] anyhow::Error),
+
+	#[error("{0}")]
+	CommonError(#[from] pop_common::Error),
 }
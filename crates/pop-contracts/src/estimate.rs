@@ -0,0 +1,58 @@
+// SPDX-License-Identifier: GPL-3.0
+use sp_weights::Weight;
+
+/// The default margin, as a percentage, added to the estimated gas of a dry-run.
+pub const DEFAULT_GAS_MARGIN: u8 = 10;
+
+/// The resources estimated by a dry-run, to be used as the limits when submitting the extrinsic.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GasEstimate {
+	/// The gas limit, including any margin.
+	pub weight: Weight,
+	/// The storage deposit charged, if any.
+	pub storage_deposit: u128,
+}
+
+/// Adds a margin to an estimated value, where the margin is a percentage of the value.
+///
+/// # Arguments
+///
+/// * `value` - the estimated value
+/// * `margin` - the margin to be added, as a percentage
+pub(crate) fn with_margin(value: u64, margin: u8) -> u64 {
+	value.saturating_add(value.saturating_mul(margin as u64) / 100)
+}
+
+/// Resolves the gas limit to be used, preferring user specified values and otherwise falling back
+/// to the estimates (with the margin added).
+pub(crate) fn gas_limit(
+	ref_time: Option<u64>,
+	proof_size: Option<u64>,
+	estimate: Weight,
+	margin: u8,
+) -> Weight {
+	Weight::from_parts(
+		ref_time.unwrap_or_else(|| with_margin(estimate.ref_time(), margin)),
+		proof_size.unwrap_or_else(|| with_margin(estimate.proof_size(), margin)),
+	)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_with_margin() {
+		assert_eq!(with_margin(1_000, 0), 1_000);
+		assert_eq!(with_margin(1_000, 10), 1_100);
+		assert_eq!(with_margin(u64::MAX, 10), u64::MAX);
+	}
+
+	#[test]
+	fn test_gas_limit_prefers_specified_values() {
+		let estimate = Weight::from_parts(1_000, 100);
+		assert_eq!(gas_limit(None, None, estimate, 10), Weight::from_parts(1_100, 110));
+		assert_eq!(gas_limit(Some(5), None, estimate, 10), Weight::from_parts(5, 110));
+		assert_eq!(gas_limit(Some(5), Some(6), estimate, 10), Weight::from_parts(5, 6));
+	}
+}
@@ -0,0 +1,34 @@
+// SPDX-License-Identifier: GPL-3.0
+use askama::Template;
+
+#[derive(Template)]
+#[template(path = "token/Cargo.templ", escape = "none")]
+pub(crate) struct TokenCargoToml {
+	pub(crate) name: String,
+	pub(crate) ink_version: String,
+}
+
+#[derive(Template)]
+#[template(path = "token/gitignore.templ", escape = "none")]
+pub(crate) struct TokenGitIgnore {}
+
+/// The `name` and `symbol` of the token templates are Rust string literals, including quotes.
+#[derive(Template)]
+#[template(path = "psp22/lib.rs.templ", escape = "none")]
+pub(crate) struct Psp22Lib {
+	pub(crate) module: String,
+	pub(crate) contract: String,
+	pub(crate) name: String,
+	pub(crate) symbol: String,
+	pub(crate) decimals: u8,
+	pub(crate) supply: u128,
+}
+
+#[derive(Template)]
+#[template(path = "psp34/lib.rs.templ", escape = "none")]
+pub(crate) struct Psp34Lib {
+	pub(crate) module: String,
+	pub(crate) contract: String,
+	pub(crate) name: String,
+	pub(crate) symbol: String,
+}
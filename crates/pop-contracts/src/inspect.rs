@@ -0,0 +1,329 @@
+// SPDX-License-Identifier: GPL-3.0
+use crate::{
+	errors::Error,
+	utils::metadata::{array, metadata_file, type_name},
+};
+use serde::Serialize;
+use serde_json::Value;
+use std::{
+	fs,
+	path::{Path, PathBuf},
+};
+
+/// A summary of the metadata of a contract.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct ContractMetadata {
+	/// The name of the contract.
+	pub name: String,
+	/// The version of the contract.
+	pub version: String,
+	/// The language and version the contract was written in (e.g. "ink! 5.0.0").
+	pub language: Option<String>,
+	/// The constructors of the contract.
+	pub constructors: Vec<Constructor>,
+	/// The messages of the contract.
+	pub messages: Vec<Message>,
+	/// The events which may be emitted by the contract.
+	pub events: Vec<Event>,
+	/// The root storage fields of the contract.
+	pub storage: Vec<StorageField>,
+}
+
+/// A constructor of a contract.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Constructor {
+	/// The label of the constructor.
+	pub label: String,
+	/// The selector of the constructor.
+	pub selector: String,
+	/// Whether the constructor accepts a value.
+	pub payable: bool,
+	/// The arguments of the constructor.
+	pub args: Vec<Param>,
+	/// The documentation of the constructor.
+	pub docs: Vec<String>,
+}
+
+/// A message of a contract.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Message {
+	/// The label of the message.
+	pub label: String,
+	/// The selector of the message.
+	pub selector: String,
+	/// Whether the message mutates the contract state.
+	pub mutates: bool,
+	/// Whether the message accepts a value.
+	pub payable: bool,
+	/// The arguments of the message.
+	pub args: Vec<Param>,
+	/// The return type of the message, if any.
+	pub return_type: Option<String>,
+	/// The documentation of the message.
+	pub docs: Vec<String>,
+}
+
+/// An event of a contract.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Event {
+	/// The label of the event.
+	pub label: String,
+	/// The fields of the event.
+	pub fields: Vec<EventField>,
+	/// The documentation of the event.
+	pub docs: Vec<String>,
+}
+
+/// A field of an event.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct EventField {
+	/// The label of the field.
+	pub label: String,
+	/// The type of the field.
+	pub type_name: String,
+	/// Whether the field is indexed as a topic.
+	pub indexed: bool,
+}
+
+/// An argument of a constructor or message.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Param {
+	/// The label of the argument.
+	pub label: String,
+	/// The type of the argument.
+	pub type_name: String,
+}
+
+/// A root storage field of a contract.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct StorageField {
+	/// The name of the field.
+	pub name: String,
+	/// The type of the field.
+	pub type_name: String,
+}
+
+/// Reads and summarizes the metadata of a contract.
+///
+/// # Arguments
+///
+/// * `path` - path to the contract project, whose metadata is read from its build artifacts
+/// * `metadata` - path to a metadata (`.json`) or bundle (`.contract`) file, used instead of the
+///   build artifacts of the project when provided
+pub fn inspect_contract(
+	path: &Option<PathBuf>,
+	metadata: Option<&Path>,
+) -> Result<ContractMetadata, Error> {
+	let metadata = metadata_file(path, metadata)?;
+	let json: Value = serde_json::from_str(&fs::read_to_string(&metadata)?)
+		.map_err(|e| Error::MetadataParsing(format!("{}", e)))?;
+	parse_metadata(&json)
+}
+
+/// Summarizes the provided contract metadata.
+///
+/// # Arguments
+///
+/// * `json` - the metadata of a contract
+pub fn parse_metadata(json: &Value) -> Result<ContractMetadata, Error> {
+	let contract = &json["contract"];
+	let spec = json
+		.get("spec")
+		.ok_or(Error::MetadataParsing("the metadata does not contain a `spec`".into()))?;
+	let types = json["types"].as_array().map(|t| t.as_slice()).unwrap_or_default();
+
+	let constructors = array(&spec["constructors"])
+		.map(|c| Constructor {
+			label: string(&c["label"]),
+			selector: string(&c["selector"]),
+			payable: c["payable"].as_bool().unwrap_or_default(),
+			args: params(types, &c["args"]),
+			docs: docs(&c["docs"]),
+		})
+		.collect();
+	let messages = array(&spec["messages"])
+		.map(|m| Message {
+			label: string(&m["label"]),
+			selector: string(&m["selector"]),
+			mutates: m["mutates"].as_bool().unwrap_or_default(),
+			payable: m["payable"].as_bool().unwrap_or_default(),
+			args: params(types, &m["args"]),
+			return_type: m.get("returnType").filter(|r| !r.is_null()).map(|r| type_spec(types, r)),
+			docs: docs(&m["docs"]),
+		})
+		.collect();
+	let events = array(&spec["events"])
+		.map(|e| Event {
+			label: string(&e["label"]),
+			fields: array(&e["args"])
+				.map(|a| EventField {
+					label: string(&a["label"]),
+					type_name: type_spec(types, &a["type"]),
+					indexed: a["indexed"].as_bool().unwrap_or_default(),
+				})
+				.collect(),
+			docs: docs(&e["docs"]),
+		})
+		.collect();
+
+	Ok(ContractMetadata {
+		name: string(&contract["name"]),
+		version: string(&contract["version"]),
+		language: json["source"]["language"].as_str().map(|l| l.to_string()),
+		constructors,
+		messages,
+		events,
+		storage: storage(types, &json["storage"]),
+	})
+}
+
+fn string(value: &Value) -> String {
+	value.as_str().unwrap_or_default().to_string()
+}
+
+fn docs(value: &Value) -> Vec<String> {
+	array(value).filter_map(|d| d.as_str()).map(|d| d.trim().to_string()).collect()
+}
+
+fn params(types: &[Value], args: &Value) -> Vec<Param> {
+	array(args)
+		.map(|a| Param { label: string(&a["label"]), type_name: type_spec(types, &a["type"]) })
+		.collect()
+}
+
+/// Resolves a type specification (`{ "displayName": [..], "type": id }`) to a readable type name.
+fn type_spec(types: &[Value], spec: &Value) -> String {
+	match spec["type"].as_u64() {
+		Some(id) => type_name(types, id),
+		None => array(&spec["displayName"])
+			.filter_map(|s| s.as_str())
+			.collect::<Vec<_>>()
+			.join("::"),
+	}
+}
+
+/// The root storage fields of the contract, using the field types of the storage struct within the
+/// type registry where available.
+fn storage(types: &[Value], storage: &Value) -> Vec<StorageField> {
+	let root = &storage["root"];
+	let layout = &root["layout"]["struct"];
+	// The type of the storage struct is available directly from the root as of ink! v5, otherwise
+	// it is located by name.
+	let ty = match root["ty"].as_u64() {
+		Some(id) => types.iter().find(|t| t["id"].as_u64() == Some(id)),
+		None => types.iter().find(|t| {
+			t["type"]["def"].get("composite").is_some()
+				&& array(&t["type"]["path"]).last().and_then(|p| p.as_str())
+					== layout["name"].as_str()
+		}),
+	};
+	let fields: Vec<_> = ty
+		.map(|t| array(&t["type"]["def"]["composite"]["fields"]).collect())
+		.unwrap_or_default();
+	array(&layout["fields"])
+		.map(|f| {
+			let name = string(&f["name"]);
+			let type_name = fields
+				.iter()
+				.find(|field| field["name"].as_str() == Some(name.as_str()))
+				.and_then(|field| {
+					field["typeName"]
+						.as_str()
+						.map(|t| t.to_string())
+						.or_else(|| field["type"].as_u64().map(|id| type_name(types, id)))
+				})
+				.unwrap_or_else(|| "<unknown>".to_string());
+			StorageField { name, type_name }
+		})
+		.collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+	use serde_json::json;
+
+	fn flipper() -> Value {
+		json!({
+			"source": { "language": "ink! 5.0.0" },
+			"contract": { "name": "flipper", "version": "0.1.0" },
+			"spec": {
+				"constructors": [
+					{ "label": "new", "selector": "0x9bae9d5e", "payable": false, "docs": [" Creates a new flipper."],
+					  "args": [{ "label": "init_value", "type": { "displayName": ["bool"], "type": 0 } }] }
+				],
+				"messages": [
+					{ "label": "flip", "selector": "0x633aa551", "mutates": true, "payable": false, "args": [], "docs": [],
+					  "returnType": { "displayName": ["ink", "MessageResult"], "type": 1 } },
+					{ "label": "get", "selector": "0x2f865bd9", "mutates": false, "payable": false, "args": [], "docs": [],
+					  "returnType": { "displayName": ["ink", "MessageResult"], "type": 4 } }
+				],
+				"events": [
+					{ "label": "Flipped", "docs": [], "args": [
+						{ "label": "value", "indexed": true, "type": { "displayName": ["bool"], "type": 0 } }
+					] }
+				]
+			},
+			"storage": { "root": { "layout": { "struct": { "name": "Flipper", "fields": [
+				{ "name": "value", "layout": { "leaf": { "key": "0x00000000", "ty": 0 } } }
+			] } }, "root_key": "0x00000000" } },
+			"types": [
+				{ "id": 0, "type": { "def": { "primitive": "bool" } } },
+				{ "id": 1, "type": { "path": ["Result"], "params": [{ "name": "T", "type": 2 }, { "name": "E", "type": 3 }], "def": { "variant": {} } } },
+				{ "id": 2, "type": { "def": { "tuple": [] } } },
+				{ "id": 3, "type": { "path": ["ink_primitives", "LangError"], "def": { "variant": {} } } },
+				{ "id": 4, "type": { "path": ["Result"], "params": [{ "name": "T", "type": 0 }, { "name": "E", "type": 3 }], "def": { "variant": {} } } },
+				{ "id": 5, "type": { "path": ["flipper", "flipper", "Flipper"], "def": { "composite": { "fields": [
+					{ "name": "value", "type": 0, "typeName": "<bool as ::ink::storage::traits::AutoStorableHint<..>>::Type" }
+				] } } } }
+			]
+		})
+	}
+
+	#[test]
+	fn test_parse_metadata() -> Result<()> {
+		let metadata = parse_metadata(&flipper())?;
+		assert_eq!(metadata.name, "flipper");
+		assert_eq!(metadata.language, Some("ink! 5.0.0".into()));
+		assert_eq!(
+			metadata.constructors,
+			vec![Constructor {
+				label: "new".into(),
+				selector: "0x9bae9d5e".into(),
+				payable: false,
+				args: vec![Param { label: "init_value".into(), type_name: "bool".into() }],
+				docs: vec!["Creates a new flipper.".into()],
+			}]
+		);
+		assert_eq!(metadata.messages.len(), 2);
+		assert!(metadata.messages[0].mutates);
+		assert_eq!(metadata.messages[0].return_type, Some("Result<(), LangError>".into()));
+		assert_eq!(metadata.messages[1].return_type, Some("Result<bool, LangError>".into()));
+		assert_eq!(
+			metadata.events[0].fields,
+			vec![EventField { label: "value".into(), type_name: "bool".into(), indexed: true }]
+		);
+		assert_eq!(metadata.storage.len(), 1);
+		assert_eq!(metadata.storage[0].name, "value");
+		Ok(())
+	}
+
+	#[test]
+	fn test_parse_metadata_fails_without_spec() {
+		assert!(matches!(parse_metadata(&json!({})), Err(Error::MetadataParsing(..))));
+	}
+
+	#[test]
+	fn test_inspect_contract_fails_without_metadata() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let metadata = temp_dir.path().join("flipper.json");
+		assert!(matches!(
+			inspect_contract(&None, Some(&metadata)),
+			Err(Error::MetadataParsing(..))
+		));
+		fs::write(&metadata, flipper().to_string())?;
+		assert_eq!(inspect_contract(&None, Some(&metadata))?.name, "flipper");
+		Ok(())
+	}
+}
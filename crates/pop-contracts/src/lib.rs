@@ -1,19 +1,62 @@
 // SPDX-License-Identifier: GPL-3.0
+mod bindings;
 mod build;
 mod call;
+mod deployment;
+mod environment;
 mod errors;
+mod estimate;
+mod generator;
+mod inspect;
 mod new;
+mod node;
+mod offline;
+mod query;
+mod report;
+mod templates;
 mod test;
 mod up;
 pub mod utils;
+mod verify;
 
-pub use build::build_smart_contract;
+pub use bindings::{generate_typescript_bindings, typescript_bindings};
+pub use build::{
+	build_contract_artifacts, build_smart_contract, build_verifiable_contract, built_artifacts,
+	workspace_contracts, ContractArtifacts, VerifiableBuild,
+};
 pub use call::{
 	call_smart_contract, dry_run_call, dry_run_gas_estimate_call, set_up_call, CallOpts,
 };
-pub use new::create_smart_contract;
+pub use deployment::{ContractDeployment, DeploymentPlan, DeploymentState};
+pub use environment::{environment, environments, Environment, CONFIG_FILE, DEFAULT_ENVIRONMENT};
+pub use errors::Error;
+pub use estimate::{GasEstimate, DEFAULT_GAS_MARGIN};
+pub use inspect::{
+	inspect_contract, parse_metadata, Constructor, ContractMetadata, Event, EventField, Message,
+	Param, StorageField,
+};
+pub use new::{create_smart_contract, create_token_contract, resolve_ink_version};
+pub use node::{
+	clean_contracts_node_state, compatible_contracts_node_version, contracts_node_args,
+	contracts_node_generator, contracts_node_state, contracts_node_states, is_contracts_node_ready,
+	run_contracts_node, DEFAULT_CONTRACTS_NODE_PORT,
+};
+pub use offline::{
+	prepare_offline_call, prepare_offline_instantiate, SubmittedExtrinsic, UnsignedExtrinsic,
+};
+pub use query::{query_contract_storage, StorageEntry};
+pub use report::{build_report, BuildMetrics, BuildReport};
+pub use templates::{Template, TokenConfig};
 pub use test::{test_e2e_smart_contract, test_smart_contract};
 pub use up::{
-	dry_run_gas_estimate_instantiate, instantiate_smart_contract, set_up_deployment, UpOpts,
+	dry_run_gas_estimate_instantiate, dry_run_upload, instantiate_smart_contract,
+	set_up_deployment, set_up_upload, upload_smart_contract, uploaded_code_hash, ContractInfo,
+	UpOpts,
+};
+pub use utils::{
+	address::{Address, AddressFormat, AddressMapping, DEFAULT_SS58_PREFIX},
+	helpers::is_contract,
+	metadata::{load_metadata, validate_arg, FunctionType},
+	signer::parse_hex_bytes,
 };
-pub use utils::signer::parse_hex_bytes;
+pub use verify::{verify_contract, Verification};
@@ -1,9 +1,62 @@
 // SPDX-License-Identifier: GPL-3.0
-use crate::errors::Error;
+use crate::{
+	errors::Error,
+	generator::{Psp22Lib, Psp34Lib, TokenCargoToml, TokenGitIgnore},
+	templates::{Template, TokenConfig},
+};
+use askama::Template as _;
 use contract_build::new_contract_project;
-use std::path::Path;
+use std::{fs, path::Path};
+use toml_edit::{value, DocumentMut};
+
+/// The ink! releases which contracts can be generated for, ordered from oldest to newest.
+const INK_RELEASES: [&str; 7] = ["4.0.0", "4.0.1", "4.1.0", "4.2.0", "4.2.1", "4.3.0", "5.0.0"];
+/// The marker of the end-to-end tests module within the generated contract.
+const E2E_TESTS_MODULE: &str = "#[cfg(all(test, feature = \"e2e-tests\"))]";
+/// The ink! version targeted by the token templates, unless another version is requested.
+const TOKEN_INK_VERSION: &str = "4.3.0";
+
+/// Resolves the requested ink! version to a known release, where the latest matching release is
+/// selected (e.g. "5" or "5.0" resolves to "5.0.0").
+///
+/// # Arguments
+///
+/// * `version` - the requested ink! version
+pub fn resolve_ink_version(version: &str) -> Result<String, Error> {
+	let version = version.trim_start_matches('v');
+	let requested: Vec<_> = version.split('.').collect();
+	INK_RELEASES
+		.iter()
+		.rev()
+		.find(|release| {
+			let release: Vec<_> = release.split('.').collect();
+			requested.len() <= release.len() && requested.iter().zip(&release).all(|(r, v)| r == v)
+		})
+		.map(|release| release.to_string())
+		.ok_or(Error::UnsupportedInkVersion(format!(
+			"{version} is not a known ink! release, supported releases: {}",
+			INK_RELEASES.join(", ")
+		)))
+}
+
+/// Generates a new contract project.
+///
+/// # Arguments
+///
+/// * `name` - the name of the contract
+/// * `target` - the directory in which the contract is generated
+/// * `ink_version` - the ink! version the contract targets, otherwise the version of the default
+///   template is used
+/// * `e2e_tests` - whether end-to-end tests are included
+pub fn create_smart_contract(
+	name: &str,
+	target: &Path,
+	ink_version: Option<&str>,
+	e2e_tests: bool,
+) -> Result<(), Error> {
+	// Validate the requested ink! version before generating anything.
+	let ink_version = ink_version.map(resolve_ink_version).transpose()?;
 
-pub fn create_smart_contract(name: &str, target: &Path) -> Result<(), Error> {
 	// Canonicalize the target path to ensure consistency and resolve any symbolic links.
 	let canonicalized_path = target
 		.canonicalize()
@@ -21,7 +74,249 @@ pub fn create_smart_contract(name: &str, target: &Path) -> Result<(), Error> {
 	new_contract_project(&name, Some(parent_path))
 		// If an error occurs during the creation of the contract project,
 		// convert it into a NewContract variant with a formatted error message.
-		.map_err(|e| Error::NewContract(format!("{}", e)))
+		.map_err(|e| Error::NewContract(format!("{}", e)))?;
+
+	// Pin the generated project to the requested ink! version.
+	if let Some(version) = ink_version {
+		set_ink_version(name, &canonicalized_path, &version)?;
+	}
+	configure_e2e_tests(&canonicalized_path, e2e_tests)
+}
+
+/// Generates a new token contract project from one of the token templates, customized with the
+/// provided configuration.
+///
+/// # Arguments
+///
+/// * `name` - the name of the contract
+/// * `target` - the directory in which the contract is generated
+/// * `template` - the token template to generate the contract from
+/// * `config` - the name, symbol, decimals and initial supply of the token
+/// * `ink_version` - the ink! version the contract targets, otherwise ink! 4.3.0 is used
+pub fn create_token_contract(
+	name: &str,
+	target: &Path,
+	template: &Template,
+	config: &TokenConfig,
+	ink_version: Option<&str>,
+) -> Result<(), Error> {
+	let ink_version = match ink_version {
+		Some(version) => resolve_ink_version(version)?,
+		None => TOKEN_INK_VERSION.to_string(),
+	};
+	if !name.starts_with(|c: char| c.is_ascii_alphabetic())
+		|| !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+	{
+		return Err(Error::NewContract(format!(
+			"invalid contract name `{name}`, only alphanumeric characters and `_` are allowed, \
+			 starting with a letter"
+		)));
+	}
+	if config.name.trim().is_empty() || config.symbol.trim().is_empty() {
+		return Err(Error::NewContract("the token name and symbol must not be empty".into()));
+	}
+
+	let (module, contract) = (name.to_string(), camel_case(name));
+	// The name and symbol are substituted as string literals, escaping any special characters.
+	let (token_name, symbol) = (format!("{:?}", config.name), format!("{:?}", config.symbol));
+	let lib = match template {
+		Template::PSP22 => {
+			if config.supply == 0 {
+				return Err(Error::NewContract("the initial supply must not be zero".into()));
+			}
+			let supply = 10u128
+				.checked_pow(config.decimals.into())
+				.and_then(|unit| config.supply.checked_mul(unit))
+				.ok_or(Error::NewContract(format!(
+					"an initial supply of {} with {} decimals exceeds the maximum balance",
+					config.supply, config.decimals
+				)))?;
+			Psp22Lib {
+				module,
+				contract,
+				name: token_name,
+				symbol,
+				decimals: config.decimals,
+				supply,
+			}
+			.render()
+		},
+		Template::PSP34 => Psp34Lib { module, contract, name: token_name, symbol }.render(),
+		Template::Standard => {
+			return Err(Error::NewContract(format!("{} is not a token template", template.name())))
+		},
+	}
+	.map_err(|e| Error::NewContract(format!("{}", e)))?;
+	let manifest = TokenCargoToml { name: name.to_string(), ink_version }
+		.render()
+		.map_err(|e| Error::NewContract(format!("{}", e)))?;
+	let gitignore = TokenGitIgnore {}.render().map_err(|e| Error::NewContract(format!("{}", e)))?;
+
+	fs::create_dir_all(target)?;
+	fs::write(target.join("lib.rs"), lib)?;
+	fs::write(target.join("Cargo.toml"), manifest)?;
+	fs::write(target.join(".gitignore"), gitignore)?;
+	Ok(())
+}
+
+/// Documents how to run the end-to-end tests of the generated contract, or removes them along with
+/// their dependencies and features when not required.
+fn configure_e2e_tests(path: &Path, include: bool) -> Result<(), Error> {
+	let lib_path = path.join("lib.rs");
+	let lib = fs::read_to_string(&lib_path)?;
+	let Some(position) = lib.find(E2E_TESTS_MODULE) else {
+		return Ok(());
+	};
+	let line_start = lib[..position].rfind('\n').map(|i| i + 1).unwrap_or_default();
+	if include {
+		let indent = &lib[line_start..position];
+		let docs = format!(
+			"{indent}// Run the end-to-end tests with `pop test contract --e2e`, which requires a\n\
+			 {indent}// contracts node (see `pop up contracts-node`).\n"
+		);
+		fs::write(lib_path, format!("{}{docs}{}", &lib[..line_start], &lib[line_start..]))?;
+		return Ok(());
+	}
+
+	// Remove the module, including any comments immediately preceding it.
+	let mut start = line_start;
+	while let Some(previous) = lib[..start.saturating_sub(1)].rfind('\n').map(|i| i + 1) {
+		if !lib[previous..start].trim_start().starts_with("//") {
+			break;
+		}
+		start = previous;
+	}
+	fs::write(lib_path, format!("{}\n}}\n", lib[..start].trim_end()))?;
+
+	let manifest_path = path.join("Cargo.toml");
+	let mut manifest = fs::read_to_string(&manifest_path)?
+		.parse::<DocumentMut>()
+		.map_err(|e| Error::ManifestParsing(format!("{}", e)))?;
+	if let Some(dev_dependencies) =
+		manifest.get_mut("dev-dependencies").and_then(|d| d.as_table_like_mut())
+	{
+		dev_dependencies.remove("ink_e2e");
+		if dev_dependencies.is_empty() {
+			manifest.remove("dev-dependencies");
+		}
+	}
+	if let Some(features) = manifest.get_mut("features").and_then(|f| f.as_table_like_mut()) {
+		features.remove("e2e-tests");
+	}
+	fs::write(manifest_path, manifest.to_string())?;
+	Ok(())
+}
+
+/// Pins the ink! dependencies of the generated contract to `version`, updating the end-to-end
+/// tests to the syntax of ink! v5 where required.
+fn set_ink_version(name: &str, path: &Path, version: &str) -> Result<(), Error> {
+	let manifest_path = path.join("Cargo.toml");
+	let mut manifest = fs::read_to_string(&manifest_path)?
+		.parse::<DocumentMut>()
+		.map_err(|e| Error::ManifestParsing(format!("{}", e)))?;
+	manifest["dependencies"]["ink"]["version"] = value(version);
+	manifest["dev-dependencies"]["ink_e2e"] = value(version);
+	fs::write(manifest_path, manifest.to_string())?;
+
+	if version.starts_with('5') {
+		let lib_path = path.join("lib.rs");
+		let lib = fs::read_to_string(&lib_path)?;
+		if let Some(position) = lib.find(E2E_TESTS_MODULE) {
+			let mut lib = lib[..position].to_string();
+			lib.push_str(&e2e_tests_v5(name));
+			fs::write(lib_path, lib)?;
+		}
+	}
+	Ok(())
+}
+
+/// The end-to-end tests module of the default contract, using the syntax of ink! v5.
+fn e2e_tests_v5(name: &str) -> String {
+	let camel_name = camel_case(name);
+	format!(
+		r#"{E2E_TESTS_MODULE}
+    mod e2e_tests {{
+        /// Imports all the definitions from the outer scope so we can use them here.
+        use super::*;
+
+        /// A helper function used for calling contract messages.
+        use ink_e2e::ContractsBackend;
+
+        /// The End-to-End test `Result` type.
+        type E2EResult<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+        /// We test that we can upload and instantiate the contract using its default constructor.
+        #[ink_e2e::test]
+        async fn default_works(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {{
+            // Given
+            let mut constructor = {camel_name}Ref::default();
+
+            // When
+            let contract = client
+                .instantiate("{name}", &ink_e2e::alice(), &mut constructor)
+                .submit()
+                .await
+                .expect("instantiate failed");
+            let call_builder = contract.call_builder::<{camel_name}>();
+
+            // Then
+            let get = call_builder.get();
+            let get_result = client.call(&ink_e2e::alice(), &get).dry_run().await?;
+            assert!(matches!(get_result.return_value(), false));
+
+            Ok(())
+        }}
+
+        /// We test that we can read and write a value from the on-chain contract.
+        #[ink_e2e::test]
+        async fn it_works(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {{
+            // Given
+            let mut constructor = {camel_name}Ref::new(false);
+            let contract = client
+                .instantiate("{name}", &ink_e2e::bob(), &mut constructor)
+                .submit()
+                .await
+                .expect("instantiate failed");
+            let mut call_builder = contract.call_builder::<{camel_name}>();
+
+            let get = call_builder.get();
+            let get_result = client.call(&ink_e2e::bob(), &get).dry_run().await?;
+            assert!(matches!(get_result.return_value(), false));
+
+            // When
+            let flip = call_builder.flip();
+            let _flip_result = client
+                .call(&ink_e2e::bob(), &flip)
+                .submit()
+                .await
+                .expect("flip failed");
+
+            // Then
+            let get = call_builder.get();
+            let get_result = client.call(&ink_e2e::bob(), &get).dry_run().await?;
+            assert!(matches!(get_result.return_value(), true));
+
+            Ok(())
+        }}
+    }}
+}}
+"#
+	)
+}
+
+/// Converts the name of a contract to the name of its storage struct (e.g. `my_token` to
+/// `MyToken`).
+pub(crate) fn camel_case(name: &str) -> String {
+	name.split(|c: char| c == '_' || c == '-')
+		.filter(|s| !s.is_empty())
+		.map(|s| {
+			let mut chars = s.chars();
+			chars
+				.next()
+				.map(|c| c.to_uppercase().chain(chars).collect::<String>())
+				.unwrap_or_default()
+		})
+		.collect()
 }
 
 #[cfg(test)]
@@ -35,7 +330,7 @@ mod tests {
 		let temp_dir = tempfile::tempdir()?;
 		let temp_contract_dir = temp_dir.path().join("test_contract");
 		fs::create_dir(&temp_contract_dir)?;
-		create_smart_contract("test_contract", temp_contract_dir.as_path())?;
+		create_smart_contract("test_contract", temp_contract_dir.as_path(), None, true)?;
 		Ok(temp_dir)
 	}
 
@@ -57,4 +352,143 @@ mod tests {
 
 		Ok(())
 	}
+
+	#[test]
+	fn test_create_smart_contract_with_ink_version() -> Result<(), Error> {
+		let temp_dir = tempfile::tempdir()?;
+		let temp_contract_dir = temp_dir.path().join("test_contract");
+		fs::create_dir(&temp_contract_dir)?;
+		create_smart_contract("test_contract", temp_contract_dir.as_path(), Some("5.0"), true)?;
+
+		let manifest = fs::read_to_string(temp_contract_dir.join("Cargo.toml"))?;
+		assert!(manifest.contains("version = \"5.0.0\""));
+		assert!(manifest.contains("ink_e2e = \"5.0.0\""));
+		let lib = fs::read_to_string(temp_contract_dir.join("lib.rs"))?;
+		assert!(lib.contains("use ink_e2e::ContractsBackend;"));
+		assert!(lib.contains("contract.call_builder::<TestContract>()"));
+		assert!(!lib.contains("build_message"));
+		Ok(())
+	}
+
+	#[test]
+	fn test_resolve_ink_version() -> Result<(), Error> {
+		assert_eq!(resolve_ink_version("5")?, "5.0.0");
+		assert_eq!(resolve_ink_version("5.0")?, "5.0.0");
+		assert_eq!(resolve_ink_version("v4.2")?, "4.2.1");
+		assert_eq!(resolve_ink_version("4")?, "4.3.0");
+		assert_eq!(resolve_ink_version("4.0.0")?, "4.0.0");
+		assert!(resolve_ink_version("3").is_err());
+		assert!(resolve_ink_version("5.1").is_err());
+		assert!(resolve_ink_version("4.30").is_err());
+		Ok(())
+	}
+
+	#[test]
+	fn test_create_smart_contract_without_e2e_tests() -> Result<(), Error> {
+		let temp_dir = tempfile::tempdir()?;
+		let temp_contract_dir = temp_dir.path().join("test_contract");
+		fs::create_dir(&temp_contract_dir)?;
+		create_smart_contract("test_contract", temp_contract_dir.as_path(), None, false)?;
+
+		let lib = fs::read_to_string(temp_contract_dir.join("lib.rs"))?;
+		assert!(lib.contains("#[ink::test]"));
+		assert!(!lib.contains("mod e2e_tests"));
+		assert!(!lib.contains("ink_e2e"));
+		assert!(lib.trim_end().ends_with('}'));
+		let manifest = fs::read_to_string(temp_contract_dir.join("Cargo.toml"))?;
+		assert!(!manifest.contains("ink_e2e"));
+		assert!(!manifest.contains("e2e-tests"));
+		Ok(())
+	}
+
+	#[test]
+	fn test_create_smart_contract_documents_e2e_tests() -> Result<(), Error> {
+		let temp_dir = setup_test_environment()?;
+		let lib = fs::read_to_string(temp_dir.path().join("test_contract/lib.rs"))?;
+		assert!(lib.contains("pop test contract --e2e"));
+		assert!(lib.contains("mod e2e_tests"));
+		Ok(())
+	}
+
+	fn token_config() -> TokenConfig {
+		TokenConfig {
+			name: "My \"Token\"".into(),
+			symbol: "MYT".into(),
+			decimals: 10,
+			supply: 1_000_000,
+		}
+	}
+
+	#[test]
+	fn test_create_psp22_contract() -> Result<(), Error> {
+		let temp_dir = tempfile::tempdir()?;
+		let contract_dir = temp_dir.path().join("my_token");
+		create_token_contract("my_token", &contract_dir, &Template::PSP22, &token_config(), None)?;
+
+		let lib = fs::read_to_string(contract_dir.join("lib.rs"))?;
+		assert!(lib.contains("mod my_token {"));
+		assert!(lib.contains("pub struct MyToken {"));
+		assert!(lib.contains("const NAME: &str = \"My \\\"Token\\\"\";"));
+		assert!(lib.contains("const SYMBOL: &str = \"MYT\";"));
+		assert!(lib.contains("const DECIMALS: u8 = 10;"));
+		assert!(lib.contains("const INITIAL_SUPPLY: Balance = 10000000000000000;"));
+		assert!(lib.contains("assert_eq!(token.total_supply(), 10000000000000000);"));
+		assert!(lib.contains("assert_eq!(token.token_decimals(), 10);"));
+		assert!(!lib.contains("^^"));
+		let manifest = fs::read_to_string(contract_dir.join("Cargo.toml"))?;
+		assert!(manifest.contains("name = \"my_token\""));
+		assert!(manifest.contains("ink = { version = \"4.3.0\""));
+		assert!(contract_dir.join(".gitignore").exists());
+		Ok(())
+	}
+
+	#[test]
+	fn test_create_psp34_contract() -> Result<(), Error> {
+		let temp_dir = tempfile::tempdir()?;
+		let contract_dir = temp_dir.path().join("my_nft");
+		create_token_contract(
+			"my_nft",
+			&contract_dir,
+			&Template::PSP34,
+			&token_config(),
+			Some("5"),
+		)?;
+
+		let lib = fs::read_to_string(contract_dir.join("lib.rs"))?;
+		assert!(lib.contains("mod my_nft {"));
+		assert!(lib.contains("pub struct MyNft {"));
+		assert!(lib.contains("const SYMBOL: &str = \"MYT\";"));
+		assert!(lib.contains("Some(Vec::from(\"MYT\".as_bytes()))"));
+		assert!(!lib.contains("^^"));
+		let manifest = fs::read_to_string(contract_dir.join("Cargo.toml"))?;
+		assert!(manifest.contains("ink = { version = \"5.0.0\""));
+		Ok(())
+	}
+
+	#[test]
+	fn test_create_token_contract_fails_with_invalid_config() -> Result<(), Error> {
+		let temp_dir = tempfile::tempdir()?;
+		let contract_dir = temp_dir.path().join("my_token");
+		let config = token_config();
+		for (name, template, config) in [
+			("my-token", Template::PSP22, config.clone()),
+			("my_token", Template::Standard, config.clone()),
+			("my_token", Template::PSP22, TokenConfig { supply: 0, ..config.clone() }),
+			("my_token", Template::PSP22, TokenConfig { decimals: 38, ..config.clone() }),
+			("my_token", Template::PSP34, TokenConfig { symbol: " ".into(), ..config.clone() }),
+		] {
+			assert!(matches!(
+				create_token_contract(name, &contract_dir, &template, &config, None),
+				Err(crate::errors::Error::NewContract(..))
+			));
+		}
+		assert!(!contract_dir.exists());
+		Ok(())
+	}
+
+	#[test]
+	fn test_camel_case() {
+		assert_eq!(camel_case("test_contract"), "TestContract");
+		assert_eq!(camel_case("flipper"), "Flipper");
+	}
 }
@@ -0,0 +1,310 @@
+// SPDX-License-Identifier: GPL-3.0
+use crate::{errors::Error, utils::helpers::ink_version};
+use pop_common::{
+	platform::{Arch, Os},
+	Binary, GitHub, Platform, Source,
+};
+use std::{
+	fs::{self, File},
+	path::{Path, PathBuf},
+	process::{Child, Command, Stdio},
+};
+use url::Url;
+
+const SUBSTRATE_CONTRACTS_NODE: &str = "https://github.com/paritytech/substrate-contracts-node";
+const BINARY: &str = "substrate-contracts-node";
+/// The latest release of `substrate-contracts-node` known to be compatible with each major ink!
+/// version.
+const COMPATIBLE_RELEASES: [(&str, &str); 2] = [("4", "v0.31.0"), ("5", "v0.41.0")];
+/// The default port on which a local contracts node serves RPC requests.
+pub const DEFAULT_CONTRACTS_NODE_PORT: u16 = 9944;
+
+/// Resolves the `substrate-contracts-node` binary to be used, which may need sourcing if it is not
+/// yet available within the cache.
+///
+/// # Arguments
+///
+/// * `cache` - path to the local cache
+/// * `version` - the release tag to be used (e.g. "v0.41.0"); the latest release is used if not
+///   provided
+pub async fn contracts_node_generator(
+	cache: PathBuf,
+	version: Option<&str>,
+) -> Result<Binary, Error> {
+	let version = match version {
+		Some(v) => v.to_string(),
+		None => latest_contracts_node_release().await?,
+	};
+	let versioned_name = Source::versioned_name(BINARY, Some(&version));
+	let path = Source::cached_path(&cache, BINARY, Some(&version));
+	let mut sources = Vec::new();
+	if !path.exists() {
+		let repo = Url::parse(SUBSTRATE_CONTRACTS_NODE).expect("repository url valid");
+		match release_asset(Platform::detect().ok()) {
+			Some(asset) => sources.push(Source::Archive {
+				version: version.clone(),
+				url: GitHub::release(&repo, &version, asset),
+				contents: vec![BINARY.into()],
+			}),
+			// Built from source rather than downloading a binary which cannot run.
+			None => sources.push(Source::Git {
+				url: repo,
+				branch: Some(version.clone()),
+				package: "contracts-node".into(),
+				binaries: vec![BINARY.into()],
+				version: Some(version.clone()),
+			}),
+		}
+	}
+	Ok(Binary { name: versioned_name, version, path, sources })
+}
+
+/// Returns the release of `substrate-contracts-node` compatible with the ink! version used by the
+/// contract at `path`, if known.
+pub fn compatible_contracts_node_version(path: &Option<PathBuf>) -> Result<Option<String>, Error> {
+	Ok(ink_version(path)?.and_then(|ink| {
+		let major = ink.split('.').next().unwrap_or_default().to_string();
+		COMPATIBLE_RELEASES
+			.iter()
+			.find(|(ink, _)| *ink == major)
+			.map(|(_, release)| release.to_string())
+	}))
+}
+
+/// Runs the `substrate-contracts-node` binary at `path` as a development chain.
+///
+/// # Arguments
+///
+/// * `path` - path to the binary
+/// * `args` - additional arguments to be provided to the node
+/// * `log` - an optional file to which the output of the node is written, otherwise discarded
+pub fn run_contracts_node(
+	path: &Path,
+	args: &[String],
+	log: Option<&File>,
+) -> Result<Child, Error> {
+	let mut command = Command::new(path);
+	command.arg("--dev").args(args);
+	tracing::debug!(?command, "spawning contracts node");
+	match log {
+		Some(log) => command.stdout(log.try_clone()?).stderr(log.try_clone()?),
+		None => command.stdout(Stdio::null()).stderr(Stdio::null()),
+	};
+	Ok(command.spawn()?)
+}
+
+/// The arguments launching a local contracts node serving RPC requests on `port`, including from
+/// browser based tools such as Contracts UI. Its state is persisted within `base_path` across
+/// launches, otherwise discarded once the node terminates.
+///
+/// # Arguments
+///
+/// * `port` - the port on which RPC requests are served
+/// * `base_path` - an optional directory in which the state of the node is persisted
+pub fn contracts_node_args(port: u16, base_path: Option<&Path>) -> Vec<String> {
+	let mut args = vec![format!("--rpc-port={port}"), "--rpc-cors=all".into()];
+	match base_path {
+		Some(base_path) => args.push(format!("--base-path={}", base_path.display())),
+		None => args.push("--tmp".into()),
+	}
+	args
+}
+
+/// The directory within the cache in which the state of the contracts node `binary` is persisted,
+/// which is specific to its release as the state of one release may not be readable by another.
+///
+/// # Arguments
+///
+/// * `cache` - path to the local cache
+/// * `binary` - the name of the (versioned) binary
+pub fn contracts_node_state(cache: &Path, binary: &str) -> PathBuf {
+	cache.join(format!("{binary}-state"))
+}
+
+/// The directories within the cache in which the state of contracts nodes is persisted, across
+/// every release.
+///
+/// # Arguments
+///
+/// * `cache` - path to the local cache
+pub fn contracts_node_states(cache: &Path) -> Result<Vec<PathBuf>, Error> {
+	if !cache.exists() {
+		return Ok(Vec::new());
+	}
+	let mut states: Vec<_> = fs::read_dir(cache)?
+		.flatten()
+		.filter(|e| {
+			let name = e.file_name().to_string_lossy().to_string();
+			name.starts_with(&format!("{BINARY}-")) && name.ends_with("-state")
+		})
+		.map(|e| e.path())
+		.filter(|p| p.is_dir())
+		.collect();
+	states.sort();
+	Ok(states)
+}
+
+/// Removes the state of a contracts node persisted at `base_path`, returning whether any existed.
+///
+/// # Arguments
+///
+/// * `base_path` - the directory in which the state of the node is persisted
+pub fn clean_contracts_node_state(base_path: &Path) -> Result<bool, Error> {
+	if !base_path.exists() {
+		return Ok(false);
+	}
+	fs::remove_dir_all(base_path)?;
+	Ok(true)
+}
+
+/// Whether the `output` of a contracts node shows it is ready to serve RPC requests.
+///
+/// # Arguments
+///
+/// * `output` - the output of the node
+pub fn is_contracts_node_ready(output: &str) -> bool {
+	output.contains("Running JSON-RPC server")
+}
+
+/// The name of the release asset for `platform`, if released for it: only x86_64 Linux linked
+/// against glibc and macOS, as a universal binary, are.
+///
+/// # Arguments
+///
+/// * `platform` - the platform detected, if supported
+fn release_asset(platform: Option<Platform>) -> Option<&'static str> {
+	match platform? {
+		Platform { os: Os::LinuxGnu, arch: Arch::X86_64 } => {
+			Some("substrate-contracts-node-linux.tar.gz")
+		},
+		Platform { os: Os::MacOs, .. } => Some("substrate-contracts-node-mac-universal.tar.gz"),
+		_ => None,
+	}
+}
+
+async fn latest_contracts_node_release() -> Result<String, Error> {
+	let repo = Url::parse(SUBSTRATE_CONTRACTS_NODE).expect("repository url valid");
+	let releases = GitHub::get_latest_releases(&repo).await?;
+	releases
+		.into_iter()
+		.find(|r| !r.prerelease)
+		.map(|r| r.tag_name)
+		.ok_or(Error::CommonError(pop_common::Error::Git(
+			"no release of substrate-contracts-node could be found".into(),
+		)))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+
+	#[tokio::test]
+	async fn test_contracts_node_generator() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let cache = PathBuf::from(temp_dir.path());
+		let binary = contracts_node_generator(cache.clone(), Some("v0.41.0")).await?;
+		assert_eq!(binary.name, "substrate-contracts-node-v0.41.0");
+		assert_eq!(binary.version, "v0.41.0");
+		assert_eq!(
+			binary.path,
+			cache.join("bins/substrate-contracts-node/v0.41.0/substrate-contracts-node")
+		);
+		assert_eq!(binary.sources.len(), 1);
+
+		// Nothing to source once cached
+		fs::create_dir_all(binary.path.parent().unwrap())?;
+		fs::write(&binary.path, "")?;
+		let binary = contracts_node_generator(cache, Some("v0.41.0")).await?;
+		assert!(binary.sources.is_empty());
+		Ok(())
+	}
+
+	#[test]
+	fn test_compatible_contracts_node_version() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let path = Some(temp_dir.path().to_path_buf());
+		fs::write(temp_dir.path().join("Cargo.toml"), "[dependencies]\nink = \"5.0.0\"")?;
+		assert_eq!(compatible_contracts_node_version(&path)?, Some("v0.41.0".into()));
+		fs::write(temp_dir.path().join("Cargo.toml"), "[dependencies]\nink = \"4.3.0\"")?;
+		assert_eq!(compatible_contracts_node_version(&path)?, Some("v0.31.0".into()));
+		fs::write(temp_dir.path().join("Cargo.toml"), "[dependencies]\nink = \"3.4.0\"")?;
+		assert_eq!(compatible_contracts_node_version(&path)?, None);
+		Ok(())
+	}
+
+	#[test]
+	fn test_contracts_node_args() {
+		let state = PathBuf::from("/cache/substrate-contracts-node-v0.41.0-state");
+		assert_eq!(
+			contracts_node_args(9944, Some(&state)),
+			vec![
+				"--rpc-port=9944",
+				"--rpc-cors=all",
+				"--base-path=/cache/substrate-contracts-node-v0.41.0-state"
+			]
+		);
+		assert_eq!(
+			contracts_node_args(9955, None),
+			vec!["--rpc-port=9955", "--rpc-cors=all", "--tmp"]
+		);
+	}
+
+	#[test]
+	fn test_clean_contracts_node_state() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let state = contracts_node_state(temp_dir.path(), "substrate-contracts-node-v0.41.0");
+		assert_eq!(state, temp_dir.path().join("substrate-contracts-node-v0.41.0-state"));
+		assert!(!clean_contracts_node_state(&state)?);
+		fs::create_dir_all(state.join("chains").join("dev").join("db"))?;
+		assert!(clean_contracts_node_state(&state)?);
+		assert!(!state.exists());
+		Ok(())
+	}
+
+	#[test]
+	fn test_contracts_node_states() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let cache = temp_dir.path();
+		for binary in ["substrate-contracts-node-v0.41.0", "substrate-contracts-node-v0.31.0"] {
+			fs::create_dir_all(contracts_node_state(cache, binary))?;
+		}
+		fs::create_dir_all(cache.join("bins"))?;
+		assert_eq!(
+			contracts_node_states(cache)?,
+			vec![
+				cache.join("substrate-contracts-node-v0.31.0-state"),
+				cache.join("substrate-contracts-node-v0.41.0-state")
+			]
+		);
+		assert!(contracts_node_states(&cache.join("missing"))?.is_empty());
+		Ok(())
+	}
+
+	#[test]
+	fn test_is_contracts_node_ready() {
+		assert!(is_contracts_node_ready(
+			"2024-05-01 10:00:00 Running JSON-RPC server: addr=127.0.0.1:9944, allowed origins=[\"*\"]"
+		));
+		assert!(!is_contracts_node_ready("2024-05-01 10:00:00 Substrate Contracts Node"));
+	}
+
+	#[test]
+	fn test_release_asset() {
+		let platform = |os, arch| Some(Platform { os, arch });
+		assert_eq!(
+			release_asset(platform(Os::LinuxGnu, Arch::X86_64)),
+			Some("substrate-contracts-node-linux.tar.gz")
+		);
+		for arch in [Arch::X86_64, Arch::Aarch64] {
+			assert_eq!(
+				release_asset(platform(Os::MacOs, arch)),
+				Some("substrate-contracts-node-mac-universal.tar.gz")
+			);
+		}
+		// Built from source instead.
+		assert_eq!(release_asset(platform(Os::LinuxGnu, Arch::Aarch64)), None);
+		assert_eq!(release_asset(platform(Os::LinuxMusl, Arch::X86_64)), None);
+		assert_eq!(release_asset(None), None);
+	}
+}
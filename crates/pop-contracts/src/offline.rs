@@ -0,0 +1,324 @@
+// SPDX-License-Identifier: GPL-3.0
+use crate::{
+	errors::Error,
+	estimate::{gas_limit, GasEstimate},
+	utils::{
+		helpers::{parse_account, parse_value, to_hex},
+		metadata::{ensure_payable, load_metadata, metadata_path, resolve_args, FunctionType},
+	},
+	CallOpts, UpOpts,
+};
+use contract_build::util::decode_hex;
+use contract_extrinsics::TokenMetadata;
+use contract_transcode::ContractMessageTranscoder;
+use sp_core::{sr25519, Pair};
+use sp_weights::Weight;
+use std::{fs, path::PathBuf};
+use subxt::{
+	config::polkadot::PolkadotExtrinsicParamsBuilder,
+	dynamic::Value,
+	ext::codec::{Decode, Encode},
+	tx::{DynamicPayload, PartialExtrinsic},
+	utils::{AccountId32, MultiAddress, MultiSignature},
+	OnlineClient, PolkadotConfig as DefaultConfig,
+};
+
+/// The `REVERT` flag set by a contract when its execution is reverted.
+const REVERT_FLAG: u32 = 1;
+
+/// A contract extrinsic prepared on behalf of an external signer, such as an air-gapped device or
+/// a hardware wallet, so that the private key of the signer never has to be provided to pop.
+pub struct UnsignedExtrinsic {
+	partial: PartialExtrinsic<DefaultConfig, OnlineClient<DefaultConfig>>,
+	signer: AccountId32,
+	/// The gas limit and storage deposit estimated by a dry run on behalf of the signer.
+	pub estimate: GasEstimate,
+}
+
+/// The result of an extrinsic signed externally and submitted to the chain.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SubmittedExtrinsic {
+	/// The hash of the extrinsic.
+	pub hash: String,
+	/// The events emitted by the extrinsic, as `Pallet::Event`.
+	pub events: Vec<String>,
+	/// The address of the contract, when a contract was instantiated.
+	pub contract: Option<String>,
+}
+
+impl UnsignedExtrinsic {
+	/// The payload to be signed by the signer. Payloads longer than 256 bytes are hashed using
+	/// blake2-256 before signing, as expected by the chain.
+	pub fn payload(&self) -> Vec<u8> {
+		self.partial.signer_payload()
+	}
+
+	/// The hex encoded payload to be signed by the signer.
+	pub fn payload_hex(&self) -> String {
+		to_hex(&self.payload())
+	}
+
+	/// Submits the extrinsic using the provided signature, waiting for it to be finalized.
+	///
+	/// # Arguments
+	///
+	/// * `signature` - the hex encoded sr25519 signature of the payload
+	pub async fn submit(&self, signature: &str) -> Result<SubmittedExtrinsic, Error> {
+		let signature = self.verify(signature)?;
+		let events = self
+			.partial
+			.sign_with_address_and_signature(
+				&MultiAddress::Id(self.signer.clone()),
+				&MultiSignature::Sr25519(signature),
+			)
+			.submit_and_watch()
+			.await?
+			.wait_for_finalized_success()
+			.await?;
+		let mut submitted = SubmittedExtrinsic {
+			hash: to_hex(events.extrinsic_hash().as_ref()),
+			events: Vec::new(),
+			contract: None,
+		};
+		for event in events.iter() {
+			let event = event?;
+			// The `Instantiated` event consists of the deployer, followed by the contract.
+			if event.pallet_name() == "Contracts" && event.variant_name() == "Instantiated" {
+				if let Some(contract) = event.field_bytes().get(32..64) {
+					let contract: [u8; 32] = contract.try_into().expect("length checked above");
+					submitted.contract = Some(AccountId32::from(contract).to_string());
+				}
+			}
+			submitted
+				.events
+				.push(format!("{}::{}", event.pallet_name(), event.variant_name()));
+		}
+		Ok(submitted)
+	}
+
+	/// Verifies that `signature` is a valid signature of the payload by the signer, so that an
+	/// invalid signature is reported before the extrinsic is submitted.
+	///
+	/// # Arguments
+	///
+	/// * `signature` - the hex encoded sr25519 signature of the payload
+	pub fn verify(&self, signature: &str) -> Result<[u8; 64], Error> {
+		let bytes =
+			decode_hex(signature.trim()).map_err(|e| Error::HexParsing(format!("{}", e)))?;
+		let signature: [u8; 64] = bytes.try_into().map_err(|bytes: Vec<u8>| {
+			Error::Signature(format!("expected 64 bytes, found {}", bytes.len()))
+		})?;
+		let public = sr25519::Public::from_raw(self.signer.0);
+		if !sr25519::Pair::verify(&sr25519::Signature::from_raw(signature), self.payload(), &public)
+		{
+			return Err(Error::Signature(format!(
+				"the signature does not match the payload signed by {}",
+				self.signer
+			)));
+		}
+		Ok(signature)
+	}
+}
+
+/// Prepares a call of a contract on behalf of `signer`, whose key is held by an external signer.
+/// The call is dry run on behalf of the signer to estimate its gas, unless provided.
+///
+/// # Arguments
+///
+/// * `call_opts` - options for the call, where the secret key URI is ignored
+/// * `signer` - the address of the account signing the call
+/// * `margin` - the margin added to the estimated gas, as a percentage
+pub async fn prepare_offline_call(
+	call_opts: &CallOpts,
+	signer: &str,
+	margin: u8,
+) -> Result<UnsignedExtrinsic, Error> {
+	let args =
+		resolve_args(&call_opts.path, FunctionType::Message, &call_opts.message, &call_opts.args)?;
+	let transcoder = transcoder(&call_opts.path)?;
+	let data = transcoder
+		.encode(&call_opts.message, args)
+		.map_err(|e| Error::IncorrectArguments(format!("{}", e)))?;
+	let token_metadata = TokenMetadata::query::<DefaultConfig>(&call_opts.url).await?;
+	let value = parse_value(&call_opts.value, &token_metadata)?;
+	if let Some(metadata) = load_metadata(&call_opts.path)? {
+		ensure_payable(&metadata, FunctionType::Message, &call_opts.message, value)?;
+	}
+	let signer = parse_account(signer)?;
+	let contract = parse_account(&call_opts.contract)?;
+
+	let client = OnlineClient::<DefaultConfig>::from_url(&call_opts.url).await?;
+	let params = (&signer, &contract, value, None::<Weight>, None::<u128>, &data).encode();
+	let result = client
+		.runtime_api()
+		.at_latest()
+		.await?
+		.call_raw("ContractsApi_call", Some(&params))
+		.await?;
+	let (gas_required, storage_deposit) = decode_dry_run(&result)?;
+	let weight = gas_limit(call_opts.gas_limit, call_opts.proof_size, gas_required, margin);
+
+	let call = subxt::dynamic::tx(
+		"Contracts",
+		"call",
+		vec![
+			Value::unnamed_variant("Id", [Value::from_bytes(contract.0)]),
+			Value::u128(value),
+			weight_value(weight),
+			Value::unnamed_variant("None", []),
+			Value::from_bytes(data),
+		],
+	);
+	prepare(&client, call, signer, GasEstimate { weight, storage_deposit }).await
+}
+
+/// Prepares the upload and instantiation of a contract on behalf of `signer`, whose key is held by
+/// an external signer. The instantiation is dry run on behalf of the signer to estimate its gas,
+/// unless provided.
+///
+/// # Arguments
+///
+/// * `up_opts` - options for the deployment, where the secret key URI is ignored
+/// * `signer` - the address of the account signing the deployment
+/// * `margin` - the margin added to the estimated gas, as a percentage
+pub async fn prepare_offline_instantiate(
+	up_opts: &UpOpts,
+	signer: &str,
+	margin: u8,
+) -> Result<UnsignedExtrinsic, Error> {
+	let args = resolve_args(
+		&up_opts.path,
+		FunctionType::Constructor,
+		&up_opts.constructor,
+		&up_opts.args,
+	)?;
+	let transcoder = transcoder(&up_opts.path)?;
+	let data = transcoder
+		.encode(&up_opts.constructor, args)
+		.map_err(|e| Error::IncorrectArguments(format!("{}", e)))?;
+	let code = fs::read(metadata_path(&up_opts.path)?.with_extension("wasm"))?;
+	let salt = up_opts.salt.clone().map(|s| s.0).unwrap_or_default();
+	let token_metadata = TokenMetadata::query::<DefaultConfig>(&up_opts.url).await?;
+	let value = parse_value(&up_opts.value, &token_metadata)?;
+	if let Some(metadata) = load_metadata(&up_opts.path)? {
+		ensure_payable(&metadata, FunctionType::Constructor, &up_opts.constructor, value)?;
+	}
+	let signer = parse_account(signer)?;
+
+	let client = OnlineClient::<DefaultConfig>::from_url(&up_opts.url).await?;
+	// The code is provided as `Code::Upload`, the first variant of `Code`.
+	let params =
+		(&signer, value, None::<Weight>, None::<u128>, (0u8, &code), &data, &salt).encode();
+	let result = client
+		.runtime_api()
+		.at_latest()
+		.await?
+		.call_raw("ContractsApi_instantiate", Some(&params))
+		.await?;
+	let (gas_required, storage_deposit) = decode_dry_run(&result)?;
+	let weight = gas_limit(up_opts.gas_limit, up_opts.proof_size, gas_required, margin);
+
+	let call = subxt::dynamic::tx(
+		"Contracts",
+		"instantiate_with_code",
+		vec![
+			Value::u128(value),
+			weight_value(weight),
+			Value::unnamed_variant("None", []),
+			Value::from_bytes(code),
+			Value::from_bytes(data),
+			Value::from_bytes(salt),
+		],
+	);
+	prepare(&client, call, signer, GasEstimate { weight, storage_deposit }).await
+}
+
+async fn prepare(
+	client: &OnlineClient<DefaultConfig>,
+	call: DynamicPayload,
+	signer: AccountId32,
+	estimate: GasEstimate,
+) -> Result<UnsignedExtrinsic, Error> {
+	let params = PolkadotExtrinsicParamsBuilder::<DefaultConfig>::new().build();
+	let partial = client.tx().create_partial_signed(&call, &signer, params).await?;
+	Ok(UnsignedExtrinsic { partial, signer, estimate })
+}
+
+fn transcoder(path: &Option<PathBuf>) -> Result<ContractMessageTranscoder, Error> {
+	ContractMessageTranscoder::load(metadata_path(path)?)
+		.map_err(|e| Error::MetadataParsing(format!("{}", e)))
+}
+
+fn weight_value(weight: Weight) -> Value {
+	Value::named_composite([
+		("ref_time", Value::u128(weight.ref_time() as u128)),
+		("proof_size", Value::u128(weight.proof_size() as u128)),
+	])
+}
+
+/// Decodes the result of a contract dry run (`ContractResult`), returning the gas required and the
+/// storage deposit charged.
+fn decode_dry_run(result: &[u8]) -> Result<(Weight, u128), Error> {
+	let error = |e: subxt::ext::codec::Error| Error::Decode(format!("the dry run result: {e}"));
+	let input = &mut &result[..];
+	let _gas_consumed = Weight::decode(input).map_err(error)?;
+	let gas_required = Weight::decode(input).map_err(error)?;
+	// The storage deposit is either refunded (0) or charged (1).
+	let deposit_kind = u8::decode(input).map_err(error)?;
+	let deposit = u128::decode(input).map_err(error)?;
+	let storage_deposit = if deposit_kind == 1 { deposit } else { 0 };
+	let debug_message = Vec::<u8>::decode(input).map_err(error)?;
+	let debug_message = String::from_utf8_lossy(&debug_message);
+	match u8::decode(input).map_err(error)? {
+		0 if u32::decode(input).map_err(error)? & REVERT_FLAG == 0 => {
+			Ok((gas_required, storage_deposit))
+		},
+		0 => Err(Error::DryRun(format!("the contract reverted {debug_message}"))),
+		_ => Err(Error::DryRun(format!("the dispatch failed {debug_message}"))),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn dry_run_result(storage_deposit: (u8, u128), result: Result<u32, ()>) -> Vec<u8> {
+		let mut encoded = (
+			Weight::from_parts(1_000, 10),
+			Weight::from_parts(2_000, 20),
+			storage_deposit.0,
+			storage_deposit.1,
+			b"debug".to_vec(),
+		)
+			.encode();
+		match result {
+			Ok(flags) => encoded.extend((0u8, flags, Vec::<u8>::new()).encode()),
+			Err(_) => encoded.extend((1u8, 0u8).encode()),
+		}
+		encoded
+	}
+
+	#[test]
+	fn test_decode_dry_run() -> anyhow::Result<()> {
+		assert_eq!(
+			decode_dry_run(&dry_run_result((1, 100), Ok(0)))?,
+			(Weight::from_parts(2_000, 20), 100)
+		);
+		// Refunds are not charged.
+		assert_eq!(decode_dry_run(&dry_run_result((0, 100), Ok(0)))?.1, 0);
+		Ok(())
+	}
+
+	#[test]
+	fn test_decode_failed_dry_run() {
+		assert!(matches!(
+			decode_dry_run(&dry_run_result((1, 100), Ok(REVERT_FLAG))),
+			Err(Error::DryRun(e)) if e.contains("reverted debug")
+		));
+		assert!(matches!(
+			decode_dry_run(&dry_run_result((1, 100), Err(()))),
+			Err(Error::DryRun(e)) if e.contains("dispatch failed")
+		));
+		assert!(matches!(decode_dry_run(&[0u8; 3]), Err(Error::Decode(..))));
+	}
+}
@@ -0,0 +1,283 @@
+// SPDX-License-Identifier: GPL-3.0
+use crate::{
+	errors::Error,
+	utils::{
+		helpers::to_hex,
+		metadata::{array, find, metadata_file},
+		storage::contract_info,
+	},
+};
+use contract_build::util::decode_hex;
+use contract_transcode::ContractMessageTranscoder;
+use serde::Serialize;
+use serde_json::Value;
+use sp_core::hashing::blake2_128;
+use std::{
+	fs,
+	path::{Path, PathBuf},
+};
+use subxt::{
+	backend::rpc::{rpc_params, RpcClient},
+	OnlineClient, PolkadotConfig as DefaultConfig,
+};
+use url::Url;
+
+/// The number of keys fetched per request when dumping the storage of a contract.
+const PAGE_SIZE: u32 = 1000;
+
+/// A value stored by a contract, decoded using the storage layout of its metadata.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct StorageEntry {
+	/// The path of the storage field within the storage layout, e.g. `balances` or `data.owner`.
+	pub field: String,
+	/// The hex encoded storage key, as used by the contract, which can be used to look up the
+	/// value again.
+	pub key: String,
+	/// The decoded key of the entry, for entries of a `Mapping`.
+	pub mapping_key: Option<String>,
+	/// The decoded value, or the hex encoded value when it could not be decoded.
+	pub value: String,
+}
+
+/// Fetches the storage of the contract deployed at `contract` from its child trie, decoding it
+/// using the storage layout of the contract metadata.
+///
+/// # Arguments
+///
+/// * `path` - path to the contract project, whose metadata is read from its build artifacts
+/// * `metadata` - path to a metadata (`.json`) or bundle (`.contract`) file, used instead of the
+///   build artifacts of the project when provided
+/// * `url` - websocket endpoint of a node
+/// * `contract` - the address of the deployed contract
+/// * `key` - a hex encoded storage key to look up, rather than fetching the entire storage
+pub async fn query_contract_storage(
+	path: &Option<PathBuf>,
+	metadata: Option<&Path>,
+	url: &Url,
+	contract: &str,
+	key: Option<&str>,
+) -> Result<Vec<StorageEntry>, Error> {
+	let metadata = metadata_file(path, metadata)?;
+	let json: Value = serde_json::from_str(&fs::read_to_string(&metadata)?)
+		.map_err(|e| Error::MetadataParsing(format!("{}", e)))?;
+	let transcoder = ContractMessageTranscoder::load(&metadata)
+		.map_err(|e| Error::MetadataParsing(format!("{}", e)))?;
+	let cells = cells(&json);
+
+	let rpc = RpcClient::from_url(url).await?;
+	let client = OnlineClient::<DefaultConfig>::from_rpc_client(rpc.clone()).await?;
+	let (trie_id, _) = contract_info(&client, contract)
+		.await?
+		.ok_or(Error::ContractStorage(format!("no contract found at {contract}")))?;
+	let child_key = to_hex(&[b":child_storage:default:".as_slice(), &trie_id].concat());
+
+	// Values are stored under the blake2-128 hash of their key, followed by the key itself.
+	let keys = match key {
+		Some(key) => {
+			let key = decode_hex(key).map_err(|e| Error::HexParsing(format!("{}", e)))?;
+			vec![to_hex(&[blake2_128(&key).as_slice(), &key].concat())]
+		},
+		None => child_keys(&rpc, &child_key).await?,
+	};
+	let mut entries = Vec::new();
+	for hashed_key in keys {
+		let value: Option<String> = rpc
+			.request("childstate_getStorage", rpc_params![&child_key, &hashed_key])
+			.await?;
+		let (Some(value), Ok(hashed_key)) = (value, decode_hex(&hashed_key)) else {
+			continue;
+		};
+		let value = decode_hex(&value).map_err(|e| Error::HexParsing(format!("{}", e)))?;
+		let key = hashed_key.get(16..).unwrap_or_default();
+		entries.extend(decode_entry(&cells, &transcoder, key, &value));
+	}
+	Ok(entries)
+}
+
+/// Fetches all keys within the child trie identified by `child_key`.
+async fn child_keys(rpc: &RpcClient, child_key: &str) -> Result<Vec<String>, Error> {
+	let mut keys: Vec<String> = Vec::new();
+	loop {
+		let page: Vec<String> = rpc
+			.request(
+				"childstate_getKeysPaged",
+				rpc_params![child_key, "0x", PAGE_SIZE, keys.last()],
+			)
+			.await?;
+		let done = page.len() < PAGE_SIZE as usize;
+		keys.extend(page);
+		if done {
+			return Ok(keys);
+		}
+	}
+}
+
+/// A cell of contract storage, stored under its own root key: the root storage struct, or a
+/// `Mapping` or `Lazy` field.
+#[derive(Debug, PartialEq)]
+struct Cell {
+	/// The root key of the cell.
+	root_key: Vec<u8>,
+	/// The path of the field stored within the cell.
+	path: String,
+	/// The paths and types of the values stored within the cell, in their encoding order.
+	leaves: Vec<(String, u64)>,
+	/// The type of the keys, for cells of a `Mapping`.
+	key_type: Option<u64>,
+}
+
+/// The cells described by the storage layout of the contract `metadata`.
+fn cells(metadata: &Value) -> Vec<Cell> {
+	let types = metadata["types"].as_array().map(|t| t.as_slice()).unwrap_or_default();
+	let mut cells = Vec::new();
+	collect(types, &metadata["storage"], "", &mut Vec::new(), &mut cells);
+	cells
+}
+
+fn collect(
+	types: &[Value],
+	layout: &Value,
+	path: &str,
+	leaves: &mut Vec<(String, u64)>,
+	cells: &mut Vec<Cell>,
+) {
+	if let Some(root) = layout.get("root") {
+		let mut cell_leaves = Vec::new();
+		collect(types, &root["layout"], path, &mut cell_leaves, cells);
+		// The type of the field is available as of ink! v5, providing the key type of mappings.
+		let key_type = root["ty"]
+			.as_u64()
+			.and_then(|id| find(types, id))
+			.filter(|ty| array(&ty["path"]).last().and_then(|p| p.as_str()) == Some("Mapping"))
+			.and_then(|ty| ty["params"][0]["type"].as_u64());
+		let path = match path.is_empty() {
+			true => root["layout"]["struct"]["name"].as_str().unwrap_or("storage").to_string(),
+			false => path.to_string(),
+		};
+		let root_key = root["root_key"].as_str().and_then(|k| decode_hex(k).ok());
+		cells.push(Cell {
+			root_key: root_key.unwrap_or_default(),
+			path,
+			leaves: cell_leaves,
+			key_type,
+		});
+	} else if let Some(ty) = layout["leaf"]["ty"].as_u64() {
+		leaves.push((path.to_string(), ty));
+	} else if let Some(fields) = layout["struct"]["fields"].as_array() {
+		for field in fields {
+			let name = field["name"].as_str().unwrap_or_default();
+			let field_path = match path.is_empty() {
+				true => name.to_string(),
+				false => format!("{path}.{name}"),
+			};
+			collect(types, &field["layout"], &field_path, leaves, cells);
+		}
+	}
+}
+
+/// Decodes the `value` stored under `key` into an entry per value within its cell, falling back to
+/// the hex encoded value when it cannot be decoded.
+fn decode_entry(
+	cells: &[Cell],
+	transcoder: &ContractMessageTranscoder,
+	key: &[u8],
+	value: &[u8],
+) -> Vec<StorageEntry> {
+	let raw = |field: &str, mapping_key: Option<String>| StorageEntry {
+		field: field.to_string(),
+		key: to_hex(key),
+		mapping_key,
+		value: to_hex(value),
+	};
+	let (root_key, mapping_key) = key.split_at(key.len().min(4));
+	let Some(cell) = cells.iter().find(|c| c.root_key == root_key) else {
+		return vec![raw("<unknown>", None)];
+	};
+	// The key of a mapping entry follows the root key of the mapping.
+	let mapping_key = match (mapping_key.is_empty(), cell.key_type) {
+		(true, _) => None,
+		(false, Some(key_type)) => {
+			match transcoder.decode(key_type as u32, &mut &mapping_key[..]) {
+				Ok(decoded) => Some(decoded.to_string()),
+				Err(_) => Some(to_hex(mapping_key)),
+			}
+		},
+		(false, None) => Some(to_hex(mapping_key)),
+	};
+
+	let mut input = value;
+	let mut entries = Vec::new();
+	for (field, ty) in &cell.leaves {
+		match transcoder.decode(*ty as u32, &mut input) {
+			Ok(decoded) => entries.push(StorageEntry {
+				field: field.clone(),
+				key: to_hex(key),
+				mapping_key: mapping_key.clone(),
+				value: decoded.to_string(),
+			}),
+			Err(_) => return vec![raw(&cell.path, mapping_key)],
+		}
+	}
+	if entries.is_empty() {
+		entries.push(raw(&cell.path, mapping_key));
+	}
+	entries
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use serde_json::json;
+
+	fn erc20() -> Value {
+		json!({
+			"storage": { "root": {
+				"root_key": "0x00000000",
+				"layout": { "struct": { "name": "Erc20", "fields": [
+					{ "name": "total_supply", "layout": { "leaf": { "key": "0x00000000", "ty": 0 } } },
+					{ "name": "balances", "layout": { "root": {
+						"root_key": "0x2623dce7",
+						"layout": { "leaf": { "key": "0x2623dce7", "ty": 0 } },
+						"ty": 2
+					} } },
+					{ "name": "owner", "layout": { "struct": { "name": "Owner", "fields": [
+						{ "name": "account", "layout": { "leaf": { "key": "0x00000000", "ty": 1 } } }
+					] } } }
+				] } },
+				"ty": 3
+			} },
+			"types": [
+				{ "id": 0, "type": { "def": { "primitive": "u128" } } },
+				{ "id": 1, "type": { "path": ["ink_primitives", "types", "AccountId"], "def": { "composite": {} } } },
+				{ "id": 2, "type": { "path": ["ink_storage", "lazy", "mapping", "Mapping"], "params": [{ "name": "K", "type": 1 }, { "name": "V", "type": 0 }], "def": { "composite": {} } } },
+				{ "id": 3, "type": { "path": ["erc20", "Erc20"], "def": { "composite": {} } } }
+			]
+		})
+	}
+
+	#[test]
+	fn test_cells() {
+		assert_eq!(
+			cells(&erc20()),
+			vec![
+				Cell {
+					root_key: vec![0x26, 0x23, 0xdc, 0xe7],
+					path: "balances".into(),
+					leaves: vec![("balances".into(), 0)],
+					key_type: Some(1),
+				},
+				Cell {
+					root_key: vec![0, 0, 0, 0],
+					path: "Erc20".into(),
+					leaves: vec![("total_supply".into(), 0), ("owner.account".into(), 1)],
+					key_type: None,
+				},
+			]
+		);
+	}
+
+	#[test]
+	fn test_cells_without_storage_layout() {
+		assert!(cells(&json!({})).is_empty());
+	}
+}
@@ -0,0 +1,173 @@
+// SPDX-License-Identifier: GPL-3.0
+use crate::{
+	errors::Error,
+	utils::metadata::{array, metadata_path},
+};
+use contract_build::BuildResult;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{
+	fmt::{self, Display, Formatter},
+	fs,
+	path::{Path, PathBuf},
+};
+
+/// The extension of the build report, stored alongside the contract metadata in the target
+/// directory.
+const REPORT_EXTENSION: &str = "build-report.json";
+
+/// The code size and contents of a contract build.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct BuildMetrics {
+	/// The size of the wasm before optimization, in KiB.
+	pub original_size: Option<f64>,
+	/// The size of the optimized wasm, in KiB.
+	pub optimized_size: Option<f64>,
+	/// The size of the metadata, in KiB.
+	pub metadata_size: Option<f64>,
+	/// The number of messages of the contract.
+	pub messages: usize,
+	/// The number of events of the contract.
+	pub events: usize,
+}
+
+/// A report of the latest build of a contract, compared against the build before it to help track
+/// code size regressions.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct BuildReport {
+	/// The metrics of the latest build.
+	pub current: BuildMetrics,
+	/// The metrics of the previous build, if any.
+	pub previous: Option<BuildMetrics>,
+}
+
+impl Display for BuildReport {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		let previous = self.previous.as_ref();
+		let size = |current: Option<f64>, previous: Option<f64>| -> String {
+			let Some(current) = current else { return "-".into() };
+			match previous {
+				Some(previous) if format!("{current:.1}") != format!("{previous:.1}") => {
+					format!("{current:.1}K ({:+.1}K)", current - previous)
+				},
+				_ => format!("{current:.1}K"),
+			}
+		};
+		let count = |current: usize, previous: Option<usize>| -> String {
+			match previous {
+				Some(previous) if previous != current => {
+					format!("{current} ({:+})", current as i64 - previous as i64)
+				},
+				_ => current.to_string(),
+			}
+		};
+		let current = &self.current;
+		let rows = [
+			(
+				"Original wasm size",
+				size(current.original_size, previous.and_then(|p| p.original_size)),
+			),
+			(
+				"Optimized wasm size",
+				size(current.optimized_size, previous.and_then(|p| p.optimized_size)),
+			),
+			("Metadata size", size(current.metadata_size, previous.and_then(|p| p.metadata_size))),
+			("Messages", count(current.messages, previous.map(|p| p.messages))),
+			("Events", count(current.events, previous.map(|p| p.events))),
+		];
+		let lines: Vec<_> =
+			rows.iter().map(|(label, value)| format!("{label:<20} {value}")).collect();
+		write!(f, "{}", lines.join("\n"))
+	}
+}
+
+/// Records the metrics of the provided build, keeping the metrics of the build before it for
+/// comparison.
+pub(crate) fn record_build_report(result: &BuildResult) -> Result<Option<BuildReport>, Error> {
+	let Some(metadata) = result.metadata_result.as_ref().map(|m| &m.dest_metadata) else {
+		return Ok(None);
+	};
+	let json: Value = serde_json::from_str(&fs::read_to_string(metadata)?)
+		.map_err(|e| Error::MetadataParsing(format!("{}", e)))?;
+	let current = BuildMetrics {
+		original_size: result.optimization_result.as_ref().map(|r| r.original_size),
+		optimized_size: result.optimization_result.as_ref().map(|r| r.optimized_size),
+		metadata_size: Some(fs::metadata(metadata)?.len() as f64 / 1024.0),
+		messages: array(&json["spec"]["messages"]).count(),
+		events: array(&json["spec"]["events"]).count(),
+	};
+	let path = report_path(metadata);
+	let previous = read_report(&path)?.map(|report| report.current);
+	let report = BuildReport { current, previous };
+	fs::write(path, serde_json::to_string_pretty(&report).map_err(anyhow::Error::from)?)?;
+	Ok(Some(report))
+}
+
+/// Returns the report of the latest build of the contract at `path`, if it has been built.
+///
+/// # Arguments
+///
+/// * `path` - path to the contract project
+pub fn build_report(path: &Option<PathBuf>) -> Result<Option<BuildReport>, Error> {
+	read_report(&report_path(&metadata_path(path)?))
+}
+
+fn report_path(metadata: &Path) -> PathBuf {
+	metadata.with_extension(REPORT_EXTENSION)
+}
+
+fn read_report(path: &Path) -> Result<Option<BuildReport>, Error> {
+	if !path.exists() {
+		return Ok(None);
+	}
+	// An unreadable report, e.g. from an older version, is treated as missing.
+	Ok(serde_json::from_str(&fs::read_to_string(path)?).ok())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn metrics(optimized_size: f64, messages: usize) -> BuildMetrics {
+		BuildMetrics {
+			original_size: Some(20.0),
+			optimized_size: Some(optimized_size),
+			metadata_size: Some(5.0),
+			messages,
+			events: 1,
+		}
+	}
+
+	#[test]
+	fn test_display_build_report() {
+		let report = BuildReport { current: metrics(1.5, 3), previous: None };
+		assert_eq!(
+			report.to_string(),
+			"Original wasm size   20.0K\nOptimized wasm size  1.5K\nMetadata size        5.0K\n\
+			 Messages             3\nEvents               1"
+		);
+	}
+
+	#[test]
+	fn test_display_build_report_compared_with_previous_build() {
+		let report = BuildReport { current: metrics(1.5, 3), previous: Some(metrics(1.2, 4)) };
+		let report = report.to_string();
+		assert!(report.contains("Original wasm size   20.0K\n"));
+		assert!(report.contains("Optimized wasm size  1.5K (+0.3K)\n"));
+		assert!(report.contains("Messages             3 (-1)\n"));
+	}
+
+	#[test]
+	fn test_read_report() -> anyhow::Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let path = report_path(&temp_dir.path().join("flipper.json"));
+		assert_eq!(path.file_name().unwrap(), "flipper.build-report.json");
+		assert_eq!(read_report(&path)?, None);
+		let report = BuildReport { current: metrics(1.5, 3), previous: Some(metrics(1.25, 4)) };
+		fs::write(&path, serde_json::to_string(&report)?)?;
+		assert_eq!(read_report(&path)?, Some(report));
+		fs::write(&path, "{}")?;
+		assert_eq!(read_report(&path)?, None);
+		Ok(())
+	}
+}
@@ -0,0 +1,96 @@
+// SPDX-License-Identifier: GPL-3.0
+use strum::{EnumMessage as EnumMessageT, EnumProperty as EnumPropertyT};
+use strum_macros::{AsRefStr, Display, EnumMessage, EnumProperty, EnumString, VariantArray};
+
+/// The customization of a token contract, substituted into the generated code and tests.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TokenConfig {
+	/// The name of the token.
+	pub name: String,
+	/// The symbol of the token.
+	pub symbol: String,
+	/// The number of decimals of the token, only applicable to fungible tokens.
+	pub decimals: u8,
+	/// The initial supply of the token in whole units, minted to the deployer. Only applicable to
+	/// fungible tokens.
+	pub supply: u128,
+}
+
+#[derive(
+	AsRefStr,
+	Clone,
+	Debug,
+	Default,
+	Display,
+	EnumMessage,
+	EnumProperty,
+	EnumString,
+	Eq,
+	PartialEq,
+	VariantArray,
+)]
+pub enum Template {
+	#[default]
+	#[strum(
+		ascii_case_insensitive,
+		serialize = "standard",
+		message = "Standard",
+		detailed_message = "A minimal contract which stores and flips a boolean value."
+	)]
+	Standard,
+	#[strum(
+		ascii_case_insensitive,
+		serialize = "psp22",
+		message = "PSP22",
+		detailed_message = "A fungible token, compliant with the PSP22 standard.",
+		props(Token = "true")
+	)]
+	PSP22,
+	#[strum(
+		ascii_case_insensitive,
+		serialize = "psp34",
+		message = "PSP34",
+		detailed_message = "A non-fungible token (NFT), compliant with the PSP34 standard.",
+		props(Token = "true")
+	)]
+	PSP34,
+}
+
+impl Template {
+	pub fn name(&self) -> &str {
+		self.get_message().unwrap_or_default()
+	}
+
+	pub fn description(&self) -> &str {
+		self.get_detailed_message().unwrap_or_default()
+	}
+
+	/// Whether the template generates a token, which is customized with a [`TokenConfig`].
+	pub fn is_token(&self) -> bool {
+		self.get_str("Token") == Some("true")
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::str::FromStr;
+	use strum::VariantArray;
+
+	#[test]
+	fn test_templates_from_str() {
+		assert_eq!(Template::from_str("standard").unwrap(), Template::Standard);
+		assert_eq!(Template::from_str("psp22").unwrap(), Template::PSP22);
+		assert_eq!(Template::from_str("PSP34").unwrap(), Template::PSP34);
+		assert!(Template::from_str("erc20").is_err());
+	}
+
+	#[test]
+	fn test_token_templates() {
+		for template in Template::VARIANTS {
+			assert!(!template.name().is_empty());
+			assert!(!template.description().is_empty());
+			assert_eq!(template.is_token(), !matches!(template, Template::Standard));
+		}
+	}
+}
@@ -5,6 +5,7 @@ use std::path::PathBuf;
 
 pub fn test_smart_contract(path: &Option<PathBuf>) -> Result<(), Error> {
 	// Execute `cargo test` command in the specified directory.
+	tracing::debug!(?path, "running unit tests");
 	cmd("cargo", vec!["test"])
 		.dir(path.clone().unwrap_or_else(|| PathBuf::from("./")))
 		.run()
@@ -14,6 +15,7 @@ pub fn test_smart_contract(path: &Option<PathBuf>) -> Result<(), Error> {
 
 pub fn test_e2e_smart_contract(path: &Option<PathBuf>) -> Result<(), Error> {
 	// Execute `cargo test --features=e2e-tests` command in the specified directory.
+	tracing::debug!(?path, "running e2e tests");
 	cmd("cargo", vec!["test", "--features=e2e-tests"])
 		.dir(path.clone().unwrap_or_else(|| PathBuf::from("./")))
 		.run()
@@ -32,7 +34,7 @@ mod tests {
 		let temp_dir = tempfile::tempdir()?;
 		let temp_contract_dir = temp_dir.path().join("test_contract");
 		fs::create_dir(&temp_contract_dir)?;
-		crate::create_smart_contract("test_contract", temp_contract_dir.as_path())?;
+		crate::create_smart_contract("test_contract", temp_contract_dir.as_path(), None, true)?;
 		Ok(temp_dir)
 	}
 
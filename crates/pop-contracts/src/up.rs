@@ -1,18 +1,25 @@
 // SPDX-License-Identifier: GPL-3.0
-use crate::utils::{
-	helpers::{get_manifest_path, parse_balance},
-	signer::create_signer,
+use crate::{
+	errors::Error,
+	estimate::{gas_limit, GasEstimate},
+	utils::{
+		helpers::{get_manifest_path, parse_value},
+		metadata::{ensure_payable, load_metadata, metadata_path, resolve_args, FunctionType},
+		signer::{create_signer, parse_hex_bytes},
+		storage::pristine_code,
+	},
 };
 use contract_extrinsics::{
-	BalanceVariant, ErrorVariant, ExtrinsicOptsBuilder, InstantiateCommandBuilder, InstantiateExec,
-	TokenMetadata,
+	ErrorVariant, ExtrinsicOptsBuilder, InstantiateCommandBuilder, InstantiateExec, TokenMetadata,
+	UploadCommandBuilder, UploadExec,
 };
-use ink_env::{DefaultEnvironment, Environment};
+use ink_env::DefaultEnvironment;
 use sp_core::Bytes;
 use sp_weights::Weight;
 use std::path::PathBuf;
-use subxt::PolkadotConfig as DefaultConfig;
+use subxt::{OnlineClient, PolkadotConfig as DefaultConfig};
 use subxt_signer::sr25519::Keypair;
+use url::Url;
 
 pub struct UpOpts {
 	/// Path to the contract build folder.
@@ -34,28 +41,45 @@ pub struct UpOpts {
 	pub url: url::Url,
 	/// Secret key URI for the account deploying the contract.
 	pub suri: String,
+	/// Instantiate the contract from its code already uploaded on chain, rather than uploading it.
+	pub skip_upload: bool,
 }
 pub async fn set_up_deployment(
 	up_opts: UpOpts,
 ) -> anyhow::Result<InstantiateExec<DefaultConfig, DefaultEnvironment, Keypair>> {
+	let args = resolve_args(
+		&up_opts.path,
+		FunctionType::Constructor,
+		&up_opts.constructor,
+		&up_opts.args,
+	)?;
 	let manifest_path = get_manifest_path(&up_opts.path)?;
 
 	let token_metadata = TokenMetadata::query::<DefaultConfig>(&up_opts.url).await?;
 
 	let signer = create_signer(&up_opts.suri)?;
+	// Only the metadata is provided when skipping the upload, so that the contract is instantiated
+	// using the code hash declared within it.
+	let file = match up_opts.skip_upload {
+		true => Some(metadata_path(&up_opts.path)?),
+		false => None,
+	};
 	let extrinsic_opts = ExtrinsicOptsBuilder::new(signer)
 		.manifest_path(Some(manifest_path))
+		.file(file)
 		.url(up_opts.url.clone())
 		.done();
 
-	let value: BalanceVariant<<DefaultEnvironment as Environment>::Balance> =
-		parse_balance(&up_opts.value)?;
+	let value = parse_value(&up_opts.value, &token_metadata)?;
+	if let Some(metadata) = load_metadata(&up_opts.path)? {
+		ensure_payable(&metadata, FunctionType::Constructor, &up_opts.constructor, value)?;
+	}
 
 	let instantiate_exec: InstantiateExec<DefaultConfig, DefaultEnvironment, Keypair> =
 		InstantiateCommandBuilder::new(extrinsic_opts)
 			.constructor(up_opts.constructor.clone())
-			.args(up_opts.args.clone())
-			.value(value.denominate_balance(&token_metadata)?)
+			.args(args)
+			.value(value)
 			.gas_limit(up_opts.gas_limit)
 			.proof_size(up_opts.proof_size)
 			.salt(up_opts.salt.clone())
@@ -64,22 +88,102 @@ pub async fn set_up_deployment(
 	return Ok(instantiate_exec);
 }
 
+/// Prepares the upload of the code of the contract at `path`, without instantiating it.
+///
+/// # Arguments
+///
+/// * `up_opts` - options for the upload, where only the path, url and secret key URI are used
+pub async fn set_up_upload(
+	up_opts: UpOpts,
+) -> anyhow::Result<UploadExec<DefaultConfig, DefaultEnvironment, Keypair>> {
+	let manifest_path = get_manifest_path(&up_opts.path)?;
+	let signer = create_signer(&up_opts.suri)?;
+	let extrinsic_opts = ExtrinsicOptsBuilder::new(signer)
+		.manifest_path(Some(manifest_path))
+		.url(up_opts.url.clone())
+		.done();
+	let upload_exec: UploadExec<DefaultConfig, DefaultEnvironment, Keypair> =
+		UploadCommandBuilder::new(extrinsic_opts).done().await?;
+	Ok(upload_exec)
+}
+
+/// Performs a dry-run of the upload, returning the storage deposit required to store the code.
+///
+/// # Arguments
+///
+/// * `upload_exec` - the upload to be estimated
+pub async fn dry_run_upload(
+	upload_exec: &UploadExec<DefaultConfig, DefaultEnvironment, Keypair>,
+) -> anyhow::Result<u128> {
+	match upload_exec.upload_code_rpc().await? {
+		Ok(result) => Ok(result.deposit),
+		Err(err) => Err(anyhow::anyhow!("Pre-submission dry-run failed: {err:?}")),
+	}
+}
+
+/// Uploads the contract code without instantiating it, returning its code hash.
+///
+/// # Arguments
+///
+/// * `upload_exec` - the upload to be submitted
+pub async fn upload_smart_contract(
+	upload_exec: &UploadExec<DefaultConfig, DefaultEnvironment, Keypair>,
+) -> anyhow::Result<String> {
+	let upload_result = upload_exec
+		.upload_code()
+		.await
+		.map_err(|e| anyhow::anyhow!("Upload failed: {e:?}"))?;
+	let code_stored = upload_result
+		.code_stored
+		.ok_or(anyhow::anyhow!("The contract code has already been uploaded"))?;
+	Ok(format!("{:?}", code_stored.code_hash))
+}
+
+/// Returns the code hash of the built contract at `path` if its code has already been uploaded to
+/// the chain, in which case the upload can be skipped.
+///
+/// # Arguments
+///
+/// * `path` - path to the contract project
+/// * `url` - websocket endpoint of a node
+pub async fn uploaded_code_hash(
+	path: &Option<PathBuf>,
+	url: &Url,
+) -> Result<Option<String>, Error> {
+	let Some(code_hash) = load_metadata(path)?
+		.and_then(|metadata| metadata["source"]["hash"].as_str().map(|h| h.to_string()))
+	else {
+		return Ok(None);
+	};
+	let client = OnlineClient::<DefaultConfig>::from_url(url).await?;
+	let uploaded = pristine_code(&client, &parse_hex_bytes(&code_hash)?).await?.is_some();
+	Ok(uploaded.then_some(code_hash))
+}
+
+/// Performs a dry-run of the instantiation, estimating the gas and storage deposit required.
+///
+/// # Arguments
+///
+/// * `instantiate_exec` - the instantiation to be estimated
+/// * `margin` - the margin added to the estimated gas, as a percentage
 pub async fn dry_run_gas_estimate_instantiate(
 	instantiate_exec: &InstantiateExec<DefaultConfig, DefaultEnvironment, Keypair>,
-) -> anyhow::Result<Weight> {
+	margin: u8,
+) -> anyhow::Result<GasEstimate> {
 	let instantiate_result = instantiate_exec.instantiate_dry_run().await?;
 	match instantiate_result.result {
 		Ok(_) => {
 			// use user specified values where provided, otherwise use the estimates
-			let ref_time = instantiate_exec
-				.args()
-				.gas_limit()
-				.unwrap_or_else(|| instantiate_result.gas_required.ref_time());
-			let proof_size = instantiate_exec
-				.args()
-				.proof_size()
-				.unwrap_or_else(|| instantiate_result.gas_required.proof_size());
-			Ok(Weight::from_parts(ref_time, proof_size))
+			let weight = gas_limit(
+				instantiate_exec.args().gas_limit(),
+				instantiate_exec.args().proof_size(),
+				instantiate_result.gas_required,
+				margin,
+			);
+			Ok(GasEstimate {
+				weight,
+				storage_deposit: instantiate_result.storage_deposit.charge_or_zero(),
+			})
 		},
 		Err(ref _err) => {
 			Err(anyhow::anyhow!(
@@ -89,10 +193,22 @@ pub async fn dry_run_gas_estimate_instantiate(
 	}
 }
 
+/// Result of a successful contract deployment.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContractInfo {
+	/// The address of the instantiated contract.
+	pub address: String,
+	/// The hash of the uploaded code, only available when the code was uploaded as part of the
+	/// instantiation.
+	pub code_hash: Option<String>,
+}
+
 pub async fn instantiate_smart_contract(
 	instantiate_exec: InstantiateExec<DefaultConfig, DefaultEnvironment, Keypair>,
 	gas_limit: Weight,
-) -> anyhow::Result<String, ErrorVariant> {
+) -> anyhow::Result<ContractInfo, ErrorVariant> {
 	let instantiate_result = instantiate_exec.instantiate(Some(gas_limit)).await?;
-	Ok(instantiate_result.contract_address.to_string())
+	// The code hash is only returned when the code was uploaded (upload + instantiate).
+	let code_hash = instantiate_result.code_hash.map(|code_hash| format!("{:?}", code_hash));
+	Ok(ContractInfo { address: instantiate_result.contract_address.to_string(), code_hash })
 }
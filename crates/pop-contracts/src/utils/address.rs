@@ -0,0 +1,202 @@
+// SPDX-License-Identifier: GPL-3.0
+use crate::{errors::Error, utils::helpers::to_hex};
+use contract_build::util::decode_hex;
+use sp_core::{
+	crypto::{AccountId32, Ss58AddressFormat, Ss58Codec},
+	hashing::{blake2_256, keccak_256},
+};
+use strum_macros::{AsRefStr, Display, EnumString, VariantArray};
+
+/// The default SS58 prefix, used by generic Substrate chains.
+pub const DEFAULT_SS58_PREFIX: u16 = 42;
+
+/// The representations an address can be converted into.
+#[derive(AsRefStr, Clone, Copy, Debug, Display, EnumString, Eq, PartialEq, VariantArray)]
+pub enum AddressFormat {
+	/// An SS58 encoded account.
+	#[strum(ascii_case_insensitive, serialize = "ss58")]
+	Ss58,
+	/// A 32 byte hex encoded account.
+	#[strum(ascii_case_insensitive, serialize = "hex")]
+	Hex,
+	/// A 20 byte hex encoded Ethereum style address.
+	#[strum(ascii_case_insensitive, serialize = "h160")]
+	H160,
+}
+
+/// How 32 byte accounts are mapped to and from 20 byte Ethereum style addresses.
+#[derive(
+	AsRefStr, Clone, Copy, Debug, Default, Display, EnumString, Eq, PartialEq, VariantArray,
+)]
+pub enum AddressMapping {
+	/// The mapping used by `pallet-revive`: addresses are padded with `0xEE` bytes, while other
+	/// accounts are mapped to the last 20 bytes of their keccak-256 hash.
+	#[default]
+	#[strum(ascii_case_insensitive, serialize = "revive")]
+	Revive,
+	/// The mapping used by Frontier (`pallet-evm`): addresses are mapped to the blake2-256 hash of
+	/// `evm:` followed by the address, while accounts are truncated to their first 20 bytes.
+	#[strum(ascii_case_insensitive, serialize = "frontier")]
+	Frontier,
+}
+
+impl AddressMapping {
+	/// Maps a 20 byte address to a 32 byte account.
+	pub fn to_account(&self, address: [u8; 20]) -> [u8; 32] {
+		match self {
+			AddressMapping::Revive => {
+				let mut account = [0xEE; 32];
+				account[..20].copy_from_slice(&address);
+				account
+			},
+			AddressMapping::Frontier => blake2_256(&[b"evm:".as_slice(), &address].concat()),
+		}
+	}
+
+	/// Maps a 32 byte account to a 20 byte address.
+	pub fn to_address(&self, account: [u8; 32]) -> [u8; 20] {
+		let mut address = [0u8; 20];
+		match self {
+			AddressMapping::Revive if account[20..].iter().all(|b| *b == 0xEE) => {
+				address.copy_from_slice(&account[..20])
+			},
+			AddressMapping::Revive => address.copy_from_slice(&keccak_256(&account)[12..]),
+			AddressMapping::Frontier => address.copy_from_slice(&account[..20]),
+		}
+		address
+	}
+}
+
+/// An address, as provided by the user.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Address {
+	/// A 32 byte account, provided either SS58 or hex encoded.
+	Account([u8; 32]),
+	/// A 20 byte Ethereum style address.
+	H160([u8; 20]),
+}
+
+impl Address {
+	/// Parses an SS58 encoded account, a 32 byte hex encoded account or a 20 byte hex encoded
+	/// address.
+	///
+	/// # Arguments
+	///
+	/// * `input` - the address to parse
+	pub fn parse(input: &str) -> Result<Self, Error> {
+		let input = input.trim();
+		if input.starts_with("0x") {
+			let bytes = decode_hex(input).map_err(|e| Error::HexParsing(format!("{}", e)))?;
+			return match bytes.len() {
+				32 => Ok(Address::Account(bytes.try_into().expect("length checked above"))),
+				20 => Ok(Address::H160(bytes.try_into().expect("length checked above"))),
+				len => Err(Error::AccountAddressParsing(format!(
+					"expected 20 or 32 hex encoded bytes, found {len}"
+				))),
+			};
+		}
+		AccountId32::from_ss58check(input)
+			.map(|account| Address::Account(account.into()))
+			.map_err(|e| Error::AccountAddressParsing(format!("{:?}", e)))
+	}
+
+	/// The 32 byte account of the address, mapping 20 byte addresses using `mapping`.
+	pub fn account(&self, mapping: AddressMapping) -> [u8; 32] {
+		match self {
+			Address::Account(account) => *account,
+			Address::H160(address) => mapping.to_account(*address),
+		}
+	}
+
+	/// The 20 byte address, mapping 32 byte accounts using `mapping`.
+	pub fn h160(&self, mapping: AddressMapping) -> [u8; 20] {
+		match self {
+			Address::Account(account) => mapping.to_address(*account),
+			Address::H160(address) => *address,
+		}
+	}
+
+	/// Converts the address into the requested `format`.
+	///
+	/// # Arguments
+	///
+	/// * `format` - the representation to convert into
+	/// * `prefix` - the SS58 prefix, used when converting into an SS58 encoded account
+	/// * `mapping` - how accounts are mapped to and from 20 byte addresses
+	pub fn convert(&self, format: AddressFormat, prefix: u16, mapping: AddressMapping) -> String {
+		match format {
+			AddressFormat::Ss58 => AccountId32::new(self.account(mapping))
+				.to_ss58check_with_version(Ss58AddressFormat::custom(prefix)),
+			AddressFormat::Hex => to_hex(&self.account(mapping)),
+			AddressFormat::H160 => to_hex(&self.h160(mapping)),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+	use std::str::FromStr;
+
+	const ALICE: &str = "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY";
+	const ALICE_HEX: &str = "0xd43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d";
+
+	#[test]
+	fn test_parse_address() -> Result<()> {
+		let alice = Address::parse(ALICE)?;
+		assert_eq!(Address::parse(ALICE_HEX)?, alice);
+		assert!(matches!(Address::parse(&format!("0x{}", "ab".repeat(20)))?, Address::H160(_)));
+		assert!(matches!(Address::parse("0x1234"), Err(Error::AccountAddressParsing(..))));
+		assert!(matches!(Address::parse("0xzz"), Err(Error::HexParsing(..))));
+		assert!(matches!(Address::parse("alice"), Err(Error::AccountAddressParsing(..))));
+		Ok(())
+	}
+
+	#[test]
+	fn test_convert_account() -> Result<()> {
+		let alice = Address::parse(ALICE)?;
+		assert_eq!(alice.convert(AddressFormat::Hex, 42, AddressMapping::Revive), ALICE_HEX);
+		assert_eq!(alice.convert(AddressFormat::Ss58, 42, AddressMapping::Revive), ALICE);
+		assert_eq!(
+			alice.convert(AddressFormat::Ss58, 0, AddressMapping::Revive),
+			"15oF4uVJwmo4TdGW7VfQxNLavjCXviqxT9S1MgbjMNHr6Sp5"
+		);
+		assert_eq!(
+			alice.convert(AddressFormat::H160, 42, AddressMapping::Revive),
+			to_hex(&keccak_256(&decode_hex(ALICE_HEX)?)[12..])
+		);
+		assert_eq!(
+			alice.convert(AddressFormat::H160, 42, AddressMapping::Frontier),
+			ALICE_HEX[..42]
+		);
+		Ok(())
+	}
+
+	#[test]
+	fn test_convert_h160_round_trips_with_revive_mapping() -> Result<()> {
+		let h160 = format!("0x{}", "ab".repeat(20));
+		let address = Address::parse(&h160)?;
+		let account = address.convert(AddressFormat::Hex, 42, AddressMapping::Revive);
+		assert_eq!(account, format!("{h160}{}", "ee".repeat(12)));
+		let address = Address::parse(&account)?;
+		assert_eq!(address.convert(AddressFormat::H160, 42, AddressMapping::Revive), h160);
+		Ok(())
+	}
+
+	#[test]
+	fn test_convert_h160_with_frontier_mapping() -> Result<()> {
+		let address = [0xab; 20];
+		let account = Address::H160(address).account(AddressMapping::Frontier);
+		assert_eq!(account, blake2_256(&[b"evm:".as_slice(), &address].concat()));
+		Ok(())
+	}
+
+	#[test]
+	fn test_formats_from_str() {
+		assert_eq!(AddressFormat::from_str("SS58").unwrap(), AddressFormat::Ss58);
+		assert_eq!(AddressFormat::from_str("h160").unwrap(), AddressFormat::H160);
+		assert_eq!(AddressMapping::from_str("frontier").unwrap(), AddressMapping::Frontier);
+		assert!(AddressMapping::from_str("evm").is_err());
+	}
+}
@@ -1,10 +1,15 @@
 // SPDX-License-Identifier: GPL-3.0
 use crate::errors::Error;
 use contract_build::ManifestPath;
-use contract_extrinsics::BalanceVariant;
+use contract_extrinsics::{BalanceVariant, TokenMetadata};
 use ink_env::{DefaultEnvironment, Environment};
-use std::{path::PathBuf, str::FromStr};
+use std::{
+	fs,
+	path::{Path, PathBuf},
+	str::FromStr,
+};
 use subxt::{Config, PolkadotConfig as DefaultConfig};
+use toml_edit::DocumentMut;
 
 pub fn get_manifest_path(path: &Option<PathBuf>) -> Result<ManifestPath, Error> {
 	if let Some(path) = path {
@@ -23,11 +28,84 @@ pub fn parse_balance(
 	BalanceVariant::from_str(balance).map_err(|e| Error::BalanceParsing(format!("{}", e)))
 }
 
+/// Parses a `value` to be transferred into the smallest unit of the chain's token: either a plain
+/// amount (`1000`), or an amount of the token itself using its symbol (`10UNIT`, `1.5 UNIT`), which
+/// is converted using the decimals of the token.
+///
+/// # Arguments
+///
+/// * `value` - the value to parse
+/// * `token` - the symbol and decimals of the chain's token, read from the chain properties
+pub fn parse_value(value: &str, token: &TokenMetadata) -> Result<u128, Error> {
+	let value = value.replace('_', "");
+	let value = value.trim();
+	let error = |reason: &str| Error::BalanceParsing(format!("`{value}` {reason}"));
+	let split = value.find(|c: char| !(c.is_ascii_digit() || c == '.')).unwrap_or(value.len());
+	let (amount, symbol) = value.split_at(split);
+	let symbol = symbol.trim();
+	if amount.is_empty() {
+		return Err(error("is missing an amount"));
+	}
+	if symbol.is_empty() {
+		return amount.parse().map_err(|_| error("is not a valid amount"));
+	}
+	if !symbol.eq_ignore_ascii_case(&token.symbol) {
+		// Fall back to the denominations supported by cargo-contract, e.g. `1mUNIT`.
+		return parse_balance(value)
+			.ok()
+			.and_then(|balance| balance.denominate_balance(token).ok())
+			.ok_or_else(|| {
+				error(&format!("does not match the token of the chain: {}", token.symbol))
+			});
+	}
+	let (whole, fraction) = amount.split_once('.').unwrap_or((amount, ""));
+	if fraction.len() > token.decimals {
+		return Err(error(&format!("has more than {} decimals", token.decimals)));
+	}
+	format!("{whole}{fraction:0<width$}", width = token.decimals)
+		.parse()
+		.map_err(|_| error("is not a valid amount"))
+}
+
 pub fn parse_account(account: &str) -> Result<<DefaultConfig as Config>::AccountId, Error> {
 	<DefaultConfig as Config>::AccountId::from_str(account)
 		.map_err(|e| Error::AccountAddressParsing(format!("{}", e)))
 }
 
+/// Returns the version of the `ink` dependency declared in the manifest of the contract at `path`,
+/// if any.
+pub fn ink_version(path: &Option<PathBuf>) -> Result<Option<String>, Error> {
+	let manifest = path.clone().unwrap_or_else(|| PathBuf::from("./")).join("Cargo.toml");
+	if !manifest.exists() {
+		return Ok(None);
+	}
+	let manifest = fs::read_to_string(manifest)?
+		.parse::<DocumentMut>()
+		.map_err(|e| Error::ManifestParsing(format!("{}", e)))?;
+	let Some(ink) = manifest.get("dependencies").and_then(|d| d.get("ink")) else {
+		return Ok(None);
+	};
+	// Either `ink = "5.0.0"` or `ink = { version = "5.0.0", ... }`
+	Ok(ink
+		.as_str()
+		.or_else(|| ink.get("version").and_then(|v| v.as_str()))
+		.map(|v| v.trim_start_matches(|c: char| !c.is_ascii_digit()).to_string()))
+}
+
+/// Returns whether the package at `path` is an ink! contract, i.e. it depends on `ink`.
+///
+/// # Arguments
+///
+/// * `path` - path to the package
+pub fn is_contract(path: &Path) -> Result<bool, Error> {
+	Ok(ink_version(&Some(path.to_path_buf()))?.is_some())
+}
+
+/// Encodes `bytes` as a `0x` prefixed hex string.
+pub(crate) fn to_hex(bytes: &[u8]) -> String {
+	format!("0x{}", bytes.iter().map(|b| format!("{b:02x}")).collect::<String>())
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -38,7 +116,7 @@ mod tests {
 		let temp_dir = tempfile::tempdir().expect("Could not create temp dir");
 		let temp_contract_dir = temp_dir.path().join("test_contract");
 		fs::create_dir(&temp_contract_dir)?;
-		crate::create_smart_contract("test_contract", temp_contract_dir.as_path())?;
+		crate::create_smart_contract("test_contract", temp_contract_dir.as_path(), None, true)?;
 		Ok(temp_dir)
 	}
 
@@ -48,4 +126,67 @@ mod tests {
 		get_manifest_path(&Some(PathBuf::from(temp_dir.path().join("test_contract"))))?;
 		Ok(())
 	}
+
+	#[test]
+	fn test_ink_version() -> Result<(), Error> {
+		let temp_dir = setup_test_environment()?;
+		let version = ink_version(&Some(temp_dir.path().join("test_contract")))?;
+		assert!(version.is_some());
+
+		let manifest_dir = tempfile::tempdir()?;
+		fs::write(
+			manifest_dir.path().join("Cargo.toml"),
+			"[dependencies]\nink = { version = \"=4.3.0\", default-features = false }",
+		)?;
+		assert_eq!(ink_version(&Some(manifest_dir.path().to_path_buf()))?, Some("4.3.0".into()));
+
+		fs::write(manifest_dir.path().join("Cargo.toml"), "[dependencies]\nink = \"5.0.0\"")?;
+		assert_eq!(ink_version(&Some(manifest_dir.path().to_path_buf()))?, Some("5.0.0".into()));
+
+		// No ink dependency or no manifest at all
+		fs::write(manifest_dir.path().join("Cargo.toml"), "[dependencies]\n")?;
+		assert_eq!(ink_version(&Some(manifest_dir.path().to_path_buf()))?, None);
+		assert_eq!(ink_version(&Some(manifest_dir.path().join("missing")))?, None);
+		Ok(())
+	}
+
+	#[test]
+	fn test_is_contract() -> Result<(), Error> {
+		let temp_dir = setup_test_environment()?;
+		assert!(is_contract(&temp_dir.path().join("test_contract"))?);
+		assert!(!is_contract(temp_dir.path())?);
+		Ok(())
+	}
+
+	#[test]
+	fn test_parse_value() -> Result<(), Error> {
+		let token = TokenMetadata { decimals: 12, symbol: "UNIT".into() };
+		assert_eq!(parse_value("1_000", &token)?, 1_000);
+		assert_eq!(parse_value("10UNIT", &token)?, 10_000_000_000_000);
+		assert_eq!(parse_value("1.5 unit", &token)?, 1_500_000_000_000);
+		assert_eq!(parse_value("0.000000000001UNIT", &token)?, 1);
+		Ok(())
+	}
+
+	#[test]
+	fn test_parse_invalid_value() {
+		let token = TokenMetadata { decimals: 12, symbol: "UNIT".into() };
+		for (value, reason) in [
+			("10DOT", "does not match the token of the chain: UNIT"),
+			("0.0000000000001UNIT", "has more than 12 decimals"),
+			("UNIT", "is missing an amount"),
+			("1.2.3UNIT", "is not a valid amount"),
+			("1.5", "is not a valid amount"),
+		] {
+			assert!(matches!(
+				parse_value(value, &token),
+				Err(crate::errors::Error::BalanceParsing(e)) if e.ends_with(reason)
+			));
+		}
+	}
+
+	#[test]
+	fn test_to_hex() {
+		assert_eq!(to_hex(&[0, 1, 171, 255]), "0x0001abff");
+	}
 }
@@ -0,0 +1,610 @@
+// SPDX-License-Identifier: GPL-3.0
+use crate::{
+	errors::Error,
+	utils::{
+		address::{Address, AddressFormat, AddressMapping, DEFAULT_SS58_PREFIX},
+		helpers::{get_manifest_path, parse_account},
+	},
+};
+use contract_build::CrateMetadata;
+use serde_json::Value;
+use std::{
+	fs,
+	path::{Path, PathBuf},
+};
+
+/// The kind of contract function being invoked.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FunctionType {
+	/// A constructor, used when instantiating a contract.
+	Constructor,
+	/// A message, used when calling a contract.
+	Message,
+}
+
+/// The path to the metadata generated when building the contract at `path`.
+pub(crate) fn metadata_path(path: &Option<PathBuf>) -> Result<PathBuf, Error> {
+	let manifest_path = get_manifest_path(path)?;
+	Ok(CrateMetadata::collect(&manifest_path)?.metadata_path())
+}
+
+/// The metadata file of a contract: the provided `metadata` (`.json`) or bundle (`.contract`) file,
+/// otherwise the metadata generated when building the contract at `path`.
+pub(crate) fn metadata_file(
+	path: &Option<PathBuf>,
+	metadata: Option<&Path>,
+) -> Result<PathBuf, Error> {
+	let metadata = match metadata {
+		Some(metadata) => metadata.to_path_buf(),
+		None => metadata_path(path)?,
+	};
+	if !metadata.exists() {
+		return Err(Error::MetadataParsing(format!(
+			"{} not found, build the contract first with `pop build contract`",
+			metadata.display()
+		)));
+	}
+	Ok(metadata)
+}
+
+/// Loads the metadata of the contract at `path`, if it has been built.
+pub fn load_metadata(path: &Option<PathBuf>) -> Result<Option<Value>, Error> {
+	let path = metadata_path(path)?;
+	if !path.exists() {
+		return Ok(None);
+	}
+	serde_json::from_str(&fs::read_to_string(path)?)
+		.map(Some)
+		.map_err(|e| Error::MetadataParsing(format!("{}", e)))
+}
+
+/// Converts any addresses to the representation expected by the contract at `path`, then validates
+/// the arguments against its metadata so that invalid arguments are reported before attempting to
+/// encode them. The arguments are returned unchanged when the contract has not been built.
+///
+/// # Arguments
+///
+/// * `path` - path to the contract project
+/// * `function_type` - whether a constructor or message is being invoked
+/// * `label` - the label of the constructor or message
+/// * `args` - the arguments, as provided by the user
+pub(crate) fn resolve_args(
+	path: &Option<PathBuf>,
+	function_type: FunctionType,
+	label: &str,
+	args: &[String],
+) -> Result<Vec<String>, Error> {
+	let Some(metadata) = load_metadata(path)? else {
+		return Ok(args.to_vec());
+	};
+	let args = convert_args(&metadata, function_type, label, args, AddressMapping::default())?;
+	validate_args(&metadata, function_type, label, &args)?;
+	Ok(args)
+}
+
+/// Validates the provided arguments against the types declared in the contract metadata for the
+/// constructor or message with the provided `label`, so that invalid arguments are reported
+/// individually rather than as an encoding failure.
+///
+/// # Arguments
+///
+/// * `metadata` - the metadata of the contract
+/// * `function_type` - whether a constructor or message is being invoked
+/// * `label` - the label of the constructor or message
+/// * `args` - the arguments, as provided by the user
+pub fn validate_args(
+	metadata: &Value,
+	function_type: FunctionType,
+	label: &str,
+	args: &[String],
+) -> Result<(), Error> {
+	let function = function(metadata, function_type, label)?;
+	let types = metadata["types"].as_array().map(|t| t.as_slice()).unwrap_or_default();
+	let params: Vec<_> = array(&function["args"])
+		.map(|a| (a["label"].as_str().unwrap_or_default(), a["type"]["type"].as_u64()))
+		.collect();
+	if params.len() != args.len() {
+		let expected: Vec<_> = params
+			.iter()
+			.map(|(label, id)| match id {
+				Some(id) => format!("{label}: {}", type_name(types, *id)),
+				None => label.to_string(),
+			})
+			.collect();
+		return Err(Error::IncorrectArguments(format!(
+			"`{label}` expects {} argument(s) ({}), but {} provided",
+			params.len(),
+			expected.join(", "),
+			args.len()
+		)));
+	}
+
+	let errors: Vec<_> = params
+		.iter()
+		.zip(args)
+		.filter_map(|((label, id), arg)| {
+			let id = (*id)?;
+			check(types, id, arg.trim()).err().map(|reason| {
+				format!("`{label}` ({}): {reason}, found `{arg}`", type_name(types, id))
+			})
+		})
+		.collect();
+	if !errors.is_empty() {
+		return Err(Error::IncorrectArguments(errors.join("\n")));
+	}
+	Ok(())
+}
+
+/// Validates a single argument against the type declared in the contract metadata for the argument
+/// at `index` of the constructor or message with the provided `label`, e.g. while it is being
+/// entered.
+///
+/// # Arguments
+///
+/// * `metadata` - the metadata of the contract
+/// * `function_type` - whether a constructor or message is being invoked
+/// * `label` - the label of the constructor or message
+/// * `index` - the index of the argument
+/// * `value` - the argument, as provided by the user
+pub fn validate_arg(
+	metadata: &Value,
+	function_type: FunctionType,
+	label: &str,
+	index: usize,
+	value: &str,
+) -> Result<(), Error> {
+	let function = function(metadata, function_type, label)?;
+	let types = metadata["types"].as_array().map(|t| t.as_slice()).unwrap_or_default();
+	let Some(id) = function["args"][index]["type"]["type"].as_u64() else {
+		return Ok(());
+	};
+	check(types, id, value.trim()).map_err(Error::IncorrectArguments)
+}
+
+/// Converts any addresses provided for the arguments of the constructor or message with the
+/// provided `label` into the representation expected by the contract: 20 byte addresses provided
+/// for `AccountId` arguments are mapped to SS58 encoded accounts, while accounts provided for
+/// `H160` arguments are mapped to 20 byte addresses. Other arguments are returned unchanged.
+///
+/// # Arguments
+///
+/// * `metadata` - the metadata of the contract
+/// * `function_type` - whether a constructor or message is being invoked
+/// * `label` - the label of the constructor or message
+/// * `args` - the arguments, as provided by the user
+/// * `mapping` - how accounts are mapped to and from 20 byte addresses
+pub fn convert_args(
+	metadata: &Value,
+	function_type: FunctionType,
+	label: &str,
+	args: &[String],
+	mapping: AddressMapping,
+) -> Result<Vec<String>, Error> {
+	let function = function(metadata, function_type, label)?;
+	let types = metadata["types"].as_array().map(|t| t.as_slice()).unwrap_or_default();
+	let params: Vec<_> = array(&function["args"]).map(|a| a["type"]["type"].as_u64()).collect();
+	Ok(args
+		.iter()
+		.enumerate()
+		.map(|(index, arg)| {
+			let name = params
+				.get(index)
+				.copied()
+				.flatten()
+				.and_then(|id| find(types, id))
+				.and_then(|ty| array(&ty["path"]).filter_map(|s| s.as_str()).last());
+			let format = match name {
+				Some("AccountId" | "AccountId32") => AddressFormat::Ss58,
+				Some("H160") => AddressFormat::H160,
+				_ => return arg.clone(),
+			};
+			match Address::parse(arg) {
+				// Only addresses in another representation are converted.
+				Ok(address @ Address::H160(_)) if format == AddressFormat::Ss58 => {
+					address.convert(format, DEFAULT_SS58_PREFIX, mapping)
+				},
+				Ok(address @ Address::Account(_)) if format == AddressFormat::H160 => {
+					address.convert(format, DEFAULT_SS58_PREFIX, mapping)
+				},
+				_ => arg.clone(),
+			}
+		})
+		.collect())
+}
+
+/// Checks that the constructor or message with the provided `label` is payable when a non-zero
+/// `value` is to be transferred, as the contract would otherwise reject the transfer.
+///
+/// # Arguments
+///
+/// * `metadata` - the metadata of the contract
+/// * `function_type` - whether a constructor or message is being invoked
+/// * `label` - the label of the constructor or message
+/// * `value` - the value to be transferred
+pub(crate) fn ensure_payable(
+	metadata: &Value,
+	function_type: FunctionType,
+	label: &str,
+	value: u128,
+) -> Result<(), Error> {
+	if value == 0 || function(metadata, function_type, label)?["payable"].as_bool() == Some(true) {
+		return Ok(());
+	}
+	Err(Error::IncorrectArguments(format!(
+		"`{label}` is not payable, so no value can be transferred"
+	)))
+}
+
+/// The constructor or message with the provided `label`.
+fn function<'a>(
+	metadata: &'a Value,
+	function_type: FunctionType,
+	label: &str,
+) -> Result<&'a Value, Error> {
+	let (kind, functions) = match function_type {
+		FunctionType::Constructor => ("constructor", &metadata["spec"]["constructors"]),
+		FunctionType::Message => ("message", &metadata["spec"]["messages"]),
+	};
+	array(functions).find(|f| f["label"].as_str() == Some(label)).ok_or_else(|| {
+		let available: Vec<_> = array(functions).filter_map(|f| f["label"].as_str()).collect();
+		Error::IncorrectArguments(format!(
+			"no {kind} named `{label}`, expected one of: {}",
+			available.join(", ")
+		))
+	})
+}
+
+/// Checks that `value` can be interpreted as the type with the provided `id`, returning the reason
+/// if not. Types which cannot be checked are accepted, leaving their validation to the encoder.
+fn check(types: &[Value], id: u64, value: &str) -> Result<(), String> {
+	let Some(ty) = find(types, id) else { return Ok(()) };
+	let def = &ty["def"];
+	let path: Vec<_> = array(&ty["path"]).filter_map(|p| p.as_str()).collect();
+
+	if let Some(primitive) = def["primitive"].as_str() {
+		return check_primitive(primitive, value);
+	}
+	if let Some(inner) = def["compact"]["type"].as_u64() {
+		return check(types, inner, value);
+	}
+	if let Some(inner) = def["sequence"]["type"].as_u64() {
+		// Byte sequences may also be provided as hex.
+		if value.starts_with("0x") && is_byte(types, inner) {
+			return check_hex(value, None);
+		}
+		return check_items(types, inner, value, None);
+	}
+	if def.get("array").is_some() {
+		let inner = def["array"]["type"].as_u64().unwrap_or_default();
+		let len = def["array"]["len"].as_u64().unwrap_or_default() as usize;
+		if value.starts_with("0x") && is_byte(types, inner) {
+			return check_hex(value, Some(len));
+		}
+		return check_items(types, inner, value, Some(len));
+	}
+	if path.last() == Some(&"AccountId") {
+		if value.starts_with("0x") {
+			return check_hex(value, Some(32));
+		}
+		return parse_account(value)
+			.map(|_| ())
+			.map_err(|_| "expected an SS58 or 32 byte hex encoded address".into());
+	}
+	if path == ["Option"] {
+		if value == "None" {
+			return Ok(());
+		}
+		let inner = ty["params"][0]["type"].as_u64();
+		return match (value.strip_prefix("Some(").and_then(|v| v.strip_suffix(')')), inner) {
+			(Some(inner_value), Some(inner)) => check(types, inner, inner_value.trim()),
+			(Some(_), None) => Ok(()),
+			(None, _) => Err("expected `None` or `Some(..)`".into()),
+		};
+	}
+	if let Some(variants) = def["variant"]["variants"].as_array() {
+		let names: Vec<_> = variants.iter().filter_map(|v| v["name"].as_str()).collect();
+		let name = value.split(|c: char| c == '(' || c == '{' || c.is_whitespace()).next();
+		return match name {
+			Some(name) if names.contains(&name) => Ok(()),
+			_ => Err(format!("expected one of: {}", names.join(", "))),
+		};
+	}
+	Ok(())
+}
+
+fn check_primitive(primitive: &str, value: &str) -> Result<(), String> {
+	let number = value.replace('_', "");
+	let valid = match primitive {
+		"bool" => value == "true" || value == "false",
+		"char" => value.trim_matches('\'').chars().count() == 1,
+		"str" => true,
+		"u8" => number.parse::<u8>().is_ok(),
+		"u16" => number.parse::<u16>().is_ok(),
+		"u32" => number.parse::<u32>().is_ok(),
+		"u64" => number.parse::<u64>().is_ok(),
+		"u128" => number.parse::<u128>().is_ok(),
+		"i8" => number.parse::<i8>().is_ok(),
+		"i16" => number.parse::<i16>().is_ok(),
+		"i32" => number.parse::<i32>().is_ok(),
+		"i64" => number.parse::<i64>().is_ok(),
+		"i128" => number.parse::<i128>().is_ok(),
+		_ => true,
+	};
+	match (valid, primitive) {
+		(true, _) => Ok(()),
+		(false, "bool") => Err("expected `true` or `false`".into()),
+		(false, "char") => Err("expected a single character".into()),
+		(false, primitive) => Err(format!("expected a number within the range of {primitive}")),
+	}
+}
+
+fn check_hex(value: &str, len: Option<usize>) -> Result<(), String> {
+	let hex = &value[2..];
+	if hex.len() % 2 != 0 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+		return Err("expected hex encoded bytes".into());
+	}
+	match len {
+		Some(len) if hex.len() / 2 != len => Err(format!("expected {len} hex encoded bytes")),
+		_ => Ok(()),
+	}
+}
+
+fn check_items(types: &[Value], inner: u64, value: &str, len: Option<usize>) -> Result<(), String> {
+	let Some(items) = value.strip_prefix('[').and_then(|v| v.strip_suffix(']')) else {
+		return Err("expected a list of values, e.g. `[a, b]`".into());
+	};
+	let items = split(items);
+	if let Some(len) = len {
+		if items.len() != len {
+			return Err(format!("expected {len} values"));
+		}
+	}
+	for (index, item) in items.iter().enumerate() {
+		check(types, inner, item).map_err(|reason| format!("item {index}: {reason}"))?;
+	}
+	Ok(())
+}
+
+fn is_byte(types: &[Value], id: u64) -> bool {
+	find(types, id).and_then(|t| t["def"]["primitive"].as_str()) == Some("u8")
+}
+
+/// Splits a comma separated list of values, ignoring any commas within nested values or strings.
+fn split(value: &str) -> Vec<&str> {
+	let mut items = Vec::new();
+	let (mut depth, mut quoted, mut start) = (0, false, 0);
+	for (i, c) in value.char_indices() {
+		match c {
+			'"' => quoted = !quoted,
+			'(' | '[' | '{' if !quoted => depth += 1,
+			')' | ']' | '}' if !quoted => depth -= 1,
+			',' if !quoted && depth == 0 => {
+				items.push(value[start..i].trim());
+				start = i + 1;
+			},
+			_ => {},
+		}
+	}
+	let last = value[start..].trim();
+	if !last.is_empty() {
+		items.push(last);
+	}
+	items
+}
+
+pub(crate) fn find(types: &[Value], id: u64) -> Option<&Value> {
+	types.iter().find(|t| t["id"].as_u64() == Some(id)).map(|t| &t["type"])
+}
+
+pub(crate) fn array(value: &Value) -> impl Iterator<Item = &Value> {
+	value.as_array().into_iter().flatten()
+}
+
+/// Resolves the type with the provided `id` within the type registry to a readable type name.
+pub(crate) fn type_name(types: &[Value], id: u64) -> String {
+	let Some(ty) = find(types, id) else {
+		return format!("<unknown type {id}>");
+	};
+	let def = &ty["def"];
+	if let Some(primitive) = def["primitive"].as_str() {
+		return primitive.to_string();
+	}
+	if let Some(inner) = def["sequence"]["type"].as_u64() {
+		return format!("Vec<{}>", type_name(types, inner));
+	}
+	if let Some(inner) = def["array"]["type"].as_u64() {
+		return format!("[{}; {}]", type_name(types, inner), def["array"]["len"]);
+	}
+	if let Some(inner) = def["compact"]["type"].as_u64() {
+		return format!("Compact<{}>", type_name(types, inner));
+	}
+	if let Some(tuple) = def["tuple"].as_array() {
+		let fields: Vec<_> =
+			tuple.iter().filter_map(|t| t.as_u64()).map(|t| type_name(types, t)).collect();
+		return format!("({})", fields.join(", "));
+	}
+	// Composite or variant types are identified by their path, along with any type parameters.
+	let name = array(&ty["path"]).filter_map(|s| s.as_str()).last().unwrap_or("<unnamed>");
+	let params: Vec<_> = array(&ty["params"])
+		.filter_map(|p| p["type"].as_u64())
+		.map(|p| type_name(types, p))
+		.collect();
+	if params.is_empty() {
+		name.to_string()
+	} else {
+		format!("{name}<{}>", params.join(", "))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+	use serde_json::json;
+
+	fn metadata() -> Value {
+		json!({
+			"spec": {
+				"constructors": [
+					{ "label": "new", "payable": true, "args": [{ "label": "init_value", "type": { "type": 0 } }] }
+				],
+				"messages": [
+					{ "label": "transfer", "args": [
+						{ "label": "to", "type": { "type": 1 } },
+						{ "label": "value", "type": { "type": 2 } },
+						{ "label": "data", "type": { "type": 3 } },
+						{ "label": "memo", "type": { "type": 5 } },
+						{ "label": "mode", "type": { "type": 6 } }
+					] }
+				]
+			},
+			"types": [
+				{ "id": 0, "type": { "def": { "primitive": "bool" } } },
+				{ "id": 1, "type": { "path": ["ink_primitives", "types", "AccountId"], "def": { "composite": {} } } },
+				{ "id": 2, "type": { "def": { "primitive": "u128" } } },
+				{ "id": 3, "type": { "def": { "sequence": { "type": 4 } } } },
+				{ "id": 4, "type": { "def": { "primitive": "u8" } } },
+				{ "id": 5, "type": { "path": ["Option"], "params": [{ "name": "T", "type": 2 }], "def": { "variant": { "variants": [{ "name": "None" }, { "name": "Some" }] } } } },
+				{ "id": 6, "type": { "path": ["contract", "Mode"], "def": { "variant": { "variants": [{ "name": "Fast" }, { "name": "Slow" }] } } } }
+			]
+		})
+	}
+
+	fn args(args: &[&str]) -> Vec<String> {
+		args.iter().map(|a| a.to_string()).collect()
+	}
+
+	#[test]
+	fn test_validate_args_succeeds() -> Result<()> {
+		let metadata = metadata();
+		validate_args(&metadata, FunctionType::Constructor, "new", &args(&["true"]))?;
+		validate_args(
+			&metadata,
+			FunctionType::Message,
+			"transfer",
+			&args(&[
+				"5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY",
+				"1_000_000",
+				"[1, 2, 3]",
+				"Some(10)",
+				"Fast",
+			]),
+		)?;
+		validate_args(
+			&metadata,
+			FunctionType::Message,
+			"transfer",
+			&args(&[
+				"0xd43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d",
+				"0",
+				"0x0102",
+				"None",
+				"Slow",
+			]),
+		)?;
+		Ok(())
+	}
+
+	#[test]
+	fn test_validate_args_reports_each_invalid_argument() {
+		let error = validate_args(
+			&metadata(),
+			FunctionType::Message,
+			"transfer",
+			&args(&["alice", "-1", "[1, 256]", "10", "Medium"]),
+		)
+		.unwrap_err()
+		.to_string();
+		assert!(error.contains("`to` (AccountId): expected an SS58 or 32 byte hex encoded address"));
+		assert!(error.contains("`value` (u128): expected a number within the range of u128"));
+		assert!(
+			error.contains("`data` (Vec<u8>): item 1: expected a number within the range of u8")
+		);
+		assert!(error.contains("`memo` (Option<u128>): expected `None` or `Some(..)`"));
+		assert!(error.contains("`mode` (Mode): expected one of: Fast, Slow"));
+	}
+
+	#[test]
+	fn test_validate_args_fails_with_wrong_number_of_arguments() {
+		assert!(matches!(
+			validate_args(&metadata(), FunctionType::Constructor, "new", &[]),
+			Err(Error::IncorrectArguments(e)) if e.contains("expects 1 argument(s) (init_value: bool)")
+		));
+	}
+
+	#[test]
+	fn test_validate_args_fails_with_unknown_function() {
+		assert!(matches!(
+			validate_args(&metadata(), FunctionType::Message, "flip", &[]),
+			Err(Error::IncorrectArguments(e)) if e.contains("expected one of: transfer")
+		));
+	}
+
+	#[test]
+	fn test_validate_arg() {
+		let metadata = metadata();
+		assert!(validate_arg(&metadata, FunctionType::Message, "transfer", 1, "1_000").is_ok());
+		assert!(matches!(
+			validate_arg(&metadata, FunctionType::Message, "transfer", 1, "-1"),
+			Err(Error::IncorrectArguments(e)) if e == "expected a number within the range of u128"
+		));
+		assert!(validate_arg(&metadata, FunctionType::Message, "transfer", 4, "Slow").is_ok());
+		assert!(matches!(
+			validate_arg(&metadata, FunctionType::Message, "flip", 0, "true"),
+			Err(Error::IncorrectArguments(..))
+		));
+	}
+
+	#[test]
+	fn test_convert_args_maps_addresses() -> Result<()> {
+		let h160 = format!("0x{}", "ab".repeat(20));
+		let provided = args(&[&h160, "1", "[]", "None", "Fast"]);
+		let converted = convert_args(
+			&metadata(),
+			FunctionType::Message,
+			"transfer",
+			&provided,
+			AddressMapping::Revive,
+		)?;
+		let expected =
+			Address::parse(&h160)?.convert(AddressFormat::Ss58, 42, AddressMapping::Revive);
+		assert_eq!(converted[0], expected);
+		assert_eq!(converted[1..], provided[1..]);
+		validate_args(&metadata(), FunctionType::Message, "transfer", &converted)?;
+
+		// Accounts are left unchanged when an account is expected.
+		let provided =
+			args(&["5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY", "1", "[]", "None", "Fast"]);
+		assert_eq!(
+			convert_args(
+				&metadata(),
+				FunctionType::Message,
+				"transfer",
+				&provided,
+				AddressMapping::Revive
+			)?,
+			provided
+		);
+		Ok(())
+	}
+
+	#[test]
+	fn test_ensure_payable() -> Result<()> {
+		ensure_payable(&metadata(), FunctionType::Constructor, "new", 1_000)?;
+		ensure_payable(&metadata(), FunctionType::Message, "transfer", 0)?;
+		assert!(matches!(
+			ensure_payable(&metadata(), FunctionType::Message, "transfer", 1_000),
+			Err(Error::IncorrectArguments(e)) if e.contains("not payable")
+		));
+		Ok(())
+	}
+
+	#[test]
+	fn test_split() {
+		assert_eq!(
+			split("1, [2, 3], Some((4, 5)), \"a, b\""),
+			vec!["1", "[2, 3]", "Some((4, 5))", "\"a, b\""]
+		);
+		assert!(split("").is_empty());
+	}
+}
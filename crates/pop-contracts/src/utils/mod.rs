@@ -1,3 +1,6 @@
 // SPDX-License-Identifier: GPL-3.0
+pub mod address;
 pub mod helpers;
+pub mod metadata;
 pub mod signer;
+pub(crate) mod storage;
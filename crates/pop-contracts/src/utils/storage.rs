@@ -0,0 +1,71 @@
+// SPDX-License-Identifier: GPL-3.0
+use crate::{errors::Error, utils::helpers::parse_account};
+use sp_core::hashing::{twox_128, twox_64};
+use subxt::{ext::codec::Decode, OnlineClient, PolkadotConfig as DefaultConfig};
+
+/// Fetches the code hash of the contract deployed at `contract`.
+pub(crate) async fn contract_code_hash(
+	client: &OnlineClient<DefaultConfig>,
+	contract: &str,
+) -> Result<[u8; 32], Error> {
+	contract_info(client, contract)
+		.await?
+		.map(|(_, code_hash)| code_hash)
+		.ok_or(Error::Verification(format!("no contract found at {contract}")))
+}
+
+/// Fetches the trie id and code hash of the contract deployed at `contract`, if any.
+pub(crate) async fn contract_info(
+	client: &OnlineClient<DefaultConfig>,
+	contract: &str,
+) -> Result<Option<(Vec<u8>, [u8; 32])>, Error> {
+	let account = parse_account(contract)?;
+	let key = storage_key("ContractInfoOf", &[&twox_64(&account.0), &account.0]);
+	let Some(info) = client.storage().at_latest().await?.fetch_raw(key).await? else {
+		return Ok(None);
+	};
+	// The contract info starts with the trie id, followed by the code hash.
+	let mut input = &info[..];
+	let trie_id =
+		Vec::<u8>::decode(&mut input).map_err(|e| Error::Decode(format!("contract info: {e}")))?;
+	let code_hash =
+		<[u8; 32]>::decode(&mut input).map_err(|e| Error::Decode(format!("contract info: {e}")))?;
+	Ok(Some((trie_id, code_hash)))
+}
+
+/// Fetches the code uploaded with the provided `code_hash`, if any.
+pub(crate) async fn pristine_code(
+	client: &OnlineClient<DefaultConfig>,
+	code_hash: &[u8],
+) -> Result<Option<Vec<u8>>, Error> {
+	let key = storage_key("PristineCode", &[code_hash]);
+	let Some(code) = client.storage().at_latest().await?.fetch_raw(key).await? else {
+		return Ok(None);
+	};
+	Ok(Vec::<u8>::decode(&mut &code[..]).ok())
+}
+
+/// The raw key of a `Contracts` pallet storage item, where `keys` are already hashed as per the
+/// hashers of the storage item.
+pub(crate) fn storage_key(item: &str, keys: &[&[u8]]) -> Vec<u8> {
+	let mut key = twox_128(b"Contracts").to_vec();
+	key.extend(twox_128(item.as_bytes()));
+	for k in keys {
+		key.extend_from_slice(k);
+	}
+	key
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_storage_key() {
+		let key = storage_key("PristineCode", &[&[1u8; 32]]);
+		assert_eq!(key.len(), 16 + 16 + 32);
+		assert_eq!(&key[..16], &twox_128(b"Contracts"));
+		assert_eq!(&key[16..32], &twox_128(b"PristineCode"));
+		assert_eq!(&key[32..], &[1u8; 32]);
+	}
+}
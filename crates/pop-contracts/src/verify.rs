@@ -0,0 +1,69 @@
+// SPDX-License-Identifier: GPL-3.0
+use crate::{
+	build::build,
+	errors::Error,
+	utils::{
+		helpers::to_hex,
+		storage::{contract_code_hash, pristine_code},
+	},
+};
+use contract_build::BuildMode;
+use sp_core::hashing::blake2_256;
+use std::{fs, path::PathBuf};
+use subxt::{OnlineClient, PolkadotConfig as DefaultConfig};
+use url::Url;
+
+/// The outcome of verifying a deployed contract against its local build.
+#[derive(Debug, PartialEq)]
+pub enum Verification {
+	/// The code hash of the local build matches the code hash of the deployed contract.
+	Verified {
+		/// The code hash shared by the local build and the deployed contract.
+		code_hash: String,
+	},
+	/// The code hash of the local build differs from the code hash of the deployed contract.
+	Mismatch {
+		/// The code hash of the local build.
+		local: String,
+		/// The size of the local (optimized) wasm, in bytes.
+		local_size: usize,
+		/// The code hash of the deployed contract.
+		on_chain: String,
+		/// The size of the deployed wasm, in bytes, if its code could be retrieved.
+		on_chain_size: Option<usize>,
+	},
+}
+
+/// Rebuilds the contract at `path` in release mode and compares its code hash with the code hash of
+/// the contract deployed at `contract`.
+///
+/// # Arguments
+///
+/// * `path` - path to the contract project
+/// * `url` - websocket endpoint of a node
+/// * `contract` - the address of the deployed contract
+pub async fn verify_contract(
+	path: &Option<PathBuf>,
+	url: &Url,
+	contract: &str,
+) -> Result<Verification, Error> {
+	let result = build(path, BuildMode::Release)?;
+	let wasm = result
+		.dest_wasm
+		.ok_or(Error::Verification("the build did not produce a wasm artifact".into()))?;
+	let code = fs::read(wasm)?;
+	let local = blake2_256(&code);
+
+	let client = OnlineClient::<DefaultConfig>::from_url(url).await?;
+	let on_chain = contract_code_hash(&client, contract).await?;
+	if local == on_chain {
+		return Ok(Verification::Verified { code_hash: to_hex(&local) });
+	}
+	let on_chain_size = pristine_code(&client, &on_chain).await?.map(|code| code.len());
+	Ok(Verification::Mismatch {
+		local: to_hex(&local),
+		local_size: code.len(),
+		on_chain: to_hex(&on_chain),
+		on_chain_size,
+	})
+}
@@ -2,7 +2,7 @@
 use anyhow::{Error, Result};
 use pop_contracts::{
 	build_smart_contract, create_smart_contract, dry_run_gas_estimate_instantiate,
-	set_up_deployment, UpOpts,
+	set_up_deployment, UpOpts, DEFAULT_GAS_MARGIN,
 };
 use std::fs;
 use tempfile::TempDir;
@@ -12,7 +12,7 @@ fn setup_test_environment() -> std::result::Result<tempfile::TempDir, Error> {
 	let temp_dir = tempfile::tempdir().expect("Could not create temp dir");
 	let temp_contract_dir = temp_dir.path().join("test_contract");
 	fs::create_dir(&temp_contract_dir)?;
-	crate::create_smart_contract("test_contract", temp_contract_dir.as_path())?;
+	crate::create_smart_contract("test_contract", temp_contract_dir.as_path(), None, true)?;
 	Ok(temp_dir)
 }
 
@@ -63,6 +63,7 @@ async fn test_set_up_deployment() -> std::result::Result<(), Error> {
 		url: Url::parse(CONTRACTS_NETWORK_URL)?,
 		suri: "//Alice".to_string(),
 		salt: None,
+		skip_upload: false,
 	};
 	let result = set_up_deployment(call_opts).await?;
 	assert_eq!(result.url(), "wss://rococo-contracts-rpc.polkadot.io:443/");
@@ -84,12 +85,15 @@ async fn test_dry_run_gas_estimate_instantiate() -> std::result::Result<(), Erro
 		url: Url::parse(CONTRACTS_NETWORK_URL)?,
 		suri: "//Alice".to_string(),
 		salt: None,
+		skip_upload: false,
 	};
 	let instantiate_exec = set_up_deployment(call_opts).await;
 
-	let weight = dry_run_gas_estimate_instantiate(&instantiate_exec.unwrap()).await?;
-	assert!(weight.clone().ref_time() > 0);
-	assert!(weight.proof_size() > 0);
+	let estimate =
+		dry_run_gas_estimate_instantiate(&instantiate_exec.unwrap(), DEFAULT_GAS_MARGIN).await?;
+	assert!(estimate.weight.ref_time() > 0);
+	assert!(estimate.weight.proof_size() > 0);
+	assert!(estimate.storage_deposit > 0);
 
 	Ok(())
 }
@@ -0,0 +1,175 @@
+// SPDX-License-Identifier: GPL-3.0
+use crate::{build::runtime_wasm, errors::Error};
+use duct::cmd;
+use std::{
+	fs,
+	path::{Path, PathBuf},
+};
+
+/// The release of the Polkadot SDK providing `frame-omni-bencher` when none is specified.
+pub const OMNI_BENCHER_DEFAULT_VERSION: &str = "stable2409";
+
+/// A benchmark of the extrinsics of a pallet, run against a runtime compiled with the
+/// `runtime-benchmarks` feature.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PalletBenchmark {
+	/// The runtime wasm.
+	pub runtime: PathBuf,
+	/// The name of the pallet, as per its crate (e.g. `pallet_balances`), or `*` for all pallets.
+	pub pallet: String,
+	/// The extrinsics to benchmark, or `*` for all extrinsics.
+	pub extrinsic: String,
+	/// The number of samples taken across the range of each component.
+	pub steps: u32,
+	/// The number of times each sample is repeated.
+	pub repeat: u32,
+	/// The path of the generated weight file, or a directory within which a weight file is
+	/// generated for each pallet.
+	pub output: PathBuf,
+}
+
+impl PalletBenchmark {
+	/// The arguments provided to `frame-omni-bencher`.
+	pub fn args(&self) -> Vec<String> {
+		vec![
+			"v1".into(),
+			"benchmark".into(),
+			"pallet".into(),
+			format!("--runtime={}", self.runtime.display()),
+			format!("--pallet={}", self.pallet),
+			format!("--extrinsic={}", self.extrinsic),
+			format!("--steps={}", self.steps),
+			format!("--repeat={}", self.repeat),
+			format!("--output={}", self.output.display()),
+		]
+	}
+
+	/// Runs the benchmark using the `frame-omni-bencher` binary at `bencher`, writing the
+	/// resulting weights to the output.
+	///
+	/// # Arguments
+	///
+	/// * `bencher` - path to the `frame-omni-bencher` binary
+	pub fn run(&self, bencher: &Path) -> Result<(), Error> {
+		if !self.runtime.exists() {
+			return Err(Error::Config(format!(
+				"the runtime {} does not exist",
+				self.runtime.display()
+			)));
+		}
+		// The output is either a weight file or a directory of weight files.
+		let dir = match self.output.extension() {
+			Some(_) => self.output.parent(),
+			None => Some(self.output.as_path()),
+		};
+		if let Some(dir) = dir {
+			fs::create_dir_all(dir)?;
+		}
+		tracing::debug!(bencher = %bencher.display(), args = ?self.args(), "running benchmarks");
+		cmd(bencher, self.args()).run()?;
+		Ok(())
+	}
+}
+
+/// The runtime wasm of the latest release build of the project at `path`, which must be built
+/// with the `runtime-benchmarks` feature to be benchmarked.
+///
+/// # Arguments
+///
+/// * `path` - path to the project
+pub fn benchmark_runtime(path: &Path) -> Result<PathBuf, Error> {
+	runtime_wasm(&path.join("target").join("release")).ok_or(Error::Config(
+		"no runtime wasm was found, build the runtime first using `cargo build --release \
+		 --features runtime-benchmarks`"
+			.into(),
+	))
+}
+
+/// The conventional location of the weights of `pallet` within the project at `path`: the weights
+/// module of the runtime, when present, otherwise the pallet within the `pallets` directory.
+///
+/// # Arguments
+///
+/// * `path` - path to the project
+/// * `pallet` - the name of the pallet, as per its crate (e.g. `pallet_template`)
+pub fn weights_path(path: &Path, pallet: &str) -> PathBuf {
+	let runtime_weights = path.join("runtime").join("src").join("weights");
+	if runtime_weights.is_dir() {
+		return runtime_weights.join(format!("{pallet}.rs"));
+	}
+	let name = pallet.strip_prefix("pallet_").unwrap_or(pallet).replace('_', "-");
+	let pallet_dir = path.join("pallets").join(&name);
+	if pallet_dir.is_dir() {
+		return pallet_dir.join("src").join("weights.rs");
+	}
+	runtime_weights.join(format!("{pallet}.rs"))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+
+	#[test]
+	fn test_benchmark_args() {
+		let benchmark = PalletBenchmark {
+			runtime: PathBuf::from("runtime.compact.compressed.wasm"),
+			pallet: "pallet_template".into(),
+			extrinsic: "*".into(),
+			steps: 50,
+			repeat: 20,
+			output: PathBuf::from("weights.rs"),
+		};
+		assert_eq!(
+			benchmark.args(),
+			[
+				"v1",
+				"benchmark",
+				"pallet",
+				"--runtime=runtime.compact.compressed.wasm",
+				"--pallet=pallet_template",
+				"--extrinsic=*",
+				"--steps=50",
+				"--repeat=20",
+				"--output=weights.rs"
+			]
+		);
+	}
+
+	#[test]
+	fn test_weights_path() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let path = temp_dir.path();
+		assert_eq!(
+			weights_path(path, "pallet_template"),
+			path.join("runtime/src/weights/pallet_template.rs")
+		);
+		fs::create_dir_all(path.join("pallets/template"))?;
+		assert_eq!(
+			weights_path(path, "pallet_template"),
+			path.join("pallets/template/src/weights.rs")
+		);
+		fs::create_dir_all(path.join("runtime/src/weights"))?;
+		assert_eq!(
+			weights_path(path, "pallet_template"),
+			path.join("runtime/src/weights/pallet_template.rs")
+		);
+		Ok(())
+	}
+
+	#[test]
+	fn test_run_fails_without_runtime() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let benchmark = PalletBenchmark {
+			runtime: temp_dir.path().join("missing.wasm"),
+			pallet: "*".into(),
+			extrinsic: "*".into(),
+			steps: 50,
+			repeat: 20,
+			output: temp_dir.path().join("weights"),
+		};
+		assert!(matches!(benchmark.run(Path::new("frame-omni-bencher")), Err(Error::Config(..))));
+		assert!(matches!(benchmark_runtime(temp_dir.path()), Err(Error::Config(..))));
+		Ok(())
+	}
+}
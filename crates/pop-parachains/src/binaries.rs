@@ -0,0 +1,417 @@
+// SPDX-License-Identifier: GPL-3.0
+use crate::{
+	build::{manifests, parse},
+	errors::Error,
+};
+use pop_common::{
+	platform::{Arch, Os},
+	Binary, GitHub, Platform, Source,
+};
+use std::path::{Path, PathBuf};
+use toml_edit::{DocumentMut, Item};
+use url::Url;
+
+const POLKADOT_SDK: &str = "https://github.com/paritytech/polkadot-sdk";
+const POLKADOT_DEFAULT_VERSION: &str = "v1.11.0";
+/// The relay chain binary.
+const RELAY_CHAIN: &str = "polkadot";
+/// The workers required by the relay chain binary, which are expected alongside it.
+const WORKERS: [&str; 2] = ["polkadot-execute-worker", "polkadot-prepare-worker"];
+/// The system parachain binary.
+const SYSTEM_PARACHAIN: &str = "polkadot-parachain";
+/// The binary benchmarking runtimes.
+const OMNI_BENCHER: &str = "frame-omni-bencher";
+
+/// A release of the Polkadot SDK, providing the binaries of the relay chain and its system
+/// parachains. The binaries of each release are cached side by side, so that a network can be
+/// pinned to a specific release.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PolkadotRelease {
+	version: String,
+}
+
+impl PolkadotRelease {
+	/// A specific release, as per its tag (e.g. `v1.11.0`, `polkadot-v1.11.0` or
+	/// `polkadot-stable2407`).
+	///
+	/// # Arguments
+	///
+	/// * `version` - the version of the release
+	pub fn new(version: &str) -> Result<Self, Error> {
+		let version = version.trim();
+		let version = version.strip_prefix("polkadot-").unwrap_or(version);
+		let numeric = |v: &str| !v.is_empty() && v.chars().all(|c| c.is_ascii_digit());
+		let valid = match (version.strip_prefix('v'), version.strip_prefix("stable")) {
+			(Some(semver), _) => {
+				let parts: Vec<_> = semver.split('.').collect();
+				parts.len() == 3 && parts.iter().all(|p| numeric(p))
+			},
+			(_, Some(stable)) => {
+				let (release, patch) = stable.split_once('-').unwrap_or((stable, "0"));
+				release.len() == 4 && numeric(release) && numeric(patch)
+			},
+			_ => false,
+		};
+		if !valid {
+			return Err(Error::Config(format!(
+				"invalid polkadot release `{version}`, expected a release tag such as \
+				 `{POLKADOT_DEFAULT_VERSION}` or `stable2407`"
+			)));
+		}
+		Ok(Self { version: version.into() })
+	}
+
+	/// The latest numbered release (e.g. `v1.11.0`), falling back to a default release when the
+	/// releases cannot be fetched.
+	pub async fn latest() -> Self {
+		let repo = Url::parse(POLKADOT_SDK).expect("repository url valid");
+		let latest = GitHub::get_latest_releases(&repo).await.ok().and_then(|releases| {
+			releases.into_iter().filter(|r| !r.prerelease).find_map(|r| {
+				r.tag_name
+					.strip_prefix("polkadot-")
+					.filter(|v| v.starts_with('v'))
+					.and_then(|v| Self::new(v).ok())
+			})
+		});
+		latest.unwrap_or(Self { version: POLKADOT_DEFAULT_VERSION.into() })
+	}
+
+	/// The release of the Polkadot SDK the project at `path` depends on, as per the tag or branch
+	/// of its git dependencies on the Polkadot SDK, or the version of its `polkadot-sdk`
+	/// dependency. Returns `None` when the release cannot be determined.
+	///
+	/// # Arguments
+	///
+	/// * `path` - path to the project
+	pub fn from_project(path: &Path) -> Result<Option<Self>, Error> {
+		for manifest in manifests(path)? {
+			if let Some(release) = Self::from_manifest(&parse(&manifest)?) {
+				return Ok(Some(release));
+			}
+		}
+		Ok(None)
+	}
+
+	/// The release of the Polkadot SDK a manifest depends on, including within its workspace
+	/// dependencies.
+	fn from_manifest(manifest: &DocumentMut) -> Option<Self> {
+		let sections = ["dependencies", "build-dependencies", "dev-dependencies"];
+		sections
+			.iter()
+			.map(|s| manifest.get(s))
+			.chain([manifest.get("workspace").and_then(|w| w.get("dependencies"))])
+			.flatten()
+			.filter_map(|d| d.as_table_like())
+			.flat_map(|d| d.iter().map(|(name, item)| (name.to_string(), item.clone())))
+			.find_map(|(name, item)| Self::from_dependency(&name, &item))
+	}
+
+	/// The release of the Polkadot SDK a dependency refers to: either a git dependency on the
+	/// Polkadot SDK at a release tag or branch, or the `polkadot-sdk` crate, which is versioned
+	/// as per its stable release (e.g. `2503.0.0` for `stable2503`).
+	fn from_dependency(name: &str, item: &Item) -> Option<Self> {
+		let field = |key: &str| item.get(key).and_then(|v| v.as_str());
+		if field("git").is_some_and(|git| git.trim_end_matches(".git").ends_with("polkadot-sdk")) {
+			let reference = field("tag").or(field("branch"))?;
+			return Self::new(reference.strip_prefix("release-").unwrap_or(reference)).ok();
+		}
+		if name != "polkadot-sdk" {
+			return None;
+		}
+		let version = item.as_str().or(field("version"))?;
+		let major = version.trim_start_matches(|c: char| !c.is_ascii_digit()).split('.').next()?;
+		Self::new(&format!("stable{major}")).ok()
+	}
+
+	/// Whether the release is of the same release line as `other`, differing at most by patch
+	/// (e.g. `v1.11.0` and `v1.11.1`, or `stable2407` and `stable2407-1`).
+	///
+	/// # Arguments
+	///
+	/// * `other` - the release compared
+	pub fn is_compatible(&self, other: &Self) -> bool {
+		let line = |version: &str| match version.strip_prefix("stable") {
+			Some(stable) => stable.split('-').next().unwrap_or(stable).to_string(),
+			None => version.rsplit_once('.').map_or(version, |(line, _)| line).to_string(),
+		};
+		line(&self.version) == line(&other.version)
+	}
+
+	/// The version of the release.
+	pub fn version(&self) -> &str {
+		&self.version
+	}
+
+	/// The relay chain binary of the release, as cached within `cache`. The binary is sourced
+	/// along with its workers when any of them is missing.
+	///
+	/// # Arguments
+	///
+	/// * `cache` - path to the local cache
+	pub fn relay_chain(&self, cache: &Path) -> Binary {
+		let name = format!("{RELAY_CHAIN}-{}", self.version);
+		let path = Source::cached_path(cache, RELAY_CHAIN, Some(&self.version));
+		let mut sources = Vec::new();
+		if !path.exists() || self.workers(cache).iter().any(|w| !w.exists()) {
+			let binaries = [RELAY_CHAIN].into_iter().chain(WORKERS);
+			if !is_released(Platform::detect().ok()) {
+				sources.push(self.git(RELAY_CHAIN, binaries.map(|b| b.to_string()).collect()));
+			} else {
+				sources.extend(binaries.map(|b| self.url(b)));
+			}
+		}
+		Binary { name, version: self.version.clone(), path, sources }
+	}
+
+	/// The paths of the workers of the relay chain within `cache`, which the network launcher
+	/// expects alongside the relay chain binary.
+	///
+	/// # Arguments
+	///
+	/// * `cache` - path to the local cache
+	pub fn workers(&self, cache: &Path) -> Vec<PathBuf> {
+		WORKERS
+			.iter()
+			.map(|w| Source::cached_path(cache, w, Some(&self.version)))
+			.collect()
+	}
+
+	/// The system parachain binary of the release, as cached within `cache`.
+	///
+	/// # Arguments
+	///
+	/// * `cache` - path to the local cache
+	pub fn system_parachain(&self, cache: &Path) -> Binary {
+		let name = format!("{SYSTEM_PARACHAIN}-{}", self.version);
+		let path = Source::cached_path(cache, SYSTEM_PARACHAIN, Some(&self.version));
+		let mut sources = Vec::new();
+		if !path.exists() {
+			if !is_released(Platform::detect().ok()) {
+				sources.push(Source::Git {
+					package: format!("{SYSTEM_PARACHAIN}-bin"),
+					..self.git(SYSTEM_PARACHAIN, vec![SYSTEM_PARACHAIN.into()])
+				});
+			} else {
+				sources.push(self.url(SYSTEM_PARACHAIN));
+			}
+		}
+		Binary { name, version: self.version.clone(), path, sources }
+	}
+
+	/// The runtime benchmarking binary of the release, as cached within `cache`. Only released from
+	/// `stable2407` onwards.
+	///
+	/// # Arguments
+	///
+	/// * `cache` - path to the local cache
+	pub fn omni_bencher(&self, cache: &Path) -> Binary {
+		let name = format!("{OMNI_BENCHER}-{}", self.version);
+		let path = Source::cached_path(cache, OMNI_BENCHER, Some(&self.version));
+		let mut sources = Vec::new();
+		if !path.exists() {
+			if !is_released(Platform::detect().ok()) {
+				sources.push(self.git(OMNI_BENCHER, vec![OMNI_BENCHER.into()]));
+			} else {
+				sources.push(self.url(OMNI_BENCHER));
+			}
+		}
+		Binary { name, version: self.version.clone(), path, sources }
+	}
+
+	/// The release asset of the binary named `binary`.
+	fn url(&self, binary: &str) -> Source {
+		let repo = Url::parse(POLKADOT_SDK).expect("repository url valid");
+		Source::Url {
+			name: binary.into(),
+			version: self.version.clone(),
+			url: GitHub::release(&repo, &format!("polkadot-{}", self.version), binary),
+		}
+	}
+
+	/// The source of the release, built locally from `package` when binaries are not released
+	/// for the current platform. The commit tagged by the release is built, which is that of its
+	/// released binaries.
+	fn git(&self, package: &str, binaries: Vec<String>) -> Source {
+		Source::Git {
+			url: Url::parse(POLKADOT_SDK).expect("repository url valid"),
+			branch: Some(format!("polkadot-{}", self.version)),
+			package: package.into(),
+			binaries,
+			version: Some(self.version.clone()),
+		}
+	}
+}
+
+/// Whether the binaries of the Polkadot SDK are released for `platform`, which they only are for
+/// x86_64 Linux linked against glibc. They are otherwise built from source, rather than
+/// downloading binaries which cannot run.
+///
+/// # Arguments
+///
+/// * `platform` - the platform detected, if supported
+pub(crate) fn is_released(platform: Option<Platform>) -> bool {
+	platform.is_some_and(|p| p.os == Os::LinuxGnu && p.arch == Arch::X86_64)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+	use std::fs::{create_dir_all, File};
+
+	/// Creates an empty file at `path`, along with its parent directories.
+	fn touch(path: &Path) -> Result<()> {
+		create_dir_all(path.parent().unwrap())?;
+		File::create(path)?;
+		Ok(())
+	}
+
+	#[test]
+	fn test_new_release() -> Result<()> {
+		assert_eq!(PolkadotRelease::new("v1.11.0")?.version(), "v1.11.0");
+		assert_eq!(PolkadotRelease::new("polkadot-v1.7.0")?.version(), "v1.7.0");
+		assert_eq!(PolkadotRelease::new("stable2407")?.version(), "stable2407");
+		assert_eq!(PolkadotRelease::new("polkadot-stable2407-1")?.version(), "stable2407-1");
+		for version in ["1.11.0", "v1.11", "vx.1.0", "stable24", "latest", ""] {
+			assert!(matches!(PolkadotRelease::new(version), Err(Error::Config(..))));
+		}
+		Ok(())
+	}
+
+	#[test]
+	fn test_from_project() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let path = temp_dir.path();
+		assert_eq!(PolkadotRelease::from_project(path)?, None);
+
+		std::fs::write(
+			path.join("Cargo.toml"),
+			"[workspace]\nmembers = [\"node\"]\n\n[workspace.dependencies]\nserde = \"1\"\n",
+		)?;
+		std::fs::create_dir(path.join("node"))?;
+		std::fs::write(
+			path.join("node").join("Cargo.toml"),
+			"[package]\nname = \"node\"\n\n[dependencies]\nsc-cli = { git = \
+			 \"https://github.com/paritytech/polkadot-sdk\", branch = \"release-polkadot-v1.11.0\" }\n",
+		)?;
+		assert_eq!(PolkadotRelease::from_project(path)?, Some(PolkadotRelease::new("v1.11.0")?));
+
+		// Workspace dependencies are considered before those of members.
+		std::fs::write(
+			path.join("Cargo.toml"),
+			"[workspace]\nmembers = [\"node\"]\n\n[workspace.dependencies]\npolkadot-sdk = \
+			 { version = \"2503.0.1\", default-features = false }\n",
+		)?;
+		assert_eq!(PolkadotRelease::from_project(path)?, Some(PolkadotRelease::new("stable2503")?));
+		Ok(())
+	}
+
+	#[test]
+	fn test_from_dependency() -> Result<()> {
+		let manifest: DocumentMut = r#"[dependencies]
+frame = { git = "https://github.com/paritytech/polkadot-sdk.git", tag = "polkadot-stable2407-1" }
+"#
+		.parse()?;
+		assert_eq!(
+			PolkadotRelease::from_manifest(&manifest),
+			Some(PolkadotRelease::new("stable2407-1")?)
+		);
+		// Unreleased branches and crates.io versions prior to stable releases are unknown.
+		for manifest in [
+			"[dependencies]\nframe = { git = \"https://github.com/paritytech/polkadot-sdk\", branch = \"master\" }\n",
+			"[dependencies]\npolkadot-sdk = \"0.7.0\"\n",
+			"[dependencies]\nsc-cli = \"0.36\"\n",
+		] {
+			assert_eq!(PolkadotRelease::from_manifest(&manifest.parse()?), None);
+		}
+		Ok(())
+	}
+
+	#[test]
+	fn test_is_compatible() -> Result<()> {
+		let release = |v| PolkadotRelease::new(v);
+		assert!(release("v1.11.0")?.is_compatible(&release("v1.11.1")?));
+		assert!(!release("v1.11.0")?.is_compatible(&release("v1.12.0")?));
+		assert!(release("stable2407")?.is_compatible(&release("stable2407-2")?));
+		assert!(!release("stable2407")?.is_compatible(&release("stable2409")?));
+		assert!(!release("v1.11.0")?.is_compatible(&release("stable2407")?));
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_latest_release() {
+		let release = PolkadotRelease::latest().await;
+		assert!(release.version().starts_with('v'));
+		assert!(PolkadotRelease::new(release.version()).is_ok());
+	}
+
+	#[test]
+	fn test_relay_chain() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let cache = temp_dir.path();
+		let release = PolkadotRelease::new("v1.7.0")?;
+		let relay_chain = release.relay_chain(cache);
+		assert_eq!(relay_chain.name, "polkadot-v1.7.0");
+		assert_eq!(relay_chain.path, cache.join("bins/polkadot/v1.7.0/polkadot"));
+		assert_eq!(relay_chain.version, "v1.7.0");
+		let released = is_released(Platform::detect().ok());
+		assert_eq!(relay_chain.sources.len(), if released { 3 } else { 1 });
+		assert_eq!(
+			release.workers(cache),
+			[
+				cache.join("bins/polkadot-execute-worker/v1.7.0/polkadot-execute-worker"),
+				cache.join("bins/polkadot-prepare-worker/v1.7.0/polkadot-prepare-worker")
+			]
+		);
+
+		// The binary is sourced again when a worker is missing.
+		touch(&relay_chain.path)?;
+		touch(&release.workers(cache)[0])?;
+		assert!(!release.relay_chain(cache).sources.is_empty());
+		touch(&release.workers(cache)[1])?;
+		assert!(release.relay_chain(cache).sources.is_empty());
+		Ok(())
+	}
+
+	#[test]
+	fn test_omni_bencher() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let bencher = PolkadotRelease::new("stable2409")?.omni_bencher(temp_dir.path());
+		assert_eq!(bencher.name, "frame-omni-bencher-stable2409");
+		assert_eq!(
+			bencher.path,
+			temp_dir.path().join("bins/frame-omni-bencher/stable2409/frame-omni-bencher")
+		);
+		assert_eq!(bencher.sources.len(), 1);
+		touch(&bencher.path)?;
+		assert!(PolkadotRelease::new("stable2409")?
+			.omni_bencher(temp_dir.path())
+			.sources
+			.is_empty());
+		Ok(())
+	}
+
+	#[test]
+	fn test_is_released() {
+		let platform = |os, arch| Some(Platform { os, arch });
+		assert!(is_released(platform(Os::LinuxGnu, Arch::X86_64)));
+		assert!(!is_released(platform(Os::LinuxGnu, Arch::Aarch64)));
+		assert!(!is_released(platform(Os::LinuxMusl, Arch::X86_64)));
+		assert!(!is_released(platform(Os::MacOs, Arch::Aarch64)));
+		assert!(!is_released(None));
+	}
+
+	#[test]
+	fn test_system_parachain() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let release = PolkadotRelease::new("stable2407")?;
+		let system_parachain = release.system_parachain(temp_dir.path());
+		assert_eq!(system_parachain.name, "polkadot-parachain-stable2407");
+		assert_eq!(system_parachain.sources.len(), 1);
+		if let Source::Git { branch, package, .. } = &system_parachain.sources[0] {
+			assert_eq!(branch.as_deref(), Some("polkadot-stable2407"));
+			assert_eq!(package, "polkadot-parachain-bin");
+		}
+		Ok(())
+	}
+}
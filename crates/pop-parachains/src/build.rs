@@ -1,11 +1,506 @@
 // SPDX-License-Identifier: GPL-3.0
+use crate::errors::Error;
 use duct::cmd;
-use std::path::PathBuf;
+use pop_common::BuildCache;
+use std::{
+	fs,
+	path::{Path, PathBuf},
+};
+use strum_macros::{Display, EnumString};
+use toml_edit::DocumentMut;
+
+/// The directory within the target directory of a project into which the artifacts of a build are
+/// exported.
+pub(crate) const ARTIFACTS_DIR: &str = "pop";
+
+/// The artifacts of a parachain build, as required to launch or register the parachain.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParachainArtifacts {
+	/// The node binary.
+	pub node: PathBuf,
+	/// The compressed runtime wasm.
+	pub runtime: PathBuf,
+	/// The genesis state (head) of the parachain.
+	pub genesis_state: PathBuf,
+	/// The genesis wasm (validation code) of the parachain.
+	pub genesis_wasm: PathBuf,
+}
+
+/// The profile with which a parachain is built.
+#[derive(Clone, Copy, Debug, Default, Display, EnumString, PartialEq)]
+#[strum(serialize_all = "lowercase")]
+pub enum Profile {
+	/// An unoptimized build, for quick local iteration.
+	Dev,
+	/// An optimized build.
+	#[default]
+	Release,
+	/// A fully optimized build, as deployed, using the `production` profile defined by the
+	/// project.
+	Production,
+}
+
+impl Profile {
+	/// The directory within the target directory into which the profile is built.
+	pub fn target_dir(&self) -> &'static str {
+		match self {
+			Profile::Dev => "debug",
+			Profile::Release => "release",
+			Profile::Production => "production",
+		}
+	}
+
+	/// The arguments selecting the profile when building with cargo.
+	fn args(&self) -> Vec<String> {
+		match self {
+			Profile::Dev => Vec::new(),
+			Profile::Release => vec!["--release".into()],
+			Profile::Production => vec!["--profile=production".into()],
+		}
+	}
+
+	/// The cargo features enabled when building the project at `path` with the profile, as
+	/// configured by its manifest, e.g.:
+	///
+	/// ```toml
+	/// [workspace.metadata.pop.profiles.dev]
+	/// features = ["fast-runtime"]
+	/// ```
+	///
+	/// # Arguments
+	///
+	/// * `path` - path to the project
+	pub fn features(&self, path: &Path) -> Result<Vec<String>, Error> {
+		let manifest = path.join("Cargo.toml");
+		if !manifest.exists() {
+			return Ok(Vec::new());
+		}
+		let manifest = parse(&manifest)?;
+		let profile = [manifest.get("workspace"), manifest.get("package")]
+			.into_iter()
+			.flatten()
+			.find_map(|m| m.get("metadata")?.get("pop")?.get("profiles")?.get(&self.to_string()));
+		Ok(profile
+			.and_then(|p| p.get("features"))
+			.and_then(|f| f.as_array())
+			.map(|f| f.iter().filter_map(|f| f.as_str().map(|f| f.to_string())).collect())
+			.unwrap_or_default())
+	}
+}
 
 pub fn build_parachain(path: &Option<PathBuf>) -> anyhow::Result<()> {
-	cmd("cargo", vec!["build", "--release"])
-		.dir(path.clone().unwrap_or("./".into()))
-		.run()?;
+	build_parachain_profile(&path.clone().unwrap_or("./".into()), Profile::Release, &[])?;
+	Ok(())
+}
 
+/// Builds the parachain at `path` with `profile`, enabling `features` along with those configured
+/// for the profile by the project.
+///
+/// # Arguments
+///
+/// * `path` - path to the project
+/// * `profile` - the profile to build with
+/// * `features` - additional cargo features to enable
+pub fn build_parachain_profile(
+	path: &Path,
+	profile: Profile,
+	features: &[String],
+) -> Result<(), Error> {
+	if profile == Profile::Production && !has_profile(path, "production")? {
+		return Err(Error::Config(
+			"the project defines no `production` profile, add `[profile.production]` to its \
+			 manifest"
+				.into(),
+		));
+	}
+	let build = cmd("cargo", build_args(profile, &profile.features(path)?, features));
+	BuildCache::detect(path).apply(build).dir(path).run()?;
 	Ok(())
 }
+
+/// The arguments building a project with `profile`, enabling the features configured for the
+/// profile along with any `additional` features.
+fn build_args(profile: Profile, configured: &[String], additional: &[String]) -> Vec<String> {
+	let mut args = vec!["build".to_string()];
+	args.extend(profile.args());
+	let mut features: Vec<&String> = Vec::new();
+	for feature in configured.iter().chain(additional) {
+		if !features.contains(&feature) {
+			features.push(feature);
+		}
+	}
+	if !features.is_empty() {
+		args.push(format!(
+			"--features={}",
+			features.iter().map(|f| f.as_str()).collect::<Vec<_>>().join(",")
+		));
+	}
+	args
+}
+
+/// Whether the manifest of the project at `path` defines the custom cargo profile `name`.
+fn has_profile(path: &Path, name: &str) -> Result<bool, Error> {
+	let manifest = path.join("Cargo.toml");
+	if !manifest.exists() {
+		return Ok(false);
+	}
+	Ok(parse(&manifest)?.get("profile").and_then(|p| p.get(name)).is_some())
+}
+
+/// Whether the project at `path` is a parachain, determined by it (or any of its workspace members)
+/// depending on Cumulus.
+///
+/// # Arguments
+///
+/// * `path` - path to the project
+pub fn is_parachain(path: &Path) -> Result<bool, Error> {
+	for manifest in manifests(path)? {
+		if dependencies(&parse(&manifest)?).iter().any(|d| d.starts_with("cumulus-")) {
+			return Ok(true);
+		}
+	}
+	Ok(false)
+}
+
+/// Exports the artifacts of the latest build of the parachain at `path` with `profile` into
+/// `target/pop`: its runtime wasm, along with the genesis state and genesis wasm exported by its
+/// node.
+///
+/// # Arguments
+///
+/// * `path` - path to the project
+/// * `profile` - the profile the parachain was built with
+pub fn export_artifacts(path: &Path, profile: Profile) -> Result<ParachainArtifacts, Error> {
+	let build = path.join("target").join(profile.target_dir());
+	let node = profile_node_binary(path, profile)?;
+	let source = runtime_wasm(&build).ok_or(Error::Config(format!(
+		"no runtime wasm was found, build the parachain with the `{profile}` profile first"
+	)))?;
+	let output = path.join("target").join(ARTIFACTS_DIR);
+	fs::create_dir_all(&output)?;
+	let runtime = output.join(source.file_name().expect("runtime wasm is a file"));
+	fs::copy(&source, &runtime)?;
+	let genesis_state = output.join("genesis-state");
+	let genesis_wasm = output.join("genesis-wasm");
+	for (command, file) in
+		[("export-genesis-state", &genesis_state), ("export-genesis-wasm", &genesis_wasm)]
+	{
+		tracing::debug!(node = %node.display(), command, "exporting genesis artifact");
+		cmd(&node, vec![command.into(), file.as_os_str().to_owned()])
+			.dir(path)
+			.stderr_null()
+			.run()?;
+	}
+	Ok(ParachainArtifacts { node, runtime, genesis_state, genesis_wasm })
+}
+
+/// The runtime crate of the project at `path`: the workspace member building a wasm runtime,
+/// returned as its directory and package name.
+///
+/// # Arguments
+///
+/// * `path` - path to the project
+pub fn runtime_crate(path: &Path) -> Result<(PathBuf, String), Error> {
+	for manifest_path in manifests(path)? {
+		let manifest = parse(&manifest_path)?;
+		let builds_wasm = manifest
+			.get("build-dependencies")
+			.and_then(|d| d.as_table_like())
+			.is_some_and(|d| d.contains_key("substrate-wasm-builder"));
+		let package = manifest.get("package").and_then(|p| p.get("name")).and_then(|n| n.as_str());
+		if let (true, Some(package)) = (builds_wasm, package) {
+			let dir = manifest_path.parent().expect("manifest is within a directory");
+			return Ok((dir.to_path_buf(), package.to_string()));
+		}
+	}
+	Err(Error::Config("the runtime of the parachain could not be found".into()))
+}
+
+/// Builds the runtime `package` of the project at `path` in release mode, returning its
+/// compressed wasm. The output of the build is only surfaced should it fail.
+///
+/// # Arguments
+///
+/// * `path` - path to the project
+/// * `package` - the package name of the runtime
+pub fn build_runtime(path: &Path, package: &str) -> Result<PathBuf, Error> {
+	let build = cmd("cargo", vec!["build", "--release", "-p", package]);
+	let output = BuildCache::detect(path)
+		.apply(build)
+		.dir(path)
+		.stdout_null()
+		.stderr_capture()
+		.unchecked()
+		.run()?;
+	if !output.status.success() {
+		return Err(Error::Build(String::from_utf8_lossy(&output.stderr).trim().to_string()));
+	}
+	let release = path.join("target").join("release");
+	let wasm = release
+		.join("wbuild")
+		.join(package)
+		.join(format!("{}.compact.compressed.wasm", package.replace('-', "_")));
+	match wasm.exists() {
+		true => Ok(wasm),
+		false => runtime_wasm(&release)
+			.ok_or(Error::Build(format!("no runtime wasm was built for {package}"))),
+	}
+}
+
+/// The node binary of the latest release build of the parachain at `path`.
+///
+/// # Arguments
+///
+/// * `path` - path to the project
+pub fn node_binary(path: &Path) -> Result<PathBuf, Error> {
+	profile_node_binary(path, Profile::Release)
+}
+
+/// The node binary of the latest build of the parachain at `path` with `profile`.
+fn profile_node_binary(path: &Path, profile: Profile) -> Result<PathBuf, Error> {
+	let node = path.join("target").join(profile.target_dir()).join(node_name(path)?);
+	if !node.exists() {
+		return Err(Error::MissingBinary(format!(
+			"{}, build the parachain with the `{profile}` profile first",
+			node.display()
+		)));
+	}
+	Ok(node)
+}
+
+/// The manifests of the project at `path`: its own, followed by those of its workspace members.
+pub(crate) fn manifests(path: &Path) -> Result<Vec<PathBuf>, Error> {
+	let root = path.join("Cargo.toml");
+	if !root.exists() {
+		return Ok(Vec::new());
+	}
+	let members: Vec<String> = parse(&root)?
+		.get("workspace")
+		.and_then(|w| w.get("members"))
+		.and_then(|m| m.as_array())
+		.map(|m| m.iter().filter_map(|m| m.as_str().map(|m| m.to_string())).collect())
+		.unwrap_or_default();
+	let mut manifests = vec![root];
+	for member in members {
+		let mut dirs = match member.strip_suffix("/*") {
+			Some(parent) => fs::read_dir(path.join(parent))
+				.map(|entries| entries.flatten().map(|e| e.path()).collect())
+				.unwrap_or_default(),
+			None => vec![path.join(member)],
+		};
+		dirs.sort();
+		manifests.extend(dirs.into_iter().map(|d| d.join("Cargo.toml")).filter(|m| m.exists()));
+	}
+	Ok(manifests)
+}
+
+pub(crate) fn parse(manifest: &Path) -> Result<DocumentMut, Error> {
+	fs::read_to_string(manifest)?
+		.parse::<DocumentMut>()
+		.map_err(|e| Error::Config(format!("invalid manifest {}: {e}", manifest.display())))
+}
+
+/// The names of the dependencies declared by a manifest, including workspace dependencies.
+fn dependencies(manifest: &DocumentMut) -> Vec<String> {
+	[manifest.get("dependencies"), manifest.get("workspace").and_then(|w| w.get("dependencies"))]
+		.into_iter()
+		.flatten()
+		.filter_map(|d| d.as_table_like())
+		.flat_map(|d| d.iter().map(|(name, _)| name.to_string()).collect::<Vec<_>>())
+		.collect()
+}
+
+/// The name of the node binary of the project at `path`: the binary of the workspace member
+/// providing the node command line.
+pub(crate) fn node_name(path: &Path) -> Result<String, Error> {
+	for manifest in manifests(path)? {
+		let manifest = parse(&manifest)?;
+		if !dependencies(&manifest)
+			.iter()
+			.any(|d| d == "cumulus-client-cli" || d == "sc-cli")
+		{
+			continue;
+		}
+		let bin = manifest
+			.get("bin")
+			.and_then(|b| b.as_array_of_tables())
+			.and_then(|b| b.iter().find_map(|b| b.get("name").and_then(|n| n.as_str())));
+		let package = manifest.get("package").and_then(|p| p.get("name")).and_then(|n| n.as_str());
+		if let Some(name) = bin.or(package) {
+			return Ok(name.to_string());
+		}
+	}
+	Err(Error::Config("the node of the parachain could not be found".into()))
+}
+
+/// The compressed runtime wasm built within `release`.
+pub(crate) fn runtime_wasm(release: &Path) -> Option<PathBuf> {
+	let mut runtimes: Vec<PathBuf> = fs::read_dir(release.join("wbuild"))
+		.ok()?
+		.flatten()
+		.filter_map(|runtime| fs::read_dir(runtime.path()).ok())
+		.flat_map(|files| files.flatten().map(|f| f.path()))
+		.filter(|f| f.to_string_lossy().ends_with(".compact.compressed.wasm"))
+		.collect();
+	runtimes.sort();
+	runtimes.into_iter().next()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+
+	fn parachain_project() -> Result<tempfile::TempDir> {
+		let temp_dir = tempfile::tempdir()?;
+		let path = temp_dir.path();
+		fs::write(
+			path.join("Cargo.toml"),
+			"[workspace]\nmembers = [\"node\", \"pallets/*\", \"runtime\"]\n",
+		)?;
+		for (member, manifest) in [
+			(
+				"node",
+				"[package]\nname = \"parachain-template-node\"\n\n[[bin]]\nname = \"parachain-node\"\n\n\
+				 [dependencies]\ncumulus-client-cli = \"0.7\"\n",
+			),
+			("pallets/template", "[package]\nname = \"pallet-template\"\n"),
+			(
+				"runtime",
+				"[package]\nname = \"parachain-template-runtime\"\n\n[build-dependencies]\n\
+				 substrate-wasm-builder = { version = \"17.0.0\", optional = true }\n",
+			),
+		] {
+			fs::create_dir_all(path.join(member))?;
+			fs::write(path.join(member).join("Cargo.toml"), manifest)?;
+		}
+		Ok(temp_dir)
+	}
+
+	#[test]
+	fn test_is_parachain() -> Result<()> {
+		let project = parachain_project()?;
+		assert!(is_parachain(project.path())?);
+		assert_eq!(manifests(project.path())?.len(), 4);
+
+		let temp_dir = tempfile::tempdir()?;
+		assert!(!is_parachain(temp_dir.path())?);
+		fs::write(temp_dir.path().join("Cargo.toml"), "[dependencies]\nink = \"5.0.0\"\n")?;
+		assert!(!is_parachain(temp_dir.path())?);
+		Ok(())
+	}
+
+	#[test]
+	fn test_node_name() -> Result<()> {
+		let project = parachain_project()?;
+		assert_eq!(node_name(project.path())?, "parachain-node");
+		fs::write(
+			project.path().join("node").join("Cargo.toml"),
+			"[package]\nname = \"parachain-template-node\"\n\n[dependencies]\nsc-cli = \"0.36\"\n",
+		)?;
+		assert_eq!(node_name(project.path())?, "parachain-template-node");
+		Ok(())
+	}
+
+	#[test]
+	fn test_runtime_crate() -> Result<()> {
+		let project = parachain_project()?;
+		let (dir, package) = runtime_crate(project.path())?;
+		assert_eq!(dir, project.path().join("runtime"));
+		assert_eq!(package, "parachain-template-runtime");
+
+		let temp_dir = tempfile::tempdir()?;
+		assert!(matches!(runtime_crate(temp_dir.path()), Err(Error::Config(..))));
+		Ok(())
+	}
+
+	#[test]
+	fn test_runtime_wasm() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let release = temp_dir.path();
+		assert_eq!(runtime_wasm(release), None);
+		let runtime = release.join("wbuild").join("parachain-template-runtime");
+		fs::create_dir_all(&runtime)?;
+		for file in [
+			"parachain_template_runtime.wasm",
+			"parachain_template_runtime.compact.compressed.wasm",
+		] {
+			fs::write(runtime.join(file), "")?;
+		}
+		assert_eq!(
+			runtime_wasm(release),
+			Some(runtime.join("parachain_template_runtime.compact.compressed.wasm"))
+		);
+		Ok(())
+	}
+
+	#[test]
+	fn test_export_artifacts_requires_a_release_build() -> Result<()> {
+		let project = parachain_project()?;
+		assert!(matches!(
+			export_artifacts(project.path(), Profile::Release),
+			Err(Error::MissingBinary(..))
+		));
+		Ok(())
+	}
+
+	#[test]
+	fn test_profile() -> Result<()> {
+		use std::str::FromStr;
+		assert_eq!(Profile::from_str("production")?, Profile::Production);
+		assert_eq!(Profile::default().to_string(), "release");
+		assert_eq!(Profile::Dev.target_dir(), "debug");
+		assert_eq!(Profile::Production.target_dir(), "production");
+		Ok(())
+	}
+
+	#[test]
+	fn test_profile_features() -> Result<()> {
+		let project = parachain_project()?;
+		let path = project.path();
+		assert!(Profile::Dev.features(path)?.is_empty());
+		let manifest = fs::read_to_string(path.join("Cargo.toml"))?;
+		fs::write(
+			path.join("Cargo.toml"),
+			format!(
+				"{manifest}\n[workspace.metadata.pop.profiles.dev]\nfeatures = [\"fast-runtime\"]\n\n\
+				 [workspace.metadata.pop.profiles.production]\nfeatures = [\"on-chain-release-build\"]\n"
+			),
+		)?;
+		assert_eq!(Profile::Dev.features(path)?, vec!["fast-runtime"]);
+		assert!(Profile::Release.features(path)?.is_empty());
+		assert_eq!(Profile::Production.features(path)?, vec!["on-chain-release-build"]);
+		Ok(())
+	}
+
+	#[test]
+	fn test_build_args() {
+		assert_eq!(build_args(Profile::Dev, &[], &[]), vec!["build"]);
+		assert_eq!(build_args(Profile::Release, &[], &[]), vec!["build", "--release"]);
+		assert_eq!(
+			build_args(
+				Profile::Production,
+				&["on-chain-release-build".into()],
+				&["try-runtime".into()]
+			),
+			vec!["build", "--profile=production", "--features=on-chain-release-build,try-runtime"]
+		);
+	}
+
+	#[test]
+	fn test_production_profile_must_be_defined() -> Result<()> {
+		let project = parachain_project()?;
+		assert!(!has_profile(project.path(), "production")?);
+		assert!(matches!(
+			build_parachain_profile(project.path(), Profile::Production, &[]),
+			Err(Error::Config(..))
+		));
+		let manifest = fs::read_to_string(project.path().join("Cargo.toml"))?;
+		fs::write(
+			project.path().join("Cargo.toml"),
+			format!("{manifest}\n[profile.production]\ninherits = \"release\"\n"),
+		)?;
+		assert!(has_profile(project.path(), "production")?);
+		Ok(())
+	}
+}
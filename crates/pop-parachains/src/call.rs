@@ -0,0 +1,285 @@
+// SPDX-License-Identifier: GPL-3.0
+use crate::{
+	errors::Error,
+	onboard::client,
+	relay::{dispatch_sudo, submit, sudo_call},
+};
+use subxt::{
+	blocks::ExtrinsicEvents,
+	dynamic::Value,
+	ext::scale_value::{stringify, ValueDef},
+	tx::DynamicPayload,
+	Metadata, PolkadotConfig,
+};
+use url::Url;
+
+/// A pallet of a chain, along with the calls it exposes.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Pallet {
+	/// The name of the pallet, e.g. `Balances`.
+	pub name: String,
+	/// The first paragraph of the documentation of the pallet.
+	pub docs: String,
+	/// The calls exposed by the pallet.
+	pub calls: Vec<Call>,
+}
+
+/// A call exposed by a pallet, dispatched by submitting an extrinsic.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Call {
+	/// The name of the pallet exposing the call.
+	pub pallet: String,
+	/// The name of the call, e.g. `transfer_keep_alive`.
+	pub name: String,
+	/// The first paragraph of the documentation of the call.
+	pub docs: String,
+	/// The parameters of the call.
+	pub params: Vec<Param>,
+}
+
+/// A parameter of a call.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Param {
+	/// The name of the parameter, e.g. `dest`.
+	pub name: String,
+	/// The name of the type of the parameter, as declared by the pallet, e.g.
+	/// `AccountIdLookupOf<T>`.
+	pub type_name: String,
+}
+
+/// An extrinsic submitted to a chain, once finalized.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Submitted {
+	/// The hash of the extrinsic.
+	pub hash: String,
+	/// The events emitted by the extrinsic, e.g. `Balances.Transfer`.
+	pub events: Vec<String>,
+}
+
+/// The metadata of the chain at `url`, describing its pallets along with their calls and storage.
+///
+/// # Arguments
+///
+/// * `url` - the endpoint of the chain
+pub async fn metadata(url: &Url) -> Result<Metadata, Error> {
+	Ok(client(url).await?.metadata())
+}
+
+/// The pallets of the chain described by `metadata` which expose calls, ordered by name.
+///
+/// # Arguments
+///
+/// * `metadata` - the metadata of the chain
+pub fn pallets(metadata: &Metadata) -> Vec<Pallet> {
+	let mut pallets: Vec<_> = metadata
+		.pallets()
+		.filter_map(|pallet| {
+			let calls = pallet
+				.call_variants()?
+				.iter()
+				.map(|variant| Call {
+					pallet: pallet.name().to_string(),
+					name: variant.name.clone(),
+					docs: first_paragraph(&variant.docs),
+					params: variant
+						.fields
+						.iter()
+						.map(|field| Param {
+							name: field.name.clone().unwrap_or_default(),
+							type_name: field.type_name.clone().unwrap_or_default(),
+						})
+						.collect(),
+				})
+				.collect();
+			Some(Pallet {
+				name: pallet.name().to_string(),
+				docs: first_paragraph(pallet.docs()),
+				calls,
+			})
+		})
+		.collect();
+	pallets.sort_by(|a, b| a.name.cmp(&b.name));
+	pallets
+}
+
+/// Constructs `call` of `pallet` from its `args`, one per parameter of the call, as validated
+/// against the `metadata` of the chain. Accounts provided where an address is expected are
+/// addressed by their account id.
+///
+/// # Arguments
+///
+/// * `metadata` - the metadata of the chain
+/// * `pallet` - the name of the pallet
+/// * `call` - the name of the call
+/// * `args` - the arguments of the call, as parsed by [`parse_value`]
+pub fn construct_call(
+	metadata: &Metadata,
+	pallet: &str,
+	call: &str,
+	args: &[String],
+) -> Result<DynamicPayload, Error> {
+	let variant = metadata
+		.pallet_by_name(pallet)
+		.and_then(|p| p.call_variant_by_name(call))
+		.ok_or_else(|| Error::Metadata(format!("the chain does not support `{pallet}::{call}`")))?;
+	if variant.fields.len() != args.len() {
+		return Err(Error::Metadata(format!(
+			"`{pallet}::{call}` expects {} arguments, whereas {} were provided",
+			variant.fields.len(),
+			args.len()
+		)));
+	}
+	let values = variant
+		.fields
+		.iter()
+		.zip(args)
+		.map(|(field, arg)| {
+			let value = parse_value(arg)?;
+			let address = metadata
+				.types()
+				.resolve(field.ty.id)
+				.is_some_and(|ty| ty.path.segments.last().is_some_and(|s| s == "MultiAddress"));
+			Ok(match (address, &value.value) {
+				(true, ValueDef::Composite(_)) => Value::unnamed_variant("Id", [value]),
+				_ => value,
+			})
+		})
+		.collect::<Result<Vec<_>, Error>>()?;
+	Ok(subxt::dynamic::tx(pallet, call, values))
+}
+
+/// Parses `arg` as a value, using the syntax of `scale-value` (e.g. `1000`, `"text"`, `(1, 2)`,
+/// `{ id: 1 }` or `Id(5Grwva…)`), additionally accepting SS58 addresses and hex encoded bytes.
+///
+/// # Arguments
+///
+/// * `arg` - the argument
+pub fn parse_value(arg: &str) -> Result<Value, Error> {
+	let (value, rest) = stringify::from_str_custom()
+		.add_custom_parser(stringify::custom_parsers::parse_ss58)
+		.add_custom_parser(stringify::custom_parsers::parse_hex)
+		.parse(arg.trim());
+	match value {
+		Ok(value) if rest.trim().is_empty() => Ok(value),
+		Ok(_) => Err(Error::Metadata(format!("invalid argument `{arg}`: unexpected `{rest}`"))),
+		Err(e) => Err(Error::Metadata(format!(
+			"invalid argument `{arg}`: {e}, text is provided within double quotes"
+		))),
+	}
+}
+
+/// Wraps `call` so that it is dispatched using the sudo account of the chain, as
+/// `Sudo::sudo_unchecked_weight` if `unchecked_weight`, otherwise as `Sudo::sudo`.
+///
+/// # Arguments
+///
+/// * `call` - the call dispatched using sudo
+/// * `unchecked_weight` - whether the weight of the call is not checked against the limits of a
+///   block, as required by `System::set_code`
+pub fn construct_sudo_call(call: DynamicPayload, unchecked_weight: bool) -> DynamicPayload {
+	sudo_call(call.into_value(), unchecked_weight)
+}
+
+/// Signs `call` with the account of `suri` and submits it to the chain at `url`, waiting for it
+/// to be finalized successfully.
+///
+/// # Arguments
+///
+/// * `url` - the endpoint of the chain
+/// * `suri` - the secret key URI of the account signing the extrinsic
+/// * `call` - the call dispatched
+pub async fn submit_call(url: &Url, suri: &str, call: &DynamicPayload) -> Result<Submitted, Error> {
+	submitted(submit(url, suri, call).await?)
+}
+
+/// Signs `call`, as constructed by [`construct_sudo_call`], with the sudo account of `suri` and
+/// submits it to the chain at `url`, waiting for it to be finalized and for the call dispatched
+/// by sudo to succeed.
+///
+/// # Arguments
+///
+/// * `url` - the endpoint of the chain
+/// * `suri` - the secret key URI of the sudo account of the chain
+/// * `call` - the call dispatched by sudo
+pub async fn submit_sudo_call(
+	url: &Url,
+	suri: &str,
+	call: &DynamicPayload,
+) -> Result<Submitted, Error> {
+	submitted(dispatch_sudo(url, suri, call).await?)
+}
+
+/// The hash and events of an extrinsic finalized.
+fn submitted(events: ExtrinsicEvents<PolkadotConfig>) -> Result<Submitted, Error> {
+	let hash = format!("{:?}", events.extrinsic_hash());
+	let events = events
+		.iter()
+		.map(|event| {
+			event
+				.map(|e| format!("{}.{}", e.pallet_name(), e.variant_name()))
+				.map_err(|e| Error::Extrinsic(format!("{e}")))
+		})
+		.collect::<Result<_, _>>()?;
+	Ok(Submitted { hash, events })
+}
+
+/// The first paragraph of `docs`, as a single line.
+fn first_paragraph(docs: &[String]) -> String {
+	docs.iter()
+		.map(|line| line.trim())
+		.take_while(|line| !line.is_empty())
+		.collect::<Vec<_>>()
+		.join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn parse_value_works() -> Result<(), Error> {
+		assert_eq!(parse_value("1000")?, Value::u128(1000));
+		assert_eq!(parse_value(" true ")?, Value::bool(true));
+		assert_eq!(parse_value("\"pop\"")?, Value::string("pop"));
+		assert_eq!(parse_value("0x00ff")?, Value::from_bytes([0x00, 0xff]));
+		let alice = subxt_signer::sr25519::dev::alice().public_key().0;
+		assert_eq!(
+			parse_value("5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY")?,
+			Value::from_bytes(alice)
+		);
+		assert_eq!(
+			parse_value("Id(0x00ff)")?,
+			Value::unnamed_variant("Id", [Value::from_bytes([0x00, 0xff])])
+		);
+		for invalid in ["pop", "(1, 2", "1 2"] {
+			assert!(matches!(parse_value(invalid), Err(Error::Metadata(..))));
+		}
+		Ok(())
+	}
+
+	#[test]
+	fn construct_sudo_call_works() {
+		let call = || subxt::dynamic::tx("System", "remark", vec![Value::from_bytes("pop")]);
+		let sudo = construct_sudo_call(call(), false);
+		assert_eq!((sudo.pallet_name(), sudo.call_name()), ("Sudo", "sudo"));
+		assert_eq!(
+			sudo.into_value(),
+			subxt::dynamic::tx("Sudo", "sudo", vec![call().into_value()]).into_value()
+		);
+		let sudo = construct_sudo_call(call(), true);
+		assert_eq!((sudo.pallet_name(), sudo.call_name()), ("Sudo", "sudo_unchecked_weight"));
+	}
+
+	#[test]
+	fn first_paragraph_works() {
+		let docs = [" Transfer some liquid free balance.", "", " More details."].map(String::from);
+		assert_eq!(first_paragraph(&docs), "Transfer some liquid free balance.");
+		assert_eq!(first_paragraph(&[]), "");
+	}
+
+	#[tokio::test]
+	async fn metadata_fails_without_chain() {
+		let url = Url::parse("ws://127.0.0.1:9").expect("valid url");
+		assert!(matches!(metadata(&url).await, Err(Error::Extrinsic(..))));
+	}
+}
@@ -0,0 +1,454 @@
+// SPDX-License-Identifier: GPL-3.0
+use crate::{
+	build::{node_binary, ARTIFACTS_DIR},
+	errors::Error,
+	relay::read_hex,
+};
+use duct::cmd;
+use serde_json::{json, Value};
+use std::{
+	ffi::OsString,
+	fs,
+	path::{Path, PathBuf},
+};
+
+/// The locations of the parachain id within the genesis configuration of the various chain spec
+/// formats.
+const PARA_ID_GENESIS: [&str; 3] = [
+	"/genesis/runtime/parachainInfo/parachainId",
+	"/genesis/runtimeGenesis/config/parachainInfo/parachainId",
+	"/genesis/runtimeGenesis/patch/parachainInfo/parachainId",
+];
+/// The locations of the genesis configuration of the runtime within the various chain spec
+/// formats.
+const RUNTIME_GENESIS: [&str; 3] =
+	["/genesis/runtime", "/genesis/runtimeGenesis/config", "/genesis/runtimeGenesis/patch"];
+
+/// The chain specs generated for a parachain.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ChainSpec {
+	/// The plain (human-readable) chain spec.
+	pub plain: PathBuf,
+	/// The raw chain spec, as used to launch the parachain, if requested.
+	pub raw: Option<PathBuf>,
+}
+
+/// The genesis artifacts of a parachain, as required to register it on a relay chain.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GenesisArtifacts {
+	/// The identifier of the parachain the artifacts were computed for, if known.
+	pub para_id: Option<u32>,
+	/// The raw chain spec the artifacts were computed from.
+	pub chain_spec: PathBuf,
+	/// The genesis state (head) of the parachain.
+	pub genesis_state: PathBuf,
+	/// The genesis wasm (validation code) of the parachain.
+	pub genesis_wasm: PathBuf,
+}
+
+/// Overrides applied to the chain spec generated by the node of a parachain.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ChainSpecOverrides {
+	/// The identifier of the parachain.
+	pub para_id: Option<u32>,
+	/// The relay chain the parachain connects to (e.g. `paseo` or `rococo-local`).
+	pub relay_chain: Option<String>,
+	/// The bootnodes of the chain, replacing any defined by the node.
+	pub boot_nodes: Vec<String>,
+	/// The network protocol id of the chain.
+	pub protocol_id: Option<String>,
+	/// The symbol of the native token.
+	pub token_symbol: Option<String>,
+	/// The number of decimals of the native token.
+	pub token_decimals: Option<u8>,
+	/// Patches of the genesis configuration of the runtime, merged into it in order (e.g.
+	/// `{"sudo": {"key": "5GrwvaEF..."}}`). Objects are merged, while any other value replaces
+	/// that of the chain spec.
+	pub genesis: Vec<Value>,
+}
+
+impl ChainSpecOverrides {
+	/// Applies the overrides to the plain chain spec `spec`.
+	///
+	/// # Arguments
+	///
+	/// * `spec` - the plain chain spec
+	pub fn apply(&self, spec: &mut Value) -> Result<(), Error> {
+		if !spec.is_object() {
+			return Err(Error::Config("the chain spec is not a json object".into()));
+		}
+		if let Some(para_id) = self.para_id {
+			// The extension read by the collator, whose name depends on the version of the node...
+			let extension = if spec.get("paraId").is_some() { "paraId" } else { "para_id" };
+			spec[extension] = json!(para_id);
+			// ...which must match the identifier configured at genesis.
+			for pointer in PARA_ID_GENESIS {
+				if let Some(id) = spec.pointer_mut(pointer) {
+					*id = json!(para_id);
+				}
+			}
+		}
+		if let Some(relay_chain) = &self.relay_chain {
+			let extension =
+				if spec.get("relayChain").is_some() { "relayChain" } else { "relay_chain" };
+			spec[extension] = json!(relay_chain);
+		}
+		if !self.boot_nodes.is_empty() {
+			spec["bootNodes"] = json!(self.boot_nodes);
+		}
+		if let Some(protocol_id) = &self.protocol_id {
+			spec["protocolId"] = json!(protocol_id);
+		}
+		if let Some(symbol) = &self.token_symbol {
+			spec["properties"]["tokenSymbol"] = json!(symbol);
+		}
+		if let Some(decimals) = self.token_decimals {
+			spec["properties"]["tokenDecimals"] = json!(decimals);
+		}
+		if !self.genesis.is_empty() {
+			let genesis = RUNTIME_GENESIS.iter().find_map(|p| spec.pointer(p).map(|_| *p)).ok_or(
+				Error::Config("the chain spec has no genesis configuration of the runtime".into()),
+			)?;
+			let genesis = spec.pointer_mut(genesis).expect("genesis found above");
+			for patch in &self.genesis {
+				merge(genesis, patch);
+			}
+		}
+		Ok(())
+	}
+}
+
+/// Parses an override of the genesis configuration of the runtime, specified as
+/// `<path>=<json>`, where the path is dot-separated (e.g. `sudo.key="5GrwvaEF..."`), into a
+/// patch of the genesis configuration. The value of a pallet which is not an object sets the
+/// field of the pallet with the same name, so that `balances=[["5GrwvaEF...", 1e15]]` sets the
+/// endowed accounts of `balances`. Numbers in scientific notation are read as integers.
+///
+/// # Arguments
+///
+/// * `value` - the path and its value
+pub fn parse_genesis_override(value: &str) -> Result<Value, Error> {
+	let (path, json) = value
+		.split_once('=')
+		.filter(|(path, _)| !path.is_empty())
+		.ok_or(Error::Config(format!("expected `<path>=<json>`, found `{value}`")))?;
+	let mut value: Value = serde_json::from_str(json)
+		.map_err(|e| Error::Config(format!("invalid value of `{path}`: {e}")))?;
+	integers(&mut value);
+	let mut keys: Vec<_> = path.split('.').collect();
+	if keys.iter().any(|k| k.is_empty()) {
+		return Err(Error::Config(format!("invalid path `{path}`")));
+	}
+	if keys.len() == 1 && !value.is_object() {
+		keys.push(keys[0]);
+	}
+	Ok(keys.into_iter().rev().fold(value, |value, key| json!({ key: value })))
+}
+
+/// Merges `patch` into `target`: objects are merged recursively, while any other value replaces
+/// that of the target.
+fn merge(target: &mut Value, patch: &Value) {
+	match (target, patch) {
+		(Value::Object(target), Value::Object(patch)) => {
+			for (key, value) in patch {
+				merge(target.entry(key.clone()).or_insert(Value::Null), value);
+			}
+		},
+		(target, patch) => *target = patch.clone(),
+	}
+}
+
+/// Converts the integral numbers of `value` expressed as floating point (e.g. `1e15`) into
+/// integers, as expected of balances.
+fn integers(value: &mut Value) {
+	match value {
+		Value::Number(number) => {
+			if let Some(float) = number.as_f64().filter(|f| number.is_f64() && f.fract() == 0.0) {
+				if (0.0..=u64::MAX as f64).contains(&float) {
+					*value = json!(float as u64);
+				}
+			}
+		},
+		Value::Array(values) => values.iter_mut().for_each(integers),
+		Value::Object(values) => values.values_mut().for_each(integers),
+		_ => {},
+	}
+}
+
+/// Generates the chain spec of `chain` using the node of the latest release build of the parachain
+/// at `path`, applying `overrides` before writing it into `target/pop`, optionally along with its
+/// raw form.
+///
+/// # Arguments
+///
+/// * `path` - path to the project
+/// * `chain` - the chain to generate the spec of (e.g. `dev`, `local`)
+/// * `overrides` - the overrides applied to the chain spec
+/// * `raw` - whether to also generate the raw chain spec
+pub fn generate_chain_spec(
+	path: &Path,
+	chain: &str,
+	overrides: &ChainSpecOverrides,
+	raw: bool,
+) -> Result<ChainSpec, Error> {
+	let node = node_binary(path)?;
+	let output = path.join("target").join(ARTIFACTS_DIR);
+	fs::create_dir_all(&output)?;
+
+	let spec = build_spec(&node, path, chain.into(), false)?;
+	let mut spec: Value = serde_json::from_str(&spec)
+		.map_err(|e| Error::Config(format!("the node generated an invalid chain spec: {e}")))?;
+	overrides.apply(&mut spec)?;
+	let plain = output.join("chain-spec.json");
+	fs::write(&plain, serde_json::to_string_pretty(&spec).map_err(anyhow::Error::from)?)?;
+
+	let raw = match raw {
+		true => {
+			// The raw chain spec is generated from the plain one, so that it includes the overrides.
+			let raw = output.join("raw-chain-spec.json");
+			fs::write(&raw, build_spec(&node, path, plain.as_os_str().to_owned(), true)?)?;
+			Some(raw)
+		},
+		false => None,
+	};
+	Ok(ChainSpec { plain, raw })
+}
+
+/// Exports the genesis state and genesis wasm of the parachain at `path` into `target/pop`,
+/// computed from the raw chain spec of `chain` so that they match the chain the collators run.
+/// Both are written as `0x` prefixed hex, as expected by `Registrar::register` and the parachain
+/// onboarding UI.
+///
+/// # Arguments
+///
+/// * `path` - path to the project
+/// * `chain` - the chain to export the genesis of (e.g. `dev`, `local`)
+/// * `overrides` - the overrides applied to the chain spec, such as the identifier of the
+///   parachain
+pub fn export_genesis(
+	path: &Path,
+	chain: &str,
+	overrides: &ChainSpecOverrides,
+) -> Result<GenesisArtifacts, Error> {
+	let spec = generate_chain_spec(path, chain, overrides, true)?;
+	let chain_spec = spec.raw.expect("raw chain spec requested above");
+	let para_id = match overrides.para_id {
+		Some(para_id) => Some(para_id),
+		None => spec_para_id(&fs::read_to_string(&spec.plain)?),
+	};
+
+	let node = node_binary(path)?;
+	let output = path.join("target").join(ARTIFACTS_DIR);
+	let prefix = para_id.map(|id| format!("para-{id}-")).unwrap_or_default();
+	let genesis_state = output.join(format!("{prefix}genesis-state"));
+	let genesis_wasm = output.join(format!("{prefix}genesis-wasm"));
+	for (command, file) in
+		[("export-genesis-state", &genesis_state), ("export-genesis-wasm", &genesis_wasm)]
+	{
+		let args: Vec<OsString> =
+			vec![command.into(), "--chain".into(), chain_spec.as_os_str().to_owned()];
+		tracing::debug!(node = %node.display(), ?args, "exporting genesis artifact");
+		let exported = cmd(&node, args).dir(path).stderr_null().read()?;
+		fs::write(file, &exported)?;
+		normalize_hex(file)?;
+	}
+	Ok(GenesisArtifacts { para_id, chain_spec, genesis_state, genesis_wasm })
+}
+
+/// The identifier of the parachain configured by the chain spec `spec`.
+fn spec_para_id(spec: &str) -> Option<u32> {
+	let spec: Value = serde_json::from_str(spec).ok()?;
+	["paraId", "para_id"]
+		.into_iter()
+		.find_map(|extension| spec.get(extension).and_then(|id| id.as_u64()))
+		.and_then(|id| u32::try_from(id).ok())
+}
+
+/// Rewrites the hex encoded bytes of the file at `path` as a single `0x` prefixed, lower case line
+/// without any trailing whitespace, which is the format accepted when registering a parachain.
+fn normalize_hex(path: &Path) -> Result<(), Error> {
+	let bytes = read_hex(path)?;
+	if bytes.is_empty() {
+		return Err(Error::Config(format!("{} is empty", path.display())));
+	}
+	let hex: String = bytes.iter().map(|b| format!("{b:02x}")).collect();
+	fs::write(path, format!("0x{hex}"))?;
+	Ok(())
+}
+
+/// Runs the `build-spec` command of `node`, returning the generated chain spec.
+fn build_spec(node: &Path, path: &Path, chain: OsString, raw: bool) -> Result<String, Error> {
+	let mut args: Vec<OsString> =
+		vec!["build-spec".into(), "--chain".into(), chain, "--disable-default-bootnode".into()];
+	if raw {
+		args.push("--raw".into());
+	}
+	tracing::debug!(node = %node.display(), ?args, "building chain spec");
+	Ok(cmd(node, args).dir(path).stderr_null().read()?)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+
+	#[test]
+	fn test_apply_overrides() -> Result<()> {
+		let mut spec = json!({
+			"name": "Development",
+			"bootNodes": ["/ip4/127.0.0.1/tcp/30333/p2p/12D3KooW"],
+			"protocolId": "template-local",
+			"properties": { "ss58Format": 42, "tokenSymbol": "UNIT" },
+			"relay_chain": "rococo-local",
+			"para_id": 1000,
+			"genesis": { "runtimeGenesis": { "patch": { "parachainInfo": { "parachainId": 1000 } } } }
+		});
+		ChainSpecOverrides {
+			para_id: Some(2000),
+			relay_chain: Some("paseo".into()),
+			boot_nodes: vec!["/dns/pop.io/tcp/30333/p2p/12D3KooW".into()],
+			protocol_id: Some("pop".into()),
+			token_symbol: Some("POP".into()),
+			token_decimals: Some(10),
+			genesis: Vec::new(),
+		}
+		.apply(&mut spec)?;
+		assert_eq!(spec["para_id"], 2000);
+		assert_eq!(
+			spec["genesis"]["runtimeGenesis"]["patch"]["parachainInfo"]["parachainId"],
+			2000
+		);
+		assert_eq!(spec["relay_chain"], "paseo");
+		assert_eq!(spec["bootNodes"], json!(["/dns/pop.io/tcp/30333/p2p/12D3KooW"]));
+		assert_eq!(spec["protocolId"], "pop");
+		assert_eq!(
+			spec["properties"],
+			json!({ "ss58Format": 42, "tokenSymbol": "POP", "tokenDecimals": 10 })
+		);
+		Ok(())
+	}
+
+	#[test]
+	fn test_apply_no_overrides() -> Result<()> {
+		let mut spec = json!({ "name": "Development", "bootNodes": ["/ip4/127.0.0.1"] });
+		let original = spec.clone();
+		ChainSpecOverrides::default().apply(&mut spec)?;
+		assert_eq!(spec, original);
+		assert!(matches!(
+			ChainSpecOverrides::default().apply(&mut json!([])),
+			Err(Error::Config(..))
+		));
+		Ok(())
+	}
+
+	#[test]
+	fn test_spec_para_id() {
+		assert_eq!(spec_para_id(r#"{ "para_id": 2000 }"#), Some(2000));
+		assert_eq!(spec_para_id(r#"{ "paraId": 4001, "relayChain": "paseo" }"#), Some(4001));
+		assert_eq!(spec_para_id(r#"{ "name": "Development" }"#), None);
+		assert_eq!(spec_para_id("not json"), None);
+	}
+
+	#[test]
+	fn test_normalize_hex() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let file = temp_dir.path().join("genesis-state");
+		fs::write(&file, "0x00AB12\n")?;
+		normalize_hex(&file)?;
+		assert_eq!(fs::read_to_string(&file)?, "0x00ab12");
+		fs::write(&file, "00ab12")?;
+		normalize_hex(&file)?;
+		assert_eq!(fs::read_to_string(&file)?, "0x00ab12");
+		for invalid in ["", "0x", "0xabc", "not hex"] {
+			fs::write(&file, invalid)?;
+			assert!(matches!(normalize_hex(&file), Err(Error::Config(..))));
+		}
+		Ok(())
+	}
+
+	#[test]
+	fn test_export_genesis_requires_a_release_build() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		fs::write(
+			temp_dir.path().join("Cargo.toml"),
+			"[package]\nname = \"parachain-node\"\n\n[dependencies]\ncumulus-client-cli = \"0.7\"\n",
+		)?;
+		assert!(matches!(
+			export_genesis(
+				temp_dir.path(),
+				"dev",
+				&ChainSpecOverrides { para_id: Some(2000), ..Default::default() }
+			),
+			Err(Error::MissingBinary(..))
+		));
+		Ok(())
+	}
+
+	#[test]
+	fn test_generate_chain_spec_requires_a_release_build() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		fs::write(
+			temp_dir.path().join("Cargo.toml"),
+			"[package]\nname = \"parachain-node\"\n\n[dependencies]\ncumulus-client-cli = \"0.7\"\n",
+		)?;
+		let result =
+			generate_chain_spec(temp_dir.path(), "dev", &ChainSpecOverrides::default(), true);
+		assert!(matches!(result, Err(Error::MissingBinary(..))));
+		Ok(())
+	}
+
+	#[test]
+	fn test_parse_genesis_override() -> Result<()> {
+		assert_eq!(
+			parse_genesis_override(r#"balances=[["5GrwvaEF", 1e15]]"#)?,
+			json!({ "balances": { "balances": [["5GrwvaEF", 1_000_000_000_000_000u64]] } })
+		);
+		assert_eq!(
+			parse_genesis_override(r#"sudo.key="5GrwvaEF""#)?,
+			json!({ "sudo": { "key": "5GrwvaEF" } })
+		);
+		assert_eq!(
+			parse_genesis_override(r#"sudo={"key":"5GrwvaEF"}"#)?,
+			json!({ "sudo": { "key": "5GrwvaEF" } })
+		);
+		assert_eq!(parse_genesis_override("a.b=0.5")?, json!({ "a": { "b": 0.5 } }));
+		for invalid in ["balances", "=1", "sudo.key=not json", "sudo..key=1"] {
+			assert!(matches!(parse_genesis_override(invalid), Err(Error::Config(..))));
+		}
+		Ok(())
+	}
+
+	#[test]
+	fn test_apply_genesis_overrides() -> Result<()> {
+		let mut spec = json!({
+			"genesis": { "runtimeGenesis": { "patch": {
+				"balances": { "balances": [["5FHneW46", 1]] },
+				"sudo": { "key": "5FHneW46" },
+				"parachainInfo": { "parachainId": 1000 }
+			} } }
+		});
+		ChainSpecOverrides {
+			genesis: vec![
+				parse_genesis_override(r#"balances=[["5GrwvaEF", 1e15]]"#)?,
+				json!({ "sudo": { "key": "5GrwvaEF" }, "aura": { "authorities": [] } }),
+			],
+			..Default::default()
+		}
+		.apply(&mut spec)?;
+		assert_eq!(
+			spec["genesis"]["runtimeGenesis"]["patch"],
+			json!({
+				"balances": { "balances": [["5GrwvaEF", 1_000_000_000_000_000u64]] },
+				"sudo": { "key": "5GrwvaEF" },
+				"parachainInfo": { "parachainId": 1000 },
+				"aura": { "authorities": [] }
+			})
+		);
+
+		let mut spec = json!({ "name": "Development" });
+		let overrides =
+			ChainSpecOverrides { genesis: vec![json!({ "sudo": {} })], ..Default::default() };
+		assert!(matches!(overrides.apply(&mut spec), Err(Error::Config(..))));
+		Ok(())
+	}
+}
@@ -0,0 +1,344 @@
+// SPDX-License-Identifier: GPL-3.0
+use crate::errors::Error;
+use std::path::Path;
+use toml_edit::{DocumentMut, Item, Table};
+
+/// The image of the relay chain nodes, tagged by polkadot release.
+pub(crate) const POLKADOT_IMAGE: &str = "docker.io/parity/polkadot";
+/// The image of the collators of system parachains, tagged by polkadot release.
+pub(crate) const POLKADOT_PARACHAIN_IMAGE: &str = "docker.io/parity/polkadot-parachain";
+/// The image running the collators of parachains built locally, whose binary is mounted into it.
+const BASE_IMAGE: &str = "docker.io/library/debian:bookworm-slim";
+/// The directory within each container into which chain specs are mounted.
+const CHAIN_SPECS: &str = "/chain-specs";
+/// The port on which each node serves RPC requests within its container.
+const CONTAINER_RPC_PORT: u16 = 9944;
+/// The development accounts whose session keys are inserted into the keystore of a node, either by
+/// name or in order of the nodes of a chain, as done by Zombienet.
+const DEV_ACCOUNTS: [&str; 6] = ["alice", "bob", "charlie", "dave", "eve", "ferdie"];
+
+/// A service of a Docker Compose file, running a single node.
+#[derive(Debug, Default, PartialEq)]
+struct Service {
+	name: String,
+	image: String,
+	entrypoint: Option<String>,
+	command: Vec<String>,
+	ports: Vec<String>,
+	volumes: Vec<String>,
+	environment: Vec<(String, String)>,
+	depends_on: Vec<String>,
+}
+
+/// Generates a Docker Compose file replicating a network, as configured for Zombienet: a service
+/// for each node, using the images of the polkadot `version` (or a base image into which a local
+/// binary is mounted), mounting chain specs, and publishing the RPC port of each node. Nodes
+/// discover each other via mDNS within the network created by Docker Compose.
+///
+/// # Arguments
+///
+/// * `config` - the network configuration, as expected by Zombienet, with each node assigned an
+///   RPC port
+/// * `version` - the polkadot release of the network
+/// * `base` - the directory against which relative paths of the configuration are resolved
+pub(crate) fn docker_compose(
+	config: &DocumentMut,
+	version: &str,
+	base: &Path,
+) -> Result<String, Error> {
+	let relay_chain = config
+		.get("relaychain")
+		.and_then(|r| r.as_table())
+		.ok_or(Error::Config("expected `relaychain`".into()))?;
+	let relay_spec = chain_spec(relay_chain, "relay", "rococo-local", base)?;
+	let mut services = Vec::new();
+	let validators = nodes(relay_chain.get("nodes"));
+	for (index, node) in validators.iter().enumerate() {
+		let mut service = service(node, index, &default_args(relay_chain), &relay_spec)?;
+		service.image = format!("{POLKADOT_IMAGE}:{version}");
+		service.command.insert(0, "--validator".into());
+		services.push(service);
+	}
+	let first_validator = services.first().map(|s| s.name.clone());
+
+	let parachains = config.get("parachains").and_then(|p| p.as_array_of_tables());
+	for parachain in parachains.into_iter().flat_map(|p| p.iter()) {
+		let id = parachain
+			.get("id")
+			.and_then(|i| i.as_integer())
+			.ok_or(Error::Config("expected `parachain` to have `id`".into()))?;
+		let spec = chain_spec(parachain, &format!("para-{id}"), "local", base)?;
+		let default_command = parachain
+			.get("default_command")
+			.and_then(|c| c.as_str())
+			.unwrap_or("polkadot-parachain");
+		let mut collators = nodes(parachain.get("collators"));
+		collators.extend(nodes(parachain.get("collator")));
+		for (index, node) in collators.iter().enumerate() {
+			let mut service = service(node, index, &default_args(parachain), &spec)?;
+			service.command.insert(0, "--collator".into());
+			// The embedded relay chain node is configured after the arguments of the collator.
+			service.command.extend(["--".into(), format!("--chain={}", relay_spec.0)]);
+			let command = node.get("command").and_then(|c| c.as_str()).unwrap_or(default_command);
+			match command {
+				"polkadot-parachain" => {
+					service.image = format!("{POLKADOT_PARACHAIN_IMAGE}:{version}");
+				},
+				binary => {
+					let binary = resolve(base, binary)?;
+					let name = binary.file_name().and_then(|n| n.to_str()).ok_or(Error::Config(
+						format!("invalid parachain binary {}", binary.display()),
+					))?;
+					service.image = BASE_IMAGE.into();
+					service.entrypoint = Some(format!("/usr/local/bin/{name}"));
+					service.volumes.push(format!("{}:/usr/local/bin/{name}:ro", binary.display()));
+				},
+			}
+			for volume in &relay_spec.1 {
+				service.volumes.push(volume.clone());
+			}
+			service.depends_on.extend(first_validator.clone());
+			services.push(service);
+		}
+	}
+	Ok(render(&services))
+}
+
+/// The nodes of a chain, configured either as an array of tables or as a single table.
+fn nodes(item: Option<&Item>) -> Vec<&Table> {
+	match item {
+		Some(Item::ArrayOfTables(nodes)) => nodes.iter().collect(),
+		Some(Item::Table(node)) => vec![node],
+		_ => Vec::new(),
+	}
+}
+
+/// The arguments provided to every node of a chain.
+fn default_args(chain: &Table) -> Vec<String> {
+	strings(chain.get("default_args"))
+}
+
+fn strings(item: Option<&Item>) -> Vec<String> {
+	item.and_then(|a| a.as_array())
+		.map(|a| a.iter().filter_map(|a| a.as_str().map(|a| a.to_string())).collect())
+		.unwrap_or_default()
+}
+
+/// The chain spec of a chain, as provided to its nodes, along with any volume mounting it: either
+/// a chain spec file, mounted as `<name>.json`, or a chain known to the node.
+fn chain_spec(
+	chain: &Table,
+	name: &str,
+	default: &str,
+	base: &Path,
+) -> Result<(String, Option<String>), Error> {
+	if let Some(path) = chain.get("chain_spec_path").and_then(|p| p.as_str()) {
+		let path = resolve(base, path)?;
+		let mounted = format!("{CHAIN_SPECS}/{name}.json");
+		return Ok((mounted.clone(), Some(format!("{}:{mounted}:ro", path.display()))));
+	}
+	let chain = chain.get("chain").and_then(|c| c.as_str()).unwrap_or(default);
+	Ok((chain.to_string(), None))
+}
+
+/// Resolves `path` against `base`, unless absolute, falling back to the current directory.
+fn resolve(base: &Path, path: &str) -> Result<std::path::PathBuf, Error> {
+	base.join(path)
+		.canonicalize()
+		.or_else(|_| Path::new(path).canonicalize())
+		.map_err(|_| Error::Config(format!("unable to find {path}")))
+}
+
+/// The service running `node`, the node at `index` of a chain, before its image is determined.
+fn service(
+	node: &Table,
+	index: usize,
+	default_args: &[String],
+	spec: &(String, Option<String>),
+) -> Result<Service, Error> {
+	let name = node
+		.get("name")
+		.and_then(|n| n.as_str())
+		.ok_or(Error::Config("expected each node to have a `name`".into()))?;
+	let rpc_port = node
+		.get("rpc_port")
+		.and_then(|p| p.as_integer())
+		.ok_or(Error::Config(format!("expected `{name}` to have an `rpc_port`")))?;
+	let mut command = vec![
+		format!("--name={name}"),
+		format!("--chain={}", spec.0),
+		"--base-path=/data".into(),
+		format!("--rpc-port={CONTAINER_RPC_PORT}"),
+		"--rpc-external".into(),
+		"--rpc-cors=all".into(),
+		// A fixed network key, as a node launched as a validator requires one.
+		format!("--node-key={:064x}", index + 1),
+	];
+	// Session keys are inserted for the development account of the node, as by Zombienet.
+	let account = DEV_ACCOUNTS.iter().find(|a| **a == *name).or(DEV_ACCOUNTS.get(index)).ok_or(
+		Error::Config(format!("too many nodes to assign a development account to `{name}`")),
+	)?;
+	command.push(format!("--{account}"));
+	command.extend(default_args.iter().cloned());
+	command.extend(strings(node.get("args")));
+	let environment = node
+		.get("env")
+		.and_then(|e| e.as_array())
+		.map(|env| {
+			env.iter()
+				.filter_map(|var| var.as_inline_table())
+				.filter_map(|var| {
+					let name = var.get("name")?.as_str()?;
+					let value = var.get("value")?.as_str()?;
+					Some((name.to_string(), value.to_string()))
+				})
+				.collect()
+		})
+		.unwrap_or_default();
+	Ok(Service {
+		name: name.into(),
+		command,
+		ports: vec![format!("{rpc_port}:{CONTAINER_RPC_PORT}")],
+		volumes: [Some(format!("{name}-data:/data")), spec.1.clone()]
+			.into_iter()
+			.flatten()
+			.collect(),
+		environment,
+		..Default::default()
+	})
+}
+
+/// Renders the services as a Docker Compose file, quoting every value as a JSON string, which is
+/// also a valid YAML scalar.
+fn render(services: &[Service]) -> String {
+	let quote = |value: &str| serde_json::Value::from(value).to_string();
+	let list = |values: &[String]| {
+		format!("[{}]", values.iter().map(|v| quote(v)).collect::<Vec<_>>().join(", "))
+	};
+	let mut compose = String::from("services:\n");
+	for service in services {
+		compose += &format!("  {}:\n", quote(&service.name));
+		compose += &format!("    image: {}\n", quote(&service.image));
+		if let Some(entrypoint) = &service.entrypoint {
+			compose += &format!("    entrypoint: {}\n", quote(entrypoint));
+		}
+		compose += &format!("    command: {}\n", list(&service.command));
+		compose += &format!("    ports: {}\n", list(&service.ports));
+		compose += &format!("    volumes: {}\n", list(&service.volumes));
+		if !service.environment.is_empty() {
+			compose += "    environment:\n";
+			for (name, value) in &service.environment {
+				compose += &format!("      {}: {}\n", quote(name), quote(value));
+			}
+		}
+		if !service.depends_on.is_empty() {
+			compose += &format!("    depends_on: {}\n", list(&service.depends_on));
+		}
+	}
+	compose += "volumes:\n";
+	for service in services {
+		compose += &format!("  {}: {{}}\n", quote(&format!("{}-data", service.name)));
+	}
+	compose
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+	use std::fs;
+
+	#[test]
+	fn test_docker_compose() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let node = temp_dir.path().join("parachain-template-node");
+		fs::write(&node, "")?;
+		let config: DocumentMut = r#"
+[relaychain]
+chain = "rococo-local"
+
+[[relaychain.nodes]]
+name = "alice"
+rpc_port = 9900
+validator = true
+
+[[relaychain.nodes]]
+name = "bob"
+rpc_port = 9901
+validator = true
+
+[[parachains]]
+id = 2000
+default_command = "./parachain-template-node"
+default_args = ["-lruntime=debug"]
+
+[[parachains.collators]]
+name = "collator-01"
+rpc_port = 9944
+env = [{ name = "RUST_LOG", value = "info" }]
+
+[[parachains]]
+id = 1000
+chain = "asset-hub-rococo-local"
+
+[parachains.collator]
+name = "asset-hub"
+rpc_port = 9945
+"#
+		.parse()?;
+		let compose = docker_compose(&config, "v1.11.0", temp_dir.path())?;
+		let node = node.canonicalize()?;
+
+		assert!(compose.starts_with("services:\n  \"alice\":\n"));
+		assert!(compose.contains("    image: \"docker.io/parity/polkadot:v1.11.0\"\n"));
+		assert!(compose.contains(
+			"    command: [\"--validator\", \"--name=bob\", \"--chain=rococo-local\", \
+			 \"--base-path=/data\", \"--rpc-port=9944\", \"--rpc-external\", \"--rpc-cors=all\", \
+			 \"--node-key=0000000000000000000000000000000000000000000000000000000000000002\", \
+			 \"--bob\"]\n"
+		));
+		assert!(compose.contains("    ports: [\"9901:9944\"]\n"));
+
+		// Collators of a local parachain run its binary within a base image.
+		assert!(compose.contains(&format!(
+			"    entrypoint: \"/usr/local/bin/parachain-template-node\"\n    command: [\"--collator\", \
+			 \"--name=collator-01\", \"--chain=local\", \"--base-path=/data\", \"--rpc-port=9944\", \
+			 \"--rpc-external\", \"--rpc-cors=all\", \
+			 \"--node-key=0000000000000000000000000000000000000000000000000000000000000001\", \
+			 \"--alice\", \"-lruntime=debug\", \"--\", \"--chain=rococo-local\"]\n    ports: \
+			 [\"9944:9944\"]\n    volumes: [\"collator-01-data:/data\", \
+			 \"{}:/usr/local/bin/parachain-template-node:ro\"]\n    environment:\n      \
+			 \"RUST_LOG\": \"info\"\n    depends_on: [\"alice\"]\n",
+			node.display()
+		)));
+		assert!(compose.contains("    image: \"docker.io/parity/polkadot-parachain:v1.11.0\"\n"));
+		assert!(compose.contains("\"--chain=asset-hub-rococo-local\""));
+		assert!(compose.ends_with(
+			"volumes:\n  \"alice-data\": {}\n  \"bob-data\": {}\n  \"collator-01-data\": {}\n  \
+			 \"asset-hub-data\": {}\n"
+		));
+		Ok(())
+	}
+
+	#[test]
+	fn test_docker_compose_mounts_chain_specs() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		fs::write(temp_dir.path().join("relay.json"), "{}")?;
+		let config: DocumentMut = "[relaychain]\nchain_spec_path = \"relay.json\"\n\n\
+		                           [[relaychain.nodes]]\nname = \"alice\"\nrpc_port = 9944\n"
+			.parse()?;
+		let compose = docker_compose(&config, "stable2407", temp_dir.path())?;
+		assert!(compose.contains("\"--chain=/chain-specs/relay.json\""));
+		assert!(compose.contains(&format!(
+			"\"{}:/chain-specs/relay.json:ro\"",
+			temp_dir.path().join("relay.json").canonicalize()?.display()
+		)));
+
+		let missing: DocumentMut = "[relaychain]\nchain_spec_path = \"missing.json\"\n".parse()?;
+		assert!(matches!(
+			docker_compose(&missing, "stable2407", temp_dir.path()),
+			Err(Error::Config(..))
+		));
+		Ok(())
+	}
+}
@@ -10,9 +10,6 @@ pub enum Error {
 	#[error("Failed to access the current directory")]
 	CurrentDirAccess,
 
-	#[error("Failed to locate the workspace")]
-	WorkspaceLocate,
-
 	#[error("Failed to create pallet directory")]
 	PalletDirCreation,
 
@@ -43,8 +40,8 @@ pub enum Error {
 	#[error("Anyhow error: {0}")]
 	AnyhowError(#[from] anyhow::Error),
 
-	#[error("User aborted due to existing target folder.")]
-	Aborted,
+	#[error("\"{0}\" already exists: remove it or choose another name")]
+	TargetExists(String),
 
 	#[error("Failed to execute rustfmt")]
 	RustfmtError(std::io::Error),
@@ -54,4 +51,19 @@ pub enum Error {
 
 	#[error("Failed to parse the endowment value")]
 	EndowmentError,
+
+	#[error("Extrinsic error: {0}")]
+	Extrinsic(String),
+
+	#[error("Signer error: {0}")]
+	Signer(String),
+
+	#[error("Metadata error: {0}")]
+	Metadata(String),
+
+	#[error("Failed to build: {0}")]
+	Build(String),
+
+	#[error("Failed to execute test command: {0}")]
+	TestCommand(String),
 }
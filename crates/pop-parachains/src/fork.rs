@@ -0,0 +1,170 @@
+// SPDX-License-Identifier: GPL-3.0
+use crate::errors::Error;
+use duct::cmd;
+use std::{
+	fs::File,
+	path::PathBuf,
+	process::{Child, Command, Stdio},
+};
+use url::Url;
+
+/// The package forking live chains, run using `npx`.
+const CHOPSTICKS: &str = "@acala-network/chopsticks";
+/// The release of Chopsticks used when none is specified.
+pub const CHOPSTICKS_DEFAULT_VERSION: &str = "latest";
+/// The port on which a fork serves RPC requests when none is specified.
+pub const DEFAULT_FORK_PORT: u16 = 8000;
+
+/// A local fork of a live chain, served by Chopsticks: blocks are only produced locally, while any
+/// state not yet modified locally is lazily fetched from the live chain.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Fork {
+	/// The endpoints of the live chain, tried in order.
+	pub endpoints: Vec<Url>,
+	/// The port on which the fork serves RPC requests.
+	pub port: u16,
+	/// The block from which the chain is forked, by number or hash. [default: the latest block]
+	pub block: Option<String>,
+	/// A runtime wasm replacing that of the live chain, to test an upgrade.
+	pub wasm_override: Option<PathBuf>,
+	/// A database persisting the state of the fork across launches.
+	pub db: Option<PathBuf>,
+	/// Whether any signature is accepted, so that extrinsics can be submitted on behalf of any
+	/// account.
+	pub mock_signatures: bool,
+	/// The release of Chopsticks, as per its npm version.
+	pub version: String,
+}
+
+impl Fork {
+	/// The arguments provided to `npx`.
+	pub fn args(&self) -> Vec<String> {
+		let mut args = vec!["--yes".into(), format!("{CHOPSTICKS}@{}", self.version)];
+		args.extend(self.endpoints.iter().map(|e| format!("--endpoint={e}")));
+		args.push(format!("--port={}", self.port));
+		if let Some(block) = &self.block {
+			args.push(format!("--block={block}"));
+		}
+		if let Some(wasm) = &self.wasm_override {
+			args.push(format!("--wasm-override={}", wasm.display()));
+		}
+		if let Some(db) = &self.db {
+			args.push(format!("--db={}", db.display()));
+		}
+		if self.mock_signatures {
+			args.push("--mock-signature-host=true".into());
+		}
+		args
+	}
+
+	/// The endpoint of the fork.
+	pub fn endpoint(&self) -> String {
+		format!("ws://127.0.0.1:{}", self.port)
+	}
+
+	/// Launches the fork, returning the process serving it.
+	///
+	/// # Arguments
+	///
+	/// * `log` - an optional file to which the output of the fork is written, otherwise discarded
+	pub fn run(&self, log: Option<&File>) -> Result<Child, Error> {
+		if self.endpoints.is_empty() {
+			return Err(Error::Config(
+				"at least one endpoint of the live chain is required".into(),
+			));
+		}
+		if let Some(wasm) = self.wasm_override.as_ref().filter(|w| !w.exists()) {
+			return Err(Error::Config(format!("the runtime {} does not exist", wasm.display())));
+		}
+		if cmd("npx", ["--version"]).stdout_null().stderr_null().run().is_err() {
+			return Err(Error::MissingBinary(
+				"npx, which is required to run Chopsticks: install Node.js from https://nodejs.org"
+					.into(),
+			));
+		}
+		let mut command = Command::new("npx");
+		command.args(self.args());
+		tracing::debug!(?command, "spawning fork");
+		match log {
+			Some(log) => command.stdout(log.try_clone()?).stderr(log.try_clone()?),
+			None => command.stdout(Stdio::null()).stderr(Stdio::null()),
+		};
+		Ok(command.spawn()?)
+	}
+
+	/// Whether the output of the fork reports that it is serving RPC requests.
+	///
+	/// # Arguments
+	///
+	/// * `output` - the output of the fork
+	pub fn is_ready(output: &str) -> bool {
+		output.contains("RPC listening on port")
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+
+	fn fork() -> Result<Fork> {
+		Ok(Fork {
+			endpoints: vec![Url::parse("wss://rpc.polkadot.io")?],
+			port: DEFAULT_FORK_PORT,
+			block: None,
+			wasm_override: None,
+			db: None,
+			mock_signatures: false,
+			version: CHOPSTICKS_DEFAULT_VERSION.into(),
+		})
+	}
+
+	#[test]
+	fn test_fork_args() -> Result<()> {
+		assert_eq!(
+			fork()?.args(),
+			[
+				"--yes",
+				"@acala-network/chopsticks@latest",
+				"--endpoint=wss://rpc.polkadot.io/",
+				"--port=8000"
+			]
+		);
+		let fork = Fork {
+			block: Some("21000000".into()),
+			wasm_override: Some(PathBuf::from("runtime.wasm")),
+			db: Some(PathBuf::from("db.sqlite")),
+			mock_signatures: true,
+			port: 8001,
+			..fork()?
+		};
+		assert_eq!(
+			&fork.args()[3..],
+			[
+				"--port=8001",
+				"--block=21000000",
+				"--wasm-override=runtime.wasm",
+				"--db=db.sqlite",
+				"--mock-signature-host=true"
+			]
+		);
+		assert_eq!(fork.endpoint(), "ws://127.0.0.1:8001");
+		Ok(())
+	}
+
+	#[test]
+	fn test_run_fails_with_invalid_config() -> Result<()> {
+		let without_endpoints = Fork { endpoints: vec![], ..fork()? };
+		assert!(matches!(without_endpoints.run(None), Err(Error::Config(..))));
+		let without_runtime =
+			Fork { wasm_override: Some(PathBuf::from("missing.wasm")), ..fork()? };
+		assert!(matches!(without_runtime.run(None), Err(Error::Config(..))));
+		Ok(())
+	}
+
+	#[test]
+	fn test_is_ready() {
+		assert!(Fork::is_ready("[10:00:00.000] INFO: Polkadot RPC listening on port 8000"));
+		assert!(!Fork::is_ready("[10:00:00.000] INFO: Loading config file"));
+	}
+}
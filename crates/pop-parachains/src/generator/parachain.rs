@@ -14,3 +14,25 @@ pub(crate) struct ChainSpec {
 pub(crate) struct Network {
 	pub(crate) node: String,
 }
+
+#[derive(Template)]
+#[template(path = "tests/network.toml.templ", escape = "none")]
+pub(crate) struct TestNetwork {
+	pub(crate) node: String,
+	pub(crate) para_id: u32,
+}
+
+#[derive(Template)]
+#[template(path = "tests/smoke.zndsl.templ", escape = "none")]
+pub(crate) struct SmokeTest {
+	pub(crate) node: String,
+	pub(crate) para_id: u32,
+}
+
+#[derive(Template)]
+#[template(path = "tests/transfer.js.templ", escape = "none")]
+pub(crate) struct TransferScript {}
+
+#[derive(Template)]
+#[template(path = "tests/run.sh.templ", escape = "none")]
+pub(crate) struct TestRunner {}
@@ -0,0 +1,225 @@
+// SPDX-License-Identifier: GPL-3.0
+use crate::errors::Error;
+use duct::cmd;
+use std::{ffi::OsString, path::Path};
+use strum_macros::{Display, EnumString};
+
+/// A session key, as inserted into the keystore of a node so that it can author blocks or
+/// otherwise take part in consensus.
+#[derive(Clone, Copy, Debug, Display, EnumString, PartialEq)]
+#[strum(serialize_all = "kebab-case")]
+pub enum SessionKey {
+	/// Authors the blocks of a parachain (or an Aura based chain).
+	Aura,
+	/// Authors the blocks of a relay chain.
+	Babe,
+	/// Finalizes the blocks of a relay chain.
+	Grandpa,
+	/// Validates the candidates of parachains.
+	ParaValidator,
+	/// Assigns validators to check the candidates of parachains.
+	ParaAssignment,
+	/// Discovers the other authorities of a relay chain.
+	AuthorityDiscovery,
+	/// Signs BEEFY commitments, used to bridge the relay chain.
+	Beefy,
+}
+
+impl SessionKey {
+	/// The session keys of a collator.
+	pub const COLLATOR: [SessionKey; 1] = [SessionKey::Aura];
+	/// The session keys of a relay chain validator.
+	pub const VALIDATOR: [SessionKey; 6] = [
+		SessionKey::Babe,
+		SessionKey::Grandpa,
+		SessionKey::ParaValidator,
+		SessionKey::ParaAssignment,
+		SessionKey::AuthorityDiscovery,
+		SessionKey::Beefy,
+	];
+
+	/// The identifier of the key type, as used by the keystore.
+	pub fn key_type(&self) -> &'static str {
+		match self {
+			SessionKey::Aura => "aura",
+			SessionKey::Babe => "babe",
+			SessionKey::Grandpa => "gran",
+			SessionKey::ParaValidator => "para",
+			SessionKey::ParaAssignment => "asgn",
+			SessionKey::AuthorityDiscovery => "audi",
+			SessionKey::Beefy => "beef",
+		}
+	}
+
+	/// The cryptographic scheme of the key.
+	pub fn scheme(&self) -> &'static str {
+		match self {
+			SessionKey::Grandpa => "Ed25519",
+			SessionKey::Beefy => "Ecdsa",
+			_ => "Sr25519",
+		}
+	}
+}
+
+/// Generates a network key using `node`, writing it to `file` so that a node launched with
+/// `--node-key-file` has a stable peer id. Returns the peer id of the key.
+///
+/// # Arguments
+///
+/// * `node` - path to the node binary
+/// * `file` - the file to which the key is written
+pub fn generate_node_key(node: &Path, file: &Path) -> Result<String, Error> {
+	// An existing key is never overwritten, as the peer id of the node would change.
+	if file.exists() {
+		return Err(Error::Config(format!("{} already exists", file.display())));
+	}
+	if let Some(parent) = file.parent() {
+		std::fs::create_dir_all(parent)?;
+	}
+	let args: Vec<OsString> =
+		vec!["key".into(), "generate-node-key".into(), "--file".into(), file.into()];
+	tracing::debug!(node = %node.display(), ?args, "generating node key");
+	// The peer id is written to stderr, leaving stdout for the key when no file is provided.
+	let output = cmd(node, args).stdout_null().stderr_capture().unchecked().run()?;
+	let stderr = String::from_utf8_lossy(&output.stderr);
+	if !output.status.success() {
+		return Err(Error::Config(format!("unable to generate a node key: {}", stderr.trim())));
+	}
+	peer_id(&stderr).ok_or(Error::Config("the node did not report a peer id".into()))
+}
+
+/// Inserts the session `keys` derived from `suri` into the `keystore` using `node`.
+///
+/// # Arguments
+///
+/// * `node` - path to the node binary
+/// * `keystore` - the keystore directory, as provided to a node with `--keystore-path`
+/// * `chain` - the chain spec of the node, if not its default
+/// * `suri` - the secret key URI from which the keys are derived
+/// * `keys` - the session keys to insert
+pub fn insert_session_keys(
+	node: &Path,
+	keystore: &Path,
+	chain: Option<&str>,
+	suri: &str,
+	keys: &[SessionKey],
+) -> Result<(), Error> {
+	for key in keys {
+		// The arguments are not logged, as they include the secret key URI.
+		tracing::debug!(node = %node.display(), key = %key, "inserting session key");
+		let output = cmd(node, insert_args(keystore, chain, suri, key))
+			.stdout_null()
+			.stderr_capture()
+			.unchecked()
+			.run()?;
+		if !output.status.success() {
+			return Err(Error::Config(format!(
+				"unable to insert the {key} key: {}",
+				String::from_utf8_lossy(&output.stderr).trim()
+			)));
+		}
+	}
+	Ok(())
+}
+
+/// The arguments inserting `key` into the `keystore` of a node.
+fn insert_args(
+	keystore: &Path,
+	chain: Option<&str>,
+	suri: &str,
+	key: &SessionKey,
+) -> Vec<OsString> {
+	let mut args: Vec<OsString> =
+		vec!["key".into(), "insert".into(), "--keystore-path".into(), keystore.into()];
+	if let Some(chain) = chain {
+		args.extend(["--chain".into(), chain.into()]);
+	}
+	args.extend([
+		"--scheme".into(),
+		key.scheme().into(),
+		"--suri".into(),
+		suri.into(),
+		"--key-type".into(),
+		key.key_type().into(),
+	]);
+	args
+}
+
+/// The peer id reported by the node, being the last non-empty line of its output.
+fn peer_id(output: &str) -> Option<String> {
+	output
+		.lines()
+		.map(|l| l.trim())
+		.filter(|l| !l.is_empty())
+		.last()
+		.map(|l| l.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+	use std::{fs, str::FromStr};
+
+	#[test]
+	fn test_session_key() -> Result<()> {
+		assert_eq!(SessionKey::from_str("aura")?, SessionKey::Aura);
+		assert_eq!(SessionKey::from_str("para-validator")?, SessionKey::ParaValidator);
+		assert_eq!(SessionKey::Grandpa.to_string(), "grandpa");
+		assert_eq!(
+			SessionKey::VALIDATOR.iter().map(|k| k.key_type()).collect::<Vec<_>>(),
+			vec!["babe", "gran", "para", "asgn", "audi", "beef"]
+		);
+		assert_eq!(SessionKey::Aura.scheme(), "Sr25519");
+		assert_eq!(SessionKey::Grandpa.scheme(), "Ed25519");
+		assert_eq!(SessionKey::Beefy.scheme(), "Ecdsa");
+		Ok(())
+	}
+
+	#[test]
+	fn test_insert_args() {
+		let args = insert_args(Path::new("keys"), Some("dev"), "//Alice", &SessionKey::Aura);
+		assert_eq!(
+			args,
+			[
+				"key",
+				"insert",
+				"--keystore-path",
+				"keys",
+				"--chain",
+				"dev",
+				"--scheme",
+				"Sr25519",
+				"--suri",
+				"//Alice",
+				"--key-type",
+				"aura"
+			]
+			.map(OsString::from)
+		);
+		let args = insert_args(Path::new("keys"), None, "//Bob", &SessionKey::Grandpa);
+		assert!(!args.contains(&"--chain".into()));
+		assert!(args.contains(&"Ed25519".into()));
+	}
+
+	#[test]
+	fn test_peer_id() {
+		assert_eq!(
+			peer_id("12D3KooWBmAwcd4PJNJvfV89HwE48nwkRmAgo8Vy3uQEyNNHBox2\n"),
+			Some("12D3KooWBmAwcd4PJNJvfV89HwE48nwkRmAgo8Vy3uQEyNNHBox2".into())
+		);
+		assert_eq!(peer_id("\n"), None);
+	}
+
+	#[test]
+	fn test_generate_node_key_does_not_overwrite() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let file = temp_dir.path().join("node-key");
+		fs::write(&file, "")?;
+		assert!(matches!(
+			generate_node_key(Path::new("missing-node"), &file),
+			Err(Error::Config(..))
+		));
+		Ok(())
+	}
+}
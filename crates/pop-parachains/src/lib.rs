@@ -1,20 +1,96 @@
 // SPDX-License-Identifier: GPL-3.0
+mod bench;
+mod binaries;
 mod build;
+mod call;
+mod chain_spec;
+mod compose;
 mod errors;
+mod fork;
 mod generator;
+mod keys;
+mod logs;
+mod monitoring;
+mod network;
 mod new_pallet;
 mod new_parachain;
+mod new_test;
+mod onboard;
+mod ports;
+mod readiness;
+mod relay;
+mod runtime_diff;
+mod runtime_upgrade;
+mod srtool;
+mod storage;
 mod templates;
+mod test;
+mod try_runtime;
 mod up;
 mod utils;
+mod wallet;
+mod watch;
+mod xcm;
 
-pub use build::build_parachain;
+pub use bench::{benchmark_runtime, weights_path, PalletBenchmark, OMNI_BENCHER_DEFAULT_VERSION};
+pub use binaries::PolkadotRelease;
+pub use build::{
+	build_parachain, build_parachain_profile, build_runtime, export_artifacts, is_parachain,
+	node_binary, runtime_crate, ParachainArtifacts, Profile,
+};
+pub use call::{
+	construct_call, construct_sudo_call, metadata, pallets, parse_value, submit_call,
+	submit_sudo_call, Call, Pallet, Param, Submitted,
+};
+pub use chain_spec::{
+	export_genesis, generate_chain_spec, parse_genesis_override, ChainSpec, ChainSpecOverrides,
+	GenesisArtifacts,
+};
+pub use errors::Error;
+pub use fork::{Fork, CHOPSTICKS_DEFAULT_VERSION, DEFAULT_FORK_PORT};
+pub use keys::{generate_node_key, insert_session_keys, SessionKey};
+pub use logs::{tail, LogFilter, LogLevel};
+pub use monitoring::{MetricsTarget, Monitoring, GRAFANA_PORT, PROMETHEUS_PORT};
+pub use network::{configure_node, NetworkConfig, Node, NodeEntry, Parachain, RelayChain};
 pub use new_pallet::{create_pallet_template, TemplatePalletConfig};
 pub use new_parachain::instantiate_template_dir;
+pub use new_test::create_network_test;
+pub use onboard::{
+	account_address, free_balance, para_lifecycle, place_on_demand_order, register_genesis,
+	wait_for_onboarding, wait_for_para_block, Testnet,
+};
+pub use ports::{parse_rpc_port, DEFAULT_RPC_PORT};
+pub use readiness::{wait_until_ready, ChainStatus, Readiness};
+pub use relay::{
+	open_hrmp_channels, register_parachain, reserve_para_id, HrmpChannel,
+	DEFAULT_HRMP_MAX_CAPACITY, DEFAULT_HRMP_MAX_MESSAGE_SIZE,
+};
+pub use runtime_diff::{
+	diff_runtimes, Change, ItemKind, RuntimeDifference, RuntimeSource, RuntimeSummary,
+};
+pub use runtime_upgrade::{
+	read_runtime, spec_version, upgrade_runtime, wait_for_code, wait_for_spec_version,
+	UpgradeMethod,
+};
+pub use srtool::{build_deterministic_runtime, DeterministicArtifacts, SRTOOL_DEFAULT_TAG};
+pub use storage::{query_storage, StorageItem, StoragePage};
 pub use templates::{Config, Provider, Template};
-pub use up::{Source, Status, Zombienet};
-pub use utils::git::{Git, GitHub, Release};
+pub use test::{test_parachain, CrateReport, TestOptions, TestReport};
+pub use try_runtime::{
+	try_runtime, try_runtime_runtime, OnRuntimeUpgrade, UpgradeChecks, UpgradeReport,
+	TRY_RUNTIME_DEFAULT_VERSION,
+};
+pub use up::{
+	parse_parachain_image, Images, LocalNetwork, NetworkProvider, SystemChain, Zombienet,
+};
 pub use utils::helpers::is_initial_endowment_valid;
 pub use utils::pallet_helpers::resolve_pallet_path;
+pub use wallet::{balance, transfer, Balance, Token};
+pub use watch::SourceWatcher;
+pub use xcm::{
+	construct_xcm_transfer, submit_xcm_transfer, wait_for_delivery, Chain, Delivery,
+	NetworkEndpoints, TransferKind, XcmTransfer,
+};
 // External exports
+pub use pop_common::{Git, GitHub, Release, Source, Status};
 pub use zombienet_sdk::NetworkNode;
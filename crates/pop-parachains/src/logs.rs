@@ -0,0 +1,129 @@
+// SPDX-License-Identifier: GPL-3.0
+use std::{fs, path::Path};
+use strum_macros::{Display, EnumString};
+
+/// The level of a line logged by a node, ordered from the most to the least severe.
+#[derive(Clone, Copy, Debug, Display, EnumString, Eq, Ord, PartialEq, PartialOrd)]
+#[strum(ascii_case_insensitive, serialize_all = "lowercase")]
+pub enum LogLevel {
+	/// Errors.
+	Error,
+	/// Warnings.
+	Warn,
+	/// Informational messages.
+	Info,
+	/// Debugging messages.
+	Debug,
+	/// Tracing messages.
+	Trace,
+}
+
+impl LogLevel {
+	/// The level of a line logged by a node, if any. Nodes log each entry as the date, the time
+	/// and then the level (e.g. `2024-06-01 10:00:00.000  INFO main sc_cli::runner: ...`), while
+	/// any continuation of an entry has no level.
+	///
+	/// # Arguments
+	///
+	/// * `line` - a line logged by a node
+	pub fn of(line: &str) -> Option<Self> {
+		let level = line.split_whitespace().nth(2)?;
+		match level {
+			"ERROR" => Some(Self::Error),
+			"WARN" => Some(Self::Warn),
+			"INFO" => Some(Self::Info),
+			"DEBUG" => Some(Self::Debug),
+			"TRACE" => Some(Self::Trace),
+			_ => None,
+		}
+	}
+}
+
+/// Filters the lines logged by a node by level, where a line without a level continues the
+/// preceding entry and is therefore only kept along with it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LogFilter {
+	level: LogLevel,
+	keep: bool,
+}
+
+impl LogFilter {
+	/// A filter keeping entries at `level` or any more severe level.
+	///
+	/// # Arguments
+	///
+	/// * `level` - the least severe level kept
+	pub fn new(level: LogLevel) -> Self {
+		Self { level, keep: true }
+	}
+
+	/// Whether a line is kept, given the lines preceding it.
+	///
+	/// # Arguments
+	///
+	/// * `line` - the next line logged by the node
+	pub fn keep(&mut self, line: &str) -> bool {
+		if let Some(level) = LogLevel::of(line) {
+			self.keep = level <= self.level;
+		}
+		self.keep
+	}
+}
+
+/// The last `lines` lines logged to the file at `path`, as shown when a network fails to become
+/// ready.
+///
+/// # Arguments
+///
+/// * `path` - the log file
+/// * `lines` - the number of lines returned
+pub fn tail(path: &Path, lines: usize) -> std::io::Result<Vec<String>> {
+	let contents = fs::read_to_string(path)?;
+	let all: Vec<_> = contents.lines().collect();
+	Ok(all[all.len().saturating_sub(lines)..].iter().map(|l| l.to_string()).collect())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+	use std::str::FromStr;
+
+	const INFO: &str = "2024-06-01 10:00:00.000  INFO main sc_cli::runner: Parity Polkadot";
+	const WARN: &str =
+		"2024-06-01 10:00:01.000  WARN tokio-runtime-worker sc_service: Using default protocol ID";
+	const DEBUG: &str = "2024-06-01 10:00:02.000 DEBUG tokio-runtime-worker sync: Propagating";
+
+	#[test]
+	fn test_log_level() -> Result<()> {
+		assert_eq!(LogLevel::of(INFO), Some(LogLevel::Info));
+		assert_eq!(LogLevel::of(WARN), Some(LogLevel::Warn));
+		assert_eq!(LogLevel::of(DEBUG), Some(LogLevel::Debug));
+		assert_eq!(LogLevel::of("  continued"), None);
+		assert_eq!(LogLevel::from_str("WARN")?, LogLevel::Warn);
+		assert!(LogLevel::Error < LogLevel::Trace);
+		Ok(())
+	}
+
+	#[test]
+	fn test_log_filter() {
+		let mut filter = LogFilter::new(LogLevel::Warn);
+		assert!(filter.keep("====================="));
+		assert!(!filter.keep(INFO));
+		assert!(!filter.keep("  continuation of the info entry"));
+		assert!(filter.keep(WARN));
+		assert!(filter.keep("  continuation of the warning"));
+		assert!(!filter.keep(DEBUG));
+	}
+
+	#[test]
+	fn test_tail() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let log = temp_dir.path().join("alice.log");
+		std::fs::write(&log, format!("{INFO}\n{WARN}\n{DEBUG}\n"))?;
+		assert_eq!(tail(&log, 2)?, [WARN, DEBUG]);
+		assert_eq!(tail(&log, 5)?, [INFO, WARN, DEBUG]);
+		assert!(tail(&temp_dir.path().join("bob.log"), 5).is_err());
+		Ok(())
+	}
+}
@@ -0,0 +1,327 @@
+// SPDX-License-Identifier: GPL-3.0
+use crate::errors::Error;
+use duct::cmd;
+use serde_json::{json, Value};
+use std::{
+	fs::{create_dir_all, write},
+	path::{Path, PathBuf},
+};
+use toml_edit::DocumentMut;
+
+/// The first port assigned to the Prometheus endpoint of a node without an explicit port.
+pub(crate) const DEFAULT_PROMETHEUS_PORT: u16 = 9615;
+/// The port on which Prometheus is served.
+pub const PROMETHEUS_PORT: u16 = 9090;
+/// The port on which Grafana is served.
+pub const GRAFANA_PORT: u16 = 3000;
+const PROMETHEUS_IMAGE: &str = "docker.io/prom/prometheus:latest";
+const GRAFANA_IMAGE: &str = "docker.io/grafana/grafana:latest";
+const PROMETHEUS_CONTAINER: &str = "pop-prometheus";
+const GRAFANA_CONTAINER: &str = "pop-grafana";
+/// The identifier of the provisioned dashboard.
+const DASHBOARD: &str = "pop-network";
+/// The host name under which containers reach the host, and therefore the nodes of the network.
+const HOST: &str = "host.docker.internal";
+
+/// A node of a network whose Prometheus metrics are scraped.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MetricsTarget {
+	/// The name of the node.
+	pub node: String,
+	/// The chain of the node: `relay` for the relay chain, otherwise `para-<id>`.
+	pub chain: String,
+	/// The port of the Prometheus endpoint of the node.
+	pub port: u16,
+}
+
+/// The nodes of a network, as configured for Zombienet, which expose their Prometheus metrics on
+/// an explicit port.
+///
+/// # Arguments
+///
+/// * `config` - the network configuration, as expected by Zombienet
+pub(crate) fn metrics_targets(config: &DocumentMut) -> Vec<MetricsTarget> {
+	let target = |chain: String| {
+		move |node: &toml_edit::Table| {
+			Some(MetricsTarget {
+				node: node.get("name")?.as_str()?.into(),
+				chain: chain.clone(),
+				port: u16::try_from(node.get("prometheus_port")?.as_integer()?).ok()?,
+			})
+		}
+	};
+	let mut targets: Vec<_> = config
+		.get("relaychain")
+		.and_then(|r| r.get("nodes"))
+		.and_then(|n| n.as_array_of_tables())
+		.into_iter()
+		.flat_map(|n| n.iter())
+		.filter_map(target("relay".into()))
+		.collect();
+	for parachain in config
+		.get("parachains")
+		.and_then(|p| p.as_array_of_tables())
+		.into_iter()
+		.flat_map(|p| p.iter())
+	{
+		let Some(id) = parachain.get("id").and_then(|i| i.as_integer()) else { continue };
+		targets.extend(
+			parachain
+				.get("collators")
+				.and_then(|c| c.as_array_of_tables())
+				.into_iter()
+				.flat_map(|c| c.iter())
+				.filter_map(target(format!("para-{id}"))),
+		);
+	}
+	targets
+}
+
+/// A Prometheus and Grafana stack, run within containers, scraping the metrics of the nodes of a
+/// network and provisioned with a dashboard of block production, finality and collation.
+pub struct Monitoring {
+	/// The directory to which the configuration of the stack is written.
+	dir: PathBuf,
+	/// The nodes whose metrics are scraped.
+	targets: Vec<MetricsTarget>,
+}
+
+impl Monitoring {
+	/// A stack scraping the metrics of `targets`, configured within `dir`.
+	///
+	/// # Arguments
+	///
+	/// * `dir` - the directory to which the configuration of the stack is written
+	/// * `targets` - the nodes whose metrics are scraped
+	pub fn new(dir: &Path, targets: Vec<MetricsTarget>) -> Self {
+		Self { dir: dir.to_path_buf(), targets }
+	}
+
+	/// The URL of the provisioned dashboard.
+	pub fn dashboard_url(&self) -> String {
+		format!("http://localhost:{GRAFANA_PORT}/d/{DASHBOARD}")
+	}
+
+	/// The URL of Prometheus.
+	pub fn prometheus_url(&self) -> String {
+		format!("http://localhost:{PROMETHEUS_PORT}")
+	}
+
+	/// Writes the configuration of the stack and launches Prometheus and Grafana, replacing any
+	/// containers remaining from a previous launch.
+	pub fn launch(&self) -> Result<(), Error> {
+		if cmd("docker", ["version"]).stdout_null().stderr_null().run().is_err() {
+			return Err(Error::MissingBinary(
+				"docker, which is required to run Prometheus and Grafana: install it from \
+				 https://docs.docker.com/get-docker"
+					.into(),
+			));
+		}
+		self.write()?;
+		self.stop()?;
+		let prometheus = self.dir.join("prometheus.yml");
+		let grafana = self.dir.join("grafana");
+		let mut prometheus_args = run_args(PROMETHEUS_CONTAINER, PROMETHEUS_PORT, 9090);
+		prometheus_args.extend([
+			format!("--volume={}:/etc/prometheus/prometheus.yml:ro", prometheus.display()),
+			PROMETHEUS_IMAGE.into(),
+		]);
+		let mut grafana_args = run_args(GRAFANA_CONTAINER, GRAFANA_PORT, 3000);
+		grafana_args.extend([
+			format!("--volume={}:/etc/grafana/provisioning:ro", grafana.display()),
+			"--env=GF_AUTH_ANONYMOUS_ENABLED=true".into(),
+			"--env=GF_AUTH_ANONYMOUS_ORG_ROLE=Admin".into(),
+			format!("--env=GF_DASHBOARDS_DEFAULT_HOME_DASHBOARD_PATH={}", dashboard_path()),
+			GRAFANA_IMAGE.into(),
+		]);
+		for args in [prometheus_args, grafana_args] {
+			tracing::debug!(?args, "launching container");
+			let output = cmd("docker", args).stdout_null().stderr_capture().unchecked().run()?;
+			if !output.status.success() {
+				return Err(Error::Config(format!(
+					"unable to launch the monitoring stack: {}",
+					String::from_utf8_lossy(&output.stderr).trim()
+				)));
+			}
+		}
+		Ok(())
+	}
+
+	/// Stops Prometheus and Grafana, if running.
+	pub fn stop(&self) -> Result<(), Error> {
+		cmd("docker", ["rm", "--force", PROMETHEUS_CONTAINER, GRAFANA_CONTAINER])
+			.stdout_null()
+			.stderr_null()
+			.unchecked()
+			.run()?;
+		Ok(())
+	}
+
+	/// Writes the configuration of Prometheus, along with the provisioned data source and
+	/// dashboard of Grafana.
+	fn write(&self) -> Result<(), Error> {
+		let grafana = self.dir.join("grafana");
+		let dashboards = grafana.join("dashboards");
+		let datasources = grafana.join("datasources");
+		for dir in [&dashboards, &datasources] {
+			create_dir_all(dir)?;
+		}
+		write(self.dir.join("prometheus.yml"), prometheus_config(&self.targets))?;
+		write(datasources.join("prometheus.yml"), datasource())?;
+		write(dashboards.join("dashboards.yml"), dashboard_provider())?;
+		write(dashboards.join(format!("{DASHBOARD}.json")), format!("{:#}", dashboard()))?;
+		Ok(())
+	}
+}
+
+/// The arguments launching a detached container named `name`, publishing its `container_port` as
+/// `port` of the host.
+fn run_args(name: &str, port: u16, container_port: u16) -> Vec<String> {
+	vec![
+		"run".into(),
+		"--detach".into(),
+		"--rm".into(),
+		format!("--name={name}"),
+		format!("--add-host={HOST}:host-gateway"),
+		format!("--publish={port}:{container_port}"),
+	]
+}
+
+/// The path of the provisioned dashboard within the Grafana container.
+fn dashboard_path() -> String {
+	format!("/etc/grafana/provisioning/dashboards/{DASHBOARD}.json")
+}
+
+/// The configuration of Prometheus, scraping each target labelled by its node and chain.
+fn prometheus_config(targets: &[MetricsTarget]) -> String {
+	let mut config =
+		"global:\n  scrape_interval: 5s\n\nscrape_configs:\n  - job_name: \"nodes\"\n    \
+	                  static_configs:\n"
+			.to_string();
+	for target in targets {
+		config.push_str(&format!(
+			"      - targets: [\"{HOST}:{}\"]\n        labels:\n          node: \"{}\"\n          \
+			 chain: \"{}\"\n",
+			target.port, target.node, target.chain
+		));
+	}
+	config
+}
+
+/// The provisioned Prometheus data source of Grafana.
+fn datasource() -> String {
+	format!(
+		"apiVersion: 1\n\ndatasources:\n  - name: Prometheus\n    uid: prometheus\n    \
+		 type: prometheus\n    access: proxy\n    url: http://{HOST}:{PROMETHEUS_PORT}\n    \
+		 isDefault: true\n"
+	)
+}
+
+/// The provider of the provisioned dashboards of Grafana.
+fn dashboard_provider() -> String {
+	"apiVersion: 1\n\nproviders:\n  - name: pop\n    type: file\n    options:\n      \
+	 path: /etc/grafana/provisioning/dashboards\n"
+		.into()
+}
+
+/// The dashboard of the network, charting block production, finality and collation.
+fn dashboard() -> Value {
+	let panels = [
+		("Best block", "substrate_block_height{status=\"best\"}", "{{node}}"),
+		("Finalized block", "substrate_block_height{status=\"finalized\"}", "{{node}}"),
+		(
+			"Finality lag",
+			"substrate_block_height{status=\"best\"} - ignoring(status) \
+			 substrate_block_height{status=\"finalized\"}",
+			"{{node}}",
+		),
+		(
+			"Parachain blocks per minute",
+			"increase(substrate_block_height{status=\"best\", chain=~\"para-.*\"}[1m])",
+			"{{chain}} {{node}}",
+		),
+		("Peers", "substrate_sub_libp2p_peers_count", "{{node}}"),
+		("Ready transactions", "substrate_ready_transactions_number", "{{node}}"),
+	];
+	let panels: Vec<_> = panels
+		.iter()
+		.enumerate()
+		.map(|(i, (title, expr, legend))| {
+			json!({
+				"id": i + 1,
+				"type": "timeseries",
+				"title": title,
+				"gridPos": { "h": 8, "w": 12, "x": (i % 2) * 12, "y": (i / 2) * 8 },
+				"datasource": { "type": "prometheus", "uid": "prometheus" },
+				"targets": [{ "expr": expr, "legendFormat": legend, "refId": "A" }],
+			})
+		})
+		.collect();
+	json!({
+		"uid": DASHBOARD,
+		"title": "Pop Network",
+		"schemaVersion": 39,
+		"refresh": "5s",
+		"time": { "from": "now-15m", "to": "now" },
+		"panels": panels,
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+
+	const NETWORK: &str = r#"[relaychain]
+chain = "rococo-local"
+
+[[relaychain.nodes]]
+name = "alice"
+prometheus_port = 9615
+
+[[relaychain.nodes]]
+name = "bob"
+
+[[parachains]]
+id = 2000
+
+[[parachains.collators]]
+name = "collator-01"
+prometheus_port = 9616
+"#;
+
+	#[test]
+	fn test_metrics_targets() -> Result<()> {
+		let config: DocumentMut = NETWORK.parse()?;
+		assert_eq!(
+			metrics_targets(&config),
+			[
+				MetricsTarget { node: "alice".into(), chain: "relay".into(), port: 9615 },
+				MetricsTarget { node: "collator-01".into(), chain: "para-2000".into(), port: 9616 },
+			]
+		);
+		Ok(())
+	}
+
+	#[test]
+	fn test_write() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let targets = metrics_targets(&NETWORK.parse()?);
+		let monitoring = Monitoring::new(temp_dir.path(), targets);
+		monitoring.write()?;
+
+		let prometheus = std::fs::read_to_string(temp_dir.path().join("prometheus.yml"))?;
+		assert!(prometheus.contains("- targets: [\"host.docker.internal:9616\"]"));
+		assert!(prometheus.contains("chain: \"para-2000\""));
+		let grafana = temp_dir.path().join("grafana");
+		let datasource = std::fs::read_to_string(grafana.join("datasources/prometheus.yml"))?;
+		assert!(datasource.contains("url: http://host.docker.internal:9090"));
+		let dashboard: Value = serde_json::from_str(&std::fs::read_to_string(
+			grafana.join("dashboards/pop-network.json"),
+		)?)?;
+		assert_eq!(dashboard["uid"], DASHBOARD);
+		assert_eq!(dashboard["panels"].as_array().map(|p| p.len()), Some(6));
+		assert_eq!(monitoring.dashboard_url(), "http://localhost:3000/d/pop-network");
+		Ok(())
+	}
+}
@@ -0,0 +1,385 @@
+// SPDX-License-Identifier: GPL-3.0
+use crate::errors::Error;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use toml_edit::{
+	value, Array, ArrayOfTables, DocumentMut, InlineTable, Item, Table, TableLike, Value,
+};
+
+/// The relay chain of a network when none is specified.
+const DEFAULT_RELAY_CHAIN: &str = "rococo-local";
+
+/// Parses a network configuration, either in the format expected by Zombienet or in pop's native
+/// format, returning the configuration in the format expected by Zombienet.
+///
+/// # Arguments
+///
+/// * `content` - the contents of the configuration file
+pub(crate) fn parse(content: &str) -> Result<DocumentMut, Error> {
+	let config = content.parse::<DocumentMut>().map_err(|e| Error::Config(format!("{e}")))?;
+	// Pop's native format describes the relay chain as `relay`, rather than `relaychain`.
+	if !config.contains_key("relay") {
+		return Ok(config);
+	}
+	let network: NetworkConfig = toml_edit::de::from_str(content)?;
+	Ok(network.zombienet())
+}
+
+/// Sets `args` on the node named `name` within a network configuration, in either format,
+/// replacing any of its existing arguments setting the same flags. Returns the updated
+/// configuration.
+///
+/// # Arguments
+///
+/// * `content` - the contents of the configuration file
+/// * `name` - the name of the node
+/// * `args` - the arguments of the node (e.g. `--node-key-file=<path>`)
+pub fn configure_node(content: &str, name: &str, args: &[String]) -> Result<String, Error> {
+	let mut config = content.parse::<DocumentMut>().map_err(|e| Error::Config(format!("{e}")))?;
+	let mut found = false;
+	// Pop's native format describes the validators of the relay chain as `relay.validators`.
+	let (relay, validators) = if config.contains_key("relay") {
+		("relay", "validators")
+	} else {
+		("relaychain", "nodes")
+	};
+	if let Some(nodes) = config.get_mut(relay).and_then(|r| r.get_mut(validators)) {
+		found |= configure_nodes(nodes, name, args);
+	}
+	if let Some(parachains) = config.get_mut("parachains").and_then(|p| p.as_array_of_tables_mut())
+	{
+		for parachain in parachains.iter_mut() {
+			for collators in ["collators", "collator"] {
+				if let Some(nodes) = parachain.get_mut(collators) {
+					found |= configure_nodes(nodes, name, args);
+				}
+			}
+		}
+	}
+	if !found {
+		return Err(Error::Config(format!("the network has no node named `{name}`")));
+	}
+	Ok(config.to_string())
+}
+
+/// Sets `args` on the node named `name` within `nodes`: either an array of tables, a single table
+/// or an array of names and inline tables. Returns whether the node was found.
+fn configure_nodes(nodes: &mut Item, name: &str, args: &[String]) -> bool {
+	match nodes {
+		Item::ArrayOfTables(nodes) => {
+			nodes.iter_mut().any(|node| configure_table(node, name, args))
+		},
+		Item::Table(node) => configure_table(node, name, args),
+		Item::Value(Value::Array(nodes)) => nodes.iter_mut().any(|node| {
+			// A node identified by its name alone is described in full to hold its arguments.
+			if node.as_str() == Some(name) {
+				let mut table = InlineTable::new();
+				table.insert("name", name.into());
+				*node = Value::InlineTable(table);
+			}
+			node.as_inline_table_mut().is_some_and(|node| configure_table(node, name, args))
+		}),
+		_ => false,
+	}
+}
+
+fn configure_table(node: &mut dyn TableLike, name: &str, args: &[String]) -> bool {
+	if node.get("name").and_then(|n| n.as_str()) != Some(name) {
+		return false;
+	}
+	let flag = |arg: &str| arg.split_once('=').map_or(arg, |(flag, _)| flag).to_string();
+	let flags: Vec<String> = args.iter().map(|a| flag(a)).collect();
+	let mut merged: Vec<String> = node
+		.get("args")
+		.and_then(|a| a.as_array())
+		.map(|a| a.iter().filter_map(|a| a.as_str().map(|a| a.to_string())).collect())
+		.unwrap_or_default();
+	merged.retain(|arg| !flags.contains(&flag(arg)));
+	merged.extend(args.iter().cloned());
+	node.insert("args", value(array(&merged)));
+	true
+}
+
+/// A network described in pop's native format, e.g.:
+///
+/// ```toml
+/// [relay]
+/// chain = "rococo-local"
+/// validators = ["alice", { name = "bob", rpc_port = 9945 }]
+///
+/// [[parachains]]
+/// id = 2000
+/// command = "./target/release/parachain-template-node"
+///
+/// [[parachains.collators]]
+/// name = "collator-01"
+/// rpc_port = 9944
+/// args = ["-lruntime=debug"]
+/// env = { RUST_LOG = "info" }
+/// ```
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct NetworkConfig {
+	/// The relay chain of the network.
+	pub relay: RelayChain,
+	/// The parachains of the network.
+	#[serde(default)]
+	pub parachains: Vec<Parachain>,
+}
+
+/// The relay chain of a network.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct RelayChain {
+	/// The chain spec of the relay chain.
+	#[serde(default = "default_relay_chain")]
+	pub chain: String,
+	/// The arguments provided to every validator.
+	#[serde(default)]
+	pub args: Vec<String>,
+	/// The validators of the relay chain, either by name or as a node.
+	pub validators: Vec<NodeEntry>,
+}
+
+/// A parachain of a network.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Parachain {
+	/// The identifier of the parachain.
+	pub id: u32,
+	/// The command launching the collators, relative to the configuration file.
+	pub command: Option<String>,
+	/// The chain spec of the parachain.
+	pub chain: Option<String>,
+	/// The arguments provided to every collator.
+	#[serde(default)]
+	pub args: Vec<String>,
+	/// The collators of the parachain, either by name or as a node.
+	pub collators: Vec<NodeEntry>,
+}
+
+/// A node of a network, identified by its name alone or fully described.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(untagged)]
+pub enum NodeEntry {
+	/// A node with default settings.
+	Name(String),
+	/// A node with custom settings.
+	Node(Node),
+}
+
+/// A node of a network.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Node {
+	/// The name of the node.
+	pub name: String,
+	/// The command launching the node, overriding the command of its chain.
+	pub command: Option<String>,
+	/// The port of the RPC server of the node.
+	pub rpc_port: Option<u16>,
+	/// The peer-to-peer port of the node.
+	pub p2p_port: Option<u16>,
+	/// The port of the Prometheus server of the node.
+	pub prometheus_port: Option<u16>,
+	/// The arguments provided to the node.
+	#[serde(default)]
+	pub args: Vec<String>,
+	/// The environment variables of the node.
+	#[serde(default)]
+	pub env: BTreeMap<String, String>,
+}
+
+fn default_relay_chain() -> String {
+	DEFAULT_RELAY_CHAIN.into()
+}
+
+impl NodeEntry {
+	fn node(&self) -> Node {
+		match self {
+			NodeEntry::Name(name) => Node { name: name.clone(), ..Default::default() },
+			NodeEntry::Node(node) => node.clone(),
+		}
+	}
+}
+
+impl NetworkConfig {
+	/// The network configuration, as expected by Zombienet.
+	pub fn zombienet(&self) -> DocumentMut {
+		let mut config = DocumentMut::new();
+		let mut relay_chain = Table::new();
+		relay_chain.insert("chain", value(self.relay.chain.as_str()));
+		if !self.relay.args.is_empty() {
+			relay_chain.insert("default_args", value(array(&self.relay.args)));
+		}
+		let mut validators = ArrayOfTables::new();
+		for validator in &self.relay.validators {
+			let mut validator = validator.node().zombienet();
+			validator.insert("validator", value(true));
+			validators.push(validator);
+		}
+		relay_chain.insert("nodes", Item::ArrayOfTables(validators));
+		config.insert("relaychain", Item::Table(relay_chain));
+
+		if !self.parachains.is_empty() {
+			let mut parachains = ArrayOfTables::new();
+			for parachain in &self.parachains {
+				let mut table = Table::new();
+				table.insert("id", value(parachain.id as i64));
+				if let Some(command) = &parachain.command {
+					table.insert("default_command", value(command.as_str()));
+				}
+				if let Some(chain) = &parachain.chain {
+					table.insert("chain", value(chain.as_str()));
+				}
+				if !parachain.args.is_empty() {
+					table.insert("default_args", value(array(&parachain.args)));
+				}
+				let mut collators = ArrayOfTables::new();
+				for collator in &parachain.collators {
+					collators.push(collator.node().zombienet());
+				}
+				table.insert("collators", Item::ArrayOfTables(collators));
+				parachains.push(table);
+			}
+			config.insert("parachains", Item::ArrayOfTables(parachains));
+		}
+		config
+	}
+}
+
+impl Node {
+	/// The node configuration, as expected by Zombienet.
+	fn zombienet(&self) -> Table {
+		let mut node = Table::new();
+		node.insert("name", value(self.name.as_str()));
+		if let Some(command) = &self.command {
+			node.insert("command", value(command.as_str()));
+		}
+		for (key, port) in [
+			("rpc_port", self.rpc_port),
+			("p2p_port", self.p2p_port),
+			("prometheus_port", self.prometheus_port),
+		] {
+			if let Some(port) = port {
+				node.insert(key, value(port as i64));
+			}
+		}
+		if !self.args.is_empty() {
+			node.insert("args", value(array(&self.args)));
+		}
+		if !self.env.is_empty() {
+			// Zombienet expects environment variables as a list of names and values.
+			let mut env = Array::new();
+			for (name, val) in &self.env {
+				let mut var = InlineTable::new();
+				var.insert("name", name.as_str().into());
+				var.insert("value", val.as_str().into());
+				env.push(var);
+			}
+			node.insert("env", value(env));
+		}
+		node
+	}
+}
+
+fn array(values: &[String]) -> Array {
+	values.iter().map(|v| v.as_str()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const NETWORK: &str = r#"
+		[relay]
+		validators = ["alice", { name = "bob", rpc_port = 9945, args = ["-lparachain=debug"] }]
+
+		[[parachains]]
+		id = 2000
+		command = "./target/release/parachain-template-node"
+		args = ["--pool-limit=100"]
+
+		[[parachains.collators]]
+		name = "collator-01"
+		rpc_port = 9944
+		p2p_port = 30333
+		env = { RUST_LOG = "info" }
+
+		[[parachains]]
+		id = 1000
+		chain = "asset-hub-rococo-local"
+		collators = ["asset-hub"]
+	"#;
+
+	#[test]
+	fn test_parse_native_config() -> Result<(), Error> {
+		let config = parse(NETWORK)?;
+		let relay_chain = &config["relaychain"];
+		assert_eq!(relay_chain["chain"].as_str(), Some(DEFAULT_RELAY_CHAIN));
+		assert_eq!(relay_chain["nodes"][0]["name"].as_str(), Some("alice"));
+		assert_eq!(relay_chain["nodes"][0]["validator"].as_bool(), Some(true));
+		assert_eq!(relay_chain["nodes"][1]["rpc_port"].as_integer(), Some(9945));
+		assert_eq!(relay_chain["nodes"][1]["args"][0].as_str(), Some("-lparachain=debug"));
+
+		let parachain = &config["parachains"][0];
+		assert_eq!(parachain["id"].as_integer(), Some(2000));
+		assert_eq!(
+			parachain["default_command"].as_str(),
+			Some("./target/release/parachain-template-node")
+		);
+		assert_eq!(parachain["default_args"][0].as_str(), Some("--pool-limit=100"));
+		let collator = &parachain["collators"][0];
+		assert_eq!(collator["rpc_port"].as_integer(), Some(9944));
+		assert_eq!(collator["p2p_port"].as_integer(), Some(30333));
+		assert_eq!(collator["env"][0]["name"].as_str(), Some("RUST_LOG"));
+		assert_eq!(collator["env"][0]["value"].as_str(), Some("info"));
+
+		let system_chain = &config["parachains"][1];
+		assert_eq!(system_chain["chain"].as_str(), Some("asset-hub-rococo-local"));
+		assert!(system_chain.get("default_command").is_none());
+		assert_eq!(system_chain["collators"][0]["name"].as_str(), Some("asset-hub"));
+		Ok(())
+	}
+
+	#[test]
+	fn test_parse_zombienet_config() -> Result<(), Error> {
+		let content = "[relaychain]\nchain = \"rococo-local\"\n";
+		assert_eq!(parse(content)?.to_string(), content);
+		Ok(())
+	}
+
+	#[test]
+	fn test_configure_node() -> Result<(), Error> {
+		let args = ["--node-key-file=/keys/node-key".to_string()];
+		let config = parse(&configure_node(NETWORK, "alice", &args)?)?;
+		assert_eq!(config["relaychain"]["nodes"][0]["args"][0].as_str(), Some(args[0].as_str()));
+
+		// Existing arguments are kept, unless they set the same flag.
+		let content = configure_node(NETWORK, "collator-01", &args)?;
+		let args = ["--node-key-file=/keys/other".to_string(), "--keystore-path=/keys".to_string()];
+		let config = parse(&configure_node(&content, "collator-01", &args)?)?;
+		let collator = &config["parachains"][0]["collators"][0];
+		assert_eq!(collator["args"].as_array().map(|a| a.len()), Some(2));
+		assert_eq!(collator["args"][0].as_str(), Some("--node-key-file=/keys/other"));
+		assert_eq!(collator["rpc_port"].as_integer(), Some(9944));
+
+		let zombienet = "[relaychain]\n\n[[relaychain.nodes]]\nname = \"alice\"\n\n\
+		                 [[parachains]]\nid = 2000\n\n[parachains.collator]\nname = \"collator\"\n";
+		let config = parse(&configure_node(zombienet, "collator", &args)?)?;
+		assert_eq!(
+			config["parachains"][0]["collator"]["args"][1].as_str(),
+			Some("--keystore-path=/keys")
+		);
+
+		assert!(matches!(configure_node(NETWORK, "charlie", &args), Err(Error::Config(..))));
+		Ok(())
+	}
+
+	#[test]
+	fn test_parse_invalid_config() {
+		assert!(matches!(parse("[relay]\nvalidators = [1]\n"), Err(Error::TomlError(..))));
+		assert!(matches!(parse("[relay]\nunknown = true\n"), Err(Error::TomlError(..))));
+		assert!(matches!(parse("[relay"), Err(Error::Config(..))));
+	}
+}
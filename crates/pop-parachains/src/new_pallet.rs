@@ -21,6 +21,7 @@ pub fn create_pallet_template(
 	config: TemplatePalletConfig,
 ) -> Result<(), Error> {
 	let target = resolve_pallet_path(path)?;
+	fs::create_dir_all(&target).map_err(|_| Error::PalletDirCreation)?;
 	let pallet_name = config.name.clone();
 	let pallet_path = target.join(pallet_name.clone());
 	sanitize(&pallet_path)?;
@@ -2,30 +2,33 @@
 
 use crate::{
 	generator::parachain::{ChainSpec, Network},
-	utils::{
-		git::Git,
-		helpers::{sanitize, write_to_file},
-	},
+	utils::helpers::{sanitize, write_to_file},
 	Config, Provider, Template,
 };
 use anyhow::Result;
+use pop_common::{cache::template_mirror, Git, Status};
 use std::{fs, path::Path};
+use tracing::warn;
+use url::Url;
 use walkdir::WalkDir;
 
-/// Creates a new template at `target` dir
+/// Creates a new template at `target` dir, reporting the progress of cloning the template to
+/// `status`. The mirror of the template within `cache`, if any, is used when its repository cannot
+/// be reached.
 pub fn instantiate_template_dir(
 	template: &Template,
 	target: &Path,
 	tag_version: Option<String>,
 	config: Config,
+	cache: Option<&Path>,
+	status: impl Status,
 ) -> Result<Option<String>> {
 	sanitize(target)?;
 
 	if template.matches(&Provider::Pop) {
-		return instantiate_standard_template(template, target, config, tag_version);
+		return instantiate_standard_template(template, target, config, tag_version, cache, status);
 	}
-	let tag = Git::clone_and_degit(template.repository_url()?, target, tag_version)?;
-	Ok(tag)
+	clone(template, target, tag_version, cache, status)
 }
 
 pub fn instantiate_standard_template(
@@ -33,11 +36,13 @@ pub fn instantiate_standard_template(
 	target: &Path,
 	config: Config,
 	tag_version: Option<String>,
+	cache: Option<&Path>,
+	status: impl Status,
 ) -> Result<Option<String>> {
 	let temp_dir = ::tempfile::TempDir::new_in(std::env::temp_dir())?;
 	let source = temp_dir.path();
 
-	let tag = Git::clone_and_degit(template.repository_url()?, source, tag_version)?;
+	let tag = clone(template, source, tag_version, cache, status)?;
 
 	for entry in WalkDir::new(&source) {
 		let entry = entry?;
@@ -67,6 +72,35 @@ pub fn instantiate_standard_template(
 	Ok(tag)
 }
 
+/// Clones and degits the repository of `template` into `target`, falling back to its mirror
+/// within `cache`, as exported and imported for air-gapped use, when the repository cannot be
+/// reached.
+fn clone(
+	template: &Template,
+	target: &Path,
+	tag_version: Option<String>,
+	cache: Option<&Path>,
+	status: impl Status,
+) -> Result<Option<String>> {
+	let url = template.repository_url()?;
+	let error = match Git::clone_and_degit(url, target, tag_version.clone(), status) {
+		Ok(tag) => return Ok(tag),
+		Err(e) => e,
+	};
+	let Some(mirror) = cache
+		.and_then(|cache| template_mirror(cache, &Url::parse(url).ok()?).ok())
+		.filter(|m| m.exists())
+	else {
+		return Err(error);
+	};
+	warn!(url, error = %error, mirror = %mirror.display(), "clone failed, using mirror");
+	// Removes anything left by the failed clone.
+	if target.exists() {
+		fs::remove_dir_all(target)?;
+	}
+	Git::clone_and_degit(&mirror.to_string_lossy(), target, tag_version, status)
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -80,7 +114,14 @@ mod tests {
 			decimals: 18,
 			initial_endowment: "1000000".to_string(),
 		};
-		instantiate_standard_template(&Template::Standard, temp_dir.path(), config, None)?;
+		instantiate_standard_template(
+			&Template::Standard,
+			temp_dir.path(),
+			config,
+			None,
+			None,
+			(),
+		)?;
 		Ok(temp_dir)
 	}
 
@@ -0,0 +1,88 @@
+// SPDX-License-Identifier: GPL-3.0
+use crate::{
+	build::node_name,
+	errors::Error,
+	generator::parachain::{SmokeTest, TestNetwork, TestRunner, TransferScript},
+	utils::helpers::write_to_file,
+};
+use askama::Template;
+use std::{
+	fs,
+	os::unix::fs::PermissionsExt,
+	path::{Path, PathBuf},
+};
+
+/// Generates an end-to-end test of the parachain project at `path`, written in the Zombienet DSL,
+/// along with the network it is run against and a script running it. The test asserts that the
+/// nodes are launched, that the parachain is registered and produces blocks, and that a sample
+/// extrinsic is included. Returns the paths of the generated files.
+///
+/// # Arguments
+///
+/// * `path` - path to the project
+/// * `para_id` - the identifier of the parachain within the test network
+pub fn create_network_test(path: &Path, para_id: u32) -> Result<Vec<PathBuf>, Error> {
+	let node = node_name(path)?;
+	let tests = path.join("tests");
+	let files = [
+		(tests.join("network.toml"), TestNetwork { node: node.clone(), para_id }.render()),
+		(tests.join("smoke.zndsl"), SmokeTest { node, para_id }.render()),
+		(tests.join("transfer.js"), TransferScript {}.render()),
+		(path.join("scripts").join("network-tests.sh"), TestRunner {}.render()),
+	];
+	// Existing tests are never overwritten.
+	if let Some((existing, _)) = files.iter().find(|(file, _)| file.exists()) {
+		return Err(Error::Config(format!("{} already exists", existing.display())));
+	}
+	let mut generated = Vec::new();
+	for (file, contents) in files {
+		if let Some(parent) = file.parent() {
+			fs::create_dir_all(parent)?;
+		}
+		write_to_file(&file, &contents.expect("infallible"))?;
+		generated.push(file);
+	}
+	let runner = generated.last().expect("runner generated above");
+	fs::set_permissions(runner, fs::Permissions::from_mode(0o755))?;
+	Ok(generated)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+
+	fn parachain_project() -> Result<tempfile::TempDir> {
+		let temp_dir = tempfile::tempdir()?;
+		let path = temp_dir.path();
+		fs::write(path.join("Cargo.toml"), "[workspace]\nmembers = [\"node\"]\n")?;
+		fs::create_dir(path.join("node"))?;
+		fs::write(
+			path.join("node").join("Cargo.toml"),
+			"[package]\nname = \"parachain-template-node\"\n\n[dependencies]\nsc-cli = \"0.36\"\n",
+		)?;
+		Ok(temp_dir)
+	}
+
+	#[test]
+	fn test_create_network_test() -> Result<()> {
+		let project = parachain_project()?;
+		let path = project.path();
+		let files = create_network_test(path, 2000)?;
+		assert_eq!(files.len(), 4);
+
+		let network = fs::read_to_string(path.join("tests/network.toml"))?;
+		assert!(network.contains("id = 2000"));
+		assert!(network.contains("default_command = \"./target/release/parachain-template-node\""));
+		let test = fs::read_to_string(path.join("tests/smoke.zndsl"))?;
+		assert!(test.contains("Network: ./network.toml"));
+		assert!(test.contains("alice: parachain 2000 is registered within 225 seconds"));
+		assert!(path.join("tests/transfer.js").exists());
+		let runner = fs::metadata(path.join("scripts/network-tests.sh"))?;
+		assert_eq!(runner.permissions().mode() & 0o111, 0o111);
+
+		// Existing tests are never overwritten.
+		assert!(matches!(create_network_test(path, 2000), Err(Error::Config(..))));
+		Ok(())
+	}
+}
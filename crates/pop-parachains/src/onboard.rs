@@ -0,0 +1,346 @@
+// SPDX-License-Identifier: GPL-3.0
+use crate::{
+	errors::Error,
+	relay::{read_hex, signer, submit},
+	wallet::Token,
+};
+use std::{
+	path::Path,
+	time::{Duration, Instant},
+};
+use strum_macros::{Display, EnumString};
+use subxt::{
+	dynamic::{DecodedValueThunk, Value},
+	ext::{
+		codec::Decode,
+		scale_value::{At, ValueDef},
+	},
+	OnlineClient, PolkadotConfig,
+};
+use url::Url;
+
+/// The interval at which the state of a parachain is checked while awaiting its onboarding.
+const POLL_INTERVAL: Duration = Duration::from_secs(12);
+/// The pallets providing on-demand coretime, whose name depends on the version of the relay chain.
+const ON_DEMAND_PALLETS: [&str; 2] = ["OnDemand", "OnDemandAssignmentProvider"];
+
+/// A public test network onto which parachains are onboarded.
+#[derive(Clone, Copy, Debug, Default, Display, EnumString, PartialEq)]
+#[strum(serialize_all = "lowercase")]
+pub enum Testnet {
+	/// The Paseo community testnet.
+	#[default]
+	Paseo,
+}
+
+impl Testnet {
+	/// The default endpoint of the relay chain.
+	pub fn rpc_url(&self) -> &'static str {
+		match self {
+			Testnet::Paseo => "wss://paseo.rpc.amforc.com",
+		}
+	}
+
+	/// The faucet from which accounts are funded with the native token of the relay chain.
+	pub fn faucet(&self) -> &'static str {
+		match self {
+			Testnet::Paseo => "https://faucet.polkadot.io",
+		}
+	}
+
+	/// The identifier of the relay chain, as referenced by the chain spec of a parachain.
+	pub fn relay_chain(&self) -> &'static str {
+		match self {
+			Testnet::Paseo => "paseo",
+		}
+	}
+
+	/// The native token of the relay chain.
+	fn token(&self) -> Token {
+		match self {
+			Testnet::Paseo => Token { symbol: "PAS".into(), decimals: 10 },
+		}
+	}
+
+	/// Formats `amount`, in the smallest unit of the native token, as whole tokens.
+	///
+	/// # Arguments
+	///
+	/// * `amount` - the amount, in the smallest unit of the native token
+	pub fn format_balance(&self, amount: u128) -> String {
+		self.token().format(amount)
+	}
+
+	/// The amount of `tokens` whole tokens, in the smallest unit of the native token.
+	///
+	/// # Arguments
+	///
+	/// * `tokens` - the number of whole tokens
+	pub fn units(&self, tokens: u128) -> u128 {
+		tokens.saturating_mul(10u128.pow(self.token().decimals.into()))
+	}
+}
+
+/// The SS58 address of the account of the secret key URI `suri`.
+///
+/// # Arguments
+///
+/// * `suri` - the secret key URI of the account
+pub fn account_address(suri: &str) -> Result<String, Error> {
+	Ok(signer(suri)?.public_key().to_account_id().to_string())
+}
+
+/// The free balance of the account of `suri` on the chain at `url`, in the smallest unit of its
+/// native token.
+///
+/// # Arguments
+///
+/// * `url` - the endpoint of the chain
+/// * `suri` - the secret key URI of the account
+pub async fn free_balance(url: &Url, suri: &str) -> Result<u128, Error> {
+	let account = signer(suri)?.public_key().to_account_id();
+	let Some(info) = fetch(url, "System", "Account", vec![Value::from_bytes(account.0)]).await?
+	else {
+		// Accounts without any balance are not stored.
+		return Ok(0);
+	};
+	let info = info.to_value().map_err(|e| Error::Extrinsic(format!("{e}")))?;
+	info.at("data")
+		.at("free")
+		.and_then(|free| free.as_u128())
+		.ok_or(Error::Extrinsic("unable to decode the balance of the account".into()))
+}
+
+/// Registers the genesis state and validation code of `para_id`, previously reserved by the account
+/// of `suri`, with the registrar of the relay chain at `url`. Unlike registering using sudo, as
+/// done on local networks, the parachain is onboarded as an on-demand parachain at the start of
+/// the next session but one, and the deposit required by the registrar is reserved from the
+/// account.
+///
+/// # Arguments
+///
+/// * `url` - the endpoint of the relay chain
+/// * `suri` - the secret key URI of the account which reserved the para id
+/// * `para_id` - the identifier of the parachain
+/// * `genesis_state` - path to the hex encoded genesis state (head) of the parachain
+/// * `genesis_wasm` - path to the hex encoded genesis wasm (validation code) of the parachain
+pub async fn register_genesis(
+	url: &Url,
+	suri: &str,
+	para_id: u32,
+	genesis_state: &Path,
+	genesis_wasm: &Path,
+) -> Result<(), Error> {
+	let call = subxt::dynamic::tx(
+		"Registrar",
+		"register",
+		vec![
+			Value::u128(para_id as u128),
+			Value::from_bytes(read_hex(genesis_state)?),
+			Value::from_bytes(read_hex(genesis_wasm)?),
+		],
+	);
+	submit(url, suri, &call)
+		.await
+		.map_err(|e| Error::Extrinsic(format!("unable to register parachain {para_id}: {e}")))?;
+	Ok(())
+}
+
+/// The lifecycle stage of `para_id` on the relay chain at `url` (e.g. `Onboarding`,
+/// `Parathread` or `Parachain`), if registered.
+///
+/// # Arguments
+///
+/// * `url` - the endpoint of the relay chain
+/// * `para_id` - the identifier of the parachain
+pub async fn para_lifecycle(url: &Url, para_id: u32) -> Result<Option<String>, Error> {
+	let key = vec![Value::u128(para_id as u128)];
+	let Some(lifecycle) = fetch(url, "Paras", "ParaLifecycles", key).await? else {
+		return Ok(None);
+	};
+	match lifecycle.to_value().map_err(|e| Error::Extrinsic(format!("{e}")))?.value {
+		ValueDef::Variant(variant) => Ok(Some(variant.name)),
+		_ => Err(Error::Extrinsic("unable to decode the lifecycle of the parachain".into())),
+	}
+}
+
+/// Waits for `para_id` to be onboarded by the relay chain at `url`, as either an on-demand
+/// parachain or a parachain, returning its lifecycle stage once onboarded.
+///
+/// # Arguments
+///
+/// * `url` - the endpoint of the relay chain
+/// * `para_id` - the identifier of the parachain
+/// * `timeout` - the time after which waiting is abandoned
+pub async fn wait_for_onboarding(
+	url: &Url,
+	para_id: u32,
+	timeout: Duration,
+) -> Result<String, Error> {
+	let started = Instant::now();
+	loop {
+		let lifecycle = para_lifecycle(url, para_id).await?;
+		if let Some(lifecycle) = lifecycle.as_deref().filter(|l| is_onboarded(l)) {
+			return Ok(lifecycle.to_string());
+		}
+		if started.elapsed() > timeout {
+			return Err(Error::Extrinsic(format!(
+				"parachain {para_id} was not onboarded after {}s: it is {}",
+				timeout.as_secs(),
+				lifecycle.unwrap_or("not registered".into())
+			)));
+		}
+		tokio::time::sleep(POLL_INTERVAL).await;
+	}
+}
+
+/// Whether a parachain at the `lifecycle` stage has been onboarded, and can therefore produce
+/// blocks once assigned coretime.
+fn is_onboarded(lifecycle: &str) -> bool {
+	matches!(lifecycle, "Parathread" | "Parachain")
+}
+
+/// Places an order for a single on-demand core for `para_id` on the relay chain at `url`, paid by
+/// the account of `suri`, so that the relay chain validates the next block of the parachain.
+///
+/// # Arguments
+///
+/// * `url` - the endpoint of the relay chain
+/// * `suri` - the secret key URI of the account paying for the order
+/// * `para_id` - the identifier of the parachain
+/// * `max_amount` - the maximum amount paid for the order, in the smallest unit of the native
+///   token
+pub async fn place_on_demand_order(
+	url: &Url,
+	suri: &str,
+	para_id: u32,
+	max_amount: u128,
+) -> Result<(), Error> {
+	let metadata = client(url).await?.metadata();
+	let pallet = ON_DEMAND_PALLETS
+		.into_iter()
+		.find(|p| metadata.pallet_by_name(p).is_some())
+		.ok_or(Error::Extrinsic(format!(
+			"the chain at {url} does not support on-demand coretime"
+		)))?;
+	let call = subxt::dynamic::tx(
+		pallet,
+		"place_order_allow_death",
+		vec![Value::u128(max_amount), Value::u128(para_id as u128)],
+	);
+	submit(url, suri, &call)
+		.await
+		.map_err(|e| Error::Extrinsic(format!("unable to place an on-demand order: {e}")))?;
+	Ok(())
+}
+
+/// Waits for the head of `para_id` on the relay chain at `url` to differ from the genesis state
+/// at `genesis_state`, as recorded once a block of the parachain is included.
+///
+/// # Arguments
+///
+/// * `url` - the endpoint of the relay chain
+/// * `para_id` - the identifier of the parachain
+/// * `genesis_state` - path to the hex encoded genesis state (head) of the parachain
+/// * `timeout` - the time after which waiting is abandoned
+pub async fn wait_for_para_block(
+	url: &Url,
+	para_id: u32,
+	genesis_state: &Path,
+	timeout: Duration,
+) -> Result<(), Error> {
+	let genesis = read_hex(genesis_state)?;
+	let started = Instant::now();
+	loop {
+		let key = vec![Value::u128(para_id as u128)];
+		if let Some(head) = fetch(url, "Paras", "Heads", key).await? {
+			let head = Vec::<u8>::decode(&mut head.encoded())
+				.map_err(|e| Error::Extrinsic(format!("unable to decode the head: {e}")))?;
+			if head != genesis {
+				return Ok(());
+			}
+		}
+		if started.elapsed() > timeout {
+			return Err(Error::Extrinsic(format!(
+				"parachain {para_id} did not produce a block after {}s",
+				timeout.as_secs()
+			)));
+		}
+		tokio::time::sleep(POLL_INTERVAL).await;
+	}
+}
+
+/// A client of the chain at `url`.
+pub(crate) async fn client(url: &Url) -> Result<OnlineClient<PolkadotConfig>, Error> {
+	OnlineClient::<PolkadotConfig>::from_url(url.as_str())
+		.await
+		.map_err(|e| Error::Extrinsic(format!("unable to connect to {url}: {e}")))
+}
+
+/// Fetches the value of the storage `entry` of `pallet` at `keys` from the latest block of the
+/// chain at `url`.
+pub(crate) async fn fetch(
+	url: &Url,
+	pallet: &str,
+	entry: &str,
+	keys: Vec<Value>,
+) -> Result<Option<DecodedValueThunk>, Error> {
+	let address = subxt::dynamic::storage(pallet, entry, keys);
+	client(url)
+		.await?
+		.storage()
+		.at_latest()
+		.await
+		.map_err(|e| Error::Extrinsic(format!("{e}")))?
+		.fetch(&address)
+		.await
+		.map_err(|e| Error::Extrinsic(format!("{e}")))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+	use std::str::FromStr;
+
+	#[test]
+	fn test_testnet() -> Result<()> {
+		let paseo = Testnet::from_str("paseo")?;
+		assert_eq!(paseo, Testnet::default());
+		assert_eq!(paseo.to_string(), "paseo");
+		assert_eq!(paseo.relay_chain(), "paseo");
+		assert!(Url::parse(paseo.rpc_url()).is_ok());
+		assert!(Testnet::from_str("rococo").is_err());
+		Ok(())
+	}
+
+	#[test]
+	fn test_format_balance() {
+		let paseo = Testnet::Paseo;
+		assert_eq!(paseo.format_balance(0), "0 PAS");
+		assert_eq!(paseo.format_balance(paseo.units(5)), "5 PAS");
+		assert_eq!(paseo.format_balance(15_000_000_000), "1.5 PAS");
+		assert_eq!(paseo.format_balance(1), "0.0000000001 PAS");
+	}
+
+	#[test]
+	fn test_account_address() -> Result<()> {
+		assert_eq!(account_address("//Alice")?, "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY");
+		assert!(matches!(account_address("invalid uri"), Err(Error::Signer(..))));
+		Ok(())
+	}
+
+	#[test]
+	fn test_is_onboarded() {
+		assert!(is_onboarded("Parathread"));
+		assert!(is_onboarded("Parachain"));
+		assert!(!is_onboarded("Onboarding"));
+		assert!(!is_onboarded("OffboardingParathread"));
+	}
+
+	#[tokio::test]
+	async fn test_para_lifecycle_fails_without_chain() {
+		let url = Url::parse("ws://127.0.0.1:9").expect("valid url");
+		assert!(matches!(para_lifecycle(&url, 2000).await, Err(Error::Extrinsic(..))));
+	}
+}
@@ -0,0 +1,246 @@
+// SPDX-License-Identifier: GPL-3.0
+use crate::errors::Error;
+use std::{collections::HashSet, net::TcpListener};
+use toml_edit::{value, DocumentMut, Table};
+
+/// The first port assigned to the RPC server of a node without an explicit port, with each
+/// subsequent node assigned the next free port.
+pub const DEFAULT_RPC_PORT: u16 = 9944;
+/// The ports of a node which can be explicitly configured.
+const PORTS: [&str; 3] = ["rpc_port", "p2p_port", "prometheus_port"];
+
+/// Parses an explicit RPC port of a node, specified as `<node>=<port>` (e.g. `alice=9944`).
+///
+/// # Arguments
+///
+/// * `value` - the node and its port
+pub fn parse_rpc_port(value: &str) -> Result<(String, u16), Error> {
+	let (node, port) = value
+		.split_once('=')
+		.filter(|(node, _)| !node.is_empty())
+		.ok_or(Error::Config(format!("expected `<node>=<port>`, found `{value}`")))?;
+	let port = port.parse().map_err(|_| Error::Config(format!("invalid port `{port}`")))?;
+	Ok((node.into(), port))
+}
+
+/// Whether `port` is free on the local host.
+fn is_free(port: u16) -> bool {
+	TcpListener::bind(("127.0.0.1", port)).is_ok()
+}
+
+/// Ensures every node of a network, as configured for Zombienet, can be served: the RPC ports in
+/// `overrides` are applied, explicitly configured ports are checked to be free, and any node
+/// without an RPC port is assigned the next free port from `first`, so that endpoints remain
+/// predictable even when the default ports are taken.
+///
+/// # Arguments
+///
+/// * `config` - the network configuration, as expected by Zombienet
+/// * `overrides` - the names of nodes, along with their RPC ports
+/// * `first` - the first port assigned to a node without an RPC port
+pub(crate) fn assign_ports(
+	config: &mut DocumentMut,
+	overrides: &[(String, u16)],
+	first: u16,
+) -> Result<(), Error> {
+	let mut nodes = nodes(config);
+	for (name, port) in overrides {
+		let node = nodes
+			.iter_mut()
+			.find(|n| n.get("name").and_then(|n| n.as_str()) == Some(name))
+			.ok_or(Error::Config(format!("the network has no node named `{name}`")))?;
+		node.insert("rpc_port", value(*port as i64));
+	}
+
+	let mut used = HashSet::new();
+	for node in &nodes {
+		let name = node.get("name").and_then(|n| n.as_str()).unwrap_or_default();
+		for key in PORTS {
+			let Some(port) = node.get(key).and_then(|p| p.as_integer()) else { continue };
+			let port = u16::try_from(port)
+				.map_err(|_| Error::Config(format!("invalid {key} `{port}` of `{name}`")))?;
+			if !used.insert(port) {
+				return Err(Error::Config(format!("port {port} is configured for multiple nodes")));
+			}
+			if !is_free(port) {
+				return Err(Error::Config(format!(
+					"port {port} of `{name}` is already in use, configure another port or stop \
+					 the process using it"
+				)));
+			}
+		}
+	}
+
+	let mut next = first;
+	for node in nodes.iter_mut().filter(|n| !n.contains_key("rpc_port")) {
+		while used.contains(&next) || !is_free(next) {
+			next = next
+				.checked_add(1)
+				.ok_or(Error::Config("no free port is available for the network".into()))?;
+		}
+		node.insert("rpc_port", value(next as i64));
+		used.insert(next);
+	}
+	Ok(())
+}
+
+/// Ensures every node of a network, as configured for Zombienet, exposes its Prometheus metrics on
+/// a known port: any node without a Prometheus port is assigned the next free port from `first`,
+/// skipping the ports configured for other nodes.
+///
+/// # Arguments
+///
+/// * `config` - the network configuration, as expected by Zombienet
+/// * `first` - the first port assigned to a node without a Prometheus port
+pub(crate) fn assign_prometheus_ports(config: &mut DocumentMut, first: u16) -> Result<(), Error> {
+	let mut nodes = nodes(config);
+	let mut used: HashSet<i64> = nodes
+		.iter()
+		.flat_map(|n| PORTS.iter().filter_map(|key| n.get(key).and_then(|p| p.as_integer())))
+		.collect();
+	let mut next = first;
+	for node in nodes.iter_mut().filter(|n| !n.contains_key("prometheus_port")) {
+		while used.contains(&(next as i64)) || !is_free(next) {
+			next = next
+				.checked_add(1)
+				.ok_or(Error::Config("no free port is available for the network".into()))?;
+		}
+		node.insert("prometheus_port", value(next as i64));
+		used.insert(next as i64);
+	}
+	Ok(())
+}
+
+/// The validators of the relay chain followed by the collators of each parachain.
+fn nodes(config: &mut DocumentMut) -> Vec<&mut Table> {
+	let (mut validators, mut collators) = (Vec::new(), Vec::new());
+	for (key, item) in config.iter_mut() {
+		match key.get() {
+			"relaychain" => validators.extend(
+				item.get_mut("nodes")
+					.and_then(|n| n.as_array_of_tables_mut())
+					.into_iter()
+					.flat_map(|n| n.iter_mut()),
+			),
+			"parachains" => collators.extend(
+				item.as_array_of_tables_mut()
+					.into_iter()
+					.flat_map(|p| p.iter_mut())
+					.filter_map(|p| p.get_mut("collators"))
+					.filter_map(|c| c.as_array_of_tables_mut())
+					.flat_map(|c| c.iter_mut()),
+			),
+			_ => {},
+		}
+	}
+	validators.extend(collators);
+	validators
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+
+	const NETWORK: &str = r#"
+[relaychain]
+chain = "rococo-local"
+
+[[relaychain.nodes]]
+name = "alice"
+
+[[relaychain.nodes]]
+name = "bob"
+
+[[parachains]]
+id = 2000
+
+[[parachains.collators]]
+name = "collator-01"
+"#;
+
+	/// A port which is free, followed by a port which is in use while the listener is held.
+	fn ports() -> Result<(u16, TcpListener)> {
+		loop {
+			let listener = TcpListener::bind(("127.0.0.1", 0))?;
+			let taken = listener.local_addr()?.port();
+			if let Some(free) = taken.checked_sub(1).filter(|p| is_free(*p)) {
+				return Ok((free, listener));
+			}
+		}
+	}
+
+	fn rpc_port(config: &DocumentMut, node: &str) -> Option<i64> {
+		let relay_nodes = config["relaychain"]["nodes"].as_array_of_tables()?;
+		let collators = config["parachains"][0]["collators"].as_array_of_tables()?;
+		relay_nodes
+			.iter()
+			.chain(collators.iter())
+			.find(|n| n.get("name").and_then(|n| n.as_str()) == Some(node))?
+			.get("rpc_port")?
+			.as_integer()
+	}
+
+	#[test]
+	fn test_parse_rpc_port() -> Result<()> {
+		assert_eq!(parse_rpc_port("alice=9944")?, ("alice".into(), 9944));
+		for value in ["alice", "=9944", "alice=port", "alice=70000"] {
+			assert!(matches!(parse_rpc_port(value), Err(Error::Config(..))));
+		}
+		Ok(())
+	}
+
+	#[test]
+	fn test_assign_ports_skips_taken_ports() -> Result<()> {
+		let (free, _listener) = ports()?;
+		let mut config: DocumentMut = NETWORK.parse()?;
+		assign_ports(&mut config, &[], free)?;
+		assert_eq!(rpc_port(&config, "alice"), Some(free as i64));
+		// The next port is taken, so is skipped.
+		let bob = rpc_port(&config, "bob").expect("port assigned");
+		assert!(bob > free as i64 + 1);
+		assert!(rpc_port(&config, "collator-01").expect("port assigned") > bob);
+		Ok(())
+	}
+
+	#[test]
+	fn test_assign_ports_applies_overrides() -> Result<()> {
+		let (free, listener) = ports()?;
+		let mut config: DocumentMut = NETWORK.parse()?;
+		assign_ports(&mut config, &[("collator-01".into(), free)], free)?;
+		assert_eq!(rpc_port(&config, "collator-01"), Some(free as i64));
+		assert_ne!(rpc_port(&config, "alice"), Some(free as i64));
+
+		let taken = listener.local_addr()?.port();
+		let mut config: DocumentMut = NETWORK.parse()?;
+		assert!(matches!(
+			assign_ports(&mut config, &[("alice".into(), taken)], free),
+			Err(Error::Config(..))
+		));
+		assert!(matches!(
+			assign_ports(&mut config, &[("charlie".into(), free)], free),
+			Err(Error::Config(..))
+		));
+		Ok(())
+	}
+
+	#[test]
+	fn test_assign_prometheus_ports() -> Result<()> {
+		let (free, _listener) = ports()?;
+		let mut config: DocumentMut = NETWORK.parse()?;
+		config["relaychain"]["nodes"][1]["prometheus_port"] = value(free as i64 + 2);
+		assign_prometheus_ports(&mut config, free)?;
+		let port = |index: usize| {
+			config["relaychain"]["nodes"][index]["prometheus_port"]
+				.as_integer()
+				.expect("assigned")
+		};
+		assert_eq!(port(0), free as i64);
+		// Explicit ports are retained, and neither they nor taken ports are assigned to others.
+		assert_eq!(port(1), free as i64 + 2);
+		assert!(config["parachains"][0]["collators"][0]["prometheus_port"]
+			.as_integer()
+			.is_some_and(|p| p > free as i64 + 2));
+		Ok(())
+	}
+}
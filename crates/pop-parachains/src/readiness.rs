@@ -0,0 +1,125 @@
+// SPDX-License-Identifier: GPL-3.0
+use crate::errors::Error;
+use std::time::{Duration, Instant};
+use subxt::{
+	backend::{legacy::LegacyRpcMethods, rpc::RpcClient},
+	PolkadotConfig,
+};
+use url::Url;
+
+/// The interval at which the blocks of a chain are checked while awaiting its readiness.
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// The block numbers of a chain, as reported by one of its nodes.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ChainStatus {
+	/// The number of the best block.
+	pub best: u32,
+	/// The number of the finalized block.
+	pub finalized: u32,
+}
+
+impl ChainStatus {
+	/// The block numbers of the chain served at `url`.
+	///
+	/// # Arguments
+	///
+	/// * `url` - the endpoint of a node of the chain
+	pub async fn of(url: &Url) -> Result<Self, Error> {
+		let rpc = RpcClient::from_url(url.as_str())
+			.await
+			.map_err(|e| Error::Extrinsic(format!("unable to connect to {url}: {e}")))?;
+		let rpc = LegacyRpcMethods::<PolkadotConfig>::new(rpc);
+		let number = |header: Option<<PolkadotConfig as subxt::Config>::Header>| {
+			header
+				.map(|h| h.number)
+				.ok_or(Error::Extrinsic(format!("no header was returned by {url}")))
+		};
+		let map_err = |e: subxt::Error| Error::Extrinsic(format!("{e}"));
+		let best = number(rpc.chain_get_header(None).await.map_err(map_err)?)?;
+		let finalized_head = rpc.chain_get_finalized_head().await.map_err(map_err)?;
+		let finalized = number(rpc.chain_get_header(Some(finalized_head)).await.map_err(map_err)?)?;
+		Ok(Self { best, finalized })
+	}
+}
+
+/// The progress awaited of a chain before it is considered ready.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Readiness {
+	/// A block has been finalized, as awaited of a relay chain.
+	Finalizing,
+	/// A block has been produced, as awaited of a parachain once onboarded.
+	Producing,
+}
+
+impl Readiness {
+	/// Whether a chain with the specified status is ready.
+	///
+	/// # Arguments
+	///
+	/// * `status` - the block numbers of the chain
+	pub fn is_ready(&self, status: &ChainStatus) -> bool {
+		match self {
+			Readiness::Finalizing => status.finalized > 0,
+			Readiness::Producing => status.best > 0,
+		}
+	}
+}
+
+/// Waits for the chain served at `url` to be ready, returning its status once ready. Nodes which
+/// cannot yet be reached are retried until `timeout` elapses.
+///
+/// # Arguments
+///
+/// * `url` - the endpoint of a node of the chain
+/// * `readiness` - the progress awaited of the chain
+/// * `timeout` - the time after which waiting is abandoned
+pub async fn wait_until_ready(
+	url: &Url,
+	readiness: Readiness,
+	timeout: Duration,
+) -> Result<ChainStatus, Error> {
+	let started = Instant::now();
+	loop {
+		let status = match ChainStatus::of(url).await {
+			Ok(status) if readiness.is_ready(&status) => return Ok(status),
+			status => status,
+		};
+		if started.elapsed() > timeout {
+			let reason = match status {
+				Ok(status) => {
+					format!("the best block is #{}, finalized #{}", status.best, status.finalized)
+				},
+				Err(e) => format!("{e}"),
+			};
+			return Err(Error::Extrinsic(format!(
+				"{url} is not ready after {}s: {reason}",
+				timeout.as_secs()
+			)));
+		}
+		tokio::time::sleep(POLL_INTERVAL).await;
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+
+	#[test]
+	fn test_readiness() {
+		let status = |best, finalized| ChainStatus { best, finalized };
+		assert!(!Readiness::Finalizing.is_ready(&status(2, 0)));
+		assert!(Readiness::Finalizing.is_ready(&status(3, 1)));
+		assert!(!Readiness::Producing.is_ready(&status(0, 0)));
+		assert!(Readiness::Producing.is_ready(&status(1, 0)));
+	}
+
+	#[tokio::test]
+	async fn test_wait_until_ready_times_out() -> Result<()> {
+		let url = Url::parse("ws://127.0.0.1:1")?;
+		let result = wait_until_ready(&url, Readiness::Producing, Duration::ZERO).await;
+		assert!(matches!(result, Err(Error::Extrinsic(e)) if e.contains("is not ready after 0s")));
+		Ok(())
+	}
+}
@@ -0,0 +1,311 @@
+// SPDX-License-Identifier: GPL-3.0
+use crate::errors::Error;
+use std::{fs, path::Path};
+use subxt::{
+	blocks::ExtrinsicEvents, dynamic::Value, ext::codec::Decode, tx::DynamicPayload, OnlineClient,
+	PolkadotConfig,
+};
+use subxt_signer::{sr25519::Keypair, SecretUri};
+use url::Url;
+
+/// The default maximum number of messages within an HRMP channel.
+pub const DEFAULT_HRMP_MAX_CAPACITY: u32 = 8;
+/// The default maximum size of a message sent over an HRMP channel, in bytes.
+pub const DEFAULT_HRMP_MAX_MESSAGE_SIZE: u32 = 512;
+
+/// Reserves a para id on the relay chain at `url`, on behalf of the account of `suri`, as the
+/// first step of onboarding a parachain. The deposit required by the registrar is reserved from
+/// the account.
+///
+/// # Arguments
+///
+/// * `url` - the endpoint of the relay chain
+/// * `suri` - the secret key URI of the account reserving the para id
+pub async fn reserve_para_id(url: &Url, suri: &str) -> Result<u32, Error> {
+	let call = subxt::dynamic::tx("Registrar", "reserve", Vec::<Value>::new());
+	let events = submit(url, suri, &call).await?;
+	for event in events.iter() {
+		let event = event.map_err(|e| Error::Extrinsic(format!("{e}")))?;
+		// The `Reserved` event consists of the para id, followed by the account.
+		if event.pallet_name() == "Registrar" && event.variant_name() == "Reserved" {
+			return u32::decode(&mut event.field_bytes()).map_err(|e| {
+				Error::Extrinsic(format!("unable to decode the reserved para id: {e}"))
+			});
+		}
+	}
+	Err(Error::Extrinsic("the relay chain did not report the reserved para id".into()))
+}
+
+/// Registers a parachain on the relay chain at `url` using the sudo account of `suri`, scheduling
+/// its initialization with the provided genesis state and validation code, so that it starts
+/// producing blocks once onboarded without any manual steps.
+///
+/// # Arguments
+///
+/// * `url` - the endpoint of the relay chain
+/// * `suri` - the secret key URI of the sudo account of the relay chain
+/// * `para_id` - the identifier of the parachain
+/// * `genesis_state` - path to the hex encoded genesis state (head) of the parachain
+/// * `genesis_wasm` - path to the hex encoded genesis wasm (validation code) of the parachain
+pub async fn register_parachain(
+	url: &Url,
+	suri: &str,
+	para_id: u32,
+	genesis_state: &Path,
+	genesis_wasm: &Path,
+) -> Result<(), Error> {
+	let genesis = Value::named_composite([
+		("genesis_head", Value::from_bytes(read_hex(genesis_state)?)),
+		("validation_code", Value::from_bytes(read_hex(genesis_wasm)?)),
+		// Registered as a parachain, rather than a parathread.
+		("para_kind", Value::bool(true)),
+	]);
+	let initialize = Value::unnamed_variant(
+		"ParasSudoWrapper",
+		[Value::named_variant(
+			"sudo_schedule_para_initialize",
+			[("id", Value::u128(para_id as u128)), ("genesis", genesis)],
+		)],
+	);
+	sudo(url, suri, initialize)
+		.await
+		.map_err(|e| Error::Extrinsic(format!("unable to register parachain {para_id}: {e}")))
+}
+
+/// An HRMP channel between two parachains, allowing the sender to send XCM messages to the
+/// recipient.
+#[derive(Clone, Debug, PartialEq)]
+pub struct HrmpChannel {
+	/// The identifier of the sending parachain.
+	pub sender: u32,
+	/// The identifier of the receiving parachain.
+	pub recipient: u32,
+	/// The maximum number of messages within the channel.
+	pub max_capacity: u32,
+	/// The maximum size of a message, in bytes.
+	pub max_message_size: u32,
+}
+
+impl HrmpChannel {
+	/// The channels between two parachains, in both directions.
+	///
+	/// # Arguments
+	///
+	/// * `a` - the identifier of a parachain
+	/// * `b` - the identifier of the other parachain
+	/// * `max_capacity` - the maximum number of messages within each channel
+	/// * `max_message_size` - the maximum size of a message, in bytes
+	pub fn bidirectional(a: u32, b: u32, max_capacity: u32, max_message_size: u32) -> [Self; 2] {
+		[
+			Self { sender: a, recipient: b, max_capacity, max_message_size },
+			Self { sender: b, recipient: a, max_capacity, max_message_size },
+		]
+	}
+
+	/// The call forcing the channel open.
+	fn force_open(&self) -> Value {
+		Value::unnamed_variant(
+			"Hrmp",
+			[Value::named_variant(
+				"force_open_hrmp_channel",
+				[
+					("sender", Value::u128(self.sender as u128)),
+					("recipient", Value::u128(self.recipient as u128)),
+					("max_capacity", Value::u128(self.max_capacity as u128)),
+					("max_message_size", Value::u128(self.max_message_size as u128)),
+				],
+			)],
+		)
+	}
+}
+
+/// Opens HRMP channels between parachains on the relay chain at `url` using the sudo account of
+/// `suri`, so that the parachains can exchange XCM messages. The channels are opened within a
+/// single batch, so either all or none are opened.
+///
+/// # Arguments
+///
+/// * `url` - the endpoint of the relay chain
+/// * `suri` - the secret key URI of the sudo account of the relay chain
+/// * `channels` - the channels to open
+pub async fn open_hrmp_channels(
+	url: &Url,
+	suri: &str,
+	channels: &[HrmpChannel],
+) -> Result<(), Error> {
+	let calls = channels.iter().map(|c| c.force_open()).collect::<Vec<_>>();
+	let batch = Value::unnamed_variant(
+		"Utility",
+		[Value::named_variant("batch_all", [("calls", Value::unnamed_composite(calls))])],
+	);
+	sudo(url, suri, batch)
+		.await
+		.map_err(|e| Error::Extrinsic(format!("unable to open the HRMP channels: {e}")))
+}
+
+/// Dispatches `call` on the chain at `url` using the sudo account of `suri`, ensuring the call
+/// itself succeeded.
+pub(crate) async fn sudo(url: &Url, suri: &str, call: Value) -> Result<(), Error> {
+	dispatch_sudo(url, suri, &sudo_call(call, false)).await.map(|_| ())
+}
+
+/// Dispatches `call` on the chain at `url` using the sudo account of `suri`, without checking the
+/// weight of the call against the limits of a block, as required by `System::set_code`.
+pub(crate) async fn sudo_unchecked_weight(url: &Url, suri: &str, call: Value) -> Result<(), Error> {
+	dispatch_sudo(url, suri, &sudo_call(call, true)).await.map(|_| ())
+}
+
+/// The call dispatching `call` using the sudo account, as `Sudo::sudo_unchecked_weight` if
+/// `unchecked_weight`, otherwise as `Sudo::sudo`.
+pub(crate) fn sudo_call(call: Value, unchecked_weight: bool) -> DynamicPayload {
+	match unchecked_weight {
+		true => {
+			let weight = Value::named_composite([
+				("ref_time", Value::u128(0)),
+				("proof_size", Value::u128(0)),
+			]);
+			subxt::dynamic::tx("Sudo", "sudo_unchecked_weight", vec![call, weight])
+		},
+		false => subxt::dynamic::tx("Sudo", "sudo", vec![call]),
+	}
+}
+
+/// Submits `call`, dispatched by sudo, to the chain at `url` with the account of `suri`, ensuring
+/// the call dispatched by sudo itself succeeded.
+pub(crate) async fn dispatch_sudo(
+	url: &Url,
+	suri: &str,
+	call: &DynamicPayload,
+) -> Result<ExtrinsicEvents<PolkadotConfig>, Error> {
+	let events = submit(url, suri, call).await?;
+	for event in events.iter() {
+		let event = event.map_err(|e| Error::Extrinsic(format!("{e}")))?;
+		// The result of the call dispatched by sudo is reported by the `Sudid` event.
+		if event.pallet_name() == "Sudo" && event.variant_name() == "Sudid" {
+			if event.field_bytes().first() != Some(&0) {
+				return Err(Error::Extrinsic("the call was rejected by the chain".into()));
+			}
+		}
+	}
+	Ok(events)
+}
+
+/// Signs `call` with the account of `suri` and submits it to the chain at `url`, waiting for it
+/// to be finalized successfully.
+pub(crate) async fn submit(
+	url: &Url,
+	suri: &str,
+	call: &DynamicPayload,
+) -> Result<ExtrinsicEvents<PolkadotConfig>, Error> {
+	let signer = signer(suri)?;
+	let client = OnlineClient::<PolkadotConfig>::from_url(url.as_str())
+		.await
+		.map_err(|e| Error::Extrinsic(format!("unable to connect to {url}: {e}")))?;
+	// Check the call against the metadata of the chain, reporting unsupported calls clearly.
+	let (pallet, name) = (call.pallet_name(), call.call_name());
+	if client
+		.metadata()
+		.pallet_by_name(pallet)
+		.and_then(|p| p.call_variant_by_name(name))
+		.is_none()
+	{
+		return Err(Error::Extrinsic(format!(
+			"the chain at {url} does not support `{pallet}::{name}`"
+		)));
+	}
+	client
+		.tx()
+		.sign_and_submit_then_watch_default(call, &signer)
+		.await
+		.map_err(|e| Error::Extrinsic(format!("{e}")))?
+		.wait_for_finalized_success()
+		.await
+		.map_err(|e| Error::Extrinsic(format!("{e}")))
+}
+
+/// The signer of the secret key URI `suri`.
+pub(crate) fn signer(suri: &str) -> Result<Keypair, Error> {
+	let uri = <SecretUri as std::str::FromStr>::from_str(suri)
+		.map_err(|e| Error::Signer(format!("invalid secret key URI: {e}")))?;
+	Keypair::from_uri(&uri).map_err(|e| Error::Signer(format!("{e}")))
+}
+
+/// Reads the hex encoded bytes of the file at `path`, as exported by a node.
+pub(crate) fn read_hex(path: &Path) -> Result<Vec<u8>, Error> {
+	decode_hex(&fs::read_to_string(path)?).map_err(|_| {
+		Error::Config(format!("{} does not contain hex encoded bytes", path.display()))
+	})
+}
+
+/// Decodes the hex encoded bytes `hex`, with or without a `0x` prefix.
+pub(crate) fn decode_hex(hex: &str) -> Result<Vec<u8>, Error> {
+	let hex = hex.trim();
+	let hex = hex.strip_prefix("0x").unwrap_or(hex);
+	let invalid = || Error::Config(format!("`{hex}` is not hex encoded"));
+	if hex.len() % 2 != 0 {
+		return Err(invalid());
+	}
+	(0..hex.len())
+		.step_by(2)
+		.map(|i| {
+			hex.get(i..i + 2)
+				.and_then(|b| u8::from_str_radix(b, 16).ok())
+				.ok_or_else(invalid)
+		})
+		.collect()
+}
+
+/// Encodes `bytes` as hex, prefixed by `0x`.
+pub(crate) fn to_hex(bytes: &[u8]) -> String {
+	format!("0x{}", bytes.iter().map(|b| format!("{b:02x}")).collect::<String>())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_signer() -> Result<(), Error> {
+		assert_eq!(
+			signer("//Alice")?.public_key().0,
+			subxt_signer::sr25519::dev::alice().public_key().0
+		);
+		assert!(matches!(signer("invalid uri"), Err(Error::Signer(..))));
+		Ok(())
+	}
+
+	#[test]
+	fn test_read_hex() -> Result<(), Error> {
+		let temp_dir = tempfile::tempdir()?;
+		let path = temp_dir.path().join("genesis-state");
+		fs::write(&path, "0x00ff1A\n")?;
+		assert_eq!(read_hex(&path)?, [0x00, 0xff, 0x1a]);
+		for invalid in ["0x0", "0xzz", "genesis"] {
+			fs::write(&path, invalid)?;
+			assert!(matches!(read_hex(&path), Err(Error::Config(..))));
+		}
+		Ok(())
+	}
+
+	#[test]
+	fn test_hex() -> Result<(), Error> {
+		assert_eq!(decode_hex(" 0x00ff1A ")?, [0x00, 0xff, 0x1a]);
+		assert_eq!(decode_hex("00ff")?, [0x00, 0xff]);
+		assert_eq!(to_hex(&[0x00, 0xff, 0x1a]), "0x00ff1a");
+		assert_eq!(to_hex(&[]), "0x");
+		Ok(())
+	}
+
+	#[test]
+	fn test_bidirectional_hrmp_channels() {
+		let [a, b] = HrmpChannel::bidirectional(2000, 1000, 8, 512);
+		assert_eq!((a.sender, a.recipient, b.sender, b.recipient), (2000, 1000, 1000, 2000));
+		assert_eq!((b.max_capacity, b.max_message_size), (8, 512));
+	}
+
+	#[tokio::test]
+	async fn test_reserve_para_id_fails_without_chain() {
+		let url = Url::parse("ws://127.0.0.1:9").expect("valid url");
+		assert!(matches!(reserve_para_id(&url, "//Alice").await, Err(Error::Extrinsic(..))));
+	}
+}
@@ -0,0 +1,285 @@
+// SPDX-License-Identifier: GPL-3.0
+use crate::errors::Error;
+use duct::cmd;
+use std::{
+	collections::{BTreeMap, BTreeSet},
+	fmt::{Display, Formatter},
+	path::{Path, PathBuf},
+	str::FromStr,
+};
+use strum_macros::Display;
+use subxt::{ext::codec::Decode, Metadata, OnlineClient, PolkadotConfig};
+use url::Url;
+
+/// The runtime whose metadata is compared: either a wasm runtime or the runtime of a live chain.
+#[derive(Clone, Debug, PartialEq)]
+pub enum RuntimeSource {
+	/// A wasm runtime.
+	Wasm(PathBuf),
+	/// The runtime of the chain served at the endpoint.
+	Chain(Url),
+}
+
+impl FromStr for RuntimeSource {
+	type Err = Error;
+
+	fn from_str(value: &str) -> Result<Self, Self::Err> {
+		match value.starts_with("ws://") || value.starts_with("wss://") {
+			true => Ok(Self::Chain(Url::parse(value)?)),
+			false => Ok(Self::Wasm(PathBuf::from(value))),
+		}
+	}
+}
+
+impl Display for RuntimeSource {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self {
+			RuntimeSource::Wasm(path) => write!(f, "{}", path.display()),
+			RuntimeSource::Chain(url) => write!(f, "{url}"),
+		}
+	}
+}
+
+impl RuntimeSource {
+	/// The metadata of the runtime. The metadata of a wasm runtime is extracted using `subwasm`.
+	pub async fn metadata(&self) -> Result<Metadata, Error> {
+		match self {
+			RuntimeSource::Wasm(path) => wasm_metadata(path),
+			RuntimeSource::Chain(url) => Ok(OnlineClient::<PolkadotConfig>::from_url(url.as_str())
+				.await
+				.map_err(|e| Error::Extrinsic(format!("unable to connect to {url}: {e}")))?
+				.metadata()),
+		}
+	}
+}
+
+/// The metadata of the wasm runtime at `path`, extracted using `subwasm`.
+fn wasm_metadata(path: &Path) -> Result<Metadata, Error> {
+	if !path.exists() {
+		return Err(Error::Config(format!("the runtime {} does not exist", path.display())));
+	}
+	if cmd("subwasm", ["--version"]).stdout_null().stderr_null().run().is_err() {
+		return Err(Error::MissingBinary(
+			"subwasm, which is required to extract the metadata of a wasm runtime: install it \
+			 with `cargo install --locked --git https://github.com/chevdor/subwasm`"
+				.into(),
+		));
+	}
+	let output = tempfile::NamedTempFile::new()?;
+	let result = cmd(
+		"subwasm",
+		vec![
+			"metadata".into(),
+			"--format=scale".into(),
+			format!("--output={}", output.path().display()),
+			path.display().to_string(),
+		],
+	)
+	.stdout_null()
+	.stderr_capture()
+	.unchecked()
+	.run()?;
+	if !result.status.success() {
+		return Err(Error::Config(format!(
+			"unable to extract the metadata of {}: {}",
+			path.display(),
+			String::from_utf8_lossy(&result.stderr).trim()
+		)));
+	}
+	decode_metadata(&std::fs::read(output.path())?)
+}
+
+/// Decodes metadata, either as returned by a runtime, prefixed by its length, or as is.
+fn decode_metadata(bytes: &[u8]) -> Result<Metadata, Error> {
+	let metadata = match bytes.starts_with(b"meta") {
+		true => bytes.to_vec(),
+		false => Vec::<u8>::decode(&mut &bytes[..])
+			.map_err(|e| Error::Config(format!("invalid metadata: {e}")))?,
+	};
+	Metadata::decode(&mut &metadata[..])
+		.map_err(|e| Error::Config(format!("invalid metadata: {e}")))
+}
+
+/// A kind of item declared by a pallet.
+#[derive(Clone, Copy, Debug, Display, Eq, Ord, PartialEq, PartialOrd)]
+#[strum(serialize_all = "lowercase")]
+pub enum ItemKind {
+	/// A dispatchable call.
+	Call,
+	/// A storage item.
+	Storage,
+	/// A constant.
+	Constant,
+}
+
+/// The pallets of a runtime, along with the items each declares, identified by their kind and
+/// name, and the hash of their shape, which changes whenever their types (or, for constants,
+/// their values) change.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RuntimeSummary {
+	pallets: BTreeMap<String, BTreeMap<(ItemKind, String), [u8; 32]>>,
+}
+
+impl From<&Metadata> for RuntimeSummary {
+	fn from(metadata: &Metadata) -> Self {
+		let mut pallets = BTreeMap::new();
+		for pallet in metadata.pallets() {
+			let mut items = BTreeMap::new();
+			for call in pallet.call_variants().unwrap_or_default() {
+				if let Some(hash) = pallet.call_hash(&call.name) {
+					items.insert((ItemKind::Call, call.name.clone()), hash);
+				}
+			}
+			for entry in pallet.storage().map(|s| s.entries()).unwrap_or_default() {
+				if let Some(hash) = pallet.storage_hash(entry.name()) {
+					items.insert((ItemKind::Storage, entry.name().to_string()), hash);
+				}
+			}
+			for constant in pallet.constants() {
+				if let Some(hash) = pallet.constant_hash(constant.name()) {
+					items.insert((ItemKind::Constant, constant.name().to_string()), hash);
+				}
+			}
+			pallets.insert(pallet.name().to_string(), items);
+		}
+		Self { pallets }
+	}
+}
+
+/// How an item differs between two runtimes.
+#[derive(Clone, Copy, Debug, Display, Eq, PartialEq)]
+#[strum(serialize_all = "lowercase")]
+pub enum Change {
+	/// Only declared by the new runtime.
+	Added,
+	/// Only declared by the old runtime.
+	Removed,
+	/// Declared by both runtimes, but with a different shape.
+	Changed,
+}
+
+/// A difference between the metadata of two runtimes.
+#[derive(Clone, Debug, PartialEq)]
+pub enum RuntimeDifference {
+	/// A pallet which was added or removed.
+	Pallet { pallet: String, change: Change },
+	/// An item of a pallet present in both runtimes which was added, removed or changed.
+	Item { pallet: String, kind: ItemKind, name: String, change: Change },
+}
+
+/// The differences between the metadata of the `old` and `new` runtimes, ordered by pallet.
+///
+/// # Arguments
+///
+/// * `old` - the metadata of the old runtime
+/// * `new` - the metadata of the new runtime
+pub fn diff_runtimes(old: &RuntimeSummary, new: &RuntimeSummary) -> Vec<RuntimeDifference> {
+	let mut differences = Vec::new();
+	let pallets: BTreeSet<_> = old.pallets.keys().chain(new.pallets.keys()).collect();
+	for pallet in pallets {
+		let (old_items, new_items) = match (old.pallets.get(pallet), new.pallets.get(pallet)) {
+			(Some(old_items), Some(new_items)) => (old_items, new_items),
+			(old_items, _) => {
+				let change = if old_items.is_some() { Change::Removed } else { Change::Added };
+				differences.push(RuntimeDifference::Pallet { pallet: pallet.clone(), change });
+				continue;
+			},
+		};
+		let items: BTreeSet<_> = old_items.keys().chain(new_items.keys()).collect();
+		for key in items {
+			let (kind, name) = key;
+			let change = match (old_items.get(key), new_items.get(key)) {
+				(Some(old), Some(new)) if old == new => continue,
+				(Some(_), Some(_)) => Change::Changed,
+				(Some(_), None) => Change::Removed,
+				_ => Change::Added,
+			};
+			differences.push(RuntimeDifference::Item {
+				pallet: pallet.clone(),
+				kind: *kind,
+				name: name.clone(),
+				change,
+			});
+		}
+	}
+	differences
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+
+	fn summary(pallets: &[(&str, &[(ItemKind, &str, u8)])]) -> RuntimeSummary {
+		RuntimeSummary {
+			pallets: pallets
+				.iter()
+				.map(|(pallet, items)| {
+					let items = items
+						.iter()
+						.map(|(kind, name, hash)| ((*kind, name.to_string()), [*hash; 32]))
+						.collect();
+					(pallet.to_string(), items)
+				})
+				.collect(),
+		}
+	}
+
+	#[test]
+	fn test_runtime_source() -> Result<()> {
+		assert_eq!(
+			"wss://rpc.polkadot.io".parse::<RuntimeSource>()?,
+			RuntimeSource::Chain(Url::parse("wss://rpc.polkadot.io")?)
+		);
+		assert_eq!(
+			"runtime.wasm".parse::<RuntimeSource>()?,
+			RuntimeSource::Wasm(PathBuf::from("runtime.wasm"))
+		);
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_missing_wasm() {
+		let source = RuntimeSource::Wasm(PathBuf::from("missing.wasm"));
+		assert!(matches!(source.metadata().await, Err(Error::Config(..))));
+	}
+
+	#[test]
+	fn test_decode_invalid_metadata() {
+		assert!(matches!(decode_metadata(b"meta"), Err(Error::Config(..))));
+		assert!(matches!(decode_metadata(&[]), Err(Error::Config(..))));
+	}
+
+	#[test]
+	fn test_diff_runtimes() {
+		use ItemKind::*;
+		let old = summary(&[
+			("Balances", &[(Call, "transfer", 1), (Storage, "Account", 2), (Constant, "ED", 3)]),
+			("Sudo", &[(Call, "sudo", 4)]),
+		]);
+		let new = summary(&[
+			(
+				"Balances",
+				&[(Call, "transfer_allow_death", 1), (Storage, "Account", 2), (Constant, "ED", 5)],
+			),
+			("Treasury", &[]),
+		]);
+		let item = |kind, name: &str, change| RuntimeDifference::Item {
+			pallet: "Balances".into(),
+			kind,
+			name: name.into(),
+			change,
+		};
+		assert_eq!(
+			diff_runtimes(&old, &new),
+			[
+				item(Call, "transfer", Change::Removed),
+				item(Call, "transfer_allow_death", Change::Added),
+				item(Constant, "ED", Change::Changed),
+				RuntimeDifference::Pallet { pallet: "Sudo".into(), change: Change::Removed },
+				RuntimeDifference::Pallet { pallet: "Treasury".into(), change: Change::Added },
+			]
+		);
+		assert!(diff_runtimes(&old, &old).is_empty());
+	}
+}
@@ -0,0 +1,191 @@
+// SPDX-License-Identifier: GPL-3.0
+use crate::{
+	errors::Error,
+	relay::{read_hex, submit, sudo, sudo_unchecked_weight},
+};
+use std::{
+	fs,
+	path::Path,
+	time::{Duration, Instant},
+};
+use strum_macros::{Display, EnumString};
+use subxt::{config::Hasher, dynamic::Value, Config, OnlineClient, PolkadotConfig};
+use url::Url;
+
+/// The interval at which the spec version of a chain is checked while awaiting an upgrade.
+const POLL_INTERVAL: Duration = Duration::from_secs(6);
+/// The well-known storage key of the code of the runtime.
+const CODE: &[u8] = b":code";
+
+/// How the runtime of a chain is upgraded.
+#[derive(Clone, Copy, Debug, Default, Display, EnumString, PartialEq)]
+#[strum(serialize_all = "kebab-case")]
+pub enum UpgradeMethod {
+	/// Sets the code of the runtime directly, using `System::set_code`.
+	#[default]
+	SetCode,
+	/// Sets the code of the runtime using `System::set_code_without_checks`, so that the spec
+	/// version of the new runtime need not be increased, as when iterating during development.
+	SetCodeWithoutChecks,
+	/// Authorizes the hash of the code using `System::authorize_upgrade`, and then enacts the
+	/// upgrade using `System::apply_authorized_upgrade`.
+	Authorize,
+}
+
+/// Reads the runtime at `path`, either as a wasm binary or as hex encoded bytes.
+///
+/// # Arguments
+///
+/// * `path` - path to the runtime
+pub fn read_runtime(path: &Path) -> Result<Vec<u8>, Error> {
+	let code = fs::read(path)?;
+	// Hex encoded runtimes, such as those exported for registration, are decoded first.
+	match code.starts_with(b"0x") {
+		true => read_hex(path),
+		false => Ok(code),
+	}
+}
+
+/// The spec version of the runtime of the chain at `url`.
+///
+/// # Arguments
+///
+/// * `url` - the endpoint of the chain
+pub async fn spec_version(url: &Url) -> Result<u32, Error> {
+	let client = OnlineClient::<PolkadotConfig>::from_url(url.as_str())
+		.await
+		.map_err(|e| Error::Extrinsic(format!("unable to connect to {url}: {e}")))?;
+	Ok(client.runtime_version().spec_version)
+}
+
+/// Upgrades the runtime of the chain at `url` to `code` using the sudo account of `suri`.
+///
+/// # Arguments
+///
+/// * `url` - the endpoint of the chain
+/// * `suri` - the secret key URI of the sudo account of the chain
+/// * `code` - the code of the new runtime
+/// * `method` - how the runtime is upgraded
+pub async fn upgrade_runtime(
+	url: &Url,
+	suri: &str,
+	code: Vec<u8>,
+	method: UpgradeMethod,
+) -> Result<(), Error> {
+	let system = |call: &str, field: &str, value: Value| {
+		Value::unnamed_variant("System", [Value::named_variant(call, [(field, value)])])
+	};
+	let result = match method {
+		UpgradeMethod::SetCode => {
+			sudo_unchecked_weight(url, suri, system("set_code", "code", Value::from_bytes(code)))
+				.await
+		},
+		UpgradeMethod::Authorize => {
+			let hash = <PolkadotConfig as Config>::Hasher::hash(&code);
+			let authorize = system("authorize_upgrade", "code_hash", Value::from_bytes(hash));
+			sudo(url, suri, authorize).await?;
+			let apply = subxt::dynamic::tx(
+				"System",
+				"apply_authorized_upgrade",
+				vec![Value::from_bytes(code)],
+			);
+			submit(url, suri, &apply).await.map(|_| ())
+		},
+	};
+	result.map_err(|e| Error::Extrinsic(format!("unable to upgrade the runtime: {e}")))
+}
+
+/// Waits for the spec version of the chain at `url` to differ from `previous`, as reported once
+/// an upgrade is enacted, returning the new spec version.
+///
+/// # Arguments
+///
+/// * `url` - the endpoint of the chain
+/// * `previous` - the spec version before the upgrade
+/// * `timeout` - the time after which waiting is abandoned
+pub async fn wait_for_spec_version(
+	url: &Url,
+	previous: u32,
+	timeout: Duration,
+) -> Result<u32, Error> {
+	let started = Instant::now();
+	loop {
+		let current = spec_version(url).await?;
+		if current != previous {
+			return Ok(current);
+		}
+		if started.elapsed() > timeout {
+			return Err(Error::Extrinsic(format!(
+				"the spec version is still {previous} after {}s",
+				timeout.as_secs()
+			)));
+		}
+		tokio::time::sleep(POLL_INTERVAL).await;
+	}
+}
+
+/// Waits for the code of the runtime of the chain at `url` to be `code`, as stored once an upgrade
+/// is enacted, regardless of whether its spec version changed.
+///
+/// # Arguments
+///
+/// * `url` - the endpoint of the chain
+/// * `code` - the code of the new runtime
+/// * `timeout` - the time after which waiting is abandoned
+pub async fn wait_for_code(url: &Url, code: &[u8], timeout: Duration) -> Result<(), Error> {
+	let started = Instant::now();
+	loop {
+		let client = OnlineClient::<PolkadotConfig>::from_url(url.as_str())
+			.await
+			.map_err(|e| Error::Extrinsic(format!("unable to connect to {url}: {e}")))?;
+		let current = client
+			.storage()
+			.at_latest()
+			.await
+			.map_err(|e| Error::Extrinsic(format!("{e}")))?
+			.fetch_raw(CODE)
+			.await
+			.map_err(|e| Error::Extrinsic(format!("{e}")))?;
+		if current.as_deref() == Some(code) {
+			return Ok(());
+		}
+		if started.elapsed() > timeout {
+			return Err(Error::Extrinsic(format!(
+				"the new runtime was not enacted after {}s",
+				timeout.as_secs()
+			)));
+		}
+		tokio::time::sleep(POLL_INTERVAL).await;
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+	use std::str::FromStr;
+
+	#[test]
+	fn test_upgrade_method() -> Result<()> {
+		assert_eq!(UpgradeMethod::from_str("authorize")?, UpgradeMethod::Authorize);
+		assert_eq!(UpgradeMethod::default().to_string(), "set-code");
+		assert_eq!(
+			UpgradeMethod::from_str("set-code-without-checks")?,
+			UpgradeMethod::SetCodeWithoutChecks
+		);
+		Ok(())
+	}
+
+	#[test]
+	fn test_read_runtime() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let wasm = temp_dir.path().join("runtime.wasm");
+		fs::write(&wasm, [0x00, 0x61, 0x73, 0x6d])?;
+		assert_eq!(read_runtime(&wasm)?, [0x00, 0x61, 0x73, 0x6d]);
+		let hex = temp_dir.path().join("genesis-wasm");
+		fs::write(&hex, "0x0061736d\n")?;
+		assert_eq!(read_runtime(&hex)?, [0x00, 0x61, 0x73, 0x6d]);
+		assert!(matches!(read_runtime(&temp_dir.path().join("missing")), Err(Error::IO(..))));
+		Ok(())
+	}
+}
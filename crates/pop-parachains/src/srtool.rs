@@ -0,0 +1,212 @@
+// SPDX-License-Identifier: GPL-3.0
+use crate::{
+	build::{parse, runtime_crate, Profile},
+	errors::Error,
+};
+use duct::cmd;
+use serde_json::Value;
+use std::{
+	fs,
+	path::{Path, PathBuf},
+};
+
+/// The srtool image, tagged by the version of the Rust toolchain it builds with.
+const SRTOOL_IMAGE: &str = "docker.io/paritytech/srtool";
+/// The tag of the srtool image used when the project does not pin a Rust toolchain version.
+pub const SRTOOL_DEFAULT_TAG: &str = "1.81.0";
+/// The name of the build digest written alongside the runtime.
+const DIGEST: &str = "srtool-digest.json";
+
+/// The artifacts of a deterministic runtime build.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DeterministicArtifacts {
+	/// The compact runtime wasm.
+	pub compact: PathBuf,
+	/// The compressed runtime wasm, as submitted when upgrading the runtime.
+	pub compressed: PathBuf,
+	/// The hash of the proposal setting the code of the runtime to the compressed wasm.
+	pub proposal_hash: String,
+	/// The digest of the build, as reported by srtool.
+	pub digest: PathBuf,
+}
+
+/// Builds the runtime of the parachain at `path` deterministically using srtool, run within a
+/// container, so that the resulting wasm can be reproduced and verified by others. The build
+/// digest reported by srtool is written alongside the compressed wasm.
+///
+/// # Arguments
+///
+/// * `path` - path to the project
+/// * `profile` - the profile with which the runtime is built
+/// * `features` - the cargo features enabled
+pub fn build_deterministic_runtime(
+	path: &Path,
+	profile: Profile,
+	features: &[String],
+) -> Result<DeterministicArtifacts, Error> {
+	if profile == Profile::Dev {
+		return Err(Error::Config(
+			"deterministic builds require the `release` or `production` profile".into(),
+		));
+	}
+	if cmd("docker", ["version"]).stdout_null().stderr_null().run().is_err() {
+		return Err(Error::MissingBinary(
+			"docker, which is required to run srtool: install it from \
+			 https://docs.docker.com/get-docker"
+				.into(),
+		));
+	}
+	let path = path.canonicalize()?;
+	let (dir, package) = runtime_crate(&path)?;
+	let runtime_dir = dir
+		.strip_prefix(&path)
+		.map_err(|_| Error::Config("the runtime is not within the project".into()))?;
+	let args = srtool_args(&path, runtime_dir, &package, profile, features)?;
+	tracing::debug!(?args, "running srtool");
+	let output = cmd("docker", args).stdout_capture().stderr_capture().unchecked().run()?;
+	if !output.status.success() {
+		return Err(Error::Build(String::from_utf8_lossy(&output.stderr).trim().to_string()));
+	}
+	artifacts(&path, &String::from_utf8_lossy(&output.stdout))
+}
+
+/// The arguments running srtool within a container, building `package` within `runtime_dir` of
+/// the project mounted from `path`.
+fn srtool_args(
+	path: &Path,
+	runtime_dir: &Path,
+	package: &str,
+	profile: Profile,
+	features: &[String],
+) -> Result<Vec<String>, Error> {
+	let mut args = vec![
+		"run".into(),
+		"--rm".into(),
+		format!("--env=PACKAGE={package}"),
+		format!("--env=RUNTIME_DIR={}", runtime_dir.display()),
+		format!("--env=PROFILE={profile}"),
+		format!("--volume={}:/build", path.display()),
+	];
+	if !features.is_empty() {
+		args.push(format!("--env=BUILD_OPTS=--features {}", features.join(",")));
+	}
+	args.extend([
+		format!("{SRTOOL_IMAGE}:{}", srtool_tag(path)?),
+		"build".into(),
+		"--app".into(),
+		"--json".into(),
+		"-cM".into(),
+	]);
+	Ok(args)
+}
+
+/// The tag of the srtool image building the project at `path`: the Rust toolchain version pinned
+/// by its `rust-toolchain.toml`, if any.
+fn srtool_tag(path: &Path) -> Result<String, Error> {
+	let toolchain = path.join("rust-toolchain.toml");
+	if !toolchain.exists() {
+		return Ok(SRTOOL_DEFAULT_TAG.into());
+	}
+	let channel = parse(&toolchain)?
+		.get("toolchain")
+		.and_then(|t| t.get("channel"))
+		.and_then(|c| c.as_str())
+		.map(|c| c.to_string());
+	// Only numbered toolchains are published as srtool images.
+	Ok(channel
+		.filter(|c| !c.is_empty() && c.chars().all(|c| c.is_ascii_digit() || c == '.'))
+		.unwrap_or(SRTOOL_DEFAULT_TAG.into()))
+}
+
+/// The artifacts of a build of the project at `path`, as reported by the digest srtool outputs as
+/// the last line of its output, which is also written alongside the compressed wasm.
+fn artifacts(path: &Path, output: &str) -> Result<DeterministicArtifacts, Error> {
+	let digest = output
+		.lines()
+		.rev()
+		.find(|l| l.trim_start().starts_with('{'))
+		.ok_or(Error::Build("srtool did not report a build digest".into()))?;
+	let json: Value = serde_json::from_str(digest)
+		.map_err(|e| Error::Build(format!("invalid srtool build digest: {e}")))?;
+	let field = |runtime: &str, key: &str| {
+		json["runtimes"][runtime][key]
+			.as_str()
+			.map(|v| v.to_string())
+			.ok_or(Error::Build(format!("the srtool build digest has no {runtime} {key}")))
+	};
+	let compact = path.join(field("compact", "wasm")?);
+	let compressed = path.join(field("compressed", "wasm")?);
+	let proposal_hash = field("compressed", "prop")?;
+	let digest_path = compressed.parent().unwrap_or(path).join(DIGEST);
+	fs::write(&digest_path, format!("{json:#}"))?;
+	Ok(DeterministicArtifacts { compact, compressed, proposal_hash, digest: digest_path })
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+
+	const OUTPUT: &str = r#"🧰 Substrate Runtime Toolbox - srtool v0.16.0 🧰
+{"gen":"srtool v0.16.0","rustc":"rustc 1.81.0","pkg":"parachain-template-runtime","runtimes":{"compact":{"wasm":"runtime/target/srtool/release/wbuild/parachain-template-runtime/parachain_template_runtime.compact.wasm","prop":"0x11"},"compressed":{"wasm":"runtime/target/srtool/release/wbuild/parachain-template-runtime/parachain_template_runtime.compact.compressed.wasm","prop":"0x22"}}}"#;
+
+	#[test]
+	fn test_srtool_args() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let path = temp_dir.path();
+		let args = srtool_args(path, Path::new("runtime"), "my-runtime", Profile::Production, &[])?;
+		assert!(args.contains(&"--env=PACKAGE=my-runtime".to_string()));
+		assert!(args.contains(&"--env=RUNTIME_DIR=runtime".to_string()));
+		assert!(args.contains(&"--env=PROFILE=production".to_string()));
+		assert!(args.contains(&format!("{SRTOOL_IMAGE}:{SRTOOL_DEFAULT_TAG}")));
+		assert!(!args.iter().any(|a| a.starts_with("--env=BUILD_OPTS")));
+
+		fs::write(path.join("rust-toolchain.toml"), "[toolchain]\nchannel = \"1.77.0\"\n")?;
+		let features = ["on-chain-release-build".to_string()];
+		let args =
+			srtool_args(path, Path::new("runtime"), "my-runtime", Profile::Release, &features)?;
+		assert!(args.contains(&"--env=BUILD_OPTS=--features on-chain-release-build".to_string()));
+		assert!(args.contains(&format!("{SRTOOL_IMAGE}:1.77.0")));
+		Ok(())
+	}
+
+	#[test]
+	fn test_srtool_tag() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let path = temp_dir.path();
+		assert_eq!(srtool_tag(path)?, SRTOOL_DEFAULT_TAG);
+		fs::write(path.join("rust-toolchain.toml"), "[toolchain]\nchannel = \"stable\"\n")?;
+		assert_eq!(srtool_tag(path)?, SRTOOL_DEFAULT_TAG);
+		Ok(())
+	}
+
+	#[test]
+	fn test_artifacts() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let path = temp_dir.path();
+		let wbuild = path.join("runtime/target/srtool/release/wbuild/parachain-template-runtime");
+		fs::create_dir_all(&wbuild)?;
+
+		let artifacts = artifacts(path, OUTPUT)?;
+		assert_eq!(
+			artifacts.compressed,
+			wbuild.join("parachain_template_runtime.compact.compressed.wasm")
+		);
+		assert_eq!(artifacts.compact, wbuild.join("parachain_template_runtime.compact.wasm"));
+		assert_eq!(artifacts.proposal_hash, "0x22");
+		assert_eq!(artifacts.digest, wbuild.join(DIGEST));
+		let digest: Value = serde_json::from_str(&fs::read_to_string(&artifacts.digest)?)?;
+		assert_eq!(digest["pkg"], "parachain-template-runtime");
+
+		assert!(matches!(super::artifacts(path, "no digest"), Err(Error::Build(..))));
+		Ok(())
+	}
+
+	#[test]
+	fn test_dev_profile_is_not_deterministic() {
+		assert!(matches!(
+			build_deterministic_runtime(Path::new("./"), Profile::Dev, &[]),
+			Err(Error::Config(..))
+		));
+	}
+}
@@ -0,0 +1,223 @@
+// SPDX-License-Identifier: GPL-3.0
+use crate::{
+	call::parse_value,
+	errors::Error,
+	relay::{decode_hex, to_hex},
+};
+use scale_info::TypeDef;
+use serde::Serialize;
+use subxt::{
+	backend::{
+		legacy::LegacyRpcMethods,
+		rpc::{rpc_params, RpcClient},
+	},
+	ext::scale_value::scale::decode_as_type,
+	metadata::types::{StorageEntryModifier, StorageEntryType, StorageHasher},
+	Metadata, OnlineClient, PolkadotConfig,
+};
+use url::Url;
+
+/// The number of bytes prefixing the keys of every storage entry: the hashes of the names of the
+/// pallet and of the entry.
+const PREFIX_LEN: usize = 32;
+
+/// A value stored by a pallet, decoded using the metadata of the chain.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct StorageItem {
+	/// The hex encoded storage key, which can be used to look up the value again.
+	pub key: String,
+	/// The decoded keys of the value within its map, as far as they can be recovered from the
+	/// storage key.
+	pub keys: Vec<String>,
+	/// The decoded value.
+	pub value: String,
+}
+
+/// A page of the values stored by a pallet.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct StoragePage {
+	/// The values within the page.
+	pub items: Vec<StorageItem>,
+	/// The storage key after which the next page starts, if any.
+	pub next: Option<String>,
+}
+
+/// Queries the storage `entry` of `pallet` on the chain at `url`, decoding the values using the
+/// metadata of the chain. When a key is provided for each of the keys of the entry, the value at
+/// those keys is fetched, otherwise the values within the map prefixed by the keys provided are
+/// iterated, a page at a time.
+///
+/// # Arguments
+///
+/// * `url` - the endpoint of the chain
+/// * `pallet` - the name of the pallet
+/// * `entry` - the name of the storage entry
+/// * `keys` - the keys of the value, or a prefix of them, as parsed by [`parse_value`]
+/// * `at` - the hash or number of the block queried, otherwise the best block
+/// * `page_size` - the number of values within a page, when iterating
+/// * `start` - the storage key after which the page starts, when iterating
+pub async fn query_storage(
+	url: &Url,
+	pallet: &str,
+	entry: &str,
+	keys: &[String],
+	at: Option<&str>,
+	page_size: u32,
+	start: Option<&str>,
+) -> Result<StoragePage, Error> {
+	let rpc = RpcClient::from_url(url.as_str())
+		.await
+		.map_err(|e| Error::Extrinsic(format!("unable to connect to {url}: {e}")))?;
+	let client = OnlineClient::<PolkadotConfig>::from_rpc_client(rpc.clone())
+		.await
+		.map_err(|e| Error::Extrinsic(format!("unable to connect to {url}: {e}")))?;
+	let metadata = client.metadata();
+	let storage = metadata
+		.pallet_by_name(pallet)
+		.and_then(|p| p.storage())
+		.and_then(|s| s.entry_by_name(entry))
+		.ok_or_else(|| Error::Metadata(format!("the chain has no storage `{pallet}::{entry}`")))?;
+	let (hashers, value_ty) = match storage.entry_type() {
+		StorageEntryType::Plain(value_ty) => (Vec::new(), *value_ty),
+		StorageEntryType::Map { hashers, key_ty, value_ty } => (
+			hashers
+				.iter()
+				.cloned()
+				.zip(key_types(&metadata, hashers.len(), *key_ty))
+				.collect(),
+			*value_ty,
+		),
+	};
+	if keys.len() > hashers.len() {
+		return Err(Error::Metadata(format!(
+			"`{pallet}::{entry}` has {} keys, whereas {} were provided",
+			hashers.len(),
+			keys.len()
+		)));
+	}
+	let values = keys.iter().map(|k| parse_value(k)).collect::<Result<Vec<_>, _>>()?;
+	let address = subxt::dynamic::storage(pallet, entry, values);
+	let prefix = client
+		.storage()
+		.address_bytes(&address)
+		.map_err(|e| Error::Metadata(format!("invalid keys of `{pallet}::{entry}`: {e}")))?;
+	let at = block_hash(&rpc, at).await?;
+	let request_err = |e: subxt::Error| Error::Extrinsic(format!("{e}"));
+
+	// A single value, when each key is provided.
+	if keys.len() == hashers.len() {
+		let value: Option<String> = rpc
+			.request("state_getStorage", rpc_params![to_hex(&prefix), &at])
+			.await
+			.map_err(request_err)?;
+		let value = match (value, storage.modifier()) {
+			(Some(value), _) => decode_hex(&value)?,
+			// Values which are not stored default to the default value of the entry.
+			(None, StorageEntryModifier::Default) => storage.default_bytes().to_vec(),
+			(None, StorageEntryModifier::Optional) => return Ok(StoragePage::default()),
+		};
+		let item = StorageItem {
+			key: to_hex(&prefix),
+			keys: keys.to_vec(),
+			value: decode(&metadata, value_ty, &value)?,
+		};
+		return Ok(StoragePage { items: vec![item], next: None });
+	}
+
+	// Otherwise a page of the values within the map.
+	let page: Vec<String> = rpc
+		.request("state_getKeysPaged", rpc_params![to_hex(&prefix), page_size, start, &at])
+		.await
+		.map_err(request_err)?;
+	let mut items = Vec::new();
+	for key in &page {
+		let value: Option<String> = rpc
+			.request("state_getStorage", rpc_params![key, &at])
+			.await
+			.map_err(request_err)?;
+		let Some(value) = value else { continue };
+		items.push(StorageItem {
+			key: key.clone(),
+			keys: decode_keys(&metadata, &hashers, &decode_hex(key)?),
+			value: decode(&metadata, value_ty, &decode_hex(&value)?)?,
+		});
+	}
+	let next = match page.len() == page_size as usize {
+		true => page.last().cloned(),
+		false => None,
+	};
+	Ok(StoragePage { items, next })
+}
+
+/// The hash of the block `at`, provided as either a hash or a number, if any.
+async fn block_hash(rpc: &RpcClient, at: Option<&str>) -> Result<Option<String>, Error> {
+	let Some(at) = at.map(str::trim) else { return Ok(None) };
+	if at.starts_with("0x") {
+		return Ok(Some(at.to_string()));
+	}
+	let number: u64 = at
+		.parse()
+		.map_err(|_| Error::Config(format!("invalid block `{at}`, expected a hash or a number")))?;
+	let hash = LegacyRpcMethods::<PolkadotConfig>::new(rpc.clone())
+		.chain_get_block_hash(Some(number.into()))
+		.await
+		.map_err(|e| Error::Extrinsic(format!("{e}")))?
+		.ok_or_else(|| Error::Config(format!("block {number} could not be found")))?;
+	Ok(Some(format!("{hash:?}")))
+}
+
+/// The types of each of the `count` keys of a map, whose key type is `key_ty`: a tuple of the
+/// types of each key when the map has more than one.
+fn key_types(metadata: &Metadata, count: usize, key_ty: u32) -> Vec<u32> {
+	if count == 1 {
+		return vec![key_ty];
+	}
+	match metadata.types().resolve(key_ty).map(|ty| &ty.type_def) {
+		Some(TypeDef::Tuple(tuple)) if tuple.fields.len() == count => {
+			tuple.fields.iter().map(|f| f.id).collect()
+		},
+		// The keys are then counted, despite not being decoded.
+		_ => vec![key_ty; count],
+	}
+}
+
+/// Decodes the keys of a value within a map from its storage `key`, as far as they can be
+/// recovered: keys hashed without being concatenated to their hash cannot be.
+fn decode_keys(metadata: &Metadata, hashers: &[(StorageHasher, u32)], key: &[u8]) -> Vec<String> {
+	let mut keys = Vec::new();
+	let mut bytes = key.get(PREFIX_LEN..).unwrap_or_default();
+	for (hasher, ty) in hashers {
+		let hash_len = match hasher {
+			StorageHasher::Blake2_128Concat => 16,
+			StorageHasher::Twox64Concat => 8,
+			StorageHasher::Identity => 0,
+			_ => break,
+		};
+		let Some(rest) = bytes.get(hash_len..) else { break };
+		bytes = rest;
+		match decode_as_type(&mut bytes, *ty, metadata.types()) {
+			Ok(value) => keys.push(value.remove_context().to_string()),
+			Err(_) => break,
+		}
+	}
+	keys
+}
+
+/// Decodes `bytes` as a value of the type `ty`.
+fn decode(metadata: &Metadata, ty: u32, mut bytes: &[u8]) -> Result<String, Error> {
+	decode_as_type(&mut bytes, ty, metadata.types())
+		.map(|value| value.remove_context().to_string())
+		.map_err(|e| Error::Metadata(format!("unable to decode the value: {e}")))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[tokio::test]
+	async fn query_storage_fails_without_chain() {
+		let url = Url::parse("ws://127.0.0.1:9").expect("valid url");
+		let query = query_storage(&url, "System", "Number", &[], None, 100, None).await;
+		assert!(matches!(query, Err(Error::Extrinsic(..))));
+	}
+}
@@ -0,0 +1,197 @@
+// SPDX-License-Identifier: GPL-3.0
+use crate::errors::Error;
+use duct::cmd;
+use std::path::Path;
+
+/// Options for testing a parachain project.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TestOptions {
+	/// Also run the benchmarks of the runtime and its pallets in test mode, by enabling the
+	/// `runtime-benchmarks` feature.
+	pub benchmarks: bool,
+	/// Only run the tests whose names contain the filter.
+	pub filter: Option<String>,
+}
+
+/// The results of the tests of a crate, or of one of its integration test targets.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CrateReport {
+	/// The name of the crate or integration test target.
+	pub name: String,
+	/// The number of tests passed.
+	pub passed: u32,
+	/// The number of tests failed.
+	pub failed: u32,
+	/// The number of tests ignored.
+	pub ignored: u32,
+	/// The names of the tests failed.
+	pub failures: Vec<String>,
+}
+
+/// The results of testing a parachain project.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TestReport {
+	/// The results of each crate with tests, in the order they were run.
+	pub crates: Vec<CrateReport>,
+	/// The full output of the tests.
+	pub output: String,
+}
+
+impl TestReport {
+	/// Whether every test passed.
+	pub fn success(&self) -> bool {
+		self.crates.iter().all(|c| c.failed == 0)
+	}
+}
+
+/// Runs the unit, integration and documentation tests of every crate of the parachain project at
+/// `path`, including those of its runtime, summarizing the results per crate.
+///
+/// # Arguments
+///
+/// * `path` - path to the project
+/// * `options` - the options of the tests
+pub fn test_parachain(path: &Path, options: &TestOptions) -> Result<TestReport, Error> {
+	let mut args = vec!["test", "--workspace", "--no-fail-fast"];
+	if options.benchmarks {
+		args.push("--features=runtime-benchmarks");
+	}
+	if let Some(filter) = &options.filter {
+		args.push(filter);
+	}
+	tracing::debug!(path = %path.display(), ?args, "running tests");
+	let result = cmd("cargo", args)
+		.dir(path)
+		.stderr_to_stdout()
+		.stdout_capture()
+		.unchecked()
+		.run()
+		.map_err(|e| Error::TestCommand(format!("{e}")))?;
+	let output = String::from_utf8_lossy(&result.stdout).to_string();
+	let crates = parse_test_output(&output);
+	// Tests failing to compile are only reported by the status of the command.
+	if !result.status.success() && crates.iter().all(|c| c.failed == 0) {
+		return Err(Error::TestCommand(format!(
+			"the tests could not be run:\n{}",
+			output.lines().filter(|l| l.starts_with("error")).collect::<Vec<_>>().join("\n")
+		)));
+	}
+	Ok(TestReport { crates, output })
+}
+
+/// Summarizes the output of `cargo test` per crate.
+fn parse_test_output(output: &str) -> Vec<CrateReport> {
+	let mut crates: Vec<CrateReport> = Vec::new();
+	let mut current: Option<String> = None;
+	for line in output.lines().map(|l| l.trim()) {
+		if let Some(target) = line.strip_prefix("Running ") {
+			// e.g. `Running unittests src/lib.rs (target/debug/deps/pallet_template-0f1c2a)`
+			current = target
+				.rsplit_once('(')
+				.and_then(|(_, binary)| binary.trim_end_matches(')').rsplit('/').next())
+				.map(|binary| binary.rsplit_once('-').map_or(binary, |(name, _)| name).to_string());
+		} else if let Some(name) = line.strip_prefix("Doc-tests ") {
+			current = Some(name.to_string());
+		} else if let Some(name) = current.as_ref() {
+			let index = match crates.iter().position(|c| &c.name == name) {
+				Some(index) => index,
+				None => {
+					crates.push(CrateReport { name: name.clone(), ..Default::default() });
+					crates.len() - 1
+				},
+			};
+			let report = &mut crates[index];
+			if let Some(test) =
+				line.strip_prefix("test ").and_then(|t| t.strip_suffix(" ... FAILED"))
+			{
+				report.failures.push(test.to_string());
+			} else if let Some(summary) = line.strip_prefix("test result: ") {
+				// e.g. `test result: ok. 5 passed; 0 failed; 1 ignored; 0 measured; ...`
+				for (count, label) in summary
+					.split(['.', ';'])
+					.filter_map(|part| part.trim().split_once(' '))
+					.filter_map(|(count, label)| count.parse::<u32>().ok().map(|c| (c, label)))
+				{
+					match label {
+						"passed" => report.passed += count,
+						"failed" => report.failed += count,
+						"ignored" => report.ignored += count,
+						_ => {},
+					}
+				}
+			}
+		}
+	}
+	crates.retain(|c| c.passed + c.failed + c.ignored > 0);
+	crates
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const OUTPUT: &str = r#"
+   Compiling pallet-template v0.1.0
+    Finished `test` profile [unoptimized + debuginfo] target(s) in 12.00s
+     Running unittests src/lib.rs (target/debug/deps/pallet_template-0f1c2a3b4c5d6e7f)
+
+running 3 tests
+test tests::it_works_for_default_value ... ok
+test tests::correct_error_for_none_value ... FAILED
+test weights::ignored ... ignored
+
+failures:
+
+---- tests::correct_error_for_none_value stdout ----
+thread 'tests::correct_error_for_none_value' panicked
+
+test result: FAILED. 1 passed; 1 failed; 1 ignored; 0 measured; 0 filtered out; finished in 0.01s
+
+     Running unittests src/lib.rs (target/debug/deps/parachain_template_runtime-a1b2c3d4e5f6a7b8)
+
+running 0 tests
+
+test result: ok. 0 passed; 0 failed; 0 ignored; 0 measured; 0 filtered out; finished in 0.00s
+
+     Running tests/xcm.rs (target/debug/deps/xcm-0a1b2c3d4e5f6a7b)
+
+running 2 tests
+test transfer ... ok
+test teleport ... ok
+
+test result: ok. 2 passed; 0 failed; 0 ignored; 0 measured; 0 filtered out; finished in 1.20s
+
+   Doc-tests pallet_template
+
+running 1 test
+test src/lib.rs - (line 12) ... ok
+
+test result: ok. 1 passed; 0 failed; 0 ignored; 0 measured; 0 filtered out; finished in 0.30s
+"#;
+
+	#[test]
+	fn test_parse_test_output() {
+		let crates = parse_test_output(OUTPUT);
+		assert_eq!(
+			crates,
+			[
+				CrateReport {
+					name: "pallet_template".into(),
+					passed: 2,
+					failed: 1,
+					ignored: 1,
+					failures: vec!["tests::correct_error_for_none_value".into()],
+				},
+				CrateReport { name: "xcm".into(), passed: 2, ..Default::default() },
+			]
+		);
+		let report = TestReport { crates, output: OUTPUT.into() };
+		assert!(!report.success());
+	}
+
+	#[test]
+	fn test_parse_empty_output() {
+		assert!(parse_test_output("error: could not compile `pallet-template`").is_empty());
+		assert!(TestReport::default().success());
+	}
+}
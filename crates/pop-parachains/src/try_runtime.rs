@@ -0,0 +1,268 @@
+// SPDX-License-Identifier: GPL-3.0
+use crate::{build::runtime_wasm, errors::Error};
+use duct::cmd;
+use pop_common::{platform::Arch, Binary, GitHub, Platform, Source};
+use std::path::{Path, PathBuf};
+use strum_macros::{Display, EnumString};
+use url::Url;
+
+const TRY_RUNTIME_CLI: &str = "https://github.com/paritytech/try-runtime-cli";
+/// The binary executing runtime upgrades against live chain state.
+const TRY_RUNTIME: &str = "try-runtime";
+/// The release of `try-runtime-cli` used when none is specified.
+pub const TRY_RUNTIME_DEFAULT_VERSION: &str = "v0.8.0";
+
+/// The `try-runtime` binary of a release of `try-runtime-cli`, as cached within `cache`.
+///
+/// # Arguments
+///
+/// * `cache` - path to the local cache
+/// * `version` - the release of `try-runtime-cli`, as per its tag (e.g. `v0.8.0`)
+pub fn try_runtime(cache: &Path, version: &str) -> Binary {
+	let name = format!("{TRY_RUNTIME}-{version}");
+	let path = Source::cached_path(cache, TRY_RUNTIME, Some(version));
+	let mut sources = Vec::new();
+	if !path.exists() {
+		let repo = Url::parse(TRY_RUNTIME_CLI).expect("repository url valid");
+		match release_asset(Platform::detect().ok()) {
+			Some(asset) => sources.push(Source::Url {
+				name: TRY_RUNTIME.into(),
+				version: version.into(),
+				url: GitHub::release(&repo, version, asset),
+			}),
+			None => sources.push(Source::Git {
+				url: repo,
+				branch: Some(version.into()),
+				package: "try-runtime-cli".into(),
+				binaries: vec![TRY_RUNTIME.into()],
+				version: Some(version.into()),
+			}),
+		}
+	}
+	Binary { name, version: version.into(), path, sources }
+}
+
+/// The release asset of `try-runtime` for `platform`, which is only released for x86_64 Linux, as
+/// a static binary running regardless of the C library.
+///
+/// # Arguments
+///
+/// * `platform` - the platform detected, if supported
+fn release_asset(platform: Option<Platform>) -> Option<&'static str> {
+	platform
+		.filter(|p| p.is_linux() && p.arch == Arch::X86_64)
+		.map(|_| "try-runtime-x86_64-unknown-linux-musl")
+}
+
+/// The checks executed alongside the migrations of a runtime upgrade.
+#[derive(Clone, Copy, Debug, Default, Display, EnumString, PartialEq)]
+#[strum(serialize_all = "kebab-case")]
+pub enum UpgradeChecks {
+	/// No checks.
+	None,
+	/// The `pre_upgrade` and `post_upgrade` checks of each migration.
+	PreAndPost,
+	/// The `try_state` checks of each pallet.
+	TryState,
+	/// All checks.
+	#[default]
+	All,
+}
+
+/// The execution of the `on_runtime_upgrade` hooks of a runtime, compiled with the `try-runtime`
+/// feature, against the state of a live chain.
+#[derive(Clone, Debug, PartialEq)]
+pub struct OnRuntimeUpgrade {
+	/// The runtime wasm.
+	pub runtime: PathBuf,
+	/// The endpoint of a node of the live chain, from which its state is scraped.
+	pub uri: Url,
+	/// The checks executed alongside the migrations.
+	pub checks: UpgradeChecks,
+	/// The expected block time, in milliseconds.
+	pub blocktime: u64,
+	/// Whether to skip checking that the spec version of the runtime is greater than that of the
+	/// live chain.
+	pub disable_spec_version_check: bool,
+}
+
+/// The outcome of a runtime upgrade.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct UpgradeReport {
+	/// Whether the migrations and their checks succeeded.
+	pub success: bool,
+	/// The weight consumed by the migrations, as reported by `try-runtime`.
+	pub weight: Option<String>,
+	/// The checks or migrations which failed.
+	pub failures: Vec<String>,
+	/// The full output of `try-runtime`.
+	pub output: String,
+}
+
+impl OnRuntimeUpgrade {
+	/// The arguments provided to `try-runtime`.
+	pub fn args(&self) -> Vec<String> {
+		let mut args = vec![
+			format!("--runtime={}", self.runtime.display()),
+			"on-runtime-upgrade".into(),
+			format!("--checks={}", self.checks),
+			format!("--blocktime={}", self.blocktime),
+		];
+		if self.disable_spec_version_check {
+			args.push("--disable-spec-version-check".into());
+		}
+		args.extend(["live".into(), format!("--uri={}", self.uri)]);
+		args
+	}
+
+	/// Executes the runtime upgrade using the `try-runtime` binary at `binary`, reporting the
+	/// outcome of its checks and the weight consumed.
+	///
+	/// # Arguments
+	///
+	/// * `binary` - path to the `try-runtime` binary
+	pub fn run(&self, binary: &Path) -> Result<UpgradeReport, Error> {
+		if !self.runtime.exists() {
+			return Err(Error::Config(format!(
+				"the runtime {} does not exist",
+				self.runtime.display()
+			)));
+		}
+		tracing::debug!(binary = %binary.display(), args = ?self.args(), "running try-runtime");
+		let result = cmd(binary, self.args())
+			.stderr_to_stdout()
+			.stdout_capture()
+			.unchecked()
+			.run()
+			.map_err(|e| Error::TestCommand(format!("{e}")))?;
+		let output = String::from_utf8_lossy(&result.stdout).to_string();
+		Ok(UpgradeReport { success: result.status.success(), ..parse_upgrade_output(&output) })
+	}
+}
+
+/// The runtime wasm of the latest release build of the project at `path`, which must be built
+/// with the `try-runtime` feature.
+///
+/// # Arguments
+///
+/// * `path` - path to the project
+pub fn try_runtime_runtime(path: &Path) -> Result<PathBuf, Error> {
+	runtime_wasm(&path.join("target").join("release")).ok_or(Error::Config(
+		"no runtime wasm was found, build the runtime first using `cargo build --release \
+		 --features try-runtime`"
+			.into(),
+	))
+}
+
+/// Extracts the consumed weight and any failures from the output of `try-runtime`.
+fn parse_upgrade_output(output: &str) -> UpgradeReport {
+	let mut report = UpgradeReport { output: output.into(), ..Default::default() };
+	for line in output.lines().map(|l| l.trim()) {
+		if let Some((_, weight)) = line.split_once("Consumed weight = ") {
+			report.weight = Some(weight.trim_end_matches('.').into());
+		} else if line.starts_with("Error:") || line.contains(" ERROR ") || line.contains('❌') {
+			report.failures.push(line.into());
+		}
+	}
+	report
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+	use std::str::FromStr;
+
+	fn upgrade(runtime: PathBuf) -> Result<OnRuntimeUpgrade> {
+		Ok(OnRuntimeUpgrade {
+			runtime,
+			uri: Url::parse("wss://rpc.polkadot.io")?,
+			checks: UpgradeChecks::PreAndPost,
+			blocktime: 6000,
+			disable_spec_version_check: true,
+		})
+	}
+
+	#[test]
+	fn test_upgrade_args() -> Result<()> {
+		assert_eq!(
+			upgrade(PathBuf::from("runtime.compact.compressed.wasm"))?.args(),
+			[
+				"--runtime=runtime.compact.compressed.wasm",
+				"on-runtime-upgrade",
+				"--checks=pre-and-post",
+				"--blocktime=6000",
+				"--disable-spec-version-check",
+				"live",
+				"--uri=wss://rpc.polkadot.io/"
+			]
+		);
+		Ok(())
+	}
+
+	#[test]
+	fn test_upgrade_checks() -> Result<()> {
+		assert_eq!(UpgradeChecks::from_str("try-state")?, UpgradeChecks::TryState);
+		assert_eq!(UpgradeChecks::default().to_string(), "all");
+		Ok(())
+	}
+
+	#[test]
+	fn test_parse_upgrade_output() {
+		let report = parse_upgrade_output(
+			"[2024-06-01T10:00:00Z INFO  try-runtime::cli] 🔬 Running TryRuntime_on_runtime_upgrade\n\
+			 [2024-06-01T10:00:01Z ERROR runtime::template] ❌ post_upgrade: storage version mismatch\n\
+			 [2024-06-01T10:00:01Z INFO  try-runtime::cli] ✅ TryRuntime_on_runtime_upgrade executed \
+			 without errors. Consumed weight = (1234 ps, 56 byte), total weight = (2000000000000 ps, \
+			 5242880 byte) (0.00 %, 0.00 %).\n",
+		);
+		assert_eq!(
+			report.weight.as_deref(),
+			Some("(1234 ps, 56 byte), total weight = (2000000000000 ps, 5242880 byte) (0.00 %, 0.00 %)")
+		);
+		assert_eq!(report.failures.len(), 1);
+		assert!(report.failures[0].contains("storage version mismatch"));
+	}
+
+	#[test]
+	fn test_run_fails_without_runtime() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let upgrade = upgrade(temp_dir.path().join("missing.wasm"))?;
+		assert!(matches!(upgrade.run(Path::new(TRY_RUNTIME)), Err(Error::Config(..))));
+		assert!(matches!(try_runtime_runtime(temp_dir.path()), Err(Error::Config(..))));
+		Ok(())
+	}
+
+	#[test]
+	fn test_try_runtime_binary() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let binary = try_runtime(temp_dir.path(), TRY_RUNTIME_DEFAULT_VERSION);
+		assert_eq!(binary.name, format!("{TRY_RUNTIME}-{TRY_RUNTIME_DEFAULT_VERSION}"));
+		assert_eq!(
+			binary.path,
+			temp_dir
+				.path()
+				.join("bins")
+				.join(TRY_RUNTIME)
+				.join(TRY_RUNTIME_DEFAULT_VERSION)
+				.join(TRY_RUNTIME)
+		);
+		assert_eq!(binary.sources.len(), 1);
+		Ok(())
+	}
+
+	#[test]
+	fn test_release_asset() {
+		use pop_common::platform::Os;
+		let platform = |os, arch| Some(Platform { os, arch });
+		for os in [Os::LinuxGnu, Os::LinuxMusl] {
+			assert_eq!(
+				release_asset(platform(os, Arch::X86_64)),
+				Some("try-runtime-x86_64-unknown-linux-musl")
+			);
+		}
+		assert_eq!(release_asset(platform(Os::LinuxGnu, Arch::Aarch64)), None);
+		assert_eq!(release_asset(platform(Os::MacOs, Arch::Aarch64)), None);
+		assert_eq!(release_asset(None), None);
+	}
+}
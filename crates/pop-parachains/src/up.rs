@@ -1,15 +1,22 @@
 // SPDX-License-Identifier: GPL-3.0
-use crate::errors::Error;
-use crate::utils::git::{Git, GitHub};
+use crate::{
+	binaries::PolkadotRelease,
+	compose::{self, POLKADOT_IMAGE, POLKADOT_PARACHAIN_IMAGE},
+	errors::Error,
+	monitoring::{metrics_targets, MetricsTarget, DEFAULT_PROMETHEUS_PORT},
+	network::{self, NodeEntry},
+	ports::{assign_ports, assign_prometheus_ports, DEFAULT_RPC_PORT},
+};
 use duct::cmd;
 use indexmap::IndexMap;
+use pop_common::{cache::CHAIN_SPECS_DIR, Binary, GitHub, Source};
 use std::{
 	env::current_dir,
-	fs::{copy, metadata, remove_dir_all, write, File},
-	io::{BufRead, Write},
-	os::unix::fs::PermissionsExt,
+	fs::{create_dir_all, remove_dir_all, rename, write},
 	path::{Path, PathBuf},
 };
+use strum::EnumProperty as EnumPropertyT;
+use strum_macros::{AsRefStr, Display, EnumProperty, EnumString, VariantArray};
 use symlink::{remove_symlink_file, symlink_file};
 use tempfile::{Builder, NamedTempFile};
 use toml_edit::{value, DocumentMut, Formatted, Item, Table, Value};
@@ -17,8 +24,174 @@ use url::Url;
 use zombienet_sdk::{Network, NetworkConfig, NetworkConfigExt};
 use zombienet_support::fs::local::LocalFileSystem;
 
-const POLKADOT_SDK: &str = "https://github.com/paritytech/polkadot-sdk";
-const POLKADOT_DEFAULT_VERSION: &str = "v1.11.0";
+/// The relay chain launched for local networks.
+const LOCAL_RELAY_CHAIN: &str = "rococo-local";
+/// The validators of the relay chain launched for local networks.
+const LOCAL_VALIDATORS: [&str; 2] = ["alice", "bob"];
+
+/// A system parachain of the local relay chain, launched using the `polkadot-parachain` binary.
+#[derive(
+	AsRefStr, Clone, Copy, Debug, Display, EnumProperty, EnumString, Eq, PartialEq, VariantArray,
+)]
+pub enum SystemChain {
+	#[strum(
+		ascii_case_insensitive,
+		serialize = "asset-hub",
+		props(ParaId = "1000", Chain = "asset-hub-rococo-local")
+	)]
+	AssetHub,
+	#[strum(
+		ascii_case_insensitive,
+		serialize = "bridge-hub",
+		props(ParaId = "1013", Chain = "bridge-hub-rococo-local")
+	)]
+	BridgeHub,
+	#[strum(
+		ascii_case_insensitive,
+		serialize = "coretime",
+		props(ParaId = "1005", Chain = "coretime-rococo-local")
+	)]
+	Coretime,
+	#[strum(
+		ascii_case_insensitive,
+		serialize = "people",
+		props(ParaId = "1004", Chain = "people-rococo-local")
+	)]
+	People,
+}
+
+impl SystemChain {
+	/// The identifier of the system parachain.
+	pub fn para_id(&self) -> u32 {
+		self.get_str("ParaId")
+			.and_then(|id| id.parse().ok())
+			.expect("para id defined above")
+	}
+
+	/// The chain spec of the system parachain, as provided by `polkadot-parachain`.
+	pub fn chain(&self) -> &'static str {
+		self.get_str("Chain").expect("chain defined above")
+	}
+}
+
+/// A local network for a parachain project: a relay chain with two validators, along with the
+/// parachain collated by the node of the project and any system parachains. The parachain is not included within the genesis
+/// of the relay chain, but registered once the network is launched (see `register_parachain`).
+#[derive(Clone, Debug, PartialEq)]
+pub struct LocalNetwork {
+	/// The node binary of the parachain.
+	pub node: PathBuf,
+	/// The identifier of the parachain.
+	pub para_id: u32,
+	/// The number of collators of the parachain.
+	pub collators: u32,
+	/// The arguments provided to each collator (e.g. `--rpc-max-connections=1000`).
+	pub collator_args: Vec<String>,
+	/// The arguments provided to each validator of the relay chain.
+	pub validator_args: Vec<String>,
+	/// The system parachains launched alongside the parachain.
+	pub system_chains: Vec<SystemChain>,
+	/// The raw chain spec of the parachain, if not generated by Zombienet (e.g. when its genesis
+	/// configuration is overridden).
+	pub chain_spec: Option<PathBuf>,
+}
+
+impl LocalNetwork {
+	/// The network configuration, as expected by Zombienet.
+	pub fn config(&self) -> DocumentMut {
+		let network = network::NetworkConfig {
+			relay: network::RelayChain {
+				chain: LOCAL_RELAY_CHAIN.into(),
+				args: self.validator_args.clone(),
+				validators: LOCAL_VALIDATORS.map(|v| NodeEntry::Name(v.into())).to_vec(),
+			},
+			parachains: vec![network::Parachain {
+				id: self.para_id,
+				command: Some(self.node.to_string_lossy().to_string()),
+				chain: None,
+				args: self.collator_args.clone(),
+				collators: (1..=self.collators.max(1))
+					.map(|i| NodeEntry::Name(format!("collator-{i:02}")))
+					.collect(),
+			}]
+			.into_iter()
+			// System parachains are launched using the `polkadot-parachain` binary of the relay
+			// chain release, sourced when the network is launched.
+			.chain(self.system_chains.iter().map(|system_chain| network::Parachain {
+				id: system_chain.para_id(),
+				command: None,
+				chain: Some(system_chain.chain().into()),
+				args: Vec::new(),
+				collators: vec![NodeEntry::Name(system_chain.to_string())],
+			}))
+			.collect(),
+		};
+		let mut config = network.zombienet();
+		if let Some(parachain) = config
+			.get_mut("parachains")
+			.and_then(|p| p.as_array_of_tables_mut())
+			.and_then(|p| p.get_mut(0))
+		{
+			parachain.insert("add_to_genesis", value(false));
+			parachain.insert("register_para", value(false));
+			if let Some(chain_spec) = &self.chain_spec {
+				parachain.insert("chain_spec_path", value(chain_spec.to_string_lossy().as_ref()));
+			}
+		}
+		config
+	}
+
+	/// Writes the network configuration to `path`, so that it can be launched.
+	///
+	/// # Arguments
+	///
+	/// * `path` - the path of the configuration file
+	pub fn write(&self, path: &Path) -> Result<(), Error> {
+		if let Some(parent) = path.parent() {
+			create_dir_all(parent)?;
+		}
+		write(path, self.config().to_string())?;
+		Ok(())
+	}
+}
+
+/// The provider spawning the nodes of a network.
+#[derive(Clone, Copy, Debug, Default, Display, EnumString, PartialEq)]
+#[strum(serialize_all = "lowercase")]
+pub enum NetworkProvider {
+	/// Spawns each node as a local process, using cached binaries.
+	#[default]
+	Native,
+	/// Spawns each node as a pod within a Kubernetes cluster, as per the current kubeconfig
+	/// context.
+	K8s,
+}
+
+/// The container images of the nodes of a network, as required by providers spawning nodes within
+/// containers.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Images {
+	/// The image of the relay chain nodes. [default: `parity/polkadot` of the relay chain release]
+	pub relay_chain: Option<String>,
+	/// The images of the collators of parachains, by parachain identifier. System parachains
+	/// default to `parity/polkadot-parachain` of the relay chain release.
+	pub parachains: Vec<(u32, String)>,
+}
+
+/// Parses the image of the collators of a parachain, specified as `<para-id>=<image>` (e.g.
+/// `2000=docker.io/org/node:latest`).
+///
+/// # Arguments
+///
+/// * `value` - the parachain identifier and its image
+pub fn parse_parachain_image(value: &str) -> Result<(u32, String), Error> {
+	let (id, image) = value
+		.split_once('=')
+		.filter(|(_, image)| !image.is_empty())
+		.ok_or(Error::Config(format!("expected `<para-id>=<image>`, found `{value}`")))?;
+	let id = id.parse().map_err(|_| Error::Config(format!("invalid parachain id `{id}`")))?;
+	Ok((id, image.into()))
+}
 
 pub struct Zombienet {
 	/// The cache location, used for caching binaries.
@@ -29,6 +202,16 @@ pub struct Zombienet {
 	relay_chain: Binary,
 	/// The binaries required to launch parachains.
 	parachains: IndexMap<u32, Binary>,
+	/// The explicit RPC ports of nodes, by name.
+	rpc_ports: Vec<(String, u16)>,
+	/// The provider spawning the nodes.
+	provider: NetworkProvider,
+	/// The container images of the nodes, when not spawned natively.
+	images: Images,
+	/// Whether the Prometheus endpoint of each node is exposed on a known port.
+	monitoring: bool,
+	/// The directory within which the state of the network is persisted, if any.
+	base_path: Option<PathBuf>,
 }
 
 impl Zombienet {
@@ -43,9 +226,7 @@ impl Zombienet {
 		let network_config_path = PathBuf::from(network_config);
 		let config = std::fs::read_to_string(&network_config_path)
 			.map_err(|err| Error::IO(err))
-			.and_then(|content| {
-				content.parse::<DocumentMut>().map_err(|err| Error::TomlError(err.into()))
-			})?;
+			.and_then(|content| network::parse(&content))?;
 		// Determine binaries
 		let relay_chain_binary = Self::relay_chain(relay_chain_version, &config, &cache).await?;
 		let mut parachain_binaries = IndexMap::new();
@@ -112,12 +293,111 @@ impl Zombienet {
 			network_config: (network_config_path, config),
 			relay_chain: relay_chain_binary,
 			parachains: parachain_binaries,
+			rpc_ports: Vec::new(),
+			provider: NetworkProvider::Native,
+			images: Images::default(),
+			monitoring: false,
+			base_path: None,
 		})
 	}
 
+	/// Serves the RPC server of each named node on the specified port, overriding the
+	/// configuration. Any node without an RPC port is assigned the next free port from 9944 when
+	/// the network is launched.
+	///
+	/// # Arguments
+	///
+	/// * `ports` - the names of nodes, along with their RPC ports
+	pub fn rpc_ports(&mut self, ports: Vec<(String, u16)>) -> &mut Self {
+		self.rpc_ports = ports;
+		self
+	}
+
+	/// Writes a Docker Compose file to `path` replicating the network, as an alternative to
+	/// launching it natively. Each node is assigned an RPC port as when launched natively.
+	///
+	/// # Arguments
+	///
+	/// * `path` - the file to which the Docker Compose file is written
+	pub fn export_docker_compose(&self, path: &Path) -> Result<(), Error> {
+		let (network_config_path, network_config) = &self.network_config;
+		let mut config = network_config.clone();
+		assign_ports(&mut config, &self.rpc_ports, DEFAULT_RPC_PORT)?;
+		let base = network_config_path.parent().unwrap_or(Path::new("."));
+		let compose = compose::docker_compose(&config, &self.relay_chain.version, base)?;
+		if let Some(parent) = path.parent() {
+			create_dir_all(parent)?;
+		}
+		write(path, compose)?;
+		Ok(())
+	}
+
+	/// Spawns the nodes of the network using `provider`, which for providers other than native
+	/// run the binaries of the specified container `images`.
+	///
+	/// # Arguments
+	///
+	/// * `provider` - the provider spawning the nodes
+	/// * `images` - the container images of the nodes
+	pub fn provider(&mut self, provider: NetworkProvider, images: Images) -> &mut Self {
+		self.provider = provider;
+		self.images = images;
+		self
+	}
+
+	/// Exposes the Prometheus endpoint of each node, assigning any node without an explicit
+	/// Prometheus port the next free port from 9615 when the network is launched.
+	///
+	/// # Arguments
+	///
+	/// * `enabled` - whether the Prometheus endpoints are exposed
+	pub fn monitoring(&mut self, enabled: bool) -> &mut Self {
+		self.monitoring = enabled;
+		self
+	}
+
+	/// The nodes exposing their Prometheus metrics on a known port, as configured once the network
+	/// is launched.
+	pub fn metrics_targets(&self) -> Vec<MetricsTarget> {
+		metrics_targets(&self.network_config.1)
+	}
+
+	/// Persists the state of the network within `path`, rather than within a temporary directory,
+	/// so that a network launched again with the same base path resumes from its previous state.
+	///
+	/// # Arguments
+	///
+	/// * `path` - the directory within which the state of the network is persisted
+	pub fn base_path(&mut self, path: PathBuf) -> &mut Self {
+		self.base_path = Some(path);
+		self
+	}
+
+	/// Whether the base path holds the state of a previously launched network.
+	pub fn has_state(&self) -> bool {
+		self.base_path
+			.as_ref()
+			.and_then(|path| path.read_dir().ok())
+			.is_some_and(|mut entries| entries.next().is_some())
+	}
+
+	/// Wipes the state of a previously launched network from the base path, so that the network
+	/// is launched from genesis.
+	pub fn purge(&self) -> Result<(), Error> {
+		if let Some(path) = self.base_path.as_ref().filter(|p| p.exists()) {
+			remove_dir_all(path)?;
+		}
+		Ok(())
+	}
+
 	pub fn missing_binaries(&self) -> Vec<&Binary> {
 		let mut missing = Vec::new();
-		if !self.relay_chain.path.exists() {
+		// Binaries are provided by the images of nodes spawned within containers.
+		if self.provider != NetworkProvider::Native {
+			return missing;
+		}
+		if !self.relay_chain.path.exists() || self.relay_chain_workers().iter().any(|w| !w.exists())
+		{
 			missing.push(&self.relay_chain);
 		}
 		for binary in self.parachains.values().filter(|b| !b.path.exists()) {
@@ -126,21 +406,86 @@ impl Zombienet {
 		missing
 	}
 
+	/// The binaries used to launch the network: that of the relay chain, followed by those of the
+	/// parachains.
+	pub fn binaries(&self) -> impl Iterator<Item = &Binary> {
+		[&self.relay_chain].into_iter().chain(self.parachains.values())
+	}
+
+	/// The release of the relay chain binaries used to launch the network.
+	pub fn relay_chain_version(&self) -> &str {
+		&self.relay_chain.version
+	}
+
+	/// The workers of the relay chain binary, versioned as per its release.
+	fn relay_chain_workers(&self) -> Vec<PathBuf> {
+		PolkadotRelease::new(&self.relay_chain.version)
+			.map(|release| release.workers(&self.cache))
+			.unwrap_or_default()
+	}
+
+	/// Configures the relay chain, along with each parachain launched using a sourced binary, to
+	/// start from a chain spec cached by the version of its binary, rather than generating it on
+	/// every launch. Chain specs provided by the network configuration are left as is.
+	fn configure_chain_specs(&mut self) -> Result<(), Error> {
+		let (_, config) = &mut self.network_config;
+		if let Some(relay_chain) = config.get_mut("relaychain").and_then(|r| r.as_table_mut()) {
+			if let Some(chain) = uncached_chain(relay_chain) {
+				let spec = cached_chain_spec(&self.cache, &self.relay_chain, &chain)?;
+				relay_chain.insert("chain_spec_path", value(spec.to_string_lossy().as_ref()));
+			}
+		}
+		let Some(parachains) =
+			config.get_mut("parachains").and_then(|p| p.as_array_of_tables_mut())
+		else {
+			return Ok(());
+		};
+		for parachain in parachains.iter_mut() {
+			let binary = parachain
+				.get("id")
+				.and_then(|i| i.as_integer())
+				.and_then(|id| self.parachains.get(&(id as u32)))
+				// Binaries built from the latest sources are not versioned.
+				.filter(|b| !b.version.is_empty());
+			if let (Some(binary), Some(chain)) = (binary, uncached_chain(parachain)) {
+				let spec = cached_chain_spec(&self.cache, binary, &chain)?;
+				parachain.insert("chain_spec_path", value(spec.to_string_lossy().as_ref()));
+			}
+		}
+		Ok(())
+	}
+
 	pub async fn spawn(&mut self) -> Result<Network<LocalFileSystem>, Error> {
-		// Symlink polkadot-related binaries
-		for file in ["polkadot-execute-worker", "polkadot-prepare-worker"] {
-			let dest = self.cache.join(file);
+		// Symlink the workers of the pinned release, as expected alongside the relay chain binary
+		let workers = match self.provider {
+			NetworkProvider::Native => self.relay_chain_workers(),
+			_ => Vec::new(),
+		};
+		for worker in workers {
+			let (Some(file), Some(dir)) = (worker.file_name(), self.relay_chain.path.parent())
+			else {
+				return Err(Error::Config("the relay chain worker path is invalid".into()));
+			};
+			let dest = dir.join(file);
 			if dest.exists() {
 				remove_symlink_file(&dest)?;
 			}
-			symlink_file(self.cache.join(format!("{file}-{}", self.relay_chain.version)), dest)?;
+			symlink_file(worker, dest)?;
+		}
+
+		// Reuse the chain specs generated by previous launches using the same binaries.
+		if self.provider == NetworkProvider::Native {
+			self.configure_chain_specs()?;
 		}
 
 		// Load from config and spawn network
 		let config = self.configure()?;
 		let path = config.path().to_str().expect("temp config file should have a path").into();
 		let network_config = NetworkConfig::load_from_toml(path)?;
-		Ok(network_config.spawn_native().await?)
+		Ok(match self.provider {
+			NetworkProvider::Native => network_config.spawn_native().await?,
+			NetworkProvider::K8s => network_config.spawn_k8s().await?,
+		})
 	}
 
 	// Adapts provided config file to one that is compatible with current zombienet-sdk requirements
@@ -159,73 +504,95 @@ impl Zombienet {
 		settings
 			.entry("node_spawn_timeout")
 			.or_insert(Item::Value(Value::Integer(Formatted::new(300))));
+		// The state of each node is kept within the base path, rather than a temporary directory.
+		if let Some(path) = &self.base_path {
+			let path = match path.is_absolute() {
+				true => path.clone(),
+				false => current_dir()?.join(path),
+			};
+			create_dir_all(&path)?;
+			let path = path.to_str().ok_or(Error::Config("the base path is invalid".into()))?;
+			settings.insert("base_dir", value(path));
+		}
 
-		// Update relay chain config
-		let relay_path = self
-			.relay_chain
-			.path
-			.to_str()
-			.ok_or(Error::Config("the relay chain path is invalid".into()))?;
-		let Item::Table(relay_chain) =
-			network_config.entry("relaychain").or_insert(Item::Table(Table::new()))
-		else {
-			return Err(Error::Config("expected `relaychain`".into()));
-		};
-		*relay_chain.entry("default_command").or_insert(value(relay_path)) = value(relay_path);
+		// Ensure the ports of each node are free, rather than failing once launched
+		assign_ports(network_config, &self.rpc_ports, DEFAULT_RPC_PORT)?;
+		// Zombienet serves the metrics of each node externally, so they can be scraped from
+		// containers.
+		if self.monitoring {
+			assign_prometheus_ports(network_config, DEFAULT_PROMETHEUS_PORT)?;
+		}
 
-		// Update parachain config
-		if let Some(tables) =
-			network_config.get_mut("parachains").and_then(|p| p.as_array_of_tables_mut())
-		{
-			for table in tables.iter_mut() {
-				let id = table
-					.get("id")
-					.and_then(|i| i.as_integer())
-					.ok_or(Error::Config("expected `parachain` to have `id`".into()))? as u32;
+		// Nodes spawned within containers run the binaries of their images.
+		if self.provider != NetworkProvider::Native {
+			Self::configure_images(network_config, &self.relay_chain.version, &self.images)?;
+		} else {
+			// Update relay chain config
+			let relay_path = self
+				.relay_chain
+				.path
+				.to_str()
+				.ok_or(Error::Config("the relay chain path is invalid".into()))?;
+			let Item::Table(relay_chain) =
+				network_config.entry("relaychain").or_insert(Item::Table(Table::new()))
+			else {
+				return Err(Error::Config("expected `relaychain`".into()));
+			};
+			*relay_chain.entry("default_command").or_insert(value(relay_path)) = value(relay_path);
 
-				// Resolve default_command to binary
-				{
-					// Check if provided via args, therefore cached
-					if let Some(para) = self.parachains.get(&id) {
-						let para_path = para
-							.path
-							.to_str()
-							.ok_or(Error::Config("the parachain path is invalid".into()))?;
-						table.insert("default_command", value(para_path));
-					} else if let Some(default_command) = table.get_mut("default_command") {
-						// Otherwise assume local binary, fix path accordingly
-						let command_path = default_command.as_str().ok_or(Error::Config(
-							"expected `default_command` value to be a string".into(),
-						))?;
-						let path = Self::resolve_path(network_config_path, command_path)?;
-						*default_command =
-							value(path.to_str().ok_or(Error::Config(
+			// Update parachain config
+			if let Some(tables) =
+				network_config.get_mut("parachains").and_then(|p| p.as_array_of_tables_mut())
+			{
+				for table in tables.iter_mut() {
+					let id = table
+						.get("id")
+						.and_then(|i| i.as_integer())
+						.ok_or(Error::Config("expected `parachain` to have `id`".into()))? as u32;
+
+					// Resolve default_command to binary
+					{
+						// Check if provided via args, therefore cached
+						if let Some(para) = self.parachains.get(&id) {
+							let para_path = para
+								.path
+								.to_str()
+								.ok_or(Error::Config("the parachain path is invalid".into()))?;
+							table.insert("default_command", value(para_path));
+						} else if let Some(default_command) = table.get_mut("default_command") {
+							// Otherwise assume local binary, fix path accordingly
+							let command_path = default_command.as_str().ok_or(Error::Config(
+								"expected `default_command` value to be a string".into(),
+							))?;
+							let path = Self::resolve_path(network_config_path, command_path)?;
+							*default_command = value(path.to_str().ok_or(Error::Config(
 								"the parachain binary was not found".into(),
 							))?);
+						}
 					}
-				}
 
-				// Resolve individual collator command to binary
-				if let Some(collators) =
-					table.get_mut("collators").and_then(|p| p.as_array_of_tables_mut())
-				{
-					for collator in collators.iter_mut() {
-						if let Some(command) = collator.get_mut("command") {
-							// Check if provided via args, therefore cached
-							if let Some(para) = self.parachains.get(&id) {
-								let para_path = para
-									.path
-									.to_str()
-									.ok_or(Error::Config("the parachain path is invalid".into()))?;
-								*command = value(para_path);
-							} else {
-								let command_path = command.as_str().ok_or(Error::Config(
-									"expected `command` value to be a string".into(),
-								))?;
-								let path = Self::resolve_path(network_config_path, command_path)?;
-								*command = value(path.to_str().ok_or(Error::Config(
-									"the parachain binary was not found".into(),
-								))?);
+					// Resolve individual collator command to binary
+					if let Some(collators) =
+						table.get_mut("collators").and_then(|p| p.as_array_of_tables_mut())
+					{
+						for collator in collators.iter_mut() {
+							if let Some(command) = collator.get_mut("command") {
+								// Check if provided via args, therefore cached
+								if let Some(para) = self.parachains.get(&id) {
+									let para_path = para.path.to_str().ok_or(Error::Config(
+										"the parachain path is invalid".into(),
+									))?;
+									*command = value(para_path);
+								} else {
+									let command_path = command.as_str().ok_or(Error::Config(
+										"expected `command` value to be a string".into(),
+									))?;
+									let path =
+										Self::resolve_path(network_config_path, command_path)?;
+									*command = value(path.to_str().ok_or(Error::Config(
+										"the parachain binary was not found".into(),
+									))?);
+								}
 							}
 						}
 					}
@@ -247,6 +614,79 @@ impl Zombienet {
 		Ok(network_config_file)
 	}
 
+	/// Configures the nodes of the network to run the binaries of their container images.
+	///
+	/// # Arguments
+	///
+	/// * `config` - the network configuration, as expected by Zombienet
+	/// * `version` - the release of the relay chain
+	/// * `images` - the container images of the nodes
+	fn configure_images(
+		config: &mut DocumentMut,
+		version: &str,
+		images: &Images,
+	) -> Result<(), Error> {
+		let binary = |command: &str| {
+			Path::new(command)
+				.file_name()
+				.and_then(|f| f.to_str())
+				.unwrap_or(command)
+				.to_string()
+		};
+		let relay_image =
+			images.relay_chain.clone().unwrap_or(format!("{POLKADOT_IMAGE}:{version}"));
+		let Item::Table(relay_chain) =
+			config.entry("relaychain").or_insert(Item::Table(Table::new()))
+		else {
+			return Err(Error::Config("expected `relaychain`".into()));
+		};
+		relay_chain.insert("default_command", value("polkadot"));
+		relay_chain.insert("default_image", value(relay_image));
+
+		let Some(tables) = config.get_mut("parachains").and_then(|p| p.as_array_of_tables_mut())
+		else {
+			return Ok(());
+		};
+		for table in tables.iter_mut() {
+			let id = table
+				.get("id")
+				.and_then(|i| i.as_integer())
+				.ok_or(Error::Config("expected `parachain` to have `id`".into()))? as u32;
+			let command = binary(
+				table
+					.get("default_command")
+					.and_then(|c| c.as_str())
+					.unwrap_or("polkadot-parachain"),
+			);
+			let image = match images.parachains.iter().find(|(para_id, _)| *para_id == id) {
+				Some((_, image)) => image.clone(),
+				None if command == "polkadot-parachain" => {
+					format!("{POLKADOT_PARACHAIN_IMAGE}:{version}")
+				},
+				None => {
+					return Err(Error::Config(format!(
+						"parachain {id} requires a container image, provide one with \
+						 `--parachain-image {id}=<image>`"
+					)))
+				},
+			};
+			table.insert("default_command", value(command));
+			table.insert("default_image", value(image));
+			// The commands of collators refer to local binaries, rather than those of the image.
+			if let Some(collators) =
+				table.get_mut("collators").and_then(|c| c.as_array_of_tables_mut())
+			{
+				for collator in collators.iter_mut() {
+					if let Some(command) = collator.get("command").and_then(|c| c.as_str()) {
+						let command = binary(command);
+						collator.insert("command", value(command));
+					}
+				}
+			}
+		}
+		Ok(())
+	}
+
 	fn resolve_path(
 		network_config_path: &mut PathBuf,
 		command_path: &str,
@@ -268,76 +708,27 @@ impl Zombienet {
 		network_config: &DocumentMut,
 		cache: &PathBuf,
 	) -> Result<Binary, Error> {
-		const BINARY: &str = "polkadot";
 		let relay_command = network_config
 			.get("relaychain")
 			.ok_or(Error::Config("expected `relaychain`".into()))?
 			.get("default_command");
 		if let Some(Value::String(command)) = relay_command.and_then(|c| c.as_value()) {
-			if !command.value().to_lowercase().contains(BINARY) {
+			if !command.value().to_lowercase().contains("polkadot") {
 				return Err(Error::UnsupportedCommand(format!(
 					"the relay chain command is unsupported: {0}",
 					command.to_string()
 				)));
 			}
 		}
-		let version = match version {
-			Some(v) => v.to_string(),
-			None => Self::latest_polkadot_release().await?,
+		let release = match version {
+			Some(version) => PolkadotRelease::new(version)?,
+			None => PolkadotRelease::latest().await,
 		};
-		let versioned_name = format!("{BINARY}-{version}");
-		let path = cache.join(&versioned_name);
-		let mut sources = Vec::new();
-		if !path.exists() {
-			const BINARIES: [&str; 3] =
-				[BINARY, "polkadot-execute-worker", "polkadot-prepare-worker"];
-			let repo = Url::parse(POLKADOT_SDK).expect("repository url valid");
-			if cfg!(target_os = "macos") {
-				sources.push(Source::Git {
-					url: repo.into(),
-					branch: Some(format!("release-polkadot-{version}")),
-					package: BINARY.into(),
-					binaries: BINARIES.iter().map(|b| b.to_string()).collect(),
-					version: Some(version.clone()),
-				});
-			} else {
-				for b in BINARIES {
-					sources.push(Source::Url {
-						name: b.to_string(),
-						version: version.clone(),
-						url: GitHub::release(&repo, &format!("polkadot-{version}"), b),
-					})
-				}
-			};
-		}
-
-		Ok(Binary { name: versioned_name, version, path, sources })
+		Ok(release.relay_chain(cache))
 	}
 
 	fn system_parachain(version: &String, cache: &PathBuf) -> Result<Binary, Error> {
-		const BINARY: &str = "polkadot-parachain";
-		let versioned_name = format!("{BINARY}-{version}");
-		let path = cache.join(&versioned_name);
-		let mut sources = Vec::new();
-		if !path.exists() {
-			let repo = Url::parse(POLKADOT_SDK).expect("repository url valid");
-			if cfg!(target_os = "macos") {
-				sources.push(Source::Git {
-					url: repo.into(),
-					branch: Some(format!("release-polkadot-{version}")),
-					package: "polkadot-parachain-bin".into(),
-					binaries: vec![BINARY.into()],
-					version: Some(version.into()),
-				})
-			} else {
-				sources.push(Source::Url {
-					name: BINARY.into(),
-					version: version.into(),
-					url: GitHub::release(&repo, &format!("polkadot-{version}"), BINARY),
-				})
-			};
-		}
-		Ok(Binary { name: versioned_name, version: version.into(), path, sources })
+		Ok(PolkadotRelease::new(version)?.system_parachain(cache))
 	}
 
 	fn parachain(repo: Url, cache: &PathBuf) -> Result<Binary, Error> {
@@ -352,7 +743,7 @@ impl Zombienet {
 		}
 		.to_string();
 
-		let path = cache.join(&binary);
+		let path = Source::cached_path(cache, &binary, branch.as_deref());
 		let mut sources = Vec::new();
 		if !path.exists() {
 			sources.push(Source::Git {
@@ -367,229 +758,77 @@ impl Zombienet {
 	}
 
 	async fn latest_polkadot_release() -> Result<String, Error> {
-		let repo = Url::parse(POLKADOT_SDK).expect("repository url valid");
-		match GitHub::get_latest_releases(&repo).await {
-			Ok(releases) => {
-				// Fetching latest releases
-				for release in releases {
-					if !release.prerelease && release.tag_name.starts_with("polkadot-v") {
-						return Ok(release
-							.tag_name
-							.strip_prefix("polkadot-")
-							.map_or_else(|| release.tag_name.clone(), |v| v.to_string()));
-					}
-				}
-				// It should never reach this point, but in case we download a default version of polkadot
-				Ok(POLKADOT_DEFAULT_VERSION.to_string())
-			},
-			// If an error with Github API return the POLKADOT DEFAULT VERSION
-			Err(_) => Ok(POLKADOT_DEFAULT_VERSION.to_string()),
-		}
+		Ok(PolkadotRelease::latest().await.version().to_string())
 	}
 }
 
-/// A binary used to launch a node.
-pub struct Binary {
-	/// The name of a binary.
-	pub name: String,
-	version: String,
-	path: PathBuf,
-	sources: Vec<Source>,
-}
-
-impl Binary {
-	/// Sources the binary by either downloading from a url or by cloning a git repository and
-	/// building locally from the resulting source code.
-	///
-	/// # Arguments
-	///
-	/// * `cache` - path to the local cache
-	/// * `status` - used to observe status updates
-	pub async fn source(&self, cache: &PathBuf, status: impl Status) -> Result<(), Error> {
-		for source in &self.sources {
-			source.process(cache, status).await?;
-		}
-		Ok(())
+/// The chain of a relay chain or parachain whose chain spec is generated when launched, rather
+/// than provided by the network configuration.
+fn uncached_chain(table: &Table) -> Option<String> {
+	if table.contains_key("chain_spec_path") || table.contains_key("chain_spec_command") {
+		return None;
 	}
+	table.get("chain").and_then(|c| c.as_str()).map(|c| c.to_string())
 }
 
-/// The source of a binary.
-#[derive(Clone, Eq, Hash, Ord, PartialEq, PartialOrd)]
-pub enum Source {
-	/// The source is a URL.
-	Url {
-		/// The name of the binary.
-		name: String,
-		/// The version of the binary.
-		version: String,
-		/// The url to download the binary.
-		url: String,
-	},
-	/// The source is a git repository.
-	Git {
-		/// The url of the repository.
-		url: Url,
-		branch: Option<String>,
-		package: String,
-		binaries: Vec<String>,
-		version: Option<String>,
-	},
-}
-
-impl Source {
-	async fn build_binaries<'b>(
-		path: &Path,
-		package: &str,
-		names: impl Iterator<Item = (&'b String, PathBuf)>,
-		status: impl Status,
-	) -> Result<(), Error> {
-		// Build binaries and then copy to cache and target
-		let reader = cmd("cargo", vec!["build", "--release", "-p", package])
-			.dir(path)
-			.stderr_to_stdout()
-			.reader()?;
-		let mut output = std::io::BufReader::new(reader).lines();
-		while let Some(Ok(line)) = output.next() {
-			status.update(&line);
-		}
-		for (name, dest) in names {
-			copy(path.join(format!("target/release/{name}")), dest)?;
-		}
-		Ok(())
+/// The plain chain spec of `chain`, as generated by `binary`. The chain spec is cached by the
+/// version of the binary, so that it is only generated once per release.
+///
+/// # Arguments
+///
+/// * `cache` - the cache location
+/// * `binary` - the binary generating the chain spec
+/// * `chain` - the chain, as supported by the binary (e.g. `rococo-local`)
+fn cached_chain_spec(cache: &Path, binary: &Binary, chain: &str) -> Result<PathBuf, Error> {
+	let dir = cache.join(CHAIN_SPECS_DIR);
+	let spec = dir.join(format!("{}-{chain}-{}.json", binary.name, binary.version));
+	if spec.exists() {
+		return Ok(spec);
 	}
-
-	async fn download(url: &str, cache: &PathBuf) -> Result<(), Error> {
-		// Download to cache
-		let response = reqwest::get(url).await?;
-		let mut file = File::create(&cache)?;
-		file.write_all(&response.bytes().await?)?;
-		// Make executable
-		let mut perms = metadata(cache)?.permissions();
-		perms.set_mode(0o755);
-		std::fs::set_permissions(cache, perms)?;
-		Ok(())
+	let args = ["build-spec", "--chain", chain, "--disable-default-bootnode"];
+	tracing::debug!(binary = %binary.path.display(), ?args, "generating chain spec");
+	let output = cmd(&binary.path, args).stdout_capture().stderr_capture().unchecked().run()?;
+	if !output.status.success() {
+		return Err(Error::Config(format!(
+			"unable to generate the chain spec of {chain}: {}",
+			String::from_utf8_lossy(&output.stderr).trim()
+		)));
 	}
-
-	/// Processes the binary source, by either downloading the binary from a url or by cloning a
-	/// git repository and building locally from the resulting source code.
-	///
-	/// # Arguments
-	///
-	/// * `cache` - path to the local cache
-	/// * `status` - used to observe status updates
-	pub async fn process(
-		&self,
-		cache: &Path,
-		status: impl Status,
-	) -> Result<Option<Vec<PathBuf>>, Error> {
-		// Download or clone and build from source
-		match self {
-			Source::Url { name, version, url } => {
-				// Check if source already exist within cache
-				let versioned_name = Self::versioned_name(name, Some(version));
-				if cache.join(&versioned_name).exists() {
-					return Ok(None);
-				}
-
-				// Download required version of binaries
-				status.update(&format!("Downloading from {url}..."));
-				Self::download(&url, &cache.join(&versioned_name)).await?;
-				Ok(None)
-			},
-			Source::Git { url, branch, package, binaries, version } => {
-				// Check if all binaries already exist within cache
-				let versioned_names: Vec<_> = binaries
-					.iter()
-					.map(|n| (n, Self::versioned_name(n, version.as_deref())))
-					.collect();
-				if versioned_names.iter().all(|(_, n)| cache.join(&n).exists()) {
-					return Ok(None);
-				}
-
-				let repository_name = GitHub::name(url)?;
-				let working_dir = cache.join(".src").join(repository_name);
-				let working_dir = Path::new(&working_dir);
-
-				// Clone repository into working directory
-				if !working_dir.exists() {
-					status.update(&format!("Cloning {url}..."));
-					if let Err(e) = Git::clone(url, working_dir, branch.as_deref()) {
-						if working_dir.exists() {
-							// Preserve original error
-							let _ = Self::remove(working_dir);
-						}
-						return Err(e.into());
-					}
-				}
-				// Build binaries and finally remove working directory
-				if let Err(e) = Self::build_binaries(
-					working_dir,
-					package,
-					versioned_names
-						.iter()
-						.map(|(binary, versioned)| (*binary, cache.join(versioned))),
-					status,
-				)
-				.await
-				{
-					if working_dir.exists() {
-						// Preserve original error
-						let _ = Self::remove(working_dir);
-					}
-					return Err(e.into());
-				}
-				Self::remove(working_dir)?;
-				Ok(None)
-			},
-		}
-	}
-
-	fn remove(path: &Path) -> Result<(), Error> {
-		remove_dir_all(path)?;
-		if let Some(source) = path.parent() {
-			if source.exists() && source.read_dir().map(|mut i| i.next().is_none()).unwrap_or(false)
-			{
-				remove_dir_all(source)?;
-			}
-		}
-		Ok(())
-	}
-
-	/// A versioned name of a binary.
-	///
-	/// # Arguments
-	///
-	/// * `version` - an optional version to be appended to the binary name
-	pub fn versioned_name(name: &str, version: Option<&str>) -> String {
-		match version {
-			Some(version) => format!("{name}-{version}"),
-			None => name.to_string(),
-		}
-	}
-}
-
-/// Trait for observing status updates.
-pub trait Status: Copy {
-	/// Update the observer with the provided `status`.
-	fn update(&self, status: &str);
-}
-
-impl Status for () {
-	// no-op: status updates are ignored
-	fn update(&self, _: &str) {}
+	// Written in full before being moved into place, so an interrupted launch is not cached.
+	create_dir_all(&dir)?;
+	let partial = spec.with_extension("partial");
+	write(&partial, &output.stdout)?;
+	rename(&partial, &spec)?;
+	Ok(spec)
 }
 
 #[cfg(test)]
 mod tests {
 
 	use super::*;
+	use crate::binaries::is_released;
 	use anyhow::Result;
+	use pop_common::Platform;
+	use std::{fs::File, io::Write};
+	use strum::VariantArray as _;
 
 	const CONFIG_FILE_PATH: &str = "../../tests/zombienet.toml";
 	const TESTING_POLKADOT_VERSION: &str = "v1.7.0";
 	const POLKADOT_BINARY: &str = "polkadot-v1.7.0";
 	const POLKADOT_PARACHAIN_BINARY: &str = "polkadot-parachain-v1.7.0";
 
+	/// The path of the binary named `name` within `cache`.
+	fn cached(cache: &Path, name: &str, version: Option<&str>) -> PathBuf {
+		Source::cached_path(cache, name, version)
+	}
+
+	/// Creates an empty file at `path`, along with its parent directories.
+	fn touch(path: &Path) -> Result<()> {
+		create_dir_all(path.parent().unwrap())?;
+		File::create(path)?;
+		Ok(())
+	}
+
 	#[tokio::test]
 	async fn test_new_zombienet_success() -> Result<()> {
 		let temp_dir = tempfile::tempdir().expect("Could not create temp dir");
@@ -606,12 +845,15 @@ mod tests {
 
 		// Check has the binary for Polkadot
 		assert_eq!(zombienet.relay_chain.name, POLKADOT_BINARY);
-		assert_eq!(zombienet.relay_chain.path, temp_dir.path().join(POLKADOT_BINARY));
+		assert_eq!(
+			zombienet.relay_chain.path,
+			cached(&cache, "polkadot", Some(TESTING_POLKADOT_VERSION))
+		);
 		assert_eq!(zombienet.relay_chain.version, TESTING_POLKADOT_VERSION);
-		if cfg!(target_os = "macos") {
-			assert_eq!(zombienet.relay_chain.sources.len(), 1);
-		} else {
+		if is_released(Platform::detect().ok()) {
 			assert_eq!(zombienet.relay_chain.sources.len(), 3);
+		} else {
+			assert_eq!(zombienet.relay_chain.sources.len(), 1);
 		}
 
 		// Check has the binary for the System Chain
@@ -619,14 +861,17 @@ mod tests {
 
 		let system_chain = &zombienet.parachains[0];
 		assert_eq!(system_chain.name, POLKADOT_PARACHAIN_BINARY);
-		assert_eq!(system_chain.path, temp_dir.path().join(POLKADOT_PARACHAIN_BINARY));
+		assert_eq!(
+			system_chain.path,
+			cached(&cache, "polkadot-parachain", Some(TESTING_POLKADOT_VERSION))
+		);
 		assert_eq!(system_chain.version, TESTING_POLKADOT_VERSION);
 		assert_eq!(system_chain.sources.len(), 1);
 
 		// Check has the binary for POP
 		let parachain = &zombienet.parachains[1];
 		assert_eq!(parachain.name, "pop-node");
-		assert_eq!(parachain.path, temp_dir.path().join("pop-node"));
+		assert_eq!(parachain.path, cached(&cache, "pop-node", None));
 		assert_eq!(parachain.version, "");
 		assert_eq!(parachain.sources.len(), 1);
 
@@ -675,13 +920,16 @@ mod tests {
 				.await?;
 
 		assert_eq!(binary_relay_chain.name, POLKADOT_BINARY);
-		assert_eq!(binary_relay_chain.path, temp_dir.path().join(POLKADOT_BINARY));
+		assert_eq!(
+			binary_relay_chain.path,
+			cached(&cache, "polkadot", Some(TESTING_POLKADOT_VERSION))
+		);
 		assert_eq!(binary_relay_chain.version, TESTING_POLKADOT_VERSION);
 
-		if cfg!(target_os = "macos") {
-			assert_eq!(binary_relay_chain.sources.len(), 1);
-		} else {
+		if is_released(Platform::detect().ok()) {
 			assert_eq!(binary_relay_chain.sources.len(), 3);
+		} else {
+			assert_eq!(binary_relay_chain.sources.len(), 1);
 		}
 
 		Ok(())
@@ -700,10 +948,10 @@ mod tests {
 
 		assert!(binary_relay_chain.name.starts_with("polkadot-v"));
 		assert!(binary_relay_chain.version.starts_with("v"));
-		if cfg!(target_os = "macos") {
-			assert_eq!(binary_relay_chain.sources.len(), 1);
-		} else {
+		if is_released(Platform::detect().ok()) {
 			assert_eq!(binary_relay_chain.sources.len(), 3);
+		} else {
+			assert_eq!(binary_relay_chain.sources.len(), 1);
 		}
 
 		Ok(())
@@ -746,7 +994,10 @@ mod tests {
 			Zombienet::system_parachain(&TESTING_POLKADOT_VERSION.to_string(), &cache)?;
 
 		assert_eq!(binary_system_chain.name, POLKADOT_PARACHAIN_BINARY);
-		assert_eq!(binary_system_chain.path, temp_dir.path().join(POLKADOT_PARACHAIN_BINARY));
+		assert_eq!(
+			binary_system_chain.path,
+			cached(&cache, "polkadot-parachain", Some(TESTING_POLKADOT_VERSION))
+		);
 		assert_eq!(binary_system_chain.version, TESTING_POLKADOT_VERSION);
 		assert_eq!(binary_system_chain.sources.len(), 1);
 
@@ -763,7 +1014,7 @@ mod tests {
 		let binary_system_chain = Zombienet::parachain(url, &cache)?;
 
 		assert_eq!(binary_system_chain.name, "pop-node");
-		assert_eq!(binary_system_chain.path, temp_dir.path().join("pop-node"));
+		assert_eq!(binary_system_chain.path, cached(&cache, "pop-node", None));
 		assert_eq!(binary_system_chain.version, "");
 		assert_eq!(binary_system_chain.sources.len(), 1);
 
@@ -796,12 +1047,12 @@ mod tests {
 		let cache = PathBuf::from(temp_dir.path());
 
 		// Create "fake" binary files
-		let relay_chain_file_path = temp_dir.path().join(POLKADOT_BINARY);
-		File::create(relay_chain_file_path)?;
-		let system_chain_file_path = temp_dir.path().join(POLKADOT_PARACHAIN_BINARY);
-		File::create(system_chain_file_path)?;
-		let pop_file_path = temp_dir.path().join("pop-node");
-		File::create(pop_file_path)?;
+		for binary in
+			["polkadot", "polkadot-parachain", "polkadot-execute-worker", "polkadot-prepare-worker"]
+		{
+			touch(&cached(&cache, binary, Some(TESTING_POLKADOT_VERSION)))?;
+		}
+		touch(&cached(&cache, "pop-node", None))?;
 
 		let zombienet = Zombienet::new(
 			cache.clone(),
@@ -833,6 +1084,105 @@ mod tests {
 		.await?;
 
 		zombienet.configure()?;
+		// Each node is assigned a free RPC port.
+		let config = &zombienet.network_config.1;
+		assert!(config["relaychain"]["nodes"][0]["rpc_port"].is_integer());
+		assert!(config["parachains"][0]["collators"][0]["rpc_port"].is_integer());
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_base_path() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let cache = PathBuf::from(temp_dir.path());
+		let base_path = temp_dir.path().join("network");
+		let mut zombienet = Zombienet::new(
+			cache.clone(),
+			CONFIG_FILE_PATH,
+			Some(&TESTING_POLKADOT_VERSION.to_string()),
+			Some(&TESTING_POLKADOT_VERSION.to_string()),
+			Some(&vec!["https://github.com/r0gue-io/pop-node".to_string()]),
+		)
+		.await?;
+		zombienet.base_path(base_path.clone());
+		assert!(!zombienet.has_state());
+
+		zombienet.configure()?;
+		let config = &zombienet.network_config.1;
+		assert_eq!(config["settings"]["base_dir"].as_str(), base_path.to_str());
+		std::fs::create_dir(base_path.join("alice"))?;
+		assert!(zombienet.has_state());
+		zombienet.purge()?;
+		assert!(!zombienet.has_state());
+		assert!(!base_path.exists());
+		Ok(())
+	}
+
+	#[test]
+	fn test_configure_images() -> Result<()> {
+		let mut config: DocumentMut = r#"
+[relaychain]
+default_command = "./bin/polkadot"
+
+[[parachains]]
+id = 1000
+chain = "asset-hub-rococo-local"
+
+[[parachains]]
+id = 2000
+default_command = "./target/release/parachain-template-node"
+
+[[parachains.collators]]
+name = "collator-01"
+command = "./target/release/parachain-template-node"
+"#
+		.parse()?;
+		let images = Images {
+			relay_chain: None,
+			parachains: vec![(2000, "docker.io/org/node:latest".into())],
+		};
+		Zombienet::configure_images(&mut config, "v1.11.0", &images)?;
+		assert_eq!(config["relaychain"]["default_command"].as_str(), Some("polkadot"));
+		assert_eq!(
+			config["relaychain"]["default_image"].as_str(),
+			Some("docker.io/parity/polkadot:v1.11.0")
+		);
+		assert_eq!(
+			config["parachains"][0]["default_image"].as_str(),
+			Some("docker.io/parity/polkadot-parachain:v1.11.0")
+		);
+		let parachain = &config["parachains"][1];
+		assert_eq!(parachain["default_command"].as_str(), Some("parachain-template-node"));
+		assert_eq!(parachain["default_image"].as_str(), Some("docker.io/org/node:latest"));
+		assert_eq!(parachain["collators"][0]["command"].as_str(), Some("parachain-template-node"));
+
+		// Parachains built locally have no default image.
+		let mut config: DocumentMut =
+			"[relaychain]\n\n[[parachains]]\nid = 2000\ndefault_command = \"./node\"\n".parse()?;
+		assert!(matches!(
+			Zombienet::configure_images(&mut config, "v1.11.0", &Images::default()),
+			Err(Error::Config(..))
+		));
+		Ok(())
+	}
+
+	#[test]
+	fn test_parse_parachain_image() -> Result<()> {
+		assert_eq!(
+			parse_parachain_image("2000=docker.io/org/node:latest")?,
+			(2000, "docker.io/org/node:latest".into())
+		);
+		assert!(matches!(parse_parachain_image("2000"), Err(Error::Config(..))));
+		assert!(matches!(parse_parachain_image("2000="), Err(Error::Config(..))));
+		assert!(matches!(parse_parachain_image("para=image"), Err(Error::Config(..))));
+		Ok(())
+	}
+
+	#[test]
+	fn test_network_provider() -> Result<()> {
+		use std::str::FromStr;
+		assert_eq!(NetworkProvider::from_str("k8s")?, NetworkProvider::K8s);
+		assert_eq!(NetworkProvider::default().to_string(), "native");
 		Ok(())
 	}
 
@@ -857,29 +1207,103 @@ mod tests {
 	}
 
 	#[tokio::test]
-	async fn test_process_url() -> Result<()> {
+	async fn test_local_network() -> Result<()> {
 		let temp_dir = tempfile::tempdir().expect("Could not create temp dir");
-		let cache = PathBuf::from(temp_dir.path());
-
-		let source = Source::Url {
-			name: "polkadot".to_string(),
-			version: TESTING_POLKADOT_VERSION.to_string(),
-			url: "https://github.com/paritytech/polkadot-sdk/releases/download/polkadot-v1.7.0/polkadot".to_string()
+		let node = temp_dir.path().join("parachain-template-node");
+		File::create(&node)?;
+		let network = LocalNetwork {
+			node: node.clone(),
+			para_id: 2000,
+			collators: 2,
+			collator_args: vec!["--rpc-max-connections=1000".into()],
+			validator_args: vec!["--pruning=archive".into()],
+			system_chains: vec![SystemChain::AssetHub],
+			chain_spec: None,
 		};
-		source.process(&cache, ()).await?;
-		assert!(temp_dir.path().join(POLKADOT_BINARY).exists());
+		let config = network.config();
+		assert_eq!(config["relaychain"]["chain"].as_str(), Some("rococo-local"));
+		assert_eq!(config["relaychain"]["nodes"].as_array_of_tables().map(|n| n.len()), Some(2));
+		assert_eq!(config["relaychain"]["default_args"][0].as_str(), Some("--pruning=archive"));
+		assert_eq!(config["parachains"][0]["id"].as_integer(), Some(2000));
+		assert_eq!(config["parachains"][0]["default_command"].as_str(), node.to_str());
+		assert_eq!(
+			config["parachains"][0]["default_args"][0].as_str(),
+			Some("--rpc-max-connections=1000")
+		);
+		assert_eq!(config["parachains"][0]["collators"][1]["name"].as_str(), Some("collator-02"));
+		assert_eq!(config["parachains"][1]["id"].as_integer(), Some(1000));
+		assert_eq!(config["parachains"][1]["chain"].as_str(), Some("asset-hub-rococo-local"));
+		assert_eq!(config["parachains"][1]["collators"][0]["name"].as_str(), Some("asset-hub"));
+		assert!(config["parachains"][1].get("add_to_genesis").is_none());
+		assert_eq!(config["parachains"][0]["add_to_genesis"].as_bool(), Some(false));
+		assert_eq!(config["parachains"][0]["register_para"].as_bool(), Some(false));
+		assert!(config["parachains"][0].get("chain_spec_path").is_none());
+		let chain_spec = temp_dir.path().join("raw-chain-spec.json");
+		let config =
+			LocalNetwork { chain_spec: Some(chain_spec.clone()), ..network.clone() }.config();
+		assert_eq!(config["parachains"][0]["chain_spec_path"].as_str(), chain_spec.to_str());
+
+		// The written configuration can be used to launch the network.
+		let path = temp_dir.path().join("target").join("pop").join("network.toml");
+		network.write(&path)?;
+		let zombienet = Zombienet::new(
+			temp_dir.path().to_path_buf(),
+			path.to_str().expect("valid path"),
+			Some(&TESTING_POLKADOT_VERSION.to_string()),
+			None,
+			None,
+		)
+		.await?;
+		// Only the system parachain is sourced, the binary of the project being built locally.
+		assert_eq!(zombienet.parachains.len(), 1);
+		assert_eq!(zombienet.parachains[0].name, POLKADOT_PARACHAIN_BINARY);
+		Ok(())
+	}
+
+	#[test]
+	fn test_system_chain() -> Result<()> {
+		assert_eq!("asset-hub".parse::<SystemChain>()?, SystemChain::AssetHub);
+		assert_eq!(SystemChain::AssetHub.para_id(), 1000);
+		for system_chain in SystemChain::VARIANTS {
+			assert!(system_chain.para_id() > 0);
+			assert!(system_chain.chain().ends_with("-rococo-local"));
+		}
+		assert!("relay".parse::<SystemChain>().is_err());
+		Ok(())
+	}
 
+	#[test]
+	fn test_uncached_chain() -> Result<()> {
+		let config: DocumentMut = "[relaychain]\nchain = \"rococo-local\"\n".parse()?;
+		let mut relay_chain = config["relaychain"].as_table().expect("table").clone();
+		assert_eq!(uncached_chain(&relay_chain), Some("rococo-local".into()));
+		relay_chain.insert("chain_spec_path", value("./rococo-local.json"));
+		assert_eq!(uncached_chain(&relay_chain), None);
+		assert_eq!(uncached_chain(&Table::new()), None);
 		Ok(())
 	}
 
 	#[test]
-	fn test_versioned_name() -> Result<()> {
-		let versioned_name =
-			Source::versioned_name("polkadot", Some(&TESTING_POLKADOT_VERSION.to_string()));
-		assert_eq!(versioned_name, POLKADOT_BINARY);
+	fn test_cached_chain_spec() -> Result<()> {
+		use std::os::unix::fs::PermissionsExt;
+		let temp_dir = tempfile::tempdir()?;
+		let cache = temp_dir.path();
+		let path = cache.join(POLKADOT_BINARY);
+		std::fs::write(&path, "#!/bin/sh\necho '{\"id\": \"rococo_local_testnet\"}'\n")?;
+		std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755))?;
+		let binary = Binary {
+			name: "polkadot".into(),
+			version: TESTING_POLKADOT_VERSION.into(),
+			path: path.clone(),
+			sources: Vec::new(),
+		};
 
-		let versioned_name_no_version = Source::versioned_name("polkadot", None);
-		assert_eq!(versioned_name_no_version, "polkadot");
+		let spec = cached_chain_spec(cache, &binary, LOCAL_RELAY_CHAIN)?;
+		assert_eq!(spec, cache.join(CHAIN_SPECS_DIR).join("polkadot-rococo-local-v1.7.0.json"));
+		assert!(std::fs::read_to_string(&spec)?.contains("rococo_local_testnet"));
+		// Once cached, the chain spec is reused without running the binary.
+		std::fs::remove_file(&path)?;
+		assert_eq!(cached_chain_spec(cache, &binary, LOCAL_RELAY_CHAIN)?, spec);
 		Ok(())
 	}
 
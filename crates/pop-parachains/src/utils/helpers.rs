@@ -1,31 +1,25 @@
 // SPDX-License-Identifier: GPL-3.0
 use std::{
 	fs::{self, OpenOptions},
-	io::{self, stdin, stdout, Write},
+	io::{self, Write},
 	path::Path,
 };
 
 use crate::errors::Error;
 
+/// Ensures nothing exists at `target`, without prompting to remove it so that generation never
+/// blocks on input.
 pub(crate) fn sanitize(target: &Path) -> Result<(), Error> {
-	if target.exists() {
-		print!("\"{}\" folder exists. Do you want to clean it? [y/n]: ", target.display());
-		stdout().flush()?;
-
-		let mut input = String::new();
-		stdin().read_line(&mut input)?;
-
-		if input.trim().to_lowercase() == "y" {
-			fs::remove_dir_all(target).map_err(|_| Error::Aborted)?;
-		} else {
-			return Err(Error::Aborted);
-		}
+	match target.exists() {
+		true => Err(Error::TargetExists(target.display().to_string())),
+		false => Ok(()),
 	}
-	Ok(())
 }
 
 pub fn is_initial_endowment_valid(initial_endowment: &str) -> bool {
-	initial_endowment.parse::<u128>().is_ok()
+	// Digits may be separated by underscores, as within Rust literals (e.g. 1_000_000).
+	(initial_endowment.starts_with(|c: char| c.is_ascii_digit())
+		&& initial_endowment.replace('_', "").parse::<u128>().is_ok())
 		|| is_valid_bitwise_left_shift(initial_endowment).is_ok()
 }
 // Auxiliar method to check if the endowment input with a shift left (1u64 << 60) format is valid.
@@ -107,10 +101,25 @@ mod tests {
 		Ok(())
 	}
 
+	#[test]
+	fn test_sanitize() -> Result<(), Box<dyn std::error::Error>> {
+		let temp_dir = tempdir()?;
+		let target = temp_dir.path().join("my-parachain");
+		sanitize(&target)?;
+		fs::create_dir(&target)?;
+		assert!(
+			matches!(sanitize(&target), Err(Error::TargetExists(t)) if t == target.display().to_string())
+		);
+		assert!(target.exists());
+		Ok(())
+	}
+
 	#[test]
 	fn test_is_initial_endowment_valid() {
 		assert_eq!(is_initial_endowment_valid("100000"), true);
 		assert_eq!(is_initial_endowment_valid("1u64 << 60"), true);
+		assert_eq!(is_initial_endowment_valid("1_000_000_000"), true);
+		assert_eq!(is_initial_endowment_valid("_1000"), false);
 		assert_eq!(is_initial_endowment_valid("wrong"), false);
 		assert_eq!(is_initial_endowment_valid(" "), false);
 	}
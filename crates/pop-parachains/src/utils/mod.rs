@@ -1,4 +1,3 @@
 // SPDX-License-Identifier: GPL-3.0
-pub mod git;
 pub mod helpers;
 pub mod pallet_helpers;
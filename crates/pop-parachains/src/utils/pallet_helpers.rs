@@ -1,39 +1,24 @@
 // SPDX-License-Identifier: GPL-3.0
 use crate::errors::Error;
-use std::{
-	env::current_dir,
-	fs,
-	path::{Path, PathBuf},
-	process,
-};
+use pop_common::project::find_root;
+use std::{env::current_dir, path::PathBuf};
 
 /// Resolve pallet path
-/// For a template it should be `<template>/pallets/`
-/// For no path, it should just place it in the current working directory
+/// For a template, or any of its subdirectories when no path is provided, it should be
+/// `<template>/pallets/`
+/// For another path, it should place it within that path
+/// For no path outside of a template, it should just place it in the current working directory
+/// The directory is not created, so that it can be resolved without side effects.
 pub fn resolve_pallet_path(path: Option<String>) -> Result<PathBuf, Error> {
-	if let Some(path) = path {
-		return Ok(Path::new(&path).to_path_buf());
-	}
+	let (dir, provided) = match path {
+		Some(path) => (PathBuf::from(path), true),
+		None => (current_dir().map_err(|_| Error::CurrentDirAccess)?, false),
+	};
 
-	// Check if inside a template
-	let cwd = current_dir().map_err(|_| Error::CurrentDirAccess)?;
-
-	let output = process::Command::new(env!("CARGO"))
-		.arg("locate-project")
-		.arg("--workspace")
-		.arg("--message-format=plain")
-		.output()
-		.map_err(|_| Error::WorkspaceLocate)?
-		.stdout;
-
-	let workspace_path = Path::new(std::str::from_utf8(&output).unwrap().trim());
-	if workspace_path == Path::new("") {
-		return Ok(cwd);
-	}
-
-	let pallet_path = workspace_path.parent().ok_or(Error::WorkspaceLocate)?.join("pallets");
-
-	fs::create_dir_all(&pallet_path).map_err(|_| Error::PalletDirCreation)?;
-
-	Ok(pallet_path)
+	// Check if inside a template, walking up to its root.
+	let root = match find_root(&dir) {
+		Some(root) if !provided || dir.canonicalize().is_ok_and(|dir| dir == root) => root,
+		_ => return Ok(dir),
+	};
+	Ok(root.join("pallets"))
 }
@@ -0,0 +1,229 @@
+// SPDX-License-Identifier: GPL-3.0
+use crate::{
+	call::{submit_call, Submitted},
+	errors::Error,
+	onboard::fetch,
+};
+use serde::Serialize;
+use std::str::FromStr;
+use subxt::{
+	backend::{legacy::LegacyRpcMethods, rpc::RpcClient},
+	dynamic::Value,
+	ext::scale_value::At,
+	utils::AccountId32,
+	PolkadotConfig,
+};
+use url::Url;
+
+/// The symbol of the native token of chains which do not specify one, as generated by pop.
+const DEFAULT_SYMBOL: &str = "UNIT";
+/// The number of decimals of the native token of chains which do not specify them.
+const DEFAULT_DECIMALS: u8 = 12;
+
+/// The native token of a chain, used to format and parse amounts in whole tokens.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Token {
+	/// The symbol of the token, e.g. `DOT`.
+	pub symbol: String,
+	/// The number of decimals of the token.
+	pub decimals: u8,
+}
+
+impl Default for Token {
+	fn default() -> Self {
+		Self { symbol: DEFAULT_SYMBOL.into(), decimals: DEFAULT_DECIMALS }
+	}
+}
+
+impl Token {
+	/// The native token of the chain at `url`, as per its properties.
+	///
+	/// # Arguments
+	///
+	/// * `url` - the endpoint of the chain
+	pub async fn fetch(url: &Url) -> Result<Self, Error> {
+		let rpc = RpcClient::from_url(url.as_str())
+			.await
+			.map_err(|e| Error::Extrinsic(format!("unable to connect to {url}: {e}")))?;
+		let properties = LegacyRpcMethods::<PolkadotConfig>::new(rpc)
+			.system_properties()
+			.await
+			.map_err(|e| Error::Extrinsic(format!("{e}")))?;
+		// Chains with several tokens list them, the first being the native token.
+		let first = |value: &serde_json::Value| match value {
+			serde_json::Value::Array(values) => values.first().cloned(),
+			value => Some(value.clone()),
+		};
+		let default = Self::default();
+		Ok(Self {
+			symbol: properties
+				.get("tokenSymbol")
+				.and_then(first)
+				.and_then(|s| s.as_str().map(String::from))
+				.unwrap_or(default.symbol),
+			decimals: properties
+				.get("tokenDecimals")
+				.and_then(first)
+				.and_then(|d| d.as_u64())
+				.and_then(|d| u8::try_from(d).ok())
+				.unwrap_or(default.decimals),
+		})
+	}
+
+	/// Formats `amount`, in the smallest unit of the token, as whole tokens.
+	///
+	/// # Arguments
+	///
+	/// * `amount` - the amount, in the smallest unit of the token
+	pub fn format(&self, amount: u128) -> String {
+		let unit = 10u128.pow(self.decimals.into());
+		let fraction = format!("{:0width$}", amount % unit, width = self.decimals as usize);
+		let fraction = fraction.trim_end_matches('0');
+		match fraction.is_empty() {
+			true => format!("{} {}", amount / unit, self.symbol),
+			false => format!("{}.{fraction} {}", amount / unit, self.symbol),
+		}
+	}
+
+	/// Parses an amount of whole tokens, e.g. `1.5` or `1.5 DOT`, into the smallest unit of the
+	/// token.
+	///
+	/// # Arguments
+	///
+	/// * `amount` - the amount of whole tokens, optionally followed by the symbol of the token
+	pub fn parse(&self, amount: &str) -> Result<u128, Error> {
+		let invalid = || Error::Config(format!("invalid amount `{amount}` of {}", self.symbol));
+		let mut parts = amount.split_whitespace();
+		let number = parts.next().ok_or_else(invalid)?;
+		match (parts.next(), parts.next()) {
+			(None, _) => {},
+			(Some(symbol), None) if symbol.eq_ignore_ascii_case(&self.symbol) => {},
+			_ => return Err(invalid()),
+		}
+		let (whole, fraction) = number.split_once('.').unwrap_or((number, ""));
+		if (whole.is_empty() && fraction.is_empty())
+			|| fraction.len() > self.decimals as usize
+			|| !whole.chars().chain(fraction.chars()).all(|c| c.is_ascii_digit())
+		{
+			return Err(invalid());
+		}
+		let digits = format!("{whole}{fraction:0<width$}", width = self.decimals as usize);
+		match digits.trim_start_matches('0') {
+			"" => Ok(0),
+			digits => digits.parse().map_err(|_| invalid()),
+		}
+	}
+}
+
+/// The balance of an account, in the smallest unit of the native token.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct Balance {
+	/// The balance which is not reserved, part of which may be frozen.
+	pub free: u128,
+	/// The balance reserved, e.g. by deposits.
+	pub reserved: u128,
+	/// The part of the free balance which cannot be transferred, e.g. as it is staked.
+	pub frozen: u128,
+}
+
+impl Balance {
+	/// The balance which can be transferred.
+	pub fn transferable(&self) -> u128 {
+		self.free.saturating_sub(self.frozen)
+	}
+}
+
+/// The balance of the account `address` on the chain at `url`.
+///
+/// # Arguments
+///
+/// * `url` - the endpoint of the chain
+/// * `address` - the SS58 address of the account
+pub async fn balance(url: &Url, address: &str) -> Result<Balance, Error> {
+	let account = parse_address(address)?;
+	let Some(info) = fetch(url, "System", "Account", vec![Value::from_bytes(account.0)]).await?
+	else {
+		// Accounts without any balance are not stored.
+		return Ok(Balance::default());
+	};
+	let info = info.to_value().map_err(|e| Error::Extrinsic(format!("{e}")))?;
+	let data = info.at("data");
+	let field = |name: &str| data.at(name).and_then(|v| v.as_u128());
+	Ok(Balance {
+		free: field("free")
+			.ok_or(Error::Extrinsic("unable to decode the balance of the account".into()))?,
+		reserved: field("reserved").unwrap_or_default(),
+		// Older runtimes distinguish the balance frozen for fees from the rest.
+		frozen: field("frozen")
+			.or_else(|| Some(field("misc_frozen")?.max(field("fee_frozen")?)))
+			.unwrap_or_default(),
+	})
+}
+
+/// Transfers `amount` from the account of `suri` to the account `dest` on the chain at `url`,
+/// keeping the sending account alive.
+///
+/// # Arguments
+///
+/// * `url` - the endpoint of the chain
+/// * `suri` - the secret key URI of the sending account
+/// * `dest` - the SS58 address of the receiving account
+/// * `amount` - the amount transferred, in the smallest unit of the native token
+pub async fn transfer(url: &Url, suri: &str, dest: &str, amount: u128) -> Result<Submitted, Error> {
+	let dest = parse_address(dest)?;
+	let call = subxt::dynamic::tx(
+		"Balances",
+		"transfer_keep_alive",
+		vec![Value::unnamed_variant("Id", [Value::from_bytes(dest.0)]), Value::u128(amount)],
+	);
+	submit_call(url, suri, &call).await
+}
+
+fn parse_address(address: &str) -> Result<AccountId32, Error> {
+	AccountId32::from_str(address.trim())
+		.map_err(|_| Error::Config(format!("invalid address `{address}`, expected SS58")))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn format_works() {
+		let token = Token::default();
+		assert_eq!(token.format(0), "0 UNIT");
+		assert_eq!(token.format(1_500_000_000_000), "1.5 UNIT");
+		let dot = Token { symbol: "DOT".into(), decimals: 10 };
+		assert_eq!(dot.format(10_000_000_000), "1 DOT");
+		assert_eq!(dot.format(1), "0.0000000001 DOT");
+	}
+
+	#[test]
+	fn parse_works() -> Result<(), Error> {
+		let dot = Token { symbol: "DOT".into(), decimals: 10 };
+		assert_eq!(dot.parse("1")?, 10_000_000_000);
+		assert_eq!(dot.parse("1.5 DOT")?, 15_000_000_000);
+		assert_eq!(dot.parse(".5 dot")?, 5_000_000_000);
+		assert_eq!(dot.parse("0.0000000001")?, 1);
+		assert_eq!(dot.parse("0")?, 0);
+		for invalid in ["", ".", "1.5 KSM", "-1", "1e3", "0.00000000001", "1 DOT DOT"] {
+			assert!(matches!(dot.parse(invalid), Err(Error::Config(..))), "{invalid}");
+		}
+		// Amounts round-trip.
+		assert_eq!(dot.parse(&dot.format(12_345_678_901))?, 12_345_678_901);
+		Ok(())
+	}
+
+	#[test]
+	fn transferable_works() {
+		let balance = Balance { free: 100, reserved: 10, frozen: 30 };
+		assert_eq!(balance.transferable(), 70);
+		assert_eq!(Balance { free: 10, frozen: 30, ..Default::default() }.transferable(), 0);
+	}
+
+	#[test]
+	fn parse_address_works() {
+		assert!(parse_address("5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY").is_ok());
+		assert!(matches!(parse_address("alice"), Err(Error::Config(..))));
+	}
+}
@@ -0,0 +1,91 @@
+// SPDX-License-Identifier: GPL-3.0
+use crate::errors::Error;
+use std::{
+	fs,
+	path::{Path, PathBuf},
+	time::SystemTime,
+};
+
+/// The number of files within a directory along with their latest modification time, which
+/// changes whenever a file is added, removed or modified.
+type Fingerprint = (usize, Option<SystemTime>);
+
+/// Watches the sources of a crate for changes, by polling the modification times of its files.
+pub struct SourceWatcher {
+	dir: PathBuf,
+	fingerprint: Fingerprint,
+}
+
+impl SourceWatcher {
+	/// Starts watching the sources within `dir`, excluding any build output.
+	///
+	/// # Arguments
+	///
+	/// * `dir` - the directory of the crate
+	pub fn new(dir: &Path) -> Result<Self, Error> {
+		Ok(Self { dir: dir.to_path_buf(), fingerprint: fingerprint(dir)? })
+	}
+
+	/// Whether the sources changed since they were last checked.
+	pub fn changed(&mut self) -> Result<bool, Error> {
+		let fingerprint = fingerprint(&self.dir)?;
+		if fingerprint == self.fingerprint {
+			return Ok(false);
+		}
+		self.fingerprint = fingerprint;
+		Ok(true)
+	}
+}
+
+fn fingerprint(dir: &Path) -> Result<Fingerprint, Error> {
+	let mut fingerprint = (0, None);
+	for entry in fs::read_dir(dir)?.flatten() {
+		let name = entry.file_name();
+		// Build output and hidden directories (e.g. `.git`) are not sources.
+		if name == "target" || name.to_string_lossy().starts_with('.') {
+			continue;
+		}
+		let metadata = entry.metadata()?;
+		let (files, modified) = match metadata.is_dir() {
+			true => fingerprint(&entry.path())?,
+			false => (1, metadata.modified().ok()),
+		};
+		fingerprint.0 += files;
+		fingerprint.1 = fingerprint.1.max(modified);
+	}
+	Ok(fingerprint)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+	use std::time::Duration;
+
+	#[test]
+	fn test_source_watcher() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let dir = temp_dir.path();
+		fs::create_dir_all(dir.join("src"))?;
+		fs::write(dir.join("src").join("lib.rs"), "")?;
+		let mut watcher = SourceWatcher::new(dir)?;
+		assert!(!watcher.changed()?);
+
+		// Build output is ignored.
+		fs::create_dir_all(dir.join("target"))?;
+		fs::write(dir.join("target").join("runtime.wasm"), "")?;
+		assert!(!watcher.changed()?);
+
+		fs::write(dir.join("src").join("weights.rs"), "")?;
+		assert!(watcher.changed()?);
+		assert!(!watcher.changed()?);
+
+		let lib = fs::File::options().write(true).open(dir.join("src").join("lib.rs"))?;
+		lib.set_modified(SystemTime::now() + Duration::from_secs(60))?;
+		assert!(watcher.changed()?);
+
+		fs::remove_file(dir.join("src").join("weights.rs"))?;
+		assert!(watcher.changed()?);
+		Ok(())
+	}
+}
@@ -0,0 +1,546 @@
+// SPDX-License-Identifier: GPL-3.0
+use crate::{call::Submitted, errors::Error, onboard::client, relay::submit};
+use scale_info::TypeDef;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fmt, fs, path::Path, str::FromStr, time::Duration};
+use strum_macros::Display;
+use subxt::{
+	dynamic::Value,
+	ext::scale_value::{At, Composite, ValueDef},
+	tx::DynamicPayload,
+	utils::AccountId32,
+	Metadata,
+};
+use url::Url;
+
+/// The pallets dispatching XCM programs: `PolkadotXcm` on parachains and `XcmPallet` on relay
+/// chains.
+const XCM_PALLETS: [&str; 2] = ["PolkadotXcm", "XcmPallet"];
+
+/// A chain of a network, from the perspective of XCM.
+#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
+pub enum Chain {
+	/// The relay chain.
+	Relay,
+	/// A parachain, by its identifier.
+	Para(u32),
+}
+
+impl FromStr for Chain {
+	type Err = Error;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s.trim() {
+			s if s.eq_ignore_ascii_case("relay") => Ok(Chain::Relay),
+			s => s.parse().map(Chain::Para).map_err(|_| {
+				Error::Config(format!("invalid chain `{s}`, expected `relay` or a para id"))
+			}),
+		}
+	}
+}
+
+impl fmt::Display for Chain {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Chain::Relay => write!(f, "relay"),
+			Chain::Para(id) => write!(f, "{id}"),
+		}
+	}
+}
+
+impl Chain {
+	/// The location of `target`, from the perspective of this chain.
+	///
+	/// # Arguments
+	///
+	/// * `target` - the chain located
+	fn location(&self, target: Chain) -> (u8, Option<u32>) {
+		match (self, target) {
+			(Chain::Relay, Chain::Relay) => (0, None),
+			(Chain::Relay, Chain::Para(id)) => (0, Some(id)),
+			(Chain::Para(_), Chain::Relay) => (1, None),
+			(Chain::Para(a), Chain::Para(b)) if *a == b => (0, None),
+			(Chain::Para(_), Chain::Para(b)) => (1, Some(b)),
+		}
+	}
+}
+
+/// The endpoints of the chains of a local network launched by pop, recorded so that other
+/// commands can reach them while it is running.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct NetworkEndpoints {
+	/// The endpoint of the relay chain.
+	pub relay: Option<Url>,
+	/// The endpoint of each parachain, by para id.
+	#[serde(default)]
+	pub parachains: BTreeMap<u32, Url>,
+}
+
+impl NetworkEndpoints {
+	/// Loads the endpoints recorded at `path`, if any.
+	///
+	/// # Arguments
+	///
+	/// * `path` - path to the file recording the endpoints
+	pub fn load(path: &Path) -> Result<Option<Self>, Error> {
+		if !path.exists() {
+			return Ok(None);
+		}
+		serde_json::from_str(&fs::read_to_string(path)?)
+			.map(Some)
+			.map_err(|e| Error::Config(format!("invalid {}: {e}", path.display())))
+	}
+
+	/// Records the endpoints at `path`.
+	///
+	/// # Arguments
+	///
+	/// * `path` - path to the file recording the endpoints
+	pub fn save(&self, path: &Path) -> Result<(), Error> {
+		if let Some(dir) = path.parent() {
+			fs::create_dir_all(dir)?;
+		}
+		let json = serde_json::to_string_pretty(self).map_err(|e| Error::Config(format!("{e}")))?;
+		fs::write(path, json)?;
+		Ok(())
+	}
+
+	/// The endpoint of `chain`, if recorded.
+	///
+	/// # Arguments
+	///
+	/// * `chain` - the chain
+	pub fn endpoint(&self, chain: Chain) -> Option<&Url> {
+		match chain {
+			Chain::Relay => self.relay.as_ref(),
+			Chain::Para(id) => self.parachains.get(&id),
+		}
+	}
+}
+
+/// How assets are transferred between chains.
+#[derive(Clone, Copy, Debug, Display, Eq, PartialEq)]
+pub enum TransferKind {
+	/// The asset is held in reserve by its reserve chain, while a derivative is minted on the
+	/// destination.
+	#[strum(to_string = "reserve transfer")]
+	ReserveTransfer,
+	/// The asset is burnt on the origin and minted on the destination, which trust each other.
+	#[strum(to_string = "teleport")]
+	Teleport,
+}
+
+impl TransferKind {
+	/// The call of the XCM pallet transferring assets this way.
+	fn call(&self) -> &'static str {
+		match self {
+			TransferKind::ReserveTransfer => "limited_reserve_transfer_assets",
+			TransferKind::Teleport => "limited_teleport_assets",
+		}
+	}
+}
+
+/// An asset transferred between chains.
+#[derive(Clone, Debug, PartialEq)]
+pub struct XcmTransfer {
+	/// How the asset is transferred.
+	pub kind: TransferKind,
+	/// The chain the asset is transferred from.
+	pub from: Chain,
+	/// The chain the asset is transferred to.
+	pub to: Chain,
+	/// The chain whose native token is transferred.
+	pub asset: Chain,
+	/// The amount transferred, in the smallest unit of the asset.
+	pub amount: u128,
+	/// The SS58 address of the account receiving the asset on the destination.
+	pub beneficiary: String,
+}
+
+impl XcmTransfer {
+	/// A description of the transfer, e.g. `teleport of 1000000000000 (relay token) from relay to
+	/// 1000`.
+	pub fn describe(&self) -> String {
+		let asset = match self.asset {
+			Chain::Relay => "relay token".to_string(),
+			Chain::Para(id) => format!("token of {id}"),
+		};
+		format!("{} of {} ({asset}) from {} to {}", self.kind, self.amount, self.from, self.to)
+	}
+}
+
+/// The outcome of an XCM message delivered to a chain.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Delivery {
+	/// The number of the block of the destination in which the message was processed.
+	pub block: u64,
+	/// The events of the destination reporting the processing of the message, e.g.
+	/// `MessageQueue.Processed`.
+	pub events: Vec<String>,
+}
+
+/// Constructs the call of the XCM pallet of the origin chain transferring an asset, using the
+/// latest version of XCM supported by the chain.
+///
+/// # Arguments
+///
+/// * `metadata` - the metadata of the origin chain
+/// * `transfer` - the transfer
+pub fn construct_xcm_transfer(
+	metadata: &Metadata,
+	transfer: &XcmTransfer,
+) -> Result<DynamicPayload, Error> {
+	let call = transfer.kind.call();
+	let (pallet, variant) = XCM_PALLETS
+		.iter()
+		.find_map(|name| {
+			let pallet = metadata.pallet_by_name(name)?;
+			Some((pallet.name().to_string(), pallet.call_variant_by_name(call)?.clone()))
+		})
+		.ok_or_else(|| Error::Metadata(format!("the chain does not support `{call}` via XCM")))?;
+	// The versions of XCM supported are those of the destination parameter.
+	let versions: Vec<String> = variant
+		.fields
+		.first()
+		.and_then(|field| metadata.types().resolve(field.ty.id))
+		.map(|ty| match &ty.type_def {
+			TypeDef::Variant(v) => v.variants.iter().map(|v| v.name.clone()).collect(),
+			_ => Vec::new(),
+		})
+		.unwrap_or_default();
+	let version = ["V4", "V3"]
+		.into_iter()
+		.find(|v| versions.iter().any(|name| name == v))
+		.ok_or_else(|| Error::Metadata("the chain supports neither XCM v3 nor v4".into()))?;
+	let beneficiary = AccountId32::from_str(transfer.beneficiary.trim()).map_err(|_| {
+		Error::Config(format!("invalid beneficiary `{}`, expected SS58", transfer.beneficiary))
+	})?;
+	let args = xcm_args(version, transfer, beneficiary.0);
+	Ok(subxt::dynamic::tx(pallet, call, args))
+}
+
+/// The arguments of a transfer of assets by the XCM pallet, in the XCM `version`.
+fn xcm_args(version: &str, transfer: &XcmTransfer, beneficiary: [u8; 32]) -> Vec<Value> {
+	let v4 = version == "V4";
+	let location = |(parents, para): (u8, Option<u32>), junction: Option<Value>| {
+		let junction = junction
+			.or(para.map(|id| Value::unnamed_variant("Parachain", [Value::u128(id.into())])));
+		let interior = match junction {
+			// Junctions are held within an array as of v4.
+			Some(junction) if v4 => {
+				Value::unnamed_variant("X1", [Value::unnamed_composite([junction])])
+			},
+			Some(junction) => Value::unnamed_variant("X1", [junction]),
+			None => Value::unnamed_variant("Here", []),
+		};
+		Value::named_composite([("parents", Value::u128(parents.into())), ("interior", interior)])
+	};
+	let versioned = |value: Value| Value::unnamed_variant(version, [value]);
+
+	let dest = location(transfer.from.location(transfer.to), None);
+	let account = Value::named_variant(
+		"AccountId32",
+		[("network", Value::unnamed_variant("None", [])), ("id", Value::from_bytes(beneficiary))],
+	);
+	let beneficiary = location((0, None), Some(account));
+	let asset_location = location(transfer.from.location(transfer.asset), None);
+	let id = match v4 {
+		true => Value::unnamed_composite([asset_location]),
+		false => Value::unnamed_variant("Concrete", [asset_location]),
+	};
+	let asset = Value::named_composite([
+		("id", id),
+		("fun", Value::unnamed_variant("Fungible", [Value::u128(transfer.amount)])),
+	]);
+	vec![
+		versioned(dest),
+		versioned(beneficiary),
+		versioned(Value::unnamed_composite([Value::unnamed_composite([asset])])),
+		// The fees are paid using the asset transferred.
+		Value::u128(0),
+		Value::unnamed_variant("Unlimited", []),
+	]
+}
+
+/// Submits `call`, transferring assets from the chain at `url`, signed by the account of `suri`.
+/// Fails unless the XCM program was executed successfully on the origin chain.
+///
+/// # Arguments
+///
+/// * `url` - the endpoint of the origin chain
+/// * `suri` - the secret key URI of the account transferring the assets
+/// * `call` - the call transferring the assets, as constructed by [`construct_xcm_transfer`]
+pub async fn submit_xcm_transfer(
+	url: &Url,
+	suri: &str,
+	call: &DynamicPayload,
+) -> Result<Submitted, Error> {
+	let events = submit(url, suri, call).await?;
+	for event in events.iter() {
+		let event = event.map_err(|e| Error::Extrinsic(format!("{e}")))?;
+		if XCM_PALLETS.contains(&event.pallet_name()) && event.variant_name() == "Attempted" {
+			let fields = event.field_values().map_err(|e| Error::Extrinsic(format!("{e}")))?;
+			let outcome = fields.at("outcome").map(variant_name).unwrap_or_default();
+			if outcome != "Complete" {
+				return Err(Error::Extrinsic(format!(
+					"the XCM program was not executed on the origin chain: {outcome}"
+				)));
+			}
+		}
+	}
+	let hash = format!("{:?}", events.extrinsic_hash());
+	let events = events
+		.iter()
+		.filter_map(|e| e.ok())
+		.map(|e| format!("{}.{}", e.pallet_name(), e.variant_name()))
+		.collect();
+	Ok(Submitted { hash, events })
+}
+
+/// Waits until the chain at `url` processes an XCM message sent by `from`, within `timeout`.
+/// Fails when the message could not be processed.
+///
+/// # Arguments
+///
+/// * `url` - the endpoint of the destination chain
+/// * `from` - the chain which sent the message
+/// * `timeout` - how long to wait for the message
+pub async fn wait_for_delivery(
+	url: &Url,
+	from: Chain,
+	timeout: Duration,
+) -> Result<Delivery, Error> {
+	let client = client(url).await?;
+	let mut blocks = client
+		.blocks()
+		.subscribe_best()
+		.await
+		.map_err(|e| Error::Extrinsic(format!("{e}")))?;
+	let wait = async {
+		while let Some(block) = blocks.next().await {
+			let block = block.map_err(|e| Error::Extrinsic(format!("{e}")))?;
+			let events = block.events().await.map_err(|e| Error::Extrinsic(format!("{e}")))?;
+			for event in events.iter() {
+				let event = event.map_err(|e| Error::Extrinsic(format!("{e}")))?;
+				let fields = event.field_values().map_err(|e| Error::Extrinsic(format!("{e}")))?;
+				let name = format!("{}.{}", event.pallet_name(), event.variant_name());
+				let Some(success) = processed(&name, &fields, from) else { continue };
+				if !success {
+					return Err(Error::Extrinsic(format!(
+						"the XCM message could not be processed by the destination ({name})"
+					)));
+				}
+				return Ok(Delivery { block: block.number().into(), events: vec![name] });
+			}
+		}
+		Err(Error::Extrinsic("the subscription to the blocks of the destination ended".into()))
+	};
+	tokio::time::timeout(timeout, wait).await.map_err(|_| {
+		Error::Extrinsic(format!(
+			"the XCM message was not processed by the destination within {}s",
+			timeout.as_secs()
+		))
+	})?
+}
+
+/// Whether the event `name`, with `fields`, reports an XCM message sent by `from` as processed
+/// successfully, if it reports one at all.
+fn processed<T>(name: &str, fields: &Composite<T>, from: Chain) -> Option<bool> {
+	match name {
+		"MessageQueue.Processed" if fields.at("origin").is_some_and(|o| is_origin(o, from)) => {
+			Some(fields.at("success").and_then(|s| s.as_bool()).unwrap_or(true))
+		},
+		"MessageQueue.ProcessingFailed"
+			if fields.at("origin").is_some_and(|o| is_origin(o, from)) =>
+		{
+			Some(false)
+		},
+		_ => None,
+	}
+}
+
+/// Whether the origin of a message queue, e.g. `Sibling(1000)` or `Ump(Para(1000))`, is `from`.
+fn is_origin<T>(origin: &Value<T>, from: Chain) -> bool {
+	let ValueDef::Variant(variant) = &origin.value else { return false };
+	let inner = variant.values.values().next();
+	match (variant.name.as_str(), from) {
+		("Parent", Chain::Relay) => true,
+		("Sibling", Chain::Para(id)) => inner.and_then(para_id) == Some(id),
+		("Ump", Chain::Para(id)) => inner.is_some_and(|inner| is_origin_para(inner, id)),
+		_ => false,
+	}
+}
+
+fn is_origin_para<T>(value: &Value<T>, id: u32) -> bool {
+	matches!(&value.value, ValueDef::Variant(v) if v.name == "Para"
+		&& v.values.values().next().and_then(para_id) == Some(id))
+}
+
+/// The para id held by `value`, unwrapping the composites wrapping it.
+fn para_id<T>(value: &Value<T>) -> Option<u32> {
+	match &value.value {
+		ValueDef::Composite(composite) if composite.len() == 1 => {
+			composite.values().next().and_then(para_id)
+		},
+		_ => value.as_u128().and_then(|id| u32::try_from(id).ok()),
+	}
+}
+
+fn variant_name(value: &Value<u32>) -> String {
+	match &value.value {
+		ValueDef::Variant(variant) => variant.name.clone(),
+		_ => value.to_string(),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const ALICE: &str = "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY";
+
+	fn transfer(from: Chain, to: Chain, asset: Chain) -> XcmTransfer {
+		XcmTransfer {
+			kind: TransferKind::Teleport,
+			from,
+			to,
+			asset,
+			amount: 1_000,
+			beneficiary: ALICE.into(),
+		}
+	}
+
+	#[test]
+	fn chain_works() -> Result<(), Error> {
+		assert_eq!("relay".parse::<Chain>()?, Chain::Relay);
+		assert_eq!(" 1000 ".parse::<Chain>()?, Chain::Para(1000));
+		assert!(matches!("asset-hub".parse::<Chain>(), Err(Error::Config(..))));
+		assert_eq!(Chain::Para(1000).to_string(), "1000");
+		assert_eq!(Chain::Relay.location(Chain::Para(1000)), (0, Some(1000)));
+		assert_eq!(Chain::Para(1000).location(Chain::Relay), (1, None));
+		assert_eq!(Chain::Para(1000).location(Chain::Para(1000)), (0, None));
+		assert_eq!(Chain::Para(1000).location(Chain::Para(2000)), (1, Some(2000)));
+		Ok(())
+	}
+
+	#[test]
+	fn network_endpoints_work() -> anyhow::Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let path = temp_dir.path().join("pop").join("network.json");
+		assert_eq!(NetworkEndpoints::load(&path)?, None);
+		let endpoints = NetworkEndpoints {
+			relay: Some(Url::parse("ws://127.0.0.1:9944")?),
+			parachains: BTreeMap::from([(1000, Url::parse("ws://127.0.0.1:9946")?)]),
+		};
+		endpoints.save(&path)?;
+		let endpoints = NetworkEndpoints::load(&path)?.unwrap();
+		assert_eq!(
+			endpoints.endpoint(Chain::Para(1000)).map(Url::as_str),
+			Some("ws://127.0.0.1:9946")
+		);
+		assert_eq!(endpoints.endpoint(Chain::Para(2000)), None);
+		assert!(endpoints.endpoint(Chain::Relay).is_some());
+		Ok(())
+	}
+
+	#[test]
+	fn xcm_args_works() {
+		let beneficiary = [1u8; 32];
+		// A teleport of the relay token from the relay chain to a parachain, as of XCM v3.
+		let args =
+			xcm_args("V3", &transfer(Chain::Relay, Chain::Para(1000), Chain::Relay), beneficiary);
+		assert_eq!(args.len(), 5);
+		let parachain = Value::unnamed_variant("Parachain", [Value::u128(1000)]);
+		assert_eq!(
+			args[0],
+			Value::unnamed_variant(
+				"V3",
+				[Value::named_composite([
+					("parents", Value::u128(0)),
+					("interior", Value::unnamed_variant("X1", [parachain.clone()])),
+				])]
+			)
+		);
+		let here = Value::named_composite([
+			("parents", Value::u128(0)),
+			("interior", Value::unnamed_variant("Here", [])),
+		]);
+		let asset = Value::named_composite([
+			("id", Value::unnamed_variant("Concrete", [here])),
+			("fun", Value::unnamed_variant("Fungible", [Value::u128(1_000)])),
+		]);
+		assert_eq!(
+			args[2],
+			Value::unnamed_variant(
+				"V3",
+				[Value::unnamed_composite([Value::unnamed_composite([asset])])]
+			)
+		);
+		assert_eq!(args[4], Value::unnamed_variant("Unlimited", []));
+
+		// Junctions are held within an array as of XCM v4.
+		let args = xcm_args(
+			"V4",
+			&transfer(Chain::Para(1000), Chain::Para(2000), Chain::Relay),
+			beneficiary,
+		);
+		let parachain = Value::unnamed_variant("Parachain", [Value::u128(2000)]);
+		assert_eq!(
+			args[0],
+			Value::unnamed_variant(
+				"V4",
+				[Value::named_composite([
+					("parents", Value::u128(1)),
+					(
+						"interior",
+						Value::unnamed_variant("X1", [Value::unnamed_composite([parachain])])
+					),
+				])]
+			)
+		);
+	}
+
+	#[test]
+	fn processed_works() {
+		let sibling = |id: u128| {
+			Composite::named([
+				(
+					"origin",
+					Value::unnamed_variant(
+						"Sibling",
+						[Value::unnamed_composite([Value::u128(id)])],
+					),
+				),
+				("success", Value::bool(true)),
+			])
+		};
+		assert_eq!(
+			processed("MessageQueue.Processed", &sibling(1000), Chain::Para(1000)),
+			Some(true)
+		);
+		assert_eq!(processed("MessageQueue.Processed", &sibling(2000), Chain::Para(1000)), None);
+		assert_eq!(processed("System.ExtrinsicSuccess", &sibling(1000), Chain::Para(1000)), None);
+		let ump = Composite::named([
+			(
+				"origin",
+				Value::unnamed_variant(
+					"Ump",
+					[Value::unnamed_variant("Para", [Value::u128(1000)])],
+				),
+			),
+			("success", Value::bool(false)),
+		]);
+		assert_eq!(processed("MessageQueue.Processed", &ump, Chain::Para(1000)), Some(false));
+		let parent = Composite::named([("origin", Value::unnamed_variant("Parent", []))]);
+		assert_eq!(processed("MessageQueue.ProcessingFailed", &parent, Chain::Relay), Some(false));
+		assert_eq!(processed("MessageQueue.Processed", &parent, Chain::Para(1000)), None);
+	}
+
+	#[test]
+	fn describe_works() {
+		assert_eq!(
+			transfer(Chain::Relay, Chain::Para(1000), Chain::Relay).describe(),
+			"teleport of 1000 (relay token) from relay to 1000"
+		);
+	}
+}
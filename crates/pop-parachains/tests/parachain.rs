@@ -6,7 +6,6 @@ use url::Url;
 
 const CONFIG_FILE_PATH: &str = "../../tests/zombienet.toml";
 const TESTING_POLKADOT_VERSION: &str = "v1.7.0";
-const POLKADOT_BINARY: &str = "polkadot-v1.7.0";
 const POLKADOT_SDK: &str = "https://github.com/paritytech/polkadot-sdk";
 
 #[tokio::test]
@@ -40,17 +39,17 @@ async fn test_process_git() -> Result<()> {
 	let repo = Url::parse(POLKADOT_SDK).expect("repository url valid");
 	let source = Source::Git {
 		url: repo.into(),
-		branch: Some(format!("release-polkadot-{version}")),
+		branch: Some(format!("polkadot-{version}")),
 		package: "polkadot".to_string(),
 		binaries: ["polkadot", "polkadot-execute-worker", "polkadot-prepare-worker"]
 			.iter()
 			.map(|b| b.to_string())
 			.collect(),
-		version: Some(version),
+		version: Some(version.clone()),
 	};
 
 	source.process(&cache, ()).await?;
-	assert!(temp_dir.path().join(POLKADOT_BINARY).exists());
+	assert!(Source::cached_path(&cache, "polkadot", Some(&version)).exists());
 
 	Ok(())
 }
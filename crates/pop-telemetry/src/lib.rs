@@ -1,4 +1,7 @@
 // SPDX-License-Identifier: GPL-3.0
+mod queue;
+
+pub use queue::Queue;
 use reqwest::Client;
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use serde_json::{json, Value};
@@ -40,6 +43,8 @@ pub struct Telemetry {
 	opt_out: bool,
 	// Reqwest client
 	client: Client,
+	// Where events which could not be sent are queued, to be sent by a later run
+	queue: Option<Queue>,
 }
 
 impl Telemetry {
@@ -59,7 +64,17 @@ impl Telemetry {
 	fn init(endpoint: String, config_path: &PathBuf) -> Self {
 		let opt_out = Self::is_opt_out(&config_path);
 
-		Telemetry { endpoint, opt_out, client: Client::new() }
+		Telemetry { endpoint, opt_out, client: Client::new(), queue: None }
+	}
+
+	/// Queue the events which could not be sent within `queue`, so that they are sent by a later
+	/// run.
+	///
+	/// parameters:
+	/// `queue`: the local queue of events
+	pub fn with_queue(mut self, queue: Queue) -> Self {
+		self.queue = Some(queue);
+		self
 	}
 
 	fn is_opt_out_from_config(config_file_path: &PathBuf) -> bool {
@@ -92,30 +107,64 @@ impl Telemetry {
 
 	/// Send JSON payload to saved api endpoint.
 	/// Returns error and will not send anything if opt-out is true.
-	/// Returns error from reqwest if the sending fails.
-	/// It sends message only once as "best effort". There is no retry on error
+	/// Returns error from reqwest if the sending fails, having queued the payload when a queue is
+	/// set. It sends message only once as "best effort". There is no retry on error
 	/// in order to keep overhead to a minimal.
 	async fn send_json(&self, payload: Value) -> Result<()> {
 		if self.opt_out {
 			return Err(TelemetryError::OptedOut);
 		}
 
-		let request_builder = self.client.post(&self.endpoint);
+		let res = self.post(&payload).await;
+		if let (Err(_), Some(queue)) = (&res, &self.queue) {
+			queue.push(&payload)?;
+		}
+		res
+	}
+
+	/// Send the events queued by previous runs, queueing those which could not be sent again.
+	/// Returns error and will not send anything if opt-out is true.
+	pub async fn flush(&self) -> Result<()> {
+		if self.opt_out {
+			return Err(TelemetryError::OptedOut);
+		}
+		let Some(queue) = &self.queue else {
+			return Ok(());
+		};
+
+		let mut events = queue.take()?.into_iter();
+		while let Some(event) = events.next() {
+			if let Err(err) = self.post(&event).await {
+				for event in std::iter::once(event).chain(events) {
+					queue.push(&event)?;
+				}
+				return Err(err);
+			}
+		}
+		Ok(())
+	}
 
-		request_builder
-			.json(&payload)
+	async fn post(&self, payload: &Value) -> Result<()> {
+		self.client
+			.post(&self.endpoint)
+			.json(payload)
 			.send()
 			.await
+			.and_then(|response| response.error_for_status())
 			.map_err(TelemetryError::NetworkError)?;
 
 		Ok(())
 	}
 }
 
-/// Generically reports that the CLI was used to the telemetry endpoint.
+/// Generically reports that the CLI was used to the telemetry endpoint, along with the events
+/// queued by previous runs.
 /// There is explicitly no reqwest retries on failure to ensure overhead
 /// stays to a minimum.
 pub async fn record_cli_used(tel: Telemetry) -> Result<()> {
+	let res = tel.flush().await;
+	log::debug!("flush result: {:?}", res);
+
 	let payload = generate_payload("", json!({}));
 
 	let res = tel.send_json(payload).await;
@@ -160,6 +209,12 @@ pub fn config_file_path() -> Result<PathBuf> {
 	Ok(config_path.join("config.json"))
 }
 
+/// Returns the path of the file queueing the events which could not be sent, within the OS's
+/// default config directory.
+pub fn queue_file_path() -> Result<PathBuf> {
+	Ok(config_file_path()?.with_file_name("telemetry-queue.jsonl"))
+}
+
 /// Writes opt-out to the configuration file at the specified path.
 /// opt-out is currently the only config type. Hence, if the file exists, it will be overwritten.
 ///
@@ -263,6 +318,7 @@ mod tests {
 			endpoint: "127.0.0.1".to_string(),
 			opt_out: true,
 			client: Default::default(),
+			queue: None,
 		};
 
 		assert_eq!(tel.endpoint, expected_telemetry.endpoint);
@@ -270,8 +326,12 @@ mod tests {
 
 		let tel = Telemetry::new(&config_path);
 
-		let expected_telemetry =
-			Telemetry { endpoint: ENDPOINT.to_string(), opt_out: true, client: Default::default() };
+		let expected_telemetry = Telemetry {
+			endpoint: ENDPOINT.to_string(),
+			opt_out: true,
+			client: Default::default(),
+			queue: None,
+		};
 
 		assert_eq!(tel.endpoint, expected_telemetry.endpoint);
 		assert_eq!(tel.opt_out, expected_telemetry.opt_out);
@@ -368,4 +428,31 @@ mod tests {
 		));
 		mock.assert_async().await;
 	}
+
+	#[tokio::test]
+	async fn failed_events_are_queued_and_flushed() -> Result<()> {
+		let _ = env_logger::try_init();
+		let mut mock_server = Server::new_async().await;
+
+		let mut endpoint = mock_server.url();
+		endpoint.push_str("/api/send");
+
+		let temp_dir = TempDir::new().unwrap();
+		let queue = Queue::new(temp_dir.path().join("telemetry-queue.jsonl"));
+		let mut tel = Telemetry::init(endpoint, &temp_dir.path().join("config.json"))
+			.with_queue(queue.clone());
+		tel.opt_out = false; // override as endpoint is mocked
+
+		let expected_payload = generate_payload("new", json!("parachain"));
+		let failure = mock_server.mock("POST", "/api/send").with_status(500).create_async().await;
+		assert!(record_cli_command(tel.clone(), "new", json!("parachain")).await.is_err());
+		failure.assert_async().await;
+		failure.remove_async().await;
+
+		let mock = default_mock(&mut mock_server, expected_payload.to_string()).await;
+		tel.flush().await?;
+		mock.assert_async().await;
+		assert!(queue.take()?.is_empty());
+		Ok(())
+	}
 }
@@ -0,0 +1,87 @@
+// SPDX-License-Identifier: GPL-3.0
+use crate::{Result, TelemetryError};
+use serde_json::Value;
+use std::{fs, io::ErrorKind, path::PathBuf};
+
+/// The maximum number of events queued, beyond which the oldest are discarded.
+const MAX_EVENTS: usize = 100;
+
+/// The events which could not be sent, persisted locally as JSON lines so that they are sent by a
+/// later run.
+#[derive(Debug, Clone)]
+pub struct Queue {
+	path: PathBuf,
+}
+
+impl Queue {
+	/// Create a new queue persisted at `path`.
+	///
+	/// parameters:
+	/// `path`: the file holding the events queued
+	pub fn new(path: PathBuf) -> Self {
+		Queue { path }
+	}
+
+	/// Appends `event` to the queue, discarding the oldest events beyond `MAX_EVENTS`.
+	///
+	/// parameters:
+	/// `event`: the payload which could not be sent
+	pub fn push(&self, event: &Value) -> Result<()> {
+		let mut events = self.events()?;
+		events.push(event.clone());
+		let start = events.len().saturating_sub(MAX_EVENTS);
+		let lines: String = events[start..].iter().map(|e| format!("{e}\n")).collect();
+		fs::write(&self.path, lines).map_err(TelemetryError::IO)
+	}
+
+	/// Removes every event from the queue, returning them from the oldest.
+	pub fn take(&self) -> Result<Vec<Value>> {
+		let events = self.events()?;
+		match fs::remove_file(&self.path) {
+			Err(err) if err.kind() != ErrorKind::NotFound => Err(TelemetryError::IO(err)),
+			_ => Ok(events),
+		}
+	}
+
+	/// The events queued, ignoring any line which is not valid JSON.
+	fn events(&self) -> Result<Vec<Value>> {
+		match fs::read_to_string(&self.path) {
+			Ok(lines) => Ok(lines.lines().filter_map(|l| serde_json::from_str(l).ok()).collect()),
+			Err(err) if err.kind() == ErrorKind::NotFound => Ok(Vec::new()),
+			Err(err) => Err(TelemetryError::IO(err)),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use serde_json::json;
+	use tempfile::TempDir;
+
+	#[test]
+	fn push_and_take_works() -> Result<()> {
+		let temp_dir = TempDir::new().unwrap();
+		let queue = Queue::new(temp_dir.path().join("queue.jsonl"));
+		assert!(queue.take()?.is_empty());
+
+		queue.push(&json!({"name": "new"}))?;
+		queue.push(&json!({"name": "build"}))?;
+		assert_eq!(queue.take()?, vec![json!({"name": "new"}), json!({"name": "build"})]);
+		assert!(queue.take()?.is_empty());
+		Ok(())
+	}
+
+	#[test]
+	fn push_discards_oldest_events() -> Result<()> {
+		let temp_dir = TempDir::new().unwrap();
+		let queue = Queue::new(temp_dir.path().join("queue.jsonl"));
+		for i in 0..MAX_EVENTS + 5 {
+			queue.push(&json!(i))?;
+		}
+		let events = queue.take()?;
+		assert_eq!(events.len(), MAX_EVENTS);
+		assert_eq!(events[0], json!(5));
+		Ok(())
+	}
+}